@@ -16,6 +16,7 @@ function resultText(
 ): string {
   if (result === "attacker_win") return `${attackerName} 胜利`;
   if (result === "defender_win") return `${defenderName} 胜利`;
+  if (result === "interrupted") return "战斗被打断";
   return "平局";
 }
 
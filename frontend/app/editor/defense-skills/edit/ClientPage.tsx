@@ -191,6 +191,21 @@ export default function EditDefenseSkillPage() {
                   }
                   placeholder="例如: neutral, fire, ice"
                 />
+                <Input
+                  label="标签（逗号分隔）"
+                  value={(skill.tags ?? []).join(", ")}
+                  onChange={(e) =>
+                    setSkill({
+                      ...skill,
+                      tags: e.target.value
+                        ? e.target.value
+                            .split(/[,，]/)
+                            .map((tag) => tag.trim())
+                        : [],
+                    })
+                  }
+                  placeholder="例如: 剑法, 阳, 轻功"
+                />
                 <div>
                   <label className="block text-sm font-medium text-gray-700 mb-1">
                     修行公式
@@ -16,13 +16,16 @@ import type {
   GameResponse,
   GameView,
   PanelDelta,
+  SaveProblem,
 } from "@/types/game";
 import type {
   AdventureEvent,
   EnemyTemplate,
+  OptionCost,
   OwnedManualTemplate,
   Reward,
   StoryEvent,
+  StorylineStats,
 } from "@/types/event";
 import type { StoryHistoryRecord } from "@/types/save";
 import type { Condition, Entry } from "@/types/trait";
@@ -37,17 +40,22 @@ import {
 } from "@/lib/tauri/commands";
 import {
   gameAdventureOption,
+  gameAdventureSkip,
+  gameCheckSave,
   gameCultivate,
   gameEquipManual,
   gameFinish,
   gameLoadPacks,
   gameResumeSave,
   gameStartNew,
+  gameStoryHistory,
   gameStoryBattle,
   gameStoryContinue,
+  gameStoryCheck,
   gameStoryOption,
   gameTravel,
   listStorylines,
+  listStorylineStats,
 } from "@/lib/tauri/wushen-core";
 
 const PACK_SELECTION_KEY = "wushen_game_pack_selection";
@@ -66,6 +74,34 @@ const MANUAL_KIND_LABELS: Record<string, string> = {
   any: "任意功法",
 };
 
+const describeOptionCost = (cost: OptionCost): string => {
+  switch (cost.type) {
+    case "qi":
+      return `内息 ${cost.amount}`;
+    case "hp":
+      return `生命 ${cost.amount}`;
+    case "attribute":
+      return `${ATTRIBUTE_LABELS[cost.target] ?? cost.target} ${cost.amount}`;
+    case "trait":
+      return `特性「${cost.id}」`;
+    default:
+      return `${MANUAL_KIND_LABELS[cost.type]}「${cost.id}」`;
+  }
+};
+
+const describeSaveProblem = (problem: SaveProblem): string => {
+  switch (problem.kind) {
+    case "missing_storyline":
+      return `剧情线「${problem.storyline_id}」不存在，将结束当前剧情`;
+    case "missing_story_event":
+      return `事件「${problem.event_id}」不存在，将回到剧情线起点`;
+    case "missing_adventure":
+      return `奇遇「${problem.adventure_id}」不存在，将放弃该奇遇`;
+    case "missing_equipped_manual":
+      return `已装备的${MANUAL_KIND_LABELS[problem.manual_type]}「${problem.manual_id}」不存在，将卸下`;
+  }
+};
+
 type LogTone = "story" | "system";
 
 type LogItem =
@@ -113,13 +149,18 @@ type LogItem =
       id: string;
       kind: "action";
       label: string;
-      actionType: "story_continue" | "story_battle" | "story_end";
+      actionType:
+        | "story_continue"
+        | "story_battle"
+        | "story_check"
+        | "story_end";
     };
 
 type DecisionOptionMeta = {
   id: string;
   text: string;
   condition?: Condition | null;
+  costs?: OptionCost[];
   enemy?: EnemyTemplate | null;
 };
 
@@ -295,6 +336,12 @@ function applyPanelDelta(
       panel.charge_time + delta.charge_time_delta * multiplier,
     );
   }
+  if (delta.momentum_delta !== undefined) {
+    panel.momentum = Math.max(
+      0,
+      panel.momentum + delta.momentum_delta * multiplier,
+    );
+  }
 }
 
 export default function GamePage() {
@@ -307,6 +354,7 @@ export default function GamePage() {
   const [storylines, setStorylines] = useState<
     Array<{ id: string; name: string }>
   >([]);
+  const [storylineStats, setStorylineStats] = useState<StorylineStats[]>([]);
   const [dataLoading, setDataLoading] = useState(false);
   const [storylineId, setStorylineId] = useState("");
   const [characterName, setCharacterName] = useState("");
@@ -327,6 +375,10 @@ export default function GamePage() {
   const seenEntryIds = useRef(new Set<string>());
   const resumeRequestedRef = useRef(false);
   const restoredHistoryRef = useRef(false);
+  // 恢复存档时按需读取的剧情历史（读取完成前为 null）
+  const [resumeHistory, setResumeHistory] = useState<
+    StoryHistoryRecord[] | null
+  >(null);
   const lastOutcomeRef = useRef<GameOutcome | null>(null);
   const deferredViewRef = useRef<GameView | null>(null);
   const lastCultivationRef = useRef<{ id: string; type: ManualType } | null>(
@@ -410,6 +462,13 @@ export default function GamePage() {
     return result;
   }, [orderedPacks, selectedPacks]);
 
+  const selectedStorylineStats = useMemo(
+    () =>
+      storylineStats.find((stats) => stats.storyline_id === storylineId) ??
+      null,
+    [storylineStats, storylineId],
+  );
+
   const attributeSum = comprehension + boneStructure + physique;
   const attributeRemaining = Math.max(0, 100 - attributeSum);
 
@@ -638,6 +697,7 @@ export default function GamePage() {
             id: option.id,
             text: option.text,
             condition: option.condition ?? null,
+            costs: option.costs ?? [],
           }));
         }
       }
@@ -648,6 +708,7 @@ export default function GamePage() {
             id: option.id,
             text: option.text,
             condition: option.condition ?? null,
+            costs: option.costs ?? [],
             enemy: option.result.type === "battle" ? option.result.enemy : null,
           }));
         }
@@ -719,18 +780,22 @@ export default function GamePage() {
             const actionType =
               event.content.type === "battle"
                 ? "story_battle"
-                : event.content.type === "end"
-                  ? "story_end"
-                  : "story_continue";
+                : event.content.type === "check"
+                  ? "story_check"
+                  : event.content.type === "end"
+                    ? "story_end"
+                    : "story_continue";
             pushEntry({
               id: `action:${event.id}`,
               kind: "action",
               label: `阶段：${
                 event.content.type === "battle"
                   ? "战斗"
-                  : event.content.type === "end"
-                    ? "结局"
-                    : "剧情推进"
+                  : event.content.type === "check"
+                    ? "检定"
+                    : event.content.type === "end"
+                      ? "结局"
+                      : "剧情推进"
               }`,
               actionType,
             });
@@ -1198,6 +1263,25 @@ export default function GamePage() {
           return { title: "特性", value: resolveTraitName(reward.id) };
         case "start_trait_pool":
           return { title: "开局特性池", value: resolveTraitName(reward.id) };
+        case "timed_buff":
+          return {
+            title: "限时增益",
+            value: reward.name || reward.id,
+            detail: `持续 ${reward.actions} 次行动`,
+          };
+        case "story_flag":
+          return {
+            title: reward.value === false ? "清除剧情标记" : "剧情标记",
+            value: reward.flag,
+          };
+        case "title":
+          return { title: "称号", value: reward.name || reward.id };
+        case "schedule_event":
+          return {
+            title: "预约奇遇",
+            value: reward.adventure_id,
+            detail: `${reward.actions} 次行动后触发`,
+          };
         case "internal":
           return {
             title: "内功",
@@ -1500,8 +1584,8 @@ export default function GamePage() {
 
   useEffect(() => {
     if (!resumeRequestedRef.current) return;
-    if (!view) return;
-    const history = view.save.story_history ?? [];
+    if (!view || !resumeHistory) return;
+    const history = resumeHistory;
     if (history.length === 0) {
       resumeRequestedRef.current = false;
       return;
@@ -1526,7 +1610,7 @@ export default function GamePage() {
     seenEntryIds.current = new Set(entries.map((entry) => entry.id));
     restoredHistoryRef.current = true;
     resumeRequestedRef.current = false;
-  }, [buildLogEntriesFromHistory, gameData, view]);
+  }, [buildLogEntriesFromHistory, gameData, resumeHistory, view]);
 
   useEffect(() => {
     if (outcome && outcome !== lastOutcomeRef.current) {
@@ -1540,6 +1624,24 @@ export default function GamePage() {
         });
         return;
       }
+      if (outcome.type === "finished") {
+        enqueueItem({
+          id: `finished:${Date.now()}`,
+          kind: "text",
+          text: outcome.message,
+          tone: "system",
+        });
+        const summary = outcome.character.chronicle?.summary;
+        if (summary) {
+          enqueueItem({
+            id: `chronicle:${Date.now()}`,
+            kind: "text",
+            text: summary,
+            tone: "story",
+          });
+        }
+        return;
+      }
       if (outcome.type === "cultivation") {
         const lastCultivation = lastCultivationRef.current;
         const manualLabel = lastCultivation
@@ -1649,8 +1751,7 @@ export default function GamePage() {
   useEffect(() => {
     if (!view) return;
     if (resumeRequestedRef.current && !restoredHistoryRef.current) {
-      const history = view.save.story_history ?? [];
-      if (history.length > 0) {
+      if (resumeHistory === null || resumeHistory.length > 0) {
         return;
       }
     }
@@ -1668,6 +1769,20 @@ export default function GamePage() {
         message: "剧情已完成，角色已保存至完成列表。",
       });
     }
+    if (
+      targetView.phase === "blocked" &&
+      targetView.story_event?.content.type === "decision"
+    ) {
+      const reasons = (targetView.story_event.content.blocked ?? []).map(
+        (option) => `「${option.text || option.id}」：${option.reasons.join("；")}`,
+      );
+      enqueueItem({
+        id: `blocked:${targetView.story_event.id}`,
+        kind: "phase",
+        label: "阶段：无路可走",
+        message: ["当前抉择没有可选的选项。", ...reasons].join("\n"),
+      });
+    }
     if (targetView.phase === "adventure_decision" && targetView.adventure) {
       enqueueItem({
         id: `adventure:${targetView.adventure.id}:title`,
@@ -1711,17 +1826,19 @@ export default function GamePage() {
         enqueueItem({
           id: `action:${targetView.story_event.id}`,
           kind: "action",
-          label: `阶段：${targetView.story_event.content.type === "battle" ? "战斗" : targetView.story_event.content.type === "end" ? "结局" : "剧情推进"}`,
+          label: `阶段：${targetView.story_event.content.type === "battle" ? "战斗" : targetView.story_event.content.type === "check" ? "检定" : targetView.story_event.content.type === "end" ? "结局" : "剧情推进"}`,
           actionType:
             targetView.story_event.content.type === "battle"
               ? "story_battle"
-              : targetView.story_event.content.type === "end"
-                ? "story_end"
-                : "story_continue",
+              : targetView.story_event.content.type === "check"
+                ? "story_check"
+                : targetView.story_event.content.type === "end"
+                  ? "story_end"
+                  : "story_continue",
         });
       }
     }
-  }, [enqueueItem, hasUnviewedBattle, view]);
+  }, [enqueueItem, hasUnviewedBattle, resumeHistory, view]);
 
   useEffect(() => {
     if (view) {
@@ -1802,6 +1919,7 @@ export default function GamePage() {
       await gameLoadPacks(orderedSelectedPackIds);
       const list = await listStorylines();
       setStorylines(list);
+      setStorylineStats(await listStorylineStats());
       if (list.length > 0) {
         setStorylineId(list[0].id);
       }
@@ -1871,13 +1989,40 @@ export default function GamePage() {
     }
   };
 
-  const resumeSave = async (id: string) => {
+  const enterSave = async (id: string, repair: boolean) => {
     resetNarrative();
     resumeRequestedRef.current = true;
-    const res = await runGameAction(() => gameResumeSave(id));
+    setResumeHistory(null);
+    const res = await runGameAction(() => gameResumeSave(id, repair));
     if (!res) {
       resumeRequestedRef.current = false;
+      return;
     }
+    setResumeHistory(await gameStoryHistory().catch(() => []));
+  };
+
+  const resumeSave = async (id: string) => {
+    let problems: SaveProblem[];
+    try {
+      problems = await gameCheckSave(id);
+    } catch (error) {
+      openNoticeDialog("读取存档失败: " + (error as Error).message, "错误");
+      return;
+    }
+    if (problems.length === 0) {
+      await enterSave(id, false);
+      return;
+    }
+    openConfirmDialog({
+      title: "存档与当前内容不一致",
+      message: `${problems.map(describeSaveProblem).join("；")}。是否修复后继续？`,
+      confirmText: "修复并继续",
+      cancelText: "取消",
+      onConfirm: async () => {
+        closeConfirmDialog();
+        await enterSave(id, true);
+      },
+    });
   };
 
   const removeSave = async (id: string) => {
@@ -1993,6 +2138,10 @@ export default function GamePage() {
     await runGameAction(() => gameStoryContinue());
   };
 
+  const handleStoryCheck = async () => {
+    await runGameAction(() => gameStoryCheck());
+  };
+
   const handleAdventureOption = async (optionId: string) => {
     if (view?.adventure) {
       markDecisionChoice(`decision:adventure:${view.adventure.id}`, optionId);
@@ -2012,6 +2161,13 @@ export default function GamePage() {
     );
   };
 
+  const handleAdventureSkip = async () => {
+    if (view?.adventure) {
+      markDecisionChoice(`decision:adventure:${view.adventure.id}`, "skip");
+    }
+    await runGameAction(() => gameAdventureSkip());
+  };
+
   const handleFinish = async () => {
     await runGameAction(() => gameFinish());
   };
@@ -2174,6 +2330,18 @@ export default function GamePage() {
                     storylines.length > 0 ? "搜索剧情线..." : "请先加载模组数据"
                   }
                 />
+                {selectedStorylineStats && (
+                  <p className="text-xs text-gray-500">
+                    共 {selectedStorylineStats.event_count} 个事件（战斗{" "}
+                    {selectedStorylineStats.battle_count} 场，敌人{" "}
+                    {selectedStorylineStats.enemy_count} 名），结局{" "}
+                    {selectedStorylineStats.ending_count} 个，行动点{" "}
+                    {selectedStorylineStats.min_action_points ===
+                    selectedStorylineStats.max_action_points
+                      ? selectedStorylineStats.min_action_points
+                      : `${selectedStorylineStats.min_action_points} ~ ${selectedStorylineStats.max_action_points}`}
+                  </p>
+                )}
                 <Input
                   label="角色姓名"
                   value={characterName}
@@ -2348,6 +2516,13 @@ export default function GamePage() {
                           {view.save.current_character.action_points}
                         </div>
                       </div>
+                      <div>
+                        <div className="text-xs text-gray-500">时节</div>
+                        <div className="font-medium">
+                          第{view.calendar.year}年 {view.calendar.season_name}
+                          季 第{view.calendar.day_of_season}天
+                        </div>
+                      </div>
                     </div>
                   </div>
 
@@ -2704,6 +2879,15 @@ export default function GamePage() {
                     <h2 className="text-xl [@media(max-height:820px)]:text-lg font-semibold text-gray-900">
                       {view.storyline?.name ?? "剧情线"}
                     </h2>
+                    {view.chapter && (
+                      <div
+                        className="text-xs text-gray-500"
+                        title={view.chapter.summary}
+                      >
+                        第{view.chapter.index + 1}/{view.chapter.total}章 ·{" "}
+                        {view.chapter.title}
+                      </div>
+                    )}
                   </div>
                   {view.phase === "action" && (
                     <div className="text-xs text-gray-500">行动点阶段</div>
@@ -2820,6 +3004,15 @@ export default function GamePage() {
                                             {conditionText || "条件未满足"}
                                           </span>
                                         )}
+                                        {option.costs &&
+                                          option.costs.length > 0 && (
+                                            <span className="text-sm text-amber-700">
+                                              代价：
+                                              {option.costs
+                                                .map(describeOptionCost)
+                                                .join("、")}
+                                            </span>
+                                          )}
                                       </div>
                                     </Button>
                                     {enemy && (
@@ -2837,6 +3030,21 @@ export default function GamePage() {
                                   </div>
                                 );
                               })}
+                              {entry.scope === "adventure" && isActive && (
+                                <Button
+                                  variant="secondary"
+                                  size="sm"
+                                  onClick={handleAdventureSkip}
+                                  disabled={isTyping}
+                                >
+                                  放弃奇遇
+                                  {view?.adventure?.skip_costs &&
+                                    view.adventure.skip_costs.length > 0 &&
+                                    `（代价：${view.adventure.skip_costs
+                                      .map(describeOptionCost)
+                                      .join("、")}）`}
+                                </Button>
+                              )}
                             </div>
                           </div>
                         );
@@ -3007,17 +3215,23 @@ export default function GamePage() {
                             ? isActive
                               ? "进入战斗"
                               : "战斗已结束"
-                            : entry.actionType === "story_end"
-                              ? "完成结局并保存角色"
-                              : isActive
-                                ? "进入下一个事件"
-                                : "已进入下一事件";
+                            : entry.actionType === "story_check"
+                              ? isActive
+                                ? "进行检定"
+                                : "检定已完成"
+                              : entry.actionType === "story_end"
+                                ? "完成结局并保存角色"
+                                : isActive
+                                  ? "进入下一个事件"
+                                  : "已进入下一事件";
                         const onAction =
                           entry.actionType === "story_battle"
                             ? handleStoryBattle
-                            : entry.actionType === "story_end"
-                              ? handleFinish
-                              : handleStoryContinue;
+                            : entry.actionType === "story_check"
+                              ? handleStoryCheck
+                              : entry.actionType === "story_end"
+                                ? handleFinish
+                                : handleStoryContinue;
                         return (
                           <div
                             key={entry.id}
@@ -3216,6 +3430,9 @@ export default function GamePage() {
                             蓄力时间{" "}
                             {currentAttackerPanel.charge_time.toFixed(2)}
                           </div>
+                          <div>
+                            气势 {currentAttackerPanel.momentum.toFixed(1)}
+                          </div>
                         </div>
                       </div>
                     )}
@@ -3353,6 +3570,9 @@ export default function GamePage() {
                             蓄力时间{" "}
                             {currentDefenderPanel.charge_time.toFixed(2)}
                           </div>
+                          <div>
+                            气势 {currentDefenderPanel.momentum.toFixed(1)}
+                          </div>
                         </div>
                       </div>
                     )}
@@ -3365,7 +3585,9 @@ export default function GamePage() {
                       ? `${battleData.attacker_panel.name} 胜利`
                       : battleData.result === "defender_win"
                         ? `${battleData.defender_panel.name} 胜利`
-                        : "平局"}
+                        : battleData.result === "interrupted"
+                          ? "战斗被打断"
+                          : "平局"}
                   </div>
                 )}
               </Modal>
@@ -8,6 +8,7 @@ import type {
   AdventureOptionResult,
   AdventureOutcome,
   EnemyTemplate,
+  Season,
 } from "@/types/event";
 import type { Enemy } from "@/types/enemy";
 import type { ManualListItem } from "@/types/manual";
@@ -34,6 +35,13 @@ interface AdventureEventFormProps {
   submitLabel?: string;
 }
 
+const SEASON_OPTIONS: { value: Season; label: string }[] = [
+  { value: "spring", label: "春" },
+  { value: "summer", label: "夏" },
+  { value: "autumn", label: "秋" },
+  { value: "winter", label: "冬" },
+];
+
 const CONTENT_TYPE_OPTIONS = [
   { value: "story", label: "剧情事件" },
   { value: "decision", label: "抉择事件" },
@@ -567,6 +575,38 @@ export default function AdventureEventForm({
         />
       </div>
 
+      <div className="border border-gray-200 rounded-lg p-4 space-y-3">
+        <h4 className="text-sm font-semibold text-gray-700">
+          触发季节（不选表示不限）
+        </h4>
+        <div className="flex flex-wrap gap-4">
+          {SEASON_OPTIONS.map((option) => {
+            const seasons = event.seasons ?? [];
+            return (
+              <label
+                key={option.value}
+                className="flex items-center gap-2 text-sm text-gray-700"
+              >
+                <input
+                  type="checkbox"
+                  checked={seasons.includes(option.value)}
+                  onChange={(e) =>
+                    setEvent({
+                      ...event,
+                      seasons: e.target.checked
+                        ? [...seasons, option.value]
+                        : seasons.filter((s) => s !== option.value),
+                    })
+                  }
+                  className="h-4 w-4 rounded border-gray-300 text-blue-600"
+                />
+                {option.label}
+              </label>
+            );
+          })}
+        </div>
+      </div>
+
       <Select
         label="事件类型"
         value={event.content.type}
@@ -39,6 +39,9 @@ const ATTRIBUTE_TYPE_OPTIONS = [
   { value: "bone_structure", label: "根骨" },
   { value: "physique", label: "体魄" },
   { value: "martial_arts_attainment", label: "武学素养" },
+  { value: "max_hp", label: "生命上限" },
+  { value: "base_attack", label: "基础攻击力" },
+  { value: "base_defense", label: "基础防御力" },
 ];
 
 const BATTLE_ATTRIBUTE_TYPE_OPTIONS = [
@@ -191,6 +194,9 @@ export default function ConditionEditor({
       "attack_skill_type_is" in cond ||
       "defense_skill_is" in cond ||
       "defense_skill_type_is" in cond ||
+      "internal_has_tag" in cond ||
+      "attack_skill_has_tag" in cond ||
+      "defense_skill_has_tag" in cond ||
       "has_trait" in cond ||
       "attribute_comparison" in cond
     ) {
@@ -267,6 +273,15 @@ export default function ConditionEditor({
                 value: "defense_skill_type_is",
                 label: "修行的防御武技类型为指定类型",
               },
+              { value: "internal_has_tag", label: "修行的内功具有指定标签" },
+              {
+                value: "attack_skill_has_tag",
+                label: "修行的攻击武技具有指定标签",
+              },
+              {
+                value: "defense_skill_has_tag",
+                label: "修行的防御武技具有指定标签",
+              },
               { value: "has_trait", label: "具备特性" },
               { value: "attribute_comparison", label: "属性比较" },
             ]}
@@ -391,6 +406,15 @@ export default function ConditionEditor({
               value: "defense_skill_type_is",
               label: "修行的防御武技类型为指定类型",
             },
+            { value: "internal_has_tag", label: "修行的内功具有指定标签" },
+            {
+              value: "attack_skill_has_tag",
+              label: "修行的攻击武技具有指定标签",
+            },
+            {
+              value: "defense_skill_has_tag",
+              label: "修行的防御武技具有指定标签",
+            },
             { value: "has_trait", label: "具备特性" },
             { value: "attribute_comparison", label: "属性比较" },
           ]}
@@ -7,6 +7,7 @@ import {
   Operation,
   Trigger,
   PanelTarget,
+  SkillSlot,
 } from "@/types/trait";
 import Button from "@/components/ui/Button";
 import Select from "@/components/ui/Select";
@@ -41,6 +42,7 @@ const ATTRIBUTE_TARGET_OPTIONS = [
   { value: "damage_bonus", label: "增伤" },
   { value: "damage_reduction", label: "减伤" },
   { value: "max_damage_reduction", label: "减伤上限" },
+  { value: "momentum", label: "气势" },
   { value: "martial_arts_attainment_gain", label: "武学素养增益" },
   { value: "cultivation_exp_gain", label: "修行经验增益" },
   { value: "qi_gain", label: "内息增益" },
@@ -109,6 +111,7 @@ function getAllowedTargets(trigger?: Trigger): AttributeTarget[] {
         "damage_bonus",
         "damage_reduction",
         "max_damage_reduction",
+        "momentum",
       ];
     case "before_attack":
     case "before_defense":
@@ -119,6 +122,7 @@ function getAllowedTargets(trigger?: Trigger): AttributeTarget[] {
         "base_defense",
         "damage_bonus",
         "damage_reduction",
+        "momentum",
       ];
     case "after_attack":
     case "after_defense":
@@ -134,6 +138,7 @@ function getAllowedTargets(trigger?: Trigger): AttributeTarget[] {
         "damage_bonus",
         "damage_reduction",
         "max_damage_reduction",
+        "momentum",
       ];
     default:
       return ATTRIBUTE_TARGET_OPTIONS.map(
@@ -147,6 +152,18 @@ function allowsExtraAttack(trigger?: Trigger): boolean {
   return trigger === "after_attack" || trigger === "after_defense";
 }
 
+// 检查指定触发时机是否允许封招效果（仅战斗中）
+function allowsSealSkill(trigger?: Trigger): boolean {
+  return (
+    trigger === "battle_start" ||
+    trigger === "before_attack" ||
+    trigger === "before_defense" ||
+    trigger === "after_attack" ||
+    trigger === "after_defense" ||
+    trigger === "round_end"
+  );
+}
+
 export default function EffectEditor({
   effect,
   onChange,
@@ -161,6 +178,10 @@ export default function EffectEditor({
     () => allowsExtraAttack(trigger),
     [trigger],
   );
+  const allowsSealSkillEffect = useMemo(
+    () => allowsSealSkill(trigger),
+    [trigger],
+  );
 
   // 过滤属性目标选项
   const filteredAttributeTargetOptions = useMemo(() => {
@@ -177,7 +198,9 @@ export default function EffectEditor({
       ? "修改数值（实际数值）"
       : effect.type === "modify_percentage"
         ? "修改百分比（1=100%）"
-        : "额外攻击";
+        : effect.type === "seal_skill"
+          ? "封招"
+          : "额外攻击";
 
   const handleTypeChange = (type: Effect["type"]) => {
     if (type === "modify_attribute" || type === "modify_percentage") {
@@ -192,6 +215,12 @@ export default function EffectEditor({
         can_exceed_limit: false,
         is_temporary: false,
       });
+    } else if (type === "seal_skill" && allowsSealSkillEffect) {
+      onChange({
+        type: "seal_skill",
+        slot: "attack_skill",
+        rounds: 1,
+      });
     } else {
       if (!allowsExtraAttackEffect) {
         // 如果不允许额外攻击，回退到修改属性
@@ -219,7 +248,7 @@ export default function EffectEditor({
     if (value.trim() === "") {
       if (isModifyEffect) {
         onChange({ ...effect, value: 0 });
-      } else {
+      } else if (effect.type === "extra_attack") {
         onChange({ ...effect, output: "0" });
       }
       return;
@@ -243,7 +272,7 @@ export default function EffectEditor({
 
     if (isModifyEffect) {
       onChange({ ...effect, value: newValue });
-    } else {
+    } else if (effect.type === "extra_attack") {
       onChange({ ...effect, output: trimmedValue });
     }
   };
@@ -266,6 +295,8 @@ export default function EffectEditor({
         return "bg-blue-50 border-blue-200";
       case "extra_attack":
         return "bg-red-50 border-red-200";
+      case "seal_skill":
+        return "bg-purple-50 border-purple-200";
       default:
         return "bg-gray-50 border-gray-200";
     }
@@ -331,6 +362,9 @@ export default function EffectEditor({
               ...(allowsExtraAttackEffect
                 ? [{ value: "extra_attack", label: "额外攻击" }]
                 : []),
+              ...(allowsSealSkillEffect
+                ? [{ value: "seal_skill", label: "封招" }]
+                : []),
             ]}
             value={effect.type}
             onChange={(e) => handleTypeChange(e.target.value as Effect["type"])}
@@ -504,6 +538,61 @@ export default function EffectEditor({
             </>
           )}
 
+          {effect.type === "seal_skill" && (
+            <>
+              <Select
+                label="封住对手的武技"
+                options={[
+                  { value: "attack_skill", label: "攻击武技" },
+                  { value: "defense_skill", label: "防御武技" },
+                ]}
+                value={effect.slot}
+                onChange={(e) =>
+                  onChange({
+                    ...effect,
+                    slot: e.target.value as SkillSlot,
+                  })
+                }
+              />
+              <Input
+                label="持续回合数"
+                type="number"
+                min={1}
+                value={effect.rounds}
+                onChange={(e) =>
+                  onChange({
+                    ...effect,
+                    rounds: Math.max(1, parseInt(e.target.value, 10) || 1),
+                  })
+                }
+              />
+              <p className="text-xs text-gray-500">
+                被封期间对手以空手数值应对，重复封招时取较大回合数
+              </p>
+              <div>
+                <label className="block text-sm font-medium text-gray-700 mb-1">
+                  战斗记录模板（可选）
+                </label>
+                <Input
+                  value={effect.battle_record_template?.template || ""}
+                  onChange={(e) =>
+                    onChange({
+                      ...effect,
+                      battle_record_template: e.target.value
+                        ? { template: e.target.value }
+                        : undefined,
+                    })
+                  }
+                  placeholder="例如: {self_name}点中{opponent_name}穴道，{value}回合内无法出招"
+                />
+                <p className="mt-1 text-xs text-gray-500">
+                  支持的占位符: {"{self_name}"}, {"{opponent_name}"},{" "}
+                  {"{value}"}
+                </p>
+              </div>
+            </>
+          )}
+
           {effect.type === "extra_attack" && (
             <>
               <div>
@@ -263,6 +263,35 @@ export default function EnemyEditor({ enemy, onChange }: EnemyEditorProps) {
           }
         />
       </div>
+
+      <div className="space-y-3">
+        <div className="text-xs text-gray-500">
+          战斗文本可使用 {"{self}"}（敌人名）与 {"{opponent}"}（玩家名）
+        </div>
+        <Input
+          label="开场文本(可选)"
+          value={enemy.intro_text ?? ""}
+          onChange={(e) =>
+            onChange({ ...enemy, intro_text: e.target.value || null })
+          }
+        />
+        <div className="grid grid-cols-2 gap-3">
+          <Input
+            label="玩家获胜文本(可选)"
+            value={enemy.win_text ?? ""}
+            onChange={(e) =>
+              onChange({ ...enemy, win_text: e.target.value || null })
+            }
+          />
+          <Input
+            label="玩家落败文本(可选)"
+            value={enemy.lose_text ?? ""}
+            onChange={(e) =>
+              onChange({ ...enemy, lose_text: e.target.value || null })
+            }
+          />
+        </div>
+      </div>
     </div>
   );
 }
@@ -65,6 +65,7 @@ function getAllowedTargetsForTrigger(trigger: Trigger): AttributeTarget[] {
         "damage_bonus",
         "damage_reduction",
         "max_damage_reduction",
+        "momentum",
       ];
     case "before_attack":
     case "before_defense":
@@ -75,6 +76,7 @@ function getAllowedTargetsForTrigger(trigger: Trigger): AttributeTarget[] {
         "base_defense",
         "damage_bonus",
         "damage_reduction",
+        "momentum",
       ];
     case "after_attack":
     case "after_defense":
@@ -90,12 +92,22 @@ function getAllowedTargetsForTrigger(trigger: Trigger): AttributeTarget[] {
         "damage_bonus",
         "damage_reduction",
         "max_damage_reduction",
+        "momentum",
       ];
     default:
       return ["hp"];
   }
 }
 
+// 按逗号拆分标签，去掉首尾空白与空标签
+function parseTags(value: string): string[] | undefined {
+  const tags = value
+    .split(/[,，]/)
+    .map((tag) => tag.trim())
+    .filter((tag) => tag !== "");
+  return tags.length > 0 ? tags : undefined;
+}
+
 interface EntryEditorProps {
   entry: Entry;
   onChange: (entry: Entry) => void;
@@ -137,8 +149,18 @@ export default function EntryEditor({
 }: EntryEditorProps) {
   const [isExpanded, setIsExpanded] = useState(false);
   const [nameLookup, setNameLookup] = useState<NameLookup | null>(null);
+  // 标签输入框保留原文（如末尾的逗号），词条中只写入非空标签
+  const tagsKey = entry.tags?.join(", ") ?? "";
+  const [tagsText, setTagsText] = useState(tagsKey);
   const { activePack } = useActivePack();
 
+  // 词条标签被外部修改时同步输入框
+  useEffect(() => {
+    setTagsText((text) =>
+      (parseTags(text)?.join(", ") ?? "") === tagsKey ? text : tagsKey,
+    );
+  }, [tagsKey]);
+
   useEffect(() => {
     let cancelled = false;
     const loadNames = async () => {
@@ -262,6 +284,11 @@ export default function EntryEditor({
     });
   };
 
+  const handleTagsChange = (value: string) => {
+    setTagsText(value);
+    onChange({ ...entry, tags: parseTags(value) });
+  };
+
   const triggerLabel =
     TRIGGER_OPTIONS.find((opt) => opt.value === entry.trigger)?.label ||
     entry.trigger;
@@ -415,6 +442,16 @@ export default function EntryEditor({
               placeholder="留空表示无限制"
             />
           </div>
+
+          <div className="bg-gray-50 rounded-lg p-4">
+            <Input
+              label="标签（可选，逗号分隔，可按标签整体禁用词条）"
+              value={tagsText}
+              onChange={(e) => handleTagsChange(e.target.value)}
+              onBlur={() => setTagsText(tagsKey)}
+              placeholder="如 healing, speed"
+            />
+          </div>
         </div>
       )}
     </div>
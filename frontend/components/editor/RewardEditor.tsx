@@ -4,11 +4,13 @@ import { useEffect, useState } from "react";
 import type { Reward, RewardTarget, ManualKind } from "@/types/event";
 import type { ManualListItem } from "@/types/manual";
 import type { TraitListItem } from "@/types/trait";
-import type { Operation } from "@/types/trait";
+import type { Entry, Operation } from "@/types/trait";
 import Button from "@/components/ui/Button";
 import Input from "@/components/ui/Input";
 import Select from "@/components/ui/Select";
 import SearchableSelect from "@/components/ui/SearchableSelect";
+import EntryEditor from "@/components/editor/EntryEditor";
+import { createEntryId } from "@/lib/utils/entryInheritance";
 import { useActivePack } from "@/lib/mods/active-pack";
 import {
   listAttackSkills,
@@ -30,6 +32,15 @@ const REWARD_TYPE_OPTIONS = [
   { value: "attack_skill", label: "攻击武技奖励" },
   { value: "defense_skill", label: "防御武技奖励" },
   { value: "random_manual", label: "随机功法奖励" },
+  { value: "timed_buff", label: "限时增益" },
+  { value: "story_flag", label: "剧情标记" },
+  { value: "title", label: "称号" },
+  { value: "schedule_event", label: "预约奇遇" },
+];
+
+const STORY_FLAG_VALUE_OPTIONS = [
+  { value: "true", label: "设置" },
+  { value: "false", label: "清除" },
 ];
 
 const ATTRIBUTE_TARGET_OPTIONS: { value: RewardTarget; label: string }[] = [
@@ -37,6 +48,9 @@ const ATTRIBUTE_TARGET_OPTIONS: { value: RewardTarget; label: string }[] = [
   { value: "bone_structure", label: "根骨" },
   { value: "physique", label: "体魄" },
   { value: "martial_arts_attainment", label: "武学素养" },
+  { value: "max_hp", label: "生命上限" },
+  { value: "base_attack", label: "基础攻击力" },
+  { value: "base_defense", label: "基础防御力" },
 ];
 
 const OPERATION_OPTIONS: { value: Operation; label: string }[] = [
@@ -258,6 +272,180 @@ export default function RewardEditor({ rewards, onChange }: RewardEditorProps) {
             />
           </div>
         );
+      case "story_flag":
+        return (
+          <div className="grid grid-cols-1 md:grid-cols-2 gap-3">
+            <Input
+              label="标记"
+              value={reward.flag}
+              onChange={(e) =>
+                updateReward(index, { ...reward, flag: e.target.value })
+              }
+            />
+            <Select
+              label="操作"
+              value={String(reward.value ?? true)}
+              options={STORY_FLAG_VALUE_OPTIONS}
+              onChange={(e) =>
+                updateReward(index, {
+                  ...reward,
+                  value: e.target.value === "true",
+                })
+              }
+            />
+          </div>
+        );
+      case "schedule_event":
+        return (
+          <div className="grid grid-cols-1 md:grid-cols-2 gap-3">
+            <Input
+              label="奇遇ID"
+              value={reward.adventure_id}
+              onChange={(e) =>
+                updateReward(index, { ...reward, adventure_id: e.target.value })
+              }
+            />
+            <Input
+              label="几次行动后触发"
+              type="number"
+              value={reward.actions.toString()}
+              onChange={(e) =>
+                updateReward(index, {
+                  ...reward,
+                  actions: Number(e.target.value || 0),
+                })
+              }
+            />
+          </div>
+        );
+      case "timed_buff": {
+        const updateEntries = (entries: Entry[]) =>
+          updateReward(index, { ...reward, entries });
+        return (
+          <div className="space-y-3">
+            <div className="grid grid-cols-1 md:grid-cols-3 gap-3">
+              <Input
+                label="增益ID"
+                value={reward.id}
+                onChange={(e) =>
+                  updateReward(index, { ...reward, id: e.target.value })
+                }
+              />
+              <Input
+                label="增益名称"
+                value={reward.name}
+                onChange={(e) =>
+                  updateReward(index, { ...reward, name: e.target.value })
+                }
+              />
+              <Input
+                label="持续行动次数"
+                type="number"
+                value={reward.actions.toString()}
+                onChange={(e) =>
+                  updateReward(index, {
+                    ...reward,
+                    actions: Number(e.target.value || 0),
+                  })
+                }
+              />
+            </div>
+            {reward.entries.map((entry, entryIndex) => (
+              <EntryEditor
+                key={entryIndex}
+                entry={entry}
+                onChange={(next) =>
+                  updateEntries(
+                    reward.entries.map((item, i) =>
+                      i === entryIndex ? next : item,
+                    ),
+                  )
+                }
+                onDelete={() =>
+                  updateEntries(
+                    reward.entries.filter((_, i) => i !== entryIndex),
+                  )
+                }
+              />
+            ))}
+            <Button
+              variant="secondary"
+              size="sm"
+              onClick={() =>
+                updateEntries([
+                  ...reward.entries,
+                  {
+                    entry_id: createEntryId(),
+                    trigger: "cultivating_internal",
+                    condition: null,
+                    effects: [],
+                    max_triggers: null,
+                  },
+                ])
+              }
+            >
+              添加词条
+            </Button>
+          </div>
+        );
+      }
+      case "title": {
+        const entries = reward.entries ?? [];
+        const updateEntries = (next: Entry[]) =>
+          updateReward(index, { ...reward, entries: next });
+        return (
+          <div className="space-y-3">
+            <div className="grid grid-cols-1 md:grid-cols-2 gap-3">
+              <Input
+                label="称号ID"
+                value={reward.id}
+                onChange={(e) =>
+                  updateReward(index, { ...reward, id: e.target.value })
+                }
+              />
+              <Input
+                label="称号名称"
+                value={reward.name}
+                onChange={(e) =>
+                  updateReward(index, { ...reward, name: e.target.value })
+                }
+              />
+            </div>
+            {entries.map((entry, entryIndex) => (
+              <EntryEditor
+                key={entryIndex}
+                entry={entry}
+                onChange={(next) =>
+                  updateEntries(
+                    entries.map((item, i) => (i === entryIndex ? next : item)),
+                  )
+                }
+                onDelete={() =>
+                  updateEntries(entries.filter((_, i) => i !== entryIndex))
+                }
+              />
+            ))}
+            <Button
+              variant="secondary"
+              size="sm"
+              onClick={() =>
+                updateEntries([
+                  ...entries,
+                  {
+                    entry_id: createEntryId(),
+                    trigger: "battle_start",
+                    condition: null,
+                    effects: [],
+                    max_triggers: null,
+                  },
+                ])
+              }
+            >
+              添加词条
+            </Button>
+          </div>
+        );
+      }
       default:
         return null;
     }
@@ -328,6 +516,20 @@ function createDefaultRewardByType(type: Reward["type"]): Reward {
         manual_type: null,
         count: 1,
       };
+    case "timed_buff":
+      return {
+        type: "timed_buff",
+        id: "",
+        name: "",
+        entries: [],
+        actions: 3,
+      };
+    case "story_flag":
+      return { type: "story_flag", flag: "", value: true };
+    case "title":
+      return { type: "title", id: "", name: "", entries: [] };
+    case "schedule_event":
+      return { type: "schedule_event", adventure_id: "", actions: 3 };
     case "attribute":
     default:
       return {
@@ -261,6 +261,15 @@ function collectTargets(event: StoryEvent): string[] {
       if (event.content.next_event_id)
         targets.push(event.content.next_event_id);
       break;
+    case "check":
+      [
+        event.content.critical_success,
+        event.content.success,
+        event.content.failure,
+      ].forEach((branch) => {
+        if (branch?.next_event_id) targets.push(branch.next_event_id);
+      });
+      break;
     default:
       break;
   }
@@ -31,9 +31,7 @@ function normalizeSave(raw: unknown, fallbackId: string): SaveGame {
         ? save.completed_characters
         : [],
       rng_state: save.rng_state ?? 0,
-      story_history: Array.isArray(save.story_history)
-        ? save.story_history
-        : [],
+      story_history: save.story_history ?? [],
     };
   }
 
@@ -75,6 +75,18 @@ export function isConditionMet(
   if ("defense_skill_type_is" in condition) {
     return defenseType === condition.defense_skill_type_is;
   }
+  if ("internal_has_tag" in condition) {
+    const tags = internalId ? manuals.internals[internalId]?.tags : undefined;
+    return tags?.includes(condition.internal_has_tag) ?? false;
+  }
+  if ("attack_skill_has_tag" in condition) {
+    const tags = attackId ? manuals.attackSkills[attackId]?.tags : undefined;
+    return tags?.includes(condition.attack_skill_has_tag) ?? false;
+  }
+  if ("defense_skill_has_tag" in condition) {
+    const tags = defenseId ? manuals.defenseSkills[defenseId]?.tags : undefined;
+    return tags?.includes(condition.defense_skill_has_tag) ?? false;
+  }
   if ("has_trait" in condition) {
     return character.traits.includes(condition.has_trait);
   }
@@ -87,7 +99,14 @@ export function isConditionMet(
           ? character.three_d.bone_structure
           : attribute === "physique"
             ? character.three_d.physique
-            : (character.martial_arts_attainment ?? 0);
+            : attribute === "max_hp"
+              ? (character.max_hp ?? 100 * character.three_d.physique)
+              : attribute === "base_attack"
+                ? (character.base_attack ?? 3 * character.three_d.physique)
+                : attribute === "base_defense"
+                  ? (character.base_defense ??
+                    2 * character.three_d.physique)
+                  : (character.martial_arts_attainment ?? 0);
     return compare(op, current, value);
   }
 
@@ -232,6 +232,10 @@ export function applyRewards(
         }
         break;
       case "start_trait_pool":
+      case "timed_buff":
+      case "story_flag":
+      case "title":
+      case "schedule_event":
         break;
       case "internal": {
         const manual = pools.internals.find((item) => item.id === reward.id);
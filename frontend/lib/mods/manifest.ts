@@ -13,6 +13,7 @@ export function buildManifest(
     author: pack.author,
     description: pack.description,
     files: files && files.length > 0 ? files : defaultPackFileNames(),
+    dependencies: pack.dependencies ?? [],
   };
 }
 
@@ -24,6 +25,10 @@ export function manifestToToml(manifest: ModPackManifest): string {
     author: manifest.author ?? "",
     description: manifest.description ?? "",
     files: manifest.files,
+    dependencies: (manifest.dependencies ?? []).map((dep) => ({
+      id: dep.id,
+      ...(dep.version ? { version: dep.version } : {}),
+    })),
   });
 }
 
@@ -42,5 +47,6 @@ export function parseManifestToml(source: string): ModPackManifest {
     description: data.description,
     files:
       data.files && data.files.length > 0 ? data.files : defaultPackFileNames(),
+    dependencies: data.dependencies ?? [],
   };
 }
@@ -5,6 +5,9 @@ export const PACK_DATA_FILES = [
   { file: "defense_skills.json", key: "defense_skills" },
   { file: "adventures.json", key: "adventures" },
   { file: "storylines.json", key: "storylines" },
+  { file: "quests.json", key: "quests" },
+  { file: "items.json", key: "items" },
+  { file: "recipes.json", key: "recipes" },
 ] as const;
 
 export type PackDataFile = (typeof PACK_DATA_FILES)[number];
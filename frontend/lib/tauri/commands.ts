@@ -2,10 +2,21 @@ import { invoke } from "@tauri-apps/api/core";
 import type { ModPackMetadata } from "@/types/mod";
 import type { Trait } from "@/types/trait";
 import type { Internal, AttackSkill, DefenseSkill } from "@/types/manual";
-import type { AdventureEvent, Storyline } from "@/types/event";
+import type {
+  AdventureEvent,
+  EnemyTemplate,
+  Quest,
+  Storyline,
+} from "@/types/event";
 import type { Character } from "@/types/character";
 import type { Enemy } from "@/types/enemy";
-import type { SaveGame } from "@/types/save";
+import type {
+  ImportedSave,
+  IntegrityPolicy,
+  RosterEntry,
+  SaveFormat,
+  SaveGame,
+} from "@/types/save";
 
 export type NamedItem = { id: string; name: string; created_at?: number };
 
@@ -192,6 +203,28 @@ export async function deleteAdventureEvent(
   await invoke("delete_adventure_event", { packId, id });
 }
 
+export async function listQuests(packId: string): Promise<NamedItem[]> {
+  return invoke("list_quests", { packId });
+}
+
+export async function getQuest(
+  packId: string,
+  id: string,
+): Promise<Quest | null> {
+  return invoke("get_quest", { packId, id });
+}
+
+export async function saveQuest(
+  packId: string,
+  payload: Quest,
+): Promise<string> {
+  return invoke("save_quest", { packId, payload });
+}
+
+export async function deleteQuest(packId: string, id: string): Promise<void> {
+  await invoke("delete_quest", { packId, id });
+}
+
 export async function listStorylines(packId: string): Promise<NamedItem[]> {
   return invoke("list_storylines", { packId });
 }
@@ -225,8 +258,18 @@ export async function loadSave(id: string): Promise<SaveGame | null> {
   return invoke("load_save", { id });
 }
 
-export async function saveGame(payload: SaveGame): Promise<string> {
-  return invoke("save_game", { payload });
+export async function saveGame(
+  payload: SaveGame,
+  format?: SaveFormat,
+): Promise<string> {
+  return invoke("save_game", { payload, format: format ?? null });
+}
+
+export async function convertSaveFormat(
+  id: string,
+  format: SaveFormat,
+): Promise<void> {
+  await invoke("convert_save_format", { id, format });
 }
 
 export async function saveCharacter(payload: Character): Promise<string> {
@@ -246,3 +289,32 @@ export async function saveCharacter(payload: Character): Promise<string> {
 export async function deleteSave(id: string): Promise<void> {
   await invoke("delete_save", { id });
 }
+
+export async function exportSave(id: string, destPath: string): Promise<void> {
+  await invoke("export_save", { id, destPath });
+}
+
+export async function importSave(
+  srcPath: string,
+  policy?: IntegrityPolicy,
+): Promise<ImportedSave> {
+  return invoke("import_save", { srcPath, policy: policy ?? null });
+}
+
+export async function listRoster(limit?: number): Promise<RosterEntry[]> {
+  return invoke("list_roster", { limit: limit ?? null });
+}
+
+export async function rosterOpponents(
+  limit: number,
+  excludeSaveId?: string,
+): Promise<EnemyTemplate[]> {
+  return invoke("roster_opponents", {
+    limit,
+    excludeSaveId: excludeSaveId ?? null,
+  });
+}
+
+export async function rebuildRoster(): Promise<number> {
+  return invoke("rebuild_roster");
+}
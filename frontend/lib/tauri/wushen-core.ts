@@ -1,45 +1,169 @@
-import { invoke } from "@tauri-apps/api/core";
-import type { Trait } from "@/types/trait";
+import { invoke as tauriInvoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type {
+  EntryDryRun,
+  EntryDryRunRequest,
+  FormulaContextKind,
+  FormulaVariable,
+  Trait,
+  TraitPreview,
+} from "@/types/trait";
 import type {
   Internal,
   AttackSkill,
   DefenseSkill,
   ManualType,
+  CultivationSweepReport,
+  CultivationSweepRequest,
 } from "@/types/manual";
-import type { CharacterPanel } from "@/types/character";
 import type {
+  Character,
+  CharacterAppearance,
+  CharacterBuild,
+  CharacterPanel,
+  CompletedCharacterSummary,
+  TitleView,
+} from "@/types/character";
+import type {
+  BattleConfig,
+  BattleMatchup,
   BattleResult,
+  BattleSessionUpdate,
+  BattleSessionView,
+  BattleSimulationReport,
   CultivationResult,
+  DebugCommand,
+  ExchangePreview,
   GameResponse,
+  GameViewDiff,
+  Scenario,
+  SaveProblem,
+  ScenarioReport,
+  SimulationProgress,
 } from "@/types/game";
-import type { AdventureEvent, Storyline } from "@/types/event";
+import type {
+  AdventureEvent,
+  DefeatPolicy,
+  EnemyBattleOptions,
+  EnemyTemplate,
+  PostBattleRecovery,
+  Reward,
+  RewardPreview,
+  Storyline,
+  StorylineStats,
+} from "@/types/event";
+import type {
+  ContentBundle,
+  ContentBundleReport,
+  ContentFingerprint,
+  LoadWarning,
+  ValidationReport,
+  WushenError,
+  WushenErrorCode,
+} from "@/types/mod";
+import type {
+  AdventureCollectionView,
+  AnalyticsReport,
+  CodexEntryView,
+  CodexKind,
+  BattleArchivePolicy,
+  ChallengeRuleset,
+  StoryHistoryRecord,
+  TrainingAssignment,
+} from "@/types/save";
+
+/** 核心命令失败时抛出；按 code 区分错误类别，message 为中文说明 */
+export class CoreError extends Error {
+  readonly code: WushenErrorCode;
+
+  constructor(error: WushenError) {
+    super(error.message);
+    this.name = "CoreError";
+    this.code = error.code;
+  }
+}
+
+function isWushenError(error: unknown): error is WushenError {
+  return (
+    typeof error === "object" &&
+    error !== null &&
+    typeof (error as WushenError).code === "string" &&
+    typeof (error as WushenError).message === "string"
+  );
+}
+
+async function invoke<T>(
+  command: string,
+  args?: Record<string, unknown>,
+): Promise<T> {
+  try {
+    return await tauriInvoke<T>(command, args);
+  } catch (error) {
+    throw isWushenError(error) ? new CoreError(error) : error;
+  }
+}
+
+export interface ManualFilter {
+  tag?: string;
+  rarity?: number;
+}
 
 export async function initCore(): Promise<void> {
   await invoke("core_reset");
 }
 
-export async function loadTraits(json: string): Promise<void> {
-  await invoke("core_load_traits", { json });
+export async function loadTraits(json: string): Promise<LoadWarning[]> {
+  return invoke<LoadWarning[]>("core_load_traits", { json });
+}
+
+export async function loadInternals(json: string): Promise<LoadWarning[]> {
+  return invoke<LoadWarning[]>("core_load_internals", { json });
 }
 
-export async function loadInternals(json: string): Promise<void> {
-  await invoke("core_load_internals", { json });
+export async function loadAttackSkills(json: string): Promise<LoadWarning[]> {
+  return invoke<LoadWarning[]>("core_load_attack_skills", { json });
 }
 
-export async function loadAttackSkills(json: string): Promise<void> {
-  await invoke("core_load_attack_skills", { json });
+export async function loadDefenseSkills(json: string): Promise<LoadWarning[]> {
+  return invoke<LoadWarning[]>("core_load_defense_skills", { json });
 }
 
-export async function loadDefenseSkills(json: string): Promise<void> {
-  await invoke("core_load_defense_skills", { json });
+export async function loadStorylines(json: string): Promise<LoadWarning[]> {
+  return invoke<LoadWarning[]>("core_load_storylines", { json });
 }
 
-export async function loadStorylines(json: string): Promise<void> {
-  await invoke("core_load_storylines", { json });
+export async function loadAdventureEvents(json: string): Promise<LoadWarning[]> {
+  return invoke<LoadWarning[]>("core_load_adventure_events", { json });
 }
 
-export async function loadAdventureEvents(json: string): Promise<void> {
-  await invoke("core_load_adventure_events", { json });
+export async function loadQuests(json: string): Promise<LoadWarning[]> {
+  return invoke<LoadWarning[]>("core_load_quests", { json });
+}
+
+export async function loadItems(json: string): Promise<LoadWarning[]> {
+  return invoke<LoadWarning[]>("core_load_items", { json });
+}
+
+export async function loadRecipes(json: string): Promise<LoadWarning[]> {
+  return invoke<LoadWarning[]>("core_load_recipes", { json });
+}
+
+export async function loadContentBundle(
+  bundle: ContentBundle,
+): Promise<ContentBundleReport> {
+  return invoke<ContentBundleReport>("core_load_content_bundle", { bundle });
+}
+
+// 敌人库需先于剧情线与奇遇加载
+export async function loadEnemies(json: string): Promise<LoadWarning[]> {
+  return invoke<LoadWarning[]>("core_load_enemies", { json });
+}
+
+export async function listQuests(): Promise<
+  Array<{ id: string; name: string }>
+> {
+  const json = await invoke<string>("core_list_quests");
+  return JSON.parse(json);
 }
 
 export async function getTrait(id: string): Promise<Trait> {
@@ -66,6 +190,16 @@ export async function listInternals(): Promise<
   return JSON.parse(json);
 }
 
+export async function listInternalsFiltered(
+  filter: ManualFilter,
+): Promise<Array<{ id: string; name: string }>> {
+  const json = await invoke<string>("core_list_internals_filtered", {
+    tag: filter.tag ?? null,
+    rarity: filter.rarity ?? null,
+  });
+  return JSON.parse(json);
+}
+
 export async function getAttackSkill(id: string): Promise<AttackSkill> {
   const json = await invoke<string>("core_get_attack_skill", { id });
   return JSON.parse(json);
@@ -78,6 +212,16 @@ export async function listAttackSkills(): Promise<
   return JSON.parse(json);
 }
 
+export async function listAttackSkillsFiltered(
+  filter: ManualFilter,
+): Promise<Array<{ id: string; name: string }>> {
+  const json = await invoke<string>("core_list_attack_skills_filtered", {
+    tag: filter.tag ?? null,
+    rarity: filter.rarity ?? null,
+  });
+  return JSON.parse(json);
+}
+
 export async function getDefenseSkill(id: string): Promise<DefenseSkill> {
   const json = await invoke<string>("core_get_defense_skill", { id });
   return JSON.parse(json);
@@ -90,6 +234,16 @@ export async function listDefenseSkills(): Promise<
   return JSON.parse(json);
 }
 
+export async function listDefenseSkillsFiltered(
+  filter: ManualFilter,
+): Promise<Array<{ id: string; name: string }>> {
+  const json = await invoke<string>("core_list_defense_skills_filtered", {
+    tag: filter.tag ?? null,
+    rarity: filter.rarity ?? null,
+  });
+  return JSON.parse(json);
+}
+
 export async function listStorylines(): Promise<
   Array<{ id: string; name: string }>
 > {
@@ -97,6 +251,11 @@ export async function listStorylines(): Promise<
   return JSON.parse(json);
 }
 
+export async function listStorylineStats(): Promise<StorylineStats[]> {
+  const json = await invoke<string>("core_list_storyline_stats");
+  return JSON.parse(json);
+}
+
 export async function getStoryline(id: string): Promise<Storyline> {
   const json = await invoke<string>("core_get_storyline", { id });
   return JSON.parse(json);
@@ -137,6 +296,16 @@ export async function calculateCultivationExp(
   });
 }
 
+/** 列出公式可用的变量（编辑器补全与校验） */
+export async function getFormulaVariableCatalog(
+  kind: FormulaContextKind,
+): Promise<FormulaVariable[]> {
+  const catalogJson = await invoke<string>("core_formula_variable_catalog", {
+    kind,
+  });
+  return JSON.parse(catalogJson);
+}
+
 export async function calculateBattle(
   attacker: CharacterPanel,
   defender: CharacterPanel,
@@ -154,6 +323,137 @@ export async function calculateBattle(
   return JSON.parse(resultJson);
 }
 
+export async function previewExchange(
+  attacker: CharacterPanel,
+  defender: CharacterPanel,
+  attackerQiOutputRate?: number,
+  defenderQiOutputRate?: number,
+): Promise<ExchangePreview> {
+  const resultJson = await invoke<string>("core_preview_exchange", {
+    attackerJson: JSON.stringify(attacker),
+    defenderJson: JSON.stringify(defender),
+    attackerQiOutputRate: attackerQiOutputRate ?? null,
+    defenderQiOutputRate: defenderQiOutputRate ?? null,
+  });
+  return JSON.parse(resultJson);
+}
+
+export async function testBattleAgainstEnemy(
+  character: CharacterPanel,
+  enemy: string | EnemyTemplate,
+  options: EnemyBattleOptions = {},
+): Promise<BattleResult> {
+  const resultJson = await invoke<string>("core_test_battle_against_enemy", {
+    characterJson: JSON.stringify(character),
+    enemy: typeof enemy === "string" ? enemy : JSON.stringify(enemy),
+    options,
+  });
+  return JSON.parse(resultJson);
+}
+
+/** 记录战斗回放（不执行战斗），返回可分享的回放JSON */
+export async function captureBattleReplay(
+  attacker: CharacterPanel,
+  defender: CharacterPanel,
+  attackerQiOutputRate?: number,
+  defenderQiOutputRate?: number,
+): Promise<string> {
+  return invoke<string>("core_capture_battle_replay", {
+    attackerJson: JSON.stringify(attacker),
+    defenderJson: JSON.stringify(defender),
+    attackerQiOutputRate: attackerQiOutputRate ?? null,
+    defenderQiOutputRate: defenderQiOutputRate ?? null,
+  });
+}
+
+/** 按回放重新执行战斗，结果与原战斗相同 */
+export async function replayBattle(replayJson: string): Promise<BattleResult> {
+  const resultJson = await invoke<string>("core_replay_battle", { replayJson });
+  return JSON.parse(resultJson);
+}
+
+export async function simulateCultivationSweep(
+  request: CultivationSweepRequest,
+): Promise<CultivationSweepReport> {
+  const resultJson = await invoke<string>("core_simulate_cultivation_sweep", {
+    requestJson: JSON.stringify(request),
+  });
+  return JSON.parse(resultJson);
+}
+
+export async function previewTrait(
+  trait: Trait,
+  character: CharacterPanel,
+  dummy?: CharacterPanel,
+): Promise<TraitPreview> {
+  const resultJson = await invoke<string>("core_preview_trait", {
+    traitJson: JSON.stringify(trait),
+    characterJson: JSON.stringify(character),
+    dummyJson: dummy ? JSON.stringify(dummy) : null,
+  });
+  return JSON.parse(resultJson);
+}
+
+/** 试算单个词条：条件是否满足与各效果的数值（不必跑完整场战斗） */
+export async function dryRunEntry(
+  request: EntryDryRunRequest,
+): Promise<EntryDryRun> {
+  const resultJson = await invoke<string>("core_dry_run_entry", {
+    requestJson: JSON.stringify(request),
+  });
+  return JSON.parse(resultJson);
+}
+
+export async function runScenarios(
+  scenarios: Scenario | Scenario[],
+): Promise<ScenarioReport[]> {
+  const resultJson = await invoke<string>("core_run_scenarios", {
+    scenariosJson: JSON.stringify(scenarios),
+  });
+  return JSON.parse(resultJson);
+}
+
+export async function simulateBattles(
+  matchups: BattleMatchup[],
+  onProgress?: (progress: SimulationProgress) => void,
+): Promise<BattleSimulationReport> {
+  const unlisten = onProgress
+    ? await listen<SimulationProgress>("simulation-progress", (event) =>
+        onProgress(event.payload),
+      )
+    : undefined;
+  try {
+    const resultJson = await invoke<string>("core_simulate_battles", {
+      matchupsJson: JSON.stringify(matchups),
+    });
+    return JSON.parse(resultJson);
+  } finally {
+    unlisten?.();
+  }
+}
+
+export async function cancelSimulation(): Promise<void> {
+  await invoke("core_cancel_simulation");
+}
+
+export async function getValidationReport(): Promise<ValidationReport> {
+  const resultJson = await invoke<string>("core_validation_report");
+  return JSON.parse(resultJson);
+}
+
+export async function getContentFingerprint(): Promise<ContentFingerprint> {
+  const resultJson = await invoke<string>("core_content_fingerprint");
+  return JSON.parse(resultJson);
+}
+
+export async function setBattleConfig(config: BattleConfig): Promise<void> {
+  await invoke("core_set_battle_config", { json: JSON.stringify(config) });
+}
+
+export async function setDisabledEntryTags(tags: string[]): Promise<void> {
+  await invoke("core_set_disabled_entry_tags", { tags });
+}
+
 export async function executeCultivation(
   character: CharacterPanel,
   manualId: string,
@@ -182,6 +482,11 @@ export async function gameStartNew(payload: {
   characterId: string;
   name: string;
   threeD: { comprehension: number; bone_structure: number; physique: number };
+  postBattleRecovery?: PostBattleRecovery | null;
+  defeatPolicy?: DefeatPolicy | null;
+  analytics?: boolean;
+  challenge?: ChallengeRuleset | null;
+  appearance?: CharacterAppearance | null;
 }): Promise<GameResponse> {
   const response = await invoke<string>("core_game_start_new", {
     request: {
@@ -189,21 +494,57 @@ export async function gameStartNew(payload: {
       character_id: payload.characterId,
       name: payload.name,
       three_d: payload.threeD,
+      post_battle_recovery: payload.postBattleRecovery ?? null,
+      defeat_policy: payload.defeatPolicy ?? null,
+      analytics: payload.analytics ?? false,
+      challenge: payload.challenge ?? null,
+      appearance: payload.appearance ?? null,
     },
   });
   return JSON.parse(response);
 }
 
-export async function gameResumeSave(id: string): Promise<GameResponse> {
-  const response = await invoke<string>("core_game_resume_save", { id });
+// 检查存档与当前内容是否一致（无问题时为空数组）
+export async function gameCheckSave(id: string): Promise<SaveProblem[]> {
+  const json = await invoke<string>("core_game_check_save", { id });
+  return JSON.parse(json);
+}
+
+// repair 为 true 时先修复存档中与当前内容不一致之处再恢复
+export async function gameResumeSave(
+  id: string,
+  repair = false,
+): Promise<GameResponse> {
+  const response = await invoke<string>("core_game_resume_save", {
+    id,
+    repair,
+  });
   return JSON.parse(response);
 }
 
+// 按需读取当前存档的剧情历史（单独分段存储，不随视图返回）
+export async function gameStoryHistory(): Promise<StoryHistoryRecord[]> {
+  const json = await invoke<string>("core_game_segment", {
+    kind: "story_history",
+  });
+  return JSON.parse(json);
+}
+
 export async function gameView(): Promise<GameResponse> {
   const response = await invoke<string>("core_game_view");
   return JSON.parse(response);
 }
 
+// 只返回自 sinceRevision 以来发生变化的分区（为空时返回全部分区）
+export async function gameViewDiff(
+  sinceRevision?: number,
+): Promise<GameViewDiff> {
+  const response = await invoke<string>("core_game_view_diff", {
+    sinceRevision: sinceRevision ?? null,
+  });
+  return JSON.parse(response);
+}
+
 export async function gameCultivate(
   manualId: string,
   manualType: ManualType,
@@ -226,6 +567,56 @@ export async function gameEquipManual(
   return JSON.parse(response);
 }
 
+export async function gameEquipTitle(
+  titleId: string | null,
+): Promise<GameResponse> {
+  const response = await invoke<string>("core_game_equip_title", {
+    titleId,
+  });
+  return JSON.parse(response);
+}
+
+export async function gameUseItem(itemId: string): Promise<GameResponse> {
+  const response = await invoke<string>("core_game_use_item", { itemId });
+  return JSON.parse(response);
+}
+
+// 按配方炼制（消耗背包中的材料）
+export async function gameCraft(recipeId: string): Promise<GameResponse> {
+  const response = await invoke<string>("core_game_craft", { recipeId });
+  return JSON.parse(response);
+}
+
+export async function gameTitles(): Promise<TitleView[]> {
+  const json = await invoke<string>("core_game_titles");
+  return JSON.parse(json);
+}
+
+export async function gameRewardsPreview(
+  rewards: Reward[],
+): Promise<RewardPreview> {
+  const json = await invoke<string>("core_game_rewards_preview", {
+    rewardsJson: JSON.stringify(rewards),
+  });
+  return JSON.parse(json);
+}
+
+// kind 为空时返回全部类别
+export async function gameCodex(kind?: CodexKind): Promise<CodexEntryView[]> {
+  const json = await invoke<string>("core_game_codex", { kind: kind ?? null });
+  return JSON.parse(json);
+}
+
+// global 为 true 时合并所有存档的发现记录
+export async function gameAdventureCollection(
+  global?: boolean,
+): Promise<AdventureCollectionView> {
+  const json = await invoke<string>("core_game_adventure_collection", {
+    global: global ?? null,
+  });
+  return JSON.parse(json);
+}
+
 export async function gameTravel(
   attackerQiOutputRate?: number,
   defenderQiOutputRate?: number,
@@ -253,11 +644,41 @@ export async function gameStoryBattle(
   return JSON.parse(response);
 }
 
+export async function gameStoryBattleManual(
+  attackerQiOutputRate?: number,
+  defenderQiOutputRate?: number,
+): Promise<BattleSessionView> {
+  const session = await invoke<string>("core_game_story_battle_manual", {
+    attackerQiOutputRate: attackerQiOutputRate ?? null,
+    defenderQiOutputRate: defenderQiOutputRate ?? null,
+  });
+  return JSON.parse(session);
+}
+
+export async function battleSessionStep(): Promise<BattleSessionUpdate> {
+  const update = await invoke<string>("core_battle_session_step");
+  return JSON.parse(update);
+}
+
+export async function battleSessionSetQiOutput(
+  rate: number,
+): Promise<BattleSessionView> {
+  const session = await invoke<string>("core_battle_session_set_qi_output", {
+    rate,
+  });
+  return JSON.parse(session);
+}
+
 export async function gameStoryContinue(): Promise<GameResponse> {
   const response = await invoke<string>("core_game_story_continue");
   return JSON.parse(response);
 }
 
+export async function gameStoryCheck(): Promise<GameResponse> {
+  const response = await invoke<string>("core_game_story_check");
+  return JSON.parse(response);
+}
+
 export async function gameAdventureOption(
   optionId: string,
   attackerQiOutputRate?: number,
@@ -271,7 +692,168 @@ export async function gameAdventureOption(
   return JSON.parse(response);
 }
 
+// 放弃进行中的奇遇（可能付出代价；仍有选项可选时还要消耗行动点）
+export async function gameAdventureSkip(): Promise<GameResponse> {
+  const response = await invoke<string>("core_game_adventure_skip");
+  return JSON.parse(response);
+}
+
+// 招收队伍成员（如门下弟子）
+export async function gameRecruitMember(
+  characterId: string,
+  name: string,
+  threeD: { comprehension: number; bone_structure: number; physique: number },
+): Promise<GameResponse> {
+  const response = await invoke<string>("core_game_recruit_member", {
+    characterId,
+    name,
+    threeD,
+  });
+  return JSON.parse(response);
+}
+
+// 切换操控的队伍成员（奇遇或战后抉择进行中时不可切换）
+export async function gameSwitchCharacter(
+  characterId: string,
+): Promise<GameResponse> {
+  const response = await invoke<string>("core_game_switch_character", {
+    characterId,
+  });
+  return JSON.parse(response);
+}
+
+// 增减队伍共享资源（delta 为负表示消耗）
+export async function gameAdjustResource(
+  resourceId: string,
+  delta: number,
+): Promise<GameResponse> {
+  const response = await invoke<string>("core_game_adjust_resource", {
+    resourceId,
+    delta,
+  });
+  return JSON.parse(response);
+}
+
+// 安排队伍成员修行（assignment 为 null 时取消安排）
+export async function gameAssignTraining(
+  characterId: string,
+  assignment: TrainingAssignment | null,
+): Promise<GameResponse> {
+  const response = await invoke<string>("core_game_assign_training", {
+    characterId,
+    assignment,
+  });
+  return JSON.parse(response);
+}
+
+export async function gameAcceptQuest(questId: string): Promise<GameResponse> {
+  const response = await invoke<string>("core_game_accept_quest", {
+    questId,
+  });
+  return JSON.parse(response);
+}
+
+export async function gameAbandonQuest(questId: string): Promise<GameResponse> {
+  const response = await invoke<string>("core_game_abandon_quest", {
+    questId,
+  });
+  return JSON.parse(response);
+}
+
+export async function gameClaimQuest(questId: string): Promise<GameResponse> {
+  const response = await invoke<string>("core_game_claim_quest", {
+    questId,
+  });
+  return JSON.parse(response);
+}
+
 export async function gameFinish(): Promise<GameResponse> {
   const response = await invoke<string>("core_game_finish");
   return JSON.parse(response);
 }
+
+export async function gameSetAnalytics(
+  enabled: boolean,
+): Promise<GameResponse> {
+  const response = await invoke<string>("core_game_set_analytics", {
+    enabled,
+  });
+  return JSON.parse(response);
+}
+
+// 设置战斗存档策略（传 null 关闭战斗存档）
+export async function gameSetBattleArchive(
+  policy: BattleArchivePolicy | null,
+): Promise<GameResponse> {
+  const response = await invoke<string>("core_game_set_battle_archive", {
+    policy,
+  });
+  return JSON.parse(response);
+}
+
+// 读取已存档的战斗结果（结构与战斗接口返回一致）
+export async function gameGetArchivedBattle(
+  id: string,
+): Promise<BattleResult> {
+  const json = await invoke<string>("core_game_get_archived_battle", { id });
+  return JSON.parse(json);
+}
+
+export async function gameAnalyticsReport(): Promise<AnalyticsReport> {
+  const json = await invoke<string>("core_game_analytics_report");
+  return JSON.parse(json);
+}
+
+// 上帝模式调试指令（仅在启用 god-mode 特性的构建中可用）
+export async function gameDebugCommand(
+  command: DebugCommand,
+): Promise<GameResponse> {
+  const response = await invoke<string>("core_debug_command", { command });
+  return JSON.parse(response);
+}
+
+export async function listCompletedCharacters(): Promise<
+  CompletedCharacterSummary[]
+> {
+  const json = await invoke<string>("core_list_completed_characters");
+  return JSON.parse(json);
+}
+
+export async function getCompletedCharacter(index: number): Promise<Character> {
+  const json = await invoke<string>("core_get_completed_character", { index });
+  return JSON.parse(json);
+}
+
+export async function exportCompletedCharacter(
+  index: number,
+): Promise<EnemyTemplate> {
+  const json = await invoke<string>("core_export_completed_character", {
+    index,
+  });
+  return JSON.parse(json);
+}
+
+export async function gameExportCharacterBuild(): Promise<CharacterBuild> {
+  const json = await invoke<string>("core_game_export_character_build");
+  return JSON.parse(json);
+}
+
+// 导入角色构筑，校验通过后返回可用作切磋对手的敌人模板
+export async function importCharacterBuild(
+  build: CharacterBuild,
+): Promise<EnemyTemplate> {
+  const json = await invoke<string>("core_import_character_build", {
+    json: JSON.stringify(build),
+  });
+  return JSON.parse(json);
+}
+
+export async function gameDeleteCompletedCharacter(
+  index: number,
+): Promise<GameResponse> {
+  const response = await invoke<string>(
+    "core_game_delete_completed_character",
+    { index },
+  );
+  return JSON.parse(response);
+}
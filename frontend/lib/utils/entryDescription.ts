@@ -51,6 +51,7 @@ const ATTRIBUTE_LABELS: Record<AttributeTarget, string> = {
   damage_bonus: "增伤",
   damage_reduction: "减伤",
   max_damage_reduction: "减伤上限",
+  momentum: "气势",
   martial_arts_attainment_gain: "武学素养增益",
   cultivation_exp_gain: "修行经验增益",
   qi_gain: "内息增益",
@@ -198,6 +199,15 @@ export function describeCondition(
   if ("defense_skill_type_is" in condition) {
     return `防御武技类型为「${condition.defense_skill_type_is}」`;
   }
+  if ("internal_has_tag" in condition) {
+    return `内功带有标签「${condition.internal_has_tag}」`;
+  }
+  if ("attack_skill_has_tag" in condition) {
+    return `攻击武技带有标签「${condition.attack_skill_has_tag}」`;
+  }
+  if ("defense_skill_has_tag" in condition) {
+    return `防御武技带有标签「${condition.defense_skill_has_tag}」`;
+  }
   if ("has_trait" in condition) {
     return `拥有特性「${resolveTraitLabel(resolver, condition.has_trait)}」`;
   }
@@ -261,6 +271,10 @@ function describeEffect(effect: Effect): string {
     const outputText = annotated.variables.length > 0 ? annotated.text : output;
     return `额外攻击，输出=${outputText}`;
   }
+  if (effect.type === "seal_skill") {
+    const slotLabel = effect.slot === "attack_skill" ? "攻击武技" : "防御武技";
+    return `封住对手${slotLabel}${effect.rounds}回合`;
+  }
 
   const panelLabel =
     PANEL_TARGET_LABELS[effect.target_panel ?? "own"] ?? "自身";
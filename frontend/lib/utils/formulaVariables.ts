@@ -24,6 +24,7 @@ export const FORMULA_VARIABLE_LABELS: Record<string, string> = {
   self_attack_speed: "自身出手速度",
   self_qi_recovery_rate: "自身回气速度",
   self_charge_time: "自身蓄力时间",
+  self_momentum: "自身气势",
   opponent_x: "对手悟性",
   opponent_y: "对手根骨",
   opponent_z: "对手体魄",
@@ -49,6 +50,7 @@ export const FORMULA_VARIABLE_LABELS: Record<string, string> = {
   opponent_attack_speed: "对手出手速度",
   opponent_qi_recovery_rate: "对手回气速度",
   opponent_charge_time: "对手蓄力时间",
+  opponent_momentum: "对手气势",
   attack_total_output: "攻击总输出",
   attack_total_defense: "攻击总防御",
   attack_reduced_output: "攻击减伤后输出",
@@ -93,6 +95,7 @@ export const FORMULA_VARIABLE_GROUPS: Array<{ title: string; keys: string[] }> =
         "self_attack_speed",
         "self_qi_recovery_rate",
         "self_charge_time",
+        "self_momentum",
       ],
     },
     {
@@ -128,6 +131,7 @@ export const FORMULA_VARIABLE_GROUPS: Array<{ title: string; keys: string[] }> =
         "opponent_attack_speed",
         "opponent_qi_recovery_rate",
         "opponent_charge_time",
+        "opponent_momentum",
       ],
     },
     {
@@ -156,15 +160,23 @@ const VARIABLE_PATTERN = new RegExp(
   "g",
 );
 
+const ENVIRONMENT_PATTERN = /\benv_([a-z0-9_]+)\b/g;
+
 export function annotateFormula(formula: string): {
   text: string;
   variables: string[];
 } {
   const used = new Set<string>();
-  const text = formula.replace(VARIABLE_PATTERN, (match) => {
-    used.add(match);
-    const label = FORMULA_VARIABLE_LABELS[match];
-    return label ?? match;
-  });
+  const text = formula
+    .replace(VARIABLE_PATTERN, (match) => {
+      used.add(match);
+      const label = FORMULA_VARIABLE_LABELS[match];
+      return label ?? match;
+    })
+    // 战斗环境变量（由剧情/奇遇战斗声明，未声明时按 0 计算）
+    .replace(ENVIRONMENT_PATTERN, (match, name: string) => {
+      used.add(match);
+      return `环境「${name}」`;
+    });
   return { text, variables: Array.from(used) };
 }
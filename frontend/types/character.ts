@@ -29,9 +29,122 @@ export interface Character {
   defense_skills: ManualsData;
   action_points: number;
   cultivation_history: CultivationHistoryItem[];
+  max_hp?: number;
+  hp?: number;
+  base_attack?: number;
+  base_defense?: number;
   max_qi?: number;
   qi?: number;
   martial_arts_attainment?: number;
+  timed_buffs?: TimedBuff[];
+  // 预约的后续奇遇（按剩余行动次数计时）
+  scheduled_events?: ScheduledEvent[];
+  // 剧情标记
+  story_flags?: string[];
+  // 已获得的称号与佩戴中的称号 ID
+  titles?: Title[];
+  worn_title?: string | null;
+  // 背包中的物品
+  inventory?: ItemStack[];
+  chronicle?: Chronicle | null;
+  // 外观（创建角色时选择，随存档保留）
+  appearance?: CharacterAppearance | null;
+}
+
+// 背包中的一种物品及其数量
+export interface ItemStack {
+  id: string;
+  count: number;
+}
+
+// 角色外观（资源键与外观选项由界面自行解析）
+export interface CharacterAppearance {
+  portrait_key?: string | null;
+  options?: Record<string, string>;
+}
+
+// 可分享的角色构筑（不含剧情状态）
+export interface CharacterBuild {
+  version?: number;
+  name: string;
+  three_d: ThreeDimensional;
+  traits?: string[];
+  internals: ManualsData;
+  attack_skills: ManualsData;
+  defense_skills: ManualsData;
+  max_qi?: number;
+  martial_arts_attainment?: number;
+}
+
+// 已完成角色摘要
+export interface CompletedCharacterSummary {
+  index: number;
+  id: string;
+  name: string;
+  storyline_name?: string;
+  ending_name?: string;
+  finished_at?: number;
+  summary?: string;
+  appearance?: CharacterAppearance;
+}
+
+// 角色传记（完成剧情后生成）
+export interface Chronicle {
+  storyline_id: string;
+  storyline_name: string;
+  beats: ChronicleBeat[];
+  strongest_battle?: ChronicleBattle | null;
+  ending_event_id: string;
+  ending_name: string;
+  days: number;
+  adventures: number;
+  battles_won: number;
+  battles_lost: number;
+  finished_at: number;
+  summary: string;
+}
+
+export interface ChronicleBeat {
+  event_id: string;
+  name: string;
+  choice?: string | null;
+  battle_win?: boolean | null;
+}
+
+export interface ChronicleBattle {
+  scope: "story" | "adventure";
+  event_id: string;
+  event_name: string;
+  enemy_name: string;
+  enemy_power: number;
+  win: boolean;
+}
+
+// 称号（佩戴时词条生效）
+export interface Title {
+  id: string;
+  name: string;
+  entries?: Entry[];
+}
+
+export interface TitleView {
+  id: string;
+  name: string;
+  worn: boolean;
+}
+
+// 限时增益（按剩余行动次数计时）
+export interface TimedBuff {
+  id: string;
+  name: string;
+  entries: Entry[];
+  remaining_actions: number;
+}
+
+// 预约的后续奇遇，剩余行动次数归零后到期
+export interface ScheduledEvent {
+  adventure_id: string;
+  remaining_actions: number;
 }
 
 export interface CultivationHistoryItem {
@@ -47,6 +160,10 @@ export interface CharacterPanel {
   internals: ManualsData;
   attack_skills: ManualsData;
   defense_skills: ManualsData;
+  max_hp?: number;
+  hp?: number;
+  base_attack?: number;
+  base_defense?: number;
   max_qi?: number;
   qi?: number;
   martial_arts_attainment?: number;
@@ -2,6 +2,10 @@ import type { EnemyTemplate } from "@/types/event";
 
 export interface Enemy extends EnemyTemplate {
   id: string;
+  // 继承的基础敌人 ID（其余字段覆盖基础敌人，对象字段逐层合并）
+  extends?: string | null;
+  // 追加到基础敌人特性之后的特性
+  add_traits?: string[];
 }
 
 export interface EnemyListItem {
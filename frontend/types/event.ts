@@ -1,4 +1,4 @@
-import type { Condition, Operation } from "@/types/trait";
+import type { Condition, Entry, Operation } from "@/types/trait";
 
 export type StoryNodeType = "start" | "middle" | "end";
 
@@ -7,14 +7,159 @@ export interface Storyline {
   name: string;
   start_event_id: string;
   events: StoryEvent[];
+  post_battle_recovery?: PostBattleRecovery;
+  // 剧情战斗战败处理策略（缺省进入战败分支）
+  defeat_policy?: DefeatPolicy;
+  battle_config?: import("./game").BattleConfig | null;
+  calendar?: CalendarConfig | null;
+  // 各指令的行动点消耗（未配置的指令使用缺省消耗）
+  action_costs?: ActionCostConfig;
+  // 重复获得特性、功法时的处理策略（未配置的类型跳过）
+  duplicate_rewards?: DuplicateRewardConfig;
+  chronicle_template?: string | null;
+  echo_encounter?: EchoEncounter | null;
+  // 章节划分（需覆盖全部事件且互不重叠）
+  chapters?: Chapter[];
+  // 开局行装（新角色的初始功法、特性、内息与行动点）
+  starting_kit?: StartingKit;
 }
 
+// 开局功法（等级只能为 0 或 1）
+export interface StartingManual {
+  id: string;
+  level?: number;
+  equipped?: boolean;
+}
+
+// 开局行装
+export interface StartingKit {
+  internals?: StartingManual[];
+  attack_skills?: StartingManual[];
+  defense_skills?: StartingManual[];
+  traits?: string[];
+  qi?: number;
+  // 离开起始事件后进入的行动阶段额外获得的行动点
+  action_points?: number;
+}
+
+// 剧情章节
+export interface Chapter {
+  id: string;
+  title: string;
+  summary?: string;
+  event_ids: string[];
+  // 离开本章时发放的奖励
+  rewards?: Reward[];
+}
+
+// 剧情线统计（选择剧情线时估计篇幅、编辑器展示概要）
+export interface StorylineStats {
+  storyline_id: string;
+  name: string;
+  event_count: number;
+  node_types: { start: number; middle: number; end: number };
+  battle_count: number;
+  ending_count: number;
+  // 不同敌人的数量
+  enemy_count: number;
+  // 从起始事件到结局沿途获得的行动点
+  min_action_points: number;
+  max_action_points: number;
+}
+
+// 往昔之影：游历时遭遇已完成角色
+export interface EchoEncounter {
+  chance: number;
+  condition?: Condition | null;
+  text?: string;
+  win?: AdventureOutcome;
+  lose?: AdventureOutcome;
+  // 平局结果（未配置时按战败处理）
+  draw?: AdventureOutcome | null;
+}
+
+export type Season = "spring" | "summer" | "autumn" | "winter";
+
+export interface SeasonalValues {
+  spring?: number;
+  summer?: number;
+  autumn?: number;
+  winter?: number;
+}
+
+export interface CalendarConfig {
+  days_per_action?: number;
+  days_per_season?: number;
+  cultivation_exp_bonus?: SeasonalValues;
+}
+
+export type ActionKind =
+  | "cultivate"
+  | "travel"
+  | "equip_manual"
+  | "equip_title"
+  | "accept_quest"
+  | "abandon_quest"
+  | "claim_quest"
+  | "use_item"
+  | "craft"
+  | "adventure_skip";
+
+export type ActionCostConfig = Partial<Record<ActionKind, number>>;
+
+export type DuplicateRewardKind =
+  | "trait"
+  | "internal"
+  | "attack_skill"
+  | "defense_skill";
+
+// 重复奖励处理策略：跳过 / 转换为其他奖励 / 叠加（仅特性）
+export type DuplicatePolicy =
+  | { type: "skip" }
+  | { type: "convert"; rewards: Reward[] }
+  | { type: "stack" };
+
+export type DuplicateRewardConfig = Partial<
+  Record<DuplicateRewardKind, DuplicatePolicy>
+>;
+
+export interface DuplicateReward {
+  reward: Reward;
+  policy: DuplicatePolicy;
+}
+
+// 战后恢复：完全恢复 / 按比例恢复（至少保留 1 点生命） / 不恢复（保留战斗结束时的状态）
+export type PostBattleRecovery =
+  | { type: "full" }
+  | { type: "percentage"; ratio: number }
+  | { type: "none" };
+
+// 战败处理策略：仅剧情 / 重试（负伤并扣除行动点） / 永久死亡
+export type DefeatPolicy =
+  | { type: "narrative" }
+  | {
+      type: "retry";
+      injury?: Entry[];
+      injury_actions?: number;
+      // 从之后有行动点的节点中扣除
+      action_points_lost?: number;
+    }
+  | { type: "permadeath" };
+
 export interface StoryEvent {
   id: string;
   name: string;
   node_type: StoryNodeType;
   action_points?: number;
   content: StoryEventContent;
+  presentation?: Presentation | null;
+}
+
+// 表现层元数据（资源键由界面自行解析）
+export interface Presentation {
+  bgm?: string | null;
+  background?: string | null;
+  portraits?: string[];
 }
 
 export type StoryEventContent =
@@ -28,8 +173,22 @@ export type StoryEventContent =
       text: string;
       enemy_id?: string;
       enemy: EnemyTemplate;
+      // 同场的其余敌人（一对多战斗，全部落败才算获胜）
+      enemies?: EnemyTemplate[];
       win: StoryBattleBranch;
       lose: StoryBattleBranch;
+      // 平局分支（达到最大轮数未分胜负；未配置时按战败分支处理）
+      draw?: StoryBattleBranch | null;
+      // 双方的让子与限制
+      handicaps?: BattleHandicap[];
+      // 战斗环境变量（如 rain: 1），词条公式中以 env_rain 引用，未声明的按 0 计算
+      environment?: Record<string, number>;
+      // 剧情打断（满足条件时立即结束战斗）
+      interruptions?: BattleInterruption[];
+      // 训练模式（玩家不会落败，训练结束后进入胜利分支）
+      training?: TrainingMode | null;
+      // 战胜后的抉择（放过/击杀/招揽等）
+      victory_decision?: VictoryDecision | null;
     }
   | {
       type: "story";
@@ -37,28 +196,128 @@ export type StoryEventContent =
       rewards?: Reward[];
       next_event_id?: string | null;
     }
+  | {
+      type: "check";
+      text: string;
+      check: StatCheck;
+      // 大成功分支（未配置时按成功处理）
+      critical_success?: StoryCheckBranch | null;
+      success: StoryCheckBranch;
+      failure: StoryCheckBranch;
+    }
   | {
       type: "end";
       text: string;
     };
 
+// 检定属性
+export type CheckStat =
+  | "comprehension"
+  | "bone_structure"
+  | "physique"
+  | "martial_arts_attainment";
+
+// 属性检定（检定值 = 属性值 + [0, roll) 随机加值）
+export interface StatCheck {
+  stat: CheckStat;
+  roll?: number;
+  success_threshold: number;
+  critical_threshold?: number | null;
+}
+
+export interface StoryCheckBranch {
+  text?: string | null;
+  next_event_id: string;
+  rewards?: Reward[];
+}
+
 export interface StoryOption {
   id: string;
   text: string;
   next_event_id: string;
   condition?: Condition | null;
+  // 本局历史前置条件（全部满足才显示）
+  prerequisites?: StoryPrerequisite[];
+  // 选择时付出的代价（无法支付时不显示）
+  costs?: OptionCost[];
+  // 兜底选项：仅在其他选项都不可选时出现
+  fallback?: boolean;
 }
 
+export type StoryPrerequisite =
+  | { type: "flag"; flag: string; set?: boolean }
+  | { type: "visited_event"; event_id: string }
+  | { type: "chose_option"; event_id: string; option_id: string }
+  | { type: "completed_adventure"; adventure_id: string }
+  | { type: "battle_result"; event_id: string; win: boolean }
+  | { type: "battles_won"; min: number };
+
 export interface StoryBattleBranch {
   next_event_id: string;
   rewards?: Reward[];
 }
 
+export interface VictoryDecision {
+  text: string;
+  options: VictoryOption[];
+}
+
+export interface VictoryOption {
+  id: string;
+  text: string;
+  rewards?: Reward[];
+  // 为空时沿用胜利分支的后续事件
+  next_event_id?: string | null;
+}
+
+// 战斗让子与限制（开战前调整一方的面板或追加词条）
+export interface BattleHandicap {
+  side: "player" | "enemy";
+  name?: string | null;
+  // 开局生命、内息比例（0-1）
+  hp_ratio?: number | null;
+  qi_ratio?: number | null;
+  // 出手速度倍率（如 0.8 表示降低 20%）
+  attack_speed_multiplier?: number | null;
+  disable_defense_skill?: boolean;
+  entries?: Entry[];
+}
+
+export interface TrainingMode {
+  rounds: number;
+}
+
+// 试战选项（编辑器中直接对战敌人模板）
+export interface EnemyBattleOptions {
+  attacker_qi_output_rate?: number | null;
+  defender_qi_output_rate?: number | null;
+  handicaps?: BattleHandicap[];
+  training?: TrainingMode | null;
+  environment?: Record<string, number>;
+  // 使用该剧情线的战斗配置
+  storyline_id?: string | null;
+}
+
+export interface BattleInterruption {
+  id: string;
+  trigger: InterruptCondition;
+  text?: string | null;
+  next_event_id: string;
+  rewards?: Reward[];
+}
+
+export type InterruptCondition =
+  | { type: "round"; round: number }
+  | { type: "player_hp_below"; ratio: number }
+  | { type: "enemy_hp_below"; ratio: number };
+
 export interface AdventureEvent {
   id: string;
   name: string;
   trigger?: Condition | null;
+  seasons?: Season[];
   content: AdventureEventContent;
+  presentation?: Presentation | null;
 }
 
 export type AdventureEventContent =
@@ -66,14 +325,24 @@ export type AdventureEventContent =
       type: "decision";
       text: string;
       options: AdventureOption[];
+      // 放弃奇遇时付出的代价（无法支付的部分免除）
+      skip_costs?: OptionCost[];
     }
   | {
       type: "battle";
       text: string;
       enemy_id?: string;
       enemy: EnemyTemplate;
+      // 同场的其余敌人（一对多战斗，全部落败才算获胜）
+      enemies?: EnemyTemplate[];
       win: AdventureOutcome;
       lose: AdventureOutcome;
+      // 平局结果（未配置时按战败处理）
+      draw?: AdventureOutcome | null;
+      // 双方的让子与限制
+      handicaps?: BattleHandicap[];
+      // 战斗环境变量（如 rain: 1），词条公式中以 env_rain 引用，未声明的按 0 计算
+      environment?: Record<string, number>;
     }
   | {
       type: "story";
@@ -85,9 +354,23 @@ export interface AdventureOption {
   id: string;
   text: string;
   condition?: Condition | null;
+  // 选择时付出的代价（无法支付时不显示）
+  costs?: OptionCost[];
+  // 兜底选项：仅在其他选项都不可选时出现
+  fallback?: boolean;
   result: AdventureOptionResult;
 }
 
+// 选项代价（与奖励一并结算）
+export type OptionCost =
+  | { type: "qi"; amount: number }
+  | { type: "hp"; amount: number }
+  | { type: "attribute"; target: RewardTarget; amount: number }
+  | { type: "trait"; id: string }
+  | { type: "internal"; id: string }
+  | { type: "attack_skill"; id: string }
+  | { type: "defense_skill"; id: string };
+
 export type AdventureOptionResult =
   | {
       type: "story";
@@ -99,8 +382,16 @@ export type AdventureOptionResult =
       text: string;
       enemy_id?: string;
       enemy: EnemyTemplate;
+      // 同场的其余敌人（一对多战斗，全部落败才算获胜）
+      enemies?: EnemyTemplate[];
       win: AdventureOutcome;
       lose: AdventureOutcome;
+      // 平局结果（未配置时按战败处理）
+      draw?: AdventureOutcome | null;
+      // 双方的让子与限制
+      handicaps?: BattleHandicap[];
+      // 战斗环境变量（如 rain: 1），词条公式中以 env_rain 引用，未声明的按 0 计算
+      environment?: Record<string, number>;
     };
 
 export interface AdventureOutcome {
@@ -127,16 +418,54 @@ export type Reward =
       rarity?: number | null;
       manual_type?: string | null;
       count?: number;
-    };
+    }
+  | {
+      type: "timed_buff";
+      id: string;
+      name: string;
+      entries: Entry[];
+      actions: number;
+    }
+  | { type: "story_flag"; flag: string; value?: boolean }
+  | { type: "title"; id: string; name: string; entries?: Entry[] }
+  // 预约后续奇遇：之后第 actions 次行动时到期，到期后的首次游历必定触发
+  | { type: "schedule_event"; adventure_id: string; actions: number }
+  // 获得物品（放入背包，同种物品叠加数量）
+  | { type: "item"; id: string; count?: number };
 
 export type RewardTarget =
   | "comprehension"
   | "bone_structure"
   | "physique"
-  | "martial_arts_attainment";
+  | "martial_arts_attainment"
+  | "max_hp"
+  | "base_attack"
+  | "base_defense";
 
 export type ManualKind = "internal" | "attack_skill" | "defense_skill" | "any";
 
+// 奖励预览（确认对话框使用，不修改存档）
+export interface RewardPreview {
+  manuals: {
+    manual_kind: ManualKind;
+    id: string;
+    level: number;
+    exp: number;
+  }[];
+  traits: string[];
+  attributes: { target: RewardTarget; before: number; after: number }[];
+  max_qi_delta: number;
+  qi_delta: number;
+  // 随机功法在发放时抽取，这里只给出条件与候选数量
+  random_manuals: {
+    manual_kind: ManualKind;
+    rarity: number | null;
+    manual_type: string | null;
+    count: number;
+    candidates: number;
+  }[];
+}
+
 export interface ThreeDimensionalTemplate {
   comprehension: number;
   bone_structure: number;
@@ -159,6 +488,24 @@ export interface EnemyTemplate {
   max_qi?: number | null;
   qi?: number | null;
   martial_arts_attainment?: number | null;
+  // 开场/收场文本（{self} 为敌人名，{opponent} 为玩家名）
+  intro_text?: string | null;
+  win_text?: string | null;
+  lose_text?: string | null;
+  // 首领阶段（生命比例降至阈值时切换）
+  phases?: EnemyPhase[];
+  // 敌人库引用：一对多战斗中同场的其余敌人用它引用敌人库（主敌人使用事件的 enemy_id）
+  enemy_id?: string;
+}
+
+export interface EnemyPhase {
+  name: string;
+  // 生命比例阈值（0-1，不含端点）
+  hp_ratio: number;
+  text?: string | null;
+  attack_skill?: OwnedManualTemplate | null;
+  defense_skill?: OwnedManualTemplate | null;
+  traits?: string[];
 }
 
 export interface AdventureEventListItem {
@@ -170,3 +517,23 @@ export interface StorylineListItem {
   id: string;
   name: string;
 }
+
+export type QuestObjective =
+  | { type: "win_battles"; count: number }
+  | {
+      type: "manual_level";
+      manual_kind: ManualKind;
+      manual_id?: string | null;
+      level: number;
+    }
+  | { type: "has_trait"; trait_id: string };
+
+export interface Quest {
+  id: string;
+  name: string;
+  description?: string;
+  condition?: Condition | null;
+  objectives: QuestObjective[];
+  rewards?: Reward[];
+  repeatable?: boolean;
+}
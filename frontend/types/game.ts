@@ -1,5 +1,14 @@
 /// 游戏相关类型定义
 
+import type {
+  Character,
+  CharacterPanel,
+  ThreeDimensional,
+} from "@/types/character";
+import type { AttributeTarget, Trigger } from "@/types/trait";
+import type { ManualType } from "@/types/manual";
+import type { OptionCost } from "@/types/event";
+
 export interface BattlePanel {
   name: string;
   max_hp: number;
@@ -19,6 +28,7 @@ export interface BattlePanel {
   attack_speed: number;
   qi_recovery_rate: number;
   charge_time: number;
+  momentum: number;
 }
 
 export interface PanelDelta {
@@ -39,6 +49,7 @@ export interface PanelDelta {
   attack_speed_delta?: number;
   qi_recovery_rate_delta?: number;
   charge_time_delta?: number;
+  momentum_delta?: number;
 }
 
 export interface BattleRecord {
@@ -47,13 +58,219 @@ export interface BattleRecord {
   attacker_panel_delta?: PanelDelta;
   defender_panel_delta?: PanelDelta;
   is_terminal?: boolean;
+  // 触发词条的来源（特性名、功法名与境界、称号等）
+  source?: string;
 }
 
 export interface BattleResult {
-  result: "attacker_win" | "defender_win" | "draw";
+  result: "attacker_win" | "defender_win" | "draw" | "interrupted";
+  // 触发的剧情打断标识
+  interruption?: string;
+  // 属性上限约束记录（调试用）
+  clamp_trace?: AttributeClamp[];
+  // 因标签被禁用而跳过的词条（调试用）
+  attacker_skipped_entries?: SkippedEntry[];
+  defender_skipped_entries?: SkippedEntry[];
+  // 训练表现评分（训练战斗才有）
+  training?: TrainingScore;
   records: BattleRecord[];
   attacker_panel: BattlePanel;
   defender_panel: BattlePanel;
+  // 溯源信息（引擎版本、内容指纹、随机种子与配置摘要）
+  provenance: BattleProvenance;
+  // 各敌人的结果（按出场序号，一对多战斗时有多个）
+  enemies?: EnemyOutcome[];
+  // 开战时未能解析的装备功法（按未装备参战）
+  unresolved_manuals?: UnresolvedManual[];
+}
+
+// 装备但未加载的功法
+export interface UnresolvedManual {
+  side: "a" | "b";
+  character: string;
+  manual_type: "internal" | "attack_skill" | "defense_skill";
+  manual_id: string;
+}
+
+// 战斗中单个敌人的结果
+export interface EnemyOutcome {
+  index: number;
+  name: string;
+  defeated: boolean;
+  hp: number;
+  max_hp: number;
+  damage_taken: number;
+}
+
+// 战斗溯源信息：据此把分享出来的战斗结果对应到确切的输入
+export interface BattleProvenance {
+  engine_version: string;
+  // 开战时加载内容的指纹摘要
+  content_digest?: string;
+  // 随机先手种子（非随机先手时为 0）
+  seed: number;
+  // 随机数流的当前状态
+  rng_state: number;
+  // 战斗配置摘要
+  config_hash: string;
+}
+
+// 训练表现评分（综合评分 0-100）
+export interface TrainingScore {
+  rounds: number;
+  damage_dealt: number;
+  damage_taken: number;
+  attacks: number;
+  attacks_broke_through: number;
+  defenses: number;
+  defenses_held: number;
+  score: number;
+}
+
+// 一次交手的预估结算
+export interface BattleCalculation {
+  total_output: number;
+  total_defense: number;
+  reduced_output: number;
+  attacker_qi_consumed: number;
+  defender_qi_consumed: number;
+  hp_damage: number;
+  broke_qi_defense: boolean;
+}
+
+// 双方互相攻击一次的预估结算
+export interface ExchangePreview {
+  attacker_to_defender: BattleCalculation;
+  defender_to_attacker: BattleCalculation;
+}
+
+// 属性被上限约束的记录
+export interface AttributeClamp {
+  character_name: string;
+  target: AttributeTarget;
+  requested: number;
+  applied: number;
+}
+
+// 因标签被禁用而跳过的词条
+export interface SkippedEntry {
+  source_id: string;
+  source_name?: string;
+  trigger: Trigger;
+  // 命中的禁用标签
+  tags: string[];
+}
+
+// 属性上限与软上限（超过软上限部分按比例折算）
+export interface AttributeCap {
+  target: AttributeTarget;
+  soft_cap?: number;
+  soft_cap_ratio?: number;
+  hard_cap?: number;
+}
+
+export type TiebreakerRule =
+  | "higher_hp_percent"
+  | "higher_qi"
+  | "attacker_loses";
+
+// 空手数值（未装备武技或武技被封时使用，由体魄与武学素养推导）
+export interface UnarmedStats {
+  power?: number;
+  power_per_physique?: number;
+  power_per_attainment?: number;
+  defense_power?: number;
+  defense_power_per_physique?: number;
+  defense_power_per_attainment?: number;
+  charge_time?: number;
+  charge_time_per_attainment?: number;
+  min_charge_time?: number;
+  attack_speed?: number;
+}
+
+// 基础内息池（与内功增益相加作为内息上限）
+export interface BaseQiPool {
+  base?: number;
+  per_physique?: number;
+  per_bone_structure?: number;
+}
+
+// 内息输出自动策略（每回合开始时调整内息输出）
+export type QiOutputProfile =
+  | { type: "fixed" }
+  | { type: "burst_early"; rounds?: number; later_ratio?: number }
+  | {
+      type: "save_for_finisher";
+      opponent_hp_threshold?: number;
+      saving_ratio?: number;
+    }
+  | { type: "mirror_opponent" };
+
+// 气势规则（攻防得手的一方积累气势，另一方衰减）
+export interface MomentumRule {
+  gain_on_hit?: number;
+  gain_on_defense?: number;
+  decay_per_round?: number;
+  max?: number;
+}
+
+// 双方同时蓄力完成时的先手规则
+export type FirstStrikeRule =
+  | { type: "side_a" }
+  | { type: "higher_attainment" }
+  | { type: "defender" }
+  | { type: "seeded"; seed: number };
+
+// 一对多战斗中玩家出手时选择目标的规则
+export type TargetRule = "in_order" | "lowest_hp" | "highest_attack";
+
+// 装备的功法未加载时的处理方式（默认 warn：照常开战并在战斗结果中列出）
+export type MissingManualPolicy = "ignore" | "warn" | "reject";
+
+// 数值策略（结算每一步的取整方式；仍为浮点运算，不保证跨平台逐位一致）
+export type NumericPolicy =
+  | { type: "float" }
+  | { type: "quantized"; scale: number };
+
+export interface BattleConfig {
+  max_rounds?: number;
+  tiebreakers?: TiebreakerRule[];
+  unarmed?: UnarmedStats;
+  base_qi?: BaseQiPool;
+  attribute_caps?: AttributeCap[];
+  side_a_qi_profile?: QiOutputProfile;
+  side_b_qi_profile?: QiOutputProfile;
+  numeric_policy?: NumericPolicy;
+  momentum?: MomentumRule;
+  first_strike?: FirstStrikeRule;
+  target_rule?: TargetRule;
+  missing_manuals?: MissingManualPolicy;
+}
+
+// 批量模拟的单场对局
+export interface BattleMatchup {
+  attacker: CharacterPanel;
+  defender: CharacterPanel;
+  attacker_qi_output_rate?: number;
+  defender_qi_output_rate?: number;
+}
+
+// 模拟进度（simulation-progress 事件）
+export interface SimulationProgress {
+  completed: number;
+  total: number;
+  elapsed_ms: number;
+  eta_ms: number | null;
+}
+
+export interface BattleSimulationReport {
+  total: number;
+  completed: number;
+  cancelled: boolean;
+  attacker_wins: number;
+  defender_wins: number;
+  draws: number;
+  results: BattleResult["result"][];
 }
 
 export interface CultivationResult {
@@ -66,7 +283,13 @@ export interface CultivationResult {
   updated_character: string; // JSON字符串
 }
 
-export type GamePhase = "action" | "story" | "adventure_decision" | "completed";
+export type GamePhase =
+  | "action"
+  | "story"
+  | "adventure_decision"
+  // 剧情抉择没有任何可选选项（原因见抉择视图的 blocked）
+  | "blocked"
+  | "completed";
 
 export interface StoryEventSummary {
   id: string;
@@ -78,6 +301,13 @@ export interface StoryOptionView {
   id: string;
   text: string;
   next_event_id: string;
+  costs?: OptionCost[];
+}
+
+export interface BlockedOptionView {
+  id: string;
+  text: string;
+  reasons: string[];
 }
 
 export type StoryEventContentView =
@@ -85,17 +315,30 @@ export type StoryEventContentView =
       type: "decision";
       text: string;
       options: StoryOptionView[];
+      // 没有可选选项时，各选项不可选的原因
+      blocked?: BlockedOptionView[];
     }
   | {
       type: "battle";
       text: string;
       enemy_name: string;
+      // 训练轮数（训练战斗才有）
+      training_rounds?: number;
+      // 双方装备但未加载的功法（开战前提示）
+      unresolved_manuals?: UnresolvedManual[];
     }
   | {
       type: "story";
       text: string;
       rewards: import("./event").Reward[];
     }
+  | {
+      type: "check";
+      text: string;
+      stat: import("./event").CheckStat;
+      success_threshold: number;
+      critical_threshold?: number;
+    }
   | {
       type: "end";
       text: string;
@@ -107,11 +350,13 @@ export interface StoryEventView {
   node_type: "start" | "middle" | "end";
   action_points: number;
   content: StoryEventContentView;
+  presentation?: import("./event").Presentation | null;
 }
 
 export interface AdventureOptionView {
   id: string;
   text: string;
+  costs?: OptionCost[];
 }
 
 export interface AdventureDecisionView {
@@ -119,15 +364,82 @@ export interface AdventureDecisionView {
   name: string;
   text: string;
   options: AdventureOptionView[];
+  // 放弃奇遇时付出的代价
+  skip_costs?: OptionCost[];
+  presentation?: import("./event").Presentation | null;
 }
 
 export interface GameView {
   save: import("./save").SaveGame;
   storyline?: { id: string; name: string } | null;
+  chapter?: ChapterView | null;
   phase: GamePhase;
   current_event?: StoryEventSummary | null;
   story_event?: StoryEventView | null;
   adventure?: AdventureDecisionView | null;
+  quests: QuestView[];
+  calendar: CalendarView;
+}
+
+// 差量视图：未变化的分区不返回
+export interface GameViewDiff {
+  revision: number;
+  character?: import("./character").Character;
+  phase?: GamePhase;
+  story?: {
+    storyline?: { id: string; name: string } | null;
+    chapter?: ChapterView | null;
+    current_event?: StoryEventSummary | null;
+    story_event?: StoryEventView | null;
+  };
+  adventure?: AdventureDecisionView;
+  // 奇遇分区变为“无奇遇”
+  adventure_cleared?: boolean;
+  quests: QuestView[];
+  calendar: CalendarView;
+}
+
+// 当前章节
+export interface ChapterView {
+  id: string;
+  title: string;
+  summary: string;
+  index: number;
+  total: number;
+}
+
+export interface CalendarView {
+  day: number;
+  year: number;
+  season: import("./event").Season;
+  season_name: string;
+  day_of_season: number;
+}
+
+export type QuestStatus = "available" | "active" | "completable" | "completed";
+
+export interface QuestObjectiveView {
+  text: string;
+  current: number;
+  target: number;
+}
+
+export interface QuestView {
+  id: string;
+  name: string;
+  description: string;
+  status: QuestStatus;
+  repeatable: boolean;
+  objectives: QuestObjectiveView[];
+  rewards: import("./event").Reward[];
+}
+
+export interface BattleRecovery {
+  rule: import("./event").PostBattleRecovery;
+  hp_before: number;
+  hp_after: number;
+  qi_before: number;
+  qi_after: number;
 }
 
 export type GameOutcome =
@@ -148,19 +460,170 @@ export type GameOutcome =
       type: "story";
       text?: string | null;
       rewards: import("./event").Reward[];
+      // 重复获得的奖励及处理方式
+      duplicates?: import("./event").DuplicateReward[];
       battle_result?: BattleResult | null;
+      // 各敌人的结果（按出场序号，一对多战斗时有多个）
+      enemies?: EnemyOutcome[];
       win?: boolean | null;
+      recovery?: BattleRecovery | null;
+      // 战败时生效的处理策略
+      defeat?: import("./event").DefeatPolicy | null;
     }
   | {
       type: "adventure";
       name: string;
       text?: string | null;
       rewards: import("./event").Reward[];
+      // 重复获得的奖励及处理方式
+      duplicates?: import("./event").DuplicateReward[];
       battle_result?: BattleResult | null;
+      // 各敌人的结果（按出场序号，一对多战斗时有多个）
+      enemies?: EnemyOutcome[];
       win?: boolean | null;
+      recovery?: BattleRecovery | null;
+    }
+  | {
+      type: "quest";
+      name: string;
+      rewards: import("./event").Reward[];
+      // 重复获得的奖励及处理方式
+      duplicates?: import("./event").DuplicateReward[];
+    }
+  | {
+      type: "finished";
+      message: string;
+      character: import("./character").Character;
     };
 
 export interface GameResponse {
   view: GameView;
   outcome?: GameOutcome | null;
+  /** 提示信息（如恢复存档时内容已变化） */
+  warnings?: string[];
+  /** 进入新剧情节点时，队伍成员按安排修行的结果 */
+  training?: TrainingReport[];
+}
+
+/** 手动剧情战斗中一方的状态 */
+export interface BattleSessionSide {
+  name: string;
+  hp: number;
+  max_hp: number;
+  qi: number;
+  max_qi: number;
+  qi_output_rate: number;
+  max_qi_output_rate: number;
+  momentum: number;
+}
+
+/** 手动剧情战斗的当前状态 */
+export interface BattleSessionView {
+  event_id: string;
+  /** 已开始的回合数 */
+  round: number;
+  finished: boolean;
+  player: BattleSessionSide;
+  enemy: BattleSessionSide;
+  /** 上次返回之后新增的战斗日志 */
+  logs: BattleRecord[];
+}
+
+export interface BattleSessionUpdate {
+  session: BattleSessionView;
+  /** 战斗结束后剧情结算的结果 */
+  response?: GameResponse;
+}
+
+export interface TrainingReport {
+  character_id: string;
+  name: string;
+  manual_id: string;
+  manual_type: ManualType;
+  sessions: number;
+  exp_gain: number;
+  old_level: number;
+  new_level: number;
+}
+
+/** 存档与当前内容不一致之处（恢复存档前检查） */
+export type SaveProblem =
+  | { kind: "missing_storyline"; storyline_id: string }
+  | { kind: "missing_story_event"; storyline_id: string; event_id: string }
+  | { kind: "missing_adventure"; adventure_id: string }
+  | {
+      kind: "missing_equipped_manual";
+      manual_type: ManualType;
+      manual_id: string;
+    };
+
+// 上帝模式调试指令（编辑器试玩用）
+export type DebugCommand =
+  | { type: "grant_manual"; manual_id: string; manual_type: string }
+  | { type: "grant_trait"; trait_id: string }
+  | {
+      type: "set_manual_level";
+      manual_id: string;
+      manual_type: string;
+      level: number;
+    }
+  | { type: "jump_to_event"; event_id: string }
+  | {
+      type: "goto_event";
+      storyline_id: string;
+      event_id: string;
+      // 替换当前角色（为空时沿用当前角色）
+      character?: Character | null;
+    }
+  | { type: "set_three_d"; three_d: ThreeDimensional }
+  | { type: "set_attainment"; value: number }
+  | {
+      type: "trigger_adventure";
+      adventure_id: string;
+      attacker_qi_output_rate?: number | null;
+      defender_qi_output_rate?: number | null;
+    };
+
+// 剧情场景测试步骤：操作或断言
+export type ScenarioStep =
+  | {
+      step: "start";
+      storyline_id: string;
+      name?: string;
+      three_d?: ThreeDimensional;
+    }
+  | { step: "cultivate"; manual_id: string; manual_type: string }
+  | { step: "equip_manual"; manual_id: string; manual_type: string }
+  | { step: "travel" }
+  | { step: "story_option"; option_id: string }
+  | { step: "story_battle" }
+  | { step: "story_continue" }
+  | { step: "adventure_option"; option_id: string }
+  | { step: "adventure_skip" }
+  | { step: "finish" }
+  | { step: "expect_phase"; phase: GamePhase }
+  | { step: "expect_event"; event_id: string }
+  | { step: "expect_trait"; trait_id: string; present?: boolean }
+  | { step: "expect_manual"; manual_id: string }
+  | { step: "expect_reward_trait"; trait_id: string }
+  | { step: "expect_battle_win"; win: boolean };
+
+export interface Scenario {
+  name: string;
+  steps: ScenarioStep[];
+}
+
+export interface ScenarioFailure {
+  // 步骤序号（从 0 开始）
+  step_index: number;
+  message: string;
+  // 失败时的存档状态
+  state?: unknown;
+}
+
+export interface ScenarioReport {
+  name: string;
+  passed: boolean;
+  steps_run: number;
+  failures: ScenarioFailure[];
 }
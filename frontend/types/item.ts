@@ -0,0 +1,38 @@
+/// 物品类型定义
+
+import type { RewardTarget } from "./event";
+import { Condition, Entry } from "./trait";
+
+// 物品（丹药等消耗品，或不可使用的宝物、信物）
+export interface Item {
+  id: string;
+  name: string;
+  description?: string;
+  // 使用效果（为空时物品不可使用，只能持有）
+  effects?: ItemEffect[];
+}
+
+export type ItemEffect =
+  // 恢复生命与内息（不超过上限）
+  | { type: "heal"; hp?: number; qi?: number }
+  // 限时增益：之后若干次行动内词条生效
+  | { type: "timed_buff"; entries: Entry[]; actions: number }
+  // 永久提升属性（受属性上限约束）
+  | { type: "boost"; target: RewardTarget; value: number };
+
+// 炼制配方：以背包中的材料炼成丹药等物品
+export interface Recipe {
+  id: string;
+  name: string;
+  description?: string;
+  ingredients: RecipeStack[];
+  output: RecipeStack;
+  // 炼制条件（如武学素养要求）
+  condition?: Condition | null;
+}
+
+export interface RecipeStack {
+  id: string;
+  // 缺省为 1
+  count?: number;
+}
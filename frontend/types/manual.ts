@@ -1,6 +1,6 @@
 /// 功法类型定义
 
-import { Entry } from "./trait";
+import { BattleRecordTemplate, Condition, Entry } from "./trait";
 
 export interface Manual {
   id: string;
@@ -8,11 +8,21 @@ export interface Manual {
   description: string;
   rarity: number;
   manual_type: string;
+  /** 标签（如剑法、拳法、阴、阳、轻功） */
+  tags?: string[];
   cultivation_formula: string;
+  /** 修行资质权重（偏重悟性/根骨/体魄） */
+  aptitude?: Aptitude | null;
   level: number;
   current_exp: number;
 }
 
+export interface Aptitude {
+  comprehension?: number;
+  bone_structure?: number;
+  physique?: number;
+}
+
 export interface InternalRealm {
   level: number;
   exp_required: number;
@@ -48,11 +58,36 @@ export interface Internal extends Manual {
 export interface AttackSkill extends Manual {
   realms: AttackSkillRealm[];
   log_template?: string;
+  /** 绝招：出手时内息足够且条件满足时代替普通攻击 */
+  ultimate?: UltimateMove | null;
+}
+
+export interface UltimateMove {
+  name: string;
+  /** 施展所需的内息 */
+  qi_threshold: number;
+  /** 施展时额外消耗的内息（不超过施展所需的内息） */
+  qi_cost?: number;
+  condition?: Condition;
+  /** 威能公式（代替武技威能） */
+  power_formula: string;
+  log_template?: string;
+  /** 每场战斗最多施展次数 */
+  max_uses?: number;
 }
 
 export interface DefenseSkill extends Manual {
   realms: DefenseSkillRealm[];
   log_template?: string;
+  /** 反击：以内息挡下攻击且条件满足时对攻击者反击 */
+  counter?: CounterAttack | null;
+}
+
+export interface CounterAttack {
+  /** 反击输出公式（可使用 attack_* 攻防结果变量） */
+  output: string;
+  condition?: Condition;
+  battle_record_template?: BattleRecordTemplate;
 }
 
 export interface ManualListItem {
@@ -61,3 +96,34 @@ export interface ManualListItem {
 }
 
 export type ManualType = "internal" | "attack_skill" | "defense_skill";
+
+/** 修行曲线推演请求：固定三维预算，体魄取悟性与根骨之外的余量 */
+export interface CultivationSweepRequest {
+  budget: number;
+  /** 每一维的下限 */
+  min_stat?: number;
+  /** 悟性与根骨的取值步长（默认 1） */
+  step?: number;
+  action_points: number;
+  /** 依次修行的功法：前一门圆满后转修下一门 */
+  manuals: { manual_id: string; manual_type: ManualType }[];
+  traits?: string[];
+}
+
+export interface SweepCell {
+  comprehension: number;
+  bone_structure: number;
+  physique: number;
+  /** 各功法达到的等级（与请求中的功法顺序一致） */
+  levels: number[];
+  total_level: number;
+  action_points_used: number;
+}
+
+/** 推演结果：cells[行][列] 对应悟性 × 根骨，体魄不足下限的分配为 null */
+export interface CultivationSweepReport {
+  comprehension: number[];
+  bone_structure: number[];
+  cells: (SweepCell | null)[][];
+  best: SweepCell | null;
+}
@@ -1,3 +1,11 @@
+import type { BattleConfig } from "@/types/game";
+
+export interface PackDependency {
+  id: string;
+  /** 版本要求，如 "^1.2"、">=1.0, <2.0"；为空表示不限版本 */
+  version?: string;
+}
+
 export interface ModPackMetadata {
   id: string;
   name: string;
@@ -6,6 +14,7 @@ export interface ModPackMetadata {
   description?: string;
   created_at: string;
   updated_at?: string;
+  dependencies?: PackDependency[];
 }
 
 export interface ActivePack {
@@ -22,4 +31,71 @@ export interface ModPackManifest {
   author?: string;
   description?: string;
   files: string[];
+  dependencies?: PackDependency[];
+}
+
+/** 加载警告类别：废弃的字段或格式 / 可疑数值 / 自动修正 */
+export type LoadWarningKind = "deprecated" | "suspicious" | "auto_corrected";
+
+/** 加载警告（不阻止加载） */
+export interface LoadWarning {
+  kind: LoadWarningKind;
+  /** 所属集合（如 internals） */
+  collection: string;
+  id?: string;
+  message: string;
+}
+
+/** 核心错误码（与 Rust 端 WushenError 对应） */
+export type WushenErrorCode =
+  | "parse_error"
+  | "not_found"
+  | "invalid_state"
+  | "condition_not_met"
+  | "formula_error"
+  | "invalid"
+  | "other";
+
+/** 核心命令返回的错误 */
+export interface WushenError {
+  code: WushenErrorCode;
+  message: string;
+}
+
+export interface ValidationReport {
+  errors: string[];
+  /** 加载过程中的警告（旧版数据格式升级、可疑数值与自动修正） */
+  warnings: LoadWarning[];
+  /** 条件检查结果（永远不满足的条件、矛盾的组合与被覆盖的选项） */
+  lints: string[];
+}
+
+/** 内容合集（各集合与对应 load 接口的格式相同，缺省视为空） */
+export interface ContentBundle {
+  traits?: unknown;
+  internals?: unknown;
+  attack_skills?: unknown;
+  defense_skills?: unknown;
+  enemies?: unknown;
+  storylines?: unknown;
+  adventures?: unknown;
+  quests?: unknown;
+  items?: unknown;
+  recipes?: unknown;
+  battle_config?: BattleConfig;
+  /** 严格模式：拒绝未知字段与不合规的 ID，并检查重复 ID */
+  strict?: boolean;
+}
+
+/** 内容指纹（各集合按 ID 排序后逐条哈希） */
+export interface ContentFingerprint {
+  digest: string;
+  /** 各集合的摘要（键为集合字段名，空集合不记录） */
+  collections: Record<string, string>;
+}
+
+export interface ContentBundleReport extends ValidationReport {
+  /** 是否已替换当前内容（存在错误时保持原内容不变） */
+  loaded: boolean;
+  fingerprint: ContentFingerprint;
 }
@@ -10,8 +10,46 @@ export type StoryHistoryRecord = {
   event_id: string;
   option_id?: string | null;
   battle_win?: boolean | null;
+  // 已存档战斗的 ID（开启战斗存档时记录）
+  battle_id?: string | null;
+  // 奇遇被放弃（未作出选择）
+  skipped?: boolean;
 };
 
+// 战斗存档保留策略
+export interface BattleArchivePolicy {
+  // 最多保留的战斗数，为空表示不限
+  max_battles?: number | null;
+}
+
+// 已存档的战斗
+export interface ArchivedBattle {
+  id: string;
+  scope: "story" | "adventure";
+  event_id: string;
+  enemy_name: string;
+  win: boolean;
+  day: number;
+  result: unknown;
+}
+
+// 存档分段引用
+export interface SegmentRef {
+  len: number;
+}
+
+// 存档格式：json 为可读文本，compact 为紧凑二进制（.wsav）
+export type SaveFormat = "json" | "compact";
+
+// 导入存档时的完整性校验策略：拒绝 / 警告 / 不校验
+export type IntegrityPolicy = "reject" | "warn" | "accept";
+
+export interface ImportedSave {
+  id: string;
+  // 完整性校验警告
+  warnings: string[];
+}
+
 export interface SaveGame {
   id: string;
   name: string;
@@ -22,5 +60,163 @@ export interface SaveGame {
   start_trait_pool?: string[];
   completed_characters: Character[];
   rng_state?: number;
-  story_history?: StoryHistoryRecord[];
+  // 剧情历史单独分段存储，存档主体只记录条目数（旧存档为内联数组）
+  story_history?: SegmentRef | StoryHistoryRecord[];
+  post_battle_recovery?: import("./event").PostBattleRecovery | null;
+  active_quests?: QuestProgress[];
+  completed_quests?: string[];
+  day?: number;
+  // 本地数据统计（未开启时为空）
+  analytics?: SaveAnalytics | null;
+  // 已完成的章节 ID
+  completed_chapters?: string[];
+  // 已战胜、等待战后抉择的战斗事件 ID
+  pending_victory_decision?: string | null;
+  // 奇遇图鉴（遇到过与完成过的奇遇）
+  adventure_discovery?: AdventureDiscovery;
+  // 武学图鉴（首次获得的功法、特性与首次交手的敌人）
+  codex?: Codex;
+  // 战斗存档保留策略（为空表示不存档战斗）
+  battle_archive_policy?: BattleArchivePolicy | null;
+  // 已存档的战斗单独分段存储
+  battle_archive?: SegmentRef | ArchivedBattle[];
+  battles_archived?: number;
+  // 本局生效的挑战规则
+  challenge?: ChallengeRuleset | null;
+  // 难度设置：覆盖剧情线的战败处理策略
+  defeat_policy?: import("./event").DefeatPolicy | null;
+  // 战败重试累计的行动点惩罚（从之后有行动点的节点中扣除）
+  action_point_penalty?: number;
+  /** 创建存档时的内容指纹（旧存档为空） */
+  content_fingerprint?: import("./mod").ContentFingerprint | null;
+  /** 队伍成员与共享资源（单人存档缺省） */
+  party?: Party;
+}
+
+// 队伍：当前操控的角色为 current_character，其余成员在 members 中
+export interface Party {
+  // 主角 ID（为空表示尚未组队）
+  protagonist_id?: string | null;
+  members?: Character[];
+  // 共享资源（资源 ID -> 数量）
+  resources?: Record<string, number>;
+  // 成员修行安排（角色 ID -> 安排）
+  training?: Record<string, TrainingAssignment>;
+}
+
+// 修行安排：每到新的剧情节点，按分配的行动点修行指定功法
+export interface TrainingAssignment {
+  manual_id: string;
+  manual_type: import("./manual").ManualType;
+  action_points?: number;
+}
+
+// 计分倍率（缺省为 1）
+export interface ScoreMultipliers {
+  three_d?: number;
+  manual_levels?: number;
+  martial_arts_attainment?: number;
+}
+
+// 挑战规则：固定种子、禁用内容、难度调整与计分倍率
+export interface ChallengeRuleset {
+  id: string;
+  name: string;
+  seed?: number | null;
+  banned_traits?: string[];
+  banned_manuals?: string[];
+  // 禁用的词条标签
+  disabled_entry_tags?: string[];
+  post_battle_recovery?: import("./event").PostBattleRecovery | null;
+  score_multipliers?: ScoreMultipliers;
+}
+
+export type CodexKind =
+  | "internal"
+  | "attack_skill"
+  | "defense_skill"
+  | "trait"
+  | "enemy";
+
+export interface CodexEntry {
+  kind: CodexKind;
+  // 敌人以名称作为标识
+  id: string;
+  first_seen_at: number;
+  first_seen_day?: number;
+}
+
+export interface Codex {
+  entries?: CodexEntry[];
+}
+
+export interface CodexEntryView {
+  kind: CodexKind;
+  id: string;
+  name: string;
+  first_seen_at: number;
+  first_seen_day: number;
+}
+
+export interface AdventureDiscovery {
+  seen?: string[];
+  completed?: string[];
+}
+
+// 奇遇图鉴视图（未发现的条目不返回名称）
+export interface AdventureCollectionView {
+  discovered: number;
+  total: number;
+  entries: AdventureCollectionEntry[];
+}
+
+export interface AdventureCollectionEntry {
+  id: string;
+  name?: string;
+  discovered: boolean;
+  completed: boolean;
+}
+
+export interface BattleTally {
+  wins: number;
+  losses: number;
+}
+
+export interface NodeTally {
+  visits: number;
+  // 到达时已装备功法等级之和的累计
+  level_sum: number;
+}
+
+export interface SaveAnalytics {
+  // 敌人名称 -> 胜负
+  battles: Record<string, BattleTally>;
+  // 事件 ID -> 选项 ID -> 选择次数
+  option_picks: Record<string, Record<string, number>>;
+  // 剧情事件 ID -> 到达统计
+  story_nodes: Record<string, NodeTally>;
+}
+
+// 数据统计报告
+export interface AnalyticsReport {
+  battles: {
+    enemy_name: string;
+    wins: number;
+    losses: number;
+    win_rate: number;
+  }[];
+  option_picks: Record<string, Record<string, number>>;
+  story_nodes: { event_id: string; visits: number; average_level: number }[];
+}
+
+// 名人堂条目（跨存档的已完成角色）
+export interface RosterEntry {
+  save_id: string;
+  character: Character;
+  score: number;
+}
+
+export interface QuestProgress {
+  quest_id: string;
+  battles_won?: number;
 }
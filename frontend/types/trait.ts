@@ -1,5 +1,7 @@
 /// 特性类型定义
 
+import type { CharacterPanel } from "@/types/character";
+
 export interface Trait {
   id: string;
   name: string;
@@ -14,6 +16,8 @@ export interface Entry {
   condition?: Condition | null;
   effects: Effect[];
   max_triggers?: number | null;
+  // 标签（如 healing、speed），可按标签整体禁用词条
+  tags?: string[];
 }
 
 // 触发时机
@@ -48,7 +52,10 @@ export type AttributeType =
   | "comprehension"
   | "bone_structure"
   | "physique"
-  | "martial_arts_attainment";
+  | "martial_arts_attainment"
+  | "max_hp"
+  | "base_attack"
+  | "base_defense";
 
 // 战斗属性类型
 export type BattleAttributeType =
@@ -68,6 +75,9 @@ export type CultivationCondition =
   | { attack_skill_type_is: string }
   | { defense_skill_is: string }
   | { defense_skill_type_is: string }
+  | { internal_has_tag: string }
+  | { attack_skill_has_tag: string }
+  | { defense_skill_has_tag: string }
   | { has_trait: string }
   | {
       attribute_comparison: {
@@ -130,6 +140,7 @@ export type AttributeTarget =
   | "damage_bonus"
   | "damage_reduction"
   | "max_damage_reduction"
+  | "momentum"
   | "martial_arts_attainment_gain"
   | "cultivation_exp_gain"
   | "qi_gain"
@@ -144,6 +155,19 @@ export type PanelTarget = "own" | "opponent";
 // 公式值（可以是固定值或公式字符串）
 export type FormulaValue = number | string;
 
+// 公式上下文（修行经验公式 / 修行词条 / 战斗词条）
+export type FormulaContextKind = "manual_exp" | "cultivation" | "battle";
+
+// 公式变量的可用时机
+export type FormulaAvailability = "always" | "after_attack" | "declared";
+
+// 公式可用的变量
+export interface FormulaVariable {
+  name: string;
+  description: string;
+  availability: FormulaAvailability;
+}
+
 // 战斗记录模板
 export interface BattleRecordTemplate {
   template: string;
@@ -178,12 +202,80 @@ export interface EffectExtraAttack {
   battle_record_template?: BattleRecordTemplate;
 }
 
+// 武技槽位（封招目标）
+export type SkillSlot = "attack_skill" | "defense_skill";
+
+// 封招：对手指定武技在若干回合内失效
+export interface EffectSealSkill {
+  type: "seal_skill";
+  slot: SkillSlot;
+  rounds: number;
+  battle_record_template?: BattleRecordTemplate;
+}
+
 export type Effect =
   | EffectModifyAttribute
   | EffectModifyPercentage
-  | EffectExtraAttack;
+  | EffectExtraAttack
+  | EffectSealSkill;
 
 export interface TraitListItem {
   id: string;
   name: string;
 }
+
+// 特性预览：有无该特性时的修行经验与交手伤害对比
+export interface TraitPreview {
+  trait_id: string;
+  cultivation: {
+    manual_id: string;
+    manual_type: "internal" | "attack_skill" | "defense_skill";
+    base_exp: number;
+    trait_exp: number;
+  }[];
+  battle: {
+    base_damage_dealt: number;
+    trait_damage_dealt: number;
+    base_damage_taken: number;
+    trait_damage_taken: number;
+  };
+}
+
+// 攻击结果（试算攻击后/防御后的词条时使用）
+export interface AttackResult {
+  total_output?: number;
+  total_defense?: number;
+  reduced_output?: number;
+  hp_damage?: number;
+  attacker_qi_consumed?: number;
+  defender_qi_consumed?: number;
+  broke_qi_defense?: boolean;
+}
+
+// 词条试算请求
+export interface EntryDryRunRequest {
+  entry: Entry;
+  character: CharacterPanel;
+  opponent?: CharacterPanel;
+  attack_result?: AttackResult;
+  environment?: Record<string, number>;
+}
+
+// 单个效果的试算结果
+export interface EffectDryRun {
+  effect_type: Effect["type"];
+  target?: AttributeTarget;
+  target_panel?: PanelTarget;
+  operation?: Operation;
+  value?: number;
+  applied_value?: number;
+  error?: string;
+}
+
+// 词条试算结果
+export interface EntryDryRun {
+  trigger: Trigger;
+  condition_met: boolean;
+  invalid?: string;
+  effects: EffectDryRun[];
+}
@@ -0,0 +1,233 @@
+#!/usr/bin/env python3
+"""根据 rustdoc JSON 生成公开接口快照（由 scripts/public_api.sh 调用）
+
+每行一个可从 crate 外部访问的条目：模块、类型、字段、变体、方法与函数签名、
+非自动实现的 trait 实现。公开签名中引用了外部无法命名的本 crate 类型时报错退出。
+"""
+
+import json
+import sys
+
+CRATE = "wushen_core"
+
+
+def main(path):
+    with open(path, encoding="utf-8") as f:
+        doc = json.load(f)
+    index = doc["index"]
+    item = lambda id_: index.get(str(id_))
+
+    # 第一遍：从根模块出发，收集每个可访问条目的全部公开路径
+    candidates = {}
+    modules = [(doc["root"], CRATE)]
+    seen_modules = set()
+    while modules:
+        module_id, prefix = modules.pop()
+        if module_id in seen_modules:
+            continue
+        seen_modules.add(module_id)
+        candidates.setdefault(module_id, set()).add(prefix)
+        for child_id in item(module_id)["inner"]["module"]["items"]:
+            child = item(child_id)
+            if child is None or child["visibility"] != "public":
+                continue
+            inner = child["inner"]
+            if "use" in inner:
+                use = inner["use"]
+                target = item(use["id"]) if use["id"] is not None else None
+                if target is None:
+                    continue
+                if use["is_glob"]:
+                    modules.append((use["id"], prefix))
+                    continue
+                child_id, name, child = use["id"], use["name"], target
+                inner = child["inner"]
+            else:
+                name = child["name"]
+            child_path = f"{prefix}::{name}"
+            if "module" in inner:
+                modules.append((child_id, child_path))
+            else:
+                candidates.setdefault(child_id, set()).add(child_path)
+
+    # 同一条目有多个路径时取非 prelude 的最短路径，保证快照稳定
+    def path_key(path):
+        segments = path.split("::")
+        return ("prelude" in segments, len(segments), path)
+
+    public_path = {id_: min(paths, key=path_key) for id_, paths in candidates.items()}
+
+    # 第二遍：输出条目，并收集签名中引用到的本 crate 类型
+    lines = set()
+    referenced = {}
+
+    def render_path(p):
+        id_ = p["id"]
+        if id_ in public_path:
+            name = public_path[id_]
+        else:
+            summary = doc["paths"].get(str(id_))
+            if summary and summary["crate_id"] == 0:
+                referenced.setdefault(id_, "::".join(summary["path"]))
+            name = p["path"].split("::")[-1]
+        return name + render_args(p.get("args"))
+
+    def render_args(args):
+        if not args:
+            return ""
+        if "angle_bracketed" in args:
+            parts = []
+            for arg in args["angle_bracketed"]["args"]:
+                if "type" in arg:
+                    parts.append(render_type(arg["type"]))
+                elif "lifetime" in arg:
+                    parts.append(arg["lifetime"])
+                elif "const" in arg:
+                    parts.append(arg["const"]["expr"])
+            for c in args["angle_bracketed"]["constraints"]:
+                binding = c["binding"]
+                if "equality" in binding:
+                    parts.append(f"{c['name']} = {render_type(binding['equality']['type'])}")
+            return f"<{', '.join(parts)}>" if parts else ""
+        if "parenthesized" in args:
+            p = args["parenthesized"]
+            out = f"({', '.join(render_type(t) for t in p['inputs'])})"
+            return out + (f" -> {render_type(p['output'])}" if p["output"] else "")
+        return ""
+
+    def render_bounds(bounds):
+        out = []
+        for b in bounds:
+            if "trait_bound" in b:
+                out.append(render_path(b["trait_bound"]["trait"]))
+            elif "outlives" in b:
+                out.append(b["outlives"])
+        return " + ".join(out)
+
+    def render_type(t):
+        if t is None:
+            return "()"
+        kind, value = next(iter(t.items()))
+        if kind == "resolved_path":
+            return render_path(value)
+        if kind in ("primitive", "generic"):
+            return value
+        if kind == "borrowed_ref":
+            lifetime = f"{value['lifetime']} " if value["lifetime"] else ""
+            mutable = "mut " if value["is_mutable"] else ""
+            return f"&{lifetime}{mutable}{render_type(value['type'])}"
+        if kind == "raw_pointer":
+            return f"*{'mut' if value['is_mutable'] else 'const'} {render_type(value['type'])}"
+        if kind == "slice":
+            return f"[{render_type(value)}]"
+        if kind == "array":
+            return f"[{render_type(value['type'])}; {value['len']}]"
+        if kind == "tuple":
+            return f"({', '.join(render_type(v) for v in value)})"
+        if kind == "impl_trait":
+            return f"impl {render_bounds(value)}"
+        if kind == "dyn_trait":
+            return "dyn " + " + ".join(render_path(t["trait"]) for t in value["traits"])
+        if kind == "qualified_path":
+            return f"<{render_type(value['self_type'])}>::{value['name']}"
+        if kind == "function_pointer":
+            sig = value["sig"]
+            return f"fn({', '.join(render_type(i[1]) for i in sig['inputs'])}) -> {render_type(sig['output'])}"
+        return kind
+
+    def render_fn(name, func):
+        sig = func["sig"]
+        header = func["header"]
+        prefix = ("const " if header["is_const"] else "") + ("async " if header["is_async"] else "")
+        params = [p["name"] for p in func["generics"]["params"] if "type" in p["kind"]]
+        generics = f"<{', '.join(params)}>" if params else ""
+        inputs = []
+        for arg_name, ty in sig["inputs"]:
+            if arg_name == "self" and ty == {"generic": "Self"}:
+                inputs.append("self")
+            elif arg_name == "self" and ty.get("borrowed_ref", {}).get("type") == {"generic": "Self"}:
+                inputs.append(f"&{'mut ' if ty['borrowed_ref']['is_mutable'] else ''}self")
+            else:
+                inputs.append(f"{arg_name}: {render_type(ty)}")
+        output = f" -> {render_type(sig['output'])}" if sig["output"] else ""
+        return f"pub {prefix}fn {name}{generics}({', '.join(inputs)}){output}"
+
+    def emit_impls(owner_path, impl_ids):
+        for impl_id in impl_ids:
+            impl = item(impl_id)["inner"]["impl"]
+            if impl["is_synthetic"] or impl["blanket_impl"] is not None:
+                continue
+            if impl["trait"] is not None:
+                if impl["trait"]["path"] == "StructuralPartialEq":
+                    continue
+                lines.add(f"impl {render_path(impl['trait'])} for {owner_path}")
+                continue
+            for member_id in impl["items"]:
+                member = item(member_id)
+                if member is None or member["visibility"] != "public":
+                    continue
+                inner = member["inner"]
+                if "function" in inner:
+                    lines.add(render_fn(f"{owner_path}::{member['name']}", inner["function"]))
+                elif "assoc_const" in inner:
+                    ty = render_type(inner["assoc_const"]["type"])
+                    lines.add(f"pub const {owner_path}::{member['name']}: {ty}")
+
+    for id_, path in sorted(public_path.items(), key=lambda kv: kv[1]):
+        entry = item(id_)
+        kind, inner = next(iter(entry["inner"].items()))
+        if kind == "module":
+            lines.add(f"pub mod {path}")
+        elif kind == "struct":
+            lines.add(f"pub struct {path}")
+            struct_kind = inner["kind"] if isinstance(inner["kind"], dict) else {}
+            fields = list(struct_kind.get("plain", {}).get("fields", []))
+            fields += [f for f in (struct_kind.get("tuple") or []) if f is not None]
+            for field_id in fields:
+                field = item(field_id)
+                if field["visibility"] == "public":
+                    lines.add(f"pub {path}::{field['name']}: {render_type(field['inner']['struct_field'])}")
+            emit_impls(path, inner["impls"])
+        elif kind == "enum":
+            lines.add(f"pub enum {path}")
+            for variant_id in inner["variants"]:
+                variant = item(variant_id)
+                vkind = variant["inner"]["variant"]["kind"]
+                fields = []
+                if isinstance(vkind, dict) and "struct" in vkind:
+                    fields = [item(f) for f in vkind["struct"]["fields"]]
+                    rendered = ", ".join(f"{f['name']}: {render_type(f['inner']['struct_field'])}" for f in fields)
+                    lines.add(f"{path}::{variant['name']} {{ {rendered} }}")
+                elif isinstance(vkind, dict) and "tuple" in vkind:
+                    rendered = ", ".join(render_type(item(f)["inner"]["struct_field"]) for f in vkind["tuple"] if f is not None)
+                    lines.add(f"{path}::{variant['name']}({rendered})")
+                else:
+                    lines.add(f"{path}::{variant['name']}")
+            emit_impls(path, inner["impls"])
+        elif kind == "trait":
+            lines.add(f"pub trait {path}")
+            for member_id in inner["items"]:
+                member = item(member_id)
+                if "function" in member["inner"]:
+                    lines.add(render_fn(f"{path}::{member['name']}", member["inner"]["function"]))
+        elif kind == "function":
+            lines.add(render_fn(path, inner))
+        elif kind == "constant":
+            lines.add(f"pub const {path}: {render_type(inner['type'])}")
+        elif kind == "static":
+            lines.add(f"pub static {path}: {render_type(inner['type'])}")
+        elif kind == "type_alias":
+            lines.add(f"pub type {path} = {render_type(inner['type'])}")
+        elif kind == "macro":
+            lines.add(f"macro {path}!")
+
+    if referenced:
+        for path in sorted(referenced.values()):
+            print(f"公开接口引用了外部无法命名的类型：{path}", file=sys.stderr)
+        sys.exit(1)
+    for line in sorted(lines):
+        print(line)
+
+
+if __name__ == "__main__":
+    main(sys.argv[1])
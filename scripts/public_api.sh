@@ -0,0 +1,21 @@
+#!/usr/bin/env bash
+# 生成公开接口快照 tests/public-api.txt；带 --check 时只比对，不一致则失败
+# 需要 nightly 工具链（rustdoc JSON 输出仍为不稳定特性）
+set -euo pipefail
+
+ROOT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
+SNAPSHOT="$ROOT_DIR/tests/public-api.txt"
+
+cd "$ROOT_DIR"
+
+cargo +nightly rustdoc --lib --features server,god-mode -- \
+  -Z unstable-options --output-format json
+
+if [[ "${1:-}" == "--check" ]]; then
+  if ! python3 scripts/public_api.py target/doc/wushen_core.json | diff -u "$SNAPSHOT" -; then
+    echo "error: public API changed. Run scripts/public_api.sh and commit tests/public-api.txt."
+    exit 1
+  fi
+else
+  python3 scripts/public_api.py target/doc/wushen_core.json >"$SNAPSHOT"
+fi
@@ -9,9 +9,15 @@ use tauri::{AppHandle, Manager};
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
 use ulid::Ulid;
+use wushen_core::event::EnemyTemplate;
+use wushen_core::game::{
+    convert_save, decode_save_value, encode_save_value, AdventureDiscovery, IntegrityPolicy,
+    PackHash, Roster, RosterEntry, SaveEnvelope, SaveFormat, SaveGame, SaveSegments, SegmentKind,
+};
+use wushen_core::pack::{resolve_load_order, PackDependency, PackManifest, SCHEMA_VERSION_KEY};
 use zip::write::FileOptions;
 
-const PACK_FILES: [(&str, &str); 7] = [
+const PACK_FILES: [(&str, &str); 10] = [
     ("traits.json", "traits"),
     ("internals.json", "internals"),
     ("attack_skills.json", "attack_skills"),
@@ -19,6 +25,9 @@ const PACK_FILES: [(&str, &str); 7] = [
     ("enemies.json", "enemies"),
     ("adventures.json", "adventures"),
     ("storylines.json", "storylines"),
+    ("quests.json", "quests"),
+    ("items.json", "items"),
+    ("recipes.json", "recipes"),
 ];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,16 +39,8 @@ pub struct PackMetadata {
     pub description: Option<String>,
     pub created_at: String,
     pub updated_at: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct PackManifest {
-    pub id: String,
-    pub name: String,
-    pub version: String,
-    pub author: Option<String>,
-    pub description: Option<String>,
-    pub files: Option<Vec<String>>,
+    #[serde(default)]
+    pub dependencies: Vec<PackDependency>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -70,6 +71,14 @@ fn pack_order_path(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(data_root(app)?.join("pack-order.json"))
 }
 
+fn roster_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(data_root(app)?.join("roster.json"))
+}
+
+fn discovery_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(data_root(app)?.join("adventure-discovery.json"))
+}
+
 fn pack_dir(app: &AppHandle, pack_id: &str) -> Result<PathBuf, String> {
     let dir = data_root(app)?.join("packs").join(pack_id);
     fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
@@ -172,6 +181,10 @@ fn pack_metadata_from_disk(pack_id: &str, dir: &Path) -> Result<Option<PackMetad
         .unwrap_or_else(|| "0.1.0".to_string());
     let author = manifest.as_ref().and_then(|m| m.author.clone());
     let description = manifest.as_ref().and_then(|m| m.description.clone());
+    let dependencies = manifest
+        .as_ref()
+        .map(|m| m.dependencies.clone())
+        .unwrap_or_default();
 
     let created_at = pack_dir_timestamp(dir)
         .and_then(timestamp_to_iso)
@@ -187,6 +200,7 @@ fn pack_metadata_from_disk(pack_id: &str, dir: &Path) -> Result<Option<PackMetad
         description,
         created_at,
         updated_at,
+        dependencies,
     }))
 }
 
@@ -248,6 +262,10 @@ fn read_packs(app: &AppHandle) -> Result<Vec<PackMetadata>, String> {
                     existing.description = disk_pack.description.clone();
                     changed = true;
                 }
+                if existing.dependencies != disk_pack.dependencies {
+                    existing.dependencies = disk_pack.dependencies.clone();
+                    changed = true;
+                }
                 if existing.created_at.trim().is_empty() {
                     existing.created_at = disk_pack.created_at.clone();
                     changed = true;
@@ -303,6 +321,7 @@ fn write_manifest(app: &AppHandle, pack: &PackMetadata, files: &[String]) -> Res
         author: pack.author.clone(),
         description: pack.description.clone(),
         files: Some(files.to_vec()),
+        dependencies: pack.dependencies.clone(),
     };
     let toml = toml::to_string(&manifest).map_err(|e| e.to_string())?;
     let dir = pack_dir(app, &pack.id)?;
@@ -326,7 +345,17 @@ fn read_collection(
     }
     if let Some(obj) = value.as_object() {
         if let Some(items) = obj.get(key).and_then(|v| v.as_array()) {
-            return Ok(items.clone());
+            // 集合级版本号下发到条目上，合并多个包后仍可按条目升级
+            let mut items = items.clone();
+            if let Some(version) = obj.get(SCHEMA_VERSION_KEY) {
+                for item in items.iter_mut() {
+                    if let Some(item) = item.as_object_mut() {
+                        item.entry(SCHEMA_VERSION_KEY)
+                            .or_insert_with(|| version.clone());
+                    }
+                }
+            }
+            return Ok(items);
         }
     }
     Ok(vec![])
@@ -373,6 +402,88 @@ fn save_dir(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(dir)
 }
 
+const SAVE_FORMATS: [SaveFormat; 2] = [SaveFormat::Json, SaveFormat::Compact];
+
+fn is_save_file(path: &Path) -> bool {
+    let ext = path.extension().and_then(|v| v.to_str());
+    SAVE_FORMATS
+        .iter()
+        .any(|format| ext == Some(format.extension()))
+}
+
+/// 查找已有存档文件及其格式
+fn find_save_file(dir: &Path, id: &str) -> Option<(PathBuf, SaveFormat)> {
+    SAVE_FORMATS.iter().find_map(|format| {
+        let path = dir.join(format!("{}.{}", id, format.extension()));
+        path.exists().then_some((path, *format))
+    })
+}
+
+fn read_save_file(path: &Path) -> Result<Value, String> {
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    Ok(decode_save_value(&bytes)?)
+}
+
+/// 按格式写入存档，并移除其他格式的同名存档
+fn write_save_file(dir: &Path, id: &str, bytes: &[u8], format: SaveFormat) -> Result<(), String> {
+    fs::write(dir.join(format!("{}.{}", id, format.extension())), bytes)
+        .map_err(|e| e.to_string())?;
+    for other in SAVE_FORMATS.iter().filter(|other| **other != format) {
+        let path = dir.join(format!("{}.{}", id, other.extension()));
+        if path.exists() {
+            fs::remove_file(path).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// 存档分段目录：saves/segments/{id}/{kind}.json
+fn segment_dir(app: &AppHandle, id: &str) -> Result<PathBuf, String> {
+    Ok(save_dir(app)?.join("segments").join(id))
+}
+
+/// 写入发生变化的存档分段
+pub fn write_save_segments(
+    app: &AppHandle,
+    id: &str,
+    segments: &SaveSegments,
+) -> Result<(), String> {
+    if segments.is_empty() {
+        return Ok(());
+    }
+    let dir = segment_dir(app, id)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let value = serde_json::to_value(segments).map_err(|e| e.to_string())?;
+    if let Value::Object(map) = value {
+        for (kind, records) in map {
+            write_json(&dir.join(format!("{}.json", kind)), &records)?;
+        }
+    }
+    Ok(())
+}
+
+/// 读取存档的全部分段（缺失的分段不返回）
+pub fn read_save_segments(app: &AppHandle, id: &str) -> Result<SaveSegments, String> {
+    let dir = segment_dir(app, id)?;
+    let mut map = serde_json::Map::new();
+    for kind in SegmentKind::ALL {
+        let path = dir.join(format!("{}.json", kind.as_str()));
+        if let Some(records) = read_json::<Value>(&path)? {
+            map.insert(kind.as_str().to_string(), records);
+        }
+    }
+    serde_json::from_value(Value::Object(map)).map_err(|e| e.to_string())
+}
+
+/// 将分段数据内联回存档（导出用；导入后首次保存时重新拆分）
+fn inline_save_segments(app: &AppHandle, id: &str, save: &mut Value) -> Result<(), String> {
+    let segments = serde_json::to_value(read_save_segments(app, id)?).map_err(|e| e.to_string())?;
+    if let (Value::Object(save), Value::Object(segments)) = (save, segments) {
+        save.extend(segments);
+    }
+    Ok(())
+}
+
 fn pick_string(value: &Value, key: &str) -> Option<String> {
     value
         .get(key)
@@ -588,6 +699,7 @@ pub fn create_pack(
         description: description.filter(|v| !v.trim().is_empty()),
         created_at: now.clone(),
         updated_at: Some(now),
+        dependencies: Vec::new(),
     };
 
     let mut packs = read_packs(&app)?;
@@ -633,7 +745,7 @@ pub fn get_pack_order(app: AppHandle) -> Result<Vec<String>, String> {
 #[tauri::command]
 pub fn set_pack_order(app: AppHandle, order: Vec<String>) -> Result<Vec<String>, String> {
     let packs = read_packs(&app)?;
-    let pack_ids: Vec<String> = packs.into_iter().map(|p| p.id).collect();
+    let pack_ids: Vec<String> = packs.iter().map(|p| p.id.clone()).collect();
     let mut next = Vec::new();
     for id in order {
         if pack_ids.contains(&id) && !next.contains(&id) {
@@ -645,10 +757,31 @@ pub fn set_pack_order(app: AppHandle, order: Vec<String>) -> Result<Vec<String>,
             next.push(id);
         }
     }
+    let next = resolve_pack_priority(&packs, &next)?;
     write_pack_order(&app, &next)?;
     Ok(next)
 }
 
+/// 按依赖关系调整模组优先级顺序（被依赖的包优先级更低，先加载后被覆盖）
+fn resolve_pack_priority(packs: &[PackMetadata], order: &[String]) -> Result<Vec<String>, String> {
+    let manifests: Vec<PackManifest> = packs
+        .iter()
+        .map(|pack| PackManifest {
+            id: pack.id.clone(),
+            name: pack.name.clone(),
+            version: pack.version.clone(),
+            author: pack.author.clone(),
+            description: pack.description.clone(),
+            files: None,
+            dependencies: pack.dependencies.clone(),
+        })
+        .collect();
+    let load_order: Vec<String> = order.iter().rev().cloned().collect();
+    let mut resolved = resolve_load_order(&manifests, &load_order)?;
+    resolved.reverse();
+    Ok(resolved)
+}
+
 #[tauri::command]
 pub fn export_pack_zip(app: AppHandle, pack_id: String, dest_path: String) -> Result<(), String> {
     let pack = read_packs(&app)?
@@ -672,6 +805,7 @@ pub fn export_pack_zip(app: AppHandle, pack_id: String, dest_path: String) -> Re
             author: pack.author.clone(),
             description: pack.description.clone(),
             files: None,
+            dependencies: pack.dependencies.clone(),
         })
     } else {
         PackManifest {
@@ -681,6 +815,7 @@ pub fn export_pack_zip(app: AppHandle, pack_id: String, dest_path: String) -> Re
             author: pack.author.clone(),
             description: pack.description.clone(),
             files: None,
+            dependencies: pack.dependencies.clone(),
         }
     };
 
@@ -768,6 +903,7 @@ pub fn import_pack_zip(app: AppHandle, zip_path: String) -> Result<PackMetadata,
         existing.version = manifest.version.clone();
         existing.author = manifest.author.clone();
         existing.description = manifest.description.clone();
+        existing.dependencies = manifest.dependencies.clone();
         existing.updated_at = Some(now.clone());
         existing.clone()
     } else {
@@ -779,6 +915,7 @@ pub fn import_pack_zip(app: AppHandle, zip_path: String) -> Result<PackMetadata,
             description: manifest.description.clone(),
             created_at: now.clone(),
             updated_at: Some(now),
+            dependencies: manifest.dependencies.clone(),
         };
         packs.push(pack.clone());
         pack
@@ -871,6 +1008,14 @@ define_entity_commands!(
     "storylines.json",
     "storylines"
 );
+define_entity_commands!(
+    list_quests,
+    get_quest,
+    save_quest,
+    delete_quest,
+    "quests.json",
+    "quests"
+);
 
 fn strip_enemy_id(mut enemy: Value) -> Value {
     if let Some(obj) = enemy.as_object_mut() {
@@ -1025,25 +1170,23 @@ pub fn list_saves(app: AppHandle) -> Result<Vec<NamedItem>, String> {
     for entry in fs::read_dir(&dir).map_err(|e| e.to_string())? {
         let entry = entry.map_err(|e| e.to_string())?;
         let path = entry.path();
-        if path.extension().and_then(|v| v.to_str()) == Some("json") {
-            if let Ok(content) = fs::read_to_string(&path) {
-                if let Ok(value) = serde_json::from_str::<Value>(&content) {
-                    let fallback_id = path.file_stem().and_then(|v| v.to_str()).unwrap_or("save");
-                    let id = pick_string(&value, "id")
-                        .or_else(|| pick_current_character_string(&value, "id"))
-                        .unwrap_or_else(|| fallback_id.to_string());
-                    let name = pick_string(&value, "name")
-                        .or_else(|| pick_current_character_string(&value, "name"))
-                        .unwrap_or_else(|| id.clone());
-                    let created_at = pick_u64(&value, "created_at")
-                        .filter(|value| *value > 0)
-                        .or_else(|| file_timestamp(&path));
-                    result.push(NamedItem {
-                        id,
-                        name,
-                        created_at,
-                    });
-                }
+        if is_save_file(&path) {
+            if let Ok(value) = read_save_file(&path) {
+                let fallback_id = path.file_stem().and_then(|v| v.to_str()).unwrap_or("save");
+                let id = pick_string(&value, "id")
+                    .or_else(|| pick_current_character_string(&value, "id"))
+                    .unwrap_or_else(|| fallback_id.to_string());
+                let name = pick_string(&value, "name")
+                    .or_else(|| pick_current_character_string(&value, "name"))
+                    .unwrap_or_else(|| id.clone());
+                let created_at = pick_u64(&value, "created_at")
+                    .filter(|value| *value > 0)
+                    .or_else(|| file_timestamp(&path));
+                result.push(NamedItem {
+                    id,
+                    name,
+                    created_at,
+                });
             }
         }
     }
@@ -1054,12 +1197,10 @@ pub fn list_saves(app: AppHandle) -> Result<Vec<NamedItem>, String> {
 #[tauri::command]
 pub fn load_save(app: AppHandle, id: String) -> Result<Option<Value>, String> {
     let dir = save_dir(&app)?;
-    let path = dir.join(format!("{}.json", id));
-    if !path.exists() {
+    let Some((path, _)) = find_save_file(&dir, &id) else {
         return Ok(None);
-    }
-    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
-    let value = serde_json::from_str::<Value>(&content).map_err(|e| e.to_string())?;
+    };
+    let value = read_save_file(&path)?;
     let binding = value.clone();
     let fallback_id = binding
         .get("id")
@@ -1070,29 +1211,190 @@ pub fn load_save(app: AppHandle, id: String) -> Result<Option<Value>, String> {
     Ok(Some(normalize_save_value(value, fallback_id)))
 }
 
+/// 保存存档；未指定格式时沿用已有存档的格式（新存档默认 JSON）
 #[tauri::command]
-pub fn save_game(app: AppHandle, payload: Value) -> Result<String, String> {
+pub fn save_game(
+    app: AppHandle,
+    payload: Value,
+    format: Option<SaveFormat>,
+) -> Result<String, String> {
     let mut payload = payload;
     let id = ensure_save_id(&mut payload)?;
     let payload = normalize_save_value(payload, &id);
     let dir = save_dir(&app)?;
-    let path = dir.join(format!("{}.json", id));
-    let content = serde_json::to_string_pretty(&payload).map_err(|e| e.to_string())?;
-    fs::write(path, content).map_err(|e| e.to_string())?;
+    let format = format
+        .or_else(|| find_save_file(&dir, &id).map(|(_, format)| format))
+        .unwrap_or_default();
+    let bytes = encode_save_value(&payload, format)?;
+    write_save_file(&dir, &id, &bytes, format)?;
     Ok(id)
 }
 
 #[tauri::command]
 pub fn save_character(app: AppHandle, payload: Value) -> Result<String, String> {
-    save_game(app, payload)
+    save_game(app, payload, None)
+}
+
+/// 转换已有存档的格式
+#[tauri::command]
+pub fn convert_save_format(app: AppHandle, id: String, format: SaveFormat) -> Result<(), String> {
+    let dir = save_dir(&app)?;
+    let (path, current) = find_save_file(&dir, &id).ok_or_else(|| "存档不存在".to_string())?;
+    if current == format {
+        return Ok(());
+    }
+    let bytes = fs::read(&path).map_err(|e| e.to_string())?;
+    let converted = convert_save(&bytes, format)?;
+    write_save_file(&dir, &id, &converted, format)
 }
 
 #[tauri::command]
 pub fn delete_save(app: AppHandle, id: String) -> Result<(), String> {
     let dir = save_dir(&app)?;
-    let path = dir.join(format!("{}.json", id));
-    if path.exists() {
+    while let Some((path, _)) = find_save_file(&dir, &id) {
         fs::remove_file(path).map_err(|e| e.to_string())?;
     }
+    let segments = segment_dir(&app, &id)?;
+    if segments.exists() {
+        fs::remove_dir_all(segments).map_err(|e| e.to_string())?;
+    }
     Ok(())
 }
+
+/// 已安装模组包的内容哈希
+fn installed_pack_hashes(app: &AppHandle) -> Result<Vec<PackHash>, String> {
+    let mut hashes = Vec::new();
+    for pack in read_packs(app)? {
+        let dir = pack_dir(app, &pack.id)?;
+        let files: Vec<Vec<u8>> = PACK_FILES
+            .iter()
+            .map(|(file, _)| fs::read(dir.join(file)).unwrap_or_default())
+            .collect();
+        hashes.push(PackHash::from_files(
+            &pack.id,
+            files.iter().map(|file| file.as_slice()),
+        ));
+    }
+    Ok(hashes)
+}
+
+/// 导出存档（附带完整性信封）
+#[tauri::command]
+pub fn export_save(app: AppHandle, id: String, dest_path: String) -> Result<(), String> {
+    let dir = save_dir(&app)?;
+    let (path, format) = find_save_file(&dir, &id).ok_or_else(|| "存档不存在".to_string())?;
+    let mut save = read_save_file(&path)?;
+    inline_save_segments(&app, &id, &mut save)?;
+    let envelope = SaveEnvelope::seal(save, installed_pack_hashes(&app)?);
+    let value = serde_json::to_value(&envelope).map_err(|e| e.to_string())?;
+    let bytes = encode_save_value(&value, format)?;
+    fs::write(dest_path, bytes).map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedSave {
+    pub id: String,
+    /// 完整性校验警告
+    pub warnings: Vec<String>,
+}
+
+/// 导入存档，按策略校验完整性（默认警告）
+#[tauri::command]
+pub fn import_save(
+    app: AppHandle,
+    src_path: String,
+    policy: Option<IntegrityPolicy>,
+) -> Result<ImportedSave, String> {
+    let policy = policy.unwrap_or_default();
+    let bytes = fs::read(&src_path).map_err(|e| e.to_string())?;
+    let value = decode_save_value(&bytes)?;
+    let (save, warnings) = match SaveEnvelope::from_value(&value) {
+        Some(envelope) => envelope.open(policy, &installed_pack_hashes(&app)?)?,
+        None => match policy {
+            IntegrityPolicy::Reject => return Err("存档缺少完整性信息".to_string()),
+            IntegrityPolicy::Warn => (value, vec!["存档缺少完整性信息，未经校验".to_string()]),
+            IntegrityPolicy::Accept => (value, Vec::new()),
+        },
+    };
+    let id = save_game(app, save, None)?;
+    Ok(ImportedSave { id, warnings })
+}
+
+fn read_roster(app: &AppHandle) -> Result<Roster, String> {
+    let path = roster_path(app)?;
+    if !path.exists() {
+        return Ok(Roster::default());
+    }
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    Ok(Roster::from_json(&content)?)
+}
+
+fn write_roster(app: &AppHandle, roster: &Roster) -> Result<(), String> {
+    fs::write(roster_path(app)?, roster.to_json()?).map_err(|e| e.to_string())
+}
+
+/// 将存档中的已完成角色收录进名人堂
+pub fn record_roster_save(app: &AppHandle, save: &SaveGame) -> Result<(), String> {
+    let mut roster = read_roster(app)?;
+    if roster.record_save(save) > 0 {
+        write_roster(app, &roster)?;
+    }
+    Ok(())
+}
+
+/// 读取跨存档合并的奇遇图鉴
+pub fn read_global_discovery(app: &AppHandle) -> Result<AdventureDiscovery, String> {
+    let path = discovery_path(app)?;
+    if !path.exists() {
+        return Ok(AdventureDiscovery::default());
+    }
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    Ok(AdventureDiscovery::from_json(&content)?)
+}
+
+/// 将存档中的奇遇发现记录合并进全局图鉴
+pub fn record_global_discovery(app: &AppHandle, save: &SaveGame) -> Result<(), String> {
+    let mut discovery = read_global_discovery(app)?;
+    if discovery.merge(&save.adventure_discovery) {
+        fs::write(discovery_path(app)?, discovery.to_json()?).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_roster(app: AppHandle, limit: Option<usize>) -> Result<Vec<RosterEntry>, String> {
+    let roster = read_roster(&app)?;
+    let limit = limit.unwrap_or(roster.entries.len());
+    Ok(roster.top(limit).to_vec())
+}
+
+#[tauri::command]
+pub fn roster_opponents(
+    app: AppHandle,
+    limit: usize,
+    exclude_save_id: Option<String>,
+) -> Result<Vec<EnemyTemplate>, String> {
+    let roster = read_roster(&app)?;
+    Ok(roster.opponents(limit, exclude_save_id.as_deref()))
+}
+
+/// 重新扫描所有存档，重建名人堂
+#[tauri::command]
+pub fn rebuild_roster(app: AppHandle) -> Result<usize, String> {
+    let dir = save_dir(&app)?;
+    let mut roster = Roster::default();
+    for entry in fs::read_dir(&dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if !is_save_file(&path) {
+            continue;
+        }
+        let Ok(value) = read_save_file(&path) else {
+            continue;
+        };
+        if let Ok(save) = serde_json::from_value::<SaveGame>(value) {
+            roster.record_save(&save);
+        }
+    }
+    write_roster(&app, &roster)?;
+    Ok(roster.entries.len())
+}
@@ -1,145 +1,224 @@
 use std::sync::Mutex;
 
 use serde_json::Value;
-use tauri::{AppHandle, State};
-use wushen_core::game::{NewGameRequest, SaveGame};
-use wushen_core::tauri_api::WushenCore;
+use tauri::{AppHandle, Emitter, State};
+use wushen_core::game::{
+    BattleArchivePolicy, NewGameRequest, SaveGame, ThreeDimensionalState, TrainingAssignment,
+};
+use wushen_core::pack::LoadWarning;
+use wushen_core::prelude::CancellationToken;
+use wushen_core::tauri_api::{ContentBundle, ContentBundleReport, EnemyBattleOptions, WushenCore};
+use wushen_core::WushenError;
 
 use crate::commands::read_pack_collection;
 
 pub struct CoreState {
     core: Mutex<WushenCore>,
+    /// 当前模拟的取消令牌（独立于核心锁，模拟进行中也可取消）
+    simulation_cancel: Mutex<CancellationToken>,
 }
 
 impl Default for CoreState {
     fn default() -> Self {
         Self {
             core: Mutex::new(WushenCore::new()),
+            simulation_cancel: Mutex::new(CancellationToken::new()),
         }
     }
 }
 
 fn lock_core<'a>(
     state: &'a State<'a, CoreState>,
-) -> Result<std::sync::MutexGuard<'a, WushenCore>, String> {
+) -> Result<std::sync::MutexGuard<'a, WushenCore>, WushenError> {
     state
         .core
         .lock()
-        .map_err(|_| "核心状态被占用，请重试".to_string())
+        .map_err(|_| WushenError::InvalidState("核心状态被占用，请重试".to_string()))
+}
+
+/// 获取只读快照：仅短暂持锁，随后的查询与计算不阻塞游戏会话
+fn snapshot_core(state: &State<CoreState>) -> Result<WushenCore, WushenError> {
+    Ok(lock_core(state)?.snapshot())
 }
 
 #[tauri::command]
-pub fn core_reset(state: State<CoreState>) -> Result<(), String> {
+pub fn core_reset(state: State<CoreState>) -> Result<(), WushenError> {
     let mut core = lock_core(&state)?;
     core.reset();
     Ok(())
 }
 
 #[tauri::command]
-pub fn core_load_traits(state: State<CoreState>, json: String) -> Result<(), String> {
+pub fn core_load_traits(
+    state: State<CoreState>,
+    json: String,
+) -> Result<Vec<LoadWarning>, WushenError> {
     let mut core = lock_core(&state)?;
     core.load_traits(&json)
 }
 
 #[tauri::command]
-pub fn core_load_internals(state: State<CoreState>, json: String) -> Result<(), String> {
+pub fn core_load_content_bundle(
+    state: State<CoreState>,
+    bundle: ContentBundle,
+) -> Result<ContentBundleReport, WushenError> {
+    let mut core = lock_core(&state)?;
+    Ok(core.load_content_bundle(bundle))
+}
+
+#[tauri::command]
+pub fn core_load_internals(
+    state: State<CoreState>,
+    json: String,
+) -> Result<Vec<LoadWarning>, WushenError> {
     let mut core = lock_core(&state)?;
     core.load_internals(&json)
 }
 
 #[tauri::command]
-pub fn core_load_attack_skills(state: State<CoreState>, json: String) -> Result<(), String> {
+pub fn core_load_attack_skills(
+    state: State<CoreState>,
+    json: String,
+) -> Result<Vec<LoadWarning>, WushenError> {
     let mut core = lock_core(&state)?;
     core.load_attack_skills(&json)
 }
 
 #[tauri::command]
-pub fn core_load_defense_skills(state: State<CoreState>, json: String) -> Result<(), String> {
+pub fn core_load_defense_skills(
+    state: State<CoreState>,
+    json: String,
+) -> Result<Vec<LoadWarning>, WushenError> {
     let mut core = lock_core(&state)?;
     core.load_defense_skills(&json)
 }
 
 #[tauri::command]
-pub fn core_load_storylines(state: State<CoreState>, json: String) -> Result<(), String> {
+pub fn core_load_storylines(
+    state: State<CoreState>,
+    json: String,
+) -> Result<Vec<LoadWarning>, WushenError> {
     let mut core = lock_core(&state)?;
     core.load_storylines(&json)
 }
 
 #[tauri::command]
-pub fn core_load_adventure_events(state: State<CoreState>, json: String) -> Result<(), String> {
+pub fn core_load_adventure_events(
+    state: State<CoreState>,
+    json: String,
+) -> Result<Vec<LoadWarning>, WushenError> {
     let mut core = lock_core(&state)?;
     core.load_adventure_events(&json)
 }
 
 #[tauri::command]
-pub fn core_get_trait(state: State<CoreState>, id: String) -> Result<String, String> {
-    let core = lock_core(&state)?;
+pub fn core_get_trait(state: State<CoreState>, id: String) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
     core.get_trait(&id)
 }
 
 #[tauri::command]
-pub fn core_list_traits(state: State<CoreState>) -> Result<String, String> {
-    let core = lock_core(&state)?;
+pub fn core_list_traits(state: State<CoreState>) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
     core.list_traits()
 }
 
 #[tauri::command]
-pub fn core_get_internal(state: State<CoreState>, id: String) -> Result<String, String> {
-    let core = lock_core(&state)?;
+pub fn core_get_internal(state: State<CoreState>, id: String) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
     core.get_internal(&id)
 }
 
 #[tauri::command]
-pub fn core_list_internals(state: State<CoreState>) -> Result<String, String> {
-    let core = lock_core(&state)?;
+pub fn core_list_internals(state: State<CoreState>) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
     core.list_internals()
 }
 
 #[tauri::command]
-pub fn core_get_attack_skill(state: State<CoreState>, id: String) -> Result<String, String> {
-    let core = lock_core(&state)?;
+pub fn core_list_internals_filtered(
+    state: State<CoreState>,
+    tag: Option<String>,
+    rarity: Option<u32>,
+) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
+    core.list_internals_filtered(tag.as_deref(), rarity)
+}
+
+#[tauri::command]
+pub fn core_get_attack_skill(state: State<CoreState>, id: String) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
     core.get_attack_skill(&id)
 }
 
 #[tauri::command]
-pub fn core_list_attack_skills(state: State<CoreState>) -> Result<String, String> {
-    let core = lock_core(&state)?;
+pub fn core_list_attack_skills(state: State<CoreState>) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
     core.list_attack_skills()
 }
 
 #[tauri::command]
-pub fn core_get_defense_skill(state: State<CoreState>, id: String) -> Result<String, String> {
-    let core = lock_core(&state)?;
+pub fn core_list_attack_skills_filtered(
+    state: State<CoreState>,
+    tag: Option<String>,
+    rarity: Option<u32>,
+) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
+    core.list_attack_skills_filtered(tag.as_deref(), rarity)
+}
+
+#[tauri::command]
+pub fn core_get_defense_skill(state: State<CoreState>, id: String) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
     core.get_defense_skill(&id)
 }
 
 #[tauri::command]
-pub fn core_list_defense_skills(state: State<CoreState>) -> Result<String, String> {
-    let core = lock_core(&state)?;
+pub fn core_list_defense_skills(state: State<CoreState>) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
     core.list_defense_skills()
 }
 
 #[tauri::command]
-pub fn core_list_storylines(state: State<CoreState>) -> Result<String, String> {
-    let core = lock_core(&state)?;
+pub fn core_list_defense_skills_filtered(
+    state: State<CoreState>,
+    tag: Option<String>,
+    rarity: Option<u32>,
+) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
+    core.list_defense_skills_filtered(tag.as_deref(), rarity)
+}
+
+#[tauri::command]
+pub fn core_list_storylines(state: State<CoreState>) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
     core.list_storylines()
 }
 
 #[tauri::command]
-pub fn core_get_storyline(state: State<CoreState>, id: String) -> Result<String, String> {
-    let core = lock_core(&state)?;
+pub fn core_list_storyline_stats(state: State<CoreState>) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
+    core.list_storyline_stats()
+}
+
+#[tauri::command]
+pub fn core_get_storyline(state: State<CoreState>, id: String) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
     core.get_storyline(&id)
 }
 
 #[tauri::command]
-pub fn core_list_adventure_events(state: State<CoreState>) -> Result<String, String> {
-    let core = lock_core(&state)?;
+pub fn core_list_adventure_events(state: State<CoreState>) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
     core.list_adventure_events()
 }
 
 #[tauri::command]
-pub fn core_get_adventure_event(state: State<CoreState>, id: String) -> Result<String, String> {
-    let core = lock_core(&state)?;
+pub fn core_get_adventure_event(
+    state: State<CoreState>,
+    id: String,
+) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
     core.get_adventure_event(&id)
 }
 
@@ -152,20 +231,30 @@ pub fn core_calculate_cultivation_exp(
     y: f64,
     z: f64,
     a: f64,
-) -> Result<f64, String> {
+) -> Result<f64, WushenError> {
     let core = lock_core(&state)?;
     core.calculate_cultivation_exp(&manual_id, &manual_type, x, y, z, a)
 }
 
+/// 列出公式可用的变量
 #[tauri::command]
+pub fn core_formula_variable_catalog(
+    state: State<CoreState>,
+    kind: String,
+) -> Result<String, WushenError> {
+    let core = lock_core(&state)?;
+    core.formula_variable_catalog(&kind)
+}
+
+#[tauri::command(async)]
 pub fn core_calculate_battle(
     state: State<CoreState>,
     attacker_json: String,
     defender_json: String,
     attacker_qi_output_rate: Option<f64>,
     defender_qi_output_rate: Option<f64>,
-) -> Result<String, String> {
-    let core = lock_core(&state)?;
+) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
     core.calculate_battle(
         &attacker_json,
         &defender_json,
@@ -174,24 +263,330 @@ pub fn core_calculate_battle(
     )
 }
 
+/// 记录战斗回放（不执行战斗），返回可分享的回放JSON
+#[tauri::command]
+pub fn core_capture_battle_replay(
+    state: State<CoreState>,
+    attacker_json: String,
+    defender_json: String,
+    attacker_qi_output_rate: Option<f64>,
+    defender_qi_output_rate: Option<f64>,
+) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
+    core.capture_battle_replay(
+        &attacker_json,
+        &defender_json,
+        attacker_qi_output_rate,
+        defender_qi_output_rate,
+    )
+}
+
+/// 按回放重新执行战斗
+#[tauri::command]
+pub fn core_replay_battle(
+    state: State<CoreState>,
+    replay_json: String,
+) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
+    core.replay_battle(&replay_json)
+}
+
+/// 以角色对战敌人模板（编辑器试战）
+#[tauri::command(async)]
+pub fn core_test_battle_against_enemy(
+    state: State<CoreState>,
+    character_json: String,
+    enemy: String,
+    options: Option<EnemyBattleOptions>,
+) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
+    core.test_battle_against_enemy(&character_json, &enemy, options.unwrap_or_default())
+}
+
+/// 推演不同三维分配下的修行曲线
+#[tauri::command(async)]
+pub fn core_simulate_cultivation_sweep(
+    state: State<CoreState>,
+    request_json: String,
+) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
+    core.simulate_cultivation_sweep(&request_json)
+}
+
+/// 预览特性对修行与战斗的影响
+#[tauri::command(async)]
+pub fn core_preview_trait(
+    state: State<CoreState>,
+    trait_json: String,
+    character_json: String,
+    dummy_json: Option<String>,
+) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
+    core.preview_trait(&trait_json, &character_json, dummy_json.as_deref())
+}
+
+/// 试算单个词条（条件是否满足与各效果的数值）
+#[tauri::command(async)]
+pub fn core_dry_run_entry(
+    state: State<CoreState>,
+    request_json: String,
+) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
+    core.dry_run_entry(&request_json)
+}
+
+/// 预估双方一次交手的结算结果
+#[tauri::command(async)]
+pub fn core_preview_exchange(
+    state: State<CoreState>,
+    attacker_json: String,
+    defender_json: String,
+    attacker_qi_output_rate: Option<f64>,
+    defender_qi_output_rate: Option<f64>,
+) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
+    core.preview_exchange(
+        &attacker_json,
+        &defender_json,
+        attacker_qi_output_rate,
+        defender_qi_output_rate,
+    )
+}
+
+/// 执行剧情场景测试（在独立会话中运行，不影响当前游戏）
+#[tauri::command(async)]
+pub fn core_run_scenarios(
+    state: State<CoreState>,
+    scenarios_json: String,
+) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
+    core.run_scenarios(&scenarios_json)
+}
+
+/// 批量模拟战斗（在后台线程执行，每场结束后发送 simulation-progress 事件）
+#[tauri::command(async)]
+pub fn core_simulate_battles(
+    app: AppHandle,
+    state: State<CoreState>,
+    matchups_json: String,
+) -> Result<String, WushenError> {
+    let cancel = CancellationToken::new();
+    *state
+        .simulation_cancel
+        .lock()
+        .map_err(|_| WushenError::InvalidState("模拟状态被占用，请重试".to_string()))? =
+        cancel.clone();
+    let core = snapshot_core(&state)?;
+    core.simulate_battles(
+        &matchups_json,
+        |progress| {
+            let _ = app.emit("simulation-progress", progress.clone());
+        },
+        &cancel,
+    )
+}
+
+#[tauri::command]
+pub fn core_cancel_simulation(state: State<CoreState>) -> Result<(), WushenError> {
+    state
+        .simulation_cancel
+        .lock()
+        .map_err(|_| WushenError::InvalidState("模拟状态被占用，请重试".to_string()))?
+        .cancel();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn core_load_quests(
+    state: State<CoreState>,
+    json: String,
+) -> Result<Vec<LoadWarning>, WushenError> {
+    let mut core = lock_core(&state)?;
+    core.load_quests(&json)
+}
+
+#[tauri::command]
+pub fn core_load_items(
+    state: State<CoreState>,
+    json: String,
+) -> Result<Vec<LoadWarning>, WushenError> {
+    let mut core = lock_core(&state)?;
+    core.load_items(&json)
+}
+
+#[tauri::command]
+pub fn core_load_recipes(
+    state: State<CoreState>,
+    json: String,
+) -> Result<Vec<LoadWarning>, WushenError> {
+    let mut core = lock_core(&state)?;
+    core.load_recipes(&json)
+}
+
+#[tauri::command]
+pub fn core_load_enemies(
+    state: State<CoreState>,
+    json: String,
+) -> Result<Vec<LoadWarning>, WushenError> {
+    let mut core = lock_core(&state)?;
+    core.load_enemies(&json)
+}
+
+#[tauri::command]
+pub fn core_list_quests(state: State<CoreState>) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
+    core.list_quests()
+}
+
+/// 招收队伍成员
+#[tauri::command]
+pub fn core_game_recruit_member(
+    app: AppHandle,
+    state: State<CoreState>,
+    character_id: String,
+    name: String,
+    three_d: ThreeDimensionalState,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let response = core.game_recruit_member(character_id, name, three_d)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
+    serialize_game_response(response)
+}
+
+/// 切换操控的队伍成员
+#[tauri::command]
+pub fn core_game_switch_character(
+    app: AppHandle,
+    state: State<CoreState>,
+    character_id: String,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let response = core.game_switch_character(character_id)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
+    serialize_game_response(response)
+}
+
+/// 增减队伍共享资源
+#[tauri::command]
+pub fn core_game_adjust_resource(
+    app: AppHandle,
+    state: State<CoreState>,
+    resource_id: String,
+    delta: i64,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let response = core.game_adjust_resource(resource_id, delta)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
+    serialize_game_response(response)
+}
+
+/// 安排队伍成员修行（assignment 为空时取消安排）
+#[tauri::command]
+pub fn core_game_assign_training(
+    app: AppHandle,
+    state: State<CoreState>,
+    character_id: String,
+    assignment: Option<TrainingAssignment>,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let response = core.game_assign_training(character_id, assignment)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
+    serialize_game_response(response)
+}
+
+#[tauri::command]
+pub fn core_game_accept_quest(
+    app: AppHandle,
+    state: State<CoreState>,
+    quest_id: String,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let response = core.game_accept_quest(quest_id)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
+    serialize_game_response(response)
+}
+
+#[tauri::command]
+pub fn core_game_abandon_quest(
+    app: AppHandle,
+    state: State<CoreState>,
+    quest_id: String,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let response = core.game_abandon_quest(quest_id)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
+    serialize_game_response(response)
+}
+
+#[tauri::command]
+pub fn core_game_claim_quest(
+    app: AppHandle,
+    state: State<CoreState>,
+    quest_id: String,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let response = core.game_claim_quest(quest_id)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
+    serialize_game_response(response)
+}
+
+#[tauri::command]
+pub fn core_validation_report(state: State<CoreState>) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
+    core.validation_report()
+}
+
+#[tauri::command]
+pub fn core_content_fingerprint(state: State<CoreState>) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
+    core.content_fingerprint()
+}
+
+#[tauri::command]
+pub fn core_set_battle_config(state: State<CoreState>, json: String) -> Result<(), WushenError> {
+    let mut core = lock_core(&state)?;
+    core.set_battle_config(&json)
+}
+
+#[tauri::command]
+pub fn core_set_disabled_entry_tags(
+    state: State<CoreState>,
+    tags: Vec<String>,
+) -> Result<(), WushenError> {
+    let mut core = lock_core(&state)?;
+    core.set_disabled_entry_tags(tags);
+    Ok(())
+}
+
 #[tauri::command]
 pub fn core_execute_cultivation(
     state: State<CoreState>,
     character_json: String,
     manual_id: String,
     manual_type: String,
-) -> Result<String, String> {
-    let core = lock_core(&state)?;
+) -> Result<String, WushenError> {
+    let core = snapshot_core(&state)?;
     core.execute_cultivation(&character_json, &manual_id, &manual_type)
 }
 
-fn persist_game_save(app: &AppHandle, save: &SaveGame) -> Result<(), String> {
-    let value = serde_json::to_value(save).map_err(|e| e.to_string())?;
-    crate::commands::save_game(app.clone(), value).map(|_| ())
+fn persist_game_save(
+    app: &AppHandle,
+    core: &mut WushenCore,
+    save: &SaveGame,
+) -> Result<(), WushenError> {
+    crate::commands::record_global_discovery(app, save)?;
+    // 存档分段单独写入，且只写入发生变化的分段
+    let segments = core.game_take_dirty_segments()?;
+    crate::commands::write_save_segments(app, &save.id, &segments)?;
+    let value = serde_json::to_value(save)?;
+    crate::commands::save_game(app.clone(), value, None)?;
+    Ok(())
 }
 
-fn serialize_game_response(response: wushen_core::game::GameResponse) -> Result<String, String> {
-    serde_json::to_string(&response).map_err(|e| e.to_string())
+fn serialize_game_response(
+    response: wushen_core::game::GameResponse,
+) -> Result<String, WushenError> {
+    Ok(serde_json::to_string(&response)?)
 }
 
 #[tauri::command]
@@ -199,7 +594,7 @@ pub fn core_game_load_packs(
     app: AppHandle,
     state: State<CoreState>,
     pack_ids: Vec<String>,
-) -> Result<(), String> {
+) -> Result<(), WushenError> {
     use std::collections::HashSet;
 
     fn merge_by_id(items: &mut Vec<Value>, seen: &mut HashSet<String>, next: Vec<Value>) {
@@ -213,24 +608,16 @@ pub fn core_game_load_packs(
         }
     }
 
-    fn ensure_type_field(items: &mut [Value]) {
-        for item in items.iter_mut() {
-            if let Some(obj) = item.as_object_mut() {
-                if obj.get("type").is_none() {
-                    if let Some(value) = obj.get("manual_type").cloned() {
-                        obj.insert("type".to_string(), value);
-                    }
-                }
-            }
-        }
-    }
-
     let mut traits = Vec::new();
     let mut internals = Vec::new();
     let mut attack_skills = Vec::new();
     let mut defense_skills = Vec::new();
     let mut adventures = Vec::new();
     let mut storylines = Vec::new();
+    let mut quests = Vec::new();
+    let mut enemies = Vec::new();
+    let mut items = Vec::new();
+    let mut recipes = Vec::new();
 
     let mut trait_seen = HashSet::new();
     let mut internal_seen = HashSet::new();
@@ -238,6 +625,10 @@ pub fn core_game_load_packs(
     let mut defense_seen = HashSet::new();
     let mut adventure_seen = HashSet::new();
     let mut storyline_seen = HashSet::new();
+    let mut quest_seen = HashSet::new();
+    let mut enemy_seen = HashSet::new();
+    let mut item_seen = HashSet::new();
+    let mut recipe_seen = HashSet::new();
 
     for pack_id in pack_ids {
         let pack_traits = read_pack_collection(&app, &pack_id, "traits.json", "traits")?;
@@ -261,40 +652,44 @@ pub fn core_game_load_packs(
         let pack_storylines =
             read_pack_collection(&app, &pack_id, "storylines.json", "storylines")?;
         merge_by_id(&mut storylines, &mut storyline_seen, pack_storylines);
-    }
 
-    ensure_type_field(&mut internals);
-    ensure_type_field(&mut attack_skills);
-    ensure_type_field(&mut defense_skills);
+        let pack_quests = read_pack_collection(&app, &pack_id, "quests.json", "quests")?;
+        merge_by_id(&mut quests, &mut quest_seen, pack_quests);
 
-    let mut core = lock_core(&state)?;
-    core.reset();
+        let pack_enemies = read_pack_collection(&app, &pack_id, "enemies.json", "enemies")?;
+        merge_by_id(&mut enemies, &mut enemy_seen, pack_enemies);
 
-    if !traits.is_empty() {
-        let json = serde_json::json!({ "traits": traits }).to_string();
-        core.load_traits(&json)?;
-    }
-    if !internals.is_empty() {
-        let json = serde_json::json!({ "internals": internals }).to_string();
-        core.load_internals(&json)?;
-    }
-    if !attack_skills.is_empty() {
-        let json = serde_json::json!({ "attack_skills": attack_skills }).to_string();
-        core.load_attack_skills(&json)?;
-    }
-    if !defense_skills.is_empty() {
-        let json = serde_json::json!({ "defense_skills": defense_skills }).to_string();
-        core.load_defense_skills(&json)?;
-    }
-    if !storylines.is_empty() {
-        let json = serde_json::json!({ "storylines": storylines }).to_string();
-        core.load_storylines(&json)?;
-    }
-    if !adventures.is_empty() {
-        let json = serde_json::json!({ "adventures": adventures }).to_string();
-        core.load_adventure_events(&json)?;
+        let pack_items = read_pack_collection(&app, &pack_id, "items.json", "items")?;
+        merge_by_id(&mut items, &mut item_seen, pack_items);
+
+        let pack_recipes = read_pack_collection(&app, &pack_id, "recipes.json", "recipes")?;
+        merge_by_id(&mut recipes, &mut recipe_seen, pack_recipes);
     }
 
+    let collection = |key: &str, items: Vec<Value>| {
+        (!items.is_empty()).then(|| serde_json::json!({ key: items }))
+    };
+    let bundle = ContentBundle {
+        traits: collection("traits", traits),
+        internals: collection("internals", internals),
+        attack_skills: collection("attack_skills", attack_skills),
+        defense_skills: collection("defense_skills", defense_skills),
+        enemies: collection("enemies", enemies),
+        storylines: collection("storylines", storylines),
+        adventures: collection("adventures", adventures),
+        quests: collection("quests", quests),
+        items: collection("items", items),
+        recipes: collection("recipes", recipes),
+        battle_config: None,
+    };
+
+    // 在新实例上加载，全部校验通过后才替换，失败时保留原有内容
+    let mut fresh = WushenCore::new();
+    let report = fresh.load_content_bundle(bundle);
+    if !report.loaded {
+        return Err(WushenError::Invalid(report.errors.join("\n")));
+    }
+    *lock_core(&state)? = fresh;
     Ok(())
 }
 
@@ -303,25 +698,25 @@ pub fn core_game_start_new(
     app: AppHandle,
     state: State<CoreState>,
     request: NewGameRequest,
-) -> Result<String, String> {
+) -> Result<String, WushenError> {
     let mut core = lock_core(&state)?;
     let response = core.game_start_new(request)?;
-    persist_game_save(&app, &response.view.save)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
     serialize_game_response(response)
 }
 
-#[tauri::command]
-pub fn core_game_resume_save(
-    app: AppHandle,
-    state: State<CoreState>,
+/// 读取存档；存档所在剧情线尚未加载时先载入全部数据包
+fn load_save_with_content(
+    app: &AppHandle,
+    state: &State<CoreState>,
     id: String,
-) -> Result<String, String> {
-    let raw =
-        crate::commands::load_save(app.clone(), id)?.ok_or_else(|| "存档不存在".to_string())?;
-    let save: SaveGame = serde_json::from_value(raw).map_err(|e| e.to_string())?;
+) -> Result<SaveGame, WushenError> {
+    let raw = crate::commands::load_save(app.clone(), id)?
+        .ok_or_else(|| WushenError::NotFound("存档不存在".to_string()))?;
+    let save: SaveGame = serde_json::from_value(raw)?;
     if let Some(progress) = save.storyline_progress.as_ref() {
         let needs_reload = {
-            let core = lock_core(&state)?;
+            let core = lock_core(state)?;
             core.get_storyline(&progress.storyline_id).is_err()
         };
         if needs_reload {
@@ -332,29 +727,75 @@ pub fn core_game_resume_save(
             }
         }
     }
+    Ok(save)
+}
+
+/// 检查存档与当前内容是否一致，返回问题列表JSON
+#[tauri::command]
+pub fn core_game_check_save(
+    app: AppHandle,
+    state: State<CoreState>,
+    id: String,
+) -> Result<String, WushenError> {
+    let save = load_save_with_content(&app, &state, id)?;
+    let core = lock_core(&state)?;
+    Ok(serde_json::to_string(&core.game_check_save(&save))?)
+}
+
+/// 恢复存档；repair 为 true 时先修复存档中与当前内容不一致之处
+#[tauri::command]
+pub fn core_game_resume_save(
+    app: AppHandle,
+    state: State<CoreState>,
+    id: String,
+    repair: Option<bool>,
+) -> Result<String, WushenError> {
+    let mut save = load_save_with_content(&app, &state, id)?;
+    let segments = crate::commands::read_save_segments(&app, &save.id)?;
     let mut core = lock_core(&state)?;
-    let response = core.game_resume(save)?;
-    persist_game_save(&app, &response.view.save)?;
+    if repair.unwrap_or(false) {
+        core.game_repair_save(&mut save);
+    }
+    let response = core.game_resume_segmented(save, segments)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
     serialize_game_response(response)
 }
 
 #[tauri::command]
-pub fn core_game_view(state: State<CoreState>) -> Result<String, String> {
+pub fn core_game_view(state: State<CoreState>) -> Result<String, WushenError> {
     let core = lock_core(&state)?;
     let response = core.game_view(None)?;
     serialize_game_response(response)
 }
 
+/// 差量视图：只返回自 since_revision 以来发生变化的分区
+#[tauri::command]
+pub fn core_game_view_diff(
+    state: State<CoreState>,
+    since_revision: Option<u64>,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let diff = core.game_view_diff(since_revision)?;
+    Ok(serde_json::to_string(&diff)?)
+}
+
+/// 按需读取当前存档的分段数据（如剧情历史）
+#[tauri::command]
+pub fn core_game_segment(state: State<CoreState>, kind: String) -> Result<String, WushenError> {
+    let core = lock_core(&state)?;
+    core.game_segment(kind)
+}
+
 #[tauri::command]
 pub fn core_game_cultivate(
     app: AppHandle,
     state: State<CoreState>,
     manual_id: String,
     manual_type: String,
-) -> Result<String, String> {
+) -> Result<String, WushenError> {
     let mut core = lock_core(&state)?;
     let response = core.game_cultivate(manual_id, manual_type)?;
-    persist_game_save(&app, &response.view.save)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
     serialize_game_response(response)
 }
 
@@ -364,10 +805,10 @@ pub fn core_game_travel(
     state: State<CoreState>,
     attacker_qi_output_rate: Option<f64>,
     defender_qi_output_rate: Option<f64>,
-) -> Result<String, String> {
+) -> Result<String, WushenError> {
     let mut core = lock_core(&state)?;
     let response = core.game_travel(attacker_qi_output_rate, defender_qi_output_rate)?;
-    persist_game_save(&app, &response.view.save)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
     serialize_game_response(response)
 }
 
@@ -376,10 +817,10 @@ pub fn core_game_story_option(
     app: AppHandle,
     state: State<CoreState>,
     option_id: String,
-) -> Result<String, String> {
+) -> Result<String, WushenError> {
     let mut core = lock_core(&state)?;
     let response = core.game_story_option(option_id)?;
-    persist_game_save(&app, &response.view.save)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
     serialize_game_response(response)
 }
 
@@ -389,31 +830,156 @@ pub fn core_game_equip_manual(
     state: State<CoreState>,
     manual_id: String,
     manual_type: String,
-) -> Result<String, String> {
+) -> Result<String, WushenError> {
     let mut core = lock_core(&state)?;
     let response = core.game_equip_manual(manual_id, manual_type)?;
-    persist_game_save(&app, &response.view.save)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
+    serialize_game_response(response)
+}
+
+#[tauri::command]
+pub fn core_game_equip_title(
+    app: AppHandle,
+    state: State<CoreState>,
+    title_id: Option<String>,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let response = core.game_equip_title(title_id)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
+    serialize_game_response(response)
+}
+
+#[tauri::command]
+pub fn core_game_use_item(
+    app: AppHandle,
+    state: State<CoreState>,
+    item_id: String,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let response = core.game_use_item(item_id)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
     serialize_game_response(response)
 }
 
+#[tauri::command]
+pub fn core_game_craft(
+    app: AppHandle,
+    state: State<CoreState>,
+    recipe_id: String,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let response = core.game_craft(recipe_id)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
+    serialize_game_response(response)
+}
+
+#[tauri::command]
+pub fn core_game_titles(state: State<CoreState>) -> Result<String, WushenError> {
+    let core = lock_core(&state)?;
+    core.game_titles()
+}
+
+#[tauri::command]
+pub fn core_game_rewards_preview(
+    state: State<CoreState>,
+    rewards_json: String,
+) -> Result<String, WushenError> {
+    let core = lock_core(&state)?;
+    core.game_rewards_preview(&rewards_json)
+}
+
+#[tauri::command]
+pub fn core_game_codex(
+    state: State<CoreState>,
+    kind: Option<String>,
+) -> Result<String, WushenError> {
+    let core = lock_core(&state)?;
+    core.game_codex(kind)
+}
+
+#[tauri::command]
+pub fn core_game_adventure_collection(
+    app: AppHandle,
+    state: State<CoreState>,
+    global: Option<bool>,
+) -> Result<String, WushenError> {
+    let global = if global.unwrap_or(false) {
+        Some(crate::commands::read_global_discovery(&app)?.to_json()?)
+    } else {
+        None
+    };
+    let core = lock_core(&state)?;
+    core.game_adventure_collection(global)
+}
+
 #[tauri::command]
 pub fn core_game_story_battle(
     app: AppHandle,
     state: State<CoreState>,
     attacker_qi_output_rate: Option<f64>,
     defender_qi_output_rate: Option<f64>,
-) -> Result<String, String> {
+) -> Result<String, WushenError> {
     let mut core = lock_core(&state)?;
     let response = core.game_story_battle(attacker_qi_output_rate, defender_qi_output_rate)?;
-    persist_game_save(&app, &response.view.save)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
     serialize_game_response(response)
 }
 
 #[tauri::command]
-pub fn core_game_story_continue(app: AppHandle, state: State<CoreState>) -> Result<String, String> {
+pub fn core_game_story_battle_manual(
+    state: State<CoreState>,
+    attacker_qi_output_rate: Option<f64>,
+    defender_qi_output_rate: Option<f64>,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let session =
+        core.game_story_battle_manual(attacker_qi_output_rate, defender_qi_output_rate)?;
+    Ok(serde_json::to_string(&session)?)
+}
+
+#[tauri::command]
+pub fn core_battle_session_step(
+    app: AppHandle,
+    state: State<CoreState>,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let update = core.battle_session_step()?;
+    // 战斗结束并结算剧情后才需要写入存档
+    if let Some(response) = &update.response {
+        persist_game_save(&app, &mut core, &response.view.save)?;
+    }
+    Ok(serde_json::to_string(&update)?)
+}
+
+#[tauri::command]
+pub fn core_battle_session_set_qi_output(
+    state: State<CoreState>,
+    rate: f64,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let session = core.battle_session_set_qi_output(rate)?;
+    Ok(serde_json::to_string(&session)?)
+}
+
+#[tauri::command]
+pub fn core_game_story_continue(
+    app: AppHandle,
+    state: State<CoreState>,
+) -> Result<String, WushenError> {
     let mut core = lock_core(&state)?;
     let response = core.game_story_continue()?;
-    persist_game_save(&app, &response.view.save)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
+    serialize_game_response(response)
+}
+
+#[tauri::command]
+pub fn core_game_story_check(
+    app: AppHandle,
+    state: State<CoreState>,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let response = core.game_story_check()?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
     serialize_game_response(response)
 }
 
@@ -424,18 +990,146 @@ pub fn core_game_adventure_option(
     option_id: String,
     attacker_qi_output_rate: Option<f64>,
     defender_qi_output_rate: Option<f64>,
-) -> Result<String, String> {
+) -> Result<String, WushenError> {
     let mut core = lock_core(&state)?;
     let response =
         core.game_adventure_option(option_id, attacker_qi_output_rate, defender_qi_output_rate)?;
-    persist_game_save(&app, &response.view.save)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
+    serialize_game_response(response)
+}
+
+#[tauri::command]
+pub fn core_game_adventure_skip(
+    app: AppHandle,
+    state: State<CoreState>,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let response = core.game_adventure_skip()?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
+    serialize_game_response(response)
+}
+
+#[tauri::command]
+pub fn core_list_completed_characters(state: State<CoreState>) -> Result<String, WushenError> {
+    let core = lock_core(&state)?;
+    core.list_completed_characters()
+}
+
+#[tauri::command]
+pub fn core_get_completed_character(
+    state: State<CoreState>,
+    index: usize,
+) -> Result<String, WushenError> {
+    let core = lock_core(&state)?;
+    core.get_completed_character(index)
+}
+
+#[tauri::command]
+pub fn core_export_completed_character(
+    state: State<CoreState>,
+    index: usize,
+) -> Result<String, WushenError> {
+    let core = lock_core(&state)?;
+    core.export_completed_character(index)
+}
+
+#[tauri::command]
+pub fn core_game_export_character_build(state: State<CoreState>) -> Result<String, WushenError> {
+    let core = lock_core(&state)?;
+    core.game_export_character_build()
+}
+
+#[tauri::command]
+pub fn core_import_character_build(
+    state: State<CoreState>,
+    json: String,
+) -> Result<String, WushenError> {
+    let core = lock_core(&state)?;
+    core.import_character_build(&json)
+}
+
+#[tauri::command]
+pub fn core_game_delete_completed_character(
+    app: AppHandle,
+    state: State<CoreState>,
+    index: usize,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let response = core.game_delete_completed_character(index)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
+    serialize_game_response(response)
+}
+
+#[tauri::command]
+pub fn core_game_set_analytics(
+    app: AppHandle,
+    state: State<CoreState>,
+    enabled: bool,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let response = core.game_set_analytics(enabled)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
     serialize_game_response(response)
 }
 
 #[tauri::command]
-pub fn core_game_finish(app: AppHandle, state: State<CoreState>) -> Result<String, String> {
+pub fn core_game_set_battle_archive(
+    app: AppHandle,
+    state: State<CoreState>,
+    policy: Option<BattleArchivePolicy>,
+) -> Result<String, WushenError> {
+    let mut core = lock_core(&state)?;
+    let response = core.game_set_battle_archive(policy)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
+    serialize_game_response(response)
+}
+
+/// 读取已存档的战斗（供江湖见闻重温战斗）
+#[tauri::command]
+pub fn core_game_get_archived_battle(
+    state: State<CoreState>,
+    id: String,
+) -> Result<String, WushenError> {
+    let core = lock_core(&state)?;
+    core.get_archived_battle(&id)
+}
+
+#[tauri::command]
+pub fn core_game_analytics_report(state: State<CoreState>) -> Result<String, WushenError> {
+    let core = lock_core(&state)?;
+    core.game_analytics_report()
+}
+
+/// 上帝模式调试指令（需启用 god-mode 特性构建）
+#[tauri::command]
+pub fn core_debug_command(
+    app: AppHandle,
+    state: State<CoreState>,
+    command: Value,
+) -> Result<String, WushenError> {
+    #[cfg(feature = "god-mode")]
+    {
+        let command: wushen_core::tauri_api::DebugCommand = serde_json::from_value(command)
+            .map_err(|e| WushenError::ParseError(format!("解析调试指令失败: {}", e)))?;
+        let mut core = lock_core(&state)?;
+        let response = core.debug_execute(command)?;
+        persist_game_save(&app, &mut core, &response.view.save)?;
+        serialize_game_response(response)
+    }
+    #[cfg(not(feature = "god-mode"))]
+    {
+        let _ = (app, state, command);
+        Err(WushenError::InvalidState(
+            "当前构建未启用上帝模式".to_string(),
+        ))
+    }
+}
+
+#[tauri::command]
+pub fn core_game_finish(app: AppHandle, state: State<CoreState>) -> Result<String, WushenError> {
     let mut core = lock_core(&state)?;
     let response = core.game_finish()?;
-    persist_game_save(&app, &response.view.save)?;
+    persist_game_save(&app, &mut core, &response.view.save)?;
+    crate::commands::record_roster_save(&app, &response.view.save)?;
     serialize_game_response(response)
 }
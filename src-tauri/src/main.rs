@@ -39,6 +39,10 @@ fn main() {
             commands::get_adventure_event,
             commands::save_adventure_event,
             commands::delete_adventure_event,
+            commands::list_quests,
+            commands::get_quest,
+            commands::save_quest,
+            commands::delete_quest,
             commands::list_storylines,
             commands::get_storyline,
             commands::save_storyline,
@@ -48,6 +52,12 @@ fn main() {
             commands::save_game,
             commands::save_character,
             commands::delete_save,
+            commands::convert_save_format,
+            commands::export_save,
+            commands::import_save,
+            commands::list_roster,
+            commands::roster_opponents,
+            commands::rebuild_roster,
             core_commands::core_reset,
             core_commands::core_load_traits,
             core_commands::core_load_internals,
@@ -55,33 +65,91 @@ fn main() {
             core_commands::core_load_defense_skills,
             core_commands::core_load_storylines,
             core_commands::core_load_adventure_events,
+            core_commands::core_load_quests,
+            core_commands::core_load_items,
+            core_commands::core_load_recipes,
+            core_commands::core_load_content_bundle,
+            core_commands::core_content_fingerprint,
+            core_commands::core_load_enemies,
+            core_commands::core_list_quests,
             core_commands::core_get_trait,
             core_commands::core_list_traits,
             core_commands::core_get_internal,
             core_commands::core_list_internals,
+            core_commands::core_list_internals_filtered,
             core_commands::core_get_attack_skill,
             core_commands::core_list_attack_skills,
+            core_commands::core_list_attack_skills_filtered,
             core_commands::core_get_defense_skill,
             core_commands::core_list_defense_skills,
+            core_commands::core_list_defense_skills_filtered,
             core_commands::core_list_storylines,
+            core_commands::core_list_storyline_stats,
             core_commands::core_get_storyline,
             core_commands::core_list_adventure_events,
             core_commands::core_get_adventure_event,
             core_commands::core_calculate_cultivation_exp,
+            core_commands::core_formula_variable_catalog,
             core_commands::core_calculate_battle,
+            core_commands::core_capture_battle_replay,
+            core_commands::core_replay_battle,
+            core_commands::core_preview_exchange,
+            core_commands::core_test_battle_against_enemy,
+            core_commands::core_preview_trait,
+            core_commands::core_dry_run_entry,
+            core_commands::core_simulate_cultivation_sweep,
+            core_commands::core_run_scenarios,
+            core_commands::core_simulate_battles,
+            core_commands::core_cancel_simulation,
+            core_commands::core_set_battle_config,
+            core_commands::core_set_disabled_entry_tags,
+            core_commands::core_validation_report,
             core_commands::core_execute_cultivation,
             core_commands::core_game_load_packs,
             core_commands::core_game_start_new,
+            core_commands::core_game_check_save,
             core_commands::core_game_resume_save,
             core_commands::core_game_view,
+            core_commands::core_game_view_diff,
+            core_commands::core_game_segment,
             core_commands::core_game_cultivate,
             core_commands::core_game_travel,
             core_commands::core_game_story_option,
             core_commands::core_game_equip_manual,
+            core_commands::core_game_equip_title,
+            core_commands::core_game_use_item,
+            core_commands::core_game_craft,
+            core_commands::core_game_titles,
+            core_commands::core_game_rewards_preview,
+            core_commands::core_game_codex,
+            core_commands::core_game_adventure_collection,
             core_commands::core_game_story_battle,
+            core_commands::core_game_story_battle_manual,
+            core_commands::core_battle_session_step,
+            core_commands::core_battle_session_set_qi_output,
             core_commands::core_game_story_continue,
+            core_commands::core_game_story_check,
             core_commands::core_game_adventure_option,
+            core_commands::core_game_adventure_skip,
+            core_commands::core_game_recruit_member,
+            core_commands::core_game_switch_character,
+            core_commands::core_game_adjust_resource,
+            core_commands::core_game_assign_training,
+            core_commands::core_game_accept_quest,
+            core_commands::core_game_abandon_quest,
+            core_commands::core_game_claim_quest,
+            core_commands::core_game_set_analytics,
+            core_commands::core_game_set_battle_archive,
+            core_commands::core_game_get_archived_battle,
+            core_commands::core_game_analytics_report,
+            core_commands::core_debug_command,
             core_commands::core_game_finish,
+            core_commands::core_list_completed_characters,
+            core_commands::core_get_completed_character,
+            core_commands::core_export_completed_character,
+            core_commands::core_game_export_character_build,
+            core_commands::core_import_character_build,
+            core_commands::core_game_delete_completed_character,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
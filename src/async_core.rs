@@ -0,0 +1,324 @@
+/// 异步外观
+/// 在独立线程中托管 `WushenCore`，调用方经命令队列提交指令，不必自行加锁，也不会阻塞界面线程；
+/// 返回的 [`PendingReply`] 既可 `.await`（不依赖特定异步运行时），也可阻塞等待
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+
+use crate::error::{WushenError, WushenResult};
+use crate::game::{GameResponse, NewGameRequest, SaveGame};
+use crate::tauri_api::WushenCore;
+
+/// 常用的运行时指令
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum CoreCommand {
+    StartNew {
+        request: Box<NewGameRequest>,
+    },
+    Resume {
+        save: Box<SaveGame>,
+    },
+    View,
+    Cultivate {
+        manual_id: String,
+        manual_type: String,
+    },
+    EquipManual {
+        manual_id: String,
+        manual_type: String,
+    },
+    Travel {
+        #[serde(default)]
+        attacker_qi_output_rate: Option<f64>,
+        #[serde(default)]
+        defender_qi_output_rate: Option<f64>,
+    },
+    StoryOption {
+        option_id: String,
+    },
+    StoryBattle {
+        #[serde(default)]
+        attacker_qi_output_rate: Option<f64>,
+        #[serde(default)]
+        defender_qi_output_rate: Option<f64>,
+    },
+    StoryContinue,
+    StoryCheck,
+    AdventureOption {
+        option_id: String,
+        #[serde(default)]
+        attacker_qi_output_rate: Option<f64>,
+        #[serde(default)]
+        defender_qi_output_rate: Option<f64>,
+    },
+    AdventureSkip,
+    Finish,
+    /// 读取当前存档
+    CurrentSave,
+}
+
+/// 指令结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum CoreReply {
+    Game(Box<GameResponse>),
+    Save(Option<Box<SaveGame>>),
+}
+
+impl CoreCommand {
+    /// 在核心上执行指令
+    pub fn execute(self, core: &mut WushenCore) -> WushenResult<CoreReply> {
+        let response = match self {
+            CoreCommand::StartNew { request } => core.game_start_new(*request),
+            CoreCommand::Resume { save } => core.game_resume(*save),
+            CoreCommand::View => core.game_view(None),
+            CoreCommand::Cultivate {
+                manual_id,
+                manual_type,
+            } => core.game_cultivate(manual_id, manual_type),
+            CoreCommand::EquipManual {
+                manual_id,
+                manual_type,
+            } => core.game_equip_manual(manual_id, manual_type),
+            CoreCommand::Travel {
+                attacker_qi_output_rate,
+                defender_qi_output_rate,
+            } => core.game_travel(attacker_qi_output_rate, defender_qi_output_rate),
+            CoreCommand::StoryOption { option_id } => core.game_story_option(option_id),
+            CoreCommand::StoryBattle {
+                attacker_qi_output_rate,
+                defender_qi_output_rate,
+            } => core.game_story_battle(attacker_qi_output_rate, defender_qi_output_rate),
+            CoreCommand::StoryContinue => core.game_story_continue(),
+            CoreCommand::StoryCheck => core.game_story_check(),
+            CoreCommand::AdventureOption {
+                option_id,
+                attacker_qi_output_rate,
+                defender_qi_output_rate,
+            } => core.game_adventure_option(
+                option_id,
+                attacker_qi_output_rate,
+                defender_qi_output_rate,
+            ),
+            CoreCommand::AdventureSkip => core.game_adventure_skip(),
+            CoreCommand::Finish => core.game_finish(),
+            CoreCommand::CurrentSave => {
+                let save = core.current_save().cloned().map(Box::new);
+                return Ok(CoreReply::Save(save));
+            }
+        };
+        response.map(|response| CoreReply::Game(Box::new(response)))
+    }
+}
+
+type Job = Box<dyn FnOnce(&mut WushenCore) + Send>;
+
+/// 异步核心：按提交顺序在工作线程中依次执行
+pub struct WushenCoreAsync {
+    sender: Option<Sender<Job>>,
+    worker: Option<JoinHandle<WushenCore>>,
+}
+
+impl WushenCoreAsync {
+    /// 启动工作线程并托管核心
+    pub fn spawn(mut core: WushenCore) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let worker = thread::spawn(move || {
+            for job in receiver {
+                job(&mut core);
+            }
+            core
+        });
+        Self {
+            sender: Some(sender),
+            worker: Some(worker),
+        }
+    }
+
+    /// 提交指令
+    pub fn submit(&self, command: CoreCommand) -> PendingReply<WushenResult<CoreReply>> {
+        self.call(move |core| command.execute(core))
+    }
+
+    /// 在工作线程中对核心执行任意操作（用于指令枚举未覆盖的接口）
+    pub fn call<T, F>(&self, f: F) -> PendingReply<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut WushenCore) -> T + Send + 'static,
+    {
+        let (reply, completer) = PendingReply::new();
+        let job: Job = Box::new(move |core| completer.complete(f(core)));
+        if let Some(sender) = &self.sender {
+            // 发送失败时任务随之丢弃，等待方得到“已停止”的错误
+            let _ = sender.send(job);
+        }
+        reply
+    }
+
+    /// 执行完已提交的指令后停止工作线程，取回核心（工作线程异常退出时为空）
+    pub fn shutdown(mut self) -> Option<WushenCore> {
+        self.stop()
+    }
+
+    fn stop(&mut self) -> Option<WushenCore> {
+        self.sender.take();
+        self.worker.take()?.join().ok()
+    }
+}
+
+impl Drop for WushenCoreAsync {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+struct Slot<T> {
+    value: Option<WushenResult<T>>,
+    waker: Option<Waker>,
+}
+
+type Shared<T> = Arc<(Mutex<Slot<T>>, Condvar)>;
+
+/// 等待中的指令结果
+pub struct PendingReply<T> {
+    shared: Shared<T>,
+}
+
+/// 写入结果；未写入即被丢弃时（工作线程已停止）写入错误
+struct Completer<T> {
+    shared: Shared<T>,
+}
+
+impl<T> PendingReply<T> {
+    fn new() -> (Self, Completer<T>) {
+        let shared = Arc::new((
+            Mutex::new(Slot {
+                value: None,
+                waker: None,
+            }),
+            Condvar::new(),
+        ));
+        let completer = Completer {
+            shared: Arc::clone(&shared),
+        };
+        (Self { shared }, completer)
+    }
+
+    /// 阻塞等待结果
+    pub fn wait(self) -> WushenResult<T> {
+        let (lock, ready) = &*self.shared;
+        let mut slot = lock.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            if let Some(value) = slot.value.take() {
+                return value;
+            }
+            slot = ready.wait(slot).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// 不阻塞地取出结果（尚未完成时为空）
+    pub fn try_take(&self) -> Option<WushenResult<T>> {
+        let (lock, _) = &*self.shared;
+        lock.lock().unwrap_or_else(|e| e.into_inner()).value.take()
+    }
+}
+
+impl<T> Future for PendingReply<T> {
+    type Output = WushenResult<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let (lock, _) = &*self.shared;
+        let mut slot = lock.lock().unwrap_or_else(|e| e.into_inner());
+        match slot.value.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Completer<T> {
+    fn complete(self, value: T) {
+        self.fill(Ok(value));
+    }
+
+    fn fill(&self, value: WushenResult<T>) {
+        let (lock, ready) = &*self.shared;
+        let mut slot = lock.lock().unwrap_or_else(|e| e.into_inner());
+        if slot.value.is_some() {
+            return;
+        }
+        slot.value = Some(value);
+        if let Some(waker) = slot.waker.take() {
+            waker.wake();
+        }
+        ready.notify_all();
+    }
+}
+
+impl<T> Drop for Completer<T> {
+    fn drop(&mut self) {
+        let (lock, _) = &*self.shared;
+        let pending = lock
+            .lock()
+            .map(|slot| slot.value.is_none())
+            .unwrap_or(false);
+        if pending {
+            self.fill(Err(WushenError::InvalidState(
+                "核心工作线程已停止".to_string(),
+            )));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{core_with_storyline as core, new_game_request};
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+            thread::yield_now();
+        }
+    }
+
+    #[test]
+    fn commands_run_in_order_on_the_worker() {
+        let core = WushenCoreAsync::spawn(core());
+        let started = core.submit(CoreCommand::StartNew {
+            request: Box::new(new_game_request("少侠")),
+        });
+        let continued = core.submit(CoreCommand::StoryContinue);
+        let save = core.submit(CoreCommand::CurrentSave);
+
+        assert!(matches!(started.wait().unwrap(), Ok(CoreReply::Game(_))));
+        let Ok(CoreReply::Game(response)) = block_on(continued).unwrap() else {
+            panic!("剧情推进失败");
+        };
+        assert_eq!(response.view.current_event.unwrap().id, "end");
+        let Ok(CoreReply::Save(Some(save))) = save.wait().unwrap() else {
+            panic!("读取存档失败");
+        };
+        assert_eq!(save.current_character.name, "少侠");
+
+        let name = core.call(|core| core.current_save().map(|s| s.name.clone()));
+        assert_eq!(name.wait().unwrap().as_deref(), Some("少侠"));
+        let failed = core.submit(CoreCommand::StoryOption {
+            option_id: "missing".to_string(),
+        });
+        assert!(failed.wait().unwrap().is_err());
+        assert!(core.shutdown().is_some());
+    }
+}
@@ -17,7 +17,7 @@ pub enum ActionReady {
 
 impl ActionReady {
     /// 转换为 Side（返回应该行动的一方）
-    pub fn to_side(&self) -> Option<Side> {
+    pub fn to_side(self) -> Option<Side> {
         match self {
             ActionReady::SideA | ActionReady::Both => Some(Side::A),
             ActionReady::SideB => Some(Side::B),
@@ -75,6 +75,14 @@ impl ActionBar {
         }
     }
 
+    /// 设置蓄力时间（切换攻击武技时）
+    pub fn set_charge_time(&mut self, side: Side, charge_time: f64) {
+        match side {
+            Side::A => self.side_a_charge_time = charge_time,
+            Side::B => self.side_b_charge_time = charge_time,
+        }
+    }
+
     /// 重置 Side A 行动条（行动后重置）
     pub fn reset_side_a(&mut self) {
         self.side_a_progress = 0.0;
@@ -93,19 +101,6 @@ impl ActionBar {
         }
     }
 
-    /// 重置指定就绪状态的行动条
-    pub fn reset_ready(&mut self, ready: ActionReady) {
-        match ready {
-            ActionReady::SideA => self.reset_side_a(),
-            ActionReady::SideB => self.reset_side_b(),
-            ActionReady::Both => {
-                self.reset_side_a();
-                self.reset_side_b();
-            }
-            ActionReady::None => {}
-        }
-    }
-
     /// 获取指定方的行动条进度
     pub fn get_progress(&self, side: Side) -> f64 {
         match side {
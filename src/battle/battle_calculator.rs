@@ -1,9 +1,11 @@
 /// 战斗结算计算器
 /// 实现战斗文档中的6步结算流程
+use super::battle_config::NumericPolicy;
 use super::battle_panel::BattlePanel;
+use serde::{Deserialize, Serialize};
 
 /// 战斗结算结果
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct BattleCalculationResult {
     /// 总输出
     pub total_output: f64,
@@ -60,26 +62,53 @@ impl BattleCalculator {
         defense.max(0.0)
     }
 
-    /// 完整战斗结算流程（步骤2-6，步骤1回气在攻击前阶段完成）
+    /// 按数值策略执行完整战斗结算（步骤2-6，每一步结果都按策略规整）
     /// 注意：回气应在攻击前阶段进行，不在此处
-    pub fn calculate_battle(
+    pub fn calculate_battle_with_policy(
         attacker: &mut BattlePanel,
         defender: &mut BattlePanel,
+        policy: NumericPolicy,
     ) -> BattleCalculationResult {
+        let q = |value: f64| policy.quantize(value);
+        let result = Self::preview_exchange(attacker, defender, policy);
+
+        // 应用消耗
+        attacker.qi = q(attacker.qi - result.attacker_qi_consumed);
+        attacker.clamp_qi();
+
+        defender.qi = q(defender.qi - result.defender_qi_consumed);
+        defender.clamp_qi();
+
+        // 应用伤害
+        defender.hp = q(defender.hp - result.hp_damage);
+        defender.clamp_hp();
+
+        result
+    }
+
+    /// 预估一次交手的结算结果（步骤2-5，不修改面板、不产生日志）
+    /// 供 AI 策略、界面预览等使用，与实际结算共用同一套公式
+    pub fn preview_exchange(
+        attacker: &BattlePanel,
+        defender: &BattlePanel,
+        policy: NumericPolicy,
+    ) -> BattleCalculationResult {
+        let q = |value: f64| policy.quantize(value);
+
         // 步骤2: 计算攻击者输出（回气已在攻击前阶段完成）
-        let total_output = Self::calculate_attack_output(attacker);
+        let total_output = q(Self::calculate_attack_output(attacker));
 
         // 步骤3: 计算防御者防御力
-        let total_defense = Self::calculate_defense(defender);
+        let total_defense = q(Self::calculate_defense(defender));
 
         // 步骤4: 计算减伤后输出
         // 减伤后输出 = 总输出 × (1 - 防御者减伤)
         let damage_reduction = defender.damage_reduction.min(defender.max_damage_reduction);
-        let reduced_output = total_output * (1.0 - damage_reduction);
+        let reduced_output = q(total_output * (1.0 - damage_reduction));
 
         // 步骤5: 比较减伤后输出与防御者防御力
-        let defender_qi_output = defender.qi.min(defender.max_qi * defender.qi_output_rate);
-        let attacker_qi_output = attacker.qi.min(attacker.max_qi * attacker.qi_output_rate);
+        let defender_qi_output = q(defender.qi.min(defender.max_qi * defender.qi_output_rate));
+        let attacker_qi_output = q(attacker.qi.min(attacker.max_qi * attacker.qi_output_rate));
 
         let broke_qi_defense = reduced_output > total_defense;
         let hp_damage;
@@ -87,14 +116,14 @@ impl BattleCalculator {
 
         if broke_qi_defense {
             // 输出大于防御力：扣除生命值差值，扣除防御者此次消耗的内息量
-            hp_damage = reduced_output - total_defense;
+            hp_damage = q(reduced_output - total_defense);
             defender_qi_consumed = defender_qi_output;
         } else {
             // 输出小于等于防御力：不造成生命值伤害
             hp_damage = 0.0;
             // 扣除防御者内息值 = 减伤后输出 × min(当前内息, 内息量 × 内息输出) / 总防御力
             if total_defense > 0.0 {
-                defender_qi_consumed = reduced_output * defender_qi_output / total_defense;
+                defender_qi_consumed = q(reduced_output * defender_qi_output / total_defense);
             } else {
                 defender_qi_consumed = 0.0;
             }
@@ -103,17 +132,6 @@ impl BattleCalculator {
         // 无论是否击破内息防御，攻击者均要消耗内息
         let attacker_qi_consumed = attacker_qi_output;
 
-        // 应用消耗
-        attacker.qi -= attacker_qi_consumed;
-        attacker.clamp_qi();
-
-        defender.qi -= defender_qi_consumed;
-        defender.clamp_qi();
-
-        // 应用伤害
-        defender.hp -= hp_damage;
-        defender.clamp_hp();
-
         BattleCalculationResult {
             total_output,
             total_defense,
@@ -165,4 +183,70 @@ mod tests {
         // (100 + min(500, 1000*0.3)) * 1.5 * 2.0 * 1.1 = (100 + 300) * 3.3 = 1320
         assert!((output - 1320.0).abs() < 0.01);
     }
+
+    #[test]
+    fn test_policy_absorbs_small_drift() {
+        // 模拟运算顺序导致的末位误差，取整模式下结算结果应完全一致
+        let build = |drift: f64| {
+            let char_panel =
+                CharacterPanel::new("测试".to_string(), ThreeDimensional::new(10, 8, 12));
+            let mut attacker = BattlePanel::from_character_panel(&char_panel);
+            attacker.base_attack = 100.0 + drift;
+            attacker.max_qi = 1000.0;
+            attacker.qi = 500.0;
+            attacker.qi_output_rate = 0.3;
+            attacker.qi_quality = 1.1 + drift;
+            attacker.power = 1.7;
+            let mut defender = attacker.clone();
+            defender.base_defense = 80.0;
+            defender.defense_power = 1.3 - drift;
+            defender.damage_reduction = 0.15;
+            (attacker, defender)
+        };
+
+        for policy in [
+            NumericPolicy::Quantized { scale: 10_000 },
+            NumericPolicy::Quantized { scale: 8 },
+        ] {
+            let (mut a1, mut d1) = build(0.0);
+            let (mut a2, mut d2) = build(1e-12);
+            let r1 = BattleCalculator::calculate_battle_with_policy(&mut a1, &mut d1, policy);
+            let r2 = BattleCalculator::calculate_battle_with_policy(&mut a2, &mut d2, policy);
+            assert_eq!(r1, r2);
+            assert_eq!(d1.hp, d2.hp);
+            assert_eq!(d1.qi, d2.qi);
+        }
+    }
+
+    #[test]
+    fn test_preview_exchange_matches_calculation() {
+        let char_panel = CharacterPanel::new("测试".to_string(), ThreeDimensional::new(10, 8, 12));
+        let mut attacker = BattlePanel::from_character_panel(&char_panel);
+        attacker.base_attack = 300.0;
+        attacker.max_qi = 1000.0;
+        attacker.qi = 500.0;
+        attacker.qi_output_rate = 0.3;
+        attacker.qi_quality = 1.0;
+        attacker.power = 1.0;
+        attacker.damage_bonus = 0.0;
+        let mut defender = attacker.clone();
+        defender.base_defense = 100.0;
+        defender.defense_power = 1.0;
+        defender.damage_reduction = 0.1;
+
+        let preview =
+            BattleCalculator::preview_exchange(&attacker, &defender, NumericPolicy::Float);
+        assert_eq!(attacker.qi, 500.0);
+        assert_eq!(defender.qi, 500.0);
+        assert!(preview.broke_qi_defense);
+
+        let hp_before = defender.hp;
+        let result = BattleCalculator::calculate_battle_with_policy(
+            &mut attacker,
+            &mut defender,
+            NumericPolicy::Float,
+        );
+        assert_eq!(preview, result);
+        assert_eq!(defender.hp, hp_before - preview.hp_damage);
+    }
 }
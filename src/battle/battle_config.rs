@@ -0,0 +1,473 @@
+/// 战斗配置
+/// 控制最大轮数以及达到轮数上限时的胜负判定规则
+use serde::{Deserialize, Serialize};
+
+use super::battle_panel::BattlePanel;
+use super::battle_setup::MissingManualPolicy;
+use super::battle_state::BattleResult;
+use super::qi_strategy::QiOutputProfile;
+use crate::effect::effect::AttributeTarget;
+
+/// 默认最大战斗轮数
+pub const DEFAULT_MAX_ROUNDS: u32 = 100;
+
+/// 达到最大轮数时的胜负判定规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TiebreakerRule {
+    /// 剩余生命百分比较高者胜
+    HigherHpPercent,
+    /// 剩余内息较高者胜
+    HigherQi,
+    /// 攻击方（Side A）判负
+    AttackerLoses,
+}
+
+impl TiebreakerRule {
+    /// 规则名称（用于战斗日志）
+    pub fn name(&self) -> &'static str {
+        match self {
+            TiebreakerRule::HigherHpPercent => "剩余生命百分比",
+            TiebreakerRule::HigherQi => "剩余内息",
+            TiebreakerRule::AttackerLoses => "攻击方判负",
+        }
+    }
+
+    /// 按规则判定胜负，无法分出胜负时返回 None
+    pub fn resolve(&self, side_a: &BattlePanel, side_b: &BattlePanel) -> Option<BattleResult> {
+        let (a, b) = match self {
+            TiebreakerRule::HigherHpPercent => (hp_percent(side_a), hp_percent(side_b)),
+            TiebreakerRule::HigherQi => (side_a.qi, side_b.qi),
+            TiebreakerRule::AttackerLoses => return Some(BattleResult::SideBWin),
+        };
+        if (a - b).abs() < 1e-9 {
+            None
+        } else if a > b {
+            Some(BattleResult::SideAWin)
+        } else {
+            Some(BattleResult::SideBWin)
+        }
+    }
+}
+
+/// 双方同时蓄力完成时的先手规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FirstStrikeRule {
+    /// 攻击方（Side A）先手（默认）
+    #[default]
+    SideA,
+    /// 武学素养较高者先手（相同时攻击方先手）
+    HigherAttainment,
+    /// 防御方（Side B）先手
+    Defender,
+    /// 按种子随机决定先手（同一种子结果可复现）
+    Seeded { seed: u64 },
+}
+
+impl FirstStrikeRule {
+    /// 规则名称（用于战斗日志）
+    pub fn name(&self) -> &'static str {
+        match self {
+            FirstStrikeRule::SideA => "攻击方先手",
+            FirstStrikeRule::HigherAttainment => "武学素养高者先手",
+            FirstStrikeRule::Defender => "防御方先手",
+            FirstStrikeRule::Seeded { .. } => "随机先手",
+        }
+    }
+}
+
+/// 一对多战斗中 Side A 出手时选择目标的规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetRule {
+    /// 按出场顺序攻击（默认）
+    #[default]
+    InOrder,
+    /// 攻击生命最低的敌人
+    LowestHp,
+    /// 攻击基础攻击最高的敌人
+    HighestAttack,
+}
+
+impl TargetRule {
+    /// 规则名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            TargetRule::InOrder => "依次攻击",
+            TargetRule::LowestHp => "先攻生命最低者",
+            TargetRule::HighestAttack => "先攻攻击最高者",
+        }
+    }
+
+    /// 在候选敌人（序号与战斗面板）中选出目标，相同时取序号较小者
+    pub fn select<'a>(
+        &self,
+        candidates: impl IntoIterator<Item = (usize, &'a BattlePanel)>,
+    ) -> Option<usize> {
+        let mut best: Option<(usize, &BattlePanel)> = None;
+        for (index, panel) in candidates {
+            let better = best.is_none_or(|(best_index, best_panel)| match self {
+                TargetRule::InOrder => index < best_index,
+                TargetRule::LowestHp => (panel.hp, index) < (best_panel.hp, best_index),
+                TargetRule::HighestAttack => {
+                    panel.base_attack > best_panel.base_attack
+                        || (panel.base_attack == best_panel.base_attack && index < best_index)
+                }
+            });
+            if better {
+                best = Some((index, panel));
+            }
+        }
+        best.map(|(index, _)| index)
+    }
+}
+
+fn hp_percent(panel: &BattlePanel) -> f64 {
+    if panel.max_hp > 0.0 {
+        panel.hp / panel.max_hp
+    } else {
+        0.0
+    }
+}
+
+/// 空手数值
+///
+/// 未装备武技（或武技被封）时使用，由体魄与武学素养推导：
+/// - 威能 = power + 体魄 × power_per_physique + 武学素养 × power_per_attainment
+/// - 守御 = defense_power + 体魄 × defense_power_per_physique + 武学素养 × defense_power_per_attainment
+/// - 蓄力时间 = max(charge_time - 武学素养 × charge_time_per_attainment, min_charge_time)
+/// - 出手速度 = attack_speed（仅在未装备内功时使用）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UnarmedStats {
+    pub power: f64,
+    pub power_per_physique: f64,
+    pub power_per_attainment: f64,
+    pub defense_power: f64,
+    pub defense_power_per_physique: f64,
+    pub defense_power_per_attainment: f64,
+    pub charge_time: f64,
+    pub charge_time_per_attainment: f64,
+    pub min_charge_time: f64,
+    pub attack_speed: f64,
+}
+
+impl Default for UnarmedStats {
+    fn default() -> Self {
+        Self {
+            power: 1.0,
+            power_per_physique: 0.01,
+            power_per_attainment: 0.01,
+            defense_power: 1.0,
+            defense_power_per_physique: 0.01,
+            defense_power_per_attainment: 0.01,
+            charge_time: 50.0,
+            charge_time_per_attainment: 0.1,
+            min_charge_time: 20.0,
+            attack_speed: 5.0,
+        }
+    }
+}
+
+impl UnarmedStats {
+    /// 空手威能
+    pub fn power_for(&self, physique: u32, attainment: f64) -> f64 {
+        self.power
+            + physique as f64 * self.power_per_physique
+            + attainment * self.power_per_attainment
+    }
+
+    /// 空手守御
+    pub fn defense_power_for(&self, physique: u32, attainment: f64) -> f64 {
+        self.defense_power
+            + physique as f64 * self.defense_power_per_physique
+            + attainment * self.defense_power_per_attainment
+    }
+
+    /// 空手蓄力时间
+    pub fn charge_time_for(&self, attainment: f64) -> f64 {
+        (self.charge_time - attainment * self.charge_time_per_attainment).max(self.min_charge_time)
+    }
+}
+
+/// 基础内息池
+///
+/// 与内功无关、由体魄与根骨推导的内息，和已装备内功各境界的内息增益相加作为内息上限：
+/// - 基础内息 = base + 体魄 × per_physique + 根骨 × per_bone_structure
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BaseQiPool {
+    pub base: f64,
+    pub per_physique: f64,
+    pub per_bone_structure: f64,
+}
+
+impl BaseQiPool {
+    /// 基础内息
+    pub fn amount_for(&self, physique: u32, bone_structure: u32) -> f64 {
+        (self.base
+            + physique as f64 * self.per_physique
+            + bone_structure as f64 * self.per_bone_structure)
+            .max(0.0)
+    }
+}
+
+/// 气势规则
+///
+/// 每回合攻防结算后，击破内息防御时攻击方积累 gain_on_hit 点气势，否则防御方积累
+/// gain_on_defense 点；另一方气势衰减 decay_per_round 点。气势介于 0 与 max 之间，
+/// 词条可在公式中以 self_momentum / opponent_momentum 引用，或以属性 momentum 修改
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MomentumRule {
+    pub gain_on_hit: f64,
+    pub gain_on_defense: f64,
+    pub decay_per_round: f64,
+    pub max: f64,
+}
+
+impl Default for MomentumRule {
+    fn default() -> Self {
+        Self {
+            gain_on_hit: 1.0,
+            gain_on_defense: 1.0,
+            decay_per_round: 1.0,
+            max: 10.0,
+        }
+    }
+}
+
+/// 属性上限与软上限（递减收益），防止词条无限叠加
+///
+/// 属性提升时，超过软上限的部分按 soft_cap_ratio 折算，结果再受硬上限约束；
+/// 可突破上限的效果不受此限制
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AttributeCap {
+    pub target: AttributeTarget,
+    #[serde(default)]
+    pub soft_cap: Option<f64>,
+    /// 超过软上限部分的折算比例
+    #[serde(default = "default_soft_cap_ratio")]
+    pub soft_cap_ratio: f64,
+    #[serde(default)]
+    pub hard_cap: Option<f64>,
+}
+
+fn default_soft_cap_ratio() -> f64 {
+    0.5
+}
+
+impl AttributeCap {
+    /// 将属性从 current 修改为 requested 时实际生效的值（只约束提升）
+    pub fn apply(&self, current: f64, requested: f64) -> f64 {
+        if requested <= current {
+            return requested;
+        }
+        let mut value = requested;
+        if let Some(soft_cap) = self.soft_cap {
+            let start = current.max(soft_cap);
+            if value > start {
+                value = start + (value - start) * self.soft_cap_ratio.clamp(0.0, 1.0);
+            }
+        }
+        if let Some(hard_cap) = self.hard_cap {
+            value = value.min(hard_cap.max(current));
+        }
+        value
+    }
+}
+
+/// 属性被上限约束的记录（调试追踪用）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AttributeClamp {
+    pub character_name: String,
+    pub target: AttributeTarget,
+    /// 约束前的目标值
+    pub requested: f64,
+    /// 实际生效的值
+    pub applied: f64,
+}
+
+/// 数值策略：控制战斗结算每一步的取整方式
+/// 取整仍在浮点数上进行，只吸收运算顺序带来的微小误差，不保证跨平台逐位一致
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NumericPolicy {
+    /// 直接使用浮点数（默认）
+    #[default]
+    Float,
+    /// 每一步结果取整到 1/scale 的倍数（保留 d 位小数即 scale = 10^d）
+    Quantized { scale: u32 },
+}
+
+impl NumericPolicy {
+    /// 按策略规整数值（四舍五入，远离零方向）
+    pub fn quantize(&self, value: f64) -> f64 {
+        match self {
+            NumericPolicy::Float => value,
+            NumericPolicy::Quantized { scale } => {
+                let scale = (*scale).max(1) as f64;
+                (value * scale).round() / scale
+            }
+        }
+    }
+}
+
+/// 战斗配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BattleConfig {
+    /// 最大战斗轮数
+    #[serde(default = "default_max_rounds")]
+    pub max_rounds: u32,
+    /// 达到最大轮数时依次尝试的判定规则（全部无法分出胜负时为平局）
+    #[serde(default)]
+    pub tiebreakers: Vec<TiebreakerRule>,
+    /// 空手数值（未装备武技或武技被封时使用）
+    #[serde(default)]
+    pub unarmed: UnarmedStats,
+    /// 基础内息池（默认为 0，即内息上限完全来自内功）
+    #[serde(default)]
+    pub base_qi: BaseQiPool,
+    /// 属性上限与软上限
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attribute_caps: Vec<AttributeCap>,
+    /// Side A（攻击方）内息输出策略
+    #[serde(default)]
+    pub side_a_qi_profile: QiOutputProfile,
+    /// Side B（防御方）内息输出策略
+    #[serde(default)]
+    pub side_b_qi_profile: QiOutputProfile,
+    /// 数值策略（结算每一步的取整方式）
+    #[serde(default)]
+    pub numeric_policy: NumericPolicy,
+    /// 气势规则
+    #[serde(default)]
+    pub momentum: MomentumRule,
+    /// 双方同时蓄力完成时的先手规则
+    #[serde(default)]
+    pub first_strike: FirstStrikeRule,
+    /// 一对多战斗中 Side A 选择目标的规则
+    #[serde(default)]
+    pub target_rule: TargetRule,
+    /// 装备的功法未加载时的处理方式
+    #[serde(default)]
+    pub missing_manuals: MissingManualPolicy,
+}
+
+fn default_max_rounds() -> u32 {
+    DEFAULT_MAX_ROUNDS
+}
+
+impl Default for BattleConfig {
+    fn default() -> Self {
+        Self {
+            max_rounds: DEFAULT_MAX_ROUNDS,
+            tiebreakers: Vec::new(),
+            unarmed: UnarmedStats::default(),
+            base_qi: BaseQiPool::default(),
+            attribute_caps: Vec::new(),
+            side_a_qi_profile: QiOutputProfile::default(),
+            side_b_qi_profile: QiOutputProfile::default(),
+            numeric_policy: NumericPolicy::default(),
+            momentum: MomentumRule::default(),
+            first_strike: FirstStrikeRule::default(),
+            target_rule: TargetRule::default(),
+            missing_manuals: MissingManualPolicy::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::panel::{CharacterPanel, ThreeDimensional};
+
+    fn panel(name: &str) -> BattlePanel {
+        let char_panel = CharacterPanel::new(name.to_string(), ThreeDimensional::new(10, 8, 12));
+        BattlePanel::from_character_panel(&char_panel)
+    }
+
+    #[test]
+    fn test_higher_hp_percent() {
+        let a = panel("A");
+        let mut b = panel("B");
+        b.hp = b.max_hp * 0.5;
+        assert_eq!(
+            TiebreakerRule::HigherHpPercent.resolve(&a, &b),
+            Some(BattleResult::SideAWin)
+        );
+        assert_eq!(TiebreakerRule::HigherHpPercent.resolve(&a, &a), None);
+    }
+
+    #[test]
+    fn test_attacker_loses() {
+        let a = panel("A");
+        let b = panel("B");
+        assert_eq!(
+            TiebreakerRule::AttackerLoses.resolve(&a, &b),
+            Some(BattleResult::SideBWin)
+        );
+    }
+
+    #[test]
+    fn test_unarmed_stats_scale_and_clamp() {
+        let unarmed = UnarmedStats::default();
+        assert!((unarmed.power_for(10, 20.0) - 1.3).abs() < 1e-9);
+        assert!((unarmed.defense_power_for(0, 0.0) - 1.0).abs() < 1e-9);
+        assert_eq!(unarmed.charge_time_for(100.0), 40.0);
+        assert_eq!(unarmed.charge_time_for(1000.0), 20.0);
+    }
+
+    #[test]
+    fn test_base_qi_pool() {
+        assert_eq!(BaseQiPool::default().amount_for(30, 20), 0.0);
+        let pool = BaseQiPool {
+            base: 10.0,
+            per_physique: 2.0,
+            per_bone_structure: 1.0,
+        };
+        assert_eq!(pool.amount_for(30, 20), 90.0);
+    }
+
+    #[test]
+    fn test_attribute_cap_soft_and_hard() {
+        let cap = AttributeCap {
+            target: AttributeTarget::AttackSpeed,
+            soft_cap: Some(20.0),
+            soft_cap_ratio: 0.5,
+            hard_cap: Some(30.0),
+        };
+        // 软上限以下不折算
+        assert_eq!(cap.apply(10.0, 18.0), 18.0);
+        // 跨越软上限时，超出部分减半
+        assert_eq!(cap.apply(10.0, 30.0), 25.0);
+        assert_eq!(cap.apply(25.0, 35.0), 30.0);
+        // 硬上限
+        assert_eq!(cap.apply(29.0, 50.0), 30.0);
+        // 降低不受影响
+        assert_eq!(cap.apply(40.0, 5.0), 5.0);
+    }
+
+    #[test]
+    fn test_numeric_policy_quantize() {
+        assert_eq!(NumericPolicy::Float.quantize(1.23456), 1.23456);
+        assert_eq!(
+            NumericPolicy::Quantized { scale: 100 }.quantize(1.235001),
+            1.24
+        );
+        assert_eq!(
+            NumericPolicy::Quantized { scale: 1000 }.quantize(-2.0004),
+            -2.0
+        );
+        let config: BattleConfig =
+            serde_json::from_str(r#"{"numeric_policy":{"type":"quantized","scale":8}}"#).unwrap();
+        assert_eq!(config.numeric_policy, NumericPolicy::Quantized { scale: 8 });
+    }
+
+    #[test]
+    fn test_config_defaults_from_json() {
+        let config: BattleConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config, BattleConfig::default());
+    }
+}
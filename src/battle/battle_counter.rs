@@ -0,0 +1,110 @@
+use crate::character::panel::{CharacterPanel, ThreeDimensional};
+use crate::effect::battle_record_template::BattleRecordTemplate;
+use crate::effect::condition::{AttackResult, Condition};
+use crate::effect::formula::{BattleFormulaContext, FormulaCalculator};
+/// 防御武技反击
+/// 防御者以内息挡下攻击（未被击破内息防御）且条件满足时，沿用额外攻击流程对攻击者反击一次，
+/// 战斗记录归于防御武技
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 反击配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CounterAttack {
+    /// 反击输出公式（可使用 attack_reduced_output 等本次攻防结果变量）
+    pub output: String,
+    /// 反击条件（战斗条件，为空时只要以内息挡下攻击即反击）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<Condition>,
+    /// 反击记录模板（支持 {self_name}、{opponent_name}、{output}、{hp_damage} 等占位符）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub battle_record_template: Option<BattleRecordTemplate>,
+}
+
+impl CounterAttack {
+    pub fn validate(&self) -> WushenResult<()> {
+        // 以空白面板与空白攻防结果试算，提前发现公式语法错误与未知变量
+        let panel = CharacterPanel::new(String::new(), ThreeDimensional::new(0, 0, 0));
+        let context = BattleFormulaContext {
+            self_panel: panel.clone(),
+            opponent_panel: Some(panel),
+            attack_result: Some(AttackResult {
+                total_output: 0.0,
+                total_defense: 0.0,
+                reduced_output: 0.0,
+                hp_damage: 0.0,
+                attacker_qi_consumed: 0.0,
+                defender_qi_consumed: 0.0,
+                broke_qi_defense: false,
+            }),
+            environment: BTreeMap::new(),
+        };
+        FormulaCalculator::evaluate_battle(&self.output, &context)
+            .map_err(|e| WushenError::Invalid(format!("反击输出公式无效: {}", e)))?;
+        Ok(())
+    }
+
+    /// 未配置记录模板时的默认记录
+    pub fn default_record_template(skill_name: &str) -> BattleRecordTemplate {
+        BattleRecordTemplate::new(format!(
+            "{{self_name}}以「{}」反击，对{{opponent_name}}造成了{{hp_damage}}点生命值伤害",
+            skill_name
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::battle::battle_config::BattleConfig;
+    use crate::battle::battle_engine::BattleEngine;
+    use crate::battle::battle_record::BattleRecord;
+    use crate::battle::battle_state::Side;
+    use crate::effect::executor::EntryExecutor;
+
+    #[test]
+    fn counter_hits_back_after_qi_defense() {
+        let counter: CounterAttack = serde_json::from_value(serde_json::json!({
+            "output": "attack_reduced_output + 1000",
+            "battle_record_template": { "template": "{self_name}借力打力，{opponent_name}受创{hp_damage}" }
+        }))
+        .unwrap();
+        counter.validate().unwrap();
+        let mut invalid = counter.clone();
+        invalid.output = "unknown_value * 2".to_string();
+        assert!(invalid.validate().is_err());
+
+        let side_a = CharacterPanel::new("角色A".to_string(), ThreeDimensional::new(10, 8, 12));
+        let side_b = CharacterPanel::new("角色B".to_string(), ThreeDimensional::new(10, 8, 12));
+        let mut engine =
+            BattleEngine::new(&side_a, &side_b, EntryExecutor::new(), EntryExecutor::new());
+        engine.set_config(BattleConfig {
+            max_rounds: 20,
+            ..BattleConfig::default()
+        });
+        let panel = engine.side_panel_mut(Side::B);
+        panel.max_qi = 10000.0;
+        panel.qi = 10000.0;
+        panel.defense_power = 10.0;
+        panel.defense_skill_name = Some("金钟罩".to_string());
+        engine.set_side_b_defense_counter(Some(counter));
+        engine.run();
+
+        let counters: Vec<&str> = engine
+            .get_log()
+            .get_all_records()
+            .iter()
+            .filter_map(|record| match record {
+                BattleRecord::ExtraAttack {
+                    entry_id,
+                    description,
+                    ..
+                } if entry_id == "defense_skill_counter" => Some(description.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert!(!counters.is_empty());
+        assert!(counters[0].starts_with("角色B借力打力，角色A受创"));
+    }
+}
@@ -1,9 +1,22 @@
 use super::{
     action_bar::ActionBar,
+    action_bar::ActionReady,
     battle_calculator::{BattleCalculationResult, BattleCalculator},
+    battle_config::{AttributeClamp, BattleConfig, FirstStrikeRule},
+    battle_counter::CounterAttack,
+    battle_interrupt::BattleInterrupt,
     battle_panel::BattlePanel,
-    battle_record::{BattleLog, BattleLogKind, BattleRecord, PanelDelta},
+    battle_phase::{sort_phases, BattlePhase},
+    battle_record::{
+        BattleFlavor, BattleLog, BattleLogKind, BattleRecord, BattleSummary, LogPolicy, PanelDelta,
+    },
+    battle_replay::{BattleProvenance, BattleReplay, ReplaySide, BATTLE_REPLAY_VERSION},
+    battle_roster::{BenchedEnemy, EnemyOutcome},
+    battle_setup::UnresolvedManual,
     battle_state::{BattleResult, BattleState, Side},
+    battle_training::{TrainingMode, TrainingScore, TrainingStats},
+    battle_ultimate::UltimateMove,
+    qi_strategy::QiOutputProfile,
 };
 /// 战斗引擎
 /// 主控制器，协调所有系统
@@ -11,13 +24,17 @@ use crate::character::panel::CharacterPanel;
 use crate::effect::{
     battle_record_template::BattleRecordTemplate,
     condition::{AttackResult, BattleContext},
+    dry_run::EntryDryRun,
     effect::{AttributeTarget, Effect, FormulaValue, Operation, PanelTarget},
-    executor::{EntryEffect, EntryExecutor},
+    entry::Entry,
+    executor::{EntryEffect, EntryExecutor, SkippedEntry},
     formula::{BattleFormulaContext, FormulaCalculator},
     trigger::Trigger,
 };
+use std::collections::BTreeMap;
 
 /// 战斗引擎
+#[derive(Clone)]
 pub struct BattleEngine {
     // ========== 战斗双方面板（整场战斗固定） ==========
     /// Side A 战斗面板
@@ -74,10 +91,54 @@ pub struct BattleEngine {
     next_effect_batch_id: u64,
     /// 当前词条效果批次ID
     current_effect_batch_id: Option<u64>,
+    /// 日志保留策略
+    log_policy: LogPolicy,
+    /// Side A 累计受到的生命伤害
+    side_a_damage_taken: f64,
+    /// Side B 累计受到的生命伤害
+    side_b_damage_taken: f64,
+    /// Side A 开场/收场文本
+    side_a_flavor: BattleFlavor,
+    /// Side B 开场/收场文本
+    side_b_flavor: BattleFlavor,
+    /// Side A 尚未触发的阶段（按阈值从高到低）
+    side_a_phases: Vec<BattlePhase>,
+    /// Side B 尚未触发的阶段（按阈值从高到低）
+    side_b_phases: Vec<BattlePhase>,
+    /// 剧情打断条件（按顺序检查）
+    interrupts: Vec<BattleInterrupt>,
+    /// 实际触发的打断标识
+    interrupted_by: Option<String>,
+    /// 属性上限约束记录（调试追踪）
+    clamp_trace: Vec<AttributeClamp>,
+    /// 训练模式（Side A 不会落败，达到训练轮数后结束）
+    training: Option<TrainingMode>,
+    /// 训练过程统计
+    training_stats: TrainingStats,
+    /// 战斗环境变量（词条公式以 `env_` 前缀引用）
+    environment: BTreeMap<String, f64>,
+    /// Side A 本场已施展绝招的次数
+    side_a_ultimate_uses: u32,
+    /// Side B 本场已施展绝招的次数
+    side_b_ultimate_uses: u32,
+    /// 随机先手规则的随机数状态
+    first_strike_rng: u64,
+    /// 开战时加载内容的指纹摘要（记录在回放与溯源信息中）
+    content_digest: Option<String>,
+    /// 开战时未能解析的装备功法（按未装备参战）
+    unresolved_manuals: Vec<UnresolvedManual>,
+
+    // ========== 一对多战斗 ==========
+    /// Side B 在场敌人的出场序号
+    side_b_index: usize,
+    /// Side B 在场下等候或已落败的其余敌人
+    side_b_bench: Vec<BenchedEnemy>,
+
+    // ========== 配置 ==========
+    /// 战斗配置（最大轮数、平局判定规则）
+    config: BattleConfig,
 }
 
-/// 最大战斗轮数
-const MAX_ROUNDS: u32 = 100;
 /// 行动条时间步长
 const TIME_STEP: f64 = 0.1;
 
@@ -95,9 +156,42 @@ impl BattleEngine {
         side_a_executor: EntryExecutor,
         side_b_executor: EntryExecutor,
     ) -> Self {
-        let side_a_panel = BattlePanel::from_character_panel(side_a);
-        let side_b_panel = BattlePanel::from_character_panel(side_b);
+        Self::with_config(
+            side_a,
+            side_b,
+            side_a_executor,
+            side_b_executor,
+            BattleConfig::default(),
+        )
+    }
 
+    /// 使用指定战斗配置创建战斗引擎（空手数值在创建战斗面板时生效）
+    pub fn with_config(
+        side_a: &CharacterPanel,
+        side_b: &CharacterPanel,
+        side_a_executor: EntryExecutor,
+        side_b_executor: EntryExecutor,
+        config: BattleConfig,
+    ) -> Self {
+        let side_a_panel = BattlePanel::from_character_panel_with_unarmed(side_a, &config.unarmed);
+        let side_b_panel = BattlePanel::from_character_panel_with_unarmed(side_b, &config.unarmed);
+        Self::from_battle_panels(
+            side_a_panel,
+            side_b_panel,
+            side_a_executor,
+            side_b_executor,
+            config,
+        )
+    }
+
+    /// 以已创建的战斗面板创建战斗引擎
+    fn from_battle_panels(
+        side_a_panel: BattlePanel,
+        side_b_panel: BattlePanel,
+        side_a_executor: EntryExecutor,
+        side_b_executor: EntryExecutor,
+        config: BattleConfig,
+    ) -> Self {
         let side_a_base_charge_time = side_a_panel.charge_time;
         let side_b_base_charge_time = side_b_panel.charge_time;
 
@@ -130,6 +224,260 @@ impl BattleEngine {
             log: BattleLog::new(),
             next_effect_batch_id: 0,
             current_effect_batch_id: None,
+            log_policy: LogPolicy::default(),
+            side_a_damage_taken: 0.0,
+            side_b_damage_taken: 0.0,
+            side_a_flavor: BattleFlavor::default(),
+            side_b_flavor: BattleFlavor::default(),
+            side_a_phases: Vec::new(),
+            side_b_phases: Vec::new(),
+            interrupts: Vec::new(),
+            interrupted_by: None,
+            clamp_trace: Vec::new(),
+            training: None,
+            training_stats: TrainingStats::default(),
+            environment: BTreeMap::new(),
+            side_a_ultimate_uses: 0,
+            side_b_ultimate_uses: 0,
+            first_strike_rng: 0,
+            content_digest: None,
+            unresolved_manuals: Vec::new(),
+            side_b_index: 0,
+            side_b_bench: Vec::new(),
+            config,
+        }
+    }
+
+    /// 按回放重建战斗引擎（尚未开始战斗）
+    pub fn from_replay(replay: &BattleReplay) -> Self {
+        let mut config = replay.config.clone();
+        if let FirstStrikeRule::Seeded { .. } = config.first_strike {
+            config.first_strike = FirstStrikeRule::Seeded { seed: replay.seed };
+        }
+        let mut engine = Self::from_battle_panels(
+            replay.side_a.base_panel.clone(),
+            replay.side_b.base_panel.clone(),
+            replay.side_a.executor(),
+            replay.side_b.executor(),
+            config,
+        );
+        engine.side_a_panel = replay.side_a.panel.clone();
+        engine.side_b_panel = replay.side_b.panel.clone();
+        engine.side_a_flavor = replay.side_a.flavor.clone();
+        engine.side_b_flavor = replay.side_b.flavor.clone();
+        engine.set_side_a_phases(replay.side_a.phases.clone());
+        engine.set_side_b_phases(replay.side_b.phases.clone());
+        engine.interrupts = replay.interrupts.clone();
+        engine.training = replay.training;
+        engine.environment = replay.environment.clone();
+        engine.log_policy = replay.log_policy;
+        engine.content_digest = replay.content_digest.clone();
+        for (position, side) in replay.extra_enemies.iter().enumerate() {
+            let mut enemy = BenchedEnemy::new(
+                position + 1,
+                side.base_panel.clone(),
+                side.base_panel.clone(),
+                side.executor(),
+                side.flavor.clone(),
+                side.phases.clone(),
+            );
+            enemy.panel = side.panel.clone();
+            engine.side_b_bench.push(enemy);
+        }
+        engine
+    }
+
+    /// 按回放确定性地重新执行战斗，返回战斗日志
+    pub fn replay(replay: &BattleReplay) -> BattleLog {
+        let mut engine = Self::from_replay(replay);
+        engine.run();
+        engine.log
+    }
+
+    /// 记录战斗回放（需在完成全部设置之后、战斗开始之前调用）
+    pub fn capture_replay(&self) -> BattleReplay {
+        BattleReplay {
+            version: BATTLE_REPLAY_VERSION,
+            side_a: ReplaySide::capture(
+                &self.side_a_panel,
+                &self.side_a_base,
+                &self.side_a_executor,
+                &self.side_a_flavor,
+                &self.side_a_phases,
+            ),
+            side_b: ReplaySide::capture(
+                &self.side_b_panel,
+                &self.side_b_base,
+                &self.side_b_executor,
+                &self.side_b_flavor,
+                &self.side_b_phases,
+            ),
+            extra_enemies: self
+                .side_b_bench
+                .iter()
+                .map(|enemy| {
+                    ReplaySide::capture(
+                        &enemy.panel,
+                        &enemy.base,
+                        &enemy.executor,
+                        &enemy.flavor,
+                        &enemy.phases,
+                    )
+                })
+                .collect(),
+            config: self.config.clone(),
+            seed: self.provenance().seed,
+            environment: self.environment.clone(),
+            interrupts: self.interrupts.clone(),
+            training: self.training,
+            log_policy: self.log_policy,
+            content_digest: self.content_digest.clone(),
+        }
+    }
+
+    /// 一对多战斗：以另一场已装配好的战斗中的 Side B（面板、词条、文本与阶段）作为追加的敌人
+    /// 需在战斗开始之前调用；返回该敌人的出场序号
+    pub fn add_enemy(&mut self, other: BattleEngine) -> usize {
+        let index = self.side_b_bench.len() + 1;
+        self.unresolved_manuals.extend(
+            other
+                .unresolved_manuals
+                .into_iter()
+                .filter(|manual| manual.side == Side::B),
+        );
+        let mut enemy = BenchedEnemy::new(
+            index,
+            other.side_b_panel,
+            other.side_b_base,
+            other.side_b_executor,
+            other.side_b_flavor,
+            other.side_b_phases,
+        );
+        enemy.charge_time = other.action_bar.side_b_charge_time;
+        self.side_b_bench.push(enemy);
+        index
+    }
+
+    /// 一对多战斗中各敌人的结果（按出场序号排列；单个敌人时只有一项）
+    pub fn enemy_outcomes(&self) -> Vec<EnemyOutcome> {
+        let outcome = |index: usize, panel: &BattlePanel, damage_taken: f64| EnemyOutcome {
+            index,
+            name: panel.name.clone(),
+            defeated: panel.hp <= 0.0,
+            hp: panel.hp,
+            max_hp: panel.max_hp,
+            damage_taken,
+        };
+        let mut outcomes = vec![outcome(
+            self.side_b_index,
+            &self.side_b_panel,
+            self.side_b_damage_taken,
+        )];
+        outcomes.extend(
+            self.side_b_bench
+                .iter()
+                .map(|enemy| outcome(enemy.index, &enemy.panel, enemy.damage_taken)),
+        );
+        outcomes.sort_by_key(|outcome| outcome.index);
+        outcomes
+    }
+
+    /// 设置开战时加载内容的指纹摘要
+    pub fn set_content_digest(&mut self, digest: impl Into<String>) {
+        self.content_digest = Some(digest.into());
+    }
+
+    /// 记录开战时未能解析的装备功法
+    pub fn set_unresolved_manuals(&mut self, unresolved: Vec<UnresolvedManual>) {
+        self.unresolved_manuals = unresolved;
+    }
+
+    /// 开战时未能解析的装备功法
+    pub fn unresolved_manuals(&self) -> &[UnresolvedManual] {
+        &self.unresolved_manuals
+    }
+
+    /// 战斗溯源信息（引擎版本、内容指纹、随机种子与配置摘要）
+    pub fn provenance(&self) -> BattleProvenance {
+        BattleProvenance::new(
+            &self.config,
+            self.content_digest.clone(),
+            self.first_strike_rng,
+        )
+    }
+
+    /// 以当前面板试算一方的单个词条（不触发、不改变战斗状态）
+    /// 攻击结果仅在攻击后/防御后有意义，条件与公式中的攻击结果均取自该参数
+    pub fn dry_run_entry(
+        &self,
+        side: Side,
+        entry: &Entry,
+        attack_result: Option<AttackResult>,
+    ) -> EntryDryRun {
+        let mut context = self.create_battle_context(side);
+        context.attack_result = attack_result;
+        if let Some(result) = attack_result {
+            match entry.trigger {
+                Trigger::AfterAttack => {
+                    context.attack_broke_qi_defense = Some(result.broke_qi_defense)
+                }
+                Trigger::AfterDefense => {
+                    context.successfully_defended_with_qi = Some(!result.broke_qi_defense)
+                }
+                _ => {}
+            }
+        }
+        let condition_met = entry
+            .condition
+            .as_ref()
+            .is_none_or(|condition| condition.check_battle(&context));
+
+        let self_panel = Self::battle_panel_to_character_panel(self.get_panel(side));
+        let opponent_panel = Self::battle_panel_to_character_panel(self.get_panel(side.opposite()));
+        let formula_context = BattleFormulaContext {
+            self_panel: self_panel.clone(),
+            opponent_panel: Some(opponent_panel.clone()),
+            attack_result,
+            environment: self.environment.clone(),
+        };
+        EntryDryRun::evaluate(
+            entry,
+            condition_met,
+            &self_panel,
+            Some(&opponent_panel),
+            |formula| FormulaCalculator::evaluate_battle(formula, &formula_context),
+        )
+    }
+
+    /// 设置战斗配置（需在战斗开始前调用；空手数值需通过 `with_config` 在创建时指定）
+    pub fn set_config(&mut self, config: BattleConfig) {
+        self.config = config;
+    }
+
+    /// 获取战斗配置
+    pub fn get_config(&self) -> &BattleConfig {
+        &self.config
+    }
+
+    /// 设置日志保留策略（需在战斗开始前调用）
+    pub fn set_log_policy(&mut self, policy: LogPolicy) {
+        self.log_policy = policy;
+    }
+
+    /// 战斗摘要（不依赖日志）
+    pub fn summary(&self) -> BattleSummary {
+        BattleSummary {
+            result: self.state.get_result(),
+            rounds: self.round,
+            side_a_hp: self.side_a_panel.hp,
+            side_a_max_hp: self.side_a_panel.max_hp,
+            side_a_qi: self.side_a_panel.qi,
+            side_b_hp: self.side_b_panel.hp,
+            side_b_max_hp: self.side_b_panel.max_hp,
+            side_b_qi: self.side_b_panel.qi,
+            side_a_damage_taken: self.side_a_damage_taken,
+            side_b_damage_taken: self.side_b_damage_taken,
+            provenance: self.provenance(),
         }
     }
 
@@ -163,10 +511,47 @@ impl BattleEngine {
         }
     }
 
+    /// 推进一个回合（首次调用时先完成初始化与战斗开始词条），直到回合结束或战斗结束
+    pub fn step_round(&mut self) {
+        let round = self.round;
+        while !self.state.is_finished() {
+            self.step();
+            let between_rounds = matches!(
+                self.state,
+                BattleState::ActionBarAdvancing | BattleState::RoundStarting { .. }
+            );
+            if self.round > round && between_rounds {
+                break;
+            }
+        }
+    }
+
+    /// 战斗中途调整一方的内息输出（限制在最大内息输出以内），此后该方不再按策略自动调整
+    /// 返回调整后的内息输出；在下一回合开始时生效
+    pub fn set_qi_output_rate(&mut self, side: Side, rate: f64) -> f64 {
+        match side {
+            Side::A => self.config.side_a_qi_profile = QiOutputProfile::Fixed,
+            Side::B => self.config.side_b_qi_profile = QiOutputProfile::Fixed,
+        }
+        let panel = self.get_panel_mut(side);
+        panel.qi_output_rate = rate.max(0.0).min(panel.max_qi_output_rate);
+        panel.qi_output_rate
+    }
+
     // ==================== 状态处理方法 ====================
 
     /// 处理初始化阶段
     fn handle_initializing(&mut self) {
+        // 气势上限以战斗配置为准
+        let max_momentum = self.config.momentum.max.max(0.0);
+        self.side_a_panel.max_momentum = max_momentum;
+        self.side_b_panel.max_momentum = max_momentum;
+
+        // 随机先手以配置的种子开始（种子为 0 时使用固定的非零初值）
+        if let FirstStrikeRule::Seeded { seed } = self.config.first_strike {
+            self.first_strike_rng = if seed == 0 { 0x9E3779B97F4A7C15 } else { seed };
+        }
+
         // 初始化完成，进入战斗开始特效阶段
         self.state = BattleState::BattleStartEffects;
     }
@@ -192,13 +577,38 @@ impl BattleEngine {
         self.apply_effects(side_a_effects, Side::A, None);
         self.apply_effects(side_b_effects, Side::B, None);
 
+        // 其余敌人依次上场触发战斗开始词条，再换回首位敌人
+        for position in 0..self.side_b_bench.len() {
+            self.switch_enemy(position, String::new());
+            let context = self.create_battle_context(Side::B);
+            let effects = self.side_b_executor.trigger_battle_with_source(
+                Trigger::BattleStart,
+                &mut (),
+                &context,
+            );
+            self.apply_effects(effects, Side::B, None);
+            self.switch_enemy(position, String::new());
+        }
+
         // 记录战斗开始
         self.record_with_delta(BattleRecord::BattleStart {
             side_a_name: self.side_a_panel.name.clone(),
             side_b_name: self.side_b_panel.name.clone(),
+            first_strike: self.config.first_strike,
             side_a_panel_delta: None,
             side_b_panel_delta: None,
         });
+        for side in [Side::A, Side::B] {
+            let intro = self.flavor(side).intro.clone();
+            self.record_flavor_text(side, "battle_intro", intro);
+        }
+        for position in 0..self.side_b_bench.len() {
+            let name = self.side_b_bench[position].panel.name.clone();
+            self.switch_enemy(position, format!("{} 也加入了战斗！", name));
+            let intro = self.side_b_flavor.intro.clone();
+            self.record_flavor_text(Side::B, "battle_intro", intro);
+            self.switch_enemy(position, String::new());
+        }
 
         // 进入行动条推进阶段
         self.state = BattleState::ActionBarAdvancing;
@@ -212,17 +622,150 @@ impl BattleEngine {
             self.side_b_panel.attack_speed,
             TIME_STEP,
         );
+        for enemy in self.side_b_bench.iter_mut() {
+            if !enemy.is_defeated() {
+                enemy.progress += enemy.panel.attack_speed * TIME_STEP;
+            }
+        }
 
-        // 检查是否有人可以行动
-        let ready = self.action_bar.check_action_ready();
+        // 检查是否有人可以行动（双方同时就绪时按先手规则决定；场下敌人就绪视同 Side B 就绪）
+        let mut ready = self.action_bar.check_action_ready();
+        if self.side_b_bench.iter().any(BenchedEnemy::is_ready) {
+            ready = match ready {
+                ActionReady::SideA | ActionReady::Both => ActionReady::Both,
+                ActionReady::SideB | ActionReady::None => ActionReady::SideB,
+            };
+        }
+        let attacker = match ready {
+            ActionReady::Both => Some(self.resolve_first_strike()),
+            _ => ready.to_side(),
+        };
 
-        if let Some(attacker) = ready.to_side() {
+        if let Some(attacker) = attacker {
             // 有人可以行动，进入回合开始
-            self.state = BattleState::RoundStarting { attacker };
+            self.begin_round(attacker);
         }
         // 否则继续推进（保持当前状态）
     }
 
+    /// 进入回合开始：Side A 出手时按目标规则换上目标，Side B 出手时换上蓄力完成的敌人
+    fn begin_round(&mut self, attacker: Side) {
+        let position = match attacker {
+            Side::A => self.target_position(),
+            Side::B if self.action_bar.is_ready(Side::B) => None,
+            Side::B => self.side_b_bench.iter().position(BenchedEnemy::is_ready),
+        };
+        if let Some(position) = position {
+            let name = self.side_b_bench[position].panel.name.clone();
+            let description = match attacker {
+                Side::A => format!("{} 转而攻向 {}", self.side_a_panel.name, name),
+                Side::B => format!("{} 抢上前来出手", name),
+            };
+            self.switch_enemy(position, description);
+        }
+        self.state = BattleState::RoundStarting { attacker };
+    }
+
+    /// Side A 的目标在场下时的位置（目标已在场时为空）
+    fn target_position(&self) -> Option<usize> {
+        if self.side_b_bench.is_empty() {
+            return None;
+        }
+        let on_field =
+            (self.side_b_panel.hp > 0.0).then_some((self.side_b_index, &self.side_b_panel));
+        let benched = self
+            .side_b_bench
+            .iter()
+            .filter(|enemy| !enemy.is_defeated())
+            .map(|enemy| (enemy.index, &enemy.panel));
+        let target = self
+            .config
+            .target_rule
+            .select(on_field.into_iter().chain(benched))?;
+        self.side_b_bench
+            .iter()
+            .position(|enemy| enemy.index == target)
+    }
+
+    /// 在场敌人与场下敌人互换（行动条进度一并互换），描述为空时不输出日志
+    fn switch_enemy(&mut self, position: usize, description: String) {
+        let enemy = &mut self.side_b_bench[position];
+        std::mem::swap(&mut self.side_b_index, &mut enemy.index);
+        std::mem::swap(&mut self.side_b_panel, &mut enemy.panel);
+        std::mem::swap(&mut self.side_b_base, &mut enemy.base);
+        std::mem::swap(&mut self.last_side_b_panel, &mut enemy.last_panel);
+        std::mem::swap(
+            &mut self.side_b_base_charge_time,
+            &mut enemy.base_charge_time,
+        );
+        std::mem::swap(&mut self.side_b_executor, &mut enemy.executor);
+        std::mem::swap(&mut self.side_b_damage_taken, &mut enemy.damage_taken);
+        std::mem::swap(&mut self.side_b_flavor, &mut enemy.flavor);
+        std::mem::swap(&mut self.side_b_phases, &mut enemy.phases);
+        std::mem::swap(&mut self.side_b_ultimate_uses, &mut enemy.ultimate_uses);
+        std::mem::swap(&mut self.action_bar.side_b_progress, &mut enemy.progress);
+        std::mem::swap(
+            &mut self.action_bar.side_b_charge_time,
+            &mut enemy.charge_time,
+        );
+        self.record_with_delta(BattleRecord::EnemySwitch {
+            enemy_name: self.side_b_panel.name.clone(),
+            description,
+            side_a_panel_delta: None,
+            side_b_panel_delta: None,
+        });
+    }
+
+    /// 在场敌人落败后换上出场序号最小的场下敌人（没有可换的敌人时返回 false）
+    fn replace_defeated_enemy(&mut self) -> bool {
+        if self.side_b_panel.hp > 0.0 {
+            return false;
+        }
+        let next = self
+            .side_b_bench
+            .iter()
+            .enumerate()
+            .filter(|(_, enemy)| !enemy.is_defeated())
+            .min_by_key(|(_, enemy)| enemy.index)
+            .map(|(position, _)| position);
+        let Some(position) = next else {
+            return false;
+        };
+        let name = self.side_b_bench[position].panel.name.clone();
+        self.switch_enemy(position, format!("{} 接着上阵！", name));
+        true
+    }
+
+    /// 双方同时蓄力完成时按先手规则决定出手方
+    fn resolve_first_strike(&mut self) -> Side {
+        match self.config.first_strike {
+            FirstStrikeRule::SideA => Side::A,
+            FirstStrikeRule::Defender => Side::B,
+            FirstStrikeRule::HigherAttainment => {
+                if self.side_b_panel.martial_arts_attainment
+                    > self.side_a_panel.martial_arts_attainment
+                {
+                    Side::B
+                } else {
+                    Side::A
+                }
+            }
+            FirstStrikeRule::Seeded { .. } => {
+                // xorshift64，与存档随机数同算法，保证同一种子结果可复现
+                let mut x = self.first_strike_rng;
+                x ^= x << 13;
+                x ^= x >> 7;
+                x ^= x << 17;
+                self.first_strike_rng = x;
+                if x & 1 == 0 {
+                    Side::A
+                } else {
+                    Side::B
+                }
+            }
+        }
+    }
+
     /// 处理回合开始（确定攻防，创建临时面板）
     fn handle_round_starting(&mut self, attacker: Side) {
         self.round += 1;
@@ -232,10 +775,23 @@ impl BattleEngine {
         // 缓存攻击者当前蓄力时间，便于攻击后重置
         self.cache_base_charge_time(attacker);
 
+        // 按策略调整双方本回合的内息输出
+        self.apply_qi_profile(Side::A);
+        self.apply_qi_profile(Side::B);
+
         // 从战斗面板复制创建临时面板
         self.attacker_temp = Some(self.get_panel(attacker).clone());
         self.defender_temp = Some(self.get_panel(defender).clone());
 
+        // 武技被封时以空手数值应对
+        let unarmed = self.config.unarmed;
+        if let Some(temp) = self.attacker_temp.as_mut() {
+            temp.apply_seals(&unarmed);
+        }
+        if let Some(temp) = self.defender_temp.as_mut() {
+            temp.apply_seals(&unarmed);
+        }
+
         // 记录回合开始
         let attacker_name = self.get_panel(attacker).name.clone();
         let defender_name = self.get_panel(defender).name.clone();
@@ -267,19 +823,36 @@ impl BattleEngine {
         );
         self.apply_effects(effects, attacker, None);
 
-        // 输出攻击武技日志
+        // 满足条件时以绝招代替普通攻击
+        let ultimate = self.prepare_ultimate(attacker);
+
+        // 输出攻击武技日志（施展绝招时输出绝招日志）
         if let Some(ref temp) = self.attacker_temp {
-            if let Some(ref template) = temp.attack_skill_log_template {
+            let (entry_id, template) = match &ultimate {
+                Some(ultimate) => (
+                    "attack_skill_ultimate",
+                    Some(
+                        ultimate
+                            .log_template
+                            .clone()
+                            .unwrap_or_else(|| ultimate.default_log_template()),
+                    ),
+                ),
+                None => ("attack_skill_log", temp.attack_skill_log_template.clone()),
+            };
+            if let Some(template) = template {
                 let attacker_name = temp.name.clone();
                 let defender_name = self
                     .defender_temp
                     .as_ref()
                     .map(|p| p.name.clone())
                     .unwrap_or_default();
-                let log_text = Self::replace_log_template(template, &attacker_name, &defender_name);
+                let log_text =
+                    Self::replace_log_template(&template, &attacker_name, &defender_name);
                 self.record_with_delta(BattleRecord::EntryTriggered {
-                    entry_id: "attack_skill_log".to_string(),
+                    entry_id: entry_id.to_string(),
                     entry_order: 0,
+                    source: None,
                     description: log_text,
                     log_kind: BattleLogKind::Effect,
                     batch_id: None,
@@ -293,6 +866,35 @@ impl BattleEngine {
         self.state = BattleState::BeforeDefense;
     }
 
+    /// 判断攻击者能否施展绝招；能则按绝招公式替换本回合威能并扣除内息，返回施展的绝招
+    fn prepare_ultimate(&mut self, side: Side) -> Option<UltimateMove> {
+        let temp = self.attacker_temp.as_ref()?;
+        let ultimate = temp.attack_skill_ultimate.clone()?;
+        let uses = match side {
+            Side::A => self.side_a_ultimate_uses,
+            Side::B => self.side_b_ultimate_uses,
+        };
+        if temp.qi < ultimate.qi_threshold || ultimate.max_uses.is_some_and(|max| uses >= max) {
+            return None;
+        }
+        if let Some(condition) = &ultimate.condition {
+            if !condition.check_battle(&self.create_battle_context(side)) {
+                return None;
+            }
+        }
+        let power = self.calculate_formula_value(&ultimate.power_formula, side, None);
+
+        let temp = self.attacker_temp.as_mut()?;
+        temp.power = power.max(0.0);
+        temp.qi = (temp.qi - ultimate.qi_cost).max(0.0);
+        temp.attack_skill_name = Some(ultimate.name.clone());
+        match side {
+            Side::A => self.side_a_ultimate_uses += 1,
+            Side::B => self.side_b_ultimate_uses += 1,
+        }
+        Some(ultimate)
+    }
+
     /// 处理防御者防御前
     fn handle_before_defense(&mut self) {
         let attacker = self.current_attacker.expect("回合中必须有攻击者");
@@ -311,6 +913,7 @@ impl BattleEngine {
                 self.record_with_delta(BattleRecord::EntryTriggered {
                     entry_id: "defense_skill_log".to_string(),
                     entry_order: 0,
+                    source: None,
                     description: log_text,
                     log_kind: BattleLogKind::Effect,
                     batch_id: None,
@@ -346,25 +949,49 @@ impl BattleEngine {
             .expect("结算时必须有防御者临时面板");
 
         // 执行战斗结算
-        let result = BattleCalculator::calculate_battle(attacker_temp, defender_temp);
+        let result = BattleCalculator::calculate_battle_with_policy(
+            attacker_temp,
+            defender_temp,
+            self.config.numeric_policy,
+        );
 
-        // 获取名称用于记录
-        let attacker_name = attacker_temp.name.clone();
-        let attacker_skill = attacker_temp.attack_skill_name.clone();
-        let defender_name = defender_temp.name.clone();
-        let defender_skill = defender_temp.defense_skill_name.clone();
+        // 累计受到的伤害（用于战斗摘要）
+        let defender_side = self
+            .current_attacker
+            .expect("回合中必须有攻击者")
+            .opposite();
+        self.add_damage_taken(defender_side, result.hp_damage);
+        self.record_training_exchange(defender_side, result.broke_qi_defense);
+
+        // 攻防得手的一方积累气势（变化量随结算记录输出）
+        self.update_momentum(defender_side.opposite(), result.broke_qi_defense);
 
         // 记录结算结果
-        self.record_with_delta(BattleRecord::CalculationResult {
-            attacker_name,
-            attacker_skill,
-            defender_name,
-            defender_skill,
-            result,
-            description: String::new(),
-            side_a_panel_delta: None,
-            side_b_panel_delta: None,
-        });
+        if self.log_policy.records_details() {
+            let attacker_temp = self
+                .attacker_temp
+                .as_ref()
+                .expect("结算时必须有攻击者临时面板");
+            let defender_temp = self
+                .defender_temp
+                .as_ref()
+                .expect("结算时必须有防御者临时面板");
+            let attacker_name = attacker_temp.name.clone();
+            let attacker_skill = attacker_temp.attack_skill_name.clone();
+            let defender_name = defender_temp.name.clone();
+            let defender_skill = defender_temp.defense_skill_name.clone();
+
+            self.record_with_delta(BattleRecord::CalculationResult {
+                attacker_name,
+                attacker_skill,
+                defender_name,
+                defender_skill,
+                result,
+                description: String::new(),
+                side_a_panel_delta: None,
+                side_b_panel_delta: None,
+            });
+        }
 
         // 检查是否有人死亡
         if self.check_battle_end() {
@@ -377,6 +1004,23 @@ impl BattleEngine {
         };
     }
 
+    /// 结算后更新气势：击破内息防御时攻击方积累气势，否则防御方积累；另一方气势衰减
+    fn update_momentum(&mut self, attacker: Side, broke_qi_defense: bool) {
+        let rule = self.config.momentum;
+        let (winner, gain) = if broke_qi_defense {
+            (attacker, rule.gain_on_hit)
+        } else {
+            (attacker.opposite(), rule.gain_on_defense)
+        };
+        for (side, delta) in [(winner, gain), (winner.opposite(), -rule.decay_per_round)] {
+            let panel = self.get_panel_mut(side);
+            panel.momentum = (panel.momentum + delta).clamp(0.0, panel.max_momentum);
+            if let Some(temp) = self.get_temp_panel_mut_by_side(side) {
+                temp.momentum = (temp.momentum + delta).clamp(0.0, temp.max_momentum);
+            }
+        }
+    }
+
     /// 处理攻击者攻击后
     fn handle_after_attack(&mut self, calculation_result: BattleCalculationResult) {
         let attacker = self.current_attacker.expect("回合中必须有攻击者");
@@ -445,10 +1089,57 @@ impl BattleEngine {
             return;
         }
 
+        // 以内息挡下攻击时，防御武技反击
+        if !calculation_result.broke_qi_defense {
+            self.handle_counter_attack(defender, &calculation_result, &context);
+            if self.check_battle_end() {
+                return;
+            }
+        }
+
         // 进入回合结束阶段
         self.state = BattleState::RoundEnding;
     }
 
+    /// 防御武技反击：条件满足时沿用额外攻击流程对攻击者反击，记录归于防御武技
+    fn handle_counter_attack(
+        &mut self,
+        defender: Side,
+        calculation_result: &BattleCalculationResult,
+        context: &BattleContext,
+    ) {
+        let Some(temp) = self.defender_temp.as_ref() else {
+            return;
+        };
+        let Some(counter) = temp.defense_skill_counter.clone() else {
+            return;
+        };
+        if let Some(condition) = &counter.condition {
+            if !condition.check_battle(context) {
+                return;
+            }
+        }
+        let skill_name = temp.defense_skill_name.clone().unwrap_or_default();
+        let template = counter
+            .battle_record_template
+            .clone()
+            .unwrap_or_else(|| CounterAttack::default_record_template(&skill_name));
+        let source_id = temp
+            .defense_skill_id
+            .as_ref()
+            .map(|id| format!("defense_skill:{}", id))
+            .unwrap_or_default();
+        self.handle_extra_attack(
+            &counter.output,
+            Some(&template),
+            defender,
+            "defense_skill_counter",
+            0,
+            &source_id,
+            Some(calculation_result),
+        );
+    }
+
     /// 处理回合结束
     fn handle_round_ending(&mut self) {
         let attacker = self.current_attacker.expect("回合中必须有攻击者");
@@ -465,16 +1156,25 @@ impl BattleEngine {
             &mut (),
             &side_a_context,
         );
-        let side_b_effects = self.side_b_executor.trigger_battle_with_source(
-            Trigger::RoundEnd,
-            &mut (),
-            &side_b_context,
-        );
+        // 一对多战斗中本回合落败的敌人不再触发回合结束词条
+        let side_b_effects = if self.side_b_panel.hp > 0.0 || self.side_b_bench.is_empty() {
+            self.side_b_executor.trigger_battle_with_source(
+                Trigger::RoundEnd,
+                &mut (),
+                &side_b_context,
+            )
+        } else {
+            Vec::new()
+        };
 
         // 丢弃临时面板
         self.attacker_temp = None;
         self.defender_temp = None;
 
+        // 封招回合数递减（在回合结束效果之前，避免本回合新加的封招被立即消耗）
+        self.tick_seals_and_record(Side::A);
+        self.tick_seals_and_record(Side::B);
+
         // 应用回合结束效果（永久效果，修改战斗面板）
         self.apply_effects(side_a_effects, Side::A, None);
         self.apply_effects(side_b_effects, Side::B, None);
@@ -492,15 +1192,20 @@ impl BattleEngine {
         // 清除当前攻击者
         self.current_attacker = None;
 
+        // 一对多战斗中在场敌人落败时换人上场
+        self.replace_defeated_enemy();
+
+        // 训练模式在训练轮数结束
+        if let Some(training) = self.training {
+            if self.round >= training.rounds {
+                self.finish_training(training);
+                return;
+            }
+        }
+
         // 检查是否达到最大轮数
-        if self.round >= MAX_ROUNDS {
-            self.state = BattleState::Finished(BattleResult::Draw);
-            self.record_with_delta(BattleRecord::BattleEnd {
-                winner_name: "平局".to_string(),
-                reason: format!("战斗达到最大轮数（{}轮）仍未分出胜负", MAX_ROUNDS),
-                side_a_panel_delta: None,
-                side_b_panel_delta: None,
-            });
+        if self.round >= self.config.max_rounds {
+            self.finish_at_round_limit();
             return;
         }
 
@@ -509,11 +1214,20 @@ impl BattleEngine {
             return;
         }
 
+        // 剧情打断
+        if self.check_interrupt() {
+            return;
+        }
+
+        // 生命降至阈值时切换阶段
+        self.check_phase_change(Side::A);
+        self.check_phase_change(Side::B);
+
         // 检查对方是否也可以行动（双方同时准备好的情况）
         let defender = attacker.opposite();
         if self.action_bar.is_ready(defender) {
             // 对方也准备好了，开始新回合
-            self.state = BattleState::RoundStarting { attacker: defender };
+            self.begin_round(defender);
         } else {
             // 回到行动条推进阶段
             self.state = BattleState::ActionBarAdvancing;
@@ -535,13 +1249,15 @@ impl BattleEngine {
 
         let mut attribute_effects = Vec::new();
         let mut percentage_effects = Vec::new();
-        let mut extra_attacks = Vec::new();
+        let mut special_effects = Vec::new();
 
         for entry_effect in effects {
             match &entry_effect.effect {
                 Effect::ModifyAttribute { .. } => attribute_effects.push(entry_effect),
                 Effect::ModifyPercentage { .. } => percentage_effects.push(entry_effect),
-                Effect::ExtraAttack { .. } => extra_attacks.push(entry_effect),
+                Effect::ExtraAttack { .. } | Effect::SealSkill { .. } => {
+                    special_effects.push(entry_effect)
+                }
             }
         }
 
@@ -578,10 +1294,7 @@ impl BattleEngine {
                 attribute_rest.push(entry_effect);
             }
         }
-        for entry_effect in attribute_max_first
-            .into_iter()
-            .chain(attribute_rest.into_iter())
-        {
+        for entry_effect in attribute_max_first.into_iter().chain(attribute_rest) {
             self.apply_single_effect(
                 &entry_effect.effect,
                 source_side,
@@ -620,10 +1333,7 @@ impl BattleEngine {
                 }
             }
 
-            for entry_effect in percentage_max_first
-                .into_iter()
-                .chain(percentage_rest.into_iter())
-            {
+            for entry_effect in percentage_max_first.into_iter().chain(percentage_rest) {
                 let (target, value, operation, target_panel, can_exceed_limit, is_temporary) =
                     match &entry_effect.effect {
                         Effect::ModifyPercentage {
@@ -659,6 +1369,7 @@ impl BattleEngine {
                             self_panel,
                             opponent_panel: Some(opponent_panel),
                             attack_result,
+                            environment: self.environment.clone(),
                         };
                         FormulaCalculator::evaluate_battle(formula, &context).unwrap_or(0.0)
                     }
@@ -696,6 +1407,7 @@ impl BattleEngine {
                         .map(|panel| Self::get_battle_panel_value(panel, *target)),
                 };
 
+                let caps = self.config.attribute_caps.clone();
                 let apply_percent_delta = |panel: &mut BattlePanel, base_value: f64| {
                     let delta = base_value * ratio_delta;
                     panel.apply_modifier_with_limit(
@@ -703,29 +1415,32 @@ impl BattleEngine {
                         delta,
                         &Operation::Add,
                         *can_exceed_limit,
-                    );
+                        &caps,
+                    )
                 };
 
+                let mut clamps = Vec::new();
                 if *is_temporary {
                     if let Some(temp_panel) = self.get_temp_panel_mut_by_side(target_side) {
                         if let Some(base_value) = base_value_temp {
-                            apply_percent_delta(temp_panel, base_value);
+                            clamps.extend(apply_percent_delta(temp_panel, base_value));
                         }
                     }
                 } else {
                     {
                         let panel = self.get_panel_mut(target_side);
                         if let Some(base_value) = base_value_battle {
-                            apply_percent_delta(panel, base_value);
+                            clamps.extend(apply_percent_delta(panel, base_value));
                         }
                     }
 
                     if let Some(temp_panel) = self.get_temp_panel_mut_by_side(target_side) {
                         if let Some(base_value) = base_value_temp.or(base_value_battle) {
-                            apply_percent_delta(temp_panel, base_value);
+                            clamps.extend(apply_percent_delta(temp_panel, base_value));
                         }
                     }
                 }
+                self.clamp_trace.extend(clamps);
 
                 if let Some(description) = self.generate_effect_description(
                     &entry_effect.effect,
@@ -745,9 +1460,11 @@ impl BattleEngine {
                         }
                         _ => BattleLogKind::Effect,
                     };
+                    let source = self.entry_source_name(source_side, &entry_effect.source_id);
                     self.record_with_delta(BattleRecord::EntryTriggered {
                         entry_id: entry_effect.entry_id.to_string(),
                         entry_order: entry_effect.entry_order,
+                        source,
                         description,
                         log_kind,
                         batch_id: self.current_effect_batch_id,
@@ -758,8 +1475,8 @@ impl BattleEngine {
             }
         }
 
-        // 额外攻击在属性变更后处理
-        for entry_effect in extra_attacks {
+        // 额外攻击、封招在属性变更后处理
+        for entry_effect in special_effects {
             self.apply_single_effect(
                 &entry_effect.effect,
                 source_side,
@@ -812,6 +1529,7 @@ impl BattleEngine {
                     PanelTarget::Opponent => source_side.opposite(),
                 };
 
+                let caps = self.config.attribute_caps.clone();
                 let apply_to_panel = |panel: &mut BattlePanel| {
                     let adjusted_value = match effect {
                         Effect::ModifyPercentage { .. } => {
@@ -828,26 +1546,29 @@ impl BattleEngine {
                         adjusted_value,
                         operation,
                         *can_exceed_limit,
-                    );
+                        &caps,
+                    )
                 };
 
                 // 根据是否临时效果，决定修改目标
+                let mut clamps = Vec::new();
                 if *is_temporary {
                     // 临时效果：只修改临时面板
                     if let Some(temp) = self.get_temp_panel_mut_by_side(target_side) {
-                        apply_to_panel(temp);
+                        clamps.extend(apply_to_panel(temp));
                     }
                 } else {
                     // 永久效果：修改战斗面板
                     {
                         let panel = self.get_panel_mut(target_side);
-                        apply_to_panel(panel);
+                        clamps.extend(apply_to_panel(panel));
                     }
                     // 如果临时面板存在，也同时修改
                     if let Some(temp) = self.get_temp_panel_mut_by_side(target_side) {
-                        apply_to_panel(temp);
+                        clamps.extend(apply_to_panel(temp));
                     }
                 }
+                self.clamp_trace.extend(clamps);
 
                 // 生成战斗记录
                 if let Some(description) =
@@ -870,9 +1591,11 @@ impl BattleEngine {
                         }
                         _ => BattleLogKind::Effect,
                     };
+                    let source = self.entry_source_name(source_side, template_entry_id);
                     self.record_with_delta(BattleRecord::EntryTriggered {
                         entry_id: entry_id.to_string(),
                         entry_order,
+                        source,
                         description,
                         log_kind,
                         batch_id: self.current_effect_batch_id,
@@ -896,6 +1619,77 @@ impl BattleEngine {
                     battle_result,
                 );
             }
+            Effect::SealSkill { slot, rounds, .. } => {
+                // 封招总是作用于对手：战斗面板记录剩余回合，临时面板立即生效
+                let target_side = source_side.opposite();
+                let unarmed = self.config.unarmed;
+                self.get_panel_mut(target_side).seal_skill(*slot, *rounds);
+                if let Some(temp) = self.get_temp_panel_mut_by_side(target_side) {
+                    temp.seal_skill(*slot, *rounds);
+                    temp.apply_seals(&unarmed);
+                }
+
+                if let Some(description) =
+                    self.generate_effect_description(effect, source_side, battle_result)
+                {
+                    let source = self.entry_source_name(source_side, template_entry_id);
+                    self.record_with_delta(BattleRecord::EntryTriggered {
+                        entry_id: entry_id.to_string(),
+                        entry_order,
+                        source,
+                        description,
+                        log_kind: BattleLogKind::Effect,
+                        batch_id: self.current_effect_batch_id,
+                        side_a_panel_delta: None,
+                        side_b_panel_delta: None,
+                    });
+                }
+            }
+        }
+    }
+
+    /// 按内息输出策略调整指定一方的内息输出
+    fn apply_qi_profile(&mut self, side: Side) {
+        let profile = match side {
+            Side::A => self.config.side_a_qi_profile,
+            Side::B => self.config.side_b_qi_profile,
+        };
+        if let Some(rate) = profile.rate_for(
+            self.get_panel(side),
+            self.get_panel(side.opposite()),
+            self.round,
+        ) {
+            self.get_panel_mut(side).qi_output_rate = rate;
+        }
+    }
+
+    /// 累计指定一方受到的生命伤害
+    fn add_damage_taken(&mut self, side: Side, hp_damage: f64) {
+        match side {
+            Side::A => self.side_a_damage_taken += hp_damage,
+            Side::B => self.side_b_damage_taken += hp_damage,
+        }
+    }
+
+    /// 封招回合数递减，解封时记录
+    fn tick_seals_and_record(&mut self, side: Side) {
+        let panel = self.get_panel_mut(side);
+        let name = panel.name.clone();
+        let released = panel.tick_seals();
+        if !self.log_policy.records_details() {
+            return;
+        }
+        for slot in released {
+            self.record_with_delta(BattleRecord::EntryTriggered {
+                entry_id: String::new(),
+                entry_order: 0,
+                source: None,
+                description: format!("{}的{}已解封", name, slot.name()),
+                log_kind: BattleLogKind::Effect,
+                batch_id: None,
+                side_a_panel_delta: None,
+                side_b_panel_delta: None,
+            });
         }
     }
 
@@ -915,6 +1709,7 @@ impl BattleEngine {
             AttributeTarget::ChargeTime => panel.charge_time,
             AttributeTarget::MaxQiOutputRate => panel.max_qi_output_rate,
             AttributeTarget::QiOutputRate => panel.qi_output_rate,
+            AttributeTarget::Momentum => panel.momentum,
             _ => 0.0,
         }
     }
@@ -932,7 +1727,9 @@ impl BattleEngine {
         battle_result: Option<&BattleCalculationResult>,
     ) {
         // 计算额外攻击的输出值
-        let output = self.calculate_formula_value(output_formula, source_side, battle_result);
+        let policy = self.config.numeric_policy;
+        let q = |value: f64| policy.quantize(value);
+        let output = q(self.calculate_formula_value(output_formula, source_side, battle_result));
 
         let target_side = source_side.opposite();
         let source_name = self.get_panel(source_side).name.clone();
@@ -945,27 +1742,27 @@ impl BattleEngine {
                 self.get_panel_mut(target_side)
             };
 
-            let total_defense = BattleCalculator::calculate_defense(target_panel);
+            let total_defense = q(BattleCalculator::calculate_defense(target_panel));
             let damage_reduction = target_panel
                 .damage_reduction
                 .min(target_panel.max_damage_reduction);
-            let reduced_output = output * (1.0 - damage_reduction);
-            let defender_qi_output = target_panel
+            let reduced_output = q(output * (1.0 - damage_reduction));
+            let defender_qi_output = q(target_panel
                 .qi
-                .min(target_panel.max_qi * target_panel.qi_output_rate);
+                .min(target_panel.max_qi * target_panel.qi_output_rate));
 
             let broke_qi_defense = reduced_output > total_defense;
             let (hp_damage, defender_qi_consumed) = if broke_qi_defense {
-                (reduced_output - total_defense, defender_qi_output)
+                (q(reduced_output - total_defense), defender_qi_output)
             } else if total_defense > 0.0 {
-                (0.0, reduced_output * defender_qi_output / total_defense)
+                (0.0, q(reduced_output * defender_qi_output / total_defense))
             } else {
                 (0.0, 0.0)
             };
 
-            target_panel.qi -= defender_qi_consumed;
+            target_panel.qi = q(target_panel.qi - defender_qi_consumed);
             target_panel.clamp_qi();
-            target_panel.hp -= hp_damage;
+            target_panel.hp = q(target_panel.hp - hp_damage);
             target_panel.clamp_hp();
 
             (
@@ -976,6 +1773,10 @@ impl BattleEngine {
                 broke_qi_defense,
             )
         };
+        self.add_damage_taken(target_side, hp_damage);
+        if !self.log_policy.records_details() {
+            return;
+        }
 
         let mut details = Vec::new();
         if defender_qi_consumed > 0.0 && hp_damage > 0.0 {
@@ -1090,6 +1891,7 @@ impl BattleEngine {
                 defender_qi_consumed: r.defender_qi_consumed,
                 broke_qi_defense: r.broke_qi_defense,
             }),
+            environment: self.environment.clone(),
         };
 
         FormulaCalculator::evaluate_battle(formula, &context).unwrap_or(0.0)
@@ -1102,6 +1904,9 @@ impl BattleEngine {
         source_side: Side,
         battle_result: Option<&BattleCalculationResult>,
     ) -> Option<String> {
+        if !self.log_policy.records_details() {
+            return None;
+        }
         let self_panel = Self::battle_panel_to_character_panel(self.get_panel(source_side));
         let opponent_panel =
             Self::battle_panel_to_character_panel(self.get_panel(source_side.opposite()));
@@ -1118,6 +1923,7 @@ impl BattleEngine {
                 defender_qi_consumed: r.defender_qi_consumed,
                 broke_qi_defense: r.broke_qi_defense,
             }),
+            environment: self.environment.clone(),
         };
 
         effect.generate_battle_record_text(
@@ -1187,6 +1993,10 @@ impl BattleEngine {
 
     /// 检查战斗是否结束
     fn check_battle_end(&mut self) -> bool {
+        if self.training.is_some() {
+            self.floor_training_hp();
+        }
+
         // 检查临时面板（如果存在）或战斗面板
         let side_a_hp = self
             .attacker_temp
@@ -1223,6 +2033,7 @@ impl BattleEngine {
                 side_a_panel_delta: None,
                 side_b_panel_delta: None,
             });
+            self.record_outro(BattleResult::SideBWin);
             return true;
         }
 
@@ -1232,6 +2043,20 @@ impl BattleEngine {
             self.attacker_temp = None;
             self.defender_temp = None;
 
+            // 一对多战斗中仍有敌人未落败时战斗继续：回合中落败时本回合随即结束，
+            // 在回合结束时换人上场
+            if self.side_b_bench.iter().any(|enemy| !enemy.is_defeated()) {
+                let defeat = self.side_b_flavor.defeat.clone();
+                self.record_flavor_text(Side::B, "battle_outro", defeat);
+                if self.current_attacker.is_some() {
+                    self.state = BattleState::RoundEnding;
+                } else {
+                    self.replace_defeated_enemy();
+                    self.state = BattleState::ActionBarAdvancing;
+                }
+                return true;
+            }
+
             self.state = BattleState::Finished(BattleResult::SideAWin);
             self.record_with_delta(BattleRecord::BattleEnd {
                 winner_name: self.side_a_panel.name.clone(),
@@ -1239,12 +2064,270 @@ impl BattleEngine {
                 side_a_panel_delta: None,
                 side_b_panel_delta: None,
             });
+            self.record_outro(BattleResult::SideAWin);
             return true;
         }
 
         false
     }
 
+    /// 训练模式下 Side A 的生命不低于 1
+    fn floor_training_hp(&mut self) {
+        let floor = |panel: &mut BattlePanel| {
+            if panel.hp < 1.0 {
+                panel.hp = 1.0_f64.min(panel.max_hp);
+            }
+        };
+        let temp = match self.current_attacker {
+            Some(Side::A) => self.attacker_temp.as_mut(),
+            Some(Side::B) => self.defender_temp.as_mut(),
+            None => None,
+        };
+        if let Some(temp) = temp {
+            floor(temp);
+        }
+        floor(&mut self.side_a_panel);
+    }
+
+    /// 累计训练统计（Side A 的攻防结果）
+    fn record_training_exchange(&mut self, defender_side: Side, broke_qi_defense: bool) {
+        if self.training.is_none() {
+            return;
+        }
+        let stats = &mut self.training_stats;
+        match defender_side {
+            Side::A => {
+                stats.defenses += 1;
+                if !broke_qi_defense {
+                    stats.defenses_held += 1;
+                }
+            }
+            Side::B => {
+                stats.attacks += 1;
+                if broke_qi_defense {
+                    stats.attacks_broke_through += 1;
+                }
+            }
+        }
+    }
+
+    /// 训练轮数结束时以平局结束战斗（不做胜负判定）
+    fn finish_training(&mut self, training: TrainingMode) {
+        self.state = BattleState::Finished(BattleResult::Draw);
+        self.record_with_delta(BattleRecord::BattleEnd {
+            winner_name: "平局".to_string(),
+            reason: format!("训练结束（{}轮）", training.rounds),
+            side_a_panel_delta: None,
+            side_b_panel_delta: None,
+        });
+    }
+
+    /// 达到最大轮数时按配置的判定规则结束战斗
+    fn finish_at_round_limit(&mut self) {
+        let max_rounds = self.config.max_rounds;
+        let decided = self.config.tiebreakers.iter().find_map(|rule| {
+            rule.resolve(&self.side_a_panel, &self.side_b_panel)
+                .map(|result| (*rule, result))
+        });
+
+        let (result, winner_name, reason) = match decided {
+            Some((rule, result)) => {
+                let winner_name = match result {
+                    BattleResult::SideAWin => self.side_a_panel.name.clone(),
+                    BattleResult::SideBWin => self.side_b_panel.name.clone(),
+                    BattleResult::Draw | BattleResult::Interrupted => "平局".to_string(),
+                };
+                let reason = format!(
+                    "战斗达到最大轮数（{}轮），按「{}」判定胜负",
+                    max_rounds,
+                    rule.name()
+                );
+                (result, winner_name, reason)
+            }
+            None => (
+                BattleResult::Draw,
+                "平局".to_string(),
+                format!("战斗达到最大轮数（{}轮）仍未分出胜负", max_rounds),
+            ),
+        };
+
+        self.state = BattleState::Finished(result);
+        self.record_with_delta(BattleRecord::BattleEnd {
+            winner_name,
+            reason,
+            side_a_panel_delta: None,
+            side_b_panel_delta: None,
+        });
+        self.record_outro(result);
+    }
+
+    /// 检查剧情打断条件，满足时以打断结果结束战斗
+    fn check_interrupt(&mut self) -> bool {
+        let ratio = |panel: &BattlePanel| {
+            if panel.max_hp > 0.0 {
+                panel.hp / panel.max_hp
+            } else {
+                0.0
+            }
+        };
+        let side_a_ratio = ratio(&self.side_a_panel);
+        let side_b_ratio = ratio(&self.side_b_panel);
+        let Some(id) = self
+            .interrupts
+            .iter()
+            .find(|interrupt| {
+                interrupt
+                    .trigger
+                    .is_met(self.round, side_a_ratio, side_b_ratio)
+            })
+            .map(|interrupt| interrupt.id.clone())
+        else {
+            return false;
+        };
+
+        self.interrupted_by = Some(id);
+        self.state = BattleState::Finished(BattleResult::Interrupted);
+        self.record_with_delta(BattleRecord::BattleEnd {
+            winner_name: "平局".to_string(),
+            reason: "战斗被打断".to_string(),
+            side_a_panel_delta: None,
+            side_b_panel_delta: None,
+        });
+        true
+    }
+
+    /// 生命比例降至阈值时依次切换阶段（每个阶段只触发一次）
+    fn check_phase_change(&mut self, side: Side) {
+        loop {
+            let panel = self.get_panel(side);
+            let ratio = if panel.max_hp > 0.0 {
+                panel.hp / panel.max_hp
+            } else {
+                0.0
+            };
+            let phases = match side {
+                Side::A => &mut self.side_a_phases,
+                Side::B => &mut self.side_b_phases,
+            };
+            match phases.first() {
+                Some(phase) if ratio <= phase.hp_ratio => {
+                    let phase = phases.remove(0);
+                    self.enter_phase(side, phase);
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// 进入新阶段：替换武技（含词条与日志模板）并追加阶段词条
+    fn enter_phase(&mut self, side: Side, phase: BattlePhase) {
+        let executor = self.get_executor_mut(side);
+        if let Some(skill) = &phase.attack_skill {
+            executor.remove_entries_by_source_prefix("attack_skill:");
+            executor.add_entries_with_named_source(
+                skill.entries.clone(),
+                format!("attack_skill:{}", skill.id),
+                skill.name.clone(),
+            );
+        }
+        if let Some(skill) = &phase.defense_skill {
+            executor.remove_entries_by_source_prefix("defense_skill:");
+            executor.add_entries_with_named_source(
+                skill.entries.clone(),
+                format!("defense_skill:{}", skill.id),
+                skill.name.clone(),
+            );
+        }
+        executor.add_entries_with_named_source(
+            phase.entries,
+            format!("phase:{}", phase.name),
+            phase.name.clone(),
+        );
+
+        if let Some(skill) = phase.attack_skill {
+            self.action_bar.set_charge_time(side, skill.charge_time);
+            let panel = self.get_panel_mut(side);
+            panel.attack_skill_id = Some(skill.id);
+            panel.attack_skill_name = Some(skill.name);
+            panel.attack_skill_log_template = skill.log_template;
+            panel.attack_skill_ultimate = skill.ultimate;
+            panel.power = skill.power;
+            panel.charge_time = skill.charge_time;
+        }
+        if let Some(skill) = phase.defense_skill {
+            let panel = self.get_panel_mut(side);
+            panel.defense_skill_id = Some(skill.id);
+            panel.defense_skill_name = Some(skill.name);
+            panel.defense_skill_log_template = skill.log_template;
+            panel.defense_skill_counter = skill.counter;
+            panel.defense_power = skill.defense_power;
+        }
+
+        let character_name = self.get_panel(side).name.clone();
+        let description = match &phase.text {
+            Some(template) => Self::replace_log_template(
+                template,
+                &character_name,
+                &self.get_panel(side.opposite()).name,
+            ),
+            None => format!("{}进入「{}」", character_name, phase.name),
+        };
+        self.record_with_delta(BattleRecord::PhaseChange {
+            character_name,
+            phase_name: phase.name,
+            description,
+            side_a_panel_delta: None,
+            side_b_panel_delta: None,
+        });
+    }
+
+    /// 记录双方的收场文本（平局时不输出）
+    fn record_outro(&mut self, result: BattleResult) {
+        let winner = match result {
+            BattleResult::SideAWin => Side::A,
+            BattleResult::SideBWin => Side::B,
+            BattleResult::Draw | BattleResult::Interrupted => return,
+        };
+        for side in [Side::A, Side::B] {
+            let flavor = self.flavor(side);
+            let text = if side == winner {
+                flavor.victory.clone()
+            } else {
+                flavor.defeat.clone()
+            };
+            self.record_flavor_text(side, "battle_outro", text);
+        }
+    }
+
+    fn flavor(&self, side: Side) -> &BattleFlavor {
+        match side {
+            Side::A => &self.side_a_flavor,
+            Side::B => &self.side_b_flavor,
+        }
+    }
+
+    /// 以叙事日志输出开场/收场文本
+    fn record_flavor_text(&mut self, side: Side, entry_id: &str, template: Option<String>) {
+        let Some(template) = template else {
+            return;
+        };
+        let text = Self::replace_log_template(
+            &template,
+            &self.get_panel(side).name,
+            &self.get_panel(side.opposite()).name,
+        );
+        self.record_with_delta(BattleRecord::EntryTriggered {
+            entry_id: entry_id.to_string(),
+            entry_order: 0,
+            source: None,
+            description: text,
+            log_kind: BattleLogKind::Effect,
+            batch_id: None,
+            side_a_panel_delta: None,
+            side_b_panel_delta: None,
+        });
+    }
+
     /// 创建战斗上下文
     fn create_battle_context(&self, side: Side) -> BattleContext {
         let self_panel = self.get_panel(side);
@@ -1282,6 +2365,7 @@ impl BattleEngine {
             attack_result: None,
             self_panel: Some(Self::battle_panel_to_character_panel(self_panel)),
             opponent_panel: Some(Self::battle_panel_to_character_panel(opponent_panel)),
+            environment: self.environment.clone(),
         }
     }
 
@@ -1321,6 +2405,15 @@ impl BattleEngine {
         }
     }
 
+    /// 词条来源的可读名称（用于战斗日志）
+    fn entry_source_name(&self, side: Side, source_id: &str) -> Option<String> {
+        let executor = match side {
+            Side::A => &self.side_a_executor,
+            Side::B => &self.side_b_executor,
+        };
+        executor.source_name(source_id).map(str::to_string)
+    }
+
     /// 获取指定方的词条执行器（可变引用）
     fn get_executor_mut(&mut self, side: Side) -> &mut EntryExecutor {
         match side {
@@ -1394,6 +2487,7 @@ impl BattleEngine {
         char_panel.attack_speed = panel.attack_speed;
         char_panel.qi_recovery_rate = panel.qi_recovery_rate;
         char_panel.charge_time = panel.charge_time;
+        char_panel.momentum = panel.momentum;
 
         char_panel
     }
@@ -1407,6 +2501,10 @@ impl BattleEngine {
 
     /// 记录日志并计算面板变化量
     fn record_with_delta(&mut self, mut record: BattleRecord) {
+        if !self.log_policy.keeps(&record) {
+            return;
+        }
+
         // 获取当前实际面板（如果临时面板存在，则使用临时面板）
         let current_side_a = self.get_current_panel_for_delta(Side::A);
         let current_side_b = self.get_current_panel_for_delta(Side::B);
@@ -1447,10 +2545,12 @@ impl BattleEngine {
             BattleRecord::BattleStart {
                 side_a_name,
                 side_b_name,
+                first_strike,
                 ..
             } => BattleRecord::BattleStart {
                 side_a_name,
                 side_b_name,
+                first_strike,
                 side_a_panel_delta: side_a_opt,
                 side_b_panel_delta: side_b_opt,
             },
@@ -1467,6 +2567,7 @@ impl BattleEngine {
             BattleRecord::EntryTriggered {
                 entry_id,
                 entry_order,
+                source,
                 description,
                 log_kind,
                 batch_id,
@@ -1474,6 +2575,7 @@ impl BattleEngine {
             } => BattleRecord::EntryTriggered {
                 entry_id,
                 entry_order,
+                source,
                 description,
                 log_kind,
                 batch_id,
@@ -1573,6 +2675,28 @@ impl BattleEngine {
                 side_a_panel_delta: side_a_opt,
                 side_b_panel_delta: side_b_opt,
             },
+            BattleRecord::PhaseChange {
+                character_name,
+                phase_name,
+                description,
+                ..
+            } => BattleRecord::PhaseChange {
+                character_name,
+                phase_name,
+                description,
+                side_a_panel_delta: side_a_opt,
+                side_b_panel_delta: side_b_opt,
+            },
+            BattleRecord::EnemySwitch {
+                enemy_name,
+                description,
+                ..
+            } => BattleRecord::EnemySwitch {
+                enemy_name,
+                description,
+                side_a_panel_delta: side_a_opt,
+                side_b_panel_delta: side_b_opt,
+            },
             BattleRecord::BattleEnd {
                 winner_name,
                 reason,
@@ -1598,6 +2722,11 @@ impl BattleEngine {
         &self.state
     }
 
+    /// 获取已开始的回合数
+    pub fn get_round(&self) -> u32 {
+        self.round
+    }
+
     /// 获取 Side A 战斗面板（只读）
     pub fn get_side_a_panel(&self) -> &BattlePanel {
         &self.side_a_panel
@@ -1608,11 +2737,126 @@ impl BattleEngine {
         &self.side_b_panel
     }
 
+    /// 设置 Side A 开场/收场文本
+    pub fn set_side_a_flavor(&mut self, flavor: BattleFlavor) {
+        self.side_a_flavor = flavor;
+    }
+
+    /// 设置 Side B 开场/收场文本
+    pub fn set_side_b_flavor(&mut self, flavor: BattleFlavor) {
+        self.side_b_flavor = flavor;
+    }
+
+    /// 设置 Side A 的战斗阶段
+    pub fn set_side_a_phases(&mut self, mut phases: Vec<BattlePhase>) {
+        sort_phases(&mut phases);
+        self.side_a_phases = phases;
+    }
+
+    /// 设置 Side B 的战斗阶段
+    pub fn set_side_b_phases(&mut self, mut phases: Vec<BattlePhase>) {
+        sort_phases(&mut phases);
+        self.side_b_phases = phases;
+    }
+
+    /// 为 Side A 追加词条（如佩戴的称号，需在战斗开始前调用）
+    pub fn add_side_a_entries(
+        &mut self,
+        entries: Vec<Entry>,
+        source_id: String,
+        source_name: String,
+    ) {
+        self.side_a_executor
+            .add_entries_with_named_source(entries, source_id, source_name);
+    }
+
+    /// 为 Side B 追加词条（需在战斗开始前调用）
+    pub fn add_side_b_entries(
+        &mut self,
+        entries: Vec<Entry>,
+        source_id: String,
+        source_name: String,
+    ) {
+        self.side_b_executor
+            .add_entries_with_named_source(entries, source_id, source_name);
+    }
+
+    /// 可写的战斗面板（用于战斗开始前的调整，如剧情战斗的让子与限制）
+    pub fn side_panel_mut(&mut self, side: Side) -> &mut BattlePanel {
+        self.get_panel_mut(side)
+    }
+
+    /// 设置训练模式（需在战斗开始前调用）
+    pub fn set_training(&mut self, training: Option<TrainingMode>) {
+        self.training = training;
+    }
+
+    /// 训练表现评分（非训练模式时为 None）
+    pub fn training_score(&self) -> Option<TrainingScore> {
+        self.training.map(|_| {
+            TrainingScore::new(
+                self.training_stats,
+                self.round,
+                self.side_b_damage_taken,
+                self.side_a_damage_taken,
+                self.side_b_panel.max_hp,
+            )
+        })
+    }
+
+    /// 设置战斗环境变量（需在战斗开始前调用）
+    pub fn set_environment(&mut self, environment: BTreeMap<String, f64>) {
+        self.environment = environment;
+    }
+
+    /// 设置剧情打断条件
+    pub fn set_interrupts(&mut self, interrupts: Vec<BattleInterrupt>) {
+        self.interrupts = interrupts;
+    }
+
+    /// 属性上限约束记录（按发生顺序）
+    pub fn clamp_trace(&self) -> &[AttributeClamp] {
+        &self.clamp_trace
+    }
+
+    /// 指定方因标签被禁用而跳过的词条（含战斗中途加入的阶段词条）
+    pub fn skipped_entries(&self, side: Side) -> &[SkippedEntry] {
+        match side {
+            Side::A => self.side_a_executor.skipped_entries(),
+            Side::B => self.side_b_executor.skipped_entries(),
+        }
+    }
+
+    /// 触发的打断标识（未被打断时为 None）
+    pub fn interruption(&self) -> Option<&str> {
+        self.interrupted_by.as_deref()
+    }
+
     /// 设置 Side A 攻击武技日志模板
     pub fn set_side_a_attack_log_template(&mut self, template: Option<String>) {
         self.side_a_panel.attack_skill_log_template = template;
     }
 
+    /// 设置 Side A 防御武技反击
+    pub fn set_side_a_defense_counter(&mut self, counter: Option<CounterAttack>) {
+        self.side_a_panel.defense_skill_counter = counter;
+    }
+
+    /// 设置 Side B 防御武技反击
+    pub fn set_side_b_defense_counter(&mut self, counter: Option<CounterAttack>) {
+        self.side_b_panel.defense_skill_counter = counter;
+    }
+
+    /// 设置 Side A 攻击武技绝招
+    pub fn set_side_a_attack_ultimate(&mut self, ultimate: Option<UltimateMove>) {
+        self.side_a_panel.attack_skill_ultimate = ultimate;
+    }
+
+    /// 设置 Side B 攻击武技绝招
+    pub fn set_side_b_attack_ultimate(&mut self, ultimate: Option<UltimateMove>) {
+        self.side_b_panel.attack_skill_ultimate = ultimate;
+    }
+
     /// 设置 Side A 防御武技日志模板
     pub fn set_side_a_defense_log_template(&mut self, template: Option<String>) {
         self.side_a_panel.defense_skill_log_template = template;
@@ -1681,6 +2925,298 @@ mod tests {
         assert!(!engine.state.is_finished());
     }
 
+    #[test]
+    fn test_round_limit_tiebreaker() {
+        use crate::battle::battle_config::TiebreakerRule;
+
+        let side_a = CharacterPanel::new("角色A".to_string(), ThreeDimensional::new(10, 8, 12));
+        let side_b = CharacterPanel::new("角色B".to_string(), ThreeDimensional::new(10, 8, 12));
+
+        let mut draw_engine =
+            BattleEngine::new(&side_a, &side_b, EntryExecutor::new(), EntryExecutor::new());
+        draw_engine.set_config(BattleConfig {
+            max_rounds: 1,
+            ..BattleConfig::default()
+        });
+        assert_eq!(draw_engine.run(), BattleResult::Draw);
+
+        let mut engine =
+            BattleEngine::new(&side_a, &side_b, EntryExecutor::new(), EntryExecutor::new());
+        engine.set_config(BattleConfig {
+            max_rounds: 1,
+            tiebreakers: vec![TiebreakerRule::AttackerLoses],
+            ..BattleConfig::default()
+        });
+        assert_eq!(engine.run(), BattleResult::SideBWin);
+        match engine.get_log().last() {
+            Some(BattleRecord::BattleEnd {
+                winner_name,
+                reason,
+                ..
+            }) => {
+                assert_eq!(winner_name, "角色B");
+                assert!(reason.contains("攻击方判负"));
+            }
+            other => panic!("最后一条记录应为战斗结束: {:?}", other),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "runtime")]
+    fn test_handicap_adjusts_panel_before_battle_start() {
+        use crate::event::{BattleHandicap, BattleSide};
+
+        let side_a = CharacterPanel::new("角色A".to_string(), ThreeDimensional::new(10, 8, 12));
+        let side_b = CharacterPanel::new("角色B".to_string(), ThreeDimensional::new(10, 8, 12));
+        let mut engine =
+            BattleEngine::new(&side_a, &side_b, EntryExecutor::new(), EntryExecutor::new());
+        let handicap: BattleHandicap = serde_json::from_value(serde_json::json!({
+            "side": "player",
+            "hp_ratio": 0.5,
+            "attack_speed_multiplier": 0.8,
+            "disable_defense_skill": true
+        }))
+        .unwrap();
+        assert_eq!(handicap.side, BattleSide::Player);
+        handicap.validate().unwrap();
+        let max_hp = engine.get_side_a_panel().max_hp;
+        let attack_speed = engine.get_side_a_panel().attack_speed;
+        handicap.apply_to_panel(engine.side_panel_mut(Side::A));
+        engine.step();
+        engine.step();
+        let panel = engine.get_side_a_panel();
+        assert_eq!(panel.hp, max_hp * 0.5);
+        assert_eq!(panel.attack_speed, attack_speed * 0.8);
+        assert!(panel.defense_seal_rounds > 0);
+    }
+
+    #[test]
+    fn test_seal_skill_uses_unarmed_stats_until_released() {
+        use crate::effect::effect::SkillSlot;
+        use crate::effect::entry::Entry;
+
+        let side_a = CharacterPanel::new("角色A".to_string(), ThreeDimensional::new(10, 8, 12));
+        let side_b = CharacterPanel::new("角色B".to_string(), ThreeDimensional::new(10, 8, 12));
+        let mut side_a_executor = EntryExecutor::new();
+        side_a_executor.add_entries(vec![Entry::new(
+            Trigger::BattleStart,
+            None,
+            vec![Effect::SealSkill {
+                slot: SkillSlot::AttackSkill,
+                rounds: 2,
+                battle_record_template: None,
+            }],
+            None,
+        )]);
+
+        let mut engine = BattleEngine::new(&side_a, &side_b, side_a_executor, EntryExecutor::new());
+        engine.side_b_panel.power = 3.0;
+        engine.set_config(BattleConfig {
+            max_rounds: 3,
+            ..BattleConfig::default()
+        });
+        engine.run();
+
+        assert_eq!(engine.side_b_panel.attack_seal_rounds, 0);
+        assert_eq!(engine.side_b_panel.power, 3.0);
+        let log = format!("{:?}", engine.get_log());
+        assert!(log.contains("攻击武技被封"));
+        assert!(log.contains("攻击武技已解封"));
+    }
+
+    #[test]
+    fn test_disabled_entry_tags_skip_entries() {
+        use crate::effect::entry::Entry;
+
+        let side_a = CharacterPanel::new("角色A".to_string(), ThreeDimensional::new(10, 8, 12));
+        let side_b = CharacterPanel::new("角色B".to_string(), ThreeDimensional::new(10, 8, 12));
+        let speed_entry: Entry = serde_json::from_value(serde_json::json!({
+            "trigger": "battle_start",
+            "tags": ["speed"],
+            "effects": [{"type": "modify_attribute", "target": "attack_speed",
+                         "value": 100.0, "operation": "add"}]
+        }))
+        .unwrap();
+        let mut side_a_executor = EntryExecutor::new();
+        side_a_executor.add_entries_with_named_source(
+            vec![speed_entry.clone()],
+            "trait:疾风".to_string(),
+            "疾风".to_string(),
+        );
+        side_a_executor.disable_tags(["speed".to_string()]);
+
+        let mut engine = BattleEngine::new(&side_a, &side_b, side_a_executor, EntryExecutor::new());
+        engine.add_side_a_entries(
+            vec![speed_entry],
+            "title:迅捷".to_string(),
+            "迅捷".to_string(),
+        );
+        let attack_speed = engine.get_side_a_panel().attack_speed;
+        engine.step();
+        engine.step();
+
+        assert_eq!(engine.get_side_a_panel().attack_speed, attack_speed);
+        let skipped = engine.skipped_entries(Side::A);
+        assert_eq!(skipped.len(), 2);
+        assert_eq!(skipped[0].source_name.as_deref(), Some("疾风"));
+        assert_eq!(skipped[1].source_id, "title:迅捷");
+        assert_eq!(skipped[1].tags, ["speed"]);
+        assert!(engine.skipped_entries(Side::B).is_empty());
+    }
+
+    #[test]
+    fn test_momentum_builds_on_defense_and_feeds_formulas() {
+        use crate::battle::battle_config::MomentumRule;
+        use crate::effect::entry::Entry;
+
+        let side_a = CharacterPanel::new("角色A".to_string(), ThreeDimensional::new(10, 8, 12));
+        let side_b = CharacterPanel::new("角色B".to_string(), ThreeDimensional::new(10, 8, 12));
+        let momentum_entry: Entry = serde_json::from_value(serde_json::json!({
+            "trigger": "round_end",
+            "effects": [{"type": "modify_attribute", "target": "damage_bonus",
+                         "value": "self_momentum / 10", "operation": "set"}]
+        }))
+        .unwrap();
+        let mut engine =
+            BattleEngine::new(&side_a, &side_b, EntryExecutor::new(), EntryExecutor::new());
+        engine.set_config(BattleConfig {
+            max_rounds: 6,
+            momentum: MomentumRule {
+                max: 3.0,
+                ..MomentumRule::default()
+            },
+            ..BattleConfig::default()
+        });
+        engine.add_side_b_entries(
+            vec![momentum_entry],
+            "trait:借势".to_string(),
+            "借势".to_string(),
+        );
+        // Side B 内息深厚，Side A 的攻击始终无法击破其内息防御
+        let panel = engine.side_panel_mut(Side::B);
+        panel.max_qi = 100000.0;
+        panel.qi = 100000.0;
+        panel.defense_power = 10.0;
+        engine.run();
+
+        let side_b = engine.get_side_b_panel();
+        assert_eq!(side_b.momentum, 3.0);
+        assert!((side_b.damage_bonus - 0.3).abs() < 1e-9);
+        assert!(engine
+            .get_log()
+            .get_all_records()
+            .iter()
+            .any(|record| matches!(
+                record,
+                BattleRecord::CalculationResult { side_b_panel_delta: Some(delta), .. }
+                    if delta.momentum_delta == Some(1.0)
+            )));
+    }
+
+    #[test]
+    fn test_first_strike_rule_breaks_ties() {
+        use crate::battle::battle_config::FirstStrikeRule;
+
+        let side_a = CharacterPanel::new("角色A".to_string(), ThreeDimensional::new(10, 8, 12));
+        let mut side_b = CharacterPanel::new("角色B".to_string(), ThreeDimensional::new(10, 8, 12));
+        side_b.martial_arts_attainment = 0.1;
+        // 双方出手速度相同、空手蓄力时间相差无几，首回合同时就绪
+        let first_attacker = |rule: FirstStrikeRule| {
+            let mut engine =
+                BattleEngine::new(&side_a, &side_b, EntryExecutor::new(), EntryExecutor::new());
+            engine.set_config(BattleConfig {
+                max_rounds: 1,
+                first_strike: rule,
+                ..BattleConfig::default()
+            });
+            engine.run();
+            let records = engine.get_log().get_all_records();
+            assert!(records.iter().any(|record| matches!(
+                record,
+                BattleRecord::BattleStart { first_strike, .. } if *first_strike == rule
+            )));
+            records
+                .iter()
+                .find_map(|record| match record {
+                    BattleRecord::RoundStart { attacker_name, .. } => Some(attacker_name.clone()),
+                    _ => None,
+                })
+                .unwrap()
+        };
+
+        assert_eq!(first_attacker(FirstStrikeRule::SideA), "角色A");
+        assert_eq!(first_attacker(FirstStrikeRule::Defender), "角色B");
+        assert_eq!(first_attacker(FirstStrikeRule::HigherAttainment), "角色B");
+        let seeded: Vec<String> = (1..=8)
+            .map(|seed| first_attacker(FirstStrikeRule::Seeded { seed }))
+            .collect();
+        assert!(seeded.iter().any(|name| name == "角色A"));
+        assert!(seeded.iter().any(|name| name == "角色B"));
+        assert_eq!(
+            seeded[2],
+            first_attacker(FirstStrikeRule::Seeded { seed: 3 })
+        );
+    }
+
+    #[test]
+    fn test_flavor_text_opens_and_closes_log() {
+        let side_a = CharacterPanel::new("角色A".to_string(), ThreeDimensional::new(10, 8, 12));
+        let side_b = CharacterPanel::new("角色B".to_string(), ThreeDimensional::new(10, 8, 12));
+        let mut engine =
+            BattleEngine::new(&side_a, &side_b, EntryExecutor::new(), EntryExecutor::new());
+        engine.set_config(BattleConfig {
+            max_rounds: 1,
+            tiebreakers: vec![crate::battle::battle_config::TiebreakerRule::AttackerLoses],
+            ..BattleConfig::default()
+        });
+        engine.set_side_b_flavor(BattleFlavor {
+            intro: Some("{self}拦住了{opponent}".to_string()),
+            victory: Some("{self}扬长而去".to_string()),
+            defeat: Some("{self}落荒而逃".to_string()),
+        });
+        assert_eq!(engine.run(), BattleResult::SideBWin);
+
+        let descriptions: Vec<&str> = engine
+            .get_log()
+            .get_all_records()
+            .iter()
+            .filter_map(|record| match record {
+                BattleRecord::EntryTriggered { description, .. } => Some(description.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(descriptions.first(), Some(&"角色B拦住了角色A"));
+        assert_eq!(descriptions.last(), Some(&"角色B扬长而去"));
+    }
+
+    #[test]
+    fn test_log_policy_keeps_summary() {
+        let side_a = CharacterPanel::new("角色A".to_string(), ThreeDimensional::new(10, 8, 12));
+        let side_b = CharacterPanel::new("角色B".to_string(), ThreeDimensional::new(10, 8, 12));
+
+        let mut full =
+            BattleEngine::new(&side_a, &side_b, EntryExecutor::new(), EntryExecutor::new());
+        let full_result = full.run();
+
+        let mut summary_only =
+            BattleEngine::new(&side_a, &side_b, EntryExecutor::new(), EntryExecutor::new());
+        summary_only.set_log_policy(LogPolicy::SummaryOnly);
+        assert_eq!(summary_only.run(), full_result);
+        assert_eq!(summary_only.get_log().get_all_records().len(), 1);
+        assert!(matches!(
+            summary_only.get_log().last(),
+            Some(BattleRecord::BattleEnd { .. })
+        ));
+
+        let mut silent =
+            BattleEngine::new(&side_a, &side_b, EntryExecutor::new(), EntryExecutor::new());
+        silent.set_log_policy(LogPolicy::None);
+        silent.run();
+        assert!(silent.get_log().get_all_records().is_empty());
+        assert_eq!(silent.summary(), full.summary());
+        assert_eq!(silent.summary().result, Some(full_result));
+    }
+
     #[test]
     fn test_side_opposite() {
         assert_eq!(Side::A.opposite(), Side::B);
@@ -0,0 +1,77 @@
+/// 战斗打断（剧情脚本）
+/// 满足条件时立即结束战斗，由剧情根据打断标识决定后续分支
+use super::battle_state::Side;
+use serde::{Deserialize, Serialize};
+
+/// 打断条件
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InterruptTrigger {
+    /// 第 N 回合结束时
+    Round(u32),
+    /// 某方生命比例（当前生命 / 生命上限）降至阈值以下时
+    HpBelow { side: Side, ratio: f64 },
+}
+
+/// 战斗打断
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BattleInterrupt {
+    /// 打断标识（战斗结果中原样返回）
+    pub id: String,
+    pub trigger: InterruptTrigger,
+}
+
+impl InterruptTrigger {
+    /// 回合结束时判断是否满足条件
+    pub fn is_met(&self, round: u32, side_a_hp_ratio: f64, side_b_hp_ratio: f64) -> bool {
+        match *self {
+            InterruptTrigger::Round(target) => round >= target,
+            InterruptTrigger::HpBelow { side, ratio } => {
+                let current = match side {
+                    Side::A => side_a_hp_ratio,
+                    Side::B => side_b_hp_ratio,
+                };
+                current < ratio
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::battle::battle_config::BattleConfig;
+    use crate::battle::battle_engine::BattleEngine;
+    use crate::battle::battle_state::BattleResult;
+    use crate::character::panel::{CharacterPanel, ThreeDimensional};
+    use crate::effect::executor::EntryExecutor;
+
+    #[test]
+    fn interrupt_ends_battle_with_its_id() {
+        let side_a = CharacterPanel::new("角色A".to_string(), ThreeDimensional::new(10, 8, 12));
+        let side_b = CharacterPanel::new("角色B".to_string(), ThreeDimensional::new(10, 8, 12));
+        let mut engine =
+            BattleEngine::new(&side_a, &side_b, EntryExecutor::new(), EntryExecutor::new());
+        engine.set_config(BattleConfig {
+            max_rounds: 200,
+            ..BattleConfig::default()
+        });
+        engine.set_interrupts(vec![
+            BattleInterrupt {
+                id: "never".to_string(),
+                trigger: InterruptTrigger::HpBelow {
+                    side: Side::A,
+                    ratio: 0.0,
+                },
+            },
+            BattleInterrupt {
+                id: "round_3".to_string(),
+                trigger: InterruptTrigger::Round(3),
+            },
+        ]);
+
+        assert_eq!(engine.run(), BattleResult::Interrupted);
+        assert_eq!(engine.interruption(), Some("round_3"));
+        assert_eq!(engine.summary().rounds, 3);
+    }
+}
@@ -1,9 +1,17 @@
 /// 战斗面板
 /// 战斗时的临时面板，基于角色面板创建，应用词条效果后用于战斗计算
+use super::battle_config::{AttributeCap, AttributeClamp, MomentumRule, UnarmedStats};
+use super::battle_counter::CounterAttack;
+use super::battle_ultimate::UltimateMove;
 use crate::character::panel::CharacterPanel;
+use crate::effect::effect::SkillSlot;
+use serde::{Deserialize, Serialize};
+
+/// 武技被封时显示的招式名称
+pub const UNARMED_SKILL_NAME: &str = "空手";
 
 /// 战斗面板
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BattlePanel {
     /// 角色名称
     pub name: String,
@@ -55,12 +63,16 @@ pub struct BattlePanel {
     pub attack_skill_name: Option<String>,
     /// 当前攻击武技日志模板
     pub attack_skill_log_template: Option<String>,
+    /// 当前攻击武技的绝招
+    pub attack_skill_ultimate: Option<UltimateMove>,
     /// 当前防御武技 ID
     pub defense_skill_id: Option<String>,
     /// 当前防御武技名称
     pub defense_skill_name: Option<String>,
     /// 当前防御武技日志模板
     pub defense_skill_log_template: Option<String>,
+    /// 当前防御武技的反击
+    pub defense_skill_counter: Option<CounterAttack>,
 
     /// 威能（攻击武技属性）
     pub power: f64,
@@ -75,16 +87,34 @@ pub struct BattlePanel {
     pub qi_recovery_rate: f64,
     /// 蓄力时间（攻击武技属性）
     pub charge_time: f64,
+
+    // ========== 气势 ==========
+    /// 当前气势（攻防得手时积累、回合间衰减）
+    pub momentum: f64,
+    /// 气势上限（由战斗配置的气势规则决定）
+    pub max_momentum: f64,
+
+    // ========== 封招状态 ==========
+    /// 攻击武技被封的剩余回合数
+    pub attack_seal_rounds: u32,
+    /// 防御武技被封的剩余回合数
+    pub defense_seal_rounds: u32,
 }
 
 impl BattlePanel {
-    /// 从角色面板创建战斗面板
+    /// 从角色面板创建战斗面板（使用默认空手数值）
     ///
-    /// 注意：进入战斗时，基础攻击力和防御力会重新初始化为：
-    /// - 基础攻击力 = 体魄 * 3
-    /// - 基础防御力 = 体魄 * 2
+    /// 注意：基础攻击力和防御力直接沿用角色面板（默认为体魄 * 3 / 体魄 * 2，
+    /// 另含丹药、炼体等带来的永久强化）
     pub fn from_character_panel(panel: &CharacterPanel) -> Self {
-        let z = panel.three_d.physique as f64;
+        Self::from_character_panel_with_unarmed(panel, &UnarmedStats::default())
+    }
+
+    /// 从角色面板创建战斗面板，未装备的武技/内功以空手数值补足
+    pub fn from_character_panel_with_unarmed(
+        panel: &CharacterPanel,
+        unarmed: &UnarmedStats,
+    ) -> Self {
         let mut battle_panel = Self {
             name: panel.name.clone(),
 
@@ -93,9 +123,8 @@ impl BattlePanel {
             physique: panel.three_d.physique,
             martial_arts_attainment: panel.martial_arts_attainment,
 
-            // 进入战斗时重新初始化基础攻击力和防御力
-            base_attack: 3.0 * z,
-            base_defense: 2.0 * z,
+            base_attack: panel.base_attack,
+            base_defense: panel.base_defense,
 
             max_hp: panel.max_hp,
             hp: panel.hp,
@@ -114,9 +143,11 @@ impl BattlePanel {
             attack_skill_id: panel.current_attack_skill_id.clone(),
             attack_skill_name: panel.current_attack_skill_name.clone(),
             attack_skill_log_template: None, // 将在战斗引擎中设置
+            attack_skill_ultimate: None,     // 将在战斗引擎中设置
             defense_skill_id: panel.current_defense_skill_id.clone(),
             defense_skill_name: panel.current_defense_skill_name.clone(),
             defense_skill_log_template: None, // 将在战斗引擎中设置
+            defense_skill_counter: None,      // 将在战斗引擎中设置
 
             power: panel.power,
             defense_power: panel.defense_power,
@@ -125,21 +156,82 @@ impl BattlePanel {
             attack_speed: panel.attack_speed,
             qi_recovery_rate: panel.qi_recovery_rate,
             charge_time: panel.charge_time,
+
+            momentum: 0.0,
+            max_momentum: MomentumRule::default().max,
+
+            attack_seal_rounds: 0,
+            defense_seal_rounds: 0,
         };
 
-        if battle_panel.internal_id.is_none()
-            && battle_panel.attack_skill_id.is_none()
-            && battle_panel.defense_skill_id.is_none()
-        {
-            battle_panel.attack_speed = 5.0;
-            battle_panel.charge_time = 50.0;
-            battle_panel.power = 1.0;
-            battle_panel.defense_power = 1.0;
+        if battle_panel.internal_id.is_none() {
+            battle_panel.attack_speed = unarmed.attack_speed;
+        }
+        if battle_panel.attack_skill_id.is_none() {
+            battle_panel.power = battle_panel.unarmed_power(unarmed);
+            battle_panel.charge_time =
+                unarmed.charge_time_for(battle_panel.martial_arts_attainment);
+        }
+        if battle_panel.defense_skill_id.is_none() {
+            battle_panel.defense_power = battle_panel.unarmed_defense_power(unarmed);
         }
 
         battle_panel
     }
 
+    /// 空手威能（由体魄与武学素养推导）
+    pub fn unarmed_power(&self, unarmed: &UnarmedStats) -> f64 {
+        unarmed.power_for(self.physique, self.martial_arts_attainment)
+    }
+
+    /// 空手守御（由体魄与武学素养推导）
+    pub fn unarmed_defense_power(&self, unarmed: &UnarmedStats) -> f64 {
+        unarmed.defense_power_for(self.physique, self.martial_arts_attainment)
+    }
+
+    /// 封住指定武技若干回合（已被封时取较大值）
+    pub fn seal_skill(&mut self, slot: SkillSlot, rounds: u32) {
+        let seal_rounds = match slot {
+            SkillSlot::AttackSkill => &mut self.attack_seal_rounds,
+            SkillSlot::DefenseSkill => &mut self.defense_seal_rounds,
+        };
+        *seal_rounds = (*seal_rounds).max(rounds);
+    }
+
+    /// 若武技被封，以空手数值替换对应武技属性，返回是否有武技被封
+    pub fn apply_seals(&mut self, unarmed: &UnarmedStats) -> bool {
+        if self.attack_seal_rounds > 0 {
+            self.power = self.unarmed_power(unarmed);
+            self.attack_skill_name = Some(UNARMED_SKILL_NAME.to_string());
+            self.attack_skill_log_template = None;
+            self.attack_skill_ultimate = None;
+        }
+        if self.defense_seal_rounds > 0 {
+            self.defense_power = self.unarmed_defense_power(unarmed);
+            self.defense_skill_name = Some(UNARMED_SKILL_NAME.to_string());
+            self.defense_skill_log_template = None;
+            self.defense_skill_counter = None;
+        }
+        self.attack_seal_rounds > 0 || self.defense_seal_rounds > 0
+    }
+
+    /// 封招回合数减一，返回本次解封的武技槽位
+    pub fn tick_seals(&mut self) -> Vec<SkillSlot> {
+        let mut released = Vec::new();
+        for (slot, seal_rounds) in [
+            (SkillSlot::AttackSkill, &mut self.attack_seal_rounds),
+            (SkillSlot::DefenseSkill, &mut self.defense_seal_rounds),
+        ] {
+            if *seal_rounds > 0 {
+                *seal_rounds -= 1;
+                if *seal_rounds == 0 {
+                    released.push(slot);
+                }
+            }
+        }
+        released
+    }
+
     /// 检查是否死亡
     pub fn is_dead(&self) -> bool {
         self.hp <= 0.0
@@ -164,6 +256,8 @@ impl BattlePanel {
             AttributeTarget::AttackSpeed => Some(100.0),
             // 减伤上限本身是一个属性
             AttributeTarget::DamageReduction => Some(self.max_damage_reduction),
+            // 气势上限
+            AttributeTarget::Momentum => Some(self.max_momentum),
             // 其余不设限制
             _ => None,
         }
@@ -192,14 +286,16 @@ impl BattlePanel {
 
     /// 应用属性修改器（支持上限检查）
     ///
-    /// 这是直接修改面板属性的核心方法，由 BattleEngine 调用
+    /// 这是直接修改面板属性的核心方法，由 BattleEngine 调用；
+    /// 配置的属性上限生效时返回约束记录
     pub fn apply_modifier_with_limit(
         &mut self,
         target: &crate::effect::effect::AttributeTarget,
         value: f64,
         operation: &crate::effect::effect::Operation,
         can_exceed_limit: bool,
-    ) {
+        caps: &[AttributeCap],
+    ) -> Option<AttributeClamp> {
         use crate::effect::effect::{AttributeTarget, Operation};
 
         let current_value = match target {
@@ -217,7 +313,8 @@ impl BattlePanel {
             AttributeTarget::ChargeTime => self.charge_time,
             AttributeTarget::MaxQiOutputRate => self.max_qi_output_rate,
             AttributeTarget::QiOutputRate => self.qi_output_rate,
-            _ => return, // 不支持其他属性
+            AttributeTarget::Momentum => self.momentum,
+            _ => return None, // 不支持其他属性
         };
 
         let new_value = match operation {
@@ -229,9 +326,25 @@ impl BattlePanel {
 
         // 检查是否应该跳过（已达到上限且不可突破）
         if self.should_skip_due_to_limit(target, current_value, new_value, can_exceed_limit) {
-            return;
+            return None;
         }
 
+        // 配置的属性上限与软上限
+        let requested = new_value;
+        let new_value = if can_exceed_limit {
+            new_value
+        } else {
+            caps.iter()
+                .filter(|cap| cap.target == *target)
+                .fold(new_value, |value, cap| cap.apply(current_value, value))
+        };
+        let clamp = ((requested - new_value).abs() > 1e-9).then(|| AttributeClamp {
+            character_name: self.name.clone(),
+            target: *target,
+            requested,
+            applied: new_value,
+        });
+
         match target {
             AttributeTarget::Hp => {
                 self.hp = new_value.max(0.0).min(self.max_hp);
@@ -293,8 +406,13 @@ impl BattlePanel {
             AttributeTarget::QiOutputRate => {
                 self.qi_output_rate = new_value.max(0.0).min(self.max_qi_output_rate);
             }
+            AttributeTarget::Momentum => {
+                let limit = self.get_attribute_limit(target).unwrap_or(f64::INFINITY);
+                self.momentum = new_value.max(0.0).min(limit);
+            }
             _ => {}
         }
+        clamp
     }
 
     /// 简化版的属性修改（不检查上限）
@@ -304,7 +422,7 @@ impl BattlePanel {
         value: f64,
         operation: &crate::effect::effect::Operation,
     ) {
-        self.apply_modifier_with_limit(target, value, operation, false);
+        self.apply_modifier_with_limit(target, value, operation, false, &[]);
     }
 }
 
@@ -326,6 +444,22 @@ mod tests {
         assert_eq!(battle_panel.base_defense, 24.0);
     }
 
+    #[test]
+    fn test_unarmed_fallback_per_slot() {
+        let mut char_panel =
+            CharacterPanel::new("测试角色".to_string(), ThreeDimensional::new(10, 8, 20));
+        char_panel.current_defense_skill_id = Some("d".to_string());
+        char_panel.defense_power = 2.5;
+        let battle_panel = BattlePanel::from_character_panel(&char_panel);
+
+        // 未装备攻击武技：威能 = 1 + 体魄 20 × 0.01
+        assert!((battle_panel.power - 1.2).abs() < 1e-9);
+        assert_eq!(battle_panel.charge_time, 50.0);
+        assert_eq!(battle_panel.attack_speed, 5.0);
+        // 已装备防御武技：沿用武技守御
+        assert_eq!(battle_panel.defense_power, 2.5);
+    }
+
     #[test]
     fn test_is_dead() {
         let three_d = ThreeDimensional::new(10, 8, 12);
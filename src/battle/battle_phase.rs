@@ -0,0 +1,121 @@
+/// 战斗阶段（Boss 阶段）
+/// 角色生命比例首次降至阈值时切换攻击/防御武技并追加词条
+use super::battle_counter::CounterAttack;
+use super::battle_ultimate::UltimateMove;
+use crate::effect::entry::Entry;
+use serde::{Deserialize, Serialize};
+
+/// 阶段切换后的攻击武技（已按等级解析为数值）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseAttackSkill {
+    pub id: String,
+    pub name: String,
+    /// 威能
+    pub power: f64,
+    /// 蓄力时间
+    pub charge_time: f64,
+    pub log_template: Option<String>,
+    /// 绝招
+    pub ultimate: Option<UltimateMove>,
+    /// 当前境界词条
+    pub entries: Vec<Entry>,
+}
+
+/// 阶段切换后的防御武技（已按等级解析为数值）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseDefenseSkill {
+    pub id: String,
+    pub name: String,
+    /// 守御
+    pub defense_power: f64,
+    pub log_template: Option<String>,
+    /// 反击
+    pub counter: Option<CounterAttack>,
+    /// 当前境界词条
+    pub entries: Vec<Entry>,
+}
+
+/// 战斗阶段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BattlePhase {
+    pub name: String,
+    /// 触发阈值（当前生命 / 生命上限，降至该值及以下时切换）
+    pub hp_ratio: f64,
+    /// 切换时输出的文本（`{self}` 为所属方，`{opponent}` 为对手）
+    pub text: Option<String>,
+    pub attack_skill: Option<PhaseAttackSkill>,
+    pub defense_skill: Option<PhaseDefenseSkill>,
+    /// 追加词条
+    pub entries: Vec<Entry>,
+}
+
+/// 按阈值从高到低排列阶段，保证同一时刻越过多个阈值时按顺序切换
+pub fn sort_phases(phases: &mut [BattlePhase]) {
+    phases.sort_by(|a, b| b.hp_ratio.total_cmp(&a.hp_ratio));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::battle::battle_config::BattleConfig;
+    use crate::battle::battle_engine::BattleEngine;
+    use crate::battle::battle_record::BattleRecord;
+    use crate::character::panel::{CharacterPanel, ThreeDimensional};
+    use crate::effect::executor::EntryExecutor;
+
+    fn phase(name: &str, hp_ratio: f64) -> BattlePhase {
+        BattlePhase {
+            name: name.to_string(),
+            hp_ratio,
+            text: None,
+            attack_skill: Some(PhaseAttackSkill {
+                id: format!("{}_skill", name),
+                name: format!("{}招", name),
+                power: 5.0,
+                charge_time: 60.0,
+                log_template: None,
+                ultimate: None,
+                entries: Vec::new(),
+            }),
+            defense_skill: None,
+            entries: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn phases_switch_in_threshold_order_once() {
+        let mut phases = vec![
+            phase("二阶段", 0.5),
+            phase("狂暴", 0.0),
+            phase("一阶段", 0.9),
+        ];
+        sort_phases(&mut phases);
+        assert_eq!(phases[0].name, "一阶段");
+
+        let side_a = CharacterPanel::new("角色A".to_string(), ThreeDimensional::new(10, 8, 12));
+        let side_b = CharacterPanel::new("首领".to_string(), ThreeDimensional::new(10, 8, 12));
+        let mut engine =
+            BattleEngine::new(&side_a, &side_b, EntryExecutor::new(), EntryExecutor::new());
+        engine.set_config(BattleConfig {
+            max_rounds: 200,
+            ..BattleConfig::default()
+        });
+        engine.set_side_b_phases(phases);
+        engine.run();
+
+        let changes: Vec<&str> = engine
+            .get_log()
+            .get_all_records()
+            .iter()
+            .filter_map(|record| match record {
+                BattleRecord::PhaseChange { phase_name, .. } => Some(phase_name.as_str()),
+                _ => None,
+            })
+            .collect();
+        let mut deduped = changes.clone();
+        deduped.dedup();
+        assert_eq!(changes, deduped);
+        assert_eq!(changes.first(), Some(&"一阶段"));
+        assert!(!changes.contains(&"狂暴"));
+    }
+}
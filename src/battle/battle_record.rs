@@ -1,11 +1,19 @@
 use super::battle_calculator::BattleCalculationResult;
+use super::battle_config::FirstStrikeRule;
 use super::battle_panel::BattlePanel;
+use super::battle_replay::BattleProvenance;
+use super::battle_state::BattleResult;
+use crate::error::{WushenError, WushenResult};
 /// 战斗记录系统
 /// 记录战斗过程中的所有信息
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 
+/// 战斗日志序列化格式版本
+pub const BATTLE_LOG_VERSION: u32 = 1;
+
 /// 面板变化量（只记录变化的属性）
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct PanelDelta {
     /// 生命值变化（相对于之前的值）
     pub hp_delta: Option<f64>,
@@ -41,6 +49,8 @@ pub struct PanelDelta {
     pub qi_recovery_rate_delta: Option<f64>,
     /// 蓄力时间变化（相对于之前的值）
     pub charge_time_delta: Option<f64>,
+    /// 气势变化（相对于之前的值）
+    pub momentum_delta: Option<f64>,
 }
 
 impl PanelDelta {
@@ -105,6 +115,9 @@ impl PanelDelta {
         if (old.charge_time - new.charge_time).abs() > 0.001 {
             delta.charge_time_delta = Some(new.charge_time - old.charge_time);
         }
+        if (old.momentum - new.momentum).abs() > 0.001 {
+            delta.momentum_delta = Some(new.momentum - old.momentum);
+        }
 
         delta
     }
@@ -128,11 +141,30 @@ impl PanelDelta {
             && self.attack_speed_delta.is_none()
             && self.qi_recovery_rate_delta.is_none()
             && self.charge_time_delta.is_none()
+            && self.momentum_delta.is_none()
     }
 }
 
+/// 战斗开场/收场文本
+///
+/// 模板占位符：`{self}` 为文本所属方名称，`{opponent}` 为对手名称；
+/// 胜负文本均以所属方视角区分
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BattleFlavor {
+    /// 开场文本
+    #[serde(default)]
+    pub intro: Option<String>,
+    /// 所属方获胜时的收场文本
+    #[serde(default)]
+    pub victory: Option<String>,
+    /// 所属方落败时的收场文本
+    #[serde(default)]
+    pub defeat: Option<String>,
+}
+
 /// 战斗记录类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum BattleLogKind {
     /// 词条特效/叙事类日志
     Effect,
@@ -141,26 +173,41 @@ pub enum BattleLogKind {
 }
 
 /// 战斗记录类型
-#[derive(Debug, Clone)]
+/// 序列化为外部标签格式：`{"BattleStart": {...}}`
+///
+/// 双方字段以 side_a/side_b 命名；对外 JSON 与早期日志使用 attacker/defender，
+/// 读取时两种命名均可识别
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum BattleRecord {
     /// 战斗开始
     BattleStart {
         /// Side A 角色名
+        #[serde(alias = "attacker_name")]
         side_a_name: String,
         /// Side B 角色名
+        #[serde(alias = "defender_name")]
         side_b_name: String,
+        /// 双方同时蓄力完成时的先手规则
+        #[serde(default)]
+        first_strike: FirstStrikeRule,
         /// Side A 面板变化
+        #[serde(alias = "attacker_panel_delta")]
         side_a_panel_delta: Option<PanelDelta>,
         /// Side B 面板变化
+        #[serde(alias = "defender_panel_delta")]
         side_b_panel_delta: Option<PanelDelta>,
     },
     /// 行动条更新
     ActionBarUpdate {
         /// Side A 行动条进度
+        #[serde(alias = "attacker_progress")]
         side_a_progress: f64,
         /// Side B 行动条进度
+        #[serde(alias = "defender_progress")]
         side_b_progress: f64,
+        #[serde(alias = "attacker_panel_delta")]
         side_a_panel_delta: Option<PanelDelta>,
+        #[serde(alias = "defender_panel_delta")]
         side_b_panel_delta: Option<PanelDelta>,
     },
     /// 词条触发
@@ -169,13 +216,18 @@ pub enum BattleRecord {
         entry_id: String,
         /// 词条触发顺序（用于排序）
         entry_order: u64,
+        /// 词条来源的可读名称（特性名、功法名与境界、称号等）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source: Option<String>,
         /// 描述文本
         description: String,
         /// 日志类型（用于前端展示）
         log_kind: BattleLogKind,
         /// 批次ID（用于排序日志）
         batch_id: Option<u64>,
+        #[serde(alias = "attacker_panel_delta")]
         side_a_panel_delta: Option<PanelDelta>,
+        #[serde(alias = "defender_panel_delta")]
         side_b_panel_delta: Option<PanelDelta>,
     },
     /// 攻击动作
@@ -184,7 +236,9 @@ pub enum BattleRecord {
         attacker_name: String,
         /// 武技名称
         skill_name: String,
+        #[serde(alias = "attacker_panel_delta")]
         side_a_panel_delta: Option<PanelDelta>,
+        #[serde(alias = "defender_panel_delta")]
         side_b_panel_delta: Option<PanelDelta>,
     },
     /// 防御动作
@@ -193,7 +247,9 @@ pub enum BattleRecord {
         defender_name: String,
         /// 武技名称
         skill_name: String,
+        #[serde(alias = "attacker_panel_delta")]
         side_a_panel_delta: Option<PanelDelta>,
+        #[serde(alias = "defender_panel_delta")]
         side_b_panel_delta: Option<PanelDelta>,
     },
     /// 回气记录
@@ -206,7 +262,9 @@ pub enum BattleRecord {
         current_qi: f64,
         /// 最大内息
         max_qi: f64,
+        #[serde(alias = "attacker_panel_delta")]
         side_a_panel_delta: Option<PanelDelta>,
+        #[serde(alias = "defender_panel_delta")]
         side_b_panel_delta: Option<PanelDelta>,
     },
     /// 结算结果
@@ -223,7 +281,9 @@ pub enum BattleRecord {
         result: BattleCalculationResult,
         /// 描述文本
         description: String,
+        #[serde(alias = "attacker_panel_delta")]
         side_a_panel_delta: Option<PanelDelta>,
+        #[serde(alias = "defender_panel_delta")]
         side_b_panel_delta: Option<PanelDelta>,
     },
     /// 额外攻击（词条触发）
@@ -246,7 +306,9 @@ pub enum BattleRecord {
         entry_order: u64,
         /// 描述文本
         description: String,
+        #[serde(alias = "attacker_panel_delta")]
         side_a_panel_delta: Option<PanelDelta>,
+        #[serde(alias = "defender_panel_delta")]
         side_b_panel_delta: Option<PanelDelta>,
     },
     /// 回合开始（记录临时特效应用后的面板状态）
@@ -257,14 +319,42 @@ pub enum BattleRecord {
         attacker_name: String,
         /// 防御者名称
         defender_name: String,
+        #[serde(alias = "attacker_panel_delta")]
         side_a_panel_delta: Option<PanelDelta>,
+        #[serde(alias = "defender_panel_delta")]
         side_b_panel_delta: Option<PanelDelta>,
     },
     /// 回合结束
     RoundEnd {
         /// 回合数
         round: u32,
+        #[serde(alias = "attacker_panel_delta")]
+        side_a_panel_delta: Option<PanelDelta>,
+        #[serde(alias = "defender_panel_delta")]
+        side_b_panel_delta: Option<PanelDelta>,
+    },
+    /// 阶段切换
+    PhaseChange {
+        /// 切换阶段的角色名称
+        character_name: String,
+        /// 阶段名称
+        phase_name: String,
+        /// 描述文本
+        description: String,
+        #[serde(alias = "attacker_panel_delta")]
+        side_a_panel_delta: Option<PanelDelta>,
+        #[serde(alias = "defender_panel_delta")]
+        side_b_panel_delta: Option<PanelDelta>,
+    },
+    /// 一对多战斗中 Side B 换人上场（此后的 Side B 变化量属于该敌人）
+    EnemySwitch {
+        /// 上场的敌人名称
+        enemy_name: String,
+        /// 描述文本（为空时不输出日志）
+        description: String,
+        #[serde(alias = "attacker_panel_delta")]
         side_a_panel_delta: Option<PanelDelta>,
+        #[serde(alias = "defender_panel_delta")]
         side_b_panel_delta: Option<PanelDelta>,
     },
     /// 战斗结束
@@ -273,26 +363,116 @@ pub enum BattleRecord {
         winner_name: String,
         /// 结束原因
         reason: String,
+        #[serde(alias = "attacker_panel_delta")]
         side_a_panel_delta: Option<PanelDelta>,
+        #[serde(alias = "defender_panel_delta")]
         side_b_panel_delta: Option<PanelDelta>,
     },
 }
 
+/// 日志保留策略（批量模拟时可减少内存占用）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogPolicy {
+    /// 保留全部记录（默认）
+    #[default]
+    Full,
+    /// 仅保留战斗结束记录
+    SummaryOnly,
+    /// 不保留任何记录
+    None,
+}
+
+impl LogPolicy {
+    /// 是否保留该条记录
+    pub fn keeps(&self, record: &BattleRecord) -> bool {
+        match self {
+            LogPolicy::Full => true,
+            LogPolicy::SummaryOnly => matches!(record, BattleRecord::BattleEnd { .. }),
+            LogPolicy::None => false,
+        }
+    }
+
+    /// 是否需要生成过程记录（词条描述等）
+    pub fn records_details(&self) -> bool {
+        matches!(self, LogPolicy::Full)
+    }
+}
+
+/// 战斗摘要（不依赖日志，任何日志策略下都可获取）
+#[derive(Debug, Clone, PartialEq)]
+pub struct BattleSummary {
+    /// 战斗结果（尚未结束时为 None）
+    pub result: Option<BattleResult>,
+    /// 进行的回合数
+    pub rounds: u32,
+    pub side_a_hp: f64,
+    pub side_a_max_hp: f64,
+    pub side_a_qi: f64,
+    pub side_b_hp: f64,
+    pub side_b_max_hp: f64,
+    pub side_b_qi: f64,
+    /// Side A 累计受到的生命伤害
+    pub side_a_damage_taken: f64,
+    /// Side B 累计受到的生命伤害
+    pub side_b_damage_taken: f64,
+    /// 溯源信息
+    pub provenance: BattleProvenance,
+}
+
 /// 战斗日志
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BattleLog {
+    /// 格式版本（用于日志持久化与回放兼容）
+    #[serde(default = "default_battle_log_version")]
+    version: u32,
     /// 记录队列
     records: VecDeque<BattleRecord>,
 }
 
+fn default_battle_log_version() -> u32 {
+    BATTLE_LOG_VERSION
+}
+
+impl Default for BattleLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl BattleLog {
     /// 创建新战斗日志
     pub fn new() -> Self {
         Self {
+            version: BATTLE_LOG_VERSION,
             records: VecDeque::new(),
         }
     }
 
+    /// 获取格式版本
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// 序列化为 JSON 字符串
+    pub fn to_json(&self) -> WushenResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| WushenError::ParseError(format!("序列化战斗日志失败: {}", e)))
+    }
+
+    /// 从 JSON 字符串解析，版本高于当前支持版本时报错
+    pub fn from_json(json: &str) -> WushenResult<Self> {
+        let log: Self = serde_json::from_str(json)
+            .map_err(|e| WushenError::ParseError(format!("解析战斗日志失败: {}", e)))?;
+        if log.version > BATTLE_LOG_VERSION {
+            return Err(WushenError::ParseError(format!(
+                "不支持的战斗日志版本: {}（当前支持 {}）",
+                log.version, BATTLE_LOG_VERSION
+            )));
+        }
+        Ok(log)
+    }
+
     /// 添加记录
     pub fn add_record(&mut self, record: BattleRecord) {
         self.records.push_back(record);
@@ -336,6 +516,7 @@ mod tests {
         log.add_record(BattleRecord::BattleStart {
             side_a_name: "A".to_string(),
             side_b_name: "B".to_string(),
+            first_strike: FirstStrikeRule::default(),
             side_a_panel_delta: None,
             side_b_panel_delta: None,
         });
@@ -353,4 +534,33 @@ mod tests {
         delta2.hp_delta = Some(-10.0);
         assert!(!delta2.is_empty());
     }
+
+    #[test]
+    fn test_battle_log_serde_roundtrip() {
+        let mut log = BattleLog::new();
+        log.add_record(BattleRecord::EntryTriggered {
+            entry_id: "e1".to_string(),
+            entry_order: 3,
+            source: Some("铁骨".to_string()),
+            description: "触发".to_string(),
+            log_kind: BattleLogKind::Effect,
+            batch_id: Some(1),
+            side_a_panel_delta: Some(PanelDelta {
+                hp_delta: Some(-5.0),
+                ..PanelDelta::new()
+            }),
+            side_b_panel_delta: None,
+        });
+        let json = log.to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], BATTLE_LOG_VERSION);
+        assert!(value["records"][0]["EntryTriggered"].is_object());
+        assert_eq!(value["records"][0]["EntryTriggered"]["source"], "铁骨");
+
+        let parsed = BattleLog::from_json(&json).unwrap();
+        assert_eq!(parsed, log);
+
+        let future = format!(r#"{{"version":{},"records":[]}}"#, BATTLE_LOG_VERSION + 1);
+        assert!(BattleLog::from_json(&future).is_err());
+    }
 }
@@ -0,0 +1,283 @@
+use super::battle_config::{BattleConfig, FirstStrikeRule};
+use super::battle_interrupt::BattleInterrupt;
+use super::battle_panel::BattlePanel;
+use super::battle_phase::BattlePhase;
+use super::battle_record::{BattleFlavor, LogPolicy};
+use super::battle_training::TrainingMode;
+use crate::effect::entry::Entry;
+use crate::effect::executor::EntryExecutor;
+/// 战斗回放
+/// 记录开战前双方的战斗面板、词条列表与随机种子，可序列化为 JSON 分享；
+/// 按回放重新执行战斗得到与原战斗完全相同的日志，便于重看战斗、排查词条之间的相互作用
+use crate::error::{WushenError, WushenResult};
+use crate::pack::fingerprint::value_digest;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// 战斗回放格式版本
+pub const BATTLE_REPLAY_VERSION: u32 = 1;
+
+/// 战斗引擎版本（与库版本一致）
+pub const BATTLE_ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 战斗回放
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BattleReplay {
+    /// 格式版本
+    #[serde(default = "default_battle_replay_version")]
+    pub version: u32,
+    pub side_a: ReplaySide,
+    pub side_b: ReplaySide,
+    /// 一对多战斗中 Side B 的其余敌人（按出场序号）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_enemies: Vec<ReplaySide>,
+    pub config: BattleConfig,
+    /// 随机先手种子（先手规则为随机先手时覆盖配置中的种子，改动后可观察其他先手走向）
+    pub seed: u64,
+    /// 战斗环境变量
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub environment: BTreeMap<String, f64>,
+    /// 剧情打断条件
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interrupts: Vec<BattleInterrupt>,
+    /// 训练模式
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub training: Option<TrainingMode>,
+    #[serde(default)]
+    pub log_policy: LogPolicy,
+    /// 开战时加载内容的指纹摘要
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_digest: Option<String>,
+}
+
+/// 战斗溯源信息：随战斗结果一同返回，据此把分享出来的战斗结果对应到确切的输入
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BattleProvenance {
+    pub engine_version: String,
+    /// 开战时加载内容的指纹摘要（直接以面板构建的战斗为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_digest: Option<String>,
+    /// 随机先手种子（先手规则不是随机先手时为 0）
+    pub seed: u64,
+    /// 随机数流的当前状态（战斗结束后即为最终状态，未用到随机数时为 0）
+    pub rng_state: u64,
+    /// 战斗配置摘要
+    pub config_hash: String,
+}
+
+/// 回放中一方的开战状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplaySide {
+    /// 战斗面板（已应用开战前的全部调整，如让子与日志模板）
+    pub panel: BattlePanel,
+    /// 创建战斗时的基础面板（百分比效果以此为基准）
+    pub base_panel: BattlePanel,
+    /// 词条（按加入顺序）
+    pub entries: Vec<ReplayEntry>,
+    /// 来源ID对应的可读名称
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub source_names: BTreeMap<String, String>,
+    /// 禁用的词条标签（战斗中途加入的阶段词条同样受限）
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub disabled_tags: BTreeSet<String>,
+    #[serde(default)]
+    pub flavor: BattleFlavor,
+    /// 尚未触发的战斗阶段
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub phases: Vec<BattlePhase>,
+}
+
+/// 回放中的词条及其来源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayEntry {
+    pub source_id: String,
+    pub entry: Entry,
+}
+
+fn default_battle_replay_version() -> u32 {
+    BATTLE_REPLAY_VERSION
+}
+
+impl BattleReplay {
+    /// 序列化为 JSON 字符串
+    pub fn to_json(&self) -> WushenResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| WushenError::ParseError(format!("序列化战斗回放失败: {}", e)))
+    }
+
+    /// 从 JSON 字符串解析，版本高于当前支持版本时报错
+    pub fn from_json(json: &str) -> WushenResult<Self> {
+        let replay: Self = serde_json::from_str(json)
+            .map_err(|e| WushenError::ParseError(format!("解析战斗回放失败: {}", e)))?;
+        if replay.version > BATTLE_REPLAY_VERSION {
+            return Err(WushenError::ParseError(format!(
+                "不支持的战斗回放版本: {}（当前支持 {}）",
+                replay.version, BATTLE_REPLAY_VERSION
+            )));
+        }
+        Ok(replay)
+    }
+}
+
+impl BattleProvenance {
+    /// 按战斗配置与随机数状态生成溯源信息
+    pub fn new(config: &BattleConfig, content_digest: Option<String>, rng_state: u64) -> Self {
+        Self {
+            engine_version: BATTLE_ENGINE_VERSION.to_string(),
+            content_digest,
+            seed: match config.first_strike {
+                FirstStrikeRule::Seeded { seed } => seed,
+                _ => 0,
+            },
+            rng_state,
+            config_hash: value_digest(config),
+        }
+    }
+}
+
+impl ReplaySide {
+    /// 记录一方的开战状态
+    pub fn capture(
+        panel: &BattlePanel,
+        base_panel: &BattlePanel,
+        executor: &EntryExecutor,
+        flavor: &BattleFlavor,
+        phases: &[BattlePhase],
+    ) -> Self {
+        Self {
+            panel: panel.clone(),
+            base_panel: base_panel.clone(),
+            entries: executor
+                .entries_in_order()
+                .into_iter()
+                .map(|(source_id, entry)| ReplayEntry {
+                    source_id: source_id.to_string(),
+                    entry: entry.clone(),
+                })
+                .collect(),
+            source_names: executor
+                .source_names()
+                .map(|(id, name)| (id.to_string(), name.to_string()))
+                .collect(),
+            disabled_tags: executor.disabled_tags().clone(),
+            flavor: flavor.clone(),
+            phases: phases.to_vec(),
+        }
+    }
+
+    /// 按记录的词条重建词条执行器
+    pub fn executor(&self) -> EntryExecutor {
+        let mut executor = EntryExecutor::new();
+        executor.disable_tags(self.disabled_tags.iter().cloned());
+        for (source_id, source_name) in &self.source_names {
+            executor.add_entries_with_named_source(
+                Vec::new(),
+                source_id.clone(),
+                source_name.clone(),
+            );
+        }
+        for replay_entry in &self.entries {
+            executor
+                .add_entry_with_source(replay_entry.entry.clone(), replay_entry.source_id.clone());
+        }
+        executor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::battle::battle_config::FirstStrikeRule;
+    use crate::battle::battle_engine::BattleEngine;
+    use crate::battle::battle_panel::BattlePanel;
+    use crate::battle::battle_record::BattleRecord;
+    use crate::battle::battle_state::Side;
+    use crate::battle::BattleResult;
+    use crate::character::panel::{CharacterPanel, ThreeDimensional};
+    use serde_json::{json, Value};
+
+    #[test]
+    fn replay_reproduces_the_battle_log() {
+        let side_a = CharacterPanel::new("角色A".to_string(), ThreeDimensional::new(10, 8, 12));
+        let side_b = CharacterPanel::new("角色B".to_string(), ThreeDimensional::new(10, 8, 12));
+        let entry: Entry = serde_json::from_value(serde_json::json!({
+            "trigger": "before_attack",
+            "max_triggers": 2,
+            "effects": [{"type": "modify_percentage", "target": "damage_bonus",
+                         "value": 0.5, "operation": "add"}]
+        }))
+        .unwrap();
+        let mut side_a_executor = EntryExecutor::new();
+        side_a_executor.add_entries_with_named_source(
+            vec![entry],
+            "trait:猛攻".to_string(),
+            "猛攻".to_string(),
+        );
+        let mut engine = BattleEngine::with_config(
+            &side_a,
+            &side_b,
+            side_a_executor,
+            EntryExecutor::new(),
+            BattleConfig {
+                max_rounds: 20,
+                first_strike: FirstStrikeRule::Seeded { seed: 7 },
+                ..BattleConfig::default()
+            },
+        );
+        engine.side_panel_mut(Side::B).hp *= 0.8;
+
+        let replay = BattleReplay::from_json(&engine.capture_replay().to_json().unwrap()).unwrap();
+        assert_eq!(replay.seed, 7);
+        assert_eq!(replay.side_a.entries[0].source_id, "trait:猛攻");
+        engine.run();
+        assert_eq!(&BattleEngine::replay(&replay), engine.get_log());
+
+        let mut newer = serde_json::to_value(&replay).unwrap();
+        newer["version"] = (BATTLE_REPLAY_VERSION + 1).into();
+        let err = BattleReplay::from_json(&newer.to_string()).unwrap_err();
+        assert_eq!(err.code(), "parse_error");
+        assert!(err.message().contains("不支持的战斗回放版本"), "{}", err);
+    }
+
+    /// 固定对局（取整到 0.01）按回放重新结算，每次交手与终局面板都与记录的结果一致
+    #[test]
+    fn quantized_replay_matches_recorded_result() {
+        let replay = BattleReplay::from_json(include_str!(
+            "../../tests/fixtures/quantized_battle_replay.json"
+        ))
+        .unwrap();
+        let expected: Value = serde_json::from_str(include_str!(
+            "../../tests/fixtures/quantized_battle_result.json"
+        ))
+        .unwrap();
+        let policy = replay.config.numeric_policy;
+
+        let mut engine = BattleEngine::from_replay(&replay);
+        let result = engine.run();
+        let exchanges: Vec<Value> = engine
+            .get_log()
+            .get_all_records()
+            .iter()
+            .filter_map(|record| match record {
+                BattleRecord::CalculationResult {
+                    attacker_name,
+                    result,
+                    ..
+                } => Some(json!({"attacker": attacker_name, "result": result})),
+                _ => None,
+            })
+            .collect();
+        let side = |panel: &BattlePanel| {
+            let (hp, qi) = (policy.quantize(panel.hp), policy.quantize(panel.qi));
+            json!({"hp": hp, "qi": qi})
+        };
+        let actual = json!({
+            "result": format!("{:?}", result),
+            "exchanges": exchanges,
+            "side_a": side(engine.get_side_a_panel()),
+            "side_b": side(engine.get_side_b_panel()),
+        });
+        assert_eq!(result, BattleResult::SideAWin);
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,155 @@
+/// 一对多战斗
+/// Side B 同一时刻只有一名敌人在场，其余敌人在场下等候，行动条照常推进；
+/// 轮到场下敌人出手、Side A 选定其为目标或在场敌人落败时，与在场敌人互换
+use super::battle_panel::BattlePanel;
+use super::battle_phase::BattlePhase;
+use super::battle_record::BattleFlavor;
+use crate::effect::executor::EntryExecutor;
+use serde::{Deserialize, Serialize};
+
+/// 在场下等候（或已落败）的敌人
+#[derive(Clone)]
+pub(crate) struct BenchedEnemy {
+    /// 出场序号（0 为开战时在场的敌人）
+    pub index: usize,
+    pub panel: BattlePanel,
+    pub base: BattlePanel,
+    pub last_panel: BattlePanel,
+    pub base_charge_time: f64,
+    pub executor: EntryExecutor,
+    pub damage_taken: f64,
+    pub flavor: BattleFlavor,
+    pub phases: Vec<BattlePhase>,
+    pub ultimate_uses: u32,
+    /// 行动条进度与蓄力时间
+    pub progress: f64,
+    pub charge_time: f64,
+}
+
+impl BenchedEnemy {
+    pub fn new(
+        index: usize,
+        panel: BattlePanel,
+        base: BattlePanel,
+        executor: EntryExecutor,
+        flavor: BattleFlavor,
+        phases: Vec<BattlePhase>,
+    ) -> Self {
+        Self {
+            index,
+            last_panel: panel.clone(),
+            base_charge_time: base.charge_time,
+            charge_time: base.charge_time,
+            panel,
+            base,
+            executor,
+            damage_taken: 0.0,
+            flavor,
+            phases,
+            ultimate_uses: 0,
+            progress: 0.0,
+        }
+    }
+
+    pub fn is_defeated(&self) -> bool {
+        self.panel.hp <= 0.0
+    }
+
+    pub fn is_ready(&self) -> bool {
+        !self.is_defeated() && self.progress >= self.charge_time
+    }
+}
+
+/// 战斗中单个敌人的结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnemyOutcome {
+    /// 出场序号（0 为开战时在场的敌人）
+    pub index: usize,
+    pub name: String,
+    pub defeated: bool,
+    pub hp: f64,
+    pub max_hp: f64,
+    /// 累计受到的生命伤害
+    pub damage_taken: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::battle::battle_config::{BattleConfig, TargetRule};
+    use crate::battle::battle_engine::BattleEngine;
+    use crate::battle::battle_record::BattleRecord;
+    use crate::battle::battle_replay::BattleReplay;
+    use crate::battle::battle_state::{BattleResult, Side};
+    use crate::character::panel::{CharacterPanel, ThreeDimensional};
+
+    fn panel(name: &str, stat: u32) -> CharacterPanel {
+        CharacterPanel::new(name.to_string(), ThreeDimensional::new(stat, stat, stat))
+    }
+
+    /// 玩家对战多名敌人，`hp_ratio` 为各敌人开战时的生命比例
+    fn engine(enemies: &[(&str, f64)], target_rule: TargetRule) -> BattleEngine {
+        let player = panel("少侠", 30);
+        let config = BattleConfig {
+            target_rule,
+            ..BattleConfig::default()
+        };
+        let battle = |(name, hp_ratio): (&str, f64)| {
+            let mut engine = BattleEngine::with_config(
+                &player,
+                &panel(name, 8),
+                EntryExecutor::new(),
+                EntryExecutor::new(),
+                config.clone(),
+            );
+            engine.side_panel_mut(Side::B).hp *= hp_ratio;
+            engine
+        };
+        let mut engine = battle(enemies[0]);
+        for enemy in &enemies[1..] {
+            engine.add_enemy(battle(*enemy));
+        }
+        engine
+    }
+
+    #[test]
+    fn all_enemies_must_fall_before_side_a_wins() {
+        let mut engine = engine(&[("山贼甲", 1.0), ("山贼乙", 1.0)], TargetRule::InOrder);
+        let replay = BattleReplay::from_json(&engine.capture_replay().to_json().unwrap()).unwrap();
+        assert_eq!(engine.run(), BattleResult::SideAWin);
+
+        let outcomes = engine.enemy_outcomes();
+        let names: Vec<_> = outcomes.iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(names, ["山贼甲", "山贼乙"]);
+        assert!(outcomes.iter().all(|o| o.defeated && o.damage_taken > 0.0));
+        assert!(engine
+            .get_log()
+            .get_all_records()
+            .iter()
+            .any(|record| matches!(
+                record,
+                BattleRecord::EnemySwitch { description, .. } if description.contains("接着上阵")
+            )));
+        assert_eq!(&BattleEngine::replay(&replay), engine.get_log());
+    }
+
+    #[test]
+    fn target_rule_picks_the_weakest_enemy() {
+        let mut engine = engine(&[("山贼甲", 1.0), ("山贼乙", 0.2)], TargetRule::LowestHp);
+        engine.run();
+        let first_target =
+            engine
+                .get_log()
+                .get_all_records()
+                .iter()
+                .find_map(|record| match record {
+                    BattleRecord::RoundStart {
+                        attacker_name,
+                        defender_name,
+                        ..
+                    } if attacker_name == "少侠" => Some(defender_name.clone()),
+                    _ => None,
+                });
+        assert_eq!(first_target.as_deref(), Some("山贼乙"));
+    }
+}
@@ -0,0 +1,115 @@
+/// 开战装配检查
+/// 角色装备的功法在当前内容中不存在时，按未装备处理会打出一场零威力的战斗；
+/// 开战前列出这些功法，由战斗配置决定照常开战并提示还是拒绝开战
+use super::battle_state::Side;
+use crate::character::panel::CharacterPanel;
+use crate::cultivation::manual_manager::ManualManager;
+use serde::{Deserialize, Serialize};
+
+/// 装备的功法未加载时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingManualPolicy {
+    /// 按未装备处理，不在战斗结果中提示
+    Ignore,
+    /// 照常开战，在战斗结果中列出未能解析的功法（默认）
+    #[default]
+    Warn,
+    /// 拒绝开战
+    Reject,
+}
+
+/// 未能解析的装备功法
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnresolvedManual {
+    pub side: Side,
+    /// 装备该功法的角色名称
+    pub character: String,
+    /// 功法类型（internal / attack_skill / defense_skill）
+    pub manual_type: &'static str,
+    pub manual_id: String,
+}
+
+impl UnresolvedManual {
+    /// 列出角色装备但未加载的功法
+    pub fn collect(side: Side, panel: &CharacterPanel, manuals: &ManualManager) -> Vec<Self> {
+        let equipped = [
+            (
+                "internal",
+                panel
+                    .current_internal_id
+                    .as_ref()
+                    .filter(|id| manuals.get_internal(id).is_none()),
+            ),
+            (
+                "attack_skill",
+                panel
+                    .current_attack_skill_id
+                    .as_ref()
+                    .filter(|id| manuals.get_attack_skill(id).is_none()),
+            ),
+            (
+                "defense_skill",
+                panel
+                    .current_defense_skill_id
+                    .as_ref()
+                    .filter(|id| manuals.get_defense_skill(id).is_none()),
+            ),
+        ];
+        equipped
+            .into_iter()
+            .filter_map(|(manual_type, id)| {
+                Some(Self {
+                    side,
+                    character: panel.name.clone(),
+                    manual_type,
+                    manual_id: id?.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// 拒绝开战时的错误说明
+    pub fn describe(unresolved: &[Self]) -> String {
+        let items: Vec<String> = unresolved
+            .iter()
+            .map(|manual| {
+                format!(
+                    "{} 的{} {}",
+                    manual.character,
+                    match manual.manual_type {
+                        "internal" => "内功",
+                        "attack_skill" => "攻击武技",
+                        _ => "防御武技",
+                    },
+                    manual.manual_id
+                )
+            })
+            .collect();
+        format!("装备的功法未加载: {}", items.join("，"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::panel::ThreeDimensional;
+
+    #[test]
+    fn collect_lists_only_equipped_manuals_that_are_not_loaded() {
+        let mut panel = CharacterPanel::new("少侠".to_string(), ThreeDimensional::new(5, 5, 5));
+        panel.current_internal_id = Some("失传心法".to_string());
+        panel.current_defense_skill_id = Some("金钟罩".to_string());
+
+        let unresolved = UnresolvedManual::collect(Side::A, &panel, &ManualManager::new());
+        let ids: Vec<_> = unresolved
+            .iter()
+            .map(|manual| (manual.manual_type, manual.manual_id.as_str()))
+            .collect();
+        assert_eq!(ids, [("internal", "失传心法"), ("defense_skill", "金钟罩")]);
+        assert_eq!(
+            UnresolvedManual::describe(&unresolved),
+            "装备的功法未加载: 少侠 的内功 失传心法，少侠 的防御武技 金钟罩"
+        );
+    }
+}
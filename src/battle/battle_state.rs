@@ -1,9 +1,11 @@
 /// 战斗状态机
 /// 战斗的状态和流程控制
 use super::battle_calculator::BattleCalculationResult;
+use serde::{Deserialize, Serialize};
 
 /// 战斗双方标识
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Side {
     /// 战斗中的一方（初始化时的第一个角色）
     A,
@@ -30,6 +32,8 @@ pub enum BattleResult {
     SideBWin,
     /// 平局（达到最大轮数）
     Draw,
+    /// 被剧情脚本打断（不分胜负）
+    Interrupted,
 }
 
 /// 战斗状态
@@ -0,0 +1,114 @@
+/// 训练模式（木人桩）
+/// Side A 生命不会降至 1 以下，战斗在指定轮数后结束并给出表现评分
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Serialize};
+
+/// 训练模式配置
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrainingMode {
+    /// 训练轮数（达到后结束战斗）
+    pub rounds: u32,
+}
+
+impl TrainingMode {
+    pub fn validate(&self) -> WushenResult<()> {
+        if self.rounds == 0 {
+            return Err(WushenError::Invalid("训练轮数必须大于 0".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// 训练过程统计（Side A 视角）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TrainingStats {
+    /// 发起攻击次数
+    pub attacks: u32,
+    /// 攻破对方内息防御的次数
+    pub attacks_broke_through: u32,
+    /// 承受攻击次数
+    pub defenses: u32,
+    /// 以内息挡下攻击的次数
+    pub defenses_held: u32,
+}
+
+/// 训练表现评分
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrainingScore {
+    pub rounds: u32,
+    /// 造成的生命伤害
+    pub damage_dealt: f64,
+    /// 受到的生命伤害（不受生命下限影响）
+    pub damage_taken: f64,
+    pub attacks: u32,
+    pub attacks_broke_through: u32,
+    pub defenses: u32,
+    pub defenses_held: u32,
+    /// 综合评分（0-100）：伤害占对方生命上限的比例计 60 分，守住的比例计 40 分
+    pub score: u32,
+}
+
+impl TrainingScore {
+    pub fn new(
+        stats: TrainingStats,
+        rounds: u32,
+        damage_dealt: f64,
+        damage_taken: f64,
+        target_max_hp: f64,
+    ) -> Self {
+        let damage_ratio = if target_max_hp > 0.0 {
+            (damage_dealt / target_max_hp).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        // 未承受攻击时视为全部守住
+        let defense_ratio = if stats.defenses > 0 {
+            stats.defenses_held as f64 / stats.defenses as f64
+        } else {
+            1.0
+        };
+        let score = (damage_ratio * 60.0 + defense_ratio * 40.0).round() as u32;
+        Self {
+            rounds,
+            damage_dealt,
+            damage_taken,
+            attacks: stats.attacks,
+            attacks_broke_through: stats.attacks_broke_through,
+            defenses: stats.defenses,
+            defenses_held: stats.defenses_held,
+            score,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::battle::battle_engine::BattleEngine;
+    use crate::battle::battle_state::BattleResult;
+    use crate::character::panel::{CharacterPanel, ThreeDimensional};
+    use crate::effect::executor::EntryExecutor;
+
+    #[test]
+    fn training_keeps_side_a_alive_and_reports_score() {
+        // 木人桩远强于玩家，正常战斗必败
+        let mut side_a = CharacterPanel::new("学徒".to_string(), ThreeDimensional::new(5, 5, 5));
+        side_a.max_hp = 10.0;
+        side_a.hp = 10.0;
+        let mut side_b = CharacterPanel::new("木人桩".to_string(), ThreeDimensional::new(5, 5, 5));
+        side_b.max_hp = 100000.0;
+        side_b.hp = 100000.0;
+        side_b.base_attack = 500.0;
+        let mut engine =
+            BattleEngine::new(&side_a, &side_b, EntryExecutor::new(), EntryExecutor::new());
+        engine.set_training(Some(TrainingMode { rounds: 6 }));
+
+        assert_eq!(engine.run(), BattleResult::Draw);
+        assert_eq!(engine.get_side_a_panel().hp, 1.0);
+        let score = engine.training_score().expect("训练模式应给出评分");
+        assert_eq!(score.rounds, 6);
+        assert!(score.damage_taken > 10.0);
+        assert_eq!(score.attacks + score.defenses, 6);
+        assert!(score.score <= 100);
+    }
+}
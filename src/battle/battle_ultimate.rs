@@ -0,0 +1,141 @@
+use crate::character::panel::{CharacterPanel, ThreeDimensional};
+use crate::effect::condition::Condition;
+use crate::effect::formula::{BattleFormulaContext, FormulaCalculator};
+/// 攻击武技绝招
+/// 出手时内息不低于阈值且条件满足，以绝招代替普通攻击：按绝招公式计算威能、额外消耗内息，
+/// 并以绝招的日志模板记录
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 绝招配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UltimateMove {
+    pub name: String,
+    /// 施展所需的内息（出手时内息不低于该值）
+    pub qi_threshold: f64,
+    /// 施展时额外消耗的内息（不超过施展所需的内息）
+    #[serde(default)]
+    pub qi_cost: f64,
+    /// 施展条件（战斗条件，为空时只看内息）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<Condition>,
+    /// 威能公式（代替武技威能，可使用 self_power、opponent_hp 等战斗公式变量）
+    pub power_formula: String,
+    /// 绝招日志模板（支持 {self} 和 {opponent} 占位符）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_template: Option<String>,
+    /// 每场战斗最多施展次数（为空时不限）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_uses: Option<u32>,
+}
+
+impl UltimateMove {
+    pub fn validate(&self) -> WushenResult<()> {
+        if self.name.trim().is_empty() {
+            return Err(WushenError::Invalid("绝招名称不能为空".to_string()));
+        }
+        if !self.qi_threshold.is_finite() || self.qi_threshold < 0.0 {
+            return Err(WushenError::Invalid(
+                "绝招施展所需内息必须为非负数".to_string(),
+            ));
+        }
+        if !self.qi_cost.is_finite() || self.qi_cost < 0.0 {
+            return Err(WushenError::Invalid("绝招内息消耗必须为非负数".to_string()));
+        }
+        if self.qi_cost > self.qi_threshold {
+            return Err(WushenError::Invalid(
+                "绝招内息消耗不能超过施展所需内息".to_string(),
+            ));
+        }
+        if self.max_uses == Some(0) {
+            return Err(WushenError::Invalid("绝招施展次数至少为 1".to_string()));
+        }
+        // 以空白面板试算，提前发现公式语法错误与未知变量（出手前尚无攻击结果变量）
+        let panel = CharacterPanel::new(String::new(), ThreeDimensional::new(0, 0, 0));
+        let context = BattleFormulaContext {
+            self_panel: panel.clone(),
+            opponent_panel: Some(panel),
+            attack_result: None,
+            environment: BTreeMap::new(),
+        };
+        FormulaCalculator::evaluate_battle(&self.power_formula, &context).map_err(|e| {
+            WushenError::Invalid(format!("绝招「{}」威能公式无效: {}", self.name, e))
+        })?;
+        Ok(())
+    }
+
+    /// 未配置日志模板时的默认日志
+    pub fn default_log_template(&self) -> String {
+        format!("{{self}}施展绝招「{}」", self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::battle::battle_config::BattleConfig;
+    use crate::battle::battle_engine::BattleEngine;
+    use crate::battle::battle_record::BattleRecord;
+    use crate::battle::battle_state::Side;
+    use crate::effect::executor::EntryExecutor;
+
+    fn ultimate(max_uses: Option<u32>) -> UltimateMove {
+        serde_json::from_value(serde_json::json!({
+            "name": "天外飞仙",
+            "qi_threshold": 10.0,
+            "qi_cost": 10.0,
+            "power_formula": "self_power * 100",
+            "log_template": "{self}一剑西来，{opponent}避无可避",
+            "max_uses": max_uses
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn ultimate_replaces_attack_when_qi_is_enough() {
+        assert!(ultimate(Some(0)).validate().is_err());
+        let mut invalid = ultimate(None);
+        invalid.power_formula = "attack_hp_damage * 2".to_string();
+        assert!(invalid.validate().is_err());
+        ultimate(Some(1)).validate().unwrap();
+
+        let side_a = CharacterPanel::new("角色A".to_string(), ThreeDimensional::new(10, 8, 12));
+        let side_b = CharacterPanel::new("角色B".to_string(), ThreeDimensional::new(10, 8, 12));
+        let mut engine =
+            BattleEngine::new(&side_a, &side_b, EntryExecutor::new(), EntryExecutor::new());
+        engine.set_config(BattleConfig {
+            max_rounds: 20,
+            ..BattleConfig::default()
+        });
+        let panel = engine.side_panel_mut(Side::A);
+        panel.max_qi = 50.0;
+        panel.qi = 50.0;
+        engine.set_side_a_attack_ultimate(Some(ultimate(Some(1))));
+        engine.run();
+
+        let descriptions: Vec<&str> = engine
+            .get_log()
+            .get_all_records()
+            .iter()
+            .filter_map(|record| match record {
+                BattleRecord::EntryTriggered {
+                    entry_id,
+                    description,
+                    ..
+                } if entry_id == "attack_skill_ultimate" => Some(description.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(descriptions, ["角色A一剑西来，角色B避无可避"]);
+        assert!(engine
+            .get_log()
+            .get_all_records()
+            .iter()
+            .any(|record| matches!(
+                record,
+                BattleRecord::CalculationResult { attacker_skill: Some(skill), .. }
+                    if skill == "天外飞仙"
+            )));
+    }
+}
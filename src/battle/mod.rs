@@ -1,6 +1,38 @@
-pub mod action_bar;
-pub mod battle_calculator;
-pub mod battle_engine;
-pub mod battle_panel;
-pub mod battle_record;
-pub mod battle_state;
+pub(crate) mod action_bar;
+pub(crate) mod battle_calculator;
+pub(crate) mod battle_config;
+pub(crate) mod battle_counter;
+pub(crate) mod battle_engine;
+pub(crate) mod battle_interrupt;
+pub(crate) mod battle_panel;
+pub(crate) mod battle_phase;
+pub(crate) mod battle_record;
+pub(crate) mod battle_replay;
+pub(crate) mod battle_roster;
+pub(crate) mod battle_setup;
+pub(crate) mod battle_state;
+pub(crate) mod battle_training;
+pub(crate) mod battle_ultimate;
+pub(crate) mod qi_strategy;
+
+// 重新导出常用类型
+pub use battle_calculator::BattleCalculationResult;
+pub use battle_config::{
+    AttributeCap, AttributeClamp, BaseQiPool, BattleConfig, FirstStrikeRule, MomentumRule,
+    NumericPolicy, TargetRule, TiebreakerRule, UnarmedStats,
+};
+pub use battle_counter::CounterAttack;
+pub use battle_engine::BattleEngine;
+pub use battle_interrupt::{BattleInterrupt, InterruptTrigger};
+pub use battle_panel::BattlePanel;
+pub use battle_phase::{BattlePhase, PhaseAttackSkill, PhaseDefenseSkill};
+pub use battle_record::{
+    BattleFlavor, BattleLog, BattleLogKind, BattleRecord, BattleSummary, LogPolicy, PanelDelta,
+};
+pub use battle_replay::{BattleProvenance, BattleReplay, ReplayEntry, ReplaySide};
+pub use battle_roster::EnemyOutcome;
+pub use battle_setup::{MissingManualPolicy, UnresolvedManual};
+pub use battle_state::{BattleResult, BattleState, Side};
+pub use battle_training::{TrainingMode, TrainingScore, TrainingStats};
+pub use battle_ultimate::UltimateMove;
+pub use qi_strategy::QiOutputProfile;
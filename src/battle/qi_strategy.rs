@@ -0,0 +1,133 @@
+/// 内息输出自动策略
+/// 非交互战斗中，每回合开始时按策略调整内息输出，使同一角色在不同战斗中呈现不同打法
+use serde::{Deserialize, Serialize};
+
+use super::battle_panel::BattlePanel;
+
+/// 内息输出策略
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QiOutputProfile {
+    /// 固定输出（沿用战斗开始时的内息输出，不做调整）
+    #[default]
+    Fixed,
+    /// 先声夺人：前若干回合全力输出，之后降为上限的一定比例
+    BurstEarly {
+        #[serde(default = "default_burst_rounds")]
+        rounds: u32,
+        /// 爆发结束后的输出比例（相对最大内息输出）
+        #[serde(default = "default_low_ratio")]
+        later_ratio: f64,
+    },
+    /// 蓄力收官：对手生命百分比高于阈值时保守输出，低于阈值时全力输出
+    SaveForFinisher {
+        /// 对手生命百分比阈值
+        #[serde(default = "default_finisher_threshold")]
+        opponent_hp_threshold: f64,
+        /// 保守阶段的输出比例（相对最大内息输出）
+        #[serde(default = "default_low_ratio")]
+        saving_ratio: f64,
+    },
+    /// 以彼之道：按对手当前输出占其上限的比例输出
+    MirrorOpponent,
+}
+
+fn default_burst_rounds() -> u32 {
+    3
+}
+
+fn default_low_ratio() -> f64 {
+    0.3
+}
+
+fn default_finisher_threshold() -> f64 {
+    0.3
+}
+
+impl QiOutputProfile {
+    /// 计算本回合的内息输出，固定输出时返回 None
+    ///
+    /// `round` 为即将开始的回合数（从 1 开始）
+    pub fn rate_for(&self, own: &BattlePanel, opponent: &BattlePanel, round: u32) -> Option<f64> {
+        let max_rate = own.max_qi_output_rate;
+        let ratio = match self {
+            QiOutputProfile::Fixed => return None,
+            QiOutputProfile::BurstEarly {
+                rounds,
+                later_ratio,
+            } => {
+                if round <= *rounds {
+                    1.0
+                } else {
+                    *later_ratio
+                }
+            }
+            QiOutputProfile::SaveForFinisher {
+                opponent_hp_threshold,
+                saving_ratio,
+            } => {
+                let opponent_hp_percent = if opponent.max_hp > 0.0 {
+                    opponent.hp / opponent.max_hp
+                } else {
+                    0.0
+                };
+                if opponent_hp_percent <= *opponent_hp_threshold {
+                    1.0
+                } else {
+                    *saving_ratio
+                }
+            }
+            QiOutputProfile::MirrorOpponent => {
+                if opponent.max_qi_output_rate > 0.0 {
+                    opponent.qi_output_rate / opponent.max_qi_output_rate
+                } else {
+                    1.0
+                }
+            }
+        };
+        Some((max_rate * ratio).clamp(0.0, max_rate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::panel::{CharacterPanel, ThreeDimensional};
+
+    fn panel(name: &str) -> BattlePanel {
+        let char_panel = CharacterPanel::new(name.to_string(), ThreeDimensional::new(10, 10, 10));
+        let mut panel = BattlePanel::from_character_panel(&char_panel);
+        panel.max_qi_output_rate = 0.5;
+        panel.qi_output_rate = 0.5;
+        panel
+    }
+
+    #[test]
+    fn profiles_adjust_rate_by_situation() {
+        let own = panel("A");
+        let mut opponent = panel("B");
+
+        assert_eq!(QiOutputProfile::Fixed.rate_for(&own, &opponent, 1), None);
+
+        let burst = QiOutputProfile::BurstEarly {
+            rounds: 2,
+            later_ratio: 0.2,
+        };
+        assert_eq!(burst.rate_for(&own, &opponent, 2), Some(0.5));
+        assert_eq!(burst.rate_for(&own, &opponent, 3), Some(0.1));
+
+        let finisher = QiOutputProfile::SaveForFinisher {
+            opponent_hp_threshold: 0.3,
+            saving_ratio: 0.0,
+        };
+        assert_eq!(finisher.rate_for(&own, &opponent, 1), Some(0.0));
+        opponent.hp = opponent.max_hp * 0.2;
+        assert_eq!(finisher.rate_for(&own, &opponent, 1), Some(0.5));
+
+        opponent.qi_output_rate = 0.25;
+        assert_eq!(
+            QiOutputProfile::MirrorOpponent.rate_for(&own, &opponent, 1),
+            Some(0.25)
+        );
+    }
+}
@@ -0,0 +1,20 @@
+//! 本地 JSON-RPC 服务入口
+//! 用法：`cargo run --features server --bin wushen-server -- [地址] [允许跨域的来源...]`，
+//! 默认 127.0.0.1:7878，未指定来源时只放行本机来源；只接受以本机名称访问的请求（Host 校验）
+
+use wushen_core::server::{self, ServerConfig};
+use wushen_core::tauri_api::WushenCore;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let addr = args.next().unwrap_or_else(|| "127.0.0.1:7878".to_string());
+    let config = ServerConfig {
+        allowed_origins: args.collect(),
+        ..ServerConfig::default()
+    };
+    println!("wushen-core 服务已启动: http://{}/rpc", addr);
+    if let Err(e) = server::serve_with_config(addr.as_str(), WushenCore::new(), config) {
+        eprintln!("服务启动失败: {}", e);
+        std::process::exit(1);
+    }
+}
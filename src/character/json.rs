@@ -1,3 +1,4 @@
+use crate::error::{WushenError, WushenResult};
 use serde::{Deserialize, Serialize};
 
 use crate::character::panel::{CharacterPanel, ThreeDimensional};
@@ -11,11 +12,27 @@ struct CharacterPanelJson {
     attack_skills: ManualsJson,
     defense_skills: ManualsJson,
     #[serde(default)]
+    max_hp: Option<f64>,
+    #[serde(default)]
+    hp: Option<f64>,
+    #[serde(default)]
+    base_attack: Option<f64>,
+    #[serde(default)]
+    base_defense: Option<f64>,
+    #[serde(default)]
     max_qi: Option<f64>,
     #[serde(default)]
     qi: Option<f64>,
     #[serde(default)]
     martial_arts_attainment: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    inventory: Vec<ItemStackJson>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ItemStackJson {
+    id: String,
+    count: u32,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -38,9 +55,9 @@ struct OwnedManualJson {
     exp: f64,
 }
 
-pub(crate) fn parse_character_panel(json: &str) -> Result<CharacterPanel, String> {
-    let data: CharacterPanelJson =
-        serde_json::from_str(json).map_err(|e| format!("解析角色数据失败: {}", e))?;
+pub(crate) fn parse_character_panel(json: &str) -> WushenResult<CharacterPanel> {
+    let data: CharacterPanelJson = serde_json::from_str(json)
+        .map_err(|e| WushenError::ParseError(format!("解析角色数据失败: {}", e)))?;
 
     let three_d = ThreeDimensional::new(
         data.three_d.comprehension,
@@ -72,6 +89,20 @@ pub(crate) fn parse_character_panel(json: &str) -> Result<CharacterPanel, String
         panel.current_defense_skill_id = Some(id);
     }
 
+    if let Some(max_hp) = data.max_hp {
+        panel.max_hp = max_hp;
+        panel.hp = max_hp;
+    }
+    if let Some(hp) = data.hp {
+        panel.hp = hp.min(panel.max_hp);
+    }
+    if let Some(base_attack) = data.base_attack {
+        panel.base_attack = base_attack;
+    }
+    if let Some(base_defense) = data.base_defense {
+        panel.base_defense = base_defense;
+    }
+
     if let Some(max_qi) = data.max_qi {
         panel.max_qi = max_qi;
     }
@@ -86,10 +117,14 @@ pub(crate) fn parse_character_panel(json: &str) -> Result<CharacterPanel, String
         panel.martial_arts_attainment = martial_arts_attainment;
     }
 
+    for stack in data.inventory {
+        panel.add_item(&stack.id, stack.count);
+    }
+
     Ok(panel)
 }
 
-pub(crate) fn serialize_character_panel(panel: &CharacterPanel) -> Result<String, String> {
+pub(crate) fn serialize_character_panel(panel: &CharacterPanel) -> WushenResult<String> {
     let three_d = ThreeDimensionalJson {
         comprehension: panel.three_d.comprehension,
         bone_structure: panel.three_d.bone_structure,
@@ -142,10 +177,23 @@ pub(crate) fn serialize_character_panel(panel: &CharacterPanel) -> Result<String
         internals,
         attack_skills,
         defense_skills,
+        max_hp: Some(panel.max_hp),
+        hp: Some(panel.hp),
+        base_attack: Some(panel.base_attack),
+        base_defense: Some(panel.base_defense),
         max_qi: Some(panel.max_qi),
         qi: Some(panel.qi),
         martial_arts_attainment: Some(panel.martial_arts_attainment),
+        inventory: panel
+            .inventory
+            .iter()
+            .map(|(id, count)| ItemStackJson {
+                id: id.clone(),
+                count: *count,
+            })
+            .collect(),
     };
 
-    serde_json::to_string(&character_json).map_err(|e| format!("序列化角色数据失败: {}", e))
+    serde_json::to_string(&character_json)
+        .map_err(|e| WushenError::ParseError(format!("序列化角色数据失败: {}", e)))
 }
@@ -1,4 +1,11 @@
-pub mod json;
-pub mod panel;
-pub mod trait_manager;
-pub mod traits;
+// 角色面板 JSON 格式（供运行时接口使用）
+#[cfg(feature = "runtime")]
+pub(crate) mod json;
+pub(crate) mod panel;
+pub(crate) mod trait_manager;
+pub(crate) mod traits;
+
+// 重新导出常用类型
+pub use panel::{CharacterPanel, ThreeDimensional};
+pub use trait_manager::TraitManager;
+pub use traits::{parse_traits, Trait};
@@ -21,6 +21,15 @@ impl ThreeDimensional {
     }
 }
 
+/// 每点体魄提供的生命上限（100z）
+pub(crate) const HP_PER_PHYSIQUE: f64 = 100.0;
+/// 奖励或效果调整生命上限时的下限
+pub(crate) const MIN_MAX_HP: f64 = 1.0;
+/// 每点体魄提供的基础攻击（3z）
+pub(crate) const ATTACK_PER_PHYSIQUE: f64 = 3.0;
+/// 每点体魄提供的基础防御（2z）
+pub(crate) const DEFENSE_PER_PHYSIQUE: f64 = 2.0;
+
 /// 角色面板
 #[derive(Debug, Clone)]
 pub struct CharacterPanel {
@@ -74,11 +83,11 @@ pub struct CharacterPanel {
     pub current_defense_skill_name: Option<String>,
 
     /// 拥有的内功（ID -> (等级, 经验值)）
-    pub owned_internals: std::collections::HashMap<String, (u32, f64)>,
+    pub owned_internals: std::collections::BTreeMap<String, (u32, f64)>,
     /// 拥有的攻击武技（ID -> (等级, 经验值)）
-    pub owned_attack_skills: std::collections::HashMap<String, (u32, f64)>,
+    pub owned_attack_skills: std::collections::BTreeMap<String, (u32, f64)>,
     /// 拥有的防御武技（ID -> (等级, 经验值)）
-    pub owned_defense_skills: std::collections::HashMap<String, (u32, f64)>,
+    pub owned_defense_skills: std::collections::BTreeMap<String, (u32, f64)>,
 
     /// 威能（攻击武技属性）
     pub power: f64,
@@ -94,6 +103,9 @@ pub struct CharacterPanel {
     /// 蓄力时间（攻击武技属性）
     pub charge_time: f64,
 
+    /// 气势（仅战斗中存在，非战斗时为 0）
+    pub momentum: f64,
+
     // ========== 特性与特效 ==========
     /// 拥有的特性 ID 列表
     pub traits: Vec<String>,
@@ -101,6 +113,10 @@ pub struct CharacterPanel {
     /// 当前生效的战斗特效（来自特性、武技、内功）
     /// 这是一个动态集合，会在战斗时根据当前状态计算
     pub battle_effects: Vec<String>,
+
+    // ========== 物品 ==========
+    /// 背包（物品 ID -> 数量）
+    pub inventory: std::collections::BTreeMap<String, u32>,
 }
 
 impl CharacterPanel {
@@ -114,14 +130,14 @@ impl CharacterPanel {
             three_d,
             martial_arts_attainment: 0.0,
 
-            max_hp: 100.0 * z,
-            hp: 100.0 * z,
+            max_hp: HP_PER_PHYSIQUE * z,
+            hp: HP_PER_PHYSIQUE * z,
 
             max_qi: 0.0,
             qi: 0.0,
 
-            base_attack: 3.0 * z,
-            base_defense: 2.0 * z,
+            base_attack: ATTACK_PER_PHYSIQUE * z,
+            base_defense: DEFENSE_PER_PHYSIQUE * z,
 
             max_qi_output_rate: 0.3 * y / 100.0, // 0.3y%
             qi_output_rate: 0.0,
@@ -136,9 +152,9 @@ impl CharacterPanel {
             current_defense_skill_id: None,
             current_defense_skill_name: None,
 
-            owned_internals: std::collections::HashMap::new(),
-            owned_attack_skills: std::collections::HashMap::new(),
-            owned_defense_skills: std::collections::HashMap::new(),
+            owned_internals: std::collections::BTreeMap::new(),
+            owned_attack_skills: std::collections::BTreeMap::new(),
+            owned_defense_skills: std::collections::BTreeMap::new(),
 
             power: 0.0,
             defense_power: 0.0,
@@ -148,8 +164,12 @@ impl CharacterPanel {
             qi_recovery_rate: 0.0,
             charge_time: 0.0,
 
+            momentum: 0.0,
+
             traits: Vec::new(),
             battle_effects: Vec::new(),
+
+            inventory: std::collections::BTreeMap::new(),
         }
     }
 
@@ -173,6 +193,18 @@ impl CharacterPanel {
         self.martial_arts_attainment
     }
 
+    /// 设置体魄，并按变化量同步调整由体魄派生的生命与基础攻防
+    pub fn set_physique(&mut self, physique: u32) {
+        let delta = physique as f64 - self.three_d.physique as f64;
+        self.three_d.physique = physique;
+        self.max_hp = (self.max_hp + HP_PER_PHYSIQUE * delta).max(0.0);
+        self.hp = (self.hp + HP_PER_PHYSIQUE * delta)
+            .max(0.0)
+            .min(self.max_hp);
+        self.base_attack = (self.base_attack + ATTACK_PER_PHYSIQUE * delta).max(0.0);
+        self.base_defense = (self.base_defense + DEFENSE_PER_PHYSIQUE * delta).max(0.0);
+    }
+
     /// 检查是否拥有指定的内功
     pub fn has_internal(&self, id: &str) -> bool {
         self.owned_internals.contains_key(id)
@@ -188,6 +220,34 @@ impl CharacterPanel {
         self.owned_defense_skills.contains_key(id)
     }
 
+    /// 持有的物品数量
+    pub fn item_count(&self, id: &str) -> u32 {
+        self.inventory.get(id).copied().unwrap_or(0)
+    }
+
+    /// 放入物品
+    pub fn add_item(&mut self, id: &str, count: u32) {
+        if count == 0 {
+            return;
+        }
+        let stack = self.inventory.entry(id.to_string()).or_insert(0);
+        *stack = stack.saturating_add(count);
+    }
+
+    /// 取出物品（数量不足时不修改背包并返回 false）
+    pub fn remove_item(&mut self, id: &str, count: u32) -> bool {
+        let held = self.item_count(id);
+        if held < count {
+            return false;
+        }
+        if held == count {
+            self.inventory.remove(id);
+        } else {
+            self.inventory.insert(id.to_string(), held - count);
+        }
+        true
+    }
+
     /// 获取拥有的内功等级和经验值
     pub fn get_internal_level_exp(&self, id: &str) -> Option<(u32, f64)> {
         self.owned_internals.get(id).copied()
@@ -226,36 +286,41 @@ impl CharacterPanel {
         &self,
         manual_manager: &crate::cultivation::manual_manager::ManualManager,
     ) -> crate::effect::condition::CultivationContext {
-        let internal_type = self
+        let internal = self
             .current_internal_id
             .as_ref()
-            .and_then(|id| manual_manager.get_internal(id))
-            .map(|internal| internal.manual.manual_type.clone());
-
-        let attack_skill_type = self
+            .and_then(|id| manual_manager.get_internal(id));
+        let attack_skill = self
             .current_attack_skill_id
             .as_ref()
-            .and_then(|id| manual_manager.get_attack_skill(id))
-            .map(|skill| skill.manual.manual_type.clone());
-
-        let defense_skill_type = self
+            .and_then(|id| manual_manager.get_attack_skill(id));
+        let defense_skill = self
             .current_defense_skill_id
             .as_ref()
-            .and_then(|id| manual_manager.get_defense_skill(id))
-            .map(|skill| skill.manual.manual_type.clone());
+            .and_then(|id| manual_manager.get_defense_skill(id));
 
         crate::effect::condition::CultivationContext {
             internal_id: self.current_internal_id.clone(),
-            internal_type,
+            internal_type: internal.map(|i| i.manual.manual_type.clone()),
             attack_skill_id: self.current_attack_skill_id.clone(),
-            attack_skill_type,
+            attack_skill_type: attack_skill.map(|s| s.manual.manual_type.clone()),
             defense_skill_id: self.current_defense_skill_id.clone(),
-            defense_skill_type,
+            defense_skill_type: defense_skill.map(|s| s.manual.manual_type.clone()),
+            internal_tags: internal.map(|i| i.manual.tags.clone()).unwrap_or_default(),
+            attack_skill_tags: attack_skill
+                .map(|s| s.manual.tags.clone())
+                .unwrap_or_default(),
+            defense_skill_tags: defense_skill
+                .map(|s| s.manual.tags.clone())
+                .unwrap_or_default(),
             traits: self.traits.clone(),
             comprehension: self.x(),
             bone_structure: self.y(),
             physique: self.z(),
             martial_arts_attainment: self.a(),
+            max_hp: self.max_hp,
+            base_attack: self.base_attack,
+            base_defense: self.base_defense,
         }
     }
 }
@@ -287,4 +352,30 @@ mod tests {
         assert_eq!(panel.z(), 12.0);
         assert_eq!(panel.a(), 0.0);
     }
+
+    #[test]
+    fn test_set_physique_keeps_bonus() {
+        let three_d = ThreeDimensional::new(10, 8, 12);
+        let mut panel = CharacterPanel::new("测试".to_string(), three_d);
+        panel.base_attack += 10.0;
+        panel.hp = 1000.0;
+
+        panel.set_physique(14);
+
+        assert_eq!(panel.max_hp, 1400.0);
+        assert_eq!(panel.hp, 1200.0);
+        assert_eq!(panel.base_attack, 52.0); // 3 * 14 + 10
+        assert_eq!(panel.base_defense, 28.0);
+    }
+
+    #[test]
+    fn test_owned_manuals_iterate_in_id_order() {
+        let mut panel = CharacterPanel::new("测试".to_string(), ThreeDimensional::new(10, 8, 12));
+        for id in ["c", "a", "b"] {
+            panel.owned_internals.insert(id.to_string(), (1, 0.0));
+        }
+
+        let ids: Vec<&str> = panel.owned_internals.keys().map(String::as_str).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+    }
 }
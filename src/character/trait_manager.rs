@@ -1,19 +1,20 @@
 use crate::character::traits::Trait;
 use crate::effect::executor::EntryExecutor;
 /// 特性管理器
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 /// 特性管理器
+#[derive(Clone)]
 pub struct TraitManager {
     /// 特性映射表（ID -> 特性）
-    traits: HashMap<String, Trait>,
+    traits: BTreeMap<String, Trait>,
 }
 
 impl TraitManager {
     /// 创建新特性管理器
     pub fn new() -> Self {
         Self {
-            traits: HashMap::new(),
+            traits: BTreeMap::new(),
         }
     }
 
@@ -1,4 +1,8 @@
 use crate::effect::entry::Entry;
+use crate::error::{WushenError, WushenResult};
+use crate::pack::schema::CollectionKind;
+use crate::pack::strict::{parse_collection, ParseOptions};
+use crate::pack::warning::LoadWarning;
 /// 特性系统
 use serde::{Deserialize, Serialize};
 
@@ -18,24 +22,24 @@ pub struct Trait {
     pub entries: Vec<Entry>,
 }
 
-/// 特性数据文件结构
-#[derive(Debug, Deserialize)]
-pub struct TraitsData {
-    pub traits: Vec<Trait>,
-}
-
 /// 解析特性数据
 /// 支持两种格式：
 /// 1. 对象格式：{"traits": [...]}
 /// 2. 数组格式：[...]
-pub fn parse_traits(json: &str) -> Result<Vec<Trait>, String> {
-    // 先尝试解析为对象格式
-    if let Ok(data) = serde_json::from_str::<TraitsData>(json) {
-        return Ok(data.traits);
-    }
+pub fn parse_traits(json: &str) -> WushenResult<Vec<Trait>> {
+    parse_traits_with_warnings(json).map(|(traits, _)| traits)
+}
+
+/// 解析特性数据，并返回数据版本升级产生的警告
+pub fn parse_traits_with_warnings(json: &str) -> WushenResult<(Vec<Trait>, Vec<LoadWarning>)> {
+    parse_traits_with_options(json, ParseOptions::default())
+}
 
-    // 如果失败，尝试解析为数组格式
-    let traits: Vec<Trait> =
-        serde_json::from_str(json).map_err(|e| format!("解析特性数据失败: {}", e))?;
-    Ok(traits)
+/// 按解析选项解析特性数据（严格模式见 [`crate::pack::strict`]）
+pub fn parse_traits_with_options(
+    json: &str,
+    options: ParseOptions,
+) -> WushenResult<(Vec<Trait>, Vec<LoadWarning>)> {
+    parse_collection(json, CollectionKind::Traits, options)
+        .map_err(|e| WushenError::ParseError(format!("解析特性数据失败: {}", e)))
 }
@@ -1,6 +1,8 @@
-/// 攻击武技定义
 use super::manual::Manual;
 use super::realm::AttackSkillRealm;
+use crate::battle::battle_ultimate::UltimateMove;
+/// 攻击武技定义
+use crate::error::{WushenError, WushenResult};
 
 /// 攻击武技
 #[derive(Debug, Clone)]
@@ -11,23 +13,28 @@ pub struct AttackSkill {
     pub realms: Vec<AttackSkillRealm>,
     /// 攻击日志模板（支持 {self} 和 {opponent} 占位符）
     pub log_template: Option<String>,
+    /// 绝招（内息充足且条件满足时代替普通攻击）
+    pub ultimate: Option<UltimateMove>,
 }
 
 impl AttackSkill {
     /// 创建新攻击武技
-    pub fn new(manual: Manual, realms: Vec<AttackSkillRealm>) -> Result<Self, String> {
+    pub fn new(manual: Manual, realms: Vec<AttackSkillRealm>) -> WushenResult<Self> {
         if realms.len() != 5 {
-            return Err(format!("攻击武技必须有5个境界，当前有{}个", realms.len()));
+            return Err(WushenError::Invalid(format!(
+                "攻击武技必须有5个境界，当前有{}个",
+                realms.len()
+            )));
         }
 
         // 验证境界等级
         for (idx, realm) in realms.iter().enumerate() {
             let expected_level = (idx + 1) as u32;
             if realm.level != expected_level {
-                return Err(format!(
+                return Err(WushenError::Invalid(format!(
                     "境界等级不匹配：索引{}应该是等级{}，但实际是{}",
                     idx, expected_level, realm.level
-                ));
+                )));
             }
         }
 
@@ -35,6 +42,7 @@ impl AttackSkill {
             manual,
             realms,
             log_template: None,
+            ultimate: None,
         })
     }
 
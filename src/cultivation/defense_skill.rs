@@ -1,6 +1,8 @@
-/// 防御武技定义
 use super::manual::Manual;
 use super::realm::DefenseSkillRealm;
+use crate::battle::battle_counter::CounterAttack;
+/// 防御武技定义
+use crate::error::{WushenError, WushenResult};
 
 /// 防御武技
 #[derive(Debug, Clone)]
@@ -11,23 +13,28 @@ pub struct DefenseSkill {
     pub realms: Vec<DefenseSkillRealm>,
     /// 防御日志模板（支持 {self} 和 {opponent} 占位符）
     pub log_template: Option<String>,
+    /// 反击（以内息挡下攻击且条件满足时对攻击者反击）
+    pub counter: Option<CounterAttack>,
 }
 
 impl DefenseSkill {
     /// 创建新防御武技
-    pub fn new(manual: Manual, realms: Vec<DefenseSkillRealm>) -> Result<Self, String> {
+    pub fn new(manual: Manual, realms: Vec<DefenseSkillRealm>) -> WushenResult<Self> {
         if realms.len() != 5 {
-            return Err(format!("防御武技必须有5个境界，当前有{}个", realms.len()));
+            return Err(WushenError::Invalid(format!(
+                "防御武技必须有5个境界，当前有{}个",
+                realms.len()
+            )));
         }
 
         // 验证境界等级
         for (idx, realm) in realms.iter().enumerate() {
             let expected_level = (idx + 1) as u32;
             if realm.level != expected_level {
-                return Err(format!(
+                return Err(WushenError::Invalid(format!(
                     "境界等级不匹配：索引{}应该是等级{}，但实际是{}",
                     idx, expected_level, realm.level
-                ));
+                )));
             }
         }
 
@@ -35,6 +42,7 @@ impl DefenseSkill {
             manual,
             realms,
             log_template: None,
+            counter: None,
         })
     }
 
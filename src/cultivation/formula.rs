@@ -1,3 +1,4 @@
+use crate::error::{WushenError, WushenResult};
 use meval::{Context, Expr};
 /// 修行公式解析与计算
 /// 支持基于角色三维和武学素养的公式计算
@@ -18,13 +19,13 @@ impl CultivationFormula {
     ///
     /// # 示例
     /// ```
-    /// use wushen_core::cultivation::formula::CultivationFormula;
+    /// use wushen_core::cultivation::CultivationFormula;
     /// let formula = CultivationFormula::new("x * 10 + A * 2").unwrap();
     /// ```
-    pub fn new(formula: &str) -> Result<Self, String> {
+    pub fn new(formula: &str) -> WushenResult<Self> {
         let trimmed = formula.trim();
         if trimmed.is_empty() {
-            return Err("公式不能为空".to_string());
+            return Err(WushenError::FormulaError("公式不能为空".to_string()));
         }
 
         // 规范化公式：将 Python 风格的 ** 转换为 meval 支持的 ^
@@ -44,10 +45,10 @@ impl CultivationFormula {
         Expr::from_str(&normalized)
             .and_then(|expr| expr.eval_with_context(test_ctx))
             .map_err(|e| {
-                format!(
+                WushenError::FormulaError(format!(
                     "公式解析失败: {} (原始公式: '{}', 规范化公式: '{}')",
                     e, formula, normalized
-                )
+                ))
             })?;
 
         Ok(Self {
@@ -65,7 +66,7 @@ impl CultivationFormula {
     ///
     /// # 返回
     /// 消耗一个行动点所能获得的修行经验
-    pub fn calculate(&self, x: f64, y: f64, z: f64, a: f64) -> Result<f64, String> {
+    pub fn calculate(&self, x: f64, y: f64, z: f64, a: f64) -> WushenResult<f64> {
         // 创建上下文并绑定变量值
         let mut ctx = Context::new();
         add_common_functions(&mut ctx);
@@ -76,10 +77,11 @@ impl CultivationFormula {
             .var("a", a);
 
         // 解析并计算公式
-        let expr = Expr::from_str(&self.formula_str).map_err(|e| format!("公式解析错误: {}", e))?;
+        let expr = Expr::from_str(&self.formula_str)
+            .map_err(|e| WushenError::FormulaError(format!("公式解析错误: {}", e)))?;
 
         expr.eval_with_context(ctx)
-            .map_err(|e| format!("公式计算错误: {}", e))
+            .map_err(|e| WushenError::FormulaError(format!("公式计算错误: {}", e)))
     }
 
     /// 获取公式字符串
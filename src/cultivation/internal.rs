@@ -1,6 +1,7 @@
-/// 内功定义
 use super::manual::Manual;
 use super::realm::InternalRealm;
+/// 内功定义
+use crate::error::{WushenError, WushenResult};
 
 /// 内功
 #[derive(Debug, Clone)]
@@ -13,19 +14,22 @@ pub struct Internal {
 
 impl Internal {
     /// 创建新内功
-    pub fn new(manual: Manual, realms: Vec<InternalRealm>) -> Result<Self, String> {
+    pub fn new(manual: Manual, realms: Vec<InternalRealm>) -> WushenResult<Self> {
         if realms.len() != 5 {
-            return Err(format!("内功必须有5个境界，当前有{}个", realms.len()));
+            return Err(WushenError::Invalid(format!(
+                "内功必须有5个境界，当前有{}个",
+                realms.len()
+            )));
         }
 
         // 验证境界等级
         for (idx, realm) in realms.iter().enumerate() {
             let expected_level = (idx + 1) as u32;
             if realm.level != expected_level {
-                return Err(format!(
+                return Err(WushenError::Invalid(format!(
                     "境界等级不匹配：索引{}应该是等级{}，但实际是{}",
                     idx, expected_level, realm.level
-                ));
+                )));
             }
         }
 
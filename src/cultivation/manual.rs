@@ -1,15 +1,20 @@
+use super::formula::CultivationFormula;
 /// 功法基类
 /// 内功、攻击武技、防御武技的共同基础结构
-use super::formula::CultivationFormula;
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Serialize};
 
 /// 功法稀有度（1-5级）
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Rarity(pub u32);
 
 impl Rarity {
-    pub fn new(level: u32) -> Result<Self, String> {
+    pub fn new(level: u32) -> WushenResult<Self> {
         if !(1..=5).contains(&level) {
-            return Err(format!("稀有度必须在 1-5 之间，当前值: {}", level));
+            return Err(WushenError::Invalid(format!(
+                "稀有度必须在 1-5 之间，当前值: {}",
+                level
+            )));
         }
         Ok(Self(level))
     }
@@ -19,6 +24,45 @@ impl Rarity {
     }
 }
 
+/// 修行资质权重：功法对悟性、根骨、体魄的偏重
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Aptitude {
+    #[serde(default)]
+    pub comprehension: f64,
+    #[serde(default)]
+    pub bone_structure: f64,
+    #[serde(default)]
+    pub physique: f64,
+}
+
+impl Aptitude {
+    /// 校验权重：均不为负且至少一项大于0
+    pub fn validate(&self) -> WushenResult<()> {
+        let weights = [self.comprehension, self.bone_structure, self.physique];
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            return Err(WushenError::Invalid("资质权重不能为负数".to_string()));
+        }
+        if weights.iter().sum::<f64>() <= 0.0 {
+            return Err(WushenError::Invalid(
+                "资质权重至少需有一项大于0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// 资质倍率：按权重加权的三维均值 / 三维均值（三维均衡时为 1）
+    pub fn multiplier(&self, x: f64, y: f64, z: f64) -> f64 {
+        let total_weight = self.comprehension + self.bone_structure + self.physique;
+        let average = (x + y + z) / 3.0;
+        if total_weight <= 0.0 || average <= 0.0 {
+            return 1.0;
+        }
+        let weighted =
+            (self.comprehension * x + self.bone_structure * y + self.physique * z) / total_weight;
+        weighted / average
+    }
+}
+
 /// 功法基类结构
 #[derive(Debug, Clone)]
 pub struct Manual {
@@ -32,8 +76,12 @@ pub struct Manual {
     pub rarity: Rarity,
     /// 类型（字符串，可自定义）
     pub manual_type: String,
+    /// 标签（如剑法、拳法、阴、阳、轻功等，用于分类筛选与条件判断）
+    pub tags: Vec<String>,
     /// 修行公式
     pub cultivation_formula: CultivationFormula,
+    /// 修行资质权重（为空时不按三维偏重调整）
+    pub aptitude: Option<Aptitude>,
     /// 当前等级（0-5，0表示未修行）
     pub level: u32,
     /// 当前经验值
@@ -56,15 +104,31 @@ impl Manual {
             description,
             rarity,
             manual_type,
+            tags: Vec::new(),
             cultivation_formula,
+            aptitude: None,
             level: 0,
             current_exp: 0.0,
         }
     }
 
-    /// 计算一次修行获得的经验
-    pub fn calculate_exp_gain(&self, x: f64, y: f64, z: f64, a: f64) -> Result<f64, String> {
-        self.cultivation_formula.calculate(x, y, z, a)
+    /// 是否具有指定标签
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    /// 是否满足筛选条件（标签、稀有度，未指定的条件视为满足）
+    pub fn matches_filter(&self, tag: Option<&str>, rarity: Option<u32>) -> bool {
+        tag.is_none_or(|tag| self.has_tag(tag)) && rarity.is_none_or(|r| self.rarity.level() == r)
+    }
+
+    /// 计算一次修行获得的经验（修行公式结果再乘以资质倍率）
+    pub fn calculate_exp_gain(&self, x: f64, y: f64, z: f64, a: f64) -> WushenResult<f64> {
+        let exp = self.cultivation_formula.calculate(x, y, z, a)?;
+        Ok(match &self.aptitude {
+            Some(aptitude) => exp * aptitude.multiplier(x, y, z),
+            None => exp,
+        })
     }
 
     /// 检查是否可以升级
@@ -143,4 +207,61 @@ mod tests {
         assert_eq!(manual.level, 1);
         assert_eq!(manual.current_exp, 0.0);
     }
+
+    #[test]
+    fn test_aptitude_favors_weighted_stat() {
+        let formula = CultivationFormula::new("10").unwrap();
+        let mut manual = Manual::new(
+            "sword".to_string(),
+            "剑法".to_string(),
+            "测试".to_string(),
+            Rarity::new(1).unwrap(),
+            "test".to_string(),
+            formula,
+        );
+        manual.aptitude = Some(Aptitude {
+            comprehension: 1.0,
+            ..Aptitude::default()
+        });
+
+        // 三维均衡时不调整
+        assert_eq!(
+            manual.calculate_exp_gain(10.0, 10.0, 10.0, 0.0).unwrap(),
+            10.0
+        );
+        // 悟性突出的角色修行更快，悟性偏低则更慢
+        assert_eq!(
+            manual.calculate_exp_gain(20.0, 5.0, 5.0, 0.0).unwrap(),
+            20.0
+        );
+        assert!(manual.calculate_exp_gain(5.0, 20.0, 5.0, 0.0).unwrap() < 10.0);
+
+        assert!(Aptitude::default().validate().is_err());
+        assert!(Aptitude {
+            physique: -1.0,
+            comprehension: 2.0,
+            ..Aptitude::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_manual_filter() {
+        let formula = CultivationFormula::new("x * 10").unwrap();
+        let mut manual = Manual::new(
+            "sword".to_string(),
+            "剑法".to_string(),
+            "测试".to_string(),
+            Rarity::new(3).unwrap(),
+            "test".to_string(),
+            formula,
+        );
+        manual.tags = vec!["剑法".to_string(), "阳".to_string()];
+
+        assert!(manual.matches_filter(None, None));
+        assert!(manual.matches_filter(Some("剑法"), Some(3)));
+        assert!(!manual.matches_filter(Some("阴"), None));
+        assert!(!manual.matches_filter(Some("阳"), Some(2)));
+    }
 }
@@ -9,17 +9,19 @@ use crate::effect::{
     formula::FormulaCalculator,
     trigger::Trigger,
 };
+use crate::error::{WushenError, WushenResult};
 /// 功法管理器
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 /// 功法管理器
+#[derive(Clone)]
 pub struct ManualManager {
     /// 内功映射表（ID -> 内功）
-    internals: HashMap<String, Internal>,
+    internals: BTreeMap<String, Internal>,
     /// 攻击武技映射表（ID -> 攻击武技）
-    attack_skills: HashMap<String, AttackSkill>,
+    attack_skills: BTreeMap<String, AttackSkill>,
     /// 防御武技映射表（ID -> 防御武技）
-    defense_skills: HashMap<String, DefenseSkill>,
+    defense_skills: BTreeMap<String, DefenseSkill>,
 }
 
 /// 阅读功法的基础武学素养增益（按稀有度 1-5）
@@ -39,9 +41,9 @@ impl ManualManager {
     /// 创建新功法管理器
     pub fn new() -> Self {
         Self {
-            internals: HashMap::new(),
-            attack_skills: HashMap::new(),
-            defense_skills: HashMap::new(),
+            internals: BTreeMap::new(),
+            attack_skills: BTreeMap::new(),
+            defense_skills: BTreeMap::new(),
         }
     }
 
@@ -108,29 +110,34 @@ impl ManualManager {
         &self,
         panel: &mut CharacterPanel,
         mut executor: Option<&mut EntryExecutor>,
-    ) -> Result<f64, String> {
+    ) -> WushenResult<f64> {
         // 检查是否装备了内功
         let id = panel
             .current_internal_id
             .as_ref()
-            .ok_or_else(|| "角色未装备内功，无法修行".to_string())?
+            .ok_or_else(|| WushenError::ConditionNotMet("角色未装备内功，无法修行".to_string()))?
             .clone();
 
         // 检查角色是否拥有该内功
         if !panel.has_internal(&id) {
-            return Err(format!("角色未拥有内功 {}", id));
+            return Err(WushenError::ConditionNotMet(format!(
+                "角色未拥有内功 {}",
+                id
+            )));
         }
 
         let internal = self
             .get_internal(&id)
-            .ok_or_else(|| format!("内功 {} 不存在", id))?;
+            .ok_or_else(|| WushenError::NotFound(format!("内功 {} 不存在", id)))?;
 
         // 获取角色当前拥有的等级和经验
         let (current_level, current_exp) = panel.get_internal_level_exp(&id).unwrap_or((0, 0.0));
 
         // 检查是否已经满级
         if current_level >= 5 {
-            return Err("内功已达到最高等级，无法继续修行".to_string());
+            return Err(WushenError::InvalidState(
+                "内功已达到最高等级，无法继续修行".to_string(),
+            ));
         }
 
         // 计算基础经验增益
@@ -156,11 +163,17 @@ impl ManualManager {
                 attack_skill_type: None,
                 defense_skill_id: None,
                 defense_skill_type: None,
+                internal_tags: internal.manual.tags.clone(),
+                attack_skill_tags: Vec::new(),
+                defense_skill_tags: Vec::new(),
                 traits,
                 comprehension,
                 bone_structure,
                 physique,
                 martial_arts_attainment,
+                max_hp: panel.max_hp,
+                base_attack: panel.base_attack,
+                base_defense: panel.base_defense,
             };
 
             // 触发修行内功词条
@@ -288,11 +301,17 @@ impl ManualManager {
                         attack_skill_type: None,
                         defense_skill_id: None,
                         defense_skill_type: None,
+                        internal_tags: internal.manual.tags.clone(),
+                        attack_skill_tags: Vec::new(),
+                        defense_skill_tags: Vec::new(),
                         traits: panel.traits.clone(),
                         comprehension: panel.three_d.comprehension as f64,
                         bone_structure: panel.three_d.bone_structure as f64,
                         physique: panel.three_d.physique as f64,
                         martial_arts_attainment: panel.martial_arts_attainment,
+                        max_hp: panel.max_hp,
+                        base_attack: panel.base_attack,
+                        base_defense: panel.base_defense,
                     };
 
                     let (qi_gain, martial_arts_gain) = self.apply_level_up_effects(
@@ -324,22 +343,27 @@ impl ManualManager {
         id: &str,
         panel: &mut CharacterPanel,
         mut executor: Option<&mut EntryExecutor>,
-    ) -> Result<f64, String> {
+    ) -> WushenResult<f64> {
         // 检查角色是否拥有该攻击武技
         if !panel.has_attack_skill(id) {
-            return Err(format!("角色未拥有攻击武技 {}", id));
+            return Err(WushenError::ConditionNotMet(format!(
+                "角色未拥有攻击武技 {}",
+                id
+            )));
         }
 
         let skill = self
             .get_attack_skill(id)
-            .ok_or_else(|| format!("攻击武技 {} 不存在", id))?;
+            .ok_or_else(|| WushenError::NotFound(format!("攻击武技 {} 不存在", id)))?;
 
         // 获取角色当前拥有的等级和经验
         let (current_level, current_exp) = panel.get_attack_skill_level_exp(id).unwrap_or((0, 0.0));
 
         // 检查是否已经满级
         if current_level >= 5 {
-            return Err("攻击武技已达到最高等级，无法继续修行".to_string());
+            return Err(WushenError::InvalidState(
+                "攻击武技已达到最高等级，无法继续修行".to_string(),
+            ));
         }
 
         // 计算基础经验增益
@@ -358,11 +382,17 @@ impl ManualManager {
                 attack_skill_type: Some(skill.manual.manual_type.clone()),
                 defense_skill_id: None,
                 defense_skill_type: None,
+                internal_tags: Vec::new(),
+                attack_skill_tags: skill.manual.tags.clone(),
+                defense_skill_tags: Vec::new(),
                 traits: panel.traits.clone(),
                 comprehension: panel.three_d.comprehension as f64,
                 bone_structure: panel.three_d.bone_structure as f64,
                 physique: panel.three_d.physique as f64,
                 martial_arts_attainment: panel.martial_arts_attainment,
+                max_hp: panel.max_hp,
+                base_attack: panel.base_attack,
+                base_defense: panel.base_defense,
             };
 
             // 触发修行攻击武技词条
@@ -475,11 +505,17 @@ impl ManualManager {
                         attack_skill_type: Some(skill.manual.manual_type.clone()),
                         defense_skill_id: None,
                         defense_skill_type: None,
+                        internal_tags: Vec::new(),
+                        attack_skill_tags: skill.manual.tags.clone(),
+                        defense_skill_tags: Vec::new(),
                         traits: panel.traits.clone(),
                         comprehension: panel.three_d.comprehension as f64,
                         bone_structure: panel.three_d.bone_structure as f64,
                         physique: panel.three_d.physique as f64,
                         martial_arts_attainment: panel.martial_arts_attainment,
+                        max_hp: panel.max_hp,
+                        base_attack: panel.base_attack,
+                        base_defense: panel.base_defense,
                     };
 
                     let (_qi_gain, martial_arts_gain) = self.apply_level_up_effects(
@@ -506,15 +542,18 @@ impl ManualManager {
         id: &str,
         panel: &mut CharacterPanel,
         mut executor: Option<&mut EntryExecutor>,
-    ) -> Result<f64, String> {
+    ) -> WushenResult<f64> {
         // 检查角色是否拥有该防御武技
         if !panel.has_defense_skill(id) {
-            return Err(format!("角色未拥有防御武技 {}", id));
+            return Err(WushenError::ConditionNotMet(format!(
+                "角色未拥有防御武技 {}",
+                id
+            )));
         }
 
         let skill = self
             .get_defense_skill(id)
-            .ok_or_else(|| format!("防御武技 {} 不存在", id))?;
+            .ok_or_else(|| WushenError::NotFound(format!("防御武技 {} 不存在", id)))?;
 
         // 获取角色当前拥有的等级和经验
         let (current_level, current_exp) =
@@ -522,7 +561,9 @@ impl ManualManager {
 
         // 检查是否已经满级
         if current_level >= 5 {
-            return Err("防御武技已达到最高等级，无法继续修行".to_string());
+            return Err(WushenError::InvalidState(
+                "防御武技已达到最高等级，无法继续修行".to_string(),
+            ));
         }
 
         // 计算基础经验增益
@@ -541,11 +582,17 @@ impl ManualManager {
                 attack_skill_type: None,
                 defense_skill_id: Some(id.to_string()),
                 defense_skill_type: Some(skill.manual.manual_type.clone()),
+                internal_tags: Vec::new(),
+                attack_skill_tags: Vec::new(),
+                defense_skill_tags: skill.manual.tags.clone(),
                 traits: panel.traits.clone(),
                 comprehension: panel.three_d.comprehension as f64,
                 bone_structure: panel.three_d.bone_structure as f64,
                 physique: panel.three_d.physique as f64,
                 martial_arts_attainment: panel.martial_arts_attainment,
+                max_hp: panel.max_hp,
+                base_attack: panel.base_attack,
+                base_defense: panel.base_defense,
             };
 
             // 触发修行防御武技词条
@@ -659,11 +706,17 @@ impl ManualManager {
                         attack_skill_type: None,
                         defense_skill_id: Some(id.to_string()),
                         defense_skill_type: Some(skill.manual.manual_type.clone()),
+                        internal_tags: Vec::new(),
+                        attack_skill_tags: Vec::new(),
+                        defense_skill_tags: skill.manual.tags.clone(),
                         traits: panel.traits.clone(),
                         comprehension: panel.three_d.comprehension as f64,
                         bone_structure: panel.three_d.bone_structure as f64,
                         physique: panel.three_d.physique as f64,
                         martial_arts_attainment: panel.martial_arts_attainment,
+                        max_hp: panel.max_hp,
+                        base_attack: panel.base_attack,
+                        base_defense: panel.base_defense,
                     };
 
                     let (_qi_gain, martial_arts_gain) = self.apply_level_up_effects(
@@ -700,23 +753,29 @@ impl ManualManager {
         to_id: &str,
         panel: &mut CharacterPanel,
         executor: Option<&mut EntryExecutor>,
-    ) -> Result<(), String> {
+    ) -> WushenResult<()> {
         // 检查角色是否拥有目标内功
         if !panel.has_internal(to_id) {
-            return Err(format!("角色未拥有内功 {}", to_id));
+            return Err(WushenError::ConditionNotMet(format!(
+                "角色未拥有内功 {}",
+                to_id
+            )));
         }
 
         let to_internal = self
             .get_internal(to_id)
-            .ok_or_else(|| format!("内功 {} 不存在", to_id))?;
+            .ok_or_else(|| WushenError::NotFound(format!("内功 {} 不存在", to_id)))?;
 
         // 计算损失率修改（从词条效果中获取）
         let mut qi_loss_rate_modifier = 1.0;
 
         if let Some(executor) = executor {
             // 创建转修上下文
-            let from_internal_type =
-                from_id.and_then(|id| self.get_internal(id).map(|i| i.manual.manual_type.clone()));
+            let from_internal = from_id.and_then(|id| self.get_internal(id));
+            let from_internal_type = from_internal.map(|i| i.manual.manual_type.clone());
+            let from_internal_tags = from_internal
+                .map(|i| i.manual.tags.clone())
+                .unwrap_or_default();
             let context = CultivationContext {
                 internal_id: from_id.map(|s| s.to_string()),
                 internal_type: from_internal_type,
@@ -724,11 +783,17 @@ impl ManualManager {
                 attack_skill_type: None,
                 defense_skill_id: None,
                 defense_skill_type: None,
+                internal_tags: from_internal_tags,
+                attack_skill_tags: Vec::new(),
+                defense_skill_tags: Vec::new(),
                 traits: vec![], // 转修时不需要特性信息
                 comprehension: panel.three_d.comprehension as f64,
                 bone_structure: panel.three_d.bone_structure as f64,
                 physique: panel.three_d.physique as f64,
                 martial_arts_attainment: panel.martial_arts_attainment,
+                max_hp: panel.max_hp,
+                base_attack: panel.base_attack,
+                base_defense: panel.base_defense,
             };
 
             // 触发转修词条
@@ -807,7 +872,7 @@ impl ManualManager {
         if let Some(from_id) = from_id {
             let from_internal = self
                 .get_internal(from_id)
-                .ok_or_else(|| format!("内功 {} 不存在", from_id))?;
+                .ok_or_else(|| WushenError::NotFound(format!("内功 {} 不存在", from_id)))?;
 
             let result = calculate_switching_qi(
                 panel.qi,
@@ -867,16 +932,19 @@ impl ManualManager {
     ///
     /// # 返回
     /// 如果成功获得返回 Ok(())，否则返回错误信息
-    pub fn acquire_internal(&self, id: &str, panel: &mut CharacterPanel) -> Result<(), String> {
+    pub fn acquire_internal(&self, id: &str, panel: &mut CharacterPanel) -> WushenResult<()> {
         // 检查是否已经拥有
         if panel.has_internal(id) {
-            return Err(format!("角色已经拥有内功 {}", id));
+            return Err(WushenError::InvalidState(format!(
+                "角色已经拥有内功 {}",
+                id
+            )));
         }
 
         // 检查内功是否存在
         let _internal = self
             .get_internal(id)
-            .ok_or_else(|| format!("内功 {} 不存在", id))?;
+            .ok_or_else(|| WushenError::NotFound(format!("内功 {} 不存在", id)))?;
 
         // 添加到拥有的功法列表（初始等级为0，经验为0）
         panel.set_internal_level_exp(id.to_string(), 0, 0.0);
@@ -885,15 +953,18 @@ impl ManualManager {
     }
 
     /// 获得攻击武技（添加到角色拥有的功法列表）
-    pub fn acquire_attack_skill(&self, id: &str, panel: &mut CharacterPanel) -> Result<(), String> {
+    pub fn acquire_attack_skill(&self, id: &str, panel: &mut CharacterPanel) -> WushenResult<()> {
         // 检查是否已经拥有
         if panel.has_attack_skill(id) {
-            return Err(format!("角色已经拥有攻击武技 {}", id));
+            return Err(WushenError::InvalidState(format!(
+                "角色已经拥有攻击武技 {}",
+                id
+            )));
         }
 
         // 检查攻击武技是否存在
         self.get_attack_skill(id)
-            .ok_or_else(|| format!("攻击武技 {} 不存在", id))?;
+            .ok_or_else(|| WushenError::NotFound(format!("攻击武技 {} 不存在", id)))?;
 
         // 添加到拥有的功法列表（初始等级为0，经验为0）
         panel.set_attack_skill_level_exp(id.to_string(), 0, 0.0);
@@ -902,19 +973,18 @@ impl ManualManager {
     }
 
     /// 获得防御武技（添加到角色拥有的功法列表）
-    pub fn acquire_defense_skill(
-        &self,
-        id: &str,
-        panel: &mut CharacterPanel,
-    ) -> Result<(), String> {
+    pub fn acquire_defense_skill(&self, id: &str, panel: &mut CharacterPanel) -> WushenResult<()> {
         // 检查是否已经拥有
         if panel.has_defense_skill(id) {
-            return Err(format!("角色已经拥有防御武技 {}", id));
+            return Err(WushenError::InvalidState(format!(
+                "角色已经拥有防御武技 {}",
+                id
+            )));
         }
 
         // 检查防御武技是否存在
         self.get_defense_skill(id)
-            .ok_or_else(|| format!("防御武技 {} 不存在", id))?;
+            .ok_or_else(|| WushenError::NotFound(format!("防御武技 {} 不存在", id)))?;
 
         // 添加到拥有的功法列表（初始等级为0，经验为0）
         panel.set_defense_skill_level_exp(id.to_string(), 0, 0.0);
@@ -930,13 +1000,13 @@ impl ManualManager {
         id: &str,
         panel: &mut CharacterPanel,
         executor: Option<&mut EntryExecutor>,
-    ) -> Result<f64, String> {
+    ) -> WushenResult<f64> {
         if panel.has_internal(id) {
             return Ok(0.0);
         }
         let internal = self
             .get_internal(id)
-            .ok_or_else(|| format!("内功 {} 不存在", id))?;
+            .ok_or_else(|| WushenError::NotFound(format!("内功 {} 不存在", id)))?;
         self.acquire_internal(id, panel)?;
         let base_gain = reading_base_gain(internal.manual.rarity);
         let gain = self.apply_reading_gain(
@@ -959,13 +1029,13 @@ impl ManualManager {
         id: &str,
         panel: &mut CharacterPanel,
         executor: Option<&mut EntryExecutor>,
-    ) -> Result<f64, String> {
+    ) -> WushenResult<f64> {
         if panel.has_attack_skill(id) {
             return Ok(0.0);
         }
         let skill = self
             .get_attack_skill(id)
-            .ok_or_else(|| format!("攻击武技 {} 不存在", id))?;
+            .ok_or_else(|| WushenError::NotFound(format!("攻击武技 {} 不存在", id)))?;
         self.acquire_attack_skill(id, panel)?;
         let base_gain = reading_base_gain(skill.manual.rarity);
         let gain = self.apply_reading_gain(
@@ -988,13 +1058,13 @@ impl ManualManager {
         id: &str,
         panel: &mut CharacterPanel,
         executor: Option<&mut EntryExecutor>,
-    ) -> Result<f64, String> {
+    ) -> WushenResult<f64> {
         if panel.has_defense_skill(id) {
             return Ok(0.0);
         }
         let skill = self
             .get_defense_skill(id)
-            .ok_or_else(|| format!("防御武技 {} 不存在", id))?;
+            .ok_or_else(|| WushenError::NotFound(format!("防御武技 {} 不存在", id)))?;
         self.acquire_defense_skill(id, panel)?;
         let base_gain = reading_base_gain(skill.manual.rarity);
         let gain = self.apply_reading_gain(
@@ -1012,15 +1082,18 @@ impl ManualManager {
     }
 
     /// 装备内功（只能装备已拥有的内功）
-    pub fn equip_internal(&self, id: &str, panel: &mut CharacterPanel) -> Result<(), String> {
+    pub fn equip_internal(&self, id: &str, panel: &mut CharacterPanel) -> WushenResult<()> {
         if !panel.has_internal(id) {
-            return Err(format!("角色未拥有内功 {}", id));
+            return Err(WushenError::ConditionNotMet(format!(
+                "角色未拥有内功 {}",
+                id
+            )));
         }
 
         // 验证内功存在
         let _internal = self
             .get_internal(id)
-            .ok_or_else(|| format!("内功 {} 不存在", id))?;
+            .ok_or_else(|| WushenError::NotFound(format!("内功 {} 不存在", id)))?;
 
         panel.current_internal_id = Some(id.to_string());
 
@@ -1028,14 +1101,17 @@ impl ManualManager {
     }
 
     /// 装备攻击武技（只能装备已拥有的攻击武技）
-    pub fn equip_attack_skill(&self, id: &str, panel: &mut CharacterPanel) -> Result<(), String> {
+    pub fn equip_attack_skill(&self, id: &str, panel: &mut CharacterPanel) -> WushenResult<()> {
         if !panel.has_attack_skill(id) {
-            return Err(format!("角色未拥有攻击武技 {}", id));
+            return Err(WushenError::ConditionNotMet(format!(
+                "角色未拥有攻击武技 {}",
+                id
+            )));
         }
 
         let skill = self
             .get_attack_skill(id)
-            .ok_or_else(|| format!("攻击武技 {} 不存在", id))?;
+            .ok_or_else(|| WushenError::NotFound(format!("攻击武技 {} 不存在", id)))?;
 
         panel.current_attack_skill_id = Some(id.to_string());
         panel.current_attack_skill_name = Some(skill.manual.name.clone());
@@ -1044,14 +1120,17 @@ impl ManualManager {
     }
 
     /// 装备防御武技（只能装备已拥有的防御武技）
-    pub fn equip_defense_skill(&self, id: &str, panel: &mut CharacterPanel) -> Result<(), String> {
+    pub fn equip_defense_skill(&self, id: &str, panel: &mut CharacterPanel) -> WushenResult<()> {
         if !panel.has_defense_skill(id) {
-            return Err(format!("角色未拥有防御武技 {}", id));
+            return Err(WushenError::ConditionNotMet(format!(
+                "角色未拥有防御武技 {}",
+                id
+            )));
         }
 
         let skill = self
             .get_defense_skill(id)
-            .ok_or_else(|| format!("防御武技 {} 不存在", id))?;
+            .ok_or_else(|| WushenError::NotFound(format!("防御武技 {} 不存在", id)))?;
 
         panel.current_defense_skill_id = Some(id.to_string());
         panel.current_defense_skill_name = Some(skill.manual.name.clone());
@@ -1079,6 +1158,9 @@ impl ManualManager {
 
         if !realm_entries.is_empty() {
             let mut realm_executor = EntryExecutor::new();
+            if let Some(exec) = executor.as_ref() {
+                realm_executor.disable_tags(exec.disabled_tags().iter().cloned());
+            }
             realm_executor.add_entries(realm_entries.to_vec());
             effects.extend(realm_executor.trigger_cultivation(trigger, panel, &context));
         }
@@ -1098,22 +1180,19 @@ impl ManualManager {
                         value,
                         operation,
                         ..
-                    } => {
-                        if base_qi_gain.is_some() {
-                            let calculated_value = match value.as_formula() {
-                                Some(formula) => FormulaCalculator::evaluate_cultivation(
-                                    formula,
-                                    &formula_context,
-                                )
-                                .unwrap_or(0.0),
-                                None => value.as_fixed().unwrap_or(0.0),
-                            };
-                            match operation {
-                                Operation::Add => qi_gain *= 1.0 + calculated_value,
-                                Operation::Subtract => qi_gain *= 1.0 - calculated_value,
-                                Operation::Set => qi_gain = calculated_value,
-                                Operation::Multiply => qi_gain *= calculated_value,
+                    } if base_qi_gain.is_some() => {
+                        let calculated_value = match value.as_formula() {
+                            Some(formula) => {
+                                FormulaCalculator::evaluate_cultivation(formula, &formula_context)
+                                    .unwrap_or(0.0)
                             }
+                            None => value.as_fixed().unwrap_or(0.0),
+                        };
+                        match operation {
+                            Operation::Add => qi_gain *= 1.0 + calculated_value,
+                            Operation::Subtract => qi_gain *= 1.0 - calculated_value,
+                            Operation::Set => qi_gain = calculated_value,
+                            Operation::Multiply => qi_gain *= calculated_value,
                         }
                     }
                     Effect::ModifyAttribute {
@@ -1121,22 +1200,19 @@ impl ManualManager {
                         value,
                         operation,
                         ..
-                    } => {
-                        if base_qi_gain.is_some() {
-                            let calculated_value = match value.as_formula() {
-                                Some(formula) => FormulaCalculator::evaluate_cultivation(
-                                    formula,
-                                    &formula_context,
-                                )
-                                .unwrap_or(0.0),
-                                None => value.as_fixed().unwrap_or(0.0),
-                            };
-                            match operation {
-                                Operation::Add => qi_gain += calculated_value,
-                                Operation::Subtract => qi_gain -= calculated_value,
-                                Operation::Set => qi_gain = calculated_value,
-                                Operation::Multiply => qi_gain *= calculated_value,
+                    } if base_qi_gain.is_some() => {
+                        let calculated_value = match value.as_formula() {
+                            Some(formula) => {
+                                FormulaCalculator::evaluate_cultivation(formula, &formula_context)
+                                    .unwrap_or(0.0)
                             }
+                            None => value.as_fixed().unwrap_or(0.0),
+                        };
+                        match operation {
+                            Operation::Add => qi_gain += calculated_value,
+                            Operation::Subtract => qi_gain -= calculated_value,
+                            Operation::Set => qi_gain = calculated_value,
+                            Operation::Multiply => qi_gain *= calculated_value,
                         }
                     }
                     Effect::ModifyPercentage {
@@ -1224,6 +1300,21 @@ impl ManualManager {
         let mut gain = base_gain;
 
         if let Some(executor) = executor {
+            let internal_tags = internal_id
+                .as_deref()
+                .and_then(|id| self.get_internal(id))
+                .map(|i| i.manual.tags.clone())
+                .unwrap_or_default();
+            let attack_skill_tags = attack_skill_id
+                .as_deref()
+                .and_then(|id| self.get_attack_skill(id))
+                .map(|s| s.manual.tags.clone())
+                .unwrap_or_default();
+            let defense_skill_tags = defense_skill_id
+                .as_deref()
+                .and_then(|id| self.get_defense_skill(id))
+                .map(|s| s.manual.tags.clone())
+                .unwrap_or_default();
             let context = CultivationContext {
                 internal_id,
                 internal_type,
@@ -1231,11 +1322,17 @@ impl ManualManager {
                 attack_skill_type,
                 defense_skill_id,
                 defense_skill_type,
+                internal_tags,
+                attack_skill_tags,
+                defense_skill_tags,
                 traits: panel.traits.clone(),
                 comprehension: panel.three_d.comprehension as f64,
                 bone_structure: panel.three_d.bone_structure as f64,
                 physique: panel.three_d.physique as f64,
                 martial_arts_attainment: panel.martial_arts_attainment,
+                max_hp: panel.max_hp,
+                base_attack: panel.base_attack,
+                base_defense: panel.base_defense,
             };
 
             let effects = executor.trigger_cultivation(Trigger::ReadingManual, panel, &context);
@@ -1,14 +1,19 @@
-pub mod attack_skill;
-pub mod defense_skill;
-pub mod formula;
-pub mod internal;
-pub mod manual;
-pub mod manual_manager;
-pub mod parser;
-pub mod realm;
-pub mod switching;
+pub(crate) mod attack_skill;
+pub(crate) mod defense_skill;
+pub(crate) mod formula;
+pub(crate) mod internal;
+pub(crate) mod manual;
+pub(crate) mod manual_manager;
+pub(crate) mod parser;
+pub(crate) mod realm;
+pub(crate) mod switching;
 
 // 重新导出常用类型
-pub use attack_skill::AttackSkill;
-pub use defense_skill::DefenseSkill;
-pub use internal::Internal;
+pub use attack_skill::{AttackSkill, AttackSkillLevelUpResult};
+pub use defense_skill::{DefenseSkill, DefenseSkillLevelUpResult};
+pub use formula::CultivationFormula;
+pub use internal::{Internal, InternalLevelUpResult};
+pub use manual::{Aptitude, Manual, Rarity};
+pub use parser::{parse_attack_skills, parse_defense_skills, parse_internals};
+pub use realm::{AttackSkillRealm, DefenseSkillRealm, InternalRealm};
+pub use switching::{calculate_switching_qi, SwitchingResult};
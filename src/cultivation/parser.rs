@@ -1,17 +1,23 @@
+use crate::battle::battle_counter::CounterAttack;
+use crate::battle::battle_ultimate::UltimateMove;
 use crate::cultivation::{
     attack_skill::AttackSkill,
     defense_skill::DefenseSkill,
     formula::CultivationFormula,
     internal::Internal,
-    manual::{Manual, Rarity},
+    manual::{Aptitude, Manual, Rarity},
     realm::{AttackSkillRealm, DefenseSkillRealm, InternalRealm},
 };
 use crate::effect::entry::Entry;
+use crate::error::{WushenError, WushenResult};
+use crate::pack::schema::CollectionKind;
+use crate::pack::strict::{parse_collection, ParseOptions};
+use crate::pack::warning::LoadWarning;
 /// 功法 JSON 解析器
-use serde::Deserialize;
+use serde::{Deserialize, Serialize, Serializer};
 
 /// 内功 JSON 结构
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct InternalJson {
     id: String,
     name: String,
@@ -19,12 +25,17 @@ struct InternalJson {
     rarity: u32,
     #[serde(rename = "type")]
     manual_type: String,
+    #[serde(default)]
+    tags: Vec<String>,
     cultivation_formula: String,
+    /// 修行资质权重
+    #[serde(default)]
+    aptitude: Option<Aptitude>,
     realms: Vec<InternalRealmJson>,
 }
 
 /// 内功境界 JSON 结构
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct InternalRealmJson {
     level: u32,
     exp_required: f64,
@@ -37,7 +48,7 @@ struct InternalRealmJson {
 }
 
 /// 攻击武技 JSON 结构
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct AttackSkillJson {
     id: String,
     name: String,
@@ -45,14 +56,22 @@ struct AttackSkillJson {
     rarity: u32,
     #[serde(rename = "type")]
     manual_type: String,
+    #[serde(default)]
+    tags: Vec<String>,
     cultivation_formula: String,
+    /// 修行资质权重
+    #[serde(default)]
+    aptitude: Option<Aptitude>,
     realms: Vec<AttackSkillRealmJson>,
     #[serde(default)]
     log_template: Option<String>,
+    /// 绝招
+    #[serde(default)]
+    ultimate: Option<UltimateMove>,
 }
 
 /// 攻击武技境界 JSON 结构
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct AttackSkillRealmJson {
     level: u32,
     exp_required: f64,
@@ -63,7 +82,7 @@ struct AttackSkillRealmJson {
 }
 
 /// 防御武技 JSON 结构
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct DefenseSkillJson {
     id: String,
     name: String,
@@ -71,14 +90,22 @@ struct DefenseSkillJson {
     rarity: u32,
     #[serde(rename = "type")]
     manual_type: String,
+    #[serde(default)]
+    tags: Vec<String>,
     cultivation_formula: String,
+    /// 修行资质权重
+    #[serde(default)]
+    aptitude: Option<Aptitude>,
     realms: Vec<DefenseSkillRealmJson>,
     #[serde(default)]
     log_template: Option<String>,
+    /// 反击
+    #[serde(default)]
+    counter: Option<CounterAttack>,
 }
 
 /// 防御武技境界 JSON 结构
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct DefenseSkillRealmJson {
     level: u32,
     exp_required: f64,
@@ -87,6 +114,115 @@ struct DefenseSkillRealmJson {
     entries: Vec<Entry>,
 }
 
+// 功法按数据文件格式序列化（不含修行进度），作为内容指纹等场合的规范文本
+
+impl From<&Internal> for InternalJson {
+    fn from(internal: &Internal) -> Self {
+        let manual = &internal.manual;
+        Self {
+            id: manual.id.clone(),
+            name: manual.name.clone(),
+            description: manual.description.clone(),
+            rarity: manual.rarity.level(),
+            manual_type: manual.manual_type.clone(),
+            tags: manual.tags.clone(),
+            cultivation_formula: manual.cultivation_formula.formula_str().to_string(),
+            aptitude: manual.aptitude,
+            realms: internal
+                .realms
+                .iter()
+                .map(|r| InternalRealmJson {
+                    level: r.level,
+                    exp_required: r.exp_required,
+                    qi_gain: r.qi_gain,
+                    martial_arts_attainment: r.martial_arts_attainment,
+                    qi_quality: r.qi_quality,
+                    attack_speed: r.attack_speed,
+                    qi_recovery_rate: r.qi_recovery_rate,
+                    entries: r.entries.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Serialize for Internal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        InternalJson::from(self).serialize(serializer)
+    }
+}
+
+impl From<&AttackSkill> for AttackSkillJson {
+    fn from(skill: &AttackSkill) -> Self {
+        let manual = &skill.manual;
+        Self {
+            id: manual.id.clone(),
+            name: manual.name.clone(),
+            description: manual.description.clone(),
+            rarity: manual.rarity.level(),
+            manual_type: manual.manual_type.clone(),
+            tags: manual.tags.clone(),
+            cultivation_formula: manual.cultivation_formula.formula_str().to_string(),
+            aptitude: manual.aptitude,
+            realms: skill
+                .realms
+                .iter()
+                .map(|r| AttackSkillRealmJson {
+                    level: r.level,
+                    exp_required: r.exp_required,
+                    martial_arts_attainment: r.martial_arts_attainment,
+                    power: r.power,
+                    charge_time: r.charge_time,
+                    entries: r.entries.clone(),
+                })
+                .collect(),
+            log_template: skill.log_template.clone(),
+            ultimate: skill.ultimate.clone(),
+        }
+    }
+}
+
+impl Serialize for AttackSkill {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        AttackSkillJson::from(self).serialize(serializer)
+    }
+}
+
+impl From<&DefenseSkill> for DefenseSkillJson {
+    fn from(skill: &DefenseSkill) -> Self {
+        let manual = &skill.manual;
+        Self {
+            id: manual.id.clone(),
+            name: manual.name.clone(),
+            description: manual.description.clone(),
+            rarity: manual.rarity.level(),
+            manual_type: manual.manual_type.clone(),
+            tags: manual.tags.clone(),
+            cultivation_formula: manual.cultivation_formula.formula_str().to_string(),
+            aptitude: manual.aptitude,
+            realms: skill
+                .realms
+                .iter()
+                .map(|r| DefenseSkillRealmJson {
+                    level: r.level,
+                    exp_required: r.exp_required,
+                    martial_arts_attainment: r.martial_arts_attainment,
+                    defense_power: r.defense_power,
+                    entries: r.entries.clone(),
+                })
+                .collect(),
+            log_template: skill.log_template.clone(),
+            counter: skill.counter.clone(),
+        }
+    }
+}
+
+impl Serialize for DefenseSkill {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        DefenseSkillJson::from(self).serialize(serializer)
+    }
+}
+
 /// 内功数据文件结构
 #[derive(Debug, Deserialize)]
 pub struct InternalsData {
@@ -106,17 +242,41 @@ pub struct DefenseSkillsData {
 }
 
 /// 解析内功数据
-pub fn parse_internals(json: &str) -> Result<Vec<Internal>, String> {
-    let data: InternalsData =
-        serde_json::from_str(json).map_err(|e| format!("解析内功数据失败: {}", e))?;
+pub fn parse_internals(json: &str) -> WushenResult<Vec<Internal>> {
+    parse_internals_with_warnings(json).map(|(internals, _)| internals)
+}
+
+/// 解析内功数据，并返回数据版本升级产生的警告
+pub fn parse_internals_with_warnings(
+    json: &str,
+) -> WushenResult<(Vec<Internal>, Vec<LoadWarning>)> {
+    parse_internals_with_options(json, ParseOptions::default())
+}
+
+/// 按解析选项解析内功数据
+pub fn parse_internals_with_options(
+    json: &str,
+    options: ParseOptions,
+) -> WushenResult<(Vec<Internal>, Vec<LoadWarning>)> {
+    let (internals, mut warnings) = parse_collection(json, CollectionKind::Internals, options)
+        .map_err(|e| WushenError::ParseError(format!("解析内功数据失败: {}", e)))?;
+    let data = InternalsData { internals };
 
     let mut internals = Vec::new();
-    for internal_json in data.internals {
+    for mut internal_json in data.internals {
+        check_realms(
+            CollectionKind::Internals,
+            &internal_json.id,
+            &mut internal_json.realms,
+            |r| (r.level, r.exp_required),
+            &mut warnings,
+        );
         let formula = CultivationFormula::new(&internal_json.cultivation_formula)?;
-        let rarity = Rarity::new(internal_json.rarity)
-            .map_err(|e| format!("内功 {} 稀有度无效: {}", internal_json.id, e))?;
+        let rarity = Rarity::new(internal_json.rarity).map_err(|e| {
+            WushenError::ParseError(format!("内功 {} 稀有度无效: {}", internal_json.id, e))
+        })?;
 
-        let manual = Manual::new(
+        let mut manual = Manual::new(
             internal_json.id.clone(),
             internal_json.name,
             internal_json.description,
@@ -124,8 +284,15 @@ pub fn parse_internals(json: &str) -> Result<Vec<Internal>, String> {
             internal_json.manual_type,
             formula,
         );
+        manual.tags = internal_json.tags;
+        if let Some(aptitude) = &internal_json.aptitude {
+            aptitude.validate().map_err(|e| {
+                WushenError::ParseError(format!("内功 {} 资质权重无效: {}", internal_json.id, e))
+            })?;
+        }
+        manual.aptitude = internal_json.aptitude;
 
-        let realms: Result<Vec<InternalRealm>, String> = internal_json
+        let realms: WushenResult<Vec<InternalRealm>> = internal_json
             .realms
             .into_iter()
             .map(|r| {
@@ -146,23 +313,54 @@ pub fn parse_internals(json: &str) -> Result<Vec<Internal>, String> {
         internals.push(internal);
     }
 
-    Ok(internals)
+    Ok((internals, warnings))
 }
 
 /// 解析攻击武技数据
-pub fn parse_attack_skills(json: &str) -> Result<Vec<AttackSkill>, String> {
-    let data: AttackSkillsData = serde_json::from_str(json)
-        .map_err(|e| format!("解析攻击武技数据失败: {}。请检查JSON格式是否正确，确保包含必需的字段：id, name, description, rarity, type, cultivation_formula, realms", e))?;
+pub fn parse_attack_skills(json: &str) -> WushenResult<Vec<AttackSkill>> {
+    parse_attack_skills_with_warnings(json).map(|(skills, _)| skills)
+}
+
+/// 解析攻击武技数据，并返回数据版本升级产生的警告
+pub fn parse_attack_skills_with_warnings(
+    json: &str,
+) -> WushenResult<(Vec<AttackSkill>, Vec<LoadWarning>)> {
+    parse_attack_skills_with_options(json, ParseOptions::default())
+}
+
+/// 按解析选项解析攻击武技数据
+pub fn parse_attack_skills_with_options(
+    json: &str,
+    options: ParseOptions,
+) -> WushenResult<(Vec<AttackSkill>, Vec<LoadWarning>)> {
+    let (attack_skills, mut warnings) = parse_collection(json, CollectionKind::AttackSkills, options)
+        .map_err(|e| WushenError::ParseError(format!("解析攻击武技数据失败: {}。请检查JSON格式是否正确，确保包含必需的字段：id, name, description, rarity, type, cultivation_formula, realms", e)))?;
+    let data = AttackSkillsData { attack_skills };
 
     let mut skills = Vec::new();
-    for (idx, skill_json) in data.attack_skills.into_iter().enumerate() {
+    for (idx, mut skill_json) in data.attack_skills.into_iter().enumerate() {
         let skill_id = skill_json.id.clone();
-        let formula = CultivationFormula::new(&skill_json.cultivation_formula)
-            .map_err(|e| format!("攻击武技 {} (索引 {}) 的修行公式无效: {}", skill_id, idx, e))?;
-        let rarity = Rarity::new(skill_json.rarity)
-            .map_err(|e| format!("攻击武技 {} (索引 {}) 稀有度无效: {}", skill_id, idx, e))?;
+        check_realms(
+            CollectionKind::AttackSkills,
+            &skill_id,
+            &mut skill_json.realms,
+            |r| (r.level, r.exp_required),
+            &mut warnings,
+        );
+        let formula = CultivationFormula::new(&skill_json.cultivation_formula).map_err(|e| {
+            WushenError::ParseError(format!(
+                "攻击武技 {} (索引 {}) 的修行公式无效: {}",
+                skill_id, idx, e
+            ))
+        })?;
+        let rarity = Rarity::new(skill_json.rarity).map_err(|e| {
+            WushenError::ParseError(format!(
+                "攻击武技 {} (索引 {}) 稀有度无效: {}",
+                skill_id, idx, e
+            ))
+        })?;
 
-        let manual = Manual::new(
+        let mut manual = Manual::new(
             skill_json.id.clone(),
             skill_json.name,
             skill_json.description,
@@ -170,6 +368,13 @@ pub fn parse_attack_skills(json: &str) -> Result<Vec<AttackSkill>, String> {
             skill_json.manual_type,
             formula,
         );
+        manual.tags = skill_json.tags;
+        if let Some(aptitude) = &skill_json.aptitude {
+            aptitude.validate().map_err(|e| {
+                WushenError::ParseError(format!("攻击武技 {} 资质权重无效: {}", skill_id, e))
+            })?;
+        }
+        manual.aptitude = skill_json.aptitude;
 
         let realms: Vec<AttackSkillRealm> = skill_json
             .realms
@@ -186,29 +391,70 @@ pub fn parse_attack_skills(json: &str) -> Result<Vec<AttackSkill>, String> {
             })
             .collect();
 
-        let mut skill = AttackSkill::new(manual, realms)
-            .map_err(|e| format!("攻击武技 {} (索引 {}) 创建失败: {}", skill_id, idx, e))?;
+        let mut skill = AttackSkill::new(manual, realms).map_err(|e| {
+            WushenError::ParseError(format!(
+                "攻击武技 {} (索引 {}) 创建失败: {}",
+                skill_id, idx, e
+            ))
+        })?;
         skill.log_template = skill_json.log_template;
+        if let Some(ultimate) = &skill_json.ultimate {
+            ultimate.validate().map_err(|e| {
+                WushenError::ParseError(format!("攻击武技 {} 绝招无效: {}", skill_id, e))
+            })?;
+        }
+        skill.ultimate = skill_json.ultimate;
         skills.push(skill);
     }
 
-    Ok(skills)
+    Ok((skills, warnings))
 }
 
 /// 解析防御武技数据
-pub fn parse_defense_skills(json: &str) -> Result<Vec<DefenseSkill>, String> {
-    let data: DefenseSkillsData = serde_json::from_str(json)
-        .map_err(|e| format!("解析防御武技数据失败: {}。请检查JSON格式是否正确，确保包含必需的字段：id, name, description, rarity, type, cultivation_formula, realms", e))?;
+pub fn parse_defense_skills(json: &str) -> WushenResult<Vec<DefenseSkill>> {
+    parse_defense_skills_with_warnings(json).map(|(skills, _)| skills)
+}
+
+/// 解析防御武技数据，并返回数据版本升级产生的警告
+pub fn parse_defense_skills_with_warnings(
+    json: &str,
+) -> WushenResult<(Vec<DefenseSkill>, Vec<LoadWarning>)> {
+    parse_defense_skills_with_options(json, ParseOptions::default())
+}
+
+/// 按解析选项解析防御武技数据
+pub fn parse_defense_skills_with_options(
+    json: &str,
+    options: ParseOptions,
+) -> WushenResult<(Vec<DefenseSkill>, Vec<LoadWarning>)> {
+    let (defense_skills, mut warnings) = parse_collection(json, CollectionKind::DefenseSkills, options)
+        .map_err(|e| WushenError::ParseError(format!("解析防御武技数据失败: {}。请检查JSON格式是否正确，确保包含必需的字段：id, name, description, rarity, type, cultivation_formula, realms", e)))?;
+    let data = DefenseSkillsData { defense_skills };
 
     let mut skills = Vec::new();
-    for (idx, skill_json) in data.defense_skills.into_iter().enumerate() {
+    for (idx, mut skill_json) in data.defense_skills.into_iter().enumerate() {
         let skill_id = skill_json.id.clone();
-        let formula = CultivationFormula::new(&skill_json.cultivation_formula)
-            .map_err(|e| format!("防御武技 {} (索引 {}) 的修行公式无效: {}", skill_id, idx, e))?;
-        let rarity = Rarity::new(skill_json.rarity)
-            .map_err(|e| format!("防御武技 {} (索引 {}) 稀有度无效: {}", skill_id, idx, e))?;
+        check_realms(
+            CollectionKind::DefenseSkills,
+            &skill_id,
+            &mut skill_json.realms,
+            |r| (r.level, r.exp_required),
+            &mut warnings,
+        );
+        let formula = CultivationFormula::new(&skill_json.cultivation_formula).map_err(|e| {
+            WushenError::ParseError(format!(
+                "防御武技 {} (索引 {}) 的修行公式无效: {}",
+                skill_id, idx, e
+            ))
+        })?;
+        let rarity = Rarity::new(skill_json.rarity).map_err(|e| {
+            WushenError::ParseError(format!(
+                "防御武技 {} (索引 {}) 稀有度无效: {}",
+                skill_id, idx, e
+            ))
+        })?;
 
-        let manual = Manual::new(
+        let mut manual = Manual::new(
             skill_json.id.clone(),
             skill_json.name,
             skill_json.description,
@@ -216,6 +462,13 @@ pub fn parse_defense_skills(json: &str) -> Result<Vec<DefenseSkill>, String> {
             skill_json.manual_type,
             formula,
         );
+        manual.tags = skill_json.tags;
+        if let Some(aptitude) = &skill_json.aptitude {
+            aptitude.validate().map_err(|e| {
+                WushenError::ParseError(format!("防御武技 {} 资质权重无效: {}", skill_id, e))
+            })?;
+        }
+        manual.aptitude = skill_json.aptitude;
 
         let realms: Vec<DefenseSkillRealm> = skill_json
             .realms
@@ -231,18 +484,66 @@ pub fn parse_defense_skills(json: &str) -> Result<Vec<DefenseSkill>, String> {
             })
             .collect();
 
-        let mut skill = DefenseSkill::new(manual, realms)
-            .map_err(|e| format!("防御武技 {} (索引 {}) 创建失败: {}", skill_id, idx, e))?;
+        let mut skill = DefenseSkill::new(manual, realms).map_err(|e| {
+            WushenError::ParseError(format!(
+                "防御武技 {} (索引 {}) 创建失败: {}",
+                skill_id, idx, e
+            ))
+        })?;
         skill.log_template = skill_json.log_template;
+        if let Some(counter) = &skill_json.counter {
+            counter.validate().map_err(|e| {
+                WushenError::ParseError(format!("防御武技 {} 反击无效: {}", skill_id, e))
+            })?;
+        }
+        skill.counter = skill_json.counter;
         skills.push(skill);
     }
 
-    Ok(skills)
+    Ok((skills, warnings))
+}
+
+/// 检查境界数据：按等级乱序书写的境界自动排序，升级经验为负数时给出警告
+fn check_realms<R>(
+    kind: CollectionKind,
+    id: &str,
+    realms: &mut [R],
+    key: fn(&R) -> (u32, f64),
+    warnings: &mut Vec<LoadWarning>,
+) {
+    if realms
+        .windows(2)
+        .any(|pair| key(&pair[0]).0 > key(&pair[1]).0)
+    {
+        realms.sort_by_key(|realm| key(realm).0);
+        warnings.push(LoadWarning::auto_corrected(
+            kind,
+            id,
+            format!("{} {}：境界未按等级排列，已自动排序", kind.name(), id),
+        ));
+    }
+    for realm in realms.iter() {
+        let (level, exp_required) = key(realm);
+        if exp_required < 0.0 {
+            warnings.push(LoadWarning::suspicious(
+                kind,
+                id,
+                format!(
+                    "{} {}：第 {} 重境界的升级经验为负数（{}）",
+                    kind.name(),
+                    id,
+                    level,
+                    exp_required
+                ),
+            ));
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pack::LoadWarningKind;
 
     #[test]
     fn test_parse_internals_from_data_file() {
@@ -277,4 +578,61 @@ mod tests {
             "x * 10 + a * 2"
         );
     }
+
+    #[test]
+    fn manuals_serialize_back_to_the_data_file_format() {
+        let realms: Vec<_> = (1..=5)
+            .map(|level| {
+                serde_json::json!({"level": level, "exp_required": 100 * level,
+                    "martial_arts_attainment": 10, "power": 1.5, "charge_time": 100, "entries": []})
+            })
+            .collect();
+        let json = serde_json::json!([{
+            "id": "剑诀", "name": "剑诀", "description": "", "rarity": 2, "type": "sword",
+            "tags": ["剑"], "cultivation_formula": "x * 10", "log_template": "{attacker}出剑",
+            "realms": realms
+        }])
+        .to_string();
+        let skills = parse_attack_skills(&json).unwrap();
+        let value = serde_json::to_value(&skills).unwrap();
+        assert_eq!(value[0]["type"], "sword");
+        assert_eq!(value[0]["realms"][0]["power"], 1.5);
+        assert!(value[0].get("level").is_none());
+
+        // 重新解析后序列化结果不变，修行进度不影响结果
+        let mut reparsed = parse_attack_skills(&value.to_string()).unwrap();
+        reparsed[0].manual.level = 1;
+        reparsed[0].manual.current_exp = 42.0;
+        assert_eq!(serde_json::to_value(&reparsed).unwrap(), value);
+    }
+
+    #[test]
+    fn test_realm_warnings() {
+        let json = r#"[{
+            "id": "reversed", "name": "逆练", "description": "", "rarity": 1,
+            "manual_type": "neutral", "cultivation_formula": "x * 10",
+            "realms": [
+                {"level": 2, "exp_required": 200, "qi_gain": 100, "martial_arts_attainment": 20, "qi_quality": 1.2, "attack_speed": 1.1, "qi_recovery_rate": 0.06, "entries": []},
+                {"level": 1, "exp_required": -100, "qi_gain": 50, "martial_arts_attainment": 10, "qi_quality": 1.0, "attack_speed": 1.0, "qi_recovery_rate": 0.05, "entries": []},
+                {"level": 3, "exp_required": 400, "qi_gain": 200, "martial_arts_attainment": 30, "qi_quality": 1.5, "attack_speed": 1.2, "qi_recovery_rate": 0.07, "entries": []},
+                {"level": 4, "exp_required": 800, "qi_gain": 400, "martial_arts_attainment": 40, "qi_quality": 2.0, "attack_speed": 1.3, "qi_recovery_rate": 0.08, "entries": []},
+                {"level": 5, "exp_required": 1600, "qi_gain": 800, "martial_arts_attainment": 50, "qi_quality": 2.5, "attack_speed": 1.5, "qi_recovery_rate": 0.10, "entries": []}
+            ]
+        }]"#;
+
+        let (internals, warnings) = parse_internals_with_warnings(json).unwrap();
+        assert_eq!(internals[0].realms[0].level, 1);
+        let kinds: Vec<_> = warnings.iter().map(|w| w.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                LoadWarningKind::Deprecated,
+                LoadWarningKind::AutoCorrected,
+                LoadWarningKind::Suspicious
+            ]
+        );
+        assert!(warnings
+            .iter()
+            .all(|w| w.collection == "internals" && w.id.as_deref() == Some("reversed")));
+    }
 }
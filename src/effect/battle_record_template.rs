@@ -140,6 +140,7 @@ impl BattleRecordTemplate {
             AttributeTarget::AttackSpeed => "出手速度",
             AttributeTarget::QiRecoveryRate => "回气速度",
             AttributeTarget::ChargeTime => "蓄力时间",
+            AttributeTarget::Momentum => "气势",
             AttributeTarget::Comprehension => "悟性",
             AttributeTarget::BoneStructure => "根骨",
             AttributeTarget::Physique => "体魄",
@@ -4,6 +4,7 @@ use crate::character::panel::CharacterPanel;
 /// 词条触发条件
 /// 支持复杂的条件表达式（AND/OR 组合）
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// 比较运算符
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -37,9 +38,15 @@ pub enum CultivationCondition {
     DefenseSkillIs(String),
     /// 当修行的防御武技类型为指定类型时
     DefenseSkillTypeIs(String),
+    /// 当修行的内功具有指定标签时
+    InternalHasTag(String),
+    /// 当修行的攻击武技具有指定标签时
+    AttackSkillHasTag(String),
+    /// 当修行的防御武技具有指定标签时
+    DefenseSkillHasTag(String),
     /// 当具备特性时
     HasTrait(String),
-    /// 当悟性/根骨/体魄/武学素养满足条件时
+    /// 当悟性/根骨/体魄/武学素养/生命上限/基础攻防满足条件时
     AttributeComparison {
         attribute: AttributeType,
         op: ComparisonOp,
@@ -59,6 +66,12 @@ pub enum AttributeType {
     Physique,
     /// 武学素养
     MartialArtsAttainment,
+    /// 生命上限
+    MaxHp,
+    /// 基础攻击力
+    BaseAttack,
+    /// 基础防御力
+    BaseDefense,
 }
 
 /// 战斗条件（战斗时）
@@ -157,11 +170,17 @@ impl Condition {
                     attack_skill_type: context.self_attack_skill_type.clone(),
                     defense_skill_id: context.self_defense_skill_id.clone(),
                     defense_skill_type: context.self_defense_skill_type.clone(),
+                    internal_tags: Vec::new(),
+                    attack_skill_tags: Vec::new(),
+                    defense_skill_tags: Vec::new(),
                     traits: Vec::new(),
                     comprehension: context.self_comprehension,
                     bone_structure: context.self_bone_structure,
                     physique: context.self_physique,
                     martial_arts_attainment: context.self_martial_arts_attainment,
+                    max_hp: context.self_panel.as_ref().map_or(0.0, |p| p.max_hp),
+                    base_attack: context.self_panel.as_ref().map_or(0.0, |p| p.base_attack),
+                    base_defense: context.self_panel.as_ref().map_or(0.0, |p| p.base_defense),
                 };
                 cond.check(&cult_context)
             }
@@ -181,15 +200,28 @@ pub struct CultivationContext {
     pub attack_skill_type: Option<String>,
     pub defense_skill_id: Option<String>,
     pub defense_skill_type: Option<String>,
+    /// 内功标签
+    pub internal_tags: Vec<String>,
+    /// 攻击武技标签
+    pub attack_skill_tags: Vec<String>,
+    /// 防御武技标签
+    pub defense_skill_tags: Vec<String>,
     pub traits: Vec<String>,
     pub comprehension: f64,
     pub bone_structure: f64,
     pub physique: f64,
     pub martial_arts_attainment: f64,
+    /// 生命上限
+    pub max_hp: f64,
+    /// 基础攻击力
+    pub base_attack: f64,
+    /// 基础防御力
+    pub base_defense: f64,
 }
 
 /// 攻击结果（用于词条条件判断）
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AttackResult {
     pub total_output: f64,
     pub total_defense: f64,
@@ -239,6 +271,8 @@ pub struct BattleContext {
     pub self_panel: Option<CharacterPanel>,
     /// 对方角色面板（用于公式计算）
     pub opponent_panel: Option<CharacterPanel>,
+    /// 战斗环境变量（用于公式计算）
+    pub environment: BTreeMap<String, f64>,
 }
 
 impl CultivationCondition {
@@ -256,6 +290,11 @@ impl CultivationCondition {
             CultivationCondition::DefenseSkillTypeIs(ty) => {
                 context.defense_skill_type.as_ref() == Some(ty)
             }
+            CultivationCondition::InternalHasTag(tag) => context.internal_tags.contains(tag),
+            CultivationCondition::AttackSkillHasTag(tag) => context.attack_skill_tags.contains(tag),
+            CultivationCondition::DefenseSkillHasTag(tag) => {
+                context.defense_skill_tags.contains(tag)
+            }
             CultivationCondition::HasTrait(trait_id) => context.traits.contains(trait_id),
             CultivationCondition::AttributeComparison {
                 attribute,
@@ -267,6 +306,9 @@ impl CultivationCondition {
                     AttributeType::BoneStructure => context.bone_structure,
                     AttributeType::Physique => context.physique,
                     AttributeType::MartialArtsAttainment => context.martial_arts_attainment,
+                    AttributeType::MaxHp => context.max_hp,
+                    AttributeType::BaseAttack => context.base_attack,
+                    AttributeType::BaseDefense => context.base_defense,
                 };
                 match op {
                     ComparisonOp::LessThan => attr_value < *value,
@@ -318,6 +360,7 @@ impl BattleCondition {
                             }),
                             opponent_panel: context.opponent_panel.clone(),
                             attack_result: context.attack_result,
+                            environment: context.environment.clone(),
                         };
 
                         // 计算公式值
@@ -374,6 +417,7 @@ impl BattleCondition {
                             }),
                             opponent_panel: context.opponent_panel.clone(),
                             attack_result: context.attack_result,
+                            environment: context.environment.clone(),
                         };
 
                         // 计算公式值
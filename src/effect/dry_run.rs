@@ -0,0 +1,152 @@
+use super::effect::{AttributeTarget, Effect, Operation, PanelTarget};
+use super::entry::Entry;
+use super::executor::EntryExecutor;
+use super::trigger::Trigger;
+use crate::character::panel::CharacterPanel;
+/// 词条试算
+/// 在给定的面板与攻击结果下判断单个词条的条件并计算各效果的数值，不改变任何状态；
+/// 供编辑器“测试词条”使用，不必跑完整场战斗观察词条表现
+use crate::error::{WushenError, WushenResult};
+use serde::Serialize;
+
+/// 词条试算结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EntryDryRun {
+    pub trigger: Trigger,
+    /// 条件是否满足（无条件时为 true）
+    pub condition_met: bool,
+    /// 词条不符合触发时机限制的原因（仍照常试算）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invalid: Option<String>,
+    /// 各效果的数值（条件不满足时同样计算，便于调整数值）
+    pub effects: Vec<EffectDryRun>,
+}
+
+/// 单个效果的试算结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EffectDryRun {
+    /// 效果类型（与词条 JSON 中的 type 相同）
+    pub effect_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<AttributeTarget>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_panel: Option<PanelTarget>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation: Option<Operation>,
+    /// 固定值或公式的计算结果（额外攻击为输出值，封招为持续回合数）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
+    /// 实际作用的修改量：百分比效果按目标面板的当前值折算，其余与 value 相同
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub applied_value: Option<f64>,
+    /// 公式计算失败的原因（实际触发时该效果会被跳过或按 0 计算）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl EntryDryRun {
+    /// 试算词条
+    ///
+    /// `evaluate` 在对应上下文中计算公式；`opponent_panel` 为空时，作用于对手的百分比效果无法折算
+    pub fn evaluate(
+        entry: &Entry,
+        condition_met: bool,
+        self_panel: &CharacterPanel,
+        opponent_panel: Option<&CharacterPanel>,
+        evaluate: impl Fn(&str) -> WushenResult<f64>,
+    ) -> Self {
+        let effects = entry
+            .effects
+            .iter()
+            .map(|effect| EffectDryRun::evaluate(effect, self_panel, opponent_panel, &evaluate))
+            .collect();
+        Self {
+            trigger: entry.trigger,
+            condition_met,
+            invalid: entry.validate().err().map(String::from),
+            effects,
+        }
+    }
+}
+
+impl EffectDryRun {
+    fn evaluate(
+        effect: &Effect,
+        self_panel: &CharacterPanel,
+        opponent_panel: Option<&CharacterPanel>,
+        evaluate: &impl Fn(&str) -> WushenResult<f64>,
+    ) -> Self {
+        let mut dry_run = Self {
+            effect_type: "",
+            target: None,
+            target_panel: None,
+            operation: None,
+            value: None,
+            applied_value: None,
+            error: None,
+        };
+        let calculated = match effect {
+            Effect::ModifyAttribute {
+                target,
+                value,
+                operation,
+                target_panel,
+                ..
+            }
+            | Effect::ModifyPercentage {
+                target,
+                value,
+                operation,
+                target_panel,
+                ..
+            } => {
+                dry_run.target = Some(*target);
+                dry_run.target_panel = Some(*target_panel);
+                dry_run.operation = Some(*operation);
+                match (value.as_fixed(), value.as_formula()) {
+                    (Some(fixed), _) => Ok(fixed),
+                    (None, Some(formula)) => evaluate(formula),
+                    (None, None) => Err(WushenError::Invalid(
+                        "效果值既不是公式也不是固定值".to_string(),
+                    )),
+                }
+            }
+            Effect::ExtraAttack { output, .. } => evaluate(output),
+            Effect::SealSkill { rounds, .. } => Ok(*rounds as f64),
+        };
+        dry_run.effect_type = match effect {
+            Effect::ModifyAttribute { .. } => "modify_attribute",
+            Effect::ModifyPercentage { .. } => "modify_percentage",
+            Effect::ExtraAttack { .. } => "extra_attack",
+            Effect::SealSkill { .. } => "seal_skill",
+        };
+
+        let value = match calculated {
+            Ok(value) => value,
+            Err(e) => {
+                dry_run.error = Some(e.into());
+                return dry_run;
+            }
+        };
+        dry_run.value = Some(value);
+        dry_run.applied_value = match effect {
+            Effect::ModifyPercentage {
+                target,
+                operation,
+                target_panel,
+                ..
+            } => {
+                let panel = match target_panel {
+                    PanelTarget::Own => Some(self_panel),
+                    PanelTarget::Opponent => opponent_panel,
+                };
+                panel.map(|panel| match operation {
+                    Operation::Multiply => value,
+                    _ => EntryExecutor::panel_value(panel, *target) * value,
+                })
+            }
+            _ => Some(value),
+        };
+        dry_run
+    }
+}
@@ -60,6 +60,8 @@ pub enum AttributeTarget {
     DamageBonus,
     DamageReduction,
     MaxDamageReduction,
+    /// 气势（仅战斗中存在，攻防得手时积累、回合间衰减）
+    Momentum,
 
     // 修行相关
     MartialArtsAttainmentGain,
@@ -93,6 +95,26 @@ pub enum PanelTarget {
     Opponent,
 }
 
+/// 武技槽位（用于封招等针对武技的效果）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillSlot {
+    /// 攻击武技
+    AttackSkill,
+    /// 防御武技
+    DefenseSkill,
+}
+
+impl SkillSlot {
+    /// 槽位名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            SkillSlot::AttackSkill => "攻击武技",
+            SkillSlot::DefenseSkill => "防御武技",
+        }
+    }
+}
+
 /// 效果类型
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -158,6 +180,16 @@ pub enum Effect {
         #[serde(default, skip_serializing_if = "Option::is_none")]
         battle_record_template: Option<BattleRecordTemplate>,
     },
+    /// 封招（点穴、缴械）：对手指定武技在若干回合内失效，以空手数值应对
+    SealSkill {
+        slot: SkillSlot,
+        /// 持续回合数（重复封招时取较大值）
+        rounds: u32,
+        /// 战斗记录模板（可选）
+        /// 支持的占位符：{self_name}, {opponent_name}, {value}（持续回合数）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        battle_record_template: Option<BattleRecordTemplate>,
+    },
 }
 
 /// 默认值函数，用于serde的default属性
@@ -232,8 +264,8 @@ impl Effect {
                     new_value
                 }
             }
-            Effect::ExtraAttack { .. } => {
-                // 额外攻击需要特殊处理，不在这里修改数值
+            Effect::ExtraAttack { .. } | Effect::SealSkill { .. } => {
+                // 额外攻击、封招需要特殊处理，不在这里修改数值
                 current_value
             }
         }
@@ -332,6 +364,7 @@ impl Effect {
                     AttributeTarget::AttackSpeed => "出手速度",
                     AttributeTarget::QiRecoveryRate => "回气速度",
                     AttributeTarget::ChargeTime => "蓄力时间",
+                    AttributeTarget::Momentum => "气势",
                     AttributeTarget::QiLossRate => "转修损失内息量",
                     _ => "属性",
                 };
@@ -376,6 +409,34 @@ impl Effect {
                 // 否则使用默认格式
                 Some(format!("额外攻击，输出值：{}", output))
             }
+            Effect::SealSkill {
+                slot,
+                rounds,
+                battle_record_template,
+            } => {
+                let rounds_str = rounds.to_string();
+                if let Some(template) = battle_record_template {
+                    return Some(template.generate(
+                        entry_id,
+                        self_panel,
+                        opponent_panel,
+                        battle_result,
+                        None,
+                        Some(&rounds_str),
+                        None,
+                    ));
+                }
+
+                let opponent_name = opponent_panel
+                    .map(|opponent| opponent.name.clone())
+                    .unwrap_or_else(|| "对手".to_string());
+                Some(format!(
+                    "{}的{}被封，{}回合内只能空手应对",
+                    opponent_name,
+                    slot.name(),
+                    rounds_str
+                ))
+            }
         }
     }
 }
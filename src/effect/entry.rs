@@ -1,8 +1,11 @@
-/// 词条定义
 use super::condition::Condition;
 use super::effect::{AttributeTarget, Effect, Operation};
 use super::trigger::Trigger;
-use serde::{Deserialize, Serialize};
+/// 词条定义
+use crate::error::{WushenError, WushenResult};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeSet;
 
 /// 词条
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,11 +20,28 @@ pub struct Entry {
     /// 最大触发次数（可选，每场战斗刷新）
     #[serde(default)]
     pub max_triggers: Option<u32>,
+    /// 标签（如 healing、speed），可按标签整体禁用词条（挑战模式或排查数据包交互）；
+    /// 加载时拒绝空标签
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_tags"
+    )]
+    pub tags: Vec<String>,
     /// 当前触发次数（运行时状态，不序列化）
     #[serde(skip)]
     pub current_triggers: u32,
 }
 
+/// 读取词条标签，拒绝空标签（如编辑器中多写的逗号留下的空项）
+fn deserialize_tags<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    let tags = Vec::<String>::deserialize(deserializer)?;
+    if tags.iter().any(|tag| tag.trim().is_empty()) {
+        return Err(D::Error::custom("词条标签不能为空"));
+    }
+    Ok(tags)
+}
+
 impl Entry {
     /// 创建新词条
     pub fn new(
@@ -35,10 +55,20 @@ impl Entry {
             condition,
             effects,
             max_triggers,
+            tags: Vec::new(),
             current_triggers: 0,
         }
     }
 
+    /// 词条带有的、属于给定集合的标签（如被禁用的标签）
+    pub fn matching_tags(&self, tags: &BTreeSet<String>) -> Vec<String> {
+        self.tags
+            .iter()
+            .filter(|tag| tags.contains(*tag))
+            .cloned()
+            .collect()
+    }
+
     /// 检查是否可以触发
     pub fn can_trigger(&self) -> bool {
         if let Some(max) = self.max_triggers {
@@ -63,7 +93,7 @@ impl Entry {
     /// 验证词条的效果是否符合触发时机的限制
     ///
     /// 返回 Ok(()) 如果所有效果都合法，否则返回错误信息
-    pub fn validate(&self) -> Result<(), String> {
+    pub fn validate(&self) -> WushenResult<()> {
         // 获取该触发时机允许的效果类型
         let allowed_targets = Self::get_allowed_targets(self.trigger);
         let allowed_operations = Self::get_allowed_operations(self.trigger);
@@ -79,31 +109,40 @@ impl Entry {
                 } => {
                     // 检查目标属性是否允许
                     if !allowed_targets.contains(target) {
-                        return Err(format!(
+                        return Err(WushenError::Invalid(format!(
                             "效果 #{}: 触发时机 {:?} 不允许修改属性 {:?}",
                             idx + 1,
                             self.trigger,
                             target
-                        ));
+                        )));
                     }
 
                     // 检查操作类型是否允许
                     if !allowed_operations.contains(operation) {
-                        return Err(format!(
+                        return Err(WushenError::Invalid(format!(
                             "效果 #{}: 触发时机 {:?} 不允许操作类型 {:?}",
                             idx + 1,
                             self.trigger,
                             operation
-                        ));
+                        )));
                     }
                 }
                 Effect::ExtraAttack { .. } => {
                     if !allows_extra_attack {
-                        return Err(format!(
+                        return Err(WushenError::Invalid(format!(
                             "效果 #{}: 触发时机 {:?} 不允许额外攻击效果",
                             idx + 1,
                             self.trigger
-                        ));
+                        )));
+                    }
+                }
+                Effect::SealSkill { .. } => {
+                    if !Self::allows_seal_skill(self.trigger) {
+                        return Err(WushenError::Invalid(format!(
+                            "效果 #{}: 触发时机 {:?} 不允许封招效果",
+                            idx + 1,
+                            self.trigger
+                        )));
                     }
                 }
             }
@@ -122,6 +161,9 @@ impl Entry {
                     AttributeTarget::Comprehension,
                     AttributeTarget::BoneStructure,
                     AttributeTarget::Physique,
+                    AttributeTarget::MaxHp,
+                    AttributeTarget::BaseAttack,
+                    AttributeTarget::BaseDefense,
                 ]
             }
             Trigger::ReadingManual => {
@@ -172,6 +214,7 @@ impl Entry {
                     AttributeTarget::DamageBonus,
                     AttributeTarget::DamageReduction,
                     AttributeTarget::MaxDamageReduction,
+                    AttributeTarget::Momentum,
                 ]
             }
             Trigger::BeforeAttack | Trigger::BeforeDefense => {
@@ -182,6 +225,7 @@ impl Entry {
                     AttributeTarget::BaseDefense,
                     AttributeTarget::DamageBonus,
                     AttributeTarget::DamageReduction,
+                    AttributeTarget::Momentum,
                 ]
             }
             Trigger::AfterAttack | Trigger::AfterDefense | Trigger::RoundEnd => {
@@ -196,6 +240,7 @@ impl Entry {
                     AttributeTarget::DamageBonus,
                     AttributeTarget::DamageReduction,
                     AttributeTarget::MaxDamageReduction,
+                    AttributeTarget::Momentum,
                 ]
             }
         }
@@ -245,4 +290,17 @@ impl Entry {
     fn allows_extra_attack(trigger: Trigger) -> bool {
         matches!(trigger, Trigger::AfterAttack | Trigger::AfterDefense)
     }
+
+    /// 检查指定触发时机是否允许封招效果（仅战斗中）
+    fn allows_seal_skill(trigger: Trigger) -> bool {
+        matches!(
+            trigger,
+            Trigger::BattleStart
+                | Trigger::BeforeAttack
+                | Trigger::BeforeDefense
+                | Trigger::AfterAttack
+                | Trigger::AfterDefense
+                | Trigger::RoundEnd
+        )
+    }
 }
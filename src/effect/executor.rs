@@ -8,9 +8,10 @@ use crate::effect::{
     modifier::AttributeModifier,
     trigger::Trigger,
 };
+use serde::Serialize;
 /// 词条执行引擎
 /// 处理词条的触发、条件判断和效果应用
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 /// 词条效果及其来源
 #[derive(Debug, Clone)]
@@ -25,6 +26,19 @@ pub struct EntryEffect {
     pub entry_order: u64,
 }
 
+/// 因标签被禁用而跳过的词条（调试追踪）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SkippedEntry {
+    /// 词条来源ID
+    pub source_id: String,
+    /// 来源的可读名称
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_name: Option<String>,
+    pub trigger: Trigger,
+    /// 命中的禁用标签
+    pub tags: Vec<String>,
+}
+
 #[derive(Debug, Clone)]
 struct EntryWithSource {
     entry: Entry,
@@ -34,10 +48,17 @@ struct EntryWithSource {
 }
 
 /// 词条执行器
+#[derive(Clone)]
 pub struct EntryExecutor {
     /// 按触发时机索引的词条及其来源
     entries_by_trigger: HashMap<Trigger, Vec<EntryWithSource>>,
     next_entry_order: u64,
+    /// 来源ID对应的可读名称（如特性名、内功名与境界）
+    source_names: HashMap<String, String>,
+    /// 禁用的词条标签（带有其中任一标签的词条不会加入）
+    disabled_tags: BTreeSet<String>,
+    /// 因标签被禁用而跳过的词条（按加入顺序）
+    skipped: Vec<SkippedEntry>,
 }
 
 impl EntryExecutor {
@@ -46,11 +67,101 @@ impl EntryExecutor {
         Self {
             entries_by_trigger: HashMap::new(),
             next_entry_order: 0,
+            source_names: HashMap::new(),
+            disabled_tags: BTreeSet::new(),
+            skipped: Vec::new(),
+        }
+    }
+
+    /// 禁用带有指定标签的词条：已加入的词条被移除，之后加入的词条被跳过，均记入跳过列表
+    pub fn disable_tags(&mut self, tags: impl IntoIterator<Item = String>) {
+        self.disabled_tags.extend(tags);
+        if self.disabled_tags.is_empty() {
+            return;
+        }
+        let mut removed = Vec::new();
+        for entries in self.entries_by_trigger.values_mut() {
+            entries.retain(|entry| {
+                let tags = entry.entry.matching_tags(&self.disabled_tags);
+                if tags.is_empty() {
+                    return true;
+                }
+                removed.push((
+                    entry.entry_order,
+                    entry.entry.trigger,
+                    entry.source_id.clone(),
+                    tags,
+                ));
+                false
+            });
+        }
+        removed.sort_by_key(|(order, ..)| *order);
+        for (_, trigger, source_id, tags) in removed {
+            self.record_skipped(source_id, trigger, tags);
         }
     }
 
+    /// 禁用的词条标签
+    pub fn disabled_tags(&self) -> &BTreeSet<String> {
+        &self.disabled_tags
+    }
+
+    /// 因标签被禁用而跳过的词条
+    pub fn skipped_entries(&self) -> &[SkippedEntry] {
+        &self.skipped
+    }
+
+    fn record_skipped(&mut self, source_id: String, trigger: Trigger, tags: Vec<String>) {
+        let source_name = self.source_names.get(&source_id).cloned();
+        self.skipped.push(SkippedEntry {
+            source_id,
+            source_name,
+            trigger,
+            tags,
+        });
+    }
+
+    /// 添加多个词条，并记录来源的可读名称（用于战斗日志）
+    pub fn add_entries_with_named_source(
+        &mut self,
+        entries: Vec<Entry>,
+        source_id: String,
+        source_name: String,
+    ) {
+        self.source_names.insert(source_id.clone(), source_name);
+        self.add_entries_with_source(entries, source_id);
+    }
+
+    /// 来源ID对应的可读名称
+    pub fn source_name(&self, source_id: &str) -> Option<&str> {
+        self.source_names.get(source_id).map(String::as_str)
+    }
+
+    /// 全部来源ID及其可读名称
+    pub fn source_names(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.source_names
+            .iter()
+            .map(|(id, name)| (id.as_str(), name.as_str()))
+    }
+
+    /// 已加入的词条及其来源ID（按加入顺序）
+    pub fn entries_in_order(&self) -> Vec<(&str, &Entry)> {
+        let mut entries: Vec<&EntryWithSource> =
+            self.entries_by_trigger.values().flatten().collect();
+        entries.sort_by_key(|entry| entry.entry_order);
+        entries
+            .into_iter()
+            .map(|entry| (entry.source_id.as_str(), &entry.entry))
+            .collect()
+    }
+
     /// 添加词条（带来源ID）
     pub fn add_entry_with_source(&mut self, entry: Entry, source_id: String) {
+        let tags = entry.matching_tags(&self.disabled_tags);
+        if !tags.is_empty() {
+            self.record_skipped(source_id, entry.trigger, tags);
+            return;
+        }
         let entry_order = self.next_entry_order;
         self.next_entry_order += 1;
         let entry_id = format!("{}#{}", source_id, entry_order);
@@ -84,6 +195,13 @@ impl EntryExecutor {
         }
     }
 
+    /// 移除来源ID以指定前缀开头的词条（如切换武技时移除 `attack_skill:`）
+    pub fn remove_entries_by_source_prefix(&mut self, prefix: &str) {
+        for entries in self.entries_by_trigger.values_mut() {
+            entries.retain(|entry| !entry.source_id.starts_with(prefix));
+        }
+    }
+
     /// 触发指定时机的词条（修行时）
     pub fn trigger_cultivation(
         &mut self,
@@ -216,6 +334,7 @@ impl EntryExecutor {
             self_panel: original_panel.clone(),
             opponent_panel: opponent_panel.cloned(),
             attack_result: battle_context.attack_result,
+            environment: battle_context.environment.clone(),
         };
 
         // 收集所有修改器
@@ -269,7 +388,7 @@ impl EntryExecutor {
                     Effect::ModifyPercentage {
                         target, operation, ..
                     } => {
-                        let current_value = Self::get_panel_value(original_panel, *target);
+                        let current_value = Self::panel_value(original_panel, *target);
                         match operation {
                             Operation::Multiply => calculated_value,
                             _ => current_value * calculated_value,
@@ -281,8 +400,8 @@ impl EntryExecutor {
                 // 创建修改器（不应用）
                 AttributeModifier::from_effect_with_value(effect, adjusted_value)
             }
-            Effect::ExtraAttack { .. } => {
-                // 额外攻击需要特殊处理，不在这里修改面板
+            Effect::ExtraAttack { .. } | Effect::SealSkill { .. } => {
+                // 额外攻击、封招需要特殊处理，不在这里修改面板
                 None
             }
         }
@@ -325,7 +444,7 @@ impl EntryExecutor {
                     Effect::ModifyPercentage {
                         target, operation, ..
                     } => {
-                        let current_value = Self::get_panel_value(original_panel, *target);
+                        let current_value = Self::panel_value(original_panel, *target);
                         match operation {
                             Operation::Multiply => calculated_value,
                             _ => current_value * calculated_value,
@@ -337,14 +456,15 @@ impl EntryExecutor {
                 // 创建修改器（不应用）
                 AttributeModifier::from_effect_with_value(effect, adjusted_value)
             }
-            Effect::ExtraAttack { .. } => {
-                // 额外攻击需要特殊处理，不在这里修改面板
+            Effect::ExtraAttack { .. } | Effect::SealSkill { .. } => {
+                // 额外攻击、封招需要特殊处理，不在这里修改面板
                 None
             }
         }
     }
 
-    fn get_panel_value(panel: &CharacterPanel, target: AttributeTarget) -> f64 {
+    /// 面板上指定属性的当前值（修行相关的增量属性为 0）
+    pub fn panel_value(panel: &CharacterPanel, target: AttributeTarget) -> f64 {
         match target {
             AttributeTarget::Comprehension => panel.three_d.comprehension as f64,
             AttributeTarget::BoneStructure => panel.three_d.bone_structure as f64,
@@ -360,6 +480,7 @@ impl EntryExecutor {
             AttributeTarget::AttackSpeed => panel.attack_speed,
             AttributeTarget::QiRecoveryRate => panel.qi_recovery_rate,
             AttributeTarget::ChargeTime => panel.charge_time,
+            AttributeTarget::Momentum => panel.momentum,
             AttributeTarget::DamageBonus => panel.damage_bonus,
             AttributeTarget::DamageReduction => panel.damage_reduction,
             AttributeTarget::MaxDamageReduction => panel.max_damage_reduction,
@@ -393,16 +514,20 @@ impl EntryExecutor {
 
         // 添加特性词条
         for trait_ in traits {
-            executor
-                .add_entries_with_source(trait_.entries.clone(), format!("trait:{}", trait_.id));
+            executor.add_entries_with_named_source(
+                trait_.entries.clone(),
+                format!("trait:{}", trait_.id),
+                trait_.name.clone(),
+            );
         }
 
         // 添加内功当前境界的词条
         if let Some(internal) = internal {
             if let Some(realm) = internal.current_realm() {
-                executor.add_entries_with_source(
+                executor.add_entries_with_named_source(
                     realm.entries.clone(),
                     format!("internal:{}", internal.manual.id),
+                    realm_source_name(&internal.manual.name, realm.level),
                 );
             }
         }
@@ -410,9 +535,10 @@ impl EntryExecutor {
         // 添加攻击武技当前境界的词条
         if let Some(attack_skill) = attack_skill {
             if let Some(realm) = attack_skill.current_realm() {
-                executor.add_entries_with_source(
+                executor.add_entries_with_named_source(
                     realm.entries.clone(),
                     format!("attack_skill:{}", attack_skill.manual.id),
+                    realm_source_name(&attack_skill.manual.name, realm.level),
                 );
             }
         }
@@ -420,9 +546,10 @@ impl EntryExecutor {
         // 添加防御武技当前境界的词条
         if let Some(defense_skill) = defense_skill {
             if let Some(realm) = defense_skill.current_realm() {
-                executor.add_entries_with_source(
+                executor.add_entries_with_named_source(
                     realm.entries.clone(),
                     format!("defense_skill:{}", defense_skill.manual.id),
+                    realm_source_name(&defense_skill.manual.name, realm.level),
                 );
             }
         }
@@ -449,8 +576,11 @@ impl EntryExecutor {
 
         // 添加特性词条
         for trait_ in traits {
-            executor
-                .add_entries_with_source(trait_.entries.clone(), format!("trait:{}", trait_.id));
+            executor.add_entries_with_named_source(
+                trait_.entries.clone(),
+                format!("trait:{}", trait_.id),
+                trait_.name.clone(),
+            );
         }
 
         // 添加内功当前境界的词条（根据角色拥有的等级）
@@ -459,9 +589,10 @@ impl EntryExecutor {
                 if let Some((level, _)) = panel.get_internal_level_exp(internal_id) {
                     if level > 0 && level <= 5 {
                         if let Some(realm) = internal.realm_at_level(level) {
-                            executor.add_entries_with_source(
+                            executor.add_entries_with_named_source(
                                 realm.entries.clone(),
                                 format!("internal:{}", internal.manual.id),
+                                realm_source_name(&internal.manual.name, level),
                             );
                         }
                     }
@@ -475,9 +606,10 @@ impl EntryExecutor {
                 if let Some((level, _)) = panel.get_attack_skill_level_exp(skill_id) {
                     if level > 0 && level <= 5 {
                         if let Some(realm) = attack_skill.realm_at_level(level) {
-                            executor.add_entries_with_source(
+                            executor.add_entries_with_named_source(
                                 realm.entries.clone(),
                                 format!("attack_skill:{}", attack_skill.manual.id),
+                                realm_source_name(&attack_skill.manual.name, level),
                             );
                         }
                     }
@@ -491,9 +623,10 @@ impl EntryExecutor {
                 if let Some((level, _)) = panel.get_defense_skill_level_exp(skill_id) {
                     if level > 0 && level <= 5 {
                         if let Some(realm) = defense_skill.realm_at_level(level) {
-                            executor.add_entries_with_source(
+                            executor.add_entries_with_named_source(
                                 realm.entries.clone(),
                                 format!("defense_skill:{}", defense_skill.manual.id),
+                                realm_source_name(&defense_skill.manual.name, level),
                             );
                         }
                     }
@@ -505,6 +638,11 @@ impl EntryExecutor {
     }
 }
 
+/// 功法词条来源名称：功法名与境界
+fn realm_source_name(manual_name: &str, level: u32) -> String {
+    format!("{}·第{}重", manual_name, level)
+}
+
 impl Default for EntryExecutor {
     fn default() -> Self {
         Self::new()
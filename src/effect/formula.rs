@@ -1,6 +1,10 @@
 use super::condition::AttackResult;
+use super::trigger::Trigger;
 use crate::character::panel::CharacterPanel;
+use crate::error::{WushenError, WushenResult};
 use meval::{Context, Expr};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 /// 公式系统
 /// 支持根据角色面板、对方面板、攻防结果计算表达式
 use std::str::FromStr;
@@ -21,6 +25,8 @@ pub struct BattleFormulaContext {
     pub opponent_panel: Option<CharacterPanel>,
     /// 攻击结果（可选，用于攻击后/防御后）
     pub attack_result: Option<AttackResult>,
+    /// 战斗环境变量（由剧情/奇遇战斗声明，公式中以 `env_` 前缀引用，如 `env_rain`）
+    pub environment: BTreeMap<String, f64>,
 }
 
 /// 公式计算器
@@ -31,7 +37,7 @@ impl FormulaCalculator {
     pub fn evaluate_cultivation(
         formula: &str,
         context: &CultivationFormulaContext,
-    ) -> Result<f64, String> {
+    ) -> WushenResult<f64> {
         let mut ctx = Context::new();
         add_common_functions(&mut ctx);
         Self::add_panel_to_context(&mut ctx, "self", &context.self_panel);
@@ -39,7 +45,7 @@ impl FormulaCalculator {
     }
 
     /// 计算公式值（战斗时）
-    pub fn evaluate_battle(formula: &str, context: &BattleFormulaContext) -> Result<f64, String> {
+    pub fn evaluate_battle(formula: &str, context: &BattleFormulaContext) -> WushenResult<f64> {
         let mut ctx = Context::new();
         add_common_functions(&mut ctx);
 
@@ -53,16 +59,15 @@ impl FormulaCalculator {
 
         // 添加攻击结果（如果存在）
         if let Some(ref result) = context.attack_result {
-            ctx.var("attack_total_output", result.total_output)
-                .var("attack_total_defense", result.total_defense)
-                .var("attack_reduced_output", result.reduced_output)
-                .var("attack_hp_damage", result.hp_damage)
-                .var("attack_attacker_qi_consumed", result.attacker_qi_consumed)
-                .var("attack_defender_qi_consumed", result.defender_qi_consumed)
-                .var(
-                    "attack_broke_qi_defense",
-                    if result.broke_qi_defense { 1.0 } else { 0.0 },
-                );
+            for (name, _, value) in ATTACK_RESULT_VARIABLES {
+                ctx.var(name, value(result));
+            }
+        }
+
+        // 添加环境变量：战斗未声明的环境变量按 0 计算
+        for name in environment_references(formula) {
+            let value = context.environment.get(name).copied().unwrap_or(0.0);
+            ctx.var(format!("env_{}", name), value);
         }
 
         Self::evaluate_with_context(formula, ctx)
@@ -70,65 +75,267 @@ impl FormulaCalculator {
 
     /// 将角色面板添加到上下文
     fn add_panel_to_context(ctx: &mut Context, prefix: &str, panel: &CharacterPanel) {
-        // 基本三维
-        ctx.var(format!("{}_x", prefix), panel.x())
-            .var(format!("{}_y", prefix), panel.y())
-            .var(format!("{}_z", prefix), panel.z())
-            .var(format!("{}_a", prefix), panel.a())
-            .var(format!("{}_comprehension", prefix), panel.x())
-            .var(format!("{}_bone_structure", prefix), panel.y())
-            .var(format!("{}_physique", prefix), panel.z())
-            .var(format!("{}_martial_arts_attainment", prefix), panel.a())
-            // 战斗属性
-            .var(format!("{}_max_hp", prefix), panel.max_hp)
-            .var(format!("{}_hp", prefix), panel.hp)
-            .var(format!("{}_max_qi", prefix), panel.max_qi)
-            .var(format!("{}_qi", prefix), panel.qi)
-            .var(format!("{}_base_attack", prefix), panel.base_attack)
-            .var(format!("{}_base_defense", prefix), panel.base_defense)
-            .var(
-                format!("{}_max_qi_output_rate", prefix),
-                panel.max_qi_output_rate,
-            )
-            .var(format!("{}_qi_output_rate", prefix), panel.qi_output_rate)
-            .var(format!("{}_damage_bonus", prefix), panel.damage_bonus)
-            .var(
-                format!("{}_damage_reduction", prefix),
-                panel.damage_reduction,
-            )
-            .var(
-                format!("{}_max_damage_reduction", prefix),
-                panel.max_damage_reduction,
-            )
-            // 武技相关属性
-            .var(format!("{}_power", prefix), panel.power)
-            .var(format!("{}_defense_power", prefix), panel.defense_power)
-            .var(format!("{}_qi_quality", prefix), panel.qi_quality)
-            .var(format!("{}_attack_speed", prefix), panel.attack_speed)
-            .var(
-                format!("{}_qi_recovery_rate", prefix),
-                panel.qi_recovery_rate,
-            )
-            .var(format!("{}_charge_time", prefix), panel.charge_time);
+        for (suffix, _, value) in PANEL_VARIABLES {
+            ctx.var(format!("{}_{}", prefix, suffix), value(panel));
+        }
     }
 
     /// 使用上下文计算公式
-    fn evaluate_with_context(formula: &str, ctx: Context) -> Result<f64, String> {
+    fn evaluate_with_context(formula: &str, ctx: Context) -> WushenResult<f64> {
         // 规范化公式：将 Python 风格的 ** 转换为 meval 支持的 ^
         let normalized = formula.trim().replace("**", "^");
 
         let expr = Expr::from_str(&normalized).map_err(|e| {
-            format!(
+            WushenError::FormulaError(format!(
                 "公式解析错误: {} (原始公式: '{}', 规范化公式: '{}')",
                 e, formula, normalized
-            )
+            ))
         })?;
 
         expr.eval_with_context(ctx)
-            .map_err(|e| format!("公式计算错误: {}", e))
+            .map_err(|e| WushenError::FormulaError(format!("公式计算错误: {}", e)))
+    }
+
+    /// 公式可用的变量目录（供编辑器自动补全与校验）
+    pub fn variable_catalog(kind: FormulaContextKind) -> Vec<FormulaVariable> {
+        let always = FormulaAvailability::Always;
+        match kind {
+            FormulaContextKind::ManualExp => MANUAL_EXP_VARIABLES
+                .iter()
+                .map(|(name, description)| FormulaVariable::new(name, description, always))
+                .collect(),
+            FormulaContextKind::Cultivation => panel_variables("self", "自身", always).collect(),
+            FormulaContextKind::Battle => panel_variables("self", "自身", always)
+                .chain(panel_variables("opponent", "对手", always))
+                .chain(
+                    ATTACK_RESULT_VARIABLES
+                        .iter()
+                        .map(|(name, description, _)| {
+                            FormulaVariable::new(
+                                name,
+                                description,
+                                FormulaAvailability::AfterAttack,
+                            )
+                        }),
+                )
+                .chain(std::iter::once(FormulaVariable::new(
+                    "env_<名称>",
+                    "战斗环境变量（由剧情/奇遇战斗声明，未声明时按 0 计算）",
+                    FormulaAvailability::Declared,
+                )))
+                .collect(),
+        }
+    }
+
+    /// 公式中引用、但在该上下文中不存在的标识符（函数名与常量 pi、e 除外，按出现顺序去重）
+    pub fn unknown_identifiers(formula: &str, kind: FormulaContextKind) -> Vec<String> {
+        let catalog = Self::variable_catalog(kind);
+        let mut unknown: Vec<String> = Vec::new();
+        for name in identifiers(formula) {
+            let known = catalog.iter().any(|variable| variable.name == name)
+                || FORMULA_CONSTANTS.contains(&name)
+                || (kind == FormulaContextKind::Battle && name.starts_with("env_"));
+            if !known && !unknown.iter().any(|u| u == name) {
+                unknown.push(name.to_string());
+            }
+        }
+        unknown
+    }
+}
+
+/// 公式所在的上下文
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormulaContextKind {
+    /// 功法的修行经验公式（x、y、z、a）
+    ManualExp,
+    /// 修行时触发的词条
+    Cultivation,
+    /// 战斗时触发的词条、绝招与反击
+    Battle,
+}
+
+impl FormulaContextKind {
+    /// 词条触发时机对应的公式上下文
+    pub fn for_trigger(trigger: Trigger) -> Self {
+        match trigger {
+            Trigger::GameStart
+            | Trigger::TraitAcquired
+            | Trigger::ReadingManual
+            | Trigger::CultivatingInternal
+            | Trigger::CultivatingAttack
+            | Trigger::CultivatingDefense
+            | Trigger::InternalLevelUp
+            | Trigger::AttackLevelUp
+            | Trigger::DefenseLevelUp
+            | Trigger::SwitchingCultivation => FormulaContextKind::Cultivation,
+            Trigger::BattleStart
+            | Trigger::BeforeAttack
+            | Trigger::AfterAttack
+            | Trigger::BeforeDefense
+            | Trigger::AfterDefense
+            | Trigger::RoundEnd => FormulaContextKind::Battle,
+        }
+    }
+}
+
+/// 变量的可用范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormulaAvailability {
+    /// 该上下文中始终可用
+    Always,
+    /// 仅攻击后与防御后触发的词条（其他时机计算失败）
+    AfterAttack,
+    /// 由战斗按名称声明
+    Declared,
+}
+
+/// 公式变量说明
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FormulaVariable {
+    pub name: String,
+    pub description: String,
+    pub availability: FormulaAvailability,
+}
+
+impl FormulaVariable {
+    fn new(name: &str, description: &str, availability: FormulaAvailability) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            availability,
+        }
     }
 }
 
+/// 变量表项：名称、说明、取值
+type VariableSpec<T> = (&'static str, &'static str, fn(&T) -> f64);
+
+/// 面板变量（名称为后缀），公式中以 `self_` / `opponent_` 为前缀引用
+const PANEL_VARIABLES: [VariableSpec<CharacterPanel>; 26] = [
+    // 基本三维
+    ("x", "悟性", |p| p.x()),
+    ("y", "根骨", |p| p.y()),
+    ("z", "体魄", |p| p.z()),
+    ("a", "武学素养", |p| p.a()),
+    ("comprehension", "悟性", |p| p.x()),
+    ("bone_structure", "根骨", |p| p.y()),
+    ("physique", "体魄", |p| p.z()),
+    ("martial_arts_attainment", "武学素养", |p| p.a()),
+    // 战斗属性
+    ("max_hp", "生命值上限", |p| p.max_hp),
+    ("hp", "生命值", |p| p.hp),
+    ("max_qi", "内息上限", |p| p.max_qi),
+    ("qi", "内息", |p| p.qi),
+    ("base_attack", "基础攻击力", |p| p.base_attack),
+    ("base_defense", "基础防御力", |p| p.base_defense),
+    ("max_qi_output_rate", "最大内息输出", |p| {
+        p.max_qi_output_rate
+    }),
+    ("qi_output_rate", "内息输出", |p| p.qi_output_rate),
+    ("damage_bonus", "增伤", |p| p.damage_bonus),
+    ("damage_reduction", "减伤", |p| p.damage_reduction),
+    ("max_damage_reduction", "减伤上限", |p| {
+        p.max_damage_reduction
+    }),
+    // 武技相关属性
+    ("power", "武技威能", |p| p.power),
+    ("defense_power", "守御", |p| p.defense_power),
+    ("qi_quality", "内息质量", |p| p.qi_quality),
+    ("attack_speed", "出手速度", |p| p.attack_speed),
+    ("qi_recovery_rate", "回气量", |p| p.qi_recovery_rate),
+    ("charge_time", "蓄力时间", |p| p.charge_time),
+    // 气势（仅战斗中不为 0）
+    ("momentum", "气势", |p| p.momentum),
+];
+
+/// 攻击结果变量（仅攻击后/防御后）
+const ATTACK_RESULT_VARIABLES: [VariableSpec<AttackResult>; 7] = [
+    ("attack_total_output", "攻击总输出", |r| r.total_output),
+    ("attack_total_defense", "攻击总防御", |r| {
+        r.total_defense
+    }),
+    ("attack_reduced_output", "减伤后的输出", |r| {
+        r.reduced_output
+    }),
+    ("attack_hp_damage", "造成的生命伤害", |r| r.hp_damage),
+    (
+        "attack_attacker_qi_consumed",
+        "攻击方内息消耗",
+        |r| r.attacker_qi_consumed,
+    ),
+    (
+        "attack_defender_qi_consumed",
+        "防守方内息消耗",
+        |r| r.defender_qi_consumed,
+    ),
+    (
+        "attack_broke_qi_defense",
+        "是否击破内息防御（1=是，0=否）",
+        |r| {
+            if r.broke_qi_defense {
+                1.0
+            } else {
+                0.0
+            }
+        },
+    ),
+];
+
+/// 功法修行经验公式的变量
+const MANUAL_EXP_VARIABLES: [(&str, &str); 5] = [
+    ("x", "悟性"),
+    ("y", "根骨"),
+    ("z", "体魄"),
+    ("a", "武学素养"),
+    ("A", "武学素养（a 的别名）"),
+];
+
+/// 公式中可直接使用的常量
+const FORMULA_CONSTANTS: [&str; 2] = ["pi", "e"];
+
+fn panel_variables(
+    prefix: &'static str,
+    owner: &'static str,
+    availability: FormulaAvailability,
+) -> impl Iterator<Item = FormulaVariable> {
+    PANEL_VARIABLES.iter().map(move |(suffix, description, _)| {
+        FormulaVariable::new(
+            &format!("{}_{}", prefix, suffix),
+            &format!("{}{}", owner, description),
+            availability,
+        )
+    })
+}
+
+/// 公式中作为变量引用的标识符（跳过数字与函数调用）
+fn identifiers(formula: &str) -> Vec<&str> {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut identifiers = Vec::new();
+    let mut rest = formula;
+    while let Some(start) = rest.find(is_ident) {
+        let tail = &rest[start..];
+        let len = tail.find(|c: char| !is_ident(c)).unwrap_or(tail.len());
+        let (token, after) = tail.split_at(len);
+        let is_number = token.starts_with(|c: char| c.is_ascii_digit());
+        let is_call = after.trim_start().starts_with('(');
+        if !is_number && !is_call {
+            identifiers.push(token);
+        }
+        rest = after;
+    }
+    identifiers
+}
+
+/// 公式中引用的环境变量名（去掉 `env_` 前缀）
+pub fn environment_references(formula: &str) -> impl Iterator<Item = &str> {
+    formula
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .filter_map(|token| token.strip_prefix("env_"))
+        .filter(|name| !name.is_empty())
+}
+
 fn add_common_functions(ctx: &mut Context) {
     ctx.func2("pow", f64::powf);
 }
@@ -165,6 +372,7 @@ mod tests {
             self_panel: self_panel.clone(),
             opponent_panel: Some(opponent_panel),
             attack_result: None,
+            environment: BTreeMap::new(),
         };
 
         // 测试使用对方面板
@@ -191,6 +399,7 @@ mod tests {
             self_panel: panel,
             opponent_panel: None,
             attack_result: Some(attack_result),
+            environment: BTreeMap::new(),
         };
 
         // 测试使用攻击结果
@@ -209,6 +418,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_environment_formula() {
+        let panel = CharacterPanel::new("测试".to_string(), ThreeDimensional::new(10, 20, 30));
+        let mut context = BattleFormulaContext {
+            self_panel: panel,
+            opponent_panel: None,
+            attack_result: None,
+            environment: BTreeMap::from([("rain".to_string(), 1.0)]),
+        };
+        let formula = "self_x * (1 + env_rain * 0.5 + env_night)";
+
+        assert_eq!(
+            FormulaCalculator::evaluate_battle(formula, &context).unwrap(),
+            15.0
+        );
+        assert_eq!(
+            environment_references(formula).collect::<Vec<_>>(),
+            ["rain", "night"]
+        );
+
+        // 未声明的环境变量按 0 计算
+        context.environment.clear();
+        assert_eq!(
+            FormulaCalculator::evaluate_battle(formula, &context).unwrap(),
+            10.0
+        );
+    }
+
+    #[test]
+    fn test_variable_catalog_matches_evaluation() {
+        let panel = CharacterPanel::new("测试".to_string(), ThreeDimensional::new(2, 3, 4));
+        let context = BattleFormulaContext {
+            self_panel: panel.clone(),
+            opponent_panel: Some(panel.clone()),
+            attack_result: Some(AttackResult {
+                total_output: 1.0,
+                total_defense: 1.0,
+                reduced_output: 1.0,
+                hp_damage: 1.0,
+                attacker_qi_consumed: 1.0,
+                defender_qi_consumed: 1.0,
+                broke_qi_defense: false,
+            }),
+            environment: BTreeMap::new(),
+        };
+        let catalog = FormulaCalculator::variable_catalog(FormulaContextKind::Battle);
+        for variable in &catalog {
+            if variable.availability != FormulaAvailability::Declared {
+                FormulaCalculator::evaluate_battle(&variable.name, &context).unwrap();
+            }
+        }
+        let hp = catalog.iter().find(|v| v.name == "opponent_hp").unwrap();
+        assert_eq!(hp.description, "对手生命值");
+        let cultivation = CultivationFormulaContext { self_panel: panel };
+        for variable in FormulaCalculator::variable_catalog(FormulaContextKind::Cultivation) {
+            FormulaCalculator::evaluate_cultivation(&variable.name, &cultivation).unwrap();
+        }
+
+        let formula = "max(self_hp, opponent_hp * 0.5) + self_hpp * 1e3 + pi + env_rain";
+        assert_eq!(
+            FormulaCalculator::unknown_identifiers(formula, FormulaContextKind::Battle),
+            ["self_hpp"]
+        );
+        assert_eq!(
+            FormulaCalculator::unknown_identifiers(formula, FormulaContextKind::Cultivation),
+            ["opponent_hp", "self_hpp", "env_rain"]
+        );
+        assert!(FormulaCalculator::unknown_identifiers(
+            "x * A + pow(y, 2)",
+            FormulaContextKind::ManualExp
+        )
+        .is_empty());
+    }
+
     #[test]
     fn test_power_operator() {
         // 测试幂运算：** 应该转换为 ^
@@ -1,11 +1,30 @@
 #![allow(clippy::module_inception)]
 
-pub mod battle_record_template;
-pub mod condition;
-pub mod effect;
-pub mod entry;
-pub mod executor;
-pub mod formula;
-pub mod modifier;
-pub mod parser;
-pub mod trigger;
+pub(crate) mod battle_record_template;
+pub(crate) mod condition;
+pub(crate) mod dry_run;
+pub(crate) mod effect;
+pub(crate) mod entry;
+pub(crate) mod executor;
+pub(crate) mod formula;
+pub(crate) mod modifier;
+pub(crate) mod parser;
+pub(crate) mod trigger;
+
+// 重新导出常用类型
+pub use battle_record_template::BattleRecordTemplate;
+pub use condition::{
+    AttackResult, AttributeType, BattleAttributeType, BattleCondition, BattleContext, ComparisonOp,
+    Condition, CultivationCondition, CultivationContext,
+};
+pub use dry_run::{EffectDryRun, EntryDryRun};
+pub use effect::{AttributeTarget, Effect, FormulaValue, Operation, PanelTarget, SkillSlot};
+pub use entry::Entry;
+pub use executor::{EntryEffect, EntryExecutor, SkippedEntry};
+pub use formula::{
+    BattleFormulaContext, CultivationFormulaContext, FormulaAvailability, FormulaCalculator,
+    FormulaContextKind, FormulaVariable,
+};
+pub use modifier::AttributeModifier;
+pub use parser::{parse_entries, parse_entry};
+pub use trigger::Trigger;
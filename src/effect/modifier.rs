@@ -2,7 +2,7 @@ use super::effect::{AttributeTarget, Effect, Operation, PanelTarget};
 /// 属性修改器系统
 /// 支持增加/减少/设置、突破上限
 /// 注意：战斗时的所有效果都是临时的，不会改变基础面板；只有修行时能改变基础面板
-use crate::character::panel::CharacterPanel;
+use crate::character::panel::{CharacterPanel, MIN_MAX_HP};
 
 /// 属性修改器
 ///
@@ -184,10 +184,13 @@ impl AttributeModifier {
                 let limit = self
                     .get_attribute_limit(self.target, panel)
                     .unwrap_or(f64::INFINITY);
-                panel.three_d.physique = new_value.max(0.0).min(limit) as u32;
+                panel.set_physique(new_value.max(0.0).min(limit) as u32);
             }
             AttributeTarget::MaxHp => {
-                panel.max_hp = self.apply_operation(panel.max_hp).max(0.0);
+                let previous_max = panel.max_hp;
+                panel.max_hp = self.apply_operation(panel.max_hp).max(MIN_MAX_HP);
+                let delta = panel.max_hp - previous_max;
+                panel.hp = (panel.hp + delta).max(0.0).min(panel.max_hp);
             }
             AttributeTarget::Hp => {
                 panel.hp = self.apply_operation(panel.hp).max(0.0).min(panel.max_hp);
@@ -242,6 +245,9 @@ impl AttributeModifier {
             AttributeTarget::ChargeTime => {
                 panel.charge_time = self.apply_operation(panel.charge_time).max(100.0);
             }
+            AttributeTarget::Momentum => {
+                panel.momentum = self.apply_operation(panel.momentum).max(0.0);
+            }
             AttributeTarget::DamageBonus => {
                 panel.damage_bonus = self.apply_operation(panel.damage_bonus).max(0.0);
             }
@@ -319,4 +325,33 @@ mod tests {
         modifier.apply_to_panel(&mut panel);
         assert_eq!(panel.base_attack, 120.0);
     }
+
+    #[test]
+    fn test_max_hp_change_shifts_current_hp() {
+        let three_d = ThreeDimensional::new(10, 10, 10);
+        let mut panel = CharacterPanel::new("测试".to_string(), three_d);
+        panel.hp = 300.0;
+        let max_hp = |value: f64, operation: Operation| {
+            AttributeModifier::new(
+                AttributeTarget::MaxHp,
+                value,
+                operation,
+                PanelTarget::Own,
+                false,
+                false,
+            )
+        };
+
+        // 提高上限：当前生命同步增加
+        max_hp(200.0, Operation::Add).apply_to_panel(&mut panel);
+        assert_eq!((panel.max_hp, panel.hp), (1200.0, 500.0));
+
+        // 降低上限：当前生命同步减少，仍低于新上限
+        max_hp(300.0, Operation::Subtract).apply_to_panel(&mut panel);
+        assert_eq!((panel.max_hp, panel.hp), (900.0, 200.0));
+
+        // 上限不低于下限，当前生命不低于 0
+        max_hp(0.0, Operation::Set).apply_to_panel(&mut panel);
+        assert_eq!((panel.max_hp, panel.hp), (MIN_MAX_HP, 0.0));
+    }
 }
@@ -1,17 +1,19 @@
+use crate::effect::entry::Entry;
 /// JSON 解析器
 /// 将 JSON 数据转换为 Rust 结构
-use crate::effect::entry::Entry;
+use crate::error::{WushenError, WushenResult};
 
 /// 解析词条列表
-pub fn parse_entries(json: &str) -> Result<Vec<Entry>, String> {
-    let entries: Vec<Entry> =
-        serde_json::from_str(json).map_err(|e| format!("解析词条失败: {}", e))?;
+pub fn parse_entries(json: &str) -> WushenResult<Vec<Entry>> {
+    let entries: Vec<Entry> = serde_json::from_str(json)
+        .map_err(|e| WushenError::ParseError(format!("解析词条失败: {}", e)))?;
     Ok(entries)
 }
 
 /// 解析单个词条
-pub fn parse_entry(json: &str) -> Result<Entry, String> {
-    let entry: Entry = serde_json::from_str(json).map_err(|e| format!("解析词条失败: {}", e))?;
+pub fn parse_entry(json: &str) -> WushenResult<Entry> {
+    let entry: Entry = serde_json::from_str(json)
+        .map_err(|e| WushenError::ParseError(format!("解析词条失败: {}", e)))?;
     Ok(entry)
 }
 
@@ -44,4 +46,20 @@ mod tests {
         assert_eq!(entry.trigger, Trigger::ReadingManual);
         assert_eq!(entry.effects.len(), 1);
     }
+
+    #[test]
+    fn empty_tags_are_rejected() {
+        let entry = |tags: &str| {
+            parse_entry(&format!(
+                r#"{{"trigger":"battle_start","effects":[],"tags":{}}}"#,
+                tags
+            ))
+        };
+        assert_eq!(entry(r#"["healing"]"#).unwrap().tags, ["healing"]);
+        assert!(entry(r#"["healing", ""]"#)
+            .unwrap_err()
+            .message()
+            .contains("词条标签不能为空"));
+        assert!(entry(r#"[" "]"#).is_err());
+    }
 }
@@ -0,0 +1,118 @@
+/// 错误类型
+/// 各变体携带面向玩家的中文信息；前端按 `code` 区分错误类别、显示本地化文案，
+/// 不必匹配中文文本。序列化为 `{"code": "not_found", "message": "..."}`
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 核心错误
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "code", content = "message", rename_all = "snake_case")]
+pub enum WushenError {
+    /// 数据（JSON、存档、数据包）无法解析或序列化
+    ParseError(String),
+    /// 引用的条目（功法、特性、事件、存档等）不存在
+    NotFound(String),
+    /// 当前状态不允许该操作（如游戏尚未初始化、不在对应阶段）
+    InvalidState(String),
+    /// 操作的前置条件不满足（如行动点或资源不足、未拥有功法）
+    ConditionNotMet(String),
+    /// 公式解析或计算失败
+    FormulaError(String),
+    /// 数据校验失败
+    Invalid(String),
+    /// 其他错误（无法归入以上类别）
+    Other(String),
+}
+
+/// 核心结果类型
+pub type WushenResult<T> = Result<T, WushenError>;
+
+impl WushenError {
+    /// 机器可读的错误码
+    pub fn code(&self) -> &'static str {
+        match self {
+            WushenError::ParseError(_) => "parse_error",
+            WushenError::NotFound(_) => "not_found",
+            WushenError::InvalidState(_) => "invalid_state",
+            WushenError::ConditionNotMet(_) => "condition_not_met",
+            WushenError::FormulaError(_) => "formula_error",
+            WushenError::Invalid(_) => "invalid",
+            WushenError::Other(_) => "other",
+        }
+    }
+
+    /// 错误信息
+    pub fn message(&self) -> &str {
+        match self {
+            WushenError::ParseError(message)
+            | WushenError::NotFound(message)
+            | WushenError::InvalidState(message)
+            | WushenError::ConditionNotMet(message)
+            | WushenError::FormulaError(message)
+            | WushenError::Invalid(message)
+            | WushenError::Other(message) => message,
+        }
+    }
+
+    /// 在信息前加上上下文，保留错误类别
+    pub fn context(self, prefix: &str) -> Self {
+        self.map_message(|message| format!("{}: {}", prefix, message))
+    }
+
+    fn map_message(self, f: impl FnOnce(String) -> String) -> Self {
+        match self {
+            WushenError::ParseError(m) => WushenError::ParseError(f(m)),
+            WushenError::NotFound(m) => WushenError::NotFound(f(m)),
+            WushenError::InvalidState(m) => WushenError::InvalidState(f(m)),
+            WushenError::ConditionNotMet(m) => WushenError::ConditionNotMet(f(m)),
+            WushenError::FormulaError(m) => WushenError::FormulaError(f(m)),
+            WushenError::Invalid(m) => WushenError::Invalid(f(m)),
+            WushenError::Other(m) => WushenError::Other(f(m)),
+        }
+    }
+}
+
+impl fmt::Display for WushenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for WushenError {}
+
+impl From<serde_json::Error> for WushenError {
+    fn from(error: serde_json::Error) -> Self {
+        WushenError::ParseError(error.to_string())
+    }
+}
+
+/// 仍以字符串传递错误的调用方可直接用 `?` 转换（只保留信息）
+impl From<WushenError> for String {
+    fn from(error: WushenError) -> Self {
+        match error {
+            WushenError::ParseError(m)
+            | WushenError::NotFound(m)
+            | WushenError::InvalidState(m)
+            | WushenError::ConditionNotMet(m)
+            | WushenError::FormulaError(m)
+            | WushenError::Invalid(m)
+            | WushenError::Other(m) => m,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_serialize_with_codes() {
+        let error = WushenError::NotFound("内功 吐纳 不存在".to_string()).context("修行失败");
+        assert_eq!(error.code(), "not_found");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({"code": "not_found", "message": "修行失败: 内功 吐纳 不存在"})
+        );
+        assert_eq!(String::from(error), "修行失败: 内功 吐纳 不存在");
+    }
+}
@@ -0,0 +1,169 @@
+use crate::character::panel::CharacterPanel;
+use crate::effect::effect::Operation;
+/// 选项代价的校验、判定与扣除
+use crate::error::{WushenError, WushenResult};
+use crate::event::reward::{apply_attribute_reward, attribute_value};
+use crate::event::types::{OptionCost, RewardTarget};
+
+impl OptionCost {
+    /// 校验代价数据
+    pub fn validate(&self) -> WushenResult<()> {
+        match self {
+            OptionCost::Qi { amount }
+            | OptionCost::Hp { amount }
+            | OptionCost::Attribute { amount, .. } => {
+                if !amount.is_finite() || *amount <= 0.0 {
+                    return Err(WushenError::Invalid(format!(
+                        "代价数值必须为正数，当前为 {}",
+                        amount
+                    )));
+                }
+            }
+            OptionCost::Trait { id }
+            | OptionCost::Internal { id }
+            | OptionCost::AttackSkill { id }
+            | OptionCost::DefenseSkill { id } => {
+                if id.is_empty() {
+                    return Err(WushenError::Invalid("代价缺少 ID".to_string()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 角色当前能否支付该代价
+    pub fn is_affordable(&self, panel: &CharacterPanel) -> bool {
+        match self {
+            OptionCost::Qi { amount } => panel.qi >= *amount,
+            OptionCost::Hp { amount } => panel.hp > *amount,
+            OptionCost::Attribute { target, amount } => attribute_value(panel, *target) >= *amount,
+            OptionCost::Trait { id } => panel.traits.contains(id),
+            OptionCost::Internal { id } => panel.has_internal(id),
+            OptionCost::AttackSkill { id } => panel.has_attack_skill(id),
+            OptionCost::DefenseSkill { id } => panel.has_defense_skill(id),
+        }
+    }
+
+    /// 代价的可读描述
+    pub fn describe(&self) -> String {
+        match self {
+            OptionCost::Qi { amount } => format!("内息 {}", amount),
+            OptionCost::Hp { amount } => format!("生命 {}", amount),
+            OptionCost::Attribute { target, amount } => {
+                format!("{} {}", attribute_name(*target), amount)
+            }
+            OptionCost::Trait { id } => format!("特性 {}", id),
+            OptionCost::Internal { id } => format!("内功 {}", id),
+            OptionCost::AttackSkill { id } => format!("攻击武技 {}", id),
+            OptionCost::DefenseSkill { id } => format!("防御武技 {}", id),
+        }
+    }
+
+    fn apply(&self, panel: &mut CharacterPanel) -> WushenResult<()> {
+        match self {
+            OptionCost::Qi { amount } => panel.qi -= amount,
+            OptionCost::Hp { amount } => panel.hp -= amount,
+            OptionCost::Attribute { target, amount } => {
+                apply_attribute_reward(panel, *target, *amount, Operation::Subtract, true)?
+            }
+            OptionCost::Trait { id } => {
+                if let Some(index) = panel.traits.iter().position(|t| t == id) {
+                    panel.traits.remove(index);
+                }
+            }
+            OptionCost::Internal { id } => {
+                panel.owned_internals.remove(id);
+                if panel.current_internal_id.as_ref() == Some(id) {
+                    panel.current_internal_id = None;
+                }
+            }
+            OptionCost::AttackSkill { id } => {
+                panel.owned_attack_skills.remove(id);
+                if panel.current_attack_skill_id.as_ref() == Some(id) {
+                    panel.current_attack_skill_id = None;
+                    panel.current_attack_skill_name = None;
+                }
+            }
+            OptionCost::DefenseSkill { id } => {
+                panel.owned_defense_skills.remove(id);
+                if panel.current_defense_skill_id.as_ref() == Some(id) {
+                    panel.current_defense_skill_id = None;
+                    panel.current_defense_skill_name = None;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn attribute_name(target: RewardTarget) -> &'static str {
+    match target {
+        RewardTarget::Comprehension => "悟性",
+        RewardTarget::BoneStructure => "根骨",
+        RewardTarget::Physique => "体魄",
+        RewardTarget::MartialArtsAttainment => "武学素养",
+        RewardTarget::MaxHp => "生命上限",
+        RewardTarget::BaseAttack => "基础攻击",
+        RewardTarget::BaseDefense => "基础防御",
+    }
+}
+
+/// 能否支付全部代价（按顺序累计扣除后判断）
+pub fn can_afford_costs(panel: &CharacterPanel, costs: &[OptionCost]) -> bool {
+    costs.is_empty() || apply_costs(&mut panel.clone(), costs).is_ok()
+}
+
+/// 扣除全部代价；任一代价无法支付时返回错误且不修改面板
+pub fn apply_costs(panel: &mut CharacterPanel, costs: &[OptionCost]) -> WushenResult<()> {
+    let mut paid = panel.clone();
+    for cost in costs {
+        if !cost.is_affordable(&paid) {
+            return Err(WushenError::ConditionNotMet(format!(
+                "无法支付代价：{}",
+                cost.describe()
+            )));
+        }
+        cost.apply(&mut paid)?;
+    }
+    *panel = paid;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::panel::ThreeDimensional;
+
+    #[test]
+    fn costs_are_paid_together_or_not_at_all() {
+        let mut panel = CharacterPanel::new("a".to_string(), ThreeDimensional::new(10, 10, 10));
+        panel.qi = 50.0;
+        panel.set_internal_level_exp("吐纳法".to_string(), 1, 0.0);
+        panel.current_internal_id = Some("吐纳法".to_string());
+        let costs: Vec<OptionCost> = serde_json::from_str(
+            r#"[
+                {"type":"qi","amount":30},
+                {"type":"hp","amount":100},
+                {"type":"internal","id":"吐纳法"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert!(can_afford_costs(&panel, &costs));
+        apply_costs(&mut panel, &costs).unwrap();
+        assert_eq!(panel.qi, 20.0);
+        assert_eq!(panel.hp, 900.0);
+        assert!(!panel.has_internal("吐纳法"));
+        assert!(panel.current_internal_id.is_none());
+
+        // 第二项无法支付时，第一项也不扣除
+        let costs = [
+            OptionCost::Qi { amount: 10.0 },
+            OptionCost::Qi { amount: 20.0 },
+        ];
+        assert!(!can_afford_costs(&panel, &costs));
+        assert!(apply_costs(&mut panel, &costs).is_err());
+        assert_eq!(panel.qi, 20.0);
+        assert!(OptionCost::Hp { amount: 0.0 }.validate().is_err());
+    }
+}
@@ -0,0 +1,324 @@
+use crate::error::{WushenError, WushenResult};
+use crate::event::types::{
+    AdventureEvent, AdventureEventContent, AdventureOptionResult, EnemyTemplate, StoryEventContent,
+    Storyline,
+};
+use crate::pack::{CollectionKind, LoadWarning};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+/// 敌人管理器：解析敌人库中的继承关系，并为事件替换引用的敌人
+use std::collections::{BTreeMap, HashMap};
+
+/// 敌人库中的敌人定义
+///
+/// extends 指向基础敌人时，其余字段覆盖基础敌人的同名字段（对象字段逐层合并），
+/// add_traits 追加到基础敌人的特性列表之后
+#[derive(Debug, Clone, Deserialize)]
+pub struct EnemyDefinition {
+    pub id: String,
+    #[serde(default)]
+    pub extends: Option<String>,
+    #[serde(default)]
+    pub add_traits: Vec<String>,
+    #[serde(flatten)]
+    pub fields: Map<String, Value>,
+}
+
+/// 敌人管理器
+#[derive(Clone, Default)]
+pub struct EnemyManager {
+    enemies: BTreeMap<String, EnemyTemplate>,
+}
+
+impl EnemyManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 解析继承关系并加载敌人（同 ID 覆盖已加载的敌人）
+    pub fn load_enemies(&mut self, definitions: Vec<EnemyDefinition>) -> WushenResult<()> {
+        let by_id: HashMap<&str, &EnemyDefinition> =
+            definitions.iter().map(|d| (d.id.as_str(), d)).collect();
+        let mut resolved = BTreeMap::new();
+        for definition in &definitions {
+            let mut chain = Vec::new();
+            let value = Self::resolve_value(definition, &by_id, &mut chain)?;
+            let enemy: EnemyTemplate = serde_json::from_value(value).map_err(|e| {
+                WushenError::Invalid(format!("敌人 {} 解析失败: {}", definition.id, e))
+            })?;
+            enemy
+                .validate()
+                .map_err(|e| WushenError::Invalid(format!("敌人 {} 错误: {}", definition.id, e)))?;
+            resolved.insert(definition.id.clone(), enemy);
+        }
+        self.enemies.extend(resolved);
+        Ok(())
+    }
+
+    fn resolve_value<'a>(
+        definition: &'a EnemyDefinition,
+        by_id: &HashMap<&str, &'a EnemyDefinition>,
+        chain: &mut Vec<&'a str>,
+    ) -> WushenResult<Value> {
+        if chain.contains(&definition.id.as_str()) {
+            return Err(WushenError::Invalid(format!(
+                "敌人 {} 的继承关系存在循环: {} -> {}",
+                definition.id,
+                chain.join(" -> "),
+                definition.id
+            )));
+        }
+        chain.push(&definition.id);
+        let mut value = match &definition.extends {
+            Some(base_id) => {
+                let base = by_id.get(base_id.as_str()).ok_or_else(|| {
+                    WushenError::Invalid(format!(
+                        "敌人 {} 继承的基础敌人 {} 不存在",
+                        definition.id, base_id
+                    ))
+                })?;
+                Self::resolve_value(base, by_id, chain)?
+            }
+            None => Value::Object(Map::new()),
+        };
+        merge_value(&mut value, Value::Object(definition.fields.clone()));
+        if !definition.add_traits.is_empty() {
+            if let Value::Object(object) = &mut value {
+                let traits = object
+                    .entry("traits")
+                    .or_insert_with(|| Value::Array(Vec::new()));
+                if let Value::Array(traits) = traits {
+                    for trait_id in &definition.add_traits {
+                        let trait_id = Value::String(trait_id.clone());
+                        if !traits.contains(&trait_id) {
+                            traits.push(trait_id);
+                        }
+                    }
+                }
+            }
+        }
+        chain.pop();
+        Ok(value)
+    }
+
+    pub fn get_enemy(&self, id: &str) -> Option<&EnemyTemplate> {
+        self.enemies.get(id)
+    }
+
+    pub fn all_enemies(&self) -> Vec<(&String, &EnemyTemplate)> {
+        self.enemies.iter().collect()
+    }
+
+    /// 将剧情线中引用敌人库的战斗替换为库中的敌人，返回找不到引用时的警告
+    pub fn resolve_storylines(&self, storylines: &mut [Storyline]) -> Vec<LoadWarning> {
+        let mut warnings = Vec::new();
+        for storyline in storylines {
+            for event in &mut storyline.events {
+                if let StoryEventContent::Battle {
+                    enemy,
+                    enemy_id,
+                    enemies,
+                    ..
+                } = &mut event.content
+                {
+                    let source = (CollectionKind::Storylines, storyline.id.as_str());
+                    self.resolve(enemy_id.as_deref(), enemy, source, &event.id, &mut warnings);
+                    self.resolve_all(enemies, source, &event.id, &mut warnings);
+                }
+            }
+        }
+        warnings
+    }
+
+    /// 将奇遇中引用敌人库的战斗替换为库中的敌人，返回找不到引用时的警告
+    pub fn resolve_adventures(&self, adventures: &mut [AdventureEvent]) -> Vec<LoadWarning> {
+        let mut warnings = Vec::new();
+        for adventure in adventures {
+            let source = (CollectionKind::Adventures, adventure.id.as_str());
+            match &mut adventure.content {
+                AdventureEventContent::Battle {
+                    enemy,
+                    enemy_id,
+                    enemies,
+                    ..
+                } => {
+                    self.resolve(
+                        enemy_id.as_deref(),
+                        enemy,
+                        source,
+                        &adventure.id,
+                        &mut warnings,
+                    );
+                    self.resolve_all(enemies, source, &adventure.id, &mut warnings);
+                }
+                AdventureEventContent::Decision { options, .. } => {
+                    for option in options {
+                        if let AdventureOptionResult::Battle {
+                            enemy,
+                            enemy_id,
+                            enemies,
+                            ..
+                        } = &mut option.result
+                        {
+                            self.resolve(
+                                enemy_id.as_deref(),
+                                enemy,
+                                source,
+                                &adventure.id,
+                                &mut warnings,
+                            );
+                            self.resolve_all(enemies, source, &adventure.id, &mut warnings);
+                        }
+                    }
+                }
+                AdventureEventContent::Story { .. } => {}
+            }
+        }
+        warnings
+    }
+
+    /// 替换一对多战斗中同场的其余敌人（各自按 `enemy_id` 引用敌人库）
+    fn resolve_all(
+        &self,
+        enemies: &mut [EnemyTemplate],
+        source: (CollectionKind, &str),
+        event_id: &str,
+        warnings: &mut Vec<LoadWarning>,
+    ) {
+        for enemy in enemies {
+            let Some(enemy_id) = enemy.enemy_id.clone() else {
+                continue;
+            };
+            self.resolve(Some(&enemy_id), enemy, source, event_id, warnings);
+            enemy.enemy_id = Some(enemy_id);
+        }
+    }
+
+    fn resolve(
+        &self,
+        enemy_id: Option<&str>,
+        enemy: &mut EnemyTemplate,
+        (collection, source_id): (CollectionKind, &str),
+        event_id: &str,
+        warnings: &mut Vec<LoadWarning>,
+    ) {
+        let Some(enemy_id) = enemy_id.filter(|id| !id.is_empty()) else {
+            return;
+        };
+        match self.enemies.get(enemy_id) {
+            Some(template) => *enemy = template.clone(),
+            None => warnings.push(LoadWarning::auto_corrected(
+                collection,
+                source_id,
+                format!(
+                    "事件 {} 引用的敌人 {} 不在敌人库中，沿用事件内的敌人数据",
+                    event_id, enemy_id
+                ),
+            )),
+        }
+    }
+}
+
+/// 将 overlay 合并进 base：对象逐字段合并，其余类型直接覆盖
+fn merge_value(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_value(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definitions(json: Value) -> Vec<EnemyDefinition> {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn variants_override_and_extend_base() {
+        let mut manager = EnemyManager::new();
+        manager
+            .load_enemies(definitions(serde_json::json!([
+                {
+                    "id": "bandit",
+                    "name": "山贼",
+                    "three_d": { "comprehension": 5, "bone_structure": 5, "physique": 10 },
+                    "traits": ["t_rough"]
+                },
+                {
+                    "id": "bandit_chief",
+                    "extends": "bandit",
+                    "name": "山贼头目",
+                    "three_d": { "physique": 20 },
+                    "add_traits": ["t_leader"]
+                }
+            ])))
+            .unwrap();
+
+        let chief = manager.get_enemy("bandit_chief").unwrap();
+        assert_eq!(chief.name, "山贼头目");
+        assert_eq!(chief.three_d.physique, 20);
+        assert_eq!(chief.three_d.comprehension, 5);
+        assert_eq!(chief.traits, vec!["t_rough", "t_leader"]);
+        assert_eq!(manager.get_enemy("bandit").unwrap().three_d.physique, 10);
+
+        let cyclic = definitions(serde_json::json!([
+            { "id": "a", "extends": "b", "name": "甲" },
+            { "id": "b", "extends": "a", "name": "乙" }
+        ]));
+        let err = manager.load_enemies(cyclic).unwrap_err();
+        assert_eq!(err.code(), "invalid");
+        assert!(err.message().contains("循环"));
+    }
+
+    #[test]
+    fn every_enemy_of_a_group_battle_is_resolved() {
+        let mut manager = EnemyManager::new();
+        manager
+            .load_enemies(definitions(serde_json::json!([{
+                "id": "bandit",
+                "name": "山贼",
+                "three_d": { "comprehension": 5, "bone_structure": 5, "physique": 10 }
+            }])))
+            .unwrap();
+        let placeholder = |enemy_id: &str| {
+            serde_json::json!({"enemy_id": enemy_id, "name": "占位",
+                "three_d": {"comprehension": 1, "bone_structure": 1, "physique": 1}})
+        };
+        let mut storylines: Vec<Storyline> = serde_json::from_value(serde_json::json!([{
+            "id": "s", "name": "测试", "start_event_id": "fight",
+            "events": [
+                {"id": "fight", "name": "群战", "node_type": "start",
+                 "content": {"type": "battle", "text": "", "enemy_id": "bandit",
+                             "enemy": placeholder("bandit"),
+                             "enemies": [placeholder("bandit"), placeholder("missing")],
+                             "win": {"next_event_id": "end"}, "lose": {"next_event_id": "end"}}},
+                {"id": "end", "name": "终局", "node_type": "end",
+                 "content": {"type": "end", "text": ""}}
+            ]
+        }]))
+        .unwrap();
+
+        let warnings = manager.resolve_storylines(&mut storylines);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("missing"));
+        let StoryEventContent::Battle { enemy, enemies, .. } = &storylines[0].events[0].content
+        else {
+            panic!("不是战斗事件");
+        };
+        assert_eq!(enemy.name, "山贼");
+        assert_eq!(enemies[0].name, "山贼");
+        assert_eq!(enemies[0].enemy_id.as_deref(), Some("bandit"));
+        assert_eq!(enemies[1].name, "占位");
+    }
+}
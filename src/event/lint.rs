@@ -0,0 +1,549 @@
+/// 条件检查：找出剧情与奇遇中永远不满足的条件、自相矛盾的 And 组合，
+/// 以及被前序选项覆盖的选项（条件更严格且去向完全相同，对玩家没有意义）；
+/// 另检查词条公式引用、却没有任何战斗声明的环境变量，以及触发时机下不存在的公式变量
+use crate::character::trait_manager::TraitManager;
+use crate::cultivation::manual_manager::ManualManager;
+use crate::effect::condition::{AttributeType, ComparisonOp, Condition, CultivationCondition};
+use crate::effect::entry::Entry;
+use crate::effect::formula::{
+    environment_references, FormulaAvailability, FormulaCalculator, FormulaContextKind,
+};
+use crate::effect::trigger::Trigger;
+use crate::event::types::{
+    AdventureEvent, AdventureEventContent, AdventureOptionResult, StoryEventContent, Storyline,
+};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// 条件检查器
+pub struct ConditionLinter<'a> {
+    manual_manager: &'a ManualManager,
+    trait_manager: &'a TraitManager,
+}
+
+impl<'a> ConditionLinter<'a> {
+    pub fn new(manual_manager: &'a ManualManager, trait_manager: &'a TraitManager) -> Self {
+        Self {
+            manual_manager,
+            trait_manager,
+        }
+    }
+
+    /// 检查剧情线中的选项与往昔之影条件
+    pub fn lint_storyline(&self, storyline: &Storyline) -> Vec<String> {
+        let mut lints = Vec::new();
+        if let Some(encounter) = &storyline.echo_encounter {
+            if let Some(reason) = encounter.condition.as_ref().and_then(|c| self.never_met(c)) {
+                lints.push(format!(
+                    "剧情线 {} 往昔之影条件永远不满足：{}",
+                    storyline.id, reason
+                ));
+            }
+        }
+        for event in &storyline.events {
+            let StoryEventContent::Decision { options, .. } = &event.content else {
+                continue;
+            };
+            let location = format!("剧情线 {} 事件 {}", storyline.id, event.id);
+            for (index, option) in options.iter().enumerate() {
+                if let Some(reason) = option.condition.as_ref().and_then(|c| self.never_met(c)) {
+                    lints.push(format!(
+                        "{} 选项 {} 条件永远不满足：{}",
+                        location, option.id, reason
+                    ));
+                    continue;
+                }
+                let shadowed_by = options[..index].iter().find(|earlier| {
+                    earlier.next_event_id == option.next_event_id
+                        && earlier.prerequisites == option.prerequisites
+                        && implies(option.condition.as_ref(), earlier.condition.as_ref())
+                });
+                if let Some(earlier) = shadowed_by {
+                    lints.push(format!(
+                        "{} 选项 {} 被选项 {} 覆盖：条件不比后者宽松且去向相同",
+                        location, option.id, earlier.id
+                    ));
+                }
+            }
+        }
+        lints
+    }
+
+    /// 检查奇遇事件的触发条件与选项条件
+    pub fn lint_adventure_event(&self, event: &AdventureEvent) -> Vec<String> {
+        let mut lints = Vec::new();
+        if let Some(reason) = event.trigger.as_ref().and_then(|c| self.never_met(c)) {
+            lints.push(format!("奇遇 {} 触发条件永远不满足：{}", event.id, reason));
+        }
+        if let AdventureEventContent::Decision { options, .. } = &event.content {
+            for (index, option) in options.iter().enumerate() {
+                if let Some(reason) = option.condition.as_ref().and_then(|c| self.never_met(c)) {
+                    lints.push(format!(
+                        "奇遇 {} 选项 {} 条件永远不满足：{}",
+                        event.id, option.id, reason
+                    ));
+                    continue;
+                }
+                let result = serde_json::to_value(&option.result).ok();
+                let shadowed_by = options[..index].iter().find(|earlier| {
+                    serde_json::to_value(&earlier.result).ok() == result
+                        && implies(option.condition.as_ref(), earlier.condition.as_ref())
+                });
+                if let Some(earlier) = shadowed_by {
+                    lints.push(format!(
+                        "奇遇 {} 选项 {} 被选项 {} 覆盖：条件不比后者宽松且结果相同",
+                        event.id, option.id, earlier.id
+                    ));
+                }
+            }
+        }
+        lints
+    }
+
+    /// 检查功法与特性的词条公式：引用的环境变量没有任何战斗声明时，该变量始终按 0 计算
+    pub fn lint_environment(
+        &self,
+        storylines: &[&Storyline],
+        adventures: &[&AdventureEvent],
+    ) -> Vec<String> {
+        let mut declared = BTreeSet::new();
+        for storyline in storylines {
+            for event in &storyline.events {
+                if let StoryEventContent::Battle { environment, .. } = &event.content {
+                    declared.extend(environment.keys().map(String::as_str));
+                }
+            }
+        }
+        for event in adventures {
+            match &event.content {
+                AdventureEventContent::Battle { environment, .. } => {
+                    declared.extend(environment.keys().map(String::as_str));
+                }
+                AdventureEventContent::Decision { options, .. } => {
+                    for option in options {
+                        if let AdventureOptionResult::Battle { environment, .. } = &option.result {
+                            declared.extend(environment.keys().map(String::as_str));
+                        }
+                    }
+                }
+                AdventureEventContent::Story { .. } => {}
+            }
+        }
+
+        let mut lints = Vec::new();
+        for (source, entries) in self.entry_sources() {
+            let mut referenced = BTreeSet::new();
+            for entry in entries {
+                if let Ok(value) = serde_json::to_value(entry) {
+                    collect_environment_references(&value, &mut referenced);
+                }
+            }
+            for name in referenced
+                .iter()
+                .filter(|name| !declared.contains(name.as_str()))
+            {
+                lints.push(format!(
+                    "{} 的词条公式引用了环境变量 env_{}，但没有任何战斗声明该变量（始终按 0 计算）",
+                    source, name
+                ));
+            }
+        }
+        lints
+    }
+
+    /// 检查功法与特性的词条公式：引用了该触发时机下不存在的变量时，公式计算会失败
+    pub fn lint_formulas(&self) -> Vec<String> {
+        let mut lints = Vec::new();
+        for (source, entries) in self.entry_sources() {
+            for entry in entries {
+                let kind = FormulaContextKind::for_trigger(entry.trigger);
+                let after_attack =
+                    matches!(entry.trigger, Trigger::AfterAttack | Trigger::AfterDefense);
+                let mut formulas = BTreeSet::new();
+                if let Ok(value) = serde_json::to_value(entry) {
+                    collect_formulas(&value, &mut formulas);
+                }
+                for formula in formulas {
+                    let unknown = FormulaCalculator::unknown_identifiers(&formula, kind);
+                    if !unknown.is_empty() {
+                        lints.push(format!(
+                            "{} 的词条公式「{}」引用了未知变量：{}",
+                            source,
+                            formula,
+                            unknown.join("、")
+                        ));
+                    }
+                    if kind != FormulaContextKind::Battle || after_attack {
+                        continue;
+                    }
+                    let attack_only: Vec<String> = FormulaCalculator::variable_catalog(kind)
+                        .into_iter()
+                        .filter(|v| v.availability == FormulaAvailability::AfterAttack)
+                        .filter(|v| formula_mentions(&formula, &v.name))
+                        .map(|v| v.name)
+                        .collect();
+                    if !attack_only.is_empty() {
+                        lints.push(format!(
+                            "{} 的词条公式「{}」引用了仅攻击后/防御后可用的变量：{}",
+                            source,
+                            formula,
+                            attack_only.join("、")
+                        ));
+                    }
+                }
+            }
+        }
+        lints
+    }
+
+    /// 功法各境界与特性的词条（按来源分组）
+    fn entry_sources(&self) -> Vec<(String, Vec<&'a Entry>)> {
+        let manuals = self.manual_manager;
+        let mut sources: Vec<(String, Vec<&Entry>)> = Vec::new();
+        for internal in manuals.all_internals() {
+            let entries = internal.realms.iter().flat_map(|r| &r.entries).collect();
+            sources.push((format!("内功 {}", internal.manual.id), entries));
+        }
+        for skill in manuals.all_attack_skills() {
+            let entries = skill.realms.iter().flat_map(|r| &r.entries).collect();
+            sources.push((format!("攻击武技 {}", skill.manual.id), entries));
+        }
+        for skill in manuals.all_defense_skills() {
+            let entries = skill.realms.iter().flat_map(|r| &r.entries).collect();
+            sources.push((format!("防御武技 {}", skill.manual.id), entries));
+        }
+        for t in self.trait_manager.all_traits() {
+            sources.push((format!("特性 {}", t.id), t.entries.iter().collect()));
+        }
+        sources
+    }
+
+    /// 判断条件是否永远不满足（剧情与奇遇按修行上下文检查），返回原因
+    pub fn never_met(&self, condition: &Condition) -> Option<String> {
+        match condition {
+            Condition::Cultivation(cond) => self.unknown_reference(cond),
+            Condition::Battle(_) => Some("战斗条件在剧情与奇遇中永远不满足".to_string()),
+            Condition::And(conds) => conds
+                .iter()
+                .find_map(|c| self.never_met(c))
+                .or_else(|| contradiction(conds)),
+            Condition::Or(conds) => {
+                let reasons: Vec<String> = conds.iter().filter_map(|c| self.never_met(c)).collect();
+                (reasons.len() == conds.len()).then(|| {
+                    if reasons.is_empty() {
+                        "空的 Or 组合".to_string()
+                    } else {
+                        format!("Or 组合的所有分支均不满足（{}）", reasons.join("；"))
+                    }
+                })
+            }
+        }
+    }
+
+    fn unknown_reference(&self, cond: &CultivationCondition) -> Option<String> {
+        let manuals = self.manual_manager;
+        match cond {
+            CultivationCondition::InternalIs(id) if manuals.get_internal(id).is_none() => {
+                Some(format!("内功 {} 不存在于任何数据包", id))
+            }
+            CultivationCondition::AttackSkillIs(id) if manuals.get_attack_skill(id).is_none() => {
+                Some(format!("攻击武技 {} 不存在于任何数据包", id))
+            }
+            CultivationCondition::DefenseSkillIs(id) if manuals.get_defense_skill(id).is_none() => {
+                Some(format!("防御武技 {} 不存在于任何数据包", id))
+            }
+            CultivationCondition::HasTrait(id) if self.trait_manager.get_trait(id).is_none() => {
+                Some(format!("特性 {} 不存在于任何数据包", id))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 查找 And 组合中互相矛盾的修行条件（同一槽位要求不同功法，或属性区间为空）
+/// 收集 JSON 中所有字符串（公式）引用的环境变量名
+fn collect_environment_references(value: &Value, names: &mut BTreeSet<String>) {
+    match value {
+        Value::String(formula) => names.extend(environment_references(formula).map(str::to_string)),
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| collect_environment_references(item, names)),
+        Value::Object(fields) => fields
+            .values()
+            .for_each(|field| collect_environment_references(field, names)),
+        _ => {}
+    }
+}
+
+/// 收集词条中的公式字符串（效果与比较条件的 value、额外攻击的 output）
+fn collect_formulas(value: &Value, formulas: &mut BTreeSet<String>) {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| collect_formulas(item, formulas)),
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                match field {
+                    Value::String(formula) if key == "value" || key == "output" => {
+                        formulas.insert(formula.clone());
+                    }
+                    _ => collect_formulas(field, formulas),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 公式是否以完整标识符引用了指定变量
+fn formula_mentions(formula: &str, name: &str) -> bool {
+    formula
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .any(|token| token == name)
+}
+
+fn contradiction(conds: &[Condition]) -> Option<String> {
+    let mut leaves = Vec::new();
+    collect_and_leaves(conds, &mut leaves);
+
+    let mut slots: Vec<(&str, &str)> = Vec::new();
+    let mut bounds: Vec<(AttributeType, Bounds)> = Vec::new();
+    for leaf in leaves {
+        let slot = match leaf {
+            CultivationCondition::InternalIs(id) => Some(("修行内功", id)),
+            CultivationCondition::InternalTypeIs(id) => Some(("内功类型", id)),
+            CultivationCondition::AttackSkillIs(id) => Some(("修行攻击武技", id)),
+            CultivationCondition::AttackSkillTypeIs(id) => Some(("攻击武技类型", id)),
+            CultivationCondition::DefenseSkillIs(id) => Some(("修行防御武技", id)),
+            CultivationCondition::DefenseSkillTypeIs(id) => Some(("防御武技类型", id)),
+            CultivationCondition::AttributeComparison {
+                attribute,
+                op,
+                value,
+            } => {
+                match bounds.iter_mut().find(|(a, _)| a == attribute) {
+                    Some((_, b)) => b.narrow(*op, *value),
+                    None => {
+                        let mut b = Bounds::default();
+                        b.narrow(*op, *value);
+                        bounds.push((*attribute, b));
+                    }
+                }
+                None
+            }
+            _ => None,
+        };
+        if let Some((slot, id)) = slot {
+            if let Some((_, other)) = slots.iter().find(|(s, other)| *s == slot && *other != id) {
+                return Some(format!("And 组合同时要求{}为 {} 与 {}", slot, other, id));
+            }
+            slots.push((slot, id));
+        }
+    }
+    bounds
+        .iter()
+        .find(|(_, b)| b.is_empty())
+        .map(|(attribute, _)| format!("And 组合中 {:?} 的比较条件互相矛盾", attribute))
+}
+
+fn collect_and_leaves<'c>(conds: &'c [Condition], leaves: &mut Vec<&'c CultivationCondition>) {
+    for cond in conds {
+        match cond {
+            Condition::Cultivation(leaf) => leaves.push(leaf),
+            Condition::And(inner) => collect_and_leaves(inner, leaves),
+            _ => {}
+        }
+    }
+}
+
+/// 属性取值区间（bool 表示端点是否可取）
+#[derive(Default)]
+struct Bounds {
+    lower: Option<(f64, bool)>,
+    upper: Option<(f64, bool)>,
+}
+
+impl Bounds {
+    fn narrow(&mut self, op: ComparisonOp, value: f64) {
+        let (lower, upper) = match op {
+            ComparisonOp::LessThan => (None, Some((value, false))),
+            ComparisonOp::LessThanOrEqual => (None, Some((value, true))),
+            ComparisonOp::Equal => (Some((value, true)), Some((value, true))),
+            ComparisonOp::GreaterThan => (Some((value, false)), None),
+            ComparisonOp::GreaterThanOrEqual => (Some((value, true)), None),
+        };
+        if let Some((v, inclusive)) = lower {
+            let tighter = self
+                .lower
+                .is_none_or(|(old, old_inclusive)| v > old || (v == old && old_inclusive));
+            if tighter {
+                self.lower = Some((v, inclusive));
+            }
+        }
+        if let Some((v, inclusive)) = upper {
+            let tighter = self
+                .upper
+                .is_none_or(|(old, old_inclusive)| v < old || (v == old && old_inclusive));
+            if tighter {
+                self.upper = Some((v, inclusive));
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match (self.lower, self.upper) {
+            (Some((lo, lo_inclusive)), Some((hi, hi_inclusive))) => {
+                lo > hi || (lo == hi && !(lo_inclusive && hi_inclusive))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// 判断条件 a 满足时条件 b 是否必然满足（结构上的保守判断，无条件视为恒真）
+fn implies(a: Option<&Condition>, b: Option<&Condition>) -> bool {
+    let Some(b) = b else {
+        return true;
+    };
+    let Some(a) = a else {
+        return false;
+    };
+    if same_condition(a, b) {
+        return true;
+    }
+    match (a, b) {
+        (_, Condition::And(bs)) => bs.iter().all(|b| implies(Some(a), Some(b))),
+        (Condition::Or(as_), _) => as_.iter().all(|a| implies(Some(a), Some(b))),
+        (Condition::And(as_), _) => as_.iter().any(|a| implies(Some(a), Some(b))),
+        (_, Condition::Or(bs)) => bs.iter().any(|b| implies(Some(a), Some(b))),
+        _ => false,
+    }
+}
+
+fn same_condition(a: &Condition, b: &Condition) -> bool {
+    match (serde_json::to_value(a), serde_json::to_value(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cond(json: &str) -> Condition {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn flags_unknown_references_and_contradictions() {
+        let manuals = ManualManager::new();
+        let traits = TraitManager::new();
+        let linter = ConditionLinter::new(&manuals, &traits);
+
+        assert!(linter
+            .never_met(&cond(r#"{"internal_is":"missing"}"#))
+            .is_some());
+        assert!(linter
+            .never_met(&cond(
+                r#"{"self_attribute_comparison":{"attribute":"hp","op":"less_than","value":1}}"#
+            ))
+            .is_some());
+        let range = |op: &str, value: f64| {
+            format!(
+                r#"{{"attribute_comparison":{{"attribute":"comprehension","op":"{}","value":{}}}}}"#,
+                op, value
+            )
+        };
+        let contradictory = cond(&format!(
+            "[{},{}]",
+            range("greater_than_or_equal", 10.0),
+            range("less_than", 10.0)
+        ));
+        assert!(linter.never_met(&contradictory).is_some());
+        let satisfiable = cond(&format!(
+            "[{},{}]",
+            range("greater_than_or_equal", 10.0),
+            range("less_than_or_equal", 10.0)
+        ));
+        assert!(linter.never_met(&satisfiable).is_none());
+        let two_types = cond(r#"[{"internal_type_is":"刚"},{"internal_type_is":"柔"}]"#);
+        assert!(linter.never_met(&two_types).is_some());
+    }
+
+    #[test]
+    fn flags_options_shadowed_by_earlier_options() {
+        let manuals = ManualManager::new();
+        let traits = TraitManager::new();
+        let linter = ConditionLinter::new(&manuals, &traits);
+        let storyline: Storyline = serde_json::from_str(
+            r#"{"id":"s","name":"测试","start_event_id":"start","events":[
+                {"id":"start","name":"开端","node_type":"start","content":{"type":"decision","text":"","options":[
+                    {"id":"a","text":"","next_event_id":"end","condition":{"internal_type_is":"刚"}},
+                    {"id":"b","text":"","next_event_id":"end","condition":[{"internal_type_is":"刚"},{"attribute_comparison":{"attribute":"physique","op":"greater_than","value":5}}]},
+                    {"id":"c","text":"","next_event_id":"other","condition":{"internal_type_is":"刚"}},
+                    {"id":"d","text":"","next_event_id":"leave"}
+                ]}}
+            ]}"#,
+        )
+        .unwrap();
+        let lints = linter.lint_storyline(&storyline);
+        assert_eq!(lints.len(), 1);
+        assert!(lints[0].contains("选项 b 被选项 a 覆盖"));
+    }
+
+    #[test]
+    fn flags_environment_variables_no_battle_declares() {
+        let manuals = ManualManager::new();
+        let mut traits = TraitManager::new();
+        traits.load_traits(vec![serde_json::from_str(
+            r#"{"id":"雨战","name":"雨战","description":"","entries":[{
+                "trigger":"battle_start",
+                "effects":[{"type":"modify_attribute","target":"base_attack",
+                            "value":"100 * env_rain + 50 * env_night","operation":"add"}]
+            }]}"#,
+        )
+        .unwrap()]);
+        let linter = ConditionLinter::new(&manuals, &traits);
+        let storyline: Storyline = serde_json::from_str(
+            r#"{"id":"s","name":"测试","start_event_id":"start","events":[
+                {"id":"start","name":"雨夜","node_type":"start","content":{"type":"battle","text":"",
+                 "enemy":{"name":"敌人","three_d":{"comprehension":1,"bone_structure":1,"physique":1}},
+                 "win":{"next_event_id":"end"},"lose":{"next_event_id":"end"},
+                 "environment":{"rain":1}}}
+            ]}"#,
+        )
+        .unwrap();
+
+        let lints = linter.lint_environment(&[&storyline], &[]);
+        assert_eq!(lints.len(), 1);
+        assert!(lints[0].contains("特性 雨战") && lints[0].contains("env_night"));
+    }
+
+    #[test]
+    fn flags_formula_variables_unavailable_at_the_trigger() {
+        let manuals = ManualManager::new();
+        let mut traits = TraitManager::new();
+        traits.load_traits(vec![serde_json::from_str(
+            r#"{"id":"反震","name":"反震","description":"","entries":[
+                {"trigger":"battle_start",
+                 "effects":[{"type":"modify_attribute","target":"base_attack",
+                             "value":"self_base_atack * 0.1 + attack_hp_damage","operation":"add"}]},
+                {"trigger":"after_defense",
+                 "effects":[{"type":"modify_attribute","target":"hp",
+                             "value":"attack_hp_damage * 0.2 + env_rain","operation":"add"}]}
+            ]}"#,
+        )
+        .unwrap()]);
+        let linter = ConditionLinter::new(&manuals, &traits);
+
+        let lints = linter.lint_formulas();
+        assert_eq!(lints.len(), 2, "{:?}", lints);
+        assert!(lints[0].contains("未知变量：self_base_atack"));
+        assert!(
+            lints[1].contains("仅攻击后/防御后可用的变量：attack_hp_damage"),
+            "{:?}",
+            lints
+        );
+    }
+}
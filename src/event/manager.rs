@@ -1,24 +1,84 @@
 use crate::character::panel::CharacterPanel;
 use crate::cultivation::manual_manager::ManualManager;
 use crate::effect::condition::Condition;
+use crate::error::{WushenError, WushenResult};
+use crate::event::cost::{apply_costs, can_afford_costs};
 use crate::event::types::{
-    AdventureEvent, AdventureEventContent, AdventureOptionResult, StoryEvent, StoryEventContent,
-    StoryNodeType, StoryOption, Storyline,
+    AdventureEvent, AdventureEventContent, AdventureOption, AdventureOptionResult, BattleHandicap,
+    OptionCost, PostBattleRecovery, StoryEvent, StoryEventContent, StoryNodeType, StoryOption,
+    StoryPrerequisite, Storyline,
 };
+use crate::game::{StoryHistoryRecord, StoryHistoryScope};
 /// 事件管理器
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// 本局历史（用于判断选项前置条件）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunHistory<'a> {
+    /// 已设置的剧情标记
+    pub story_flags: &'a [String],
+    pub records: &'a [StoryHistoryRecord],
+    /// 进行中（尚未作出选择）的奇遇
+    pub active_adventure_id: Option<&'a str>,
+}
+
+impl RunHistory<'_> {
+    /// 判断前置条件是否满足
+    pub fn satisfies(&self, prerequisite: &StoryPrerequisite) -> bool {
+        match prerequisite {
+            StoryPrerequisite::Flag { flag, set } => self.story_flags.contains(flag) == *set,
+            StoryPrerequisite::VisitedEvent { event_id } => self
+                .records
+                .iter()
+                .any(|r| r.scope == StoryHistoryScope::Story && &r.event_id == event_id),
+            StoryPrerequisite::ChoseOption {
+                event_id,
+                option_id,
+            } => self.records.iter().any(|r| {
+                &r.event_id == event_id && r.option_id.as_deref() == Some(option_id.as_str())
+            }),
+            StoryPrerequisite::CompletedAdventure { adventure_id } => {
+                let visits = self
+                    .records
+                    .iter()
+                    .filter(|r| {
+                        r.scope == StoryHistoryScope::Adventure
+                            && &r.event_id == adventure_id
+                            && !r.skipped
+                    })
+                    .count();
+                // 进行中的奇遇已被记录但尚未完成
+                let pending = usize::from(self.active_adventure_id == Some(adventure_id.as_str()));
+                visits > pending
+            }
+            StoryPrerequisite::BattleResult { event_id, win } => self
+                .records
+                .iter()
+                .any(|r| &r.event_id == event_id && r.battle_win == Some(*win)),
+            StoryPrerequisite::BattlesWon { min } => {
+                let wins = self
+                    .records
+                    .iter()
+                    .filter(|r| r.battle_win == Some(true))
+                    .count();
+                wins >= *min as usize
+            }
+        }
+    }
+}
 
 /// 事件管理器
+#[derive(Clone)]
 pub struct EventManager {
-    storylines: HashMap<String, Storyline>,
-    adventures: HashMap<String, AdventureEvent>,
+    storylines: BTreeMap<String, Storyline>,
+    adventures: BTreeMap<String, AdventureEvent>,
 }
 
 impl EventManager {
     pub fn new() -> Self {
         Self {
-            storylines: HashMap::new(),
-            adventures: HashMap::new(),
+            storylines: BTreeMap::new(),
+            adventures: BTreeMap::new(),
         }
     }
 
@@ -51,7 +111,7 @@ impl EventManager {
     }
 
     /// 校验所有剧情线
-    pub fn validate_storylines(&self) -> Result<(), String> {
+    pub fn validate_storylines(&self) -> WushenResult<()> {
         for storyline in self.storylines.values() {
             Self::validate_storyline(storyline)?;
         }
@@ -59,7 +119,7 @@ impl EventManager {
     }
 
     /// 校验所有奇遇事件
-    pub fn validate_adventure_events(&self) -> Result<(), String> {
+    pub fn validate_adventure_events(&self) -> WushenResult<()> {
         for event in self.adventures.values() {
             Self::validate_adventure_event(event)?;
         }
@@ -81,16 +141,65 @@ impl EventManager {
         }
     }
 
-    /// 获取剧情事件可选项
+    /// 获取剧情事件可选项（面板条件与本局历史前置条件均满足，且代价可以支付）；
+    /// 兜底选项仅在其他选项都不可选时提供
     pub fn available_story_options<'a>(
         options: &'a [StoryOption],
         panel: &CharacterPanel,
         manual_manager: &ManualManager,
+        history: &RunHistory,
     ) -> Vec<&'a StoryOption> {
-        options
+        let mut available: Vec<_> = options
             .iter()
             .filter(|o| Self::is_condition_met(&o.condition, panel, manual_manager))
-            .collect()
+            .filter(|o| o.prerequisites.iter().all(|p| history.satisfies(p)))
+            .filter(|o| can_afford_costs(panel, &o.costs))
+            .collect();
+        if available.iter().any(|o| !o.fallback) {
+            available.retain(|o| !o.fallback);
+        }
+        available
+    }
+
+    /// 获取奇遇事件可选项（条件满足且代价可以支付）；兜底选项仅在其他选项都不可选时提供
+    pub fn available_adventure_options<'a>(
+        options: &'a [AdventureOption],
+        panel: &CharacterPanel,
+        manual_manager: &ManualManager,
+    ) -> Vec<&'a AdventureOption> {
+        let mut available: Vec<_> = options
+            .iter()
+            .filter(|o| Self::is_condition_met(&o.condition, panel, manual_manager))
+            .filter(|o| can_afford_costs(panel, &o.costs))
+            .collect();
+        if available.iter().any(|o| !o.fallback) {
+            available.retain(|o| !o.fallback);
+        }
+        available
+    }
+
+    /// 剧情选项当前不可选的原因（用于无路可走时的诊断）
+    pub fn story_option_blockers(
+        option: &StoryOption,
+        panel: &CharacterPanel,
+        manual_manager: &ManualManager,
+        history: &RunHistory,
+    ) -> Vec<String> {
+        let mut reasons = Vec::new();
+        if !Self::is_condition_met(&option.condition, panel, manual_manager) {
+            reasons.push("选项条件不满足".to_string());
+        }
+        reasons.extend(
+            option
+                .prerequisites
+                .iter()
+                .filter(|p| !history.satisfies(p))
+                .map(|p| p.describe()),
+        );
+        if let Err(e) = apply_costs(&mut panel.clone(), &option.costs) {
+            reasons.push(e.into());
+        }
+        reasons
     }
 
     /// 判断奇遇事件是否满足触发条件
@@ -103,36 +212,65 @@ impl EventManager {
     }
 
     /// 校验单个剧情线
-    pub fn validate_storyline(storyline: &Storyline) -> Result<(), String> {
+    pub fn validate_storyline(storyline: &Storyline) -> WushenResult<()> {
         if storyline.events.is_empty() {
-            return Err(format!("剧情线 {} 事件列表为空", storyline.id));
+            return Err(WushenError::Invalid(format!(
+                "剧情线 {} 事件列表为空",
+                storyline.id
+            )));
         }
 
         let mut event_map: HashMap<String, &StoryEvent> = HashMap::new();
         for event in &storyline.events {
             if event_map.contains_key(&event.id) {
-                return Err(format!(
+                return Err(WushenError::Invalid(format!(
                     "剧情线 {} 存在重复事件ID: {}",
                     storyline.id, event.id
-                ));
+                )));
             }
             event_map.insert(event.id.clone(), event);
         }
 
         let start_event = event_map.get(&storyline.start_event_id).ok_or_else(|| {
-            format!(
+            WushenError::Invalid(format!(
                 "剧情线 {} 起始事件不存在: {}",
                 storyline.id, storyline.start_event_id
-            )
+            ))
         })?;
         if start_event.node_type != StoryNodeType::Start {
-            return Err(format!("剧情线 {} 起始事件类型必须为 start", storyline.id));
+            return Err(WushenError::Invalid(format!(
+                "剧情线 {} 起始事件类型必须为 start",
+                storyline.id
+            )));
         }
+        if let PostBattleRecovery::Percentage { ratio } = storyline.post_battle_recovery {
+            if !(0.0..=1.0).contains(&ratio) {
+                return Err(WushenError::Invalid(format!(
+                    "剧情线 {} 战后恢复比例必须在 0-1 之间",
+                    storyline.id
+                )));
+            }
+        }
+
+        storyline.duplicate_rewards.validate().map_err(|e| {
+            WushenError::Invalid(format!("剧情线 {} 重复奖励配置无效: {}", storyline.id, e))
+        })?;
+
+        storyline.starting_kit.validate().map_err(|e| {
+            WushenError::Invalid(format!("剧情线 {} 开局行装无效: {}", storyline.id, e))
+        })?;
+
+        validate_chapters(storyline, &event_map)?;
 
         // 构建边并做一致性检查
         let mut adjacency: HashMap<String, Vec<String>> = HashMap::new();
 
         for event in &storyline.events {
+            if let Some(presentation) = &event.presentation {
+                presentation
+                    .validate()
+                    .map_err(|e| WushenError::Invalid(format!("事件 {} 错误: {}", event.id, e)))?;
+            }
             let mut next_ids = Vec::new();
             match &event.content {
                 StoryEventContent::Decision { options, .. } => {
@@ -141,15 +279,107 @@ impl EventManager {
                         next_ids.push(option.next_event_id.clone());
                     }
                 }
-                StoryEventContent::Battle { win, lose, .. } => {
+                StoryEventContent::Battle {
+                    enemy,
+                    win,
+                    lose,
+                    draw,
+                    handicaps,
+                    environment,
+                    interruptions,
+                    training,
+                    victory_decision,
+                    ..
+                } => {
+                    enemy.validate().map_err(|e| {
+                        WushenError::Invalid(format!("事件 {} 错误: {}", event.id, e))
+                    })?;
+                    if let Some(training) = training {
+                        training.validate().map_err(|e| {
+                            WushenError::Invalid(format!("事件 {} 错误: {}", event.id, e))
+                        })?;
+                        if victory_decision.is_some() {
+                            return Err(WushenError::Invalid(format!(
+                                "事件 {} 是训练战斗，不能配置战后抉择",
+                                event.id
+                            )));
+                        }
+                    }
+                    validate_handicaps(handicaps).map_err(|e| {
+                        WushenError::Invalid(format!("事件 {} 错误: {}", event.id, e))
+                    })?;
+                    validate_environment(environment).map_err(|e| {
+                        WushenError::Invalid(format!("事件 {} 错误: {}", event.id, e))
+                    })?;
                     next_ids.push(win.next_event_id.clone());
                     next_ids.push(lose.next_event_id.clone());
+                    if let Some(draw) = draw {
+                        next_ids.push(draw.next_event_id.clone());
+                    }
+                    let mut interruption_ids = HashSet::new();
+                    for interruption in interruptions {
+                        if !interruption_ids.insert(interruption.id.as_str()) {
+                            return Err(WushenError::Invalid(format!(
+                                "事件 {} 的打断 {} 重复",
+                                event.id, interruption.id
+                            )));
+                        }
+                        interruption.trigger.validate().map_err(|e| {
+                            WushenError::Invalid(format!(
+                                "事件 {} 的打断 {} 错误: {}",
+                                event.id, interruption.id, e
+                            ))
+                        })?;
+                        next_ids.push(interruption.next_event_id.clone());
+                    }
+                    if let Some(decision) = victory_decision {
+                        if decision.options.is_empty() {
+                            return Err(WushenError::Invalid(format!(
+                                "事件 {} 的战后抉择没有选项",
+                                event.id
+                            )));
+                        }
+                        let mut option_ids = HashSet::new();
+                        for option in &decision.options {
+                            if !option_ids.insert(option.id.as_str()) {
+                                return Err(WushenError::Invalid(format!(
+                                    "事件 {} 的战后抉择选项 {} 重复",
+                                    event.id, option.id
+                                )));
+                            }
+                            if let Some(next) = &option.next_event_id {
+                                next_ids.push(next.clone());
+                            }
+                        }
+                    }
                 }
                 StoryEventContent::Story { next_event_id, .. } => {
                     if let Some(next) = next_event_id {
                         next_ids.push(next.clone());
                     }
                 }
+                StoryEventContent::Check {
+                    check,
+                    critical_success,
+                    success,
+                    failure,
+                    ..
+                } => {
+                    check.validate().map_err(|e| {
+                        WushenError::Invalid(format!("事件 {} 错误: {}", event.id, e))
+                    })?;
+                    if critical_success.is_some() && check.critical_threshold.is_none() {
+                        return Err(WushenError::Invalid(format!(
+                            "事件 {} 配置了大成功分支但未指定大成功阈值",
+                            event.id
+                        )));
+                    }
+                    if let Some(branch) = critical_success {
+                        next_ids.push(branch.next_event_id.clone());
+                    }
+                    next_ids.push(success.next_event_id.clone());
+                    next_ids.push(failure.next_event_id.clone());
+                }
                 StoryEventContent::End { .. } => {}
             }
 
@@ -157,10 +387,16 @@ impl EventManager {
             match event.node_type {
                 StoryNodeType::Start => {
                     if matches!(event.content, StoryEventContent::Decision { .. }) {
-                        return Err(format!("起始事件 {} 不能是抉择事件", event.id));
+                        return Err(WushenError::Invalid(format!(
+                            "起始事件 {} 不能是抉择事件",
+                            event.id
+                        )));
                     }
                     if matches!(event.content, StoryEventContent::End { .. }) {
-                        return Err(format!("起始事件 {} 不能是 end 类型内容", event.id));
+                        return Err(WushenError::Invalid(format!(
+                            "起始事件 {} 不能是 end 类型内容",
+                            event.id
+                        )));
                     }
                     if matches!(
                         event.content,
@@ -169,26 +405,44 @@ impl EventManager {
                             ..
                         }
                     ) {
-                        return Err(format!("起始剧情事件 {} 必须指定 next_event_id", event.id));
+                        return Err(WushenError::Invalid(format!(
+                            "起始剧情事件 {} 必须指定 next_event_id",
+                            event.id
+                        )));
                     }
                     if event.action_points != 0 {
-                        return Err(format!("起始事件 {} 行动点必须为0", event.id));
+                        return Err(WushenError::Invalid(format!(
+                            "起始事件 {} 行动点必须为0",
+                            event.id
+                        )));
                     }
                 }
                 StoryNodeType::End => {
                     if !matches!(event.content, StoryEventContent::End { .. }) {
-                        return Err(format!("结局事件 {} 必须是 end 类型内容", event.id));
+                        return Err(WushenError::Invalid(format!(
+                            "结局事件 {} 必须是 end 类型内容",
+                            event.id
+                        )));
                     }
                     if event.action_points != 0 {
-                        return Err(format!("结局事件 {} 行动点必须为0", event.id));
+                        return Err(WushenError::Invalid(format!(
+                            "结局事件 {} 行动点必须为0",
+                            event.id
+                        )));
                     }
                     if !next_ids.is_empty() {
-                        return Err(format!("结局事件 {} 不应包含后续事件", event.id));
+                        return Err(WushenError::Invalid(format!(
+                            "结局事件 {} 不应包含后续事件",
+                            event.id
+                        )));
                     }
                 }
                 StoryNodeType::Middle => {
                     if matches!(event.content, StoryEventContent::End { .. }) {
-                        return Err(format!("中间事件 {} 不能是 end 类型内容", event.id));
+                        return Err(WushenError::Invalid(format!(
+                            "中间事件 {} 不能是 end 类型内容",
+                            event.id
+                        )));
                     }
                     if matches!(
                         event.content,
@@ -197,7 +451,10 @@ impl EventManager {
                             ..
                         }
                     ) {
-                        return Err(format!("剧情事件 {} 必须指定 next_event_id", event.id));
+                        return Err(WushenError::Invalid(format!(
+                            "剧情事件 {} 必须指定 next_event_id",
+                            event.id
+                        )));
                     }
                 }
             }
@@ -205,10 +462,10 @@ impl EventManager {
             // 检查 next_event_id 是否存在
             for next_id in &next_ids {
                 if !event_map.contains_key(next_id) {
-                    return Err(format!(
+                    return Err(WushenError::Invalid(format!(
                         "事件 {} 指向不存在的后续事件 {}",
                         event.id, next_id
-                    ));
+                    )));
                 }
             }
 
@@ -221,10 +478,13 @@ impl EventManager {
             node: &str,
             adjacency: &HashMap<String, Vec<String>>,
             color: &mut HashMap<String, u8>,
-        ) -> Result<(), String> {
+        ) -> WushenResult<()> {
             let state = *color.get(node).unwrap_or(&0);
             if state == 1 {
-                return Err(format!("检测到剧情线存在环，起点/节点: {}", node));
+                return Err(WushenError::Invalid(format!(
+                    "检测到剧情线存在环，起点/节点: {}",
+                    node
+                )));
             }
             if state == 2 {
                 return Ok(());
@@ -262,7 +522,10 @@ impl EventManager {
 
         for event in &storyline.events {
             if !reachable.contains(&event.id) {
-                return Err(format!("事件 {} 不可从起始事件到达", event.id));
+                return Err(WushenError::Invalid(format!(
+                    "事件 {} 不可从起始事件到达",
+                    event.id
+                )));
             }
         }
 
@@ -270,19 +533,55 @@ impl EventManager {
     }
 
     /// 校验单个奇遇事件
-    pub fn validate_adventure_event(event: &AdventureEvent) -> Result<(), String> {
+    pub fn validate_adventure_event(event: &AdventureEvent) -> WushenResult<()> {
+        if let Some(presentation) = &event.presentation {
+            presentation
+                .validate()
+                .map_err(|e| WushenError::Invalid(format!("奇遇事件 {} 错误: {}", event.id, e)))?;
+        }
         match &event.content {
-            AdventureEventContent::Decision { options, .. } => {
+            AdventureEventContent::Decision {
+                options,
+                skip_costs,
+                ..
+            } => {
                 if options.is_empty() {
-                    return Err(format!("奇遇事件 {} 的选项不能为空", event.id));
+                    return Err(WushenError::Invalid(format!(
+                        "奇遇事件 {} 的选项不能为空",
+                        event.id
+                    )));
                 }
+                validate_costs(skip_costs).map_err(|e| {
+                    WushenError::Invalid(format!("奇遇事件 {} 放弃代价错误: {}", event.id, e))
+                })?;
                 for option in options {
-                    validate_adventure_option_result(&option.result).map_err(|e| {
-                        format!("奇遇事件 {} 选项 {} 错误: {}", event.id, option.id, e)
-                    })?;
+                    validate_costs(&option.costs)
+                        .and_then(|_| validate_adventure_option_result(&option.result))
+                        .map_err(|e| {
+                            WushenError::Invalid(format!(
+                                "奇遇事件 {} 选项 {} 错误: {}",
+                                event.id, option.id, e
+                            ))
+                        })?;
                 }
             }
-            AdventureEventContent::Battle { win, lose, .. } => {
+            AdventureEventContent::Battle {
+                enemy,
+                win,
+                lose,
+                handicaps,
+                environment,
+                ..
+            } => {
+                enemy.validate().map_err(|e| {
+                    WushenError::Invalid(format!("奇遇事件 {} 错误: {}", event.id, e))
+                })?;
+                validate_handicaps(handicaps).map_err(|e| {
+                    WushenError::Invalid(format!("奇遇事件 {} 错误: {}", event.id, e))
+                })?;
+                validate_environment(environment).map_err(|e| {
+                    WushenError::Invalid(format!("奇遇事件 {} 错误: {}", event.id, e))
+                })?;
                 if win.rewards.is_empty()
                     && lose.rewards.is_empty()
                     && win.text.is_none()
@@ -297,22 +596,88 @@ impl EventManager {
     }
 }
 
+/// 校验章节划分：章节 ID 唯一、事件存在，且每个事件恰好属于一个章节
+fn validate_chapters(
+    storyline: &Storyline,
+    event_map: &HashMap<String, &StoryEvent>,
+) -> WushenResult<()> {
+    if storyline.chapters.is_empty() {
+        return Ok(());
+    }
+    let mut chapter_ids = HashSet::new();
+    let mut owner: HashMap<&str, &str> = HashMap::new();
+    for chapter in &storyline.chapters {
+        if !chapter_ids.insert(chapter.id.as_str()) {
+            return Err(WushenError::Invalid(format!(
+                "剧情线 {} 存在重复章节ID: {}",
+                storyline.id, chapter.id
+            )));
+        }
+        if chapter.event_ids.is_empty() {
+            return Err(WushenError::Invalid(format!(
+                "章节 {} 未包含任何事件",
+                chapter.id
+            )));
+        }
+        for event_id in &chapter.event_ids {
+            if !event_map.contains_key(event_id) {
+                return Err(WushenError::Invalid(format!(
+                    "章节 {} 包含不存在的事件 {}",
+                    chapter.id, event_id
+                )));
+            }
+            if let Some(previous) = owner.insert(event_id, &chapter.id) {
+                return Err(WushenError::Invalid(format!(
+                    "事件 {} 同时属于章节 {} 与 {}",
+                    event_id, previous, chapter.id
+                )));
+            }
+        }
+    }
+    for event in &storyline.events {
+        if !owner.contains_key(event.id.as_str()) {
+            return Err(WushenError::Invalid(format!(
+                "事件 {} 未归属任何章节",
+                event.id
+            )));
+        }
+    }
+    Ok(())
+}
+
 fn validate_story_options(
     event: &StoryEvent,
     options: &[StoryOption],
     storyline: &Storyline,
-) -> Result<(), String> {
+) -> WushenResult<()> {
     if options.is_empty() {
-        return Err(format!("事件 {} 的选项不能为空", event.id));
+        return Err(WushenError::Invalid(format!(
+            "事件 {} 的选项不能为空",
+            event.id
+        )));
     }
 
-    let has_unconditional = options.iter().any(|o| o.condition.is_none());
+    // 无条件、无前置且无代价的选项保证玩家总有路可走（可为兜底选项）
+    let has_unconditional = options
+        .iter()
+        .any(|o| o.condition.is_none() && o.prerequisites.is_empty() && o.costs.is_empty());
     if !has_unconditional {
-        return Err(format!("事件 {} 至少需要一个无条件选项", event.id));
+        return Err(WushenError::Invalid(format!(
+            "事件 {} 至少需要一个无条件、无前置且无代价的选项（可添加兜底选项）",
+            event.id
+        )));
+    }
+    for option in options {
+        validate_costs(&option.costs).map_err(|e| {
+            WushenError::Invalid(format!("事件 {} 选项 {} 错误: {}", event.id, option.id, e))
+        })?;
     }
 
     if event.node_type == StoryNodeType::Start && options.iter().any(|o| o.condition.is_some()) {
-        return Err(format!("起始事件 {} 的选项不应包含条件", event.id));
+        return Err(WushenError::Invalid(format!(
+            "起始事件 {} 的选项不应包含条件",
+            event.id
+        )));
     }
 
     // 保留 storyline 参数用于未来扩展（避免未使用警告）
@@ -320,10 +685,64 @@ fn validate_story_options(
     Ok(())
 }
 
-fn validate_adventure_option_result(result: &AdventureOptionResult) -> Result<(), String> {
+fn validate_costs(costs: &[OptionCost]) -> WushenResult<()> {
+    for cost in costs {
+        cost.validate()
+            .map_err(|e| WushenError::Invalid(format!("代价「{}」无效: {}", cost.describe(), e)))?;
+    }
+    Ok(())
+}
+
+fn validate_handicaps(handicaps: &[BattleHandicap]) -> WushenResult<()> {
+    for handicap in handicaps {
+        handicap.validate().map_err(|e| {
+            WushenError::Invalid(format!(
+                "战斗限制「{}」无效: {}",
+                handicap.display_name(),
+                e
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+/// 环境变量名只能由小写字母、数字与下划线组成（公式中以 `env_` 前缀引用），取值须为有限数
+pub(crate) fn validate_environment(environment: &BTreeMap<String, f64>) -> WushenResult<()> {
+    for (name, value) in environment {
+        let valid_name = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid_name {
+            return Err(WushenError::Invalid(format!(
+                "环境变量名「{}」无效：只能使用小写字母、数字与下划线",
+                name
+            )));
+        }
+        if !value.is_finite() {
+            return Err(WushenError::Invalid(format!(
+                "环境变量 {} 的取值无效: {}",
+                name, value
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn validate_adventure_option_result(result: &AdventureOptionResult) -> WushenResult<()> {
     match result {
         AdventureOptionResult::Story { .. } => Ok(()),
-        AdventureOptionResult::Battle { win, lose, .. } => {
+        AdventureOptionResult::Battle {
+            enemy,
+            win,
+            lose,
+            handicaps,
+            environment,
+            ..
+        } => {
+            enemy.validate()?;
+            validate_handicaps(handicaps)?;
+            validate_environment(environment)?;
             if win.rewards.is_empty()
                 && lose.rewards.is_empty()
                 && win.text.is_none()
@@ -341,3 +760,87 @@ impl Default for EventManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::panel::ThreeDimensional;
+
+    #[test]
+    fn fallback_option_appears_only_when_others_are_blocked() {
+        let options: Vec<StoryOption> = serde_json::from_str(
+            r#"[
+                {"id":"a","text":"","next_event_id":"end","prerequisites":[{"type":"flag","flag":"拜师"}]},
+                {"id":"b","text":"","next_event_id":"end","costs":[{"type":"qi","amount":10}]},
+                {"id":"fallback","text":"","next_event_id":"end","fallback":true}
+            ]"#,
+        )
+        .unwrap();
+        let mut panel = CharacterPanel::new("少侠".to_string(), ThreeDimensional::new(5, 5, 5));
+        panel.qi = 0.0;
+        let manuals = ManualManager::new();
+        let ids = |flags: &[String], panel: &CharacterPanel| {
+            let history = RunHistory {
+                story_flags: flags,
+                ..RunHistory::default()
+            };
+            EventManager::available_story_options(&options, panel, &manuals, &history)
+                .iter()
+                .map(|o| o.id.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(&[], &panel), ["fallback"]);
+        assert_eq!(ids(&["拜师".to_string()], &panel), ["a"]);
+
+        let blockers = EventManager::story_option_blockers(
+            &options[0],
+            &panel,
+            &manuals,
+            &RunHistory::default(),
+        );
+        assert_eq!(blockers, ["需要剧情标记 拜师"]);
+        let blockers = EventManager::story_option_blockers(
+            &options[1],
+            &panel,
+            &manuals,
+            &RunHistory::default(),
+        );
+        assert_eq!(blockers.len(), 1);
+        assert!(blockers[0].starts_with("无法支付代价"), "{:?}", blockers);
+    }
+
+    #[test]
+    fn decisions_require_an_unconditionally_reachable_option() {
+        let decision = |last: &str| {
+            let json = format!(
+                r#"{{"id":"s","name":"测试","start_event_id":"start","events":[
+                    {{"id":"start","name":"开端","node_type":"start",
+                      "content":{{"type":"story","text":"","next_event_id":"choice"}}}},
+                    {{"id":"choice","name":"抉择","node_type":"middle","content":{{"type":"decision","text":"","options":[
+                        {{"id":"a","text":"","next_event_id":"end","prerequisites":[{{"type":"flag","flag":"拜师"}}]}},
+                        {}
+                    ]}}}},
+                    {{"id":"end","name":"终局","node_type":"end","content":{{"type":"end","text":""}}}}
+                ]}}"#,
+                last
+            );
+            serde_json::from_str::<Storyline>(&json).unwrap()
+        };
+
+        let gated = decision(
+            r#"{"id":"b","text":"","next_event_id":"end","costs":[{"type":"qi","amount":10}]}"#,
+        );
+        let err = EventManager::validate_storyline(&gated).unwrap_err();
+        assert_eq!(err.code(), "invalid");
+        assert!(
+            err.message().contains("事件 choice 至少需要一个无条件"),
+            "{}",
+            err
+        );
+
+        let with_fallback =
+            decision(r#"{"id":"b","text":"","next_event_id":"end","fallback":true}"#);
+        EventManager::validate_storyline(&with_fallback).unwrap();
+    }
+}
@@ -1,15 +1,30 @@
-pub mod manager;
-pub mod parser;
-pub mod reward;
-pub mod types;
+pub(crate) mod cost;
+pub(crate) mod enemy_manager;
+pub(crate) mod lint;
+pub(crate) mod manager;
+pub(crate) mod parser;
+pub(crate) mod reward;
+pub(crate) mod stats;
+pub(crate) mod types;
 
 pub use types::{
     AdventureEvent, AdventureEventContent, AdventureOption, AdventureOptionResult,
-    AdventureOutcome, EnemyTemplate, ManualKind, OwnedManualTemplate, Reward, RewardTarget,
-    StoryBattleBranch, StoryEvent, StoryEventContent, StoryNodeType, StoryOption, Storyline,
-    ThreeDimensionalTemplate,
+    AdventureOutcome, BattleHandicap, BattleInterruption, BattleSide, Chapter, CheckStat,
+    CheckTier, DefeatPolicy, EchoEncounter, EnemyPhase, EnemyTemplate, InterruptCondition,
+    ManualKind, OptionCost, OwnedManualTemplate, PostBattleRecovery, Presentation, Reward,
+    RewardTarget, StatCheck, StoryBattleBranch, StoryCheckBranch, StoryEvent, StoryEventContent,
+    StoryNodeType, StoryOption, StoryPrerequisite, Storyline, ThreeDimensionalTemplate,
+    VictoryDecision, VictoryOption,
 };
 
-pub use manager::EventManager;
+pub use cost::{apply_costs, can_afford_costs};
+pub use enemy_manager::{EnemyDefinition, EnemyManager};
+pub use lint::ConditionLinter;
+pub use manager::{EventManager, RunHistory};
 pub use parser::{parse_adventure_events, parse_storylines};
-pub use reward::apply_rewards;
+pub use reward::{
+    apply_rewards, apply_rewards_preview, apply_rewards_with_duplicates, AttributeChange,
+    DuplicatePolicy, DuplicateReward, DuplicateRewardConfig, DuplicateRewardKind, ManualGain,
+    RandomManualPreview, ResolvedRewards, RewardPreview,
+};
+pub use stats::{NodeTypeCounts, StorylineStats};
@@ -1,41 +1,74 @@
-use crate::event::types::{AdventureEvent, Storyline};
 /// 事件 JSON 解析器
-use serde::Deserialize;
-
-/// 剧情线数据文件结构
-#[derive(Debug, Deserialize)]
-pub struct StorylinesData {
-    pub storylines: Vec<Storyline>,
-}
-
-/// 奇遇事件数据文件结构
-#[derive(Debug, Deserialize)]
-pub struct AdventureEventsData {
-    pub adventures: Vec<AdventureEvent>,
-}
+use crate::error::{WushenError, WushenResult};
+use crate::event::enemy_manager::EnemyDefinition;
+use crate::event::types::{AdventureEvent, Storyline};
+use crate::pack::schema::CollectionKind;
+use crate::pack::strict::{parse_collection, parse_open_collection, ParseOptions};
+use crate::pack::warning::LoadWarning;
 
 /// 解析剧情线数据
 /// 支持两种格式：
 /// 1. 对象格式：{"storylines":[...]}
 /// 2. 数组格式：[...]
-pub fn parse_storylines(json: &str) -> Result<Vec<Storyline>, String> {
-    if let Ok(data) = serde_json::from_str::<StorylinesData>(json) {
-        return Ok(data.storylines);
-    }
-    let storylines: Vec<Storyline> =
-        serde_json::from_str(json).map_err(|e| format!("解析剧情线数据失败: {}", e))?;
-    Ok(storylines)
+pub fn parse_storylines(json: &str) -> WushenResult<Vec<Storyline>> {
+    parse_storylines_with_warnings(json).map(|(storylines, _)| storylines)
+}
+
+/// 解析剧情线数据，并返回数据版本升级产生的警告
+pub fn parse_storylines_with_warnings(
+    json: &str,
+) -> WushenResult<(Vec<Storyline>, Vec<LoadWarning>)> {
+    parse_storylines_with_options(json, ParseOptions::default())
+}
+
+/// 按解析选项解析剧情线数据
+pub fn parse_storylines_with_options(
+    json: &str,
+    options: ParseOptions,
+) -> WushenResult<(Vec<Storyline>, Vec<LoadWarning>)> {
+    parse_collection(json, CollectionKind::Storylines, options)
+        .map_err(|e| WushenError::ParseError(format!("解析剧情线数据失败: {}", e)))
 }
 
 /// 解析奇遇事件数据
 /// 支持两种格式：
 /// 1. 对象格式：{"adventures":[...]}
 /// 2. 数组格式：[...]
-pub fn parse_adventure_events(json: &str) -> Result<Vec<AdventureEvent>, String> {
-    if let Ok(data) = serde_json::from_str::<AdventureEventsData>(json) {
-        return Ok(data.adventures);
-    }
-    let adventures: Vec<AdventureEvent> =
-        serde_json::from_str(json).map_err(|e| format!("解析奇遇事件数据失败: {}", e))?;
-    Ok(adventures)
+pub fn parse_adventure_events(json: &str) -> WushenResult<Vec<AdventureEvent>> {
+    parse_adventure_events_with_warnings(json).map(|(adventures, _)| adventures)
+}
+
+/// 解析奇遇事件数据，并返回数据版本升级产生的警告
+pub fn parse_adventure_events_with_warnings(
+    json: &str,
+) -> WushenResult<(Vec<AdventureEvent>, Vec<LoadWarning>)> {
+    parse_adventure_events_with_options(json, ParseOptions::default())
+}
+
+/// 按解析选项解析奇遇事件数据
+pub fn parse_adventure_events_with_options(
+    json: &str,
+    options: ParseOptions,
+) -> WushenResult<(Vec<AdventureEvent>, Vec<LoadWarning>)> {
+    parse_collection(json, CollectionKind::Adventures, options)
+        .map_err(|e| WushenError::ParseError(format!("解析奇遇事件数据失败: {}", e)))
+}
+
+/// 解析敌人库数据
+/// 支持两种格式：
+/// 1. 对象格式：{"enemies":[...]}
+/// 2. 数组格式：[...]
+pub fn parse_enemies_with_warnings(
+    json: &str,
+) -> WushenResult<(Vec<EnemyDefinition>, Vec<LoadWarning>)> {
+    parse_enemies_with_options(json, ParseOptions::default())
+}
+
+/// 按解析选项解析敌人库数据（覆盖字段在继承解析后才确定，严格模式只检查 ID）
+pub fn parse_enemies_with_options(
+    json: &str,
+    options: ParseOptions,
+) -> WushenResult<(Vec<EnemyDefinition>, Vec<LoadWarning>)> {
+    parse_open_collection(json, CollectionKind::Enemies, options)
+        .map_err(|e| WushenError::ParseError(format!("解析敌人数据失败: {}", e)))
 }
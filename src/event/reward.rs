@@ -1,19 +1,190 @@
-/// 事件奖励应用逻辑
-use crate::character::panel::CharacterPanel;
+use crate::character::panel::{CharacterPanel, MIN_MAX_HP};
 use crate::character::trait_manager::TraitManager;
 use crate::cultivation::manual::Rarity;
 use crate::cultivation::manual_manager::ManualManager;
 use crate::effect::effect::Operation;
 use crate::effect::executor::EntryExecutor;
+/// 事件奖励应用逻辑
+use crate::error::{WushenError, WushenResult};
 use crate::event::types::{ManualKind, Reward, RewardTarget};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 可能重复获得的奖励类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateRewardKind {
+    Trait,
+    Internal,
+    AttackSkill,
+    DefenseSkill,
+}
+
+impl DuplicateRewardKind {
+    /// 奖励对应的类型与 ID（不会重复的奖励返回 None）
+    pub fn of(reward: &Reward) -> Option<(Self, &str)> {
+        match reward {
+            Reward::Trait { id } => Some((DuplicateRewardKind::Trait, id)),
+            Reward::Internal { id } => Some((DuplicateRewardKind::Internal, id)),
+            Reward::AttackSkill { id } => Some((DuplicateRewardKind::AttackSkill, id)),
+            Reward::DefenseSkill { id } => Some((DuplicateRewardKind::DefenseSkill, id)),
+            _ => None,
+        }
+    }
+
+    /// 角色是否已拥有该奖励
+    fn is_owned(&self, panel: &CharacterPanel, id: &str) -> bool {
+        match self {
+            DuplicateRewardKind::Trait => panel.traits.iter().any(|t| t == id),
+            DuplicateRewardKind::Internal => panel.has_internal(id),
+            DuplicateRewardKind::AttackSkill => panel.has_attack_skill(id),
+            DuplicateRewardKind::DefenseSkill => panel.has_defense_skill(id),
+        }
+    }
+}
+
+/// 重复奖励的处理策略
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DuplicatePolicy {
+    /// 跳过（缺省）
+    #[default]
+    Skip,
+    /// 转换为其他奖励
+    Convert { rewards: Vec<Reward> },
+    /// 叠加一层（仅特性，词条按层数重复生效）
+    Stack,
+}
+
+/// 重复奖励处理配置（剧情线可单独配置，未列出的类型跳过）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DuplicateRewardConfig {
+    policies: BTreeMap<DuplicateRewardKind, DuplicatePolicy>,
+}
+
+/// 一条重复奖励及实际采用的处理策略
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateReward {
+    pub reward: Reward,
+    pub policy: DuplicatePolicy,
+}
+
+/// 按重复策略处理后的奖励
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedRewards {
+    /// 实际发放的奖励（转换后的奖励替换原奖励）
+    pub rewards: Vec<Reward>,
+    /// 重复的奖励及处理方式
+    pub duplicates: Vec<DuplicateReward>,
+}
+
+impl DuplicateRewardConfig {
+    pub fn is_empty(&self) -> bool {
+        self.policies.is_empty()
+    }
+
+    /// 指定类型的处理策略
+    pub fn policy(&self, kind: DuplicateRewardKind) -> DuplicatePolicy {
+        self.policies.get(&kind).cloned().unwrap_or_default()
+    }
+
+    /// 是否允许特性叠加
+    pub fn stacks_traits(&self) -> bool {
+        matches!(
+            self.policies.get(&DuplicateRewardKind::Trait),
+            Some(DuplicatePolicy::Stack)
+        )
+    }
+
+    /// 校验配置（叠加仅适用于特性，转换奖励不可再含可重复的奖励）
+    pub fn validate(&self) -> WushenResult<()> {
+        for (kind, policy) in &self.policies {
+            match policy {
+                DuplicatePolicy::Stack if *kind != DuplicateRewardKind::Trait => {
+                    return Err(WushenError::Invalid(format!("{:?} 奖励不支持叠加", kind)));
+                }
+                DuplicatePolicy::Convert { rewards } => {
+                    if rewards.is_empty() {
+                        return Err(WushenError::Invalid(format!(
+                            "{:?} 奖励的转换奖励不能为空",
+                            kind
+                        )));
+                    }
+                    if rewards
+                        .iter()
+                        .any(|reward| DuplicateRewardKind::of(reward).is_some())
+                    {
+                        return Err(WushenError::Invalid(format!(
+                            "{:?} 奖励的转换奖励不能包含特性或功法",
+                            kind
+                        )));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// 按策略处理角色已拥有（或本批奖励中已出现）的奖励
+    pub fn resolve(&self, panel: &CharacterPanel, rewards: Vec<Reward>) -> ResolvedRewards {
+        let mut resolved = ResolvedRewards::default();
+        let mut granted: Vec<(DuplicateRewardKind, String)> = Vec::new();
+        for reward in rewards {
+            let Some((kind, id)) = DuplicateRewardKind::of(&reward) else {
+                resolved.rewards.push(reward);
+                continue;
+            };
+            let duplicate =
+                kind.is_owned(panel, id) || granted.iter().any(|(k, g)| *k == kind && g == id);
+            granted.push((kind, id.to_string()));
+            if !duplicate {
+                resolved.rewards.push(reward);
+                continue;
+            }
+            let policy = self.policy(kind);
+            match &policy {
+                DuplicatePolicy::Skip => {}
+                DuplicatePolicy::Convert { rewards } => {
+                    resolved.rewards.extend(rewards.iter().cloned())
+                }
+                DuplicatePolicy::Stack => resolved.rewards.push(reward.clone()),
+            }
+            resolved.duplicates.push(DuplicateReward { reward, policy });
+        }
+        resolved
+    }
+}
 
-/// 应用奖励到角色面板
+/// 应用奖励到角色面板（已拥有的特性与功法跳过）
 pub fn apply_rewards(
     panel: &mut CharacterPanel,
     rewards: &[Reward],
     manual_manager: Option<&ManualManager>,
     trait_manager: Option<&TraitManager>,
-) -> Result<(), String> {
+) -> WushenResult<()> {
+    apply_rewards_with_duplicates(
+        panel,
+        rewards,
+        manual_manager,
+        trait_manager,
+        &DuplicateRewardConfig::default(),
+        &[],
+    )
+}
+
+/// 应用奖励到角色面板，按重复策略决定已拥有的特性是否叠加
+///
+/// `excluded_manuals` 中的功法不会通过奖励获得（如挑战规则禁用的功法）
+pub fn apply_rewards_with_duplicates(
+    panel: &mut CharacterPanel,
+    rewards: &[Reward],
+    manual_manager: Option<&ManualManager>,
+    trait_manager: Option<&TraitManager>,
+    duplicates: &DuplicateRewardConfig,
+    excluded_manuals: &[String],
+) -> WushenResult<()> {
     for reward in rewards {
         match reward {
             Reward::Attribute {
@@ -25,12 +196,12 @@ pub fn apply_rewards(
                 apply_attribute_reward(panel, *target, *value, *operation, *can_exceed_limit)?;
             }
             Reward::Trait { id } => {
-                if panel.traits.contains(id) {
+                if panel.traits.contains(id) && !duplicates.stacks_traits() {
                     continue;
                 }
                 if let Some(manager) = trait_manager {
                     if manager.get_trait(id).is_none() {
-                        return Err(format!("特性 {} 不存在", id));
+                        return Err(WushenError::NotFound(format!("特性 {} 不存在", id)));
                     }
                 }
                 panel.traits.push(id.clone());
@@ -38,37 +209,50 @@ pub fn apply_rewards(
             Reward::StartTraitPool { .. } => {
                 // 开局特性池奖励由上层处理，这里不影响角色面板
             }
+            Reward::Item { id, count } => panel.add_item(id, *count),
+            Reward::TimedBuff { .. }
+            | Reward::StoryFlag { .. }
+            | Reward::Title { .. }
+            | Reward::ScheduleEvent { .. } => {
+                // 限时增益、剧情标记、称号与预约奇遇记录在存档角色上，由上层处理
+            }
             Reward::Internal { id } => {
                 if let Some(manager) = manual_manager {
-                    if panel.has_internal(id) {
+                    if panel.has_internal(id) || excluded_manuals.contains(id) {
                         continue;
                     }
                     let mut executor = executor_for_reading(trait_manager, panel);
                     manager.acquire_internal_with_reading(id, panel, executor.as_mut())?;
                 } else {
-                    return Err("未提供 ManualManager，无法发放内功奖励".to_string());
+                    return Err(WushenError::InvalidState(
+                        "未提供 ManualManager，无法发放内功奖励".to_string(),
+                    ));
                 }
             }
             Reward::AttackSkill { id } => {
                 if let Some(manager) = manual_manager {
-                    if panel.has_attack_skill(id) {
+                    if panel.has_attack_skill(id) || excluded_manuals.contains(id) {
                         continue;
                     }
                     let mut executor = executor_for_reading(trait_manager, panel);
                     manager.acquire_attack_skill_with_reading(id, panel, executor.as_mut())?;
                 } else {
-                    return Err("未提供 ManualManager，无法发放攻击武技奖励".to_string());
+                    return Err(WushenError::InvalidState(
+                        "未提供 ManualManager，无法发放攻击武技奖励".to_string(),
+                    ));
                 }
             }
             Reward::DefenseSkill { id } => {
                 if let Some(manager) = manual_manager {
-                    if panel.has_defense_skill(id) {
+                    if panel.has_defense_skill(id) || excluded_manuals.contains(id) {
                         continue;
                     }
                     let mut executor = executor_for_reading(trait_manager, panel);
                     manager.acquire_defense_skill_with_reading(id, panel, executor.as_mut())?;
                 } else {
-                    return Err("未提供 ManualManager，无法发放防御武技奖励".to_string());
+                    return Err(WushenError::InvalidState(
+                        "未提供 ManualManager，无法发放防御武技奖励".to_string(),
+                    ));
                 }
             }
             Reward::RandomManual {
@@ -77,8 +261,11 @@ pub fn apply_rewards(
                 manual_type,
                 count,
             } => {
-                let manager = manual_manager
-                    .ok_or_else(|| "未提供 ManualManager，无法发放随机功法奖励".to_string())?;
+                let manager = manual_manager.ok_or_else(|| {
+                    WushenError::InvalidState(
+                        "未提供 ManualManager，无法发放随机功法奖励".to_string(),
+                    )
+                })?;
                 let mut remaining = *count;
                 while remaining > 0 {
                     let candidate = match draw_random_manual(
@@ -87,6 +274,7 @@ pub fn apply_rewards(
                         *manual_kind,
                         *rarity,
                         manual_type.as_deref(),
+                        excluded_manuals,
                     ) {
                         Ok(candidate) => candidate,
                         Err(_) => break,
@@ -123,31 +311,178 @@ pub fn apply_rewards(
     Ok(())
 }
 
-fn apply_attribute_reward(
+/// 奖励可修改的角色属性
+const ATTRIBUTE_TARGETS: [RewardTarget; 7] = [
+    RewardTarget::Comprehension,
+    RewardTarget::BoneStructure,
+    RewardTarget::Physique,
+    RewardTarget::MartialArtsAttainment,
+    RewardTarget::MaxHp,
+    RewardTarget::BaseAttack,
+    RewardTarget::BaseDefense,
+];
+
+/// 发放奖励将带来的变化（预览，不修改角色面板）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RewardPreview {
+    /// 获得的功法及初始境界
+    pub manuals: Vec<ManualGain>,
+    /// 获得的特性（叠加的特性重复列出）
+    pub traits: Vec<String>,
+    /// 属性变化（含武学素养）
+    pub attributes: Vec<AttributeChange>,
+    /// 内息上限变化
+    pub max_qi_delta: f64,
+    /// 当前内息变化
+    pub qi_delta: f64,
+    /// 随机功法（具体功法在发放时抽取）
+    pub random_manuals: Vec<RandomManualPreview>,
+}
+
+/// 获得的功法
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ManualGain {
+    pub manual_kind: ManualKind,
+    pub id: String,
+    pub level: u32,
+    pub exp: f64,
+}
+
+/// 属性变化
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AttributeChange {
+    pub target: RewardTarget,
+    pub before: f64,
+    pub after: f64,
+}
+
+/// 随机功法奖励
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RandomManualPreview {
+    pub manual_kind: ManualKind,
+    pub rarity: Option<u32>,
+    pub manual_type: Option<String>,
+    pub count: u32,
+    /// 当前可抽取的功法数量
+    pub candidates: usize,
+}
+
+/// 预览奖励发放结果：在面板副本上发放固定奖励并比较前后差异，
+/// 随机功法只列出抽取条件与候选数量
+pub fn apply_rewards_preview(
+    panel: &CharacterPanel,
+    rewards: &[Reward],
+    manual_manager: Option<&ManualManager>,
+    trait_manager: Option<&TraitManager>,
+    duplicates: &DuplicateRewardConfig,
+    excluded_manuals: &[String],
+) -> WushenResult<RewardPreview> {
+    let mut preview = RewardPreview::default();
+    let mut fixed = Vec::new();
+    for reward in rewards {
+        match reward {
+            Reward::RandomManual {
+                manual_kind,
+                rarity,
+                manual_type,
+                count,
+            } => preview.random_manuals.push(RandomManualPreview {
+                manual_kind: *manual_kind,
+                rarity: *rarity,
+                manual_type: manual_type.clone(),
+                count: *count,
+                candidates: manual_manager
+                    .map(|manager| {
+                        count_available_manuals(
+                            manager,
+                            panel,
+                            *manual_kind,
+                            *rarity,
+                            manual_type.as_deref(),
+                            excluded_manuals,
+                        )
+                    })
+                    .unwrap_or(0),
+            }),
+            _ => fixed.push(reward.clone()),
+        }
+    }
+
+    let mut after = panel.clone();
+    apply_rewards_with_duplicates(
+        &mut after,
+        &fixed,
+        manual_manager,
+        trait_manager,
+        duplicates,
+        excluded_manuals,
+    )?;
+
+    let gained = [
+        (
+            ManualKind::Internal,
+            &panel.owned_internals,
+            &after.owned_internals,
+        ),
+        (
+            ManualKind::AttackSkill,
+            &panel.owned_attack_skills,
+            &after.owned_attack_skills,
+        ),
+        (
+            ManualKind::DefenseSkill,
+            &panel.owned_defense_skills,
+            &after.owned_defense_skills,
+        ),
+    ];
+    for (manual_kind, before, owned) in gained {
+        for (id, (level, exp)) in owned {
+            if !before.contains_key(id) {
+                preview.manuals.push(ManualGain {
+                    manual_kind,
+                    id: id.clone(),
+                    level: *level,
+                    exp: *exp,
+                });
+            }
+        }
+    }
+    preview.traits = after.traits[panel.traits.len().min(after.traits.len())..].to_vec();
+    preview.attributes = ATTRIBUTE_TARGETS
+        .iter()
+        .map(|target| AttributeChange {
+            target: *target,
+            before: attribute_value(panel, *target),
+            after: attribute_value(&after, *target),
+        })
+        .filter(|change| change.before != change.after)
+        .collect();
+    preview.max_qi_delta = after.max_qi - panel.max_qi;
+    preview.qi_delta = after.qi - panel.qi;
+    Ok(preview)
+}
+
+pub(crate) fn attribute_value(panel: &CharacterPanel, target: RewardTarget) -> f64 {
+    match target {
+        RewardTarget::Comprehension => panel.three_d.comprehension as f64,
+        RewardTarget::BoneStructure => panel.three_d.bone_structure as f64,
+        RewardTarget::Physique => panel.three_d.physique as f64,
+        RewardTarget::MartialArtsAttainment => panel.martial_arts_attainment,
+        RewardTarget::MaxHp => panel.max_hp,
+        RewardTarget::BaseAttack => panel.base_attack,
+        RewardTarget::BaseDefense => panel.base_defense,
+    }
+}
+
+pub(crate) fn apply_attribute_reward(
     panel: &mut CharacterPanel,
     target: RewardTarget,
     value: f64,
     operation: Operation,
     can_exceed_limit: bool,
-) -> Result<(), String> {
-    let (current, new_value) = match target {
-        RewardTarget::Comprehension => {
-            let current = panel.three_d.comprehension as f64;
-            (current, apply_operation(current, value, operation))
-        }
-        RewardTarget::BoneStructure => {
-            let current = panel.three_d.bone_structure as f64;
-            (current, apply_operation(current, value, operation))
-        }
-        RewardTarget::Physique => {
-            let current = panel.three_d.physique as f64;
-            (current, apply_operation(current, value, operation))
-        }
-        RewardTarget::MartialArtsAttainment => {
-            let current = panel.martial_arts_attainment;
-            (current, apply_operation(current, value, operation))
-        }
-    };
+) -> WushenResult<()> {
+    let current = attribute_value(panel, target);
+    let new_value = apply_operation(current, value, operation);
 
     // 限制处理
     if !can_exceed_limit {
@@ -170,11 +505,24 @@ fn apply_attribute_reward(
         }
         RewardTarget::Physique => {
             let limit = get_attribute_limit(target, panel).unwrap_or(f64::INFINITY);
-            panel.three_d.physique = new_value.max(0.0).min(limit) as u32;
+            panel.set_physique(new_value.max(0.0).min(limit) as u32);
         }
         RewardTarget::MartialArtsAttainment => {
             panel.martial_arts_attainment = new_value.max(0.0);
         }
+        RewardTarget::MaxHp => {
+            // 生命上限变化时，当前生命同步增减
+            panel.max_hp = new_value.max(MIN_MAX_HP);
+            panel.hp = (panel.hp + panel.max_hp - current)
+                .max(0.0)
+                .min(panel.max_hp);
+        }
+        RewardTarget::BaseAttack => {
+            panel.base_attack = new_value.max(0.0);
+        }
+        RewardTarget::BaseDefense => {
+            panel.base_defense = new_value.max(0.0);
+        }
     }
 
     Ok(())
@@ -218,14 +566,15 @@ fn draw_random_manual(
     manual_kind: ManualKind,
     rarity: Option<u32>,
     manual_type: Option<&str>,
-) -> Result<ManualCandidate, String> {
+    excluded: &[String],
+) -> WushenResult<ManualCandidate> {
     let mut pool: Vec<ManualCandidate> = Vec::new();
 
     match manual_kind {
         ManualKind::Internal => {
             for manual in manager.all_internals() {
                 let id = &manual.manual.id;
-                if panel.has_internal(id) {
+                if panel.has_internal(id) || excluded.contains(id) {
                     continue;
                 }
                 try_add_manual(
@@ -241,7 +590,7 @@ fn draw_random_manual(
         ManualKind::AttackSkill => {
             for manual in manager.all_attack_skills() {
                 let id = &manual.manual.id;
-                if panel.has_attack_skill(id) {
+                if panel.has_attack_skill(id) || excluded.contains(id) {
                     continue;
                 }
                 try_add_manual(
@@ -257,7 +606,7 @@ fn draw_random_manual(
         ManualKind::DefenseSkill => {
             for manual in manager.all_defense_skills() {
                 let id = &manual.manual.id;
-                if panel.has_defense_skill(id) {
+                if panel.has_defense_skill(id) || excluded.contains(id) {
                     continue;
                 }
                 try_add_manual(
@@ -273,7 +622,7 @@ fn draw_random_manual(
         ManualKind::Any => {
             for manual in manager.all_internals() {
                 let id = &manual.manual.id;
-                if panel.has_internal(id) {
+                if panel.has_internal(id) || excluded.contains(id) {
                     continue;
                 }
                 try_add_manual(
@@ -287,7 +636,7 @@ fn draw_random_manual(
             }
             for manual in manager.all_attack_skills() {
                 let id = &manual.manual.id;
-                if panel.has_attack_skill(id) {
+                if panel.has_attack_skill(id) || excluded.contains(id) {
                     continue;
                 }
                 try_add_manual(
@@ -301,7 +650,7 @@ fn draw_random_manual(
             }
             for manual in manager.all_defense_skills() {
                 let id = &manual.manual.id;
-                if panel.has_defense_skill(id) {
+                if panel.has_defense_skill(id) || excluded.contains(id) {
                     continue;
                 }
                 try_add_manual(
@@ -317,7 +666,9 @@ fn draw_random_manual(
     }
 
     if pool.is_empty() {
-        return Err("随机功法奖池为空，无法抽取".to_string());
+        return Err(WushenError::ConditionNotMet(
+            "随机功法奖池为空，无法抽取".to_string(),
+        ));
     }
 
     let idx = random_index(pool.len());
@@ -330,6 +681,7 @@ pub fn count_available_manuals(
     manual_kind: ManualKind,
     rarity: Option<u32>,
     manual_type: Option<&str>,
+    excluded: &[String],
 ) -> usize {
     let mut count = 0;
 
@@ -337,7 +689,7 @@ pub fn count_available_manuals(
         ManualKind::Internal => {
             for manual in manager.all_internals() {
                 let id = &manual.manual.id;
-                if panel.has_internal(id) {
+                if panel.has_internal(id) || excluded.contains(id) {
                     continue;
                 }
                 if matches_filters(
@@ -353,7 +705,7 @@ pub fn count_available_manuals(
         ManualKind::AttackSkill => {
             for manual in manager.all_attack_skills() {
                 let id = &manual.manual.id;
-                if panel.has_attack_skill(id) {
+                if panel.has_attack_skill(id) || excluded.contains(id) {
                     continue;
                 }
                 if matches_filters(
@@ -369,7 +721,7 @@ pub fn count_available_manuals(
         ManualKind::DefenseSkill => {
             for manual in manager.all_defense_skills() {
                 let id = &manual.manual.id;
-                if panel.has_defense_skill(id) {
+                if panel.has_defense_skill(id) || excluded.contains(id) {
                     continue;
                 }
                 if matches_filters(
@@ -385,7 +737,7 @@ pub fn count_available_manuals(
         ManualKind::Any => {
             for manual in manager.all_internals() {
                 let id = &manual.manual.id;
-                if panel.has_internal(id) {
+                if panel.has_internal(id) || excluded.contains(id) {
                     continue;
                 }
                 if matches_filters(
@@ -399,7 +751,7 @@ pub fn count_available_manuals(
             }
             for manual in manager.all_attack_skills() {
                 let id = &manual.manual.id;
-                if panel.has_attack_skill(id) {
+                if panel.has_attack_skill(id) || excluded.contains(id) {
                     continue;
                 }
                 if matches_filters(
@@ -413,7 +765,7 @@ pub fn count_available_manuals(
             }
             for manual in manager.all_defense_skills() {
                 let id = &manual.manual.id;
-                if panel.has_defense_skill(id) {
+                if panel.has_defense_skill(id) || excluded.contains(id) {
                     continue;
                 }
                 if matches_filters(
@@ -490,3 +842,142 @@ fn random_index(len: usize) -> usize {
         .unwrap_or(0);
     (nanos as usize) % len
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::panel::{ThreeDimensional, HP_PER_PHYSIQUE};
+
+    #[test]
+    fn duplicate_policies_skip_convert_and_stack() {
+        let mut panel = CharacterPanel::new("a".to_string(), ThreeDimensional::new(10, 10, 10));
+        panel.traits.push("铁骨".to_string());
+        let rewards = vec![
+            Reward::Trait {
+                id: "铁骨".to_string(),
+            },
+            Reward::Trait {
+                id: "灵犀".to_string(),
+            },
+            Reward::Trait {
+                id: "灵犀".to_string(),
+            },
+        ];
+
+        let skip = DuplicateRewardConfig::default().resolve(&panel, rewards.clone());
+        assert_eq!(skip.rewards.len(), 1);
+        assert_eq!(skip.duplicates.len(), 2);
+
+        let convert: DuplicateRewardConfig = serde_json::from_str(
+            r#"{"trait":{"type":"convert","rewards":[{"type":"attribute","target":"comprehension","value":1,"operation":"add"}]}}"#,
+        )
+        .unwrap();
+        convert.validate().unwrap();
+        let converted = convert.resolve(&panel, rewards.clone());
+        assert_eq!(converted.rewards.len(), 3);
+        assert!(matches!(
+            converted.duplicates[0].policy,
+            DuplicatePolicy::Convert { .. }
+        ));
+
+        let stack: DuplicateRewardConfig =
+            serde_json::from_str(r#"{"trait":{"type":"stack"}}"#).unwrap();
+        let stacked = stack.resolve(&panel, rewards);
+        apply_rewards_with_duplicates(&mut panel, &stacked.rewards, None, None, &stack, &[])
+            .unwrap();
+        assert_eq!(panel.traits.iter().filter(|t| *t == "铁骨").count(), 2);
+        assert_eq!(panel.traits.iter().filter(|t| *t == "灵犀").count(), 2);
+
+        let invalid: DuplicateRewardConfig =
+            serde_json::from_str(r#"{"internal":{"type":"stack"}}"#).unwrap();
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn physique_reward_moves_max_and_current_hp_together() {
+        let mut panel = CharacterPanel::new("a".to_string(), ThreeDimensional::new(10, 10, 10));
+        panel.max_hp += 50.0;
+        panel.hp = 300.0;
+        let gain: Vec<Reward> = serde_json::from_str(
+            r#"[{"type":"attribute","target":"physique","value":3,"operation":"add"}]"#,
+        )
+        .unwrap();
+
+        apply_rewards(&mut panel, &gain, None, None).unwrap();
+
+        assert_eq!(panel.three_d.physique, 13);
+        assert_eq!(panel.max_hp, 13.0 * HP_PER_PHYSIQUE + 50.0);
+        assert_eq!(panel.hp, 300.0 + 3.0 * HP_PER_PHYSIQUE);
+
+        let loss: Vec<Reward> = serde_json::from_str(
+            r#"[{"type":"attribute","target":"physique","value":5,"operation":"subtract"}]"#,
+        )
+        .unwrap();
+
+        apply_rewards(&mut panel, &loss, None, None).unwrap();
+
+        assert_eq!(panel.three_d.physique, 8);
+        assert_eq!(panel.max_hp, 8.0 * HP_PER_PHYSIQUE + 50.0);
+        assert_eq!(panel.hp, 100.0);
+    }
+
+    #[test]
+    fn preview_reports_changes_without_mutating_panel() {
+        let panel = CharacterPanel::new("a".to_string(), ThreeDimensional::new(10, 10, 10));
+        let rewards: Vec<Reward> = serde_json::from_str(
+            r#"[
+                {"type":"attribute","target":"physique","value":2,"operation":"add"},
+                {"type":"attribute","target":"martial_arts_attainment","value":5,"operation":"add"},
+                {"type":"trait","id":"灵犀"},
+                {"type":"random_manual","manual_kind":"internal","count":2}
+            ]"#,
+        )
+        .unwrap();
+
+        let preview = apply_rewards_preview(
+            &panel,
+            &rewards,
+            Some(&ManualManager::new()),
+            None,
+            &DuplicateRewardConfig::default(),
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(preview.traits, ["灵犀"]);
+        assert_eq!(
+            preview.attributes,
+            [
+                AttributeChange {
+                    target: RewardTarget::Physique,
+                    before: 10.0,
+                    after: 12.0,
+                },
+                AttributeChange {
+                    target: RewardTarget::MartialArtsAttainment,
+                    before: 0.0,
+                    after: 5.0,
+                },
+                AttributeChange {
+                    target: RewardTarget::MaxHp,
+                    before: 1000.0,
+                    after: 1200.0,
+                },
+                AttributeChange {
+                    target: RewardTarget::BaseAttack,
+                    before: 30.0,
+                    after: 36.0,
+                },
+                AttributeChange {
+                    target: RewardTarget::BaseDefense,
+                    before: 20.0,
+                    after: 24.0,
+                },
+            ]
+        );
+        assert_eq!(preview.random_manuals[0].count, 2);
+        assert_eq!(preview.random_manuals[0].candidates, 0);
+        assert!(panel.traits.is_empty());
+        assert_eq!(panel.three_d.physique, 10);
+    }
+}
@@ -0,0 +1,212 @@
+/// 剧情线统计
+/// 供剧情线选择界面估计篇幅、编辑器展示概要：各类事件数量、结局数、敌人数与行动点范围
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+
+use super::types::{StoryEvent, StoryEventContent, StoryNodeType, Storyline};
+
+/// 剧情线统计
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StorylineStats {
+    pub storyline_id: String,
+    pub name: String,
+    /// 事件总数
+    pub event_count: usize,
+    /// 各节点类型的事件数
+    pub node_types: NodeTypeCounts,
+    /// 战斗事件数
+    pub battle_count: usize,
+    /// 结局数
+    pub ending_count: usize,
+    /// 不同敌人的数量（按敌人库 ID，未引用敌人库时按敌人名）
+    pub enemy_count: usize,
+    /// 从起始事件到结局沿途获得的行动点（最少 / 最多）
+    pub min_action_points: u32,
+    pub max_action_points: u32,
+}
+
+/// 各节点类型的事件数
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NodeTypeCounts {
+    pub start: usize,
+    pub middle: usize,
+    pub end: usize,
+}
+
+impl Storyline {
+    /// 统计剧情线（剧情线须已通过校验：事件 ID 唯一且不存在环）
+    pub fn stats(&self) -> StorylineStats {
+        let mut node_types = NodeTypeCounts::default();
+        let mut battle_count = 0;
+        let mut enemies = BTreeSet::new();
+        for event in &self.events {
+            match event.node_type {
+                StoryNodeType::Start => node_types.start += 1,
+                StoryNodeType::Middle => node_types.middle += 1,
+                StoryNodeType::End => node_types.end += 1,
+            }
+            if let StoryEventContent::Battle {
+                enemy_id,
+                enemy,
+                enemies: others,
+                ..
+            } = &event.content
+            {
+                battle_count += 1;
+                enemies.insert(enemy_id.clone().unwrap_or_else(|| enemy.name.clone()));
+                for other in others {
+                    enemies.insert(other.enemy_id.clone().unwrap_or_else(|| other.name.clone()));
+                }
+            }
+        }
+
+        let events: HashMap<&str, &StoryEvent> = self
+            .events
+            .iter()
+            .map(|event| (event.id.as_str(), event))
+            .collect();
+        let mut ranges = HashMap::new();
+        let (min_action_points, max_action_points) =
+            action_point_range(&self.start_event_id, &events, &mut ranges);
+
+        StorylineStats {
+            storyline_id: self.id.clone(),
+            name: self.name.clone(),
+            event_count: self.events.len(),
+            ending_count: node_types.end,
+            node_types,
+            battle_count,
+            enemy_count: enemies.len(),
+            min_action_points,
+            max_action_points,
+        }
+    }
+}
+
+/// 从指定事件走到结局沿途获得的行动点范围（含该事件自身）
+fn action_point_range<'a>(
+    event_id: &'a str,
+    events: &HashMap<&'a str, &'a StoryEvent>,
+    ranges: &mut HashMap<&'a str, (u32, u32)>,
+) -> (u32, u32) {
+    if let Some(range) = ranges.get(event_id) {
+        return *range;
+    }
+    let Some(event) = events.get(event_id) else {
+        return (0, 0);
+    };
+    let mut range: Option<(u32, u32)> = None;
+    for next in next_event_ids(&event.content) {
+        let (min, max) = action_point_range(next, events, ranges);
+        range = Some(match range {
+            Some((lo, hi)) => (lo.min(min), hi.max(max)),
+            None => (min, max),
+        });
+    }
+    let (min, max) = range.unwrap_or((0, 0));
+    let range = (
+        min.saturating_add(event.action_points),
+        max.saturating_add(event.action_points),
+    );
+    ranges.insert(event_id, range);
+    range
+}
+
+/// 事件的全部后续事件
+fn next_event_ids(content: &StoryEventContent) -> Vec<&str> {
+    match content {
+        StoryEventContent::Decision { options, .. } => options
+            .iter()
+            .map(|option| option.next_event_id.as_str())
+            .collect(),
+        StoryEventContent::Battle {
+            win,
+            lose,
+            draw,
+            interruptions,
+            victory_decision,
+            ..
+        } => [win, lose]
+            .into_iter()
+            .chain(draw.as_ref())
+            .map(|branch| branch.next_event_id.as_str())
+            .chain(
+                interruptions
+                    .iter()
+                    .map(|interruption| interruption.next_event_id.as_str()),
+            )
+            .chain(
+                victory_decision
+                    .iter()
+                    .flat_map(|decision| &decision.options)
+                    .filter_map(|option| option.next_event_id.as_deref()),
+            )
+            .collect(),
+        StoryEventContent::Story { next_event_id, .. } => {
+            next_event_id.as_deref().into_iter().collect()
+        }
+        StoryEventContent::Check {
+            critical_success,
+            success,
+            failure,
+            ..
+        } => critical_success
+            .iter()
+            .chain([success, failure])
+            .map(|branch| branch.next_event_id.as_str())
+            .collect(),
+        StoryEventContent::End { .. } => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_count_events_enemies_and_action_point_range() {
+        let enemy = |name: &str| {
+            serde_json::json!({"name": name,
+                "three_d": {"comprehension": 1, "bone_structure": 1, "physique": 1}})
+        };
+        let storyline: Storyline = serde_json::from_value(serde_json::json!({
+            "id": "s", "name": "测试", "start_event_id": "start",
+            "events": [
+                {"id": "start", "name": "开端", "node_type": "start",
+                 "content": {"type": "story", "text": "", "next_event_id": "fork"}},
+                {"id": "fork", "name": "岔路", "node_type": "middle", "action_points": 2,
+                 "content": {"type": "decision", "text": "",
+                             "options": [{"id": "a", "text": "走大路", "next_event_id": "fight"},
+                                         {"id": "b", "text": "走小路", "next_event_id": "good"}]}},
+                {"id": "fight", "name": "拦路", "node_type": "middle", "action_points": 3,
+                 "content": {"type": "battle", "text": "", "enemy": enemy("山贼"),
+                             "win": {"next_event_id": "rematch"},
+                             "lose": {"next_event_id": "bad"}}},
+                {"id": "rematch", "name": "再战", "node_type": "middle", "action_points": 1,
+                 "content": {"type": "battle", "text": "", "enemy": enemy("山贼"),
+                             "enemies": [enemy("山贼"), enemy("喽啰")],
+                             "win": {"next_event_id": "good"},
+                             "lose": {"next_event_id": "bad"}}},
+                {"id": "good", "name": "善终", "node_type": "end",
+                 "content": {"type": "end", "text": ""}},
+                {"id": "bad", "name": "恶果", "node_type": "end",
+                 "content": {"type": "end", "text": ""}}
+            ]
+        }))
+        .unwrap();
+
+        let stats = storyline.stats();
+        assert_eq!(stats.event_count, 6);
+        assert_eq!(
+            stats.node_types,
+            NodeTypeCounts {
+                start: 1,
+                middle: 3,
+                end: 2
+            }
+        );
+        assert_eq!((stats.battle_count, stats.ending_count), (2, 2));
+        assert_eq!(stats.enemy_count, 2);
+        assert_eq!((stats.min_action_points, stats.max_action_points), (2, 6));
+    }
+}
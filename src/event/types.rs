@@ -1,7 +1,18 @@
+use crate::battle::battle_config::BattleConfig;
+use crate::battle::battle_panel::BattlePanel;
+use crate::battle::battle_record::BattleFlavor;
+use crate::battle::battle_training::TrainingMode;
 use crate::character::panel::{CharacterPanel, ThreeDimensional};
 use crate::effect::condition::Condition;
 use crate::effect::effect::Operation;
+use crate::effect::entry::Entry;
+use crate::error::{WushenError, WushenResult};
+use crate::event::reward::DuplicateRewardConfig;
+use crate::game::action_cost::ActionCostConfig;
+use crate::game::calendar::{CalendarConfig, Season};
+use crate::game::starting_kit::StartingKit;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 // ==================== Storyline Events ====================
 
@@ -21,6 +32,154 @@ pub struct Storyline {
     pub name: String,
     pub start_event_id: String,
     pub events: Vec<StoryEvent>,
+    /// 战后恢复规则（缺省为完全恢复）
+    #[serde(default)]
+    pub post_battle_recovery: PostBattleRecovery,
+    /// 剧情战斗战败时的处理策略（缺省按剧情进入战败分支）
+    #[serde(default, skip_serializing_if = "DefeatPolicy::is_narrative")]
+    pub defeat_policy: DefeatPolicy,
+    /// 剧情线内战斗使用的配置（为空时使用核心默认配置）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub battle_config: Option<BattleConfig>,
+    /// 历法配置（为空时使用默认历法）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calendar: Option<CalendarConfig>,
+    /// 各指令的行动点消耗（未配置的指令使用缺省消耗）
+    #[serde(default, skip_serializing_if = "ActionCostConfig::is_empty")]
+    pub action_costs: ActionCostConfig,
+    /// 重复获得特性、功法时的处理策略（未配置的类型跳过）
+    #[serde(default, skip_serializing_if = "DuplicateRewardConfig::is_empty")]
+    pub duplicate_rewards: DuplicateRewardConfig,
+    /// 角色传记概述模板（为空时使用默认模板）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chronicle_template: Option<String>,
+    /// 往昔之影：游历时遭遇已完成角色（为空时不触发）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub echo_encounter: Option<EchoEncounter>,
+    /// 章节划分（为空时不分章节；非空时须覆盖全部事件且互不重叠）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chapters: Vec<Chapter>,
+    /// 开局行装（新角色的初始功法、特性、内息与行动点）
+    #[serde(default, skip_serializing_if = "StartingKit::is_empty")]
+    pub starting_kit: StartingKit,
+}
+
+impl Storyline {
+    /// 事件所属章节
+    pub fn chapter_of(&self, event_id: &str) -> Option<(usize, &Chapter)> {
+        self.chapters
+            .iter()
+            .enumerate()
+            .find(|(_, chapter)| chapter.event_ids.iter().any(|id| id == event_id))
+    }
+}
+
+/// 剧情章节
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chapter {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub summary: String,
+    /// 本章包含的事件
+    pub event_ids: Vec<String>,
+    /// 离开本章进入下一章时发放的奖励
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rewards: Vec<Reward>,
+}
+
+/// 往昔之影遭遇配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EchoEncounter {
+    /// 每次游历的触发概率（0-1）
+    pub chance: f64,
+    /// 触发条件（可选）
+    #[serde(default)]
+    pub condition: Option<Condition>,
+    /// 遭遇文本，{name} 替换为往昔角色名
+    #[serde(default = "default_echo_text")]
+    pub text: String,
+    #[serde(default)]
+    pub win: AdventureOutcome,
+    #[serde(default)]
+    pub lose: AdventureOutcome,
+    /// 平局结果（为空时按战败结果处理）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub draw: Option<AdventureOutcome>,
+}
+
+fn default_echo_text() -> String {
+    "山道尽头，一道熟悉的身影拦住去路——竟是{name}的残影。".to_string()
+}
+
+/// 战后恢复规则
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PostBattleRecovery {
+    /// 完全恢复至上限
+    #[default]
+    Full,
+    /// 按上限的比例恢复（0-1）
+    Percentage { ratio: f64 },
+    /// 不恢复，保留战斗结束时的状态
+    None,
+}
+
+/// 剧情战斗战败处理策略
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DefeatPolicy {
+    /// 仅影响剧情：进入战败分支
+    #[default]
+    Narrative,
+    /// 重试：留在当前战斗事件，负伤并扣除行动点（不进入战败分支）；
+    /// 行动点从之后有行动点的节点中扣除，扣完为止
+    Retry {
+        /// 负伤词条（以限时增益的形式施加）
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        injury: Vec<Entry>,
+        /// 负伤持续的行动次数
+        #[serde(default)]
+        injury_actions: u32,
+        /// 扣除的行动点
+        #[serde(default)]
+        action_points_lost: u32,
+    },
+    /// 永久死亡：本局结束，角色归档至已完成角色
+    Permadeath,
+}
+
+impl PostBattleRecovery {
+    /// 按规则计算恢复后的数值（结果限制在 0 与上限之间）
+    pub fn recover(&self, before: f64, max: f64) -> f64 {
+        let max = max.max(0.0);
+        let before = before.clamp(0.0, max);
+        match *self {
+            PostBattleRecovery::Full => max,
+            PostBattleRecovery::Percentage { ratio } => {
+                (before + max * ratio.clamp(0.0, 1.0)).min(max)
+            }
+            PostBattleRecovery::None => before,
+        }
+    }
+
+    /// 按规则计算恢复后的生命
+    ///
+    /// 恢复型规则至少保留 1 点生命，避免角色在后续战斗中直接落败；
+    /// 不恢复时保留战斗结束时的生命，归零即为归零
+    pub fn recover_hp(&self, before: f64, max: f64) -> f64 {
+        let hp = self.recover(before, max);
+        match self {
+            PostBattleRecovery::None => hp,
+            _ => hp.max(1.0_f64.min(max.max(0.0))),
+        }
+    }
+}
+
+impl DefeatPolicy {
+    pub fn is_narrative(&self) -> bool {
+        matches!(self, DefeatPolicy::Narrative)
+    }
 }
 
 /// 剧情事件
@@ -33,6 +192,40 @@ pub struct StoryEvent {
     #[serde(default)]
     pub action_points: u32,
     pub content: StoryEventContent,
+    /// 表现层元数据
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presentation: Option<Presentation>,
+}
+
+/// 表现层元数据（背景音乐、背景图、立绘等资源键），核心仅校验并原样传给界面
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Presentation {
+    /// 背景音乐 / 环境音
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bgm: Option<String>,
+    /// 背景图
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    /// 出场立绘
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub portraits: Vec<String>,
+}
+
+impl Presentation {
+    /// 校验资源键：不能为空白
+    pub fn validate(&self) -> WushenResult<()> {
+        let keys = self
+            .bgm
+            .iter()
+            .chain(self.background.iter())
+            .chain(self.portraits.iter());
+        for key in keys {
+            if key.trim().is_empty() {
+                return Err(WushenError::Invalid("表现资源键不能为空".to_string()));
+            }
+        }
+        Ok(())
+    }
 }
 
 /// 剧情事件内容
@@ -48,9 +241,33 @@ pub enum StoryEventContent {
     /// 战斗事件
     Battle {
         text: String,
+        /// 敌人库引用（加载时以敌人库中的敌人替换 enemy）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        enemy_id: Option<String>,
         enemy: EnemyTemplate,
+        /// 同场的其余敌人（一对多战斗：敌人各自蓄力出手，全部落败才算获胜）
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        enemies: Vec<EnemyTemplate>,
         win: StoryBattleBranch,
         lose: StoryBattleBranch,
+        /// 平局分支（达到最大轮数仍未分出胜负；为空时按战败分支处理）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        draw: Option<StoryBattleBranch>,
+        /// 双方的让子与限制
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        handicaps: Vec<BattleHandicap>,
+        /// 战斗环境变量（如 rain = 1），词条公式中以 `env_rain` 引用，未声明的按 0 计算
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        environment: BTreeMap<String, f64>,
+        /// 剧情打断（按顺序检查，满足时立即结束战斗并进入对应分支）
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        interruptions: Vec<BattleInterruption>,
+        /// 训练模式（玩家不会落败，训练结束后总是进入胜利分支）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        training: Option<TrainingMode>,
+        /// 战胜后的抉择（放过/击杀/招揽等），选择后再进入后续事件
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        victory_decision: Option<VictoryDecision>,
     },
     /// 剧情事件（有奖励，无战斗/抉择）
     Story {
@@ -61,6 +278,16 @@ pub enum StoryEventContent {
         #[serde(default)]
         next_event_id: Option<String>,
     },
+    /// 属性检定事件（比较或掷骰，按结果分档进入不同分支）
+    Check {
+        text: String,
+        check: StatCheck,
+        /// 大成功分支（未配置时大成功按成功处理）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        critical_success: Option<StoryCheckBranch>,
+        success: StoryCheckBranch,
+        failure: StoryCheckBranch,
+    },
     /// 结局事件（仅文本）
     End { text: String },
 }
@@ -74,6 +301,68 @@ pub struct StoryOption {
     /// 选项条件（可选）
     #[serde(default)]
     pub condition: Option<Condition>,
+    /// 本局历史前置条件（全部满足时选项可用）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prerequisites: Vec<StoryPrerequisite>,
+    /// 选择时付出的代价（无法支付时选项不可用）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub costs: Vec<OptionCost>,
+    /// 兜底选项：仅在其他选项都不可选时出现，避免玩家无路可走
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fallback: bool,
+}
+
+/// 基于本局历史的选项前置条件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StoryPrerequisite {
+    /// 剧情标记已设置（set 为 false 时要求未设置）
+    Flag {
+        flag: String,
+        #[serde(default = "default_true")]
+        set: bool,
+    },
+    /// 已经历剧情事件
+    VisitedEvent { event_id: String },
+    /// 曾在事件中选择某选项
+    ChoseOption { event_id: String, option_id: String },
+    /// 已完成奇遇
+    CompletedAdventure { adventure_id: String },
+    /// 事件中的战斗结果
+    BattleResult { event_id: String, win: bool },
+    /// 本局战斗胜场数不少于 min
+    BattlesWon { min: u32 },
+}
+
+impl StoryPrerequisite {
+    /// 前置条件的文字说明
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Flag { flag, set: true } => format!("需要剧情标记 {}", flag),
+            Self::Flag { flag, set: false } => format!("需要未设置剧情标记 {}", flag),
+            Self::VisitedEvent { event_id } => format!("需要经历事件 {}", event_id),
+            Self::ChoseOption {
+                event_id,
+                option_id,
+            } => format!("需要在事件 {} 中选择 {}", event_id, option_id),
+            Self::CompletedAdventure { adventure_id } => {
+                format!("需要完成奇遇 {}", adventure_id)
+            }
+            Self::BattleResult {
+                event_id,
+                win: true,
+            } => format!("需要在事件 {} 中战胜", event_id),
+            Self::BattleResult {
+                event_id,
+                win: false,
+            } => format!("需要在事件 {} 中落败", event_id),
+            Self::BattlesWon { min } => format!("需要累计战胜 {} 场", min),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
 }
 
 /// 战斗分支
@@ -84,6 +373,273 @@ pub struct StoryBattleBranch {
     pub rewards: Vec<Reward>,
 }
 
+/// 战胜后的抉择
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VictoryDecision {
+    pub text: String,
+    pub options: Vec<VictoryOption>,
+}
+
+/// 战后抉择选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VictoryOption {
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub rewards: Vec<Reward>,
+    /// 后续事件（为空时沿用胜利分支的 next_event_id）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_event_id: Option<String>,
+}
+
+/// 战斗中的一方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BattleSide {
+    Player,
+    Enemy,
+}
+
+/// 战斗让子与限制（开战前调整一方的战斗面板或追加词条，用于逆境之战与切磋）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BattleHandicap {
+    pub side: BattleSide,
+    /// 战斗日志中显示的来源名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// 开局生命比例（0-1，相对生命上限）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hp_ratio: Option<f64>,
+    /// 开局内息比例（0-1，相对内息上限）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qi_ratio: Option<f64>,
+    /// 出手速度倍率（如 0.8 表示降低 20%）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attack_speed_multiplier: Option<f64>,
+    /// 全场无法使用防御武技（以空手数值代替）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disable_defense_skill: bool,
+    /// 追加的词条（如战斗开始时的增减益）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entries: Vec<Entry>,
+}
+
+impl BattleHandicap {
+    /// 日志中显示的来源名称
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or("战前限制")
+    }
+
+    /// 校验参数
+    pub fn validate(&self) -> WushenResult<()> {
+        for (label, ratio) in [("生命", self.hp_ratio), ("内息", self.qi_ratio)] {
+            if let Some(ratio) = ratio {
+                if !(ratio > 0.0 && ratio <= 1.0) {
+                    return Err(WushenError::Invalid(format!(
+                        "开局{}比例 {} 必须在 0 与 1 之间",
+                        label, ratio
+                    )));
+                }
+            }
+        }
+        if let Some(multiplier) = self.attack_speed_multiplier {
+            if !(multiplier > 0.0 && multiplier.is_finite()) {
+                return Err(WushenError::Invalid(format!(
+                    "出手速度倍率 {} 必须大于 0",
+                    multiplier
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// 在战斗开始前调整战斗面板
+    pub fn apply_to_panel(&self, panel: &mut BattlePanel) {
+        if let Some(ratio) = self.hp_ratio {
+            panel.hp = panel.max_hp * ratio;
+        }
+        if let Some(ratio) = self.qi_ratio {
+            panel.qi = panel.max_qi * ratio;
+        }
+        if let Some(multiplier) = self.attack_speed_multiplier {
+            panel.attack_speed *= multiplier;
+        }
+        if self.disable_defense_skill {
+            panel.defense_seal_rounds = u32::MAX;
+        }
+    }
+}
+
+/// 战斗中的剧情打断
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BattleInterruption {
+    pub id: String,
+    pub trigger: InterruptCondition,
+    /// 打断时展示的文本（为空时沿用战斗事件文本）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    pub next_event_id: String,
+    #[serde(default)]
+    pub rewards: Vec<Reward>,
+}
+
+/// 打断条件
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InterruptCondition {
+    /// 第 N 回合结束时
+    Round { round: u32 },
+    /// 玩家生命比例降至阈值以下
+    PlayerHpBelow { ratio: f64 },
+    /// 敌人生命比例降至阈值以下
+    EnemyHpBelow { ratio: f64 },
+}
+
+impl InterruptCondition {
+    /// 校验条件参数
+    pub fn validate(&self) -> WushenResult<()> {
+        match *self {
+            InterruptCondition::Round { round } => {
+                if round == 0 {
+                    return Err(WushenError::Invalid("打断回合必须大于0".to_string()));
+                }
+            }
+            InterruptCondition::PlayerHpBelow { ratio }
+            | InterruptCondition::EnemyHpBelow { ratio } => {
+                if !(ratio > 0.0 && ratio < 1.0) {
+                    return Err(WushenError::Invalid(format!(
+                        "打断生命比例 {} 必须在 0 与 1 之间",
+                        ratio
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 检定属性
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStat {
+    Comprehension,
+    BoneStructure,
+    Physique,
+    MartialArtsAttainment,
+}
+
+impl CheckStat {
+    /// 读取面板中的属性值
+    pub fn value(self, panel: &CharacterPanel) -> f64 {
+        match self {
+            CheckStat::Comprehension => panel.three_d.comprehension as f64,
+            CheckStat::BoneStructure => panel.three_d.bone_structure as f64,
+            CheckStat::Physique => panel.three_d.physique as f64,
+            CheckStat::MartialArtsAttainment => panel.martial_arts_attainment,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CheckStat::Comprehension => "悟性",
+            CheckStat::BoneStructure => "根骨",
+            CheckStat::Physique => "体魄",
+            CheckStat::MartialArtsAttainment => "武学素养",
+        }
+    }
+}
+
+/// 属性检定规则
+///
+/// 检定值 = 属性值 + [0, roll) 的随机加值；roll 为 0 时直接比较属性值
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StatCheck {
+    pub stat: CheckStat,
+    #[serde(default)]
+    pub roll: f64,
+    pub success_threshold: f64,
+    /// 大成功阈值（为空时没有大成功档）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub critical_threshold: Option<f64>,
+}
+
+/// 检定结果档位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckTier {
+    CriticalSuccess,
+    Success,
+    Failure,
+}
+
+impl CheckTier {
+    /// 记录到本局历史中的选项标识
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CheckTier::CriticalSuccess => "critical_success",
+            CheckTier::Success => "success",
+            CheckTier::Failure => "failure",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CheckTier::CriticalSuccess => "大成功",
+            CheckTier::Success => "成功",
+            CheckTier::Failure => "失败",
+        }
+    }
+}
+
+impl StatCheck {
+    /// 校验检定参数
+    pub fn validate(&self) -> WushenResult<()> {
+        if !self.roll.is_finite() || self.roll < 0.0 {
+            return Err(WushenError::Invalid(format!(
+                "检定随机加值 {} 不能为负数",
+                self.roll
+            )));
+        }
+        if !self.success_threshold.is_finite() {
+            return Err(WushenError::Invalid("检定成功阈值无效".to_string()));
+        }
+        if let Some(critical) = self.critical_threshold {
+            if !critical.is_finite() || critical < self.success_threshold {
+                return Err(WushenError::Invalid(format!(
+                    "检定大成功阈值 {} 不能低于成功阈值 {}",
+                    critical, self.success_threshold
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// 根据检定值判定档位
+    pub fn resolve(&self, value: f64) -> CheckTier {
+        if self
+            .critical_threshold
+            .is_some_and(|critical| value >= critical)
+        {
+            CheckTier::CriticalSuccess
+        } else if value >= self.success_threshold {
+            CheckTier::Success
+        } else {
+            CheckTier::Failure
+        }
+    }
+}
+
+/// 检定分支
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoryCheckBranch {
+    /// 进入分支时展示的文本（为空时沿用检定事件文本）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    pub next_event_id: String,
+    #[serde(default)]
+    pub rewards: Vec<Reward>,
+}
+
 // ==================== Adventure Events ====================
 
 /// 奇遇事件
@@ -94,7 +650,20 @@ pub struct AdventureEvent {
     /// 触发条件（可选）
     #[serde(default)]
     pub trigger: Option<Condition>,
+    /// 可触发的季节（为空表示不限季节）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub seasons: Vec<Season>,
     pub content: AdventureEventContent,
+    /// 表现层元数据
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presentation: Option<Presentation>,
+}
+
+impl AdventureEvent {
+    /// 是否可在指定季节触发
+    pub fn is_in_season(&self, season: Season) -> bool {
+        self.seasons.is_empty() || self.seasons.contains(&season)
+    }
 }
 
 /// 奇遇事件内容
@@ -106,13 +675,31 @@ pub enum AdventureEventContent {
     Decision {
         text: String,
         options: Vec<AdventureOption>,
+        /// 放弃奇遇时付出的代价（无法支付的部分免除）
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        skip_costs: Vec<OptionCost>,
     },
     /// 战斗事件
     Battle {
         text: String,
+        /// 敌人库引用（加载时以敌人库中的敌人替换 enemy）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        enemy_id: Option<String>,
         enemy: EnemyTemplate,
+        /// 同场的其余敌人（一对多战斗：敌人各自蓄力出手，全部落败才算获胜）
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        enemies: Vec<EnemyTemplate>,
         win: AdventureOutcome,
         lose: AdventureOutcome,
+        /// 平局结果（为空时按战败结果处理）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        draw: Option<AdventureOutcome>,
+        /// 双方的让子与限制
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        handicaps: Vec<BattleHandicap>,
+        /// 战斗环境变量（如 rain = 1），词条公式中以 `env_rain` 引用，未声明的按 0 计算
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        environment: BTreeMap<String, f64>,
     },
     /// 剧情事件
     Story {
@@ -129,6 +716,12 @@ pub struct AdventureOption {
     pub text: String,
     #[serde(default)]
     pub condition: Option<Condition>,
+    /// 选择时付出的代价（无法支付时选项不可用）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub costs: Vec<OptionCost>,
+    /// 兜底选项：仅在其他选项都不可选时出现
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fallback: bool,
     pub result: AdventureOptionResult,
 }
 
@@ -146,14 +739,29 @@ pub enum AdventureOptionResult {
     /// 进入战斗
     Battle {
         text: String,
+        /// 敌人库引用（加载时以敌人库中的敌人替换 enemy）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        enemy_id: Option<String>,
         enemy: EnemyTemplate,
+        /// 同场的其余敌人（一对多战斗：敌人各自蓄力出手，全部落败才算获胜）
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        enemies: Vec<EnemyTemplate>,
         win: AdventureOutcome,
         lose: AdventureOutcome,
+        /// 平局结果（为空时按战败结果处理）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        draw: Option<AdventureOutcome>,
+        /// 双方的让子与限制
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        handicaps: Vec<BattleHandicap>,
+        /// 战斗环境变量（如 rain = 1），词条公式中以 `env_rain` 引用，未声明的按 0 计算
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        environment: BTreeMap<String, f64>,
     },
 }
 
 /// 奇遇结果（战斗胜负或其他分支）
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AdventureOutcome {
     #[serde(default)]
     pub text: Option<String>,
@@ -199,6 +807,57 @@ pub enum Reward {
         #[serde(default = "default_count_one")]
         count: u32,
     },
+    /// 设置剧情标记（value 为 false 时清除），供选项前置条件判断
+    StoryFlag {
+        flag: String,
+        #[serde(default = "default_true")]
+        value: bool,
+    },
+    /// 限时增益（如顿悟：之后若干次行动内修行经验提升）
+    TimedBuff {
+        id: String,
+        name: String,
+        /// 增益期间生效的词条
+        entries: Vec<Entry>,
+        /// 持续的行动次数（修行、游历各计一次）
+        actions: u32,
+    },
+    /// 获得称号（佩戴时词条生效，同一时间只能佩戴一个）
+    Title {
+        id: String,
+        name: String,
+        #[serde(default)]
+        entries: Vec<Entry>,
+    },
+    /// 预约后续奇遇（如“三次行动后刺客再临”）：之后第 actions 次行动（修行、游历各计一次）时到期，
+    /// 到期时或之后的首次游历必定触发该奇遇，优先于随机奇遇，且不受季节与触发条件限制
+    ScheduleEvent { adventure_id: String, actions: u32 },
+    /// 获得物品（放入背包，同种物品叠加数量）
+    Item {
+        id: String,
+        #[serde(default = "default_count_one")]
+        count: u32,
+    },
+}
+
+/// 选项代价（与奖励同时结算，任一代价无法支付时选项不可用）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OptionCost {
+    /// 消耗内息
+    Qi { amount: f64 },
+    /// 受伤：损失生命（不会因此身亡，生命不足时无法支付）
+    Hp { amount: f64 },
+    /// 属性下降
+    Attribute { target: RewardTarget, amount: f64 },
+    /// 失去特性
+    Trait { id: String },
+    /// 失去内功（已装备时一并卸下）
+    Internal { id: String },
+    /// 失去攻击武技（已装备时一并卸下）
+    AttackSkill { id: String },
+    /// 失去防御武技（已装备时一并卸下）
+    DefenseSkill { id: String },
 }
 
 /// 奖励属性目标
@@ -211,6 +870,10 @@ pub enum RewardTarget {
     Physique,
     // 其他基础属性
     MartialArtsAttainment,
+    // 角色层面的永久强化（丹药、炼体等）
+    MaxHp,
+    BaseAttack,
+    BaseDefense,
 }
 
 /// 功法类型（用于随机抽奖池）
@@ -269,9 +932,75 @@ pub struct EnemyTemplate {
     pub qi: Option<f64>,
     #[serde(default)]
     pub martial_arts_attainment: Option<f64>,
+    /// 开场文本（`{self}` 为敌人名，`{opponent}` 为玩家名）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intro_text: Option<String>,
+    /// 玩家获胜时的收场文本
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub win_text: Option<String>,
+    /// 玩家落败时的收场文本
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lose_text: Option<String>,
+    /// 首领阶段（生命比例降至阈值时切换武技并追加特性）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub phases: Vec<EnemyPhase>,
+    /// 敌人库引用：一对多战斗中同场的其余敌人用它引用敌人库（主敌人使用事件的 enemy_id）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enemy_id: Option<String>,
+}
+
+/// 首领阶段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnemyPhase {
+    pub name: String,
+    /// 生命比例阈值（0-1，不含端点）
+    pub hp_ratio: f64,
+    /// 切换时的文本（`{self}` 为敌人名，`{opponent}` 为玩家名）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// 替换的攻击武技
+    #[serde(default)]
+    pub attack_skill: Option<OwnedManualTemplate>,
+    /// 替换的防御武技
+    #[serde(default)]
+    pub defense_skill: Option<OwnedManualTemplate>,
+    /// 追加特性（词条立即生效）
+    #[serde(default)]
+    pub traits: Vec<String>,
 }
 
 impl EnemyTemplate {
+    /// 校验首领阶段配置
+    pub fn validate(&self) -> WushenResult<()> {
+        for phase in &self.phases {
+            if !(phase.hp_ratio > 0.0 && phase.hp_ratio < 1.0) {
+                return Err(WushenError::Invalid(format!(
+                    "敌人 {} 阶段 {} 的生命比例阈值必须在 0-1 之间",
+                    self.name, phase.name
+                )));
+            }
+            let skills = phase.attack_skill.iter().chain(phase.defense_skill.iter());
+            for skill in skills {
+                if !(1..=5).contains(&skill.level) {
+                    return Err(WushenError::Invalid(format!(
+                        "敌人 {} 阶段 {} 的武技等级必须为 1-5",
+                        self.name, phase.name
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 敌方视角的开场/收场文本（玩家获胜即敌方落败）
+    pub fn flavor(&self) -> BattleFlavor {
+        BattleFlavor {
+            intro: self.intro_text.clone(),
+            victory: self.lose_text.clone(),
+            defeat: self.win_text.clone(),
+        }
+    }
+
     /// 转换为角色面板
     pub fn to_character_panel(&self) -> CharacterPanel {
         let three_d = ThreeDimensional::new(
@@ -310,3 +1039,35 @@ impl EnemyTemplate {
         panel
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::PostBattleRecovery;
+
+    #[test]
+    fn full_recovery_restores_to_max() {
+        let rule = PostBattleRecovery::Full;
+        assert_eq!(rule.recover_hp(0.0, 500.0), 500.0);
+        assert_eq!(rule.recover(30.0, 200.0), 200.0);
+    }
+
+    #[test]
+    fn percentage_recovery_adds_ratio_of_max_and_keeps_one_hp() {
+        let rule = PostBattleRecovery::Percentage { ratio: 0.2 };
+        assert_eq!(rule.recover_hp(100.0, 500.0), 200.0);
+        assert_eq!(rule.recover_hp(450.0, 500.0), 500.0);
+        assert_eq!(rule.recover(10.0, 200.0), 50.0);
+
+        let rule = PostBattleRecovery::Percentage { ratio: 0.0 };
+        assert_eq!(rule.recover_hp(0.0, 500.0), 1.0);
+        assert_eq!(rule.recover(0.0, 200.0), 0.0);
+    }
+
+    #[test]
+    fn no_recovery_keeps_battle_end_state_without_hp_floor() {
+        let rule = PostBattleRecovery::None;
+        assert_eq!(rule.recover_hp(0.0, 500.0), 0.0);
+        assert_eq!(rule.recover_hp(37.5, 500.0), 37.5);
+        assert_eq!(rule.recover(12.0, 200.0), 12.0);
+    }
+}
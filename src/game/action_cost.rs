@@ -0,0 +1,101 @@
+/// 行动点消耗：运行时指令统一按配置检查并扣除行动点
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 会消耗（或可配置为消耗）行动点的运行时指令
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionKind {
+    Cultivate,
+    Travel,
+    EquipManual,
+    EquipTitle,
+    AcceptQuest,
+    AbandonQuest,
+    ClaimQuest,
+    UseItem,
+    Craft,
+    AdventureSkip,
+}
+
+impl ActionKind {
+    /// 缺省消耗：修行、游历、炼制与（仍有选项时）放弃奇遇各 1 点，其余指令免费
+    pub fn default_cost(&self) -> u32 {
+        match self {
+            ActionKind::Cultivate
+            | ActionKind::Travel
+            | ActionKind::Craft
+            | ActionKind::AdventureSkip => 1,
+            ActionKind::EquipManual
+            | ActionKind::EquipTitle
+            | ActionKind::AcceptQuest
+            | ActionKind::AbandonQuest
+            | ActionKind::ClaimQuest
+            | ActionKind::UseItem => 0,
+        }
+    }
+}
+
+/// 行动点消耗配置（剧情线可单独配置，未列出的指令使用缺省消耗）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ActionCostConfig {
+    costs: BTreeMap<ActionKind, u32>,
+}
+
+impl ActionCostConfig {
+    pub fn is_empty(&self) -> bool {
+        self.costs.is_empty()
+    }
+
+    /// 指令的行动点消耗
+    pub fn cost(&self, action: ActionKind) -> u32 {
+        self.costs
+            .get(&action)
+            .copied()
+            .unwrap_or_else(|| action.default_cost())
+    }
+
+    /// 检查行动点是否足够，返回该指令的消耗
+    pub fn check(&self, action: ActionKind, action_points: u32) -> WushenResult<u32> {
+        let cost = self.cost(action);
+        if action_points < cost {
+            return Err(WushenError::ConditionNotMet("行动点不足".to_string()));
+        }
+        Ok(cost)
+    }
+
+    /// 扣除指令消耗的行动点（不足时不扣除并返回错误）
+    pub fn charge(&self, action: ActionKind, action_points: &mut u32) -> WushenResult<u32> {
+        let cost = self.check(action, *action_points)?;
+        *action_points -= cost;
+        Ok(cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn configured_costs_override_defaults() {
+        let config: ActionCostConfig =
+            serde_json::from_str(r#"{"travel":2,"equip_manual":1}"#).unwrap();
+        assert_eq!(config.cost(ActionKind::Travel), 2);
+        assert_eq!(config.cost(ActionKind::EquipManual), 1);
+        assert_eq!(config.cost(ActionKind::Cultivate), 1);
+        assert_eq!(config.cost(ActionKind::EquipTitle), 0);
+    }
+
+    #[test]
+    fn charge_rejects_insufficient_points() {
+        let config: ActionCostConfig = serde_json::from_str(r#"{"travel":2}"#).unwrap();
+        let mut points = 1;
+        assert!(config.charge(ActionKind::Travel, &mut points).is_err());
+        assert_eq!(points, 1);
+        assert_eq!(config.charge(ActionKind::EquipTitle, &mut points), Ok(0));
+        assert_eq!(config.charge(ActionKind::Cultivate, &mut points), Ok(1));
+        assert_eq!(points, 0);
+    }
+}
@@ -0,0 +1,187 @@
+/// 本地数据统计（可选开启，不含任何网络代码）
+/// 按存档汇总匿名的平衡性数据：各敌人胜率、选项选择次数、各剧情节点到达时的平均功法等级，
+/// 可导出 JSON 报告供内容作者调整数值
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::CharacterState;
+
+/// 战斗胜负统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BattleTally {
+    #[serde(default)]
+    pub wins: u32,
+    #[serde(default)]
+    pub losses: u32,
+}
+
+impl BattleTally {
+    pub fn total(&self) -> u32 {
+        self.wins + self.losses
+    }
+
+    /// 胜率（无战斗时为 0）
+    pub fn win_rate(&self) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => f64::from(self.wins) / f64::from(total),
+        }
+    }
+}
+
+/// 剧情节点到达统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NodeTally {
+    #[serde(default)]
+    pub visits: u32,
+    /// 到达时已装备功法等级之和的累计
+    #[serde(default)]
+    pub level_sum: u64,
+}
+
+impl NodeTally {
+    /// 平均功法等级（无到达记录时为 0）
+    pub fn average_level(&self) -> f64 {
+        match self.visits {
+            0 => 0.0,
+            visits => self.level_sum as f64 / f64::from(visits),
+        }
+    }
+}
+
+/// 存档内的统计数据
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SaveAnalytics {
+    /// 敌人名称 -> 胜负
+    #[serde(default)]
+    pub battles: BTreeMap<String, BattleTally>,
+    /// 事件 ID -> 选项 ID -> 选择次数
+    #[serde(default)]
+    pub option_picks: BTreeMap<String, BTreeMap<String, u32>>,
+    /// 剧情事件 ID -> 到达统计
+    #[serde(default)]
+    pub story_nodes: BTreeMap<String, NodeTally>,
+}
+
+impl SaveAnalytics {
+    pub fn record_battle(&mut self, enemy_name: &str, win: bool) {
+        let tally = self.battles.entry(enemy_name.to_string()).or_default();
+        if win {
+            tally.wins += 1;
+        } else {
+            tally.losses += 1;
+        }
+    }
+
+    pub fn record_option(&mut self, event_id: &str, option_id: &str) {
+        *self
+            .option_picks
+            .entry(event_id.to_string())
+            .or_default()
+            .entry(option_id.to_string())
+            .or_default() += 1;
+    }
+
+    pub fn record_story_node(&mut self, event_id: &str, character: &CharacterState) {
+        let tally = self.story_nodes.entry(event_id.to_string()).or_default();
+        tally.visits += 1;
+        tally.level_sum += u64::from(equipped_level_total(character));
+    }
+
+    /// 生成报告
+    pub fn report(&self) -> AnalyticsReport {
+        AnalyticsReport {
+            battles: self
+                .battles
+                .iter()
+                .map(|(enemy_name, tally)| EnemyReport {
+                    enemy_name: enemy_name.clone(),
+                    wins: tally.wins,
+                    losses: tally.losses,
+                    win_rate: tally.win_rate(),
+                })
+                .collect(),
+            option_picks: self.option_picks.clone(),
+            story_nodes: self
+                .story_nodes
+                .iter()
+                .map(|(event_id, tally)| NodeReport {
+                    event_id: event_id.clone(),
+                    visits: tally.visits,
+                    average_level: tally.average_level(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// 已装备内功、攻击武技、防御武技的等级之和
+fn equipped_level_total(character: &CharacterState) -> u32 {
+    [
+        &character.internals,
+        &character.attack_skills,
+        &character.defense_skills,
+    ]
+    .into_iter()
+    .filter_map(|manuals| {
+        let equipped = manuals.equipped.as_ref()?;
+        manuals
+            .owned
+            .iter()
+            .find(|owned| &owned.id == equipped)
+            .map(|owned| owned.level)
+    })
+    .sum()
+}
+
+/// 统计报告
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalyticsReport {
+    pub battles: Vec<EnemyReport>,
+    pub option_picks: BTreeMap<String, BTreeMap<String, u32>>,
+    pub story_nodes: Vec<NodeReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EnemyReport {
+    pub enemy_name: String,
+    pub wins: u32,
+    pub losses: u32,
+    pub win_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeReport {
+    pub event_id: String,
+    pub visits: u32,
+    pub average_level: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_aggregates_tallies() {
+        let mut analytics = SaveAnalytics::default();
+        analytics.record_battle("山贼", true);
+        analytics.record_battle("山贼", true);
+        analytics.record_battle("山贼", false);
+        analytics.record_option("e1", "o1");
+        analytics.record_option("e1", "o1");
+        analytics.record_option("e1", "o2");
+        analytics.story_nodes.insert(
+            "n1".to_string(),
+            NodeTally {
+                visits: 2,
+                level_sum: 5,
+            },
+        );
+
+        let report = analytics.report();
+        assert_eq!(report.battles[0].wins, 2);
+        assert!((report.battles[0].win_rate - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(report.option_picks["e1"]["o1"], 2);
+        assert_eq!(report.story_nodes[0].average_level, 2.5);
+    }
+}
@@ -0,0 +1,72 @@
+/// 战斗存档：按保留策略存储剧情与奇遇战斗的完整日志，供玩家在江湖见闻中重温
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::segments::Segment;
+use super::StoryHistoryScope;
+
+/// 战斗存档保留策略
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BattleArchivePolicy {
+    /// 最多保留的战斗数，超出时丢弃最早的战斗（为空表示不限）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_battles: Option<usize>,
+}
+
+/// 已存档的战斗
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedBattle {
+    pub id: String,
+    pub scope: StoryHistoryScope,
+    pub event_id: String,
+    pub enemy_name: String,
+    pub win: bool,
+    /// 战斗发生时的游戏天数
+    pub day: u32,
+    /// 完整的战斗结果（与战斗接口返回的结构一致）
+    pub result: Value,
+}
+
+impl BattleArchivePolicy {
+    /// 存入一场战斗并按策略丢弃最早的战斗
+    pub fn archive(&self, archive: &mut Segment<ArchivedBattle>, battle: ArchivedBattle) {
+        archive.push(battle);
+        if let Some(max_battles) = self.max_battles {
+            let excess = archive.len().saturating_sub(max_battles);
+            if excess > 0 {
+                archive.drain_front(excess);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn battle(id: &str) -> ArchivedBattle {
+        ArchivedBattle {
+            id: id.to_string(),
+            scope: StoryHistoryScope::Story,
+            event_id: "e".to_string(),
+            enemy_name: "山贼".to_string(),
+            win: true,
+            day: 0,
+            result: Value::Null,
+        }
+    }
+
+    #[test]
+    fn retention_drops_oldest_battles() {
+        let policy = BattleArchivePolicy {
+            max_battles: Some(2),
+        };
+        let mut archive = Segment::default();
+        for id in ["b1", "b2", "b3"] {
+            policy.archive(&mut archive, battle(id));
+        }
+        let ids: Vec<&str> = archive.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, ["b2", "b3"]);
+        assert_eq!(serde_json::to_string(&archive).unwrap(), r#"{"len":2}"#);
+    }
+}
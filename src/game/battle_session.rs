@@ -0,0 +1,35 @@
+/// 手动剧情战斗：战斗引擎托管在运行时中，由玩家逐回合推进并可在中途调整内息输出；
+/// 战斗会话不写入存档，读档后需重新开始该场战斗
+use std::fmt;
+
+use crate::battle::battle_engine::BattleEngine;
+
+/// 进行中的手动战斗
+#[derive(Clone)]
+pub struct BattleSession {
+    /// 战斗所属的剧情事件
+    pub event_id: String,
+    pub engine: BattleEngine,
+    /// 已返回给调用方的战斗记录条数（每次推进只返回新增日志）
+    pub reported_records: usize,
+}
+
+impl BattleSession {
+    pub fn new(event_id: String, engine: BattleEngine) -> Self {
+        Self {
+            event_id,
+            engine,
+            reported_records: 0,
+        }
+    }
+}
+
+impl fmt::Debug for BattleSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BattleSession")
+            .field("event_id", &self.event_id)
+            .field("round", &self.engine.get_round())
+            .field("reported_records", &self.reported_records)
+            .finish_non_exhaustive()
+    }
+}
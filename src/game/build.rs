@@ -0,0 +1,247 @@
+/// 角色构筑：可在玩家之间分享的独立角色配置（不含剧情状态）
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Serialize};
+
+use super::{CharacterState, ManualsState, ThreeDimensionalState};
+use crate::character::trait_manager::TraitManager;
+use crate::cultivation::manual_manager::ManualManager;
+use crate::event::{EnemyTemplate, OwnedManualTemplate, ThreeDimensionalTemplate};
+
+/// 当前构筑格式版本
+pub const CHARACTER_BUILD_VERSION: u32 = 1;
+
+/// 角色构筑（三维、特性、功法及等级）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CharacterBuild {
+    #[serde(default = "default_build_version")]
+    pub version: u32,
+    pub name: String,
+    pub three_d: ThreeDimensionalState,
+    #[serde(default)]
+    pub traits: Vec<String>,
+    pub internals: ManualsState,
+    pub attack_skills: ManualsState,
+    pub defense_skills: ManualsState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_qi: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub martial_arts_attainment: Option<f64>,
+}
+
+fn default_build_version() -> u32 {
+    CHARACTER_BUILD_VERSION
+}
+
+/// 功法类别（用于校验时查询功法库）
+#[derive(Clone, Copy)]
+enum BuildManualKind {
+    Internal,
+    AttackSkill,
+    DefenseSkill,
+}
+
+impl BuildManualKind {
+    fn label(self) -> &'static str {
+        match self {
+            BuildManualKind::Internal => "内功",
+            BuildManualKind::AttackSkill => "攻击武技",
+            BuildManualKind::DefenseSkill => "防御武技",
+        }
+    }
+
+    /// 功法的最高等级（功法不存在时为 None）
+    fn max_level(self, manual_manager: &ManualManager, id: &str) -> Option<usize> {
+        match self {
+            BuildManualKind::Internal => manual_manager.get_internal(id).map(|m| m.realms.len()),
+            BuildManualKind::AttackSkill => {
+                manual_manager.get_attack_skill(id).map(|m| m.realms.len())
+            }
+            BuildManualKind::DefenseSkill => {
+                manual_manager.get_defense_skill(id).map(|m| m.realms.len())
+            }
+        }
+    }
+}
+
+impl CharacterBuild {
+    /// 从角色状态提取构筑
+    pub fn from_character(character: &CharacterState) -> Self {
+        Self {
+            version: CHARACTER_BUILD_VERSION,
+            name: character.name.clone(),
+            three_d: character.three_d.clone(),
+            traits: character.traits.clone(),
+            internals: character.internals.clone(),
+            attack_skills: character.attack_skills.clone(),
+            defense_skills: character.defense_skills.clone(),
+            max_qi: character.max_qi,
+            martial_arts_attainment: character.martial_arts_attainment,
+        }
+    }
+
+    /// 按已加载的内容包校验构筑
+    pub fn validate(
+        &self,
+        trait_manager: &TraitManager,
+        manual_manager: &ManualManager,
+    ) -> WushenResult<()> {
+        if self.version > CHARACTER_BUILD_VERSION {
+            return Err(WushenError::Invalid(format!(
+                "不支持的构筑版本 {}",
+                self.version
+            )));
+        }
+        if self.name.trim().is_empty() {
+            return Err(WushenError::Invalid("构筑名称不能为空".to_string()));
+        }
+        for id in &self.traits {
+            if trait_manager.get_trait(id).is_none() {
+                return Err(WushenError::Invalid(format!("特性 {} 不存在", id)));
+            }
+        }
+        if self.max_qi.is_some_and(|v| !v.is_finite() || v < 0.0) {
+            return Err(WushenError::Invalid("内息上限无效".to_string()));
+        }
+        if self
+            .martial_arts_attainment
+            .is_some_and(|v| !v.is_finite() || v < 0.0)
+        {
+            return Err(WushenError::Invalid("武学素养无效".to_string()));
+        }
+        Self::validate_manuals(&self.internals, BuildManualKind::Internal, manual_manager)?;
+        Self::validate_manuals(
+            &self.attack_skills,
+            BuildManualKind::AttackSkill,
+            manual_manager,
+        )?;
+        Self::validate_manuals(
+            &self.defense_skills,
+            BuildManualKind::DefenseSkill,
+            manual_manager,
+        )
+    }
+
+    fn validate_manuals(
+        manuals: &ManualsState,
+        kind: BuildManualKind,
+        manual_manager: &ManualManager,
+    ) -> WushenResult<()> {
+        for manual in &manuals.owned {
+            let max_level = kind.max_level(manual_manager, &manual.id).ok_or_else(|| {
+                WushenError::Invalid(format!("{} {} 不存在", kind.label(), manual.id))
+            })?;
+            if manual.level as usize > max_level {
+                return Err(WushenError::Invalid(format!(
+                    "{} {} 等级 {} 超过上限 {}",
+                    kind.label(),
+                    manual.id,
+                    manual.level,
+                    max_level
+                )));
+            }
+        }
+        if let Some(equipped) = &manuals.equipped {
+            if !manuals.owned.iter().any(|m| &m.id == equipped) {
+                return Err(WushenError::Invalid(format!(
+                    "装备的{} {} 未拥有",
+                    kind.label(),
+                    equipped
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// 实例化为敌人模板（仅保留当前装备的功法），用于切磋或比武
+    pub fn to_enemy_template(&self) -> EnemyTemplate {
+        let equipped = |manuals: &ManualsState| {
+            let id = manuals.equipped.as_ref()?;
+            manuals
+                .owned
+                .iter()
+                .find(|m| &m.id == id)
+                .map(|m| OwnedManualTemplate {
+                    id: m.id.clone(),
+                    level: m.level,
+                    exp: m.exp,
+                })
+        };
+        EnemyTemplate {
+            name: self.name.clone(),
+            three_d: ThreeDimensionalTemplate {
+                comprehension: self.three_d.comprehension,
+                bone_structure: self.three_d.bone_structure,
+                physique: self.three_d.physique,
+            },
+            traits: self.traits.clone(),
+            internal: equipped(&self.internals),
+            attack_skill: equipped(&self.attack_skills),
+            defense_skill: equipped(&self.defense_skills),
+            max_qi: self.max_qi,
+            // 切磋对手以满内息出场
+            qi: self.max_qi,
+            martial_arts_attainment: self.martial_arts_attainment,
+            intro_text: None,
+            win_text: None,
+            lose_text: None,
+            phases: Vec::new(),
+            enemy_id: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::OwnedManualState;
+
+    fn build() -> CharacterBuild {
+        CharacterBuild {
+            version: CHARACTER_BUILD_VERSION,
+            name: "分享角色".to_string(),
+            three_d: ThreeDimensionalState {
+                comprehension: 10,
+                bone_structure: 10,
+                physique: 10,
+            },
+            traits: Vec::new(),
+            internals: ManualsState {
+                owned: Vec::new(),
+                equipped: None,
+            },
+            attack_skills: ManualsState {
+                owned: Vec::new(),
+                equipped: None,
+            },
+            defense_skills: ManualsState {
+                owned: Vec::new(),
+                equipped: None,
+            },
+            max_qi: Some(120.0),
+            martial_arts_attainment: None,
+        }
+    }
+
+    #[test]
+    fn validate_rejects_unknown_content() {
+        let traits = TraitManager::new();
+        let manuals = ManualManager::new();
+        assert!(build().validate(&traits, &manuals).is_ok());
+
+        let mut unknown_trait = build();
+        unknown_trait.traits.push("不存在".to_string());
+        assert!(unknown_trait.validate(&traits, &manuals).is_err());
+
+        let mut unknown_manual = build();
+        unknown_manual.internals.owned.push(OwnedManualState {
+            id: "不存在".to_string(),
+            level: 1,
+            exp: 0.0,
+        });
+        assert!(unknown_manual.validate(&traits, &manuals).is_err());
+
+        let mut unowned_equip = build();
+        unowned_equip.attack_skills.equipped = Some("未拥有".to_string());
+        assert!(unowned_equip.validate(&traits, &manuals).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+/// 游戏历法：行动推进日期，季节影响奇遇与修行
+use serde::{Deserialize, Serialize};
+
+use crate::effect::effect::{AttributeTarget, Effect, FormulaValue, Operation, PanelTarget};
+use crate::effect::entry::Entry;
+use crate::effect::trigger::Trigger;
+
+/// 季节
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    const ALL: [Season; 4] = [
+        Season::Spring,
+        Season::Summer,
+        Season::Autumn,
+        Season::Winter,
+    ];
+
+    /// 季节名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Season::Spring => "春",
+            Season::Summer => "夏",
+            Season::Autumn => "秋",
+            Season::Winter => "冬",
+        }
+    }
+}
+
+/// 按季节区分的数值（缺省为 0）
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeasonalValues {
+    pub spring: f64,
+    pub summer: f64,
+    pub autumn: f64,
+    pub winter: f64,
+}
+
+impl SeasonalValues {
+    pub fn get(&self, season: Season) -> f64 {
+        match season {
+            Season::Spring => self.spring,
+            Season::Summer => self.summer,
+            Season::Autumn => self.autumn,
+            Season::Winter => self.winter,
+        }
+    }
+}
+
+/// 历法配置（剧情线可单独配置）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalendarConfig {
+    /// 每次行动（修行、游历）经过的天数
+    #[serde(default = "default_days_per_action")]
+    pub days_per_action: u32,
+    /// 每个季节的天数
+    #[serde(default = "default_days_per_season")]
+    pub days_per_season: u32,
+    /// 各季节的修行经验增益加成（0.2 表示经验增益增加 20%）
+    #[serde(default)]
+    pub cultivation_exp_bonus: SeasonalValues,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            days_per_action: default_days_per_action(),
+            days_per_season: default_days_per_season(),
+            cultivation_exp_bonus: SeasonalValues::default(),
+        }
+    }
+}
+
+fn default_days_per_action() -> u32 {
+    1
+}
+
+fn default_days_per_season() -> u32 {
+    30
+}
+
+impl CalendarConfig {
+    /// 指定天数（从 0 开始）所处的季节
+    pub fn season_of(&self, day: u32) -> Season {
+        let days_per_season = self.days_per_season.max(1);
+        Season::ALL[((day / days_per_season) % 4) as usize]
+    }
+
+    /// 生成日历视图
+    pub fn view(&self, day: u32) -> CalendarView {
+        let days_per_season = self.days_per_season.max(1);
+        let season = self.season_of(day);
+        CalendarView {
+            day,
+            year: day / (days_per_season * 4) + 1,
+            season,
+            season_name: season.name().to_string(),
+            day_of_season: day % days_per_season + 1,
+        }
+    }
+
+    /// 季节修行加成对应的词条（无加成时返回 None）
+    pub fn cultivation_entry(&self, season: Season, trigger: Trigger) -> Option<Entry> {
+        let bonus = self.cultivation_exp_bonus.get(season);
+        if bonus == 0.0 {
+            return None;
+        }
+        let effect = Effect::ModifyPercentage {
+            target: AttributeTarget::CultivationExpGain,
+            value: FormulaValue::Fixed(bonus),
+            operation: Operation::Add,
+            target_panel: PanelTarget::Own,
+            can_exceed_limit: false,
+            is_temporary: false,
+            battle_record_template: None,
+        };
+        Some(Entry::new(trigger, None, vec![effect], None))
+    }
+}
+
+/// 日历视图
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarView {
+    /// 已经过的天数（从 0 开始）
+    pub day: u32,
+    /// 第几年（从 1 开始）
+    pub year: u32,
+    pub season: Season,
+    pub season_name: String,
+    /// 当季第几天（从 1 开始）
+    pub day_of_season: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seasons_cycle_by_days() {
+        let config = CalendarConfig {
+            days_per_season: 10,
+            ..CalendarConfig::default()
+        };
+        assert_eq!(config.season_of(0), Season::Spring);
+        assert_eq!(config.season_of(9), Season::Spring);
+        assert_eq!(config.season_of(10), Season::Summer);
+        assert_eq!(config.season_of(35), Season::Winter);
+        let view = config.view(41);
+        assert_eq!(view.year, 2);
+        assert_eq!(view.season, Season::Spring);
+        assert_eq!(view.day_of_season, 2);
+    }
+
+    #[test]
+    fn cultivation_entry_only_when_bonus_set() {
+        let config = CalendarConfig {
+            cultivation_exp_bonus: SeasonalValues {
+                winter: 0.3,
+                ..SeasonalValues::default()
+            },
+            ..CalendarConfig::default()
+        };
+        assert!(config
+            .cultivation_entry(Season::Spring, Trigger::CultivatingInternal)
+            .is_none());
+        let entry = config
+            .cultivation_entry(Season::Winter, Trigger::CultivatingInternal)
+            .unwrap();
+        assert_eq!(entry.effects[0].get_fixed_value(), Some(0.3));
+    }
+}
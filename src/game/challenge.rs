@@ -0,0 +1,169 @@
+/// 挑战规则（每周挑战等）：固定种子、禁用内容、难度调整与计分倍率
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Serialize};
+
+use crate::character::trait_manager::TraitManager;
+use crate::cultivation::manual_manager::ManualManager;
+use crate::event::{PostBattleRecovery, Reward};
+
+/// 计分倍率（作用于角色评分的各组成部分）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScoreMultipliers {
+    #[serde(default = "default_multiplier")]
+    pub three_d: f64,
+    #[serde(default = "default_multiplier")]
+    pub manual_levels: f64,
+    #[serde(default = "default_multiplier")]
+    pub martial_arts_attainment: f64,
+}
+
+fn default_multiplier() -> f64 {
+    1.0
+}
+
+impl Default for ScoreMultipliers {
+    fn default() -> Self {
+        Self {
+            three_d: 1.0,
+            manual_levels: 1.0,
+            martial_arts_attainment: 1.0,
+        }
+    }
+}
+
+/// 挑战规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChallengeRuleset {
+    pub id: String,
+    pub name: String,
+    /// 固定随机种子（同一挑战的所有玩家抽到相同的开局与随机奖励）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// 禁用的特性（不会出现在开局抽取与奖励中）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub banned_traits: Vec<String>,
+    /// 禁用的功法（不会通过奖励获得，也不能装备）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub banned_manuals: Vec<String>,
+    /// 禁用的词条标签（带有其中任一标签的词条不生效）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled_entry_tags: Vec<String>,
+    /// 难度调整：覆盖剧情线与开局设置的战后恢复规则
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_battle_recovery: Option<PostBattleRecovery>,
+    #[serde(default)]
+    pub score_multipliers: ScoreMultipliers,
+}
+
+impl ChallengeRuleset {
+    pub fn from_json(json: &str) -> WushenResult<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| WushenError::ParseError(format!("解析挑战规则失败: {}", e)))
+    }
+
+    /// 按已加载的内容包校验挑战规则
+    pub fn validate(
+        &self,
+        trait_manager: &TraitManager,
+        manual_manager: &ManualManager,
+    ) -> WushenResult<()> {
+        if self.id.trim().is_empty() {
+            return Err(WushenError::Invalid("挑战规则 ID 不能为空".to_string()));
+        }
+        if self.seed == Some(0) {
+            return Err(WushenError::Invalid(
+                "挑战规则的随机种子不能为 0".to_string(),
+            ));
+        }
+        for id in &self.banned_traits {
+            if trait_manager.get_trait(id).is_none() {
+                return Err(WushenError::Invalid(format!(
+                    "挑战规则禁用的特性 {} 不存在",
+                    id
+                )));
+            }
+        }
+        for id in &self.banned_manuals {
+            let exists = manual_manager.get_internal(id).is_some()
+                || manual_manager.get_attack_skill(id).is_some()
+                || manual_manager.get_defense_skill(id).is_some();
+            if !exists {
+                return Err(WushenError::Invalid(format!(
+                    "挑战规则禁用的功法 {} 不存在",
+                    id
+                )));
+            }
+        }
+        if self
+            .disabled_entry_tags
+            .iter()
+            .any(|tag| tag.trim().is_empty())
+        {
+            return Err(WushenError::Invalid(
+                "挑战规则禁用的词条标签不能为空".to_string(),
+            ));
+        }
+        let ScoreMultipliers {
+            three_d,
+            manual_levels,
+            martial_arts_attainment,
+        } = self.score_multipliers;
+        if [three_d, manual_levels, martial_arts_attainment]
+            .iter()
+            .any(|v| !v.is_finite() || *v < 0.0)
+        {
+            return Err(WushenError::Invalid(
+                "挑战规则的计分倍率必须为非负数".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn bans_trait(&self, id: &str) -> bool {
+        self.banned_traits.iter().any(|banned| banned == id)
+    }
+
+    pub fn bans_manual(&self, id: &str) -> bool {
+        self.banned_manuals.iter().any(|banned| banned == id)
+    }
+
+    /// 奖励是否允许发放（随机功法奖励在抽取时排除禁用功法）
+    pub fn allows_reward(&self, reward: &Reward) -> bool {
+        match reward {
+            Reward::Trait { id } | Reward::StartTraitPool { id } => !self.bans_trait(id),
+            Reward::Internal { id } | Reward::AttackSkill { id } | Reward::DefenseSkill { id } => {
+                !self.bans_manual(id)
+            }
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ruleset_filters_banned_rewards() {
+        let ruleset = ChallengeRuleset::from_json(
+            r#"{"id":"week-1","name":"第一周","seed":42,"banned_traits":["天生神力"],"banned_manuals":["易筋经"]}"#,
+        )
+        .unwrap();
+        assert_eq!(ruleset.score_multipliers, ScoreMultipliers::default());
+        assert!(!ruleset.allows_reward(&Reward::Trait {
+            id: "天生神力".to_string()
+        }));
+        assert!(!ruleset.allows_reward(&Reward::Internal {
+            id: "易筋经".to_string()
+        }));
+        assert!(ruleset.allows_reward(&Reward::AttackSkill {
+            id: "太祖长拳".to_string()
+        }));
+
+        // 禁用内容必须存在于已加载的内容包中
+        let error = ruleset
+            .validate(&TraitManager::new(), &ManualManager::new())
+            .unwrap_err();
+        assert!(error.message().contains("天生神力"));
+    }
+}
@@ -0,0 +1,161 @@
+/// 角色传记：结局时根据经历生成的编年记录与文字概述
+use serde::{Deserialize, Serialize};
+
+use super::StoryHistoryScope;
+
+/// 默认传记模板
+///
+/// 支持的占位符：
+/// - {name}: 角色名
+/// - {storyline}: 剧情线名称
+/// - {days}: 经过的天数
+/// - {adventures}: 奇遇次数
+/// - {battles_won} / {battles_lost}: 战斗胜负场数
+/// - {strongest_enemy}: 生平最强敌手（无战斗时为“无”）
+/// - {ending}: 结局名称
+pub const DEFAULT_CHRONICLE_TEMPLATE: &str = "{name}踏入「{storyline}」的江湖，历经{days}日，\
+奇遇{adventures}次，交手胜{battles_won}负{battles_lost}，生平劲敌为{strongest_enemy}。终章：{ending}。";
+
+/// 角色传记
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chronicle {
+    pub storyline_id: String,
+    pub storyline_name: String,
+    /// 主线经历（按时间顺序）
+    pub beats: Vec<ChronicleBeat>,
+    /// 生平最强一战（以敌人三维总和衡量）
+    #[serde(default)]
+    pub strongest_battle: Option<ChronicleBattle>,
+    pub ending_event_id: String,
+    pub ending_name: String,
+    #[serde(default)]
+    pub days: u32,
+    #[serde(default)]
+    pub adventures: u32,
+    #[serde(default)]
+    pub battles_won: u32,
+    #[serde(default)]
+    pub battles_lost: u32,
+    #[serde(default)]
+    pub finished_at: u64,
+    /// 按模板生成的文字概述
+    pub summary: String,
+}
+
+/// 传记中的一段经历
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChronicleBeat {
+    pub event_id: String,
+    pub name: String,
+    /// 做出的选择（选项文本）
+    #[serde(default)]
+    pub choice: Option<String>,
+    #[serde(default)]
+    pub battle_win: Option<bool>,
+}
+
+/// 传记中记录的战斗
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChronicleBattle {
+    pub scope: StoryHistoryScope,
+    pub event_id: String,
+    pub event_name: String,
+    pub enemy_name: String,
+    /// 敌人强度（三维总和）
+    pub enemy_power: u32,
+    pub win: bool,
+}
+
+impl Chronicle {
+    /// 记录一场战斗：胜负计数，并在敌人更强时更新最强一战
+    pub fn record_battle(&mut self, battle: ChronicleBattle) {
+        if battle.win {
+            self.battles_won += 1;
+        } else {
+            self.battles_lost += 1;
+        }
+        let stronger = self
+            .strongest_battle
+            .as_ref()
+            .is_none_or(|current| battle.enemy_power > current.enemy_power);
+        if stronger {
+            self.strongest_battle = Some(battle);
+        }
+    }
+
+    /// 按模板生成文字概述
+    pub fn render_summary(&self, character_name: &str, template: &str) -> String {
+        let strongest_enemy = self
+            .strongest_battle
+            .as_ref()
+            .map(|battle| battle.enemy_name.as_str())
+            .unwrap_or("无");
+        template
+            .replace("{name}", character_name)
+            .replace("{storyline}", &self.storyline_name)
+            .replace("{days}", &self.days.to_string())
+            .replace("{adventures}", &self.adventures.to_string())
+            .replace("{battles_won}", &self.battles_won.to_string())
+            .replace("{battles_lost}", &self.battles_lost.to_string())
+            .replace("{strongest_enemy}", strongest_enemy)
+            .replace("{ending}", &self.ending_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chronicle() -> Chronicle {
+        Chronicle {
+            storyline_id: "s".to_string(),
+            storyline_name: "少年游".to_string(),
+            beats: Vec::new(),
+            strongest_battle: None,
+            ending_event_id: "end".to_string(),
+            ending_name: "归隐".to_string(),
+            days: 12,
+            adventures: 3,
+            battles_won: 0,
+            battles_lost: 0,
+            finished_at: 0,
+            summary: String::new(),
+        }
+    }
+
+    fn battle(enemy_name: &str, enemy_power: u32, win: bool) -> ChronicleBattle {
+        ChronicleBattle {
+            scope: StoryHistoryScope::Story,
+            event_id: "e".to_string(),
+            event_name: "比武".to_string(),
+            enemy_name: enemy_name.to_string(),
+            enemy_power,
+            win,
+        }
+    }
+
+    #[test]
+    fn record_battle_keeps_strongest_enemy() {
+        let mut chronicle = chronicle();
+        chronicle.record_battle(battle("山贼", 30, true));
+        chronicle.record_battle(battle("剑客", 80, false));
+        chronicle.record_battle(battle("刀客", 80, true));
+        assert_eq!(chronicle.battles_won, 2);
+        assert_eq!(chronicle.battles_lost, 1);
+        assert_eq!(chronicle.strongest_battle.unwrap().enemy_name, "剑客");
+    }
+
+    #[test]
+    fn render_summary_fills_placeholders() {
+        let mut chronicle = chronicle();
+        assert_eq!(
+            chronicle.render_summary("李四", "{name}|{strongest_enemy}|{ending}"),
+            "李四|无|归隐"
+        );
+        chronicle.record_battle(battle("山贼", 30, true));
+        let summary = chronicle.render_summary("李四", DEFAULT_CHRONICLE_TEMPLATE);
+        assert!(summary.contains("少年游"));
+        assert!(summary.contains("胜1负0"));
+        assert!(summary.contains("山贼"));
+    }
+}
@@ -0,0 +1,131 @@
+/// 武学图鉴：记录存档中首次获得功法、特性与首次交手敌人的时间
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Serialize};
+
+use super::CharacterState;
+
+/// 图鉴条目类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodexKind {
+    Internal,
+    AttackSkill,
+    DefenseSkill,
+    Trait,
+    /// 敌人以名称作为标识
+    Enemy,
+}
+
+impl CodexKind {
+    pub fn parse(value: &str) -> WushenResult<Self> {
+        match value {
+            "internal" => Ok(CodexKind::Internal),
+            "attack_skill" => Ok(CodexKind::AttackSkill),
+            "defense_skill" => Ok(CodexKind::DefenseSkill),
+            "trait" => Ok(CodexKind::Trait),
+            "enemy" => Ok(CodexKind::Enemy),
+            _ => Err(WushenError::ParseError(format!(
+                "未知的图鉴类别: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// 图鉴条目
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodexEntry {
+    pub kind: CodexKind,
+    pub id: String,
+    /// 首次遇到的时间戳（秒）
+    pub first_seen_at: u64,
+    /// 首次遇到时的游戏天数
+    #[serde(default)]
+    pub first_seen_day: u32,
+}
+
+/// 图鉴（按首次遇到顺序记录）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Codex {
+    #[serde(default)]
+    pub entries: Vec<CodexEntry>,
+}
+
+impl Codex {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, kind: CodexKind, id: &str) -> bool {
+        self.entries.iter().any(|e| e.kind == kind && e.id == id)
+    }
+
+    /// 记录条目，首次遇到时返回 true
+    pub fn record(&mut self, kind: CodexKind, id: &str, at: u64, day: u32) -> bool {
+        if id.is_empty() || self.contains(kind, id) {
+            return false;
+        }
+        self.entries.push(CodexEntry {
+            kind,
+            id: id.to_string(),
+            first_seen_at: at,
+            first_seen_day: day,
+        });
+        true
+    }
+
+    /// 收录角色当前拥有的功法与特性
+    pub fn record_character(&mut self, character: &CharacterState, at: u64, day: u32) {
+        let manuals = [
+            (CodexKind::Internal, &character.internals),
+            (CodexKind::AttackSkill, &character.attack_skills),
+            (CodexKind::DefenseSkill, &character.defense_skills),
+        ];
+        for (kind, manuals) in manuals {
+            for manual in &manuals.owned {
+                self.record(kind, &manual.id, at, day);
+            }
+        }
+        for trait_id in &character.traits {
+            self.record(CodexKind::Trait, trait_id, at, day);
+        }
+    }
+
+    /// 指定类别的条目（kind 为空时返回全部）
+    pub fn entries_of(&self, kind: Option<CodexKind>) -> impl Iterator<Item = &CodexEntry> {
+        self.entries
+            .iter()
+            .filter(move |e| kind.is_none_or(|kind| e.kind == kind))
+    }
+}
+
+/// 图鉴条目视图（附带内容中的名称）
+#[derive(Debug, Clone, Serialize)]
+pub struct CodexEntryView {
+    pub kind: CodexKind,
+    pub id: String,
+    pub name: String,
+    pub first_seen_at: u64,
+    pub first_seen_day: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_keeps_first_encounter() {
+        let mut codex = Codex::default();
+        assert!(codex.record(CodexKind::Enemy, "山贼", 10, 1));
+        assert!(!codex.record(CodexKind::Enemy, "山贼", 20, 2));
+        assert!(codex.record(CodexKind::Trait, "山贼", 30, 3));
+        assert!(!codex.record(CodexKind::Trait, "", 30, 3));
+
+        let enemies: Vec<_> = codex.entries_of(Some(CodexKind::Enemy)).collect();
+        assert_eq!(enemies.len(), 1);
+        assert_eq!(enemies[0].first_seen_at, 10);
+        assert_eq!(codex.entries_of(None).count(), 2);
+        assert_eq!(CodexKind::parse("attack_skill"), Ok(CodexKind::AttackSkill));
+        assert!(CodexKind::parse("weapon").is_err());
+    }
+}
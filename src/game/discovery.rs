@@ -0,0 +1,153 @@
+/// 奇遇图鉴：记录遇到过与完成过的奇遇，供图鉴界面展示（未发现的条目隐藏名称）
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Serialize};
+
+use crate::event::AdventureEvent;
+
+/// 奇遇发现记录（可按存档保存，也可跨存档合并）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AdventureDiscovery {
+    /// 遇到过的奇遇 ID（按首次遇到顺序）
+    #[serde(default)]
+    pub seen: Vec<String>,
+    /// 完成过的奇遇 ID
+    #[serde(default)]
+    pub completed: Vec<String>,
+}
+
+impl AdventureDiscovery {
+    pub fn from_json(json: &str) -> WushenResult<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| WushenError::ParseError(format!("解析奇遇图鉴失败: {}", e)))
+    }
+
+    pub fn to_json(&self) -> WushenResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| WushenError::ParseError(format!("序列化奇遇图鉴失败: {}", e)))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty() && self.completed.is_empty()
+    }
+
+    /// 记录遇到奇遇，首次遇到时返回 true
+    pub fn record_seen(&mut self, adventure_id: &str) -> bool {
+        if self.seen.iter().any(|id| id == adventure_id) {
+            return false;
+        }
+        self.seen.push(adventure_id.to_string());
+        true
+    }
+
+    /// 记录完成奇遇（同时视为已遇到）
+    pub fn record_completed(&mut self, adventure_id: &str) {
+        self.record_seen(adventure_id);
+        if !self.completed.iter().any(|id| id == adventure_id) {
+            self.completed.push(adventure_id.to_string());
+        }
+    }
+
+    /// 合并另一份记录，返回是否有新增
+    pub fn merge(&mut self, other: &AdventureDiscovery) -> bool {
+        let before = (self.seen.len(), self.completed.len());
+        for id in &other.seen {
+            self.record_seen(id);
+        }
+        for id in &other.completed {
+            self.record_completed(id);
+        }
+        before != (self.seen.len(), self.completed.len())
+    }
+
+    pub fn is_seen(&self, adventure_id: &str) -> bool {
+        self.seen.iter().any(|id| id == adventure_id)
+    }
+
+    pub fn is_completed(&self, adventure_id: &str) -> bool {
+        self.completed.iter().any(|id| id == adventure_id)
+    }
+
+    /// 按当前内容生成图鉴视图（条目按 ID 排序）
+    pub fn collection<'a>(
+        &self,
+        adventures: impl IntoIterator<Item = &'a AdventureEvent>,
+    ) -> AdventureCollectionView {
+        let mut entries: Vec<AdventureCollectionEntry> = adventures
+            .into_iter()
+            .map(|adventure| {
+                let discovered = self.is_seen(&adventure.id);
+                AdventureCollectionEntry {
+                    id: adventure.id.clone(),
+                    name: discovered.then(|| adventure.name.clone()),
+                    discovered,
+                    completed: self.is_completed(&adventure.id),
+                }
+            })
+            .collect();
+        entries.sort_by(|a, b| a.id.cmp(&b.id));
+        AdventureCollectionView {
+            discovered: entries.iter().filter(|e| e.discovered).count(),
+            total: entries.len(),
+            entries,
+        }
+    }
+}
+
+/// 奇遇图鉴视图
+#[derive(Debug, Clone, Serialize)]
+pub struct AdventureCollectionView {
+    pub discovered: usize,
+    pub total: usize,
+    pub entries: Vec<AdventureCollectionEntry>,
+}
+
+/// 奇遇图鉴条目（未发现时不返回名称）
+#[derive(Debug, Clone, Serialize)]
+pub struct AdventureCollectionEntry {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub discovered: bool,
+    pub completed: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::AdventureEventContent;
+
+    fn adventure(id: &str) -> AdventureEvent {
+        AdventureEvent {
+            id: id.to_string(),
+            name: format!("奇遇{}", id),
+            trigger: None,
+            seasons: Vec::new(),
+            content: AdventureEventContent::Story {
+                text: String::new(),
+                rewards: Vec::new(),
+            },
+            presentation: None,
+        }
+    }
+
+    #[test]
+    fn collection_masks_undiscovered_entries() {
+        let mut discovery = AdventureDiscovery::default();
+        assert!(discovery.record_seen("b"));
+        assert!(!discovery.record_seen("b"));
+
+        let mut global = AdventureDiscovery::default();
+        global.record_completed("a");
+        assert!(discovery.merge(&global));
+        assert!(!discovery.merge(&global));
+
+        let adventures = [adventure("c"), adventure("a"), adventure("b")];
+        let view = discovery.collection(&adventures);
+        assert_eq!((view.discovered, view.total), (2, 3));
+        assert_eq!(view.entries[0].id, "a");
+        assert!(view.entries[0].completed);
+        assert_eq!(view.entries[1].name.as_deref(), Some("奇遇b"));
+        assert!(!view.entries[1].completed);
+        assert!(view.entries[2].name.is_none());
+    }
+}
@@ -1,12 +1,75 @@
+use crate::error::{WushenError, WushenResult};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::event::{Reward, StoryNodeType};
+pub(crate) mod action_cost;
+pub(crate) mod analytics;
+pub(crate) mod battle_archive;
+pub(crate) mod battle_session;
+pub(crate) mod build;
+pub(crate) mod calendar;
+pub(crate) mod challenge;
+pub(crate) mod chronicle;
+pub(crate) mod codex;
+pub(crate) mod discovery;
+pub(crate) mod party;
+pub(crate) mod roster;
+pub(crate) mod save_envelope;
+pub(crate) mod save_format;
+pub(crate) mod segments;
+pub(crate) mod starting_kit;
+pub(crate) mod view_diff;
+
+pub use action_cost::{ActionCostConfig, ActionKind};
+pub use analytics::{
+    AnalyticsReport, BattleTally, EnemyReport, NodeReport, NodeTally, SaveAnalytics,
+};
+pub use battle_archive::{ArchivedBattle, BattleArchivePolicy};
+pub use battle_session::BattleSession;
+pub use build::{CharacterBuild, CHARACTER_BUILD_VERSION};
+pub use calendar::{CalendarConfig, CalendarView, Season, SeasonalValues};
+pub use challenge::{ChallengeRuleset, ScoreMultipliers};
+pub use chronicle::{Chronicle, ChronicleBattle, ChronicleBeat, DEFAULT_CHRONICLE_TEMPLATE};
+pub use codex::{Codex, CodexEntry, CodexEntryView, CodexKind};
+pub use discovery::{AdventureCollectionEntry, AdventureCollectionView, AdventureDiscovery};
+pub use party::{Party, TrainingAssignment, TrainingReport};
+pub use roster::{Roster, RosterEntry};
+pub use save_envelope::{content_hash, IntegrityPolicy, PackHash, SaveEnvelope, ENGINE_VERSION};
+pub use save_format::{
+    convert_save, decode_save, decode_save_value, encode_save, encode_save_value, SaveFormat,
+};
+pub use segments::{SaveSegments, Segment, SegmentKind, SegmentRef};
+pub use starting_kit::{StartingKit, StartingManual};
+pub use view_diff::{GameViewDiff, StorySectionView, ViewTracker};
+
+use crate::battle::battle_roster::EnemyOutcome;
+use crate::battle::battle_setup::UnresolvedManual;
+use crate::effect::entry::Entry;
+use crate::event::{
+    CheckStat, DefeatPolicy, DuplicateReward, EnemyTemplate, OptionCost, PostBattleRecovery,
+    Presentation, Reward, StoryNodeType,
+};
+use crate::pack::ContentFingerprint;
 
 #[derive(Debug, Clone)]
 pub struct GameRuntime {
     pub save: SaveGame,
+    /// 视图版本追踪（用于差量视图）
+    pub view_tracker: ViewTracker,
+    /// 进行中的手动剧情战斗
+    pub battle_session: Option<BattleSession>,
+}
+
+impl GameRuntime {
+    pub fn new(save: SaveGame) -> Self {
+        Self {
+            save,
+            view_tracker: ViewTracker::default(),
+            battle_session: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,8 +87,162 @@ pub struct SaveGame {
     pub completed_characters: Vec<CharacterState>,
     #[serde(default)]
     pub rng_state: u64,
+    /// 剧情历史（单独分段存储，存档主体只记录条目数）
+    #[serde(default)]
+    pub story_history: Segment<StoryHistoryRecord>,
+    /// 难度覆盖的战后恢复规则（为空时使用剧情线配置）
+    #[serde(default)]
+    pub post_battle_recovery: Option<PostBattleRecovery>,
+    /// 进行中的悬赏任务
+    #[serde(default)]
+    pub active_quests: Vec<QuestProgress>,
+    /// 已完成的悬赏任务 ID（可重复任务每完成一次记录一次）
+    #[serde(default)]
+    pub completed_quests: Vec<String>,
+    /// 游戏内已经过的天数（由行动推进）
+    #[serde(default)]
+    pub day: u32,
+    /// 本地数据统计（为空表示未开启）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub analytics: Option<SaveAnalytics>,
+    /// 已完成（已发放奖励）的章节 ID
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub completed_chapters: Vec<String>,
+    /// 已战胜、等待战后抉择的战斗事件 ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_victory_decision: Option<String>,
+    /// 奇遇图鉴（本存档遇到过与完成过的奇遇）
+    #[serde(default, skip_serializing_if = "AdventureDiscovery::is_empty")]
+    pub adventure_discovery: AdventureDiscovery,
+    /// 武学图鉴（首次获得的功法、特性与首次交手的敌人）
+    #[serde(default, skip_serializing_if = "Codex::is_empty")]
+    pub codex: Codex,
+    /// 战斗存档保留策略（为空表示不存档战斗）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub battle_archive_policy: Option<BattleArchivePolicy>,
+    /// 已存档的战斗（单独分段存储）
+    #[serde(default)]
+    pub battle_archive: Segment<ArchivedBattle>,
+    /// 累计存档的战斗数（用于生成战斗存档 ID）
+    #[serde(default)]
+    pub battles_archived: u64,
+    /// 本局生效的挑战规则
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub challenge: Option<ChallengeRuleset>,
+    /// 难度设置：覆盖剧情线的战败处理策略
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defeat_policy: Option<DefeatPolicy>,
+    /// 战败重试累计的行动点惩罚（从之后有行动点的节点中扣除）
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub action_point_penalty: u32,
+    /// 创建存档时的内容指纹（旧存档为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_fingerprint: Option<ContentFingerprint>,
+    /// 队伍成员与共享资源（单人存档为空）
+    #[serde(default, skip_serializing_if = "Party::is_empty")]
+    pub party: Party,
+}
+
+impl SaveGame {
+    /// 替换当前角色，并将新获得的功法与特性收录进图鉴
+    pub fn set_current_character(&mut self, character: CharacterState) {
+        self.current_character = character;
+        self.sync_codex();
+    }
+
+    /// 将当前角色拥有的功法与特性收录进图鉴
+    pub fn sync_codex(&mut self) {
+        self.codex
+            .record_character(&self.current_character, now_timestamp(), self.day);
+    }
+
+    /// 检查所有存档分段均已加载
+    pub fn ensure_segments_loaded(&self) -> WushenResult<()> {
+        for kind in SegmentKind::ALL {
+            let loaded = match kind {
+                SegmentKind::StoryHistory => self.story_history.is_loaded(),
+                SegmentKind::BattleArchive => self.battle_archive.is_loaded(),
+            };
+            if !loaded {
+                return Err(WushenError::InvalidState(format!(
+                    "存档分段未加载：{}",
+                    kind.as_str()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// 载入宿主保存的分段数据（已加载的分段，如旧存档内联的数据，保持不变）
+    pub fn attach_segments(&mut self, segments: SaveSegments) -> WushenResult<()> {
+        if let Some(records) = segments.story_history {
+            if !self.story_history.is_loaded() {
+                self.story_history.load(records)?;
+            }
+        }
+        if let Some(battles) = segments.battle_archive {
+            if !self.battle_archive.is_loaded() {
+                self.battle_archive.load(battles)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 取出自上次调用以来发生变化的分段（供宿主持久化）
+    pub fn take_dirty_segments(&mut self) -> SaveSegments {
+        SaveSegments {
+            story_history: self
+                .story_history
+                .take_dirty()
+                .then(|| self.story_history.to_vec()),
+            battle_archive: self
+                .battle_archive
+                .take_dirty()
+                .then(|| self.battle_archive.to_vec()),
+        }
+    }
+
+    /// 按保留策略存档一场战斗（未开启战斗存档时返回 None）
+    pub fn archive_battle(
+        &mut self,
+        scope: StoryHistoryScope,
+        event_id: &str,
+        enemy_name: &str,
+        win: bool,
+        result: &Value,
+    ) -> Option<String> {
+        let policy = self.battle_archive_policy.as_ref()?;
+        self.battles_archived += 1;
+        let id = format!("battle-{}", self.battles_archived);
+        policy.archive(
+            &mut self.battle_archive,
+            ArchivedBattle {
+                id: id.clone(),
+                scope,
+                event_id: event_id.to_string(),
+                enemy_name: enemy_name.to_string(),
+                win,
+                day: self.day,
+                result: result.clone(),
+            },
+        );
+        Some(id)
+    }
+
+    /// 将交手的敌人收录进图鉴
+    pub fn record_codex_enemy(&mut self, enemy_name: &str) {
+        self.codex
+            .record(CodexKind::Enemy, enemy_name, now_timestamp(), self.day);
+    }
+}
+
+/// 悬赏任务进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestProgress {
+    pub quest_id: String,
+    /// 接取后战斗胜利场数
     #[serde(default)]
-    pub story_history: Vec<StoryHistoryRecord>,
+    pub battles_won: u32,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -43,6 +260,12 @@ pub struct StoryHistoryRecord {
     pub option_id: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub battle_win: Option<bool>,
+    /// 存档的战斗 ID（开启战斗存档时记录）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub battle_id: Option<String>,
+    /// 奇遇被放弃（未作出选择）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,12 +286,277 @@ pub struct CharacterState {
     pub action_points: u32,
     #[serde(default)]
     pub cultivation_history: Vec<CultivationHistoryItem>,
+    /// 生命上限（旧存档缺省时按体魄推导）
+    #[serde(default)]
+    pub max_hp: Option<f64>,
+    /// 当前生命（跨战斗保留，旧存档缺省时视为满血）
+    #[serde(default)]
+    pub hp: Option<f64>,
+    /// 基础攻击力（含永久强化，旧存档缺省时按体魄推导）
+    #[serde(default)]
+    pub base_attack: Option<f64>,
+    /// 基础防御力（含永久强化，旧存档缺省时按体魄推导）
+    #[serde(default)]
+    pub base_defense: Option<f64>,
     #[serde(default)]
     pub max_qi: Option<f64>,
     #[serde(default)]
     pub qi: Option<f64>,
     #[serde(default)]
     pub martial_arts_attainment: Option<f64>,
+    /// 限时增益（按剩余行动次数计时）
+    #[serde(default)]
+    pub timed_buffs: Vec<TimedBuff>,
+    /// 预约的后续奇遇（按剩余行动次数计时，到期后下一次游历触发）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scheduled_events: Vec<ScheduledEvent>,
+    /// 剧情标记
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub story_flags: Vec<String>,
+    /// 已获得的称号
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub titles: Vec<Title>,
+    /// 佩戴中的称号 ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worn_title: Option<String>,
+    /// 背包中的物品
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inventory: Vec<ItemStack>,
+    /// 角色传记（完成剧情后生成）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chronicle: Option<Chronicle>,
+    /// 外观（创建角色时选择，随存档保留）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub appearance: Option<CharacterAppearance>,
+}
+
+/// 已完成角色摘要
+#[derive(Debug, Clone, Serialize)]
+pub struct CompletedCharacterSummary {
+    /// 在已完成列表中的下标
+    pub index: usize,
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storyline_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ending_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appearance: Option<CharacterAppearance>,
+}
+
+/// 角色外观（资源键与外观选项由界面自行解析）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CharacterAppearance {
+    /// 头像/立绘资源键
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub portrait_key: Option<String>,
+    /// 其余外观选项（如发色、服饰）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub options: BTreeMap<String, String>,
+}
+
+impl CharacterAppearance {
+    pub fn validate(&self) -> WushenResult<()> {
+        if self
+            .portrait_key
+            .as_ref()
+            .is_some_and(|key| key.trim().is_empty())
+        {
+            return Err(WushenError::Invalid("头像资源键不能为空".to_string()));
+        }
+        if self.options.keys().any(|key| key.trim().is_empty()) {
+            return Err(WushenError::Invalid("外观选项名不能为空".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// 限时增益（奇遇顿悟等），在剩余行动次数内提供额外词条
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimedBuff {
+    pub id: String,
+    pub name: String,
+    pub entries: Vec<Entry>,
+    pub remaining_actions: u32,
+}
+
+/// 背包中的一种物品及其数量
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemStack {
+    pub id: String,
+    pub count: u32,
+}
+
+/// 预约的后续奇遇，剩余行动次数归零后到期
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledEvent {
+    pub adventure_id: String,
+    pub remaining_actions: u32,
+}
+
+/// 称号（佩戴时提供词条）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Title {
+    pub id: String,
+    /// 显示名称
+    pub name: String,
+    #[serde(default)]
+    pub entries: Vec<Entry>,
+}
+
+/// 称号列表项
+#[derive(Debug, Clone, Serialize)]
+pub struct TitleView {
+    pub id: String,
+    pub name: String,
+    pub worn: bool,
+}
+
+impl CharacterState {
+    /// 生成摘要（用于已完成角色列表）
+    pub fn completed_summary(&self, index: usize) -> CompletedCharacterSummary {
+        let chronicle = self.chronicle.as_ref();
+        CompletedCharacterSummary {
+            index,
+            id: self.id.clone(),
+            name: self.name.clone(),
+            storyline_name: chronicle.map(|c| c.storyline_name.clone()),
+            ending_name: chronicle.map(|c| c.ending_name.clone()),
+            finished_at: chronicle.map(|c| c.finished_at),
+            summary: chronicle.map(|c| c.summary.clone()),
+            appearance: self.appearance.clone(),
+        }
+    }
+
+    /// 综合评分：三维总和 + 功法总等级 × 10 + 武学素养
+    pub fn score(&self) -> f64 {
+        self.score_with(&ScoreMultipliers::default())
+    }
+
+    /// 按计分倍率计算综合评分（挑战规则）
+    pub fn score_with(&self, multipliers: &ScoreMultipliers) -> f64 {
+        let three_d =
+            self.three_d.comprehension + self.three_d.bone_structure + self.three_d.physique;
+        let levels: u32 = [&self.internals, &self.attack_skills, &self.defense_skills]
+            .iter()
+            .flat_map(|manuals| manuals.owned.iter())
+            .map(|m| m.level)
+            .sum();
+        three_d as f64 * multipliers.three_d
+            + levels as f64 * 10.0 * multipliers.manual_levels
+            + self.martial_arts_attainment.unwrap_or(0.0) * multipliers.martial_arts_attainment
+    }
+
+    /// 转换为敌人模板（仅保留当前装备的功法），可作为切磋对手
+    pub fn to_enemy_template(&self) -> EnemyTemplate {
+        CharacterBuild::from_character(self).to_enemy_template()
+    }
+
+    /// 添加限时增益；同 ID 增益刷新为较长的剩余次数
+    pub fn add_timed_buff(&mut self, buff: TimedBuff) {
+        if buff.remaining_actions == 0 {
+            return;
+        }
+        match self.timed_buffs.iter_mut().find(|b| b.id == buff.id) {
+            Some(existing) => {
+                let remaining = existing.remaining_actions.max(buff.remaining_actions);
+                *existing = buff;
+                existing.remaining_actions = remaining;
+            }
+            None => self.timed_buffs.push(buff),
+        }
+    }
+
+    /// 当前生效的限时增益词条
+    pub fn timed_buff_entries(&self) -> Vec<Entry> {
+        self.timed_buffs
+            .iter()
+            .flat_map(|buff| buff.entries.iter().cloned())
+            .collect()
+    }
+
+    /// 获得称号；已拥有同 ID 称号时以新内容替换
+    pub fn add_title(&mut self, title: Title) {
+        match self.titles.iter_mut().find(|t| t.id == title.id) {
+            Some(existing) => *existing = title,
+            None => self.titles.push(title),
+        }
+    }
+
+    /// 佩戴称号（为 None 时卸下）
+    pub fn wear_title(&mut self, title_id: Option<&str>) -> WushenResult<()> {
+        if let Some(id) = title_id {
+            if !self.titles.iter().any(|t| t.id == id) {
+                return Err(WushenError::ConditionNotMet(format!("尚未获得称号 {}", id)));
+            }
+        }
+        self.worn_title = title_id.map(str::to_string);
+        Ok(())
+    }
+
+    /// 佩戴中的称号
+    pub fn current_title(&self) -> Option<&Title> {
+        let id = self.worn_title.as_ref()?;
+        self.titles.iter().find(|t| &t.id == id)
+    }
+
+    /// 佩戴中称号的词条
+    pub fn title_entries(&self) -> Vec<Entry> {
+        self.current_title()
+            .map(|title| title.entries.clone())
+            .unwrap_or_default()
+    }
+
+    /// 称号列表
+    pub fn title_views(&self) -> Vec<TitleView> {
+        self.titles
+            .iter()
+            .map(|title| TitleView {
+                id: title.id.clone(),
+                name: title.name.clone(),
+                worn: self.worn_title.as_deref() == Some(title.id.as_str()),
+            })
+            .collect()
+    }
+
+    /// 消耗一次行动：所有增益剩余次数减一，移除到期的增益
+    pub fn tick_timed_buffs(&mut self) {
+        for buff in &mut self.timed_buffs {
+            buff.remaining_actions = buff.remaining_actions.saturating_sub(1);
+        }
+        self.timed_buffs.retain(|buff| buff.remaining_actions > 0);
+    }
+
+    /// 预约后续奇遇（同一奇遇已预约时以新的计时为准）
+    pub fn schedule_event(&mut self, adventure_id: &str, actions: u32) {
+        self.scheduled_events
+            .retain(|event| event.adventure_id != adventure_id);
+        self.scheduled_events.push(ScheduledEvent {
+            adventure_id: adventure_id.to_string(),
+            remaining_actions: actions,
+        });
+    }
+
+    /// 消耗一次行动：预约奇遇的剩余次数减一
+    pub fn tick_scheduled_events(&mut self) {
+        for event in &mut self.scheduled_events {
+            event.remaining_actions = event.remaining_actions.saturating_sub(1);
+        }
+    }
+
+    /// 取出最早预约的到期奇遇
+    pub fn take_due_event(&mut self) -> Option<String> {
+        let index = self
+            .scheduled_events
+            .iter()
+            .position(|event| event.remaining_actions == 0)?;
+        Some(self.scheduled_events.remove(index).adventure_id)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -104,6 +592,21 @@ pub struct NewGameRequest {
     pub character_id: String,
     pub name: String,
     pub three_d: ThreeDimensionalState,
+    /// 难度设置：覆盖剧情线的战后恢复规则
+    #[serde(default)]
+    pub post_battle_recovery: Option<PostBattleRecovery>,
+    /// 难度设置：覆盖剧情线的战败处理策略
+    #[serde(default)]
+    pub defeat_policy: Option<DefeatPolicy>,
+    /// 是否开启本地数据统计
+    #[serde(default)]
+    pub analytics: bool,
+    /// 挑战规则（固定种子、禁用内容、难度与计分倍率）
+    #[serde(default)]
+    pub challenge: Option<ChallengeRuleset>,
+    /// 创建角色时选择的外观
+    #[serde(default)]
+    pub appearance: Option<CharacterAppearance>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -111,12 +614,21 @@ pub struct GameResponse {
     pub view: GameView,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub outcome: Option<GameOutcome>,
+    /// 提示信息（如恢复存档时内容已变化）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// 本次操作进入新剧情节点时，队伍成员按安排修行的结果
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub training: Vec<TrainingReport>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct GameView {
     pub save: SaveGame,
     pub storyline: Option<StorylineSummary>,
+    /// 当前章节（剧情线未分章节时为空）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chapter: Option<ChapterView>,
     pub phase: GamePhase,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub current_event: Option<StoryEventSummary>,
@@ -124,6 +636,42 @@ pub struct GameView {
     pub story_event: Option<StoryEventView>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub adventure: Option<AdventureDecisionView>,
+    /// 悬赏榜
+    pub quests: Vec<QuestView>,
+    /// 当前日期与季节
+    pub calendar: CalendarView,
+}
+
+/// 悬赏任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestStatus {
+    /// 可接取
+    Available,
+    /// 进行中
+    Active,
+    /// 目标已达成，可领取奖励
+    Completable,
+    /// 已完成（不可重复）
+    Completed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuestView {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub status: QuestStatus,
+    pub repeatable: bool,
+    pub objectives: Vec<QuestObjectiveView>,
+    pub rewards: Vec<Reward>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuestObjectiveView {
+    pub text: String,
+    pub current: u32,
+    pub target: u32,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -132,6 +680,17 @@ pub struct StorylineSummary {
     pub name: String,
 }
 
+/// 章节信息
+#[derive(Debug, Clone, Serialize)]
+pub struct ChapterView {
+    pub id: String,
+    pub title: String,
+    pub summary: String,
+    /// 章节序号（从 0 开始）
+    pub index: usize,
+    pub total: usize,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct StoryEventSummary {
     pub id: String,
@@ -139,12 +698,14 @@ pub struct StoryEventSummary {
     pub node_type: StoryNodeType,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum GamePhase {
     Action,
     Story,
     AdventureDecision,
+    /// 剧情抉择没有任何可选选项（诊断见抉择视图的 blocked）
+    Blocked,
     Completed,
 }
 
@@ -155,6 +716,8 @@ pub struct StoryEventView {
     pub node_type: StoryNodeType,
     pub action_points: u32,
     pub content: StoryEventContentView,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presentation: Option<Presentation>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -163,15 +726,31 @@ pub enum StoryEventContentView {
     Decision {
         text: String,
         options: Vec<StoryOptionView>,
+        /// 没有可选选项时，各选项不可选的原因
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        blocked: Vec<BlockedOptionView>,
     },
     Battle {
         text: String,
         enemy_name: String,
+        /// 训练轮数（训练战斗才有）
+        #[serde(skip_serializing_if = "Option::is_none")]
+        training_rounds: Option<u32>,
+        /// 双方装备但未加载的功法（开战前提示，按未装备参战）
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        unresolved_manuals: Vec<UnresolvedManual>,
     },
     Story {
         text: String,
         rewards: Vec<Reward>,
     },
+    Check {
+        text: String,
+        stat: CheckStat,
+        success_threshold: f64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        critical_threshold: Option<f64>,
+    },
     End {
         text: String,
     },
@@ -182,6 +761,17 @@ pub struct StoryOptionView {
     pub id: String,
     pub text: String,
     pub next_event_id: String,
+    /// 选择时付出的代价
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub costs: Vec<OptionCost>,
+}
+
+/// 不可选的剧情选项及原因
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockedOptionView {
+    pub id: String,
+    pub text: String,
+    pub reasons: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -190,15 +780,24 @@ pub struct AdventureDecisionView {
     pub name: String,
     pub text: String,
     pub options: Vec<AdventureOptionView>,
+    /// 放弃奇遇时付出的代价
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skip_costs: Vec<OptionCost>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presentation: Option<Presentation>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct AdventureOptionView {
     pub id: String,
     pub text: String,
+    /// 选择时付出的代价
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub costs: Vec<OptionCost>,
 }
 
 #[derive(Debug, Clone, Serialize)]
+#[allow(clippy::large_enum_variant)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum GameOutcome {
     Info {
@@ -215,18 +814,61 @@ pub enum GameOutcome {
     Story {
         text: Option<String>,
         rewards: Vec<Reward>,
+        /// 重复获得的奖励及处理方式
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        duplicates: Vec<DuplicateReward>,
         battle_result: Option<Value>,
+        /// 各敌人的结果（按出场序号，一对多战斗时有多个）
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        enemies: Vec<EnemyOutcome>,
         win: Option<bool>,
+        recovery: Option<BattleRecovery>,
+        /// 战败时生效的处理策略
+        #[serde(skip_serializing_if = "Option::is_none")]
+        defeat: Option<DefeatPolicy>,
     },
     Adventure {
         name: String,
         text: Option<String>,
         rewards: Vec<Reward>,
+        /// 重复获得的奖励及处理方式
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        duplicates: Vec<DuplicateReward>,
         battle_result: Option<Value>,
+        /// 各敌人的结果（按出场序号，一对多战斗时有多个）
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        enemies: Vec<EnemyOutcome>,
         win: Option<bool>,
+        recovery: Option<BattleRecovery>,
+    },
+    Quest {
+        name: String,
+        rewards: Vec<Reward>,
+        /// 重复获得的奖励及处理方式
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        duplicates: Vec<DuplicateReward>,
+    },
+    /// 剧情完成，附带写入已完成角色的最终状态与传记
+    Finished {
+        message: String,
+        character: CharacterState,
     },
 }
 
+/// 战后恢复结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BattleRecovery {
+    pub rule: PostBattleRecovery,
+    /// 战斗结束时的生命
+    pub hp_before: f64,
+    /// 恢复后的生命
+    pub hp_after: f64,
+    /// 战斗结束时的内息
+    pub qi_before: f64,
+    /// 恢复后的内息
+    pub qi_after: f64,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct SimpleRng {
     state: u64,
@@ -261,6 +903,11 @@ impl SimpleRng {
         }
         (self.next_u64() % modulo as u64) as usize
     }
+
+    /// [0, 1) 区间的随机数
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
 }
 
 pub fn seed_from_time() -> u64 {
@@ -276,3 +923,44 @@ pub fn now_timestamp() -> u64 {
         .map(|d| d.as_secs())
         .unwrap_or(0)
 }
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn character() -> CharacterState {
+        let empty = serde_json::json!({"owned": [], "equipped": null});
+        serde_json::from_value(serde_json::json!({
+            "id": "c", "name": "c",
+            "three_d": {"comprehension": 10, "bone_structure": 10, "physique": 10},
+            "traits": [], "internals": empty, "attack_skills": empty, "defense_skills": empty,
+            "action_points": 0
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn scheduled_events_count_down_and_keep_the_latest_booking() {
+        let mut character = character();
+        character.schedule_event("约定", 3);
+        character.schedule_event("约定", 1);
+        character.schedule_event("重逢", 2);
+        assert_eq!(character.scheduled_events.len(), 2);
+        assert_eq!(character.scheduled_events[0].remaining_actions, 1);
+        assert_eq!(character.take_due_event(), None);
+
+        character.tick_scheduled_events();
+        assert_eq!(character.take_due_event().as_deref(), Some("约定"));
+        assert_eq!(character.take_due_event(), None);
+
+        character.tick_scheduled_events();
+        character.tick_scheduled_events();
+        assert_eq!(character.scheduled_events[0].remaining_actions, 0);
+        assert_eq!(character.take_due_event().as_deref(), Some("重逢"));
+        assert!(character.scheduled_events.is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+/// 队伍：主角之外同行的角色（如门下弟子）与全队共享的资源，为门派经营类剧情打基础
+///
+/// 当前操控的角色始终保存在 `SaveGame::current_character`，其余成员保存在队伍中，
+/// 切换操控角色时两者互换；单人存档的队伍为空，行为与组队前一致
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{CharacterState, SaveGame};
+
+/// 存档中的队伍
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Party {
+    /// 主角 ID（为空表示尚未组队，当前角色即主角）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protagonist_id: Option<String>,
+    /// 未被操控的队伍成员
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<CharacterState>,
+    /// 全队共享的资源（资源 ID -> 数量）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub resources: BTreeMap<String, u32>,
+    /// 成员的修行安排（角色 ID -> 安排），未被操控的成员每到新的剧情节点按安排自行修行
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub training: BTreeMap<String, TrainingAssignment>,
+}
+
+/// 修行安排：指定修行的功法与每个剧情节点分配的行动点（每点修行一次）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrainingAssignment {
+    pub manual_id: String,
+    /// internal / attack_skill / defense_skill
+    pub manual_type: String,
+    #[serde(default = "default_training_points")]
+    pub action_points: u32,
+}
+
+fn default_training_points() -> u32 {
+    1
+}
+
+/// 成员在一个剧情节点内的修行结果
+#[derive(Debug, Clone, Serialize)]
+pub struct TrainingReport {
+    pub character_id: String,
+    pub name: String,
+    pub manual_id: String,
+    pub manual_type: String,
+    /// 实际修行次数（功法已满级等无法继续修行时提前结束）
+    pub sessions: u32,
+    pub exp_gain: f64,
+    pub old_level: u32,
+    pub new_level: u32,
+}
+
+impl Party {
+    pub fn is_empty(&self) -> bool {
+        self.protagonist_id.is_none()
+            && self.members.is_empty()
+            && self.resources.is_empty()
+            && self.training.is_empty()
+    }
+
+    /// 共享资源数量（未记录时为 0）
+    pub fn resource(&self, resource_id: &str) -> u32 {
+        self.resources.get(resource_id).copied().unwrap_or(0)
+    }
+
+    /// 增减共享资源，返回调整后的数量；不足时不做修改
+    pub fn adjust_resource(&mut self, resource_id: &str, delta: i64) -> WushenResult<u32> {
+        let amount = i64::from(self.resource(resource_id)) + delta;
+        if amount < 0 {
+            return Err(WushenError::ConditionNotMet(format!(
+                "资源 {} 不足（现有 {}，需要 {}）",
+                resource_id,
+                self.resource(resource_id),
+                -delta
+            )));
+        }
+        let amount = u32::try_from(amount)
+            .map_err(|_| WushenError::ConditionNotMet(format!("资源 {} 数量过大", resource_id)))?;
+        if amount == 0 {
+            self.resources.remove(resource_id);
+        } else {
+            self.resources.insert(resource_id.to_string(), amount);
+        }
+        Ok(amount)
+    }
+}
+
+impl SaveGame {
+    /// 主角 ID
+    pub fn protagonist_id(&self) -> &str {
+        self.party
+            .protagonist_id
+            .as_deref()
+            .unwrap_or(&self.current_character.id)
+    }
+
+    /// 全部队伍成员（当前操控的角色在前）
+    pub fn party_members(&self) -> impl Iterator<Item = &CharacterState> {
+        std::iter::once(&self.current_character).chain(self.party.members.iter())
+    }
+
+    /// 加入队伍成员（角色 ID 在队伍中须唯一）
+    pub fn add_party_member(&mut self, character: CharacterState) -> WushenResult<()> {
+        if self.party_members().any(|member| member.id == character.id) {
+            return Err(WushenError::Invalid(format!(
+                "队伍中已有角色 {}",
+                character.id
+            )));
+        }
+        if self.party.protagonist_id.is_none() {
+            self.party.protagonist_id = Some(self.current_character.id.clone());
+        }
+        self.party.members.push(character);
+        Ok(())
+    }
+
+    /// 安排队伍成员修行（为 None 时取消安排）；所修功法须为该成员已拥有的功法
+    pub fn assign_training(
+        &mut self,
+        character_id: &str,
+        assignment: Option<TrainingAssignment>,
+    ) -> WushenResult<()> {
+        let member = self
+            .party_members()
+            .find(|member| member.id == character_id)
+            .ok_or_else(|| WushenError::NotFound(format!("队伍中没有角色 {}", character_id)))?;
+        let Some(assignment) = assignment else {
+            self.party.training.remove(character_id);
+            return Ok(());
+        };
+        let manuals = match assignment.manual_type.as_str() {
+            "internal" => &member.internals,
+            "attack_skill" => &member.attack_skills,
+            "defense_skill" => &member.defense_skills,
+            other => return Err(WushenError::Invalid(format!("未知的功法类型: {}", other))),
+        };
+        if !manuals.owned.iter().any(|m| m.id == assignment.manual_id) {
+            return Err(WushenError::ConditionNotMet(format!(
+                "{} 尚未习得功法 {}",
+                member.name, assignment.manual_id
+            )));
+        }
+        if assignment.action_points == 0 {
+            return Err(WushenError::Invalid("修行安排的行动点至少为 1".to_string()));
+        }
+        self.party
+            .training
+            .insert(character_id.to_string(), assignment);
+        Ok(())
+    }
+
+    /// 切换操控的角色：原操控角色回到队伍，目标成员成为当前角色
+    pub fn switch_character(&mut self, character_id: &str) -> WushenResult<()> {
+        if self.current_character.id == character_id {
+            return Ok(());
+        }
+        let index = self
+            .party
+            .members
+            .iter()
+            .position(|member| member.id == character_id)
+            .ok_or_else(|| WushenError::NotFound(format!("队伍中没有角色 {}", character_id)))?;
+        std::mem::swap(&mut self.current_character, &mut self.party.members[index]);
+        self.sync_codex();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn save() -> SaveGame {
+        let character = serde_json::json!({
+            "id": "主角", "name": "主角",
+            "three_d": {"comprehension": 10, "bone_structure": 10, "physique": 10},
+            "traits": [],
+            "internals": {"owned": [], "equipped": null},
+            "attack_skills": {"owned": [], "equipped": null},
+            "defense_skills": {"owned": [], "equipped": null},
+            "action_points": 3
+        });
+        serde_json::from_value(serde_json::json!({
+            "id": "s", "name": "s",
+            "current_character": character,
+            "storyline_progress": null,
+            "completed_characters": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn switching_swaps_controlled_character_and_keeps_resources() {
+        let mut save = save();
+        assert!(save.party.is_empty());
+        assert_eq!(save.protagonist_id(), "主角");
+        assert!(!serde_json::to_string(&save).unwrap().contains("party"));
+
+        let mut disciple = save.current_character.clone();
+        disciple.id = "弟子".to_string();
+        disciple.action_points = 0;
+        save.add_party_member(disciple.clone()).unwrap();
+        assert!(save.add_party_member(disciple).is_err());
+        save.party.adjust_resource("银两", 100).unwrap();
+
+        save.switch_character("弟子").unwrap();
+        assert_eq!(save.current_character.id, "弟子");
+        assert_eq!(save.protagonist_id(), "主角");
+        assert_eq!(save.party.members[0].action_points, 3);
+        assert!(save.switch_character("路人").is_err());
+
+        assert!(save.party.adjust_resource("银两", -101).is_err());
+        assert_eq!(save.party.adjust_resource("银两", -100).unwrap(), 0);
+        assert_eq!(save.party.resource("银两"), 0);
+    }
+}
@@ -0,0 +1,208 @@
+/// 名人堂：跨存档汇总已完成角色，可作为切磋对手来源
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Serialize};
+
+use super::{CharacterState, SaveGame};
+use crate::event::EnemyTemplate;
+
+/// 名人堂条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RosterEntry {
+    /// 来源存档 ID
+    pub save_id: String,
+    pub character: CharacterState,
+    /// 综合评分（用于排序）
+    pub score: f64,
+}
+
+impl RosterEntry {
+    /// 去重键：同一存档中同一角色的同一次完成只记录一次
+    fn key(&self) -> (&str, &str, u64) {
+        (
+            self.save_id.as_str(),
+            self.character.id.as_str(),
+            self.character
+                .chronicle
+                .as_ref()
+                .map(|c| c.finished_at)
+                .unwrap_or(0),
+        )
+    }
+}
+
+/// 名人堂（按评分从高到低排序）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Roster {
+    #[serde(default)]
+    pub entries: Vec<RosterEntry>,
+}
+
+impl Roster {
+    pub fn from_json(json: &str) -> WushenResult<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| WushenError::ParseError(format!("解析名人堂失败: {}", e)))
+    }
+
+    pub fn to_json(&self) -> WushenResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| WushenError::ParseError(format!("序列化名人堂失败: {}", e)))
+    }
+
+    /// 收录存档中的已完成角色，返回新增条目数
+    pub fn record_save(&mut self, save: &SaveGame) -> usize {
+        let mut added = 0;
+        let multipliers = save
+            .challenge
+            .as_ref()
+            .map(|challenge| challenge.score_multipliers)
+            .unwrap_or_default();
+        for character in &save.completed_characters {
+            let entry = RosterEntry {
+                save_id: save.id.clone(),
+                character: character.clone(),
+                score: character.score_with(&multipliers),
+            };
+            if self.entries.iter().any(|e| e.key() == entry.key()) {
+                continue;
+            }
+            self.entries.push(entry);
+            added += 1;
+        }
+        self.sort();
+        added
+    }
+
+    /// 移除来自指定存档的条目
+    pub fn remove_save(&mut self, save_id: &str) {
+        self.entries.retain(|e| e.save_id != save_id);
+    }
+
+    /// 评分最高的若干条目
+    pub fn top(&self, limit: usize) -> &[RosterEntry] {
+        &self.entries[..limit.min(self.entries.len())]
+    }
+
+    /// 选取切磋对手（可排除当前存档的角色）
+    pub fn opponents(&self, limit: usize, exclude_save_id: Option<&str>) -> Vec<EnemyTemplate> {
+        self.entries
+            .iter()
+            .filter(|e| Some(e.save_id.as_str()) != exclude_save_id)
+            .take(limit)
+            .map(|e| e.character.to_enemy_template())
+            .collect()
+    }
+
+    fn sort(&mut self) {
+        self.entries.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.key().cmp(&b.key()))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{
+        AdventureDiscovery, Codex, ManualsState, OwnedManualState, ThreeDimensionalState,
+    };
+
+    fn character(id: &str, physique: u32, level: u32) -> CharacterState {
+        CharacterState {
+            id: id.to_string(),
+            name: id.to_string(),
+            three_d: ThreeDimensionalState {
+                comprehension: 10,
+                bone_structure: 10,
+                physique,
+            },
+            traits: Vec::new(),
+            internals: ManualsState {
+                owned: vec![OwnedManualState {
+                    id: "i1".to_string(),
+                    level,
+                    exp: 0.0,
+                }],
+                equipped: Some("i1".to_string()),
+            },
+            attack_skills: ManualsState {
+                owned: Vec::new(),
+                equipped: None,
+            },
+            defense_skills: ManualsState {
+                owned: Vec::new(),
+                equipped: None,
+            },
+            action_points: 0,
+            cultivation_history: Vec::new(),
+            max_hp: None,
+            hp: None,
+            base_attack: None,
+            base_defense: None,
+            max_qi: None,
+            qi: None,
+            martial_arts_attainment: None,
+            timed_buffs: Vec::new(),
+            scheduled_events: Vec::new(),
+            story_flags: Vec::new(),
+            titles: Vec::new(),
+            worn_title: None,
+            inventory: Vec::new(),
+            chronicle: None,
+            appearance: None,
+        }
+    }
+
+    fn save(id: &str, completed: Vec<CharacterState>) -> SaveGame {
+        SaveGame {
+            id: id.to_string(),
+            name: id.to_string(),
+            created_at: 0,
+            current_character: character("current", 10, 0),
+            storyline_progress: None,
+            active_adventure_id: None,
+            start_trait_pool: Vec::new(),
+            completed_characters: completed,
+            rng_state: 0,
+            story_history: Default::default(),
+            post_battle_recovery: None,
+            active_quests: Vec::new(),
+            completed_quests: Vec::new(),
+            day: 0,
+            analytics: None,
+            completed_chapters: Vec::new(),
+            pending_victory_decision: None,
+            adventure_discovery: AdventureDiscovery::default(),
+            codex: Codex::default(),
+            battle_archive_policy: None,
+            battle_archive: Default::default(),
+            battles_archived: 0,
+            challenge: None,
+            defeat_policy: None,
+            action_point_penalty: 0,
+            content_fingerprint: None,
+            party: Default::default(),
+        }
+    }
+
+    #[test]
+    fn record_save_dedups_and_sorts_by_score() {
+        let mut roster = Roster::default();
+        let first = save("s1", vec![character("weak", 10, 1)]);
+        assert_eq!(roster.record_save(&first), 1);
+        assert_eq!(roster.record_save(&first), 0);
+
+        let second = save("s2", vec![character("strong", 30, 5)]);
+        assert_eq!(roster.record_save(&second), 1);
+        assert_eq!(roster.top(1)[0].character.id, "strong");
+
+        let opponents = roster.opponents(5, Some("s2"));
+        assert_eq!(opponents.len(), 1);
+        assert_eq!(opponents[0].name, "weak");
+        assert_eq!(opponents[0].internal.as_ref().unwrap().level, 1);
+
+        roster.remove_save("s1");
+        assert_eq!(roster.entries.len(), 1);
+    }
+}
@@ -0,0 +1,194 @@
+/// 存档完整性信封
+/// 导出存档时附带内容哈希、引擎版本与模组包哈希列表，导入时校验，
+/// 用于挑战模式防篡改以及排查损坏的存档文件
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 引擎版本
+pub const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 计算内容哈希（FNV-1a 64 位，十六进制）
+pub fn content_hash(bytes: &[u8]) -> String {
+    let mut hasher = Fnv1a::default();
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+/// FNV-1a 64 位哈希
+#[derive(Debug, Clone, Copy)]
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
+
+/// 模组包哈希
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackHash {
+    pub pack_id: String,
+    pub hash: String,
+}
+
+impl PackHash {
+    /// 按顺序对模组包的各内容文件计算哈希
+    pub fn from_files<'a>(pack_id: &str, files: impl IntoIterator<Item = &'a [u8]>) -> Self {
+        let mut hasher = Fnv1a::default();
+        for file in files {
+            // 写入长度作为分隔，避免文件边界移动后哈希相同
+            hasher.write(&(file.len() as u64).to_le_bytes());
+            hasher.write(file);
+        }
+        Self {
+            pack_id: pack_id.to_string(),
+            hash: hasher.finish(),
+        }
+    }
+}
+
+/// 完整性校验策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityPolicy {
+    /// 校验不通过时拒绝载入
+    Reject,
+    /// 校验不通过时载入并给出警告
+    #[default]
+    Warn,
+    /// 不校验
+    Accept,
+}
+
+/// 存档完整性信封
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveEnvelope {
+    pub engine_version: String,
+    /// 存档内容哈希
+    pub content_hash: String,
+    /// 导出时已安装模组包的哈希（按包 ID 排序）
+    #[serde(default)]
+    pub pack_hashes: Vec<PackHash>,
+    pub save: Value,
+}
+
+impl SaveEnvelope {
+    /// 封装存档
+    pub fn seal(save: Value, mut pack_hashes: Vec<PackHash>) -> Self {
+        pack_hashes.sort_by(|a, b| a.pack_id.cmp(&b.pack_id));
+        Self {
+            engine_version: ENGINE_VERSION.to_string(),
+            content_hash: hash_save(&save),
+            pack_hashes,
+            save,
+        }
+    }
+
+    /// 识别信封；普通存档返回 None
+    pub fn from_value(value: &Value) -> Option<Self> {
+        if value.get("content_hash").is_none() || value.get("save").is_none() {
+            return None;
+        }
+        serde_json::from_value(value.clone()).ok()
+    }
+
+    /// 校验存档，返回发现的问题（为空表示通过）
+    pub fn verify(&self, current_packs: &[PackHash]) -> Vec<String> {
+        let mut issues = Vec::new();
+        if hash_save(&self.save) != self.content_hash {
+            issues.push("存档内容哈希不一致，文件可能已被修改或损坏".to_string());
+        }
+        if self.engine_version != ENGINE_VERSION {
+            issues.push(format!(
+                "存档由引擎版本 {} 导出，当前版本为 {}",
+                self.engine_version, ENGINE_VERSION
+            ));
+        }
+        for expected in &self.pack_hashes {
+            match current_packs
+                .iter()
+                .find(|pack| pack.pack_id == expected.pack_id)
+            {
+                None => issues.push(format!("缺少模组包 {}", expected.pack_id)),
+                Some(pack) if pack.hash != expected.hash => {
+                    issues.push(format!("模组包 {} 内容与导出时不一致", expected.pack_id))
+                }
+                Some(_) => {}
+            }
+        }
+        issues
+    }
+
+    /// 按策略校验并取出存档，返回存档与警告
+    pub fn open(
+        self,
+        policy: IntegrityPolicy,
+        current_packs: &[PackHash],
+    ) -> WushenResult<(Value, Vec<String>)> {
+        let issues = match policy {
+            IntegrityPolicy::Accept => Vec::new(),
+            _ => self.verify(current_packs),
+        };
+        if policy == IntegrityPolicy::Reject && !issues.is_empty() {
+            return Err(WushenError::Invalid(format!(
+                "存档校验失败: {}",
+                issues.join("；")
+            )));
+        }
+        Ok((self.save, issues))
+    }
+}
+
+/// 存档内容哈希（序列化结果键有序，同一内容哈希稳定）
+fn hash_save(save: &Value) -> String {
+    content_hash(&serde_json::to_vec(save).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn verify_detects_tampering_and_pack_changes() {
+        let packs = vec![PackHash::from_files("base", [b"[]".as_slice()])];
+        let envelope = SaveEnvelope::seal(json!({"id": "s1", "day": 3}), packs.clone());
+        let value = serde_json::to_value(&envelope).unwrap();
+        let restored = SaveEnvelope::from_value(&value).unwrap();
+        assert!(restored.verify(&packs).is_empty());
+        assert!(SaveEnvelope::from_value(&json!({"id": "s1"})).is_none());
+
+        let mut tampered = restored.clone();
+        tampered.save["day"] = json!(99);
+        assert_eq!(tampered.verify(&packs).len(), 1);
+        assert!(tampered
+            .clone()
+            .open(IntegrityPolicy::Reject, &packs)
+            .is_err());
+        let (_, warnings) = tampered
+            .clone()
+            .open(IntegrityPolicy::Warn, &packs)
+            .unwrap();
+        assert_eq!(warnings.len(), 1);
+        let (_, warnings) = tampered.open(IntegrityPolicy::Accept, &packs).unwrap();
+        assert!(warnings.is_empty());
+
+        let changed = vec![PackHash::from_files("base", [b"[{}]".as_slice()])];
+        assert_eq!(restored.verify(&changed).len(), 1);
+        assert_eq!(restored.verify(&[]).len(), 1);
+    }
+}
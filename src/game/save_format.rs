@@ -0,0 +1,303 @@
+/// 存档格式：JSON 与紧凑二进制格式
+/// 紧凑格式将存档 JSON 树编码为二进制，并把字段名与字符串收入字符串表去重，
+/// 长局存档（完整战斗历史、名人堂）体积与解析耗时都明显低于 JSON
+///
+/// 编码布局：魔数 `WSC2`，其后为 postcard 编码的字符串表与先序展开的值节点
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+use super::SaveGame;
+
+/// 紧凑格式魔数
+pub const COMPACT_MAGIC: &[u8; 4] = b"WSC2";
+
+/// 值树最大嵌套深度（防止损坏数据导致栈溢出）
+const MAX_DEPTH: usize = 128;
+
+/// 存档格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SaveFormat {
+    #[default]
+    Json,
+    Compact,
+}
+
+impl SaveFormat {
+    /// 根据内容识别格式（以魔数开头为紧凑格式，否则视为 JSON）
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(COMPACT_MAGIC) {
+            SaveFormat::Compact
+        } else {
+            SaveFormat::Json
+        }
+    }
+
+    /// 存档文件扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SaveFormat::Json => "json",
+            SaveFormat::Compact => "wsav",
+        }
+    }
+}
+
+/// 按指定格式编码存档
+pub fn encode_save(save: &SaveGame, format: SaveFormat) -> WushenResult<Vec<u8>> {
+    let value = serde_json::to_value(save)
+        .map_err(|e| WushenError::ParseError(format!("序列化存档失败: {}", e)))?;
+    encode_save_value(&value, format)
+}
+
+/// 解码存档（自动识别格式）
+pub fn decode_save(bytes: &[u8]) -> WushenResult<SaveGame> {
+    let value = decode_save_value(bytes)?;
+    serde_json::from_value(value)
+        .map_err(|e| WushenError::ParseError(format!("解析存档失败: {}", e)))
+}
+
+/// 按指定格式编码存档 JSON 树（保留未知字段）
+pub fn encode_save_value(value: &Value, format: SaveFormat) -> WushenResult<Vec<u8>> {
+    match format {
+        SaveFormat::Json => serde_json::to_vec_pretty(value)
+            .map_err(|e| WushenError::ParseError(format!("序列化存档失败: {}", e))),
+        SaveFormat::Compact => encode_compact(value),
+    }
+}
+
+/// 解码存档 JSON 树（自动识别格式）
+pub fn decode_save_value(bytes: &[u8]) -> WushenResult<Value> {
+    match SaveFormat::detect(bytes) {
+        SaveFormat::Json => serde_json::from_slice(bytes)
+            .map_err(|e| WushenError::ParseError(format!("解析存档失败: {}", e))),
+        SaveFormat::Compact => decode_compact(bytes),
+    }
+}
+
+/// 转换存档格式（自动识别源格式）
+pub fn convert_save(bytes: &[u8], target: SaveFormat) -> WushenResult<Vec<u8>> {
+    let value = decode_save_value(bytes)?;
+    encode_save_value(&value, target)
+}
+
+/// 紧凑存档内容：字符串表与按先序展开的值节点
+///
+/// 值树展开为平铺的节点列表，编码结构不含递归，解码时再按深度上限重建
+#[derive(Serialize, Deserialize)]
+struct CompactSave {
+    strings: Vec<String>,
+    nodes: Vec<Node>,
+}
+
+/// 值节点：数组与对象节点记录成员数，其后紧跟各成员；对象成员为键节点加值
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum Node {
+    Null,
+    Bool(bool),
+    UInt(u64),
+    Int(i64),
+    Float(f64),
+    String(u32),
+    Array(u32),
+    Object(u32),
+    Key(u32),
+}
+
+fn encode_compact(value: &Value) -> WushenResult<Vec<u8>> {
+    let mut builder = CompactBuilder::default();
+    builder.push(value);
+    let save = CompactSave {
+        strings: builder.strings.into_iter().map(str::to_string).collect(),
+        nodes: builder.nodes,
+    };
+    let body = postcard::to_allocvec(&save)
+        .map_err(|e| WushenError::ParseError(format!("序列化存档失败: {}", e)))?;
+    let mut out = COMPACT_MAGIC.to_vec();
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+/// 展开值树，字符串按首次出现顺序编号
+#[derive(Default)]
+struct CompactBuilder<'a> {
+    strings: Vec<&'a str>,
+    index: HashMap<&'a str, u32>,
+    nodes: Vec<Node>,
+}
+
+impl<'a> CompactBuilder<'a> {
+    fn intern(&mut self, s: &'a str) -> u32 {
+        let next = self.strings.len() as u32;
+        *self.index.entry(s).or_insert_with(|| {
+            self.strings.push(s);
+            next
+        })
+    }
+
+    fn push(&mut self, value: &'a Value) {
+        let node = match value {
+            Value::Null => Node::Null,
+            Value::Bool(b) => Node::Bool(*b),
+            Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+                (Some(u), _) => Node::UInt(u),
+                (None, Some(i)) => Node::Int(i),
+                _ => Node::Float(n.as_f64().unwrap_or(0.0)),
+            },
+            Value::String(s) => Node::String(self.intern(s)),
+            Value::Array(items) => {
+                self.nodes.push(Node::Array(items.len() as u32));
+                items.iter().for_each(|item| self.push(item));
+                return;
+            }
+            Value::Object(map) => {
+                self.nodes.push(Node::Object(map.len() as u32));
+                for (key, item) in map {
+                    let key = self.intern(key);
+                    self.nodes.push(Node::Key(key));
+                    self.push(item);
+                }
+                return;
+            }
+        };
+        self.nodes.push(node);
+    }
+}
+
+fn decode_compact(bytes: &[u8]) -> WushenResult<Value> {
+    let (save, rest): (CompactSave, _) =
+        postcard::take_from_bytes(&bytes[COMPACT_MAGIC.len()..])
+            .map_err(|e| WushenError::ParseError(format!("紧凑存档数据无效: {}", e)))?;
+    if !rest.is_empty() {
+        return Err(WushenError::ParseError(
+            "紧凑存档末尾存在多余数据".to_string(),
+        ));
+    }
+    let mut reader = NodeReader {
+        strings: &save.strings,
+        nodes: save.nodes.iter(),
+    };
+    let value = reader.value(0)?;
+    if reader.nodes.len() != 0 {
+        return Err(WushenError::ParseError(
+            "紧凑存档末尾存在多余数据".to_string(),
+        ));
+    }
+    Ok(value)
+}
+
+/// 按先序节点重建值树
+struct NodeReader<'a> {
+    strings: &'a [String],
+    nodes: std::slice::Iter<'a, Node>,
+}
+
+impl NodeReader<'_> {
+    fn node(&mut self) -> WushenResult<Node> {
+        self.nodes
+            .next()
+            .copied()
+            .ok_or_else(|| WushenError::ParseError("紧凑存档数据不完整".to_string()))
+    }
+
+    fn string(&self, index: u32) -> WushenResult<String> {
+        self.strings
+            .get(index as usize)
+            .cloned()
+            .ok_or_else(|| WushenError::ParseError(format!("紧凑存档字符串索引 {} 越界", index)))
+    }
+
+    fn value(&mut self, depth: usize) -> WushenResult<Value> {
+        if depth > MAX_DEPTH {
+            return Err(WushenError::ParseError("紧凑存档嵌套过深".to_string()));
+        }
+        let value = match self.node()? {
+            Node::Null => Value::Null,
+            Node::Bool(b) => Value::Bool(b),
+            Node::UInt(u) => Value::from(u),
+            Node::Int(i) => Value::from(i),
+            Node::Float(f) => Number::from_f64(f)
+                .map(Value::Number)
+                .unwrap_or(Value::Null),
+            Node::String(index) => Value::String(self.string(index)?),
+            Node::Array(len) => {
+                // 成员数不超过剩余节点数，避免按损坏长度预分配
+                let mut items = Vec::with_capacity((len as usize).min(self.nodes.len()));
+                for _ in 0..len {
+                    items.push(self.value(depth + 1)?);
+                }
+                Value::Array(items)
+            }
+            Node::Object(len) => {
+                let mut map = Map::new();
+                for _ in 0..len {
+                    let Node::Key(key) = self.node()? else {
+                        return Err(WushenError::ParseError(
+                            "紧凑存档对象成员缺少键".to_string(),
+                        ));
+                    };
+                    let key = self.string(key)?;
+                    map.insert(key, self.value(depth + 1)?);
+                }
+                Value::Object(map)
+            }
+            Node::Key(_) => {
+                return Err(WushenError::ParseError(
+                    "紧凑存档键节点位置无效".to_string(),
+                ))
+            }
+        };
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn compact_round_trips_and_detects_format() {
+        let value = json!({
+            "id": "save-1",
+            "day": 42,
+            "rng_state": u64::MAX,
+            "delta": -7,
+            "min": i64::MIN,
+            "ratio": 0.25,
+            "flags": [true, false, null],
+            "history": [{"id": "save-1"}, {"id": "save-2"}],
+        });
+
+        let compact = encode_save_value(&value, SaveFormat::Compact).unwrap();
+        assert_eq!(SaveFormat::detect(&compact), SaveFormat::Compact);
+        assert_eq!(decode_save_value(&compact).unwrap(), value);
+
+        let json = convert_save(&compact, SaveFormat::Json).unwrap();
+        assert_eq!(SaveFormat::detect(&json), SaveFormat::Json);
+        assert!(compact.len() < json.len());
+        assert_eq!(convert_save(&json, SaveFormat::Compact).unwrap(), compact);
+
+        assert!(decode_save_value(&compact[..compact.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn compact_rejects_malformed_node_trees() {
+        let encode = |nodes: Vec<Node>| {
+            let save = CompactSave {
+                strings: vec!["key".to_string()],
+                nodes,
+            };
+            let mut bytes = COMPACT_MAGIC.to_vec();
+            bytes.extend(postcard::to_allocvec(&save).unwrap());
+            decode_save_value(&bytes)
+        };
+        assert!(encode(vec![Node::Object(1), Node::Key(0), Node::Null]).is_ok());
+        assert!(encode(vec![Node::Object(1), Node::Null, Node::Null]).is_err());
+        assert!(encode(vec![Node::String(7)]).is_err());
+        assert!(encode(vec![Node::Array(u32::MAX)]).is_err());
+        assert!(encode(vec![Node::Null, Node::Null]).is_err());
+        assert!(encode(vec![Node::Array(1); MAX_DEPTH + 2]).is_err());
+    }
+}
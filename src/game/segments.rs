@@ -0,0 +1,212 @@
+/// 存档分段：随游玩不断增长的数据（如剧情历史）不随存档主体序列化，
+/// 存档主体只记录引用（条目数），分段数据由宿主单独持久化并按需读取
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ops::Deref;
+
+use super::battle_archive::ArchivedBattle;
+use super::StoryHistoryRecord;
+
+/// 分段类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentKind {
+    StoryHistory,
+    BattleArchive,
+}
+
+impl SegmentKind {
+    pub const ALL: [SegmentKind; 2] = [SegmentKind::StoryHistory, SegmentKind::BattleArchive];
+
+    pub fn parse(value: &str) -> WushenResult<Self> {
+        match value {
+            "story_history" => Ok(SegmentKind::StoryHistory),
+            "battle_archive" => Ok(SegmentKind::BattleArchive),
+            _ => Err(WushenError::ParseError(format!(
+                "未知的存档分段: {}",
+                value
+            ))),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SegmentKind::StoryHistory => "story_history",
+            SegmentKind::BattleArchive => "battle_archive",
+        }
+    }
+}
+
+/// 存档主体中对分段的引用
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentRef {
+    /// 分段条目数
+    pub len: usize,
+}
+
+/// 以追加为主的存档分段（已有条目可被补充修改）
+///
+/// 序列化时只写出 [`SegmentRef`]；反序列化同时兼容旧存档内联的数组
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment<T> {
+    records: Vec<T>,
+    /// 存档引用的条目数（未加载时 records 为空）
+    referenced_len: usize,
+    loaded: bool,
+    /// 自上次导出以来是否有变化
+    dirty: bool,
+}
+
+impl<T> Default for Segment<T> {
+    fn default() -> Self {
+        Self {
+            records: Vec::new(),
+            referenced_len: 0,
+            loaded: true,
+            dirty: false,
+        }
+    }
+}
+
+impl<T> Deref for Segment<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.records
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Segment<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.records.iter()
+    }
+}
+
+impl<T> Segment<T> {
+    pub fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    pub fn push(&mut self, record: T) {
+        self.records.push(record);
+        self.referenced_len = self.records.len();
+        self.dirty = true;
+    }
+
+    /// 可变遍历（视为分段发生变化）
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.dirty = true;
+        self.records.iter_mut()
+    }
+
+    /// 丢弃最早的若干条目
+    pub fn drain_front(&mut self, count: usize) {
+        let count = count.min(self.records.len());
+        self.records.drain(..count);
+        self.referenced_len = self.records.len();
+        self.dirty = true;
+    }
+
+    /// 载入分段数据（条目数须与存档引用一致）
+    pub fn load(&mut self, records: Vec<T>) -> WushenResult<()> {
+        if records.len() != self.referenced_len {
+            return Err(WushenError::Invalid(format!(
+                "存档分段条目数不符：存档记录 {} 条，分段 {} 条",
+                self.referenced_len,
+                records.len()
+            )));
+        }
+        self.records = records;
+        self.loaded = true;
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// 取出分段变化标记
+    pub fn take_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.dirty)
+    }
+}
+
+impl<T> Serialize for Segment<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SegmentRef {
+            len: self.referenced_len,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Segment<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr<T> {
+            Inline(Vec<T>),
+            Ref(SegmentRef),
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            // 旧存档内联的数据视为已加载，下次导出时写入分段
+            Repr::Inline(records) => Self {
+                referenced_len: records.len(),
+                dirty: !records.is_empty(),
+                records,
+                loaded: true,
+            },
+            Repr::Ref(reference) => Self {
+                records: Vec::new(),
+                referenced_len: reference.len,
+                loaded: reference.len == 0,
+                dirty: false,
+            },
+        })
+    }
+}
+
+/// 存档分段数据（宿主单独持久化的内容，缺省的分段表示无需写入）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SaveSegments {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub story_history: Option<Vec<StoryHistoryRecord>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub battle_archive: Option<Vec<ArchivedBattle>>,
+}
+
+impl SaveSegments {
+    pub fn is_empty(&self) -> bool {
+        self.story_history.is_none() && self.battle_archive.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_serializes_as_reference() {
+        let mut segment = Segment::default();
+        segment.push(1u32);
+        segment.push(2);
+        assert_eq!(serde_json::to_string(&segment).unwrap(), r#"{"len":2}"#);
+
+        let mut restored: Segment<u32> = serde_json::from_str(r#"{"len":2}"#).unwrap();
+        assert!(!restored.is_loaded());
+        assert!(restored.load(vec![1]).is_err());
+        restored.load(vec![1, 2]).unwrap();
+        assert_eq!(&restored[..], &[1, 2]);
+        assert!(!restored.take_dirty());
+    }
+
+    #[test]
+    fn legacy_inline_segment_is_loaded_and_dirty() {
+        let mut segment: Segment<u32> = serde_json::from_str("[1,2,3]").unwrap();
+        assert!(segment.is_loaded());
+        assert_eq!(segment.len(), 3);
+        assert!(segment.take_dirty());
+        assert!(!segment.take_dirty());
+    }
+}
@@ -0,0 +1,237 @@
+/// 开局行装：剧情线为新角色配置的初始功法、特性、内息与行动点
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Serialize};
+
+use super::{CharacterState, ManualsState, OwnedManualState};
+use crate::character::trait_manager::TraitManager;
+use crate::cultivation::manual_manager::ManualManager;
+use crate::game::challenge::ChallengeRuleset;
+
+/// 开局功法（等级只能为 0 或 1）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StartingManual {
+    pub id: String,
+    #[serde(default)]
+    pub level: u32,
+    /// 开局即装备（同类功法至多一门）
+    #[serde(default)]
+    pub equipped: bool,
+}
+
+/// 开局行装
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StartingKit {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub internals: Vec<StartingManual>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attack_skills: Vec<StartingManual>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub defense_skills: Vec<StartingManual>,
+    /// 追加的特性（在开局抽取的特性之后）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub traits: Vec<String>,
+    /// 初始内息（同时提高内息上限）
+    #[serde(default)]
+    pub qi: f64,
+    /// 离开起始事件后进入的行动阶段额外获得的行动点
+    #[serde(default)]
+    pub action_points: u32,
+}
+
+impl StartingKit {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// 校验行装数据（不检查引用的特性与功法是否存在）
+    pub fn validate(&self) -> WushenResult<()> {
+        if !self.qi.is_finite() || self.qi < 0.0 {
+            return Err(WushenError::Invalid("开局内息必须为非负数".to_string()));
+        }
+        if self.traits.iter().any(|id| id.trim().is_empty()) {
+            return Err(WushenError::Invalid("开局特性缺少 ID".to_string()));
+        }
+        for (label, manuals) in self.manual_groups() {
+            let mut equipped = 0;
+            for (index, manual) in manuals.iter().enumerate() {
+                if manual.id.trim().is_empty() {
+                    return Err(WushenError::Invalid(format!("开局{}缺少 ID", label)));
+                }
+                if manual.level > 1 {
+                    return Err(WushenError::Invalid(format!(
+                        "开局{} {} 的等级只能为 0 或 1",
+                        label, manual.id
+                    )));
+                }
+                if manuals[..index].iter().any(|m| m.id == manual.id) {
+                    return Err(WushenError::Invalid(format!(
+                        "开局{} {} 重复",
+                        label, manual.id
+                    )));
+                }
+                equipped += usize::from(manual.equipped);
+            }
+            if equipped > 1 {
+                return Err(WushenError::Invalid(format!("开局至多装备一门{}", label)));
+            }
+        }
+        Ok(())
+    }
+
+    /// 按已加载的内容包校验引用的特性与功法
+    pub fn validate_references(
+        &self,
+        trait_manager: &TraitManager,
+        manual_manager: &ManualManager,
+    ) -> WushenResult<()> {
+        for id in &self.traits {
+            if trait_manager.get_trait(id).is_none() {
+                return Err(WushenError::Invalid(format!("开局特性 {} 不存在", id)));
+            }
+        }
+        for manual in &self.internals {
+            if manual_manager.get_internal(&manual.id).is_none() {
+                return Err(WushenError::Invalid(format!(
+                    "开局内功 {} 不存在",
+                    manual.id
+                )));
+            }
+        }
+        for manual in &self.attack_skills {
+            if manual_manager.get_attack_skill(&manual.id).is_none() {
+                return Err(WushenError::Invalid(format!(
+                    "开局攻击武技 {} 不存在",
+                    manual.id
+                )));
+            }
+        }
+        for manual in &self.defense_skills {
+            if manual_manager.get_defense_skill(&manual.id).is_none() {
+                return Err(WushenError::Invalid(format!(
+                    "开局防御武技 {} 不存在",
+                    manual.id
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// 为新角色配备行装（挑战规则禁用的特性与功法跳过，已拥有的不重复添加）
+    pub fn apply(&self, character: &mut CharacterState, challenge: Option<&ChallengeRuleset>) {
+        for id in &self.traits {
+            if challenge.is_some_and(|c| c.bans_trait(id)) || character.traits.contains(id) {
+                continue;
+            }
+            character.traits.push(id.clone());
+        }
+        let groups = [
+            (&self.internals, &mut character.internals),
+            (&self.attack_skills, &mut character.attack_skills),
+            (&self.defense_skills, &mut character.defense_skills),
+        ];
+        for (manuals, state) in groups {
+            equip_manuals(manuals, state, challenge);
+        }
+        character.max_qi = Some(character.max_qi.unwrap_or(0.0) + self.qi);
+        character.qi = Some(character.qi.unwrap_or(0.0) + self.qi);
+    }
+
+    fn manual_groups(&self) -> [(&'static str, &[StartingManual]); 3] {
+        [
+            ("内功", &self.internals),
+            ("攻击武技", &self.attack_skills),
+            ("防御武技", &self.defense_skills),
+        ]
+    }
+}
+
+fn equip_manuals(
+    manuals: &[StartingManual],
+    state: &mut ManualsState,
+    challenge: Option<&ChallengeRuleset>,
+) {
+    for manual in manuals {
+        if challenge.is_some_and(|c| c.bans_manual(&manual.id))
+            || state.owned.iter().any(|owned| owned.id == manual.id)
+        {
+            continue;
+        }
+        state.owned.push(OwnedManualState {
+            id: manual.id.clone(),
+            level: manual.level,
+            exp: 0.0,
+        });
+        if manual.equipped {
+            state.equipped = Some(manual.id.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::new_game_request;
+    use crate::{WushenCore, WushenResult};
+    use serde_json::json;
+
+    fn storylines(kit: serde_json::Value) -> String {
+        json!([{"id": "s", "name": "测试", "start_event_id": "start", "starting_kit": kit,
+            "events": [
+                {"id": "start", "name": "开端", "node_type": "start",
+                 "content": {"type": "story", "text": "", "next_event_id": "camp"}},
+                {"id": "camp", "name": "营地", "node_type": "middle", "action_points": 3,
+                 "content": {"type": "story", "text": "", "next_event_id": "end"}},
+                {"id": "end", "name": "终局", "node_type": "end", "content": {"type": "end", "text": ""}}
+            ]}])
+        .to_string()
+    }
+
+    fn start(core: &mut WushenCore) -> WushenResult<()> {
+        core.game_start_new(new_game_request("少侠")).map(|_| ())
+    }
+
+    #[test]
+    fn starting_kit_equips_new_characters() {
+        let realms: Vec<_> = (1..=5)
+            .map(|level| {
+                json!({"level": level, "exp_required": 100 * level, "qi_gain": 10,
+                       "martial_arts_attainment": 1, "qi_quality": 1.0, "attack_speed": 1.0,
+                       "qi_recovery_rate": 0.05, "entries": []})
+            })
+            .collect();
+        let mut core = WushenCore::new();
+        core.load_traits(r#"[{"id":"勇猛","name":"勇猛","description":"","entries":[]}]"#)
+            .unwrap();
+        core.load_internals(
+            &json!({"internals": [{"id": "吐纳", "name": "吐纳", "description": "", "rarity": 1,
+                                   "type": "neutral", "cultivation_formula": "x * 10",
+                                   "realms": realms}]})
+            .to_string(),
+        )
+        .unwrap();
+
+        assert!(core
+            .load_storylines(&storylines(
+                json!({"internals": [{"id": "吐纳", "level": 2}]})
+            ))
+            .is_err());
+        core.load_storylines(&storylines(json!({"internals": [{"id": "缺失"}]})))
+            .unwrap();
+        assert!(start(&mut core).unwrap_err().message().contains("缺失"));
+
+        core.load_storylines(&storylines(json!({
+            "internals": [{"id": "吐纳", "level": 1, "equipped": true}],
+            "traits": ["勇猛"], "qi": 20.0, "action_points": 2
+        })))
+        .unwrap();
+        start(&mut core).unwrap();
+        let character = &core.current_save().unwrap().current_character;
+        assert!(character.traits.iter().any(|id| id == "勇猛"));
+        assert_eq!(character.internals.owned[0].level, 1);
+        assert_eq!(character.internals.equipped.as_deref(), Some("吐纳"));
+        assert_eq!((character.max_qi, character.qi), (Some(20.0), Some(20.0)));
+
+        core.game_story_continue().unwrap();
+        let character = &core.current_save().unwrap().current_character;
+        assert_eq!(character.action_points, 5);
+    }
+}
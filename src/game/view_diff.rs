@@ -0,0 +1,138 @@
+/// 游戏视图差量：按分区记录变更版本，前端只需拉取发生变化的分区
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use super::{
+    AdventureDecisionView, CalendarView, ChapterView, CharacterState, GamePhase, GameView,
+    QuestView, StoryEventSummary, StoryEventView, StorylineSummary,
+};
+
+/// 视图分区
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ViewSection {
+    Character = 0,
+    Phase = 1,
+    Story = 2,
+    Adventure = 3,
+}
+
+/// 剧情分区内容
+#[derive(Debug, Clone, Serialize)]
+pub struct StorySectionView {
+    pub storyline: Option<StorylineSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chapter: Option<ChapterView>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_event: Option<StoryEventSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub story_event: Option<StoryEventView>,
+}
+
+/// 视图差量；未变化的分区为空（奇遇分区变化为“无奇遇”时 adventure_cleared 为 true）
+#[derive(Debug, Clone, Serialize)]
+pub struct GameViewDiff {
+    /// 当前视图版本
+    pub revision: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub character: Option<CharacterState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<GamePhase>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub story: Option<StorySectionView>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adventure: Option<AdventureDecisionView>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub adventure_cleared: bool,
+    /// 悬赏榜与日期体量很小，每次都返回
+    pub quests: Vec<QuestView>,
+    pub calendar: CalendarView,
+}
+
+/// 视图版本追踪：记录各分区的指纹及最后变化时的版本
+#[derive(Debug, Clone, Default)]
+pub struct ViewTracker {
+    revision: u64,
+    fingerprints: Option<[u64; 4]>,
+    section_revisions: [u64; 4],
+}
+
+impl ViewTracker {
+    /// 当前视图版本
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// 记录最新视图并生成相对 since 版本的差量（since 为空时返回全部分区）
+    pub fn diff(&mut self, view: GameView, since: Option<u64>) -> GameViewDiff {
+        let story = StorySectionView {
+            storyline: view.storyline,
+            chapter: view.chapter,
+            current_event: view.current_event,
+            story_event: view.story_event,
+        };
+        let fingerprints = [
+            fingerprint(&view.save.current_character),
+            fingerprint(&view.phase),
+            fingerprint(&story),
+            fingerprint(&view.adventure),
+        ];
+        if self.fingerprints != Some(fingerprints) {
+            self.revision += 1;
+            for (index, value) in fingerprints.iter().enumerate() {
+                let changed = self.fingerprints.is_none_or(|old| old[index] != *value);
+                if changed {
+                    self.section_revisions[index] = self.revision;
+                }
+            }
+            self.fingerprints = Some(fingerprints);
+        }
+
+        let changed = |section: ViewSection| {
+            since.is_none_or(|since| self.section_revisions[section as usize] > since)
+        };
+        let adventure_changed = changed(ViewSection::Adventure);
+        GameViewDiff {
+            revision: self.revision,
+            character: changed(ViewSection::Character).then_some(view.save.current_character),
+            phase: changed(ViewSection::Phase).then_some(view.phase),
+            story: changed(ViewSection::Story).then_some(story),
+            adventure_cleared: adventure_changed && view.adventure.is_none(),
+            adventure: view.adventure.filter(|_| adventure_changed),
+            quests: view.quests,
+            calendar: view.calendar,
+        }
+    }
+}
+
+fn fingerprint<T: Serialize>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_vec(value)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{core_with_storyline, start_game};
+
+    #[test]
+    fn diff_only_returns_changed_sections() {
+        let mut core = core_with_storyline();
+        start_game(&mut core, "a");
+
+        let full = core.game_view_diff(None).unwrap();
+        assert_eq!(full.revision, 1);
+        assert!(full.character.is_some() && full.phase.is_some() && full.story.is_some());
+
+        let unchanged = core.game_view_diff(Some(full.revision)).unwrap();
+        assert_eq!(unchanged.revision, 1);
+        assert!(unchanged.character.is_none() && unchanged.story.is_none());
+
+        core.game_equip_title(None).unwrap();
+        let diff = core.game_view_diff(Some(full.revision)).unwrap();
+        assert!(diff.character.is_none());
+        assert!(diff.phase.is_none());
+    }
+}
@@ -0,0 +1,47 @@
+use crate::item::recipe::Recipe;
+use crate::item::types::Item;
+/// 物品管理器
+use std::collections::BTreeMap;
+
+/// 物品管理器（含炼制配方）
+#[derive(Clone, Default)]
+pub struct ItemManager {
+    items: BTreeMap<String, Item>,
+    recipes: BTreeMap<String, Recipe>,
+}
+
+impl ItemManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 加载物品（同 ID 覆盖已加载的物品）
+    pub fn load_items(&mut self, items: Vec<Item>) {
+        for item in items {
+            self.items.insert(item.id.clone(), item);
+        }
+    }
+
+    pub fn get_item(&self, id: &str) -> Option<&Item> {
+        self.items.get(id)
+    }
+
+    pub fn all_items(&self) -> Vec<&Item> {
+        self.items.values().collect()
+    }
+
+    /// 加载炼制配方（同 ID 覆盖已加载的配方）
+    pub fn load_recipes(&mut self, recipes: Vec<Recipe>) {
+        for recipe in recipes {
+            self.recipes.insert(recipe.id.clone(), recipe);
+        }
+    }
+
+    pub fn get_recipe(&self, id: &str) -> Option<&Recipe> {
+        self.recipes.get(id)
+    }
+
+    pub fn all_recipes(&self) -> Vec<&Recipe> {
+        self.recipes.values().collect()
+    }
+}
@@ -0,0 +1,9 @@
+pub(crate) mod manager;
+pub(crate) mod parser;
+pub(crate) mod recipe;
+pub(crate) mod types;
+
+pub use manager::ItemManager;
+pub use parser::{parse_items, parse_recipes};
+pub use recipe::{Recipe, RecipeStack};
+pub use types::{Item, ItemEffect};
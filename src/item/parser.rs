@@ -0,0 +1,51 @@
+use crate::error::{WushenError, WushenResult};
+use crate::item::recipe::Recipe;
+use crate::item::types::Item;
+use crate::pack::schema::CollectionKind;
+use crate::pack::strict::{parse_collection, ParseOptions};
+use crate::pack::warning::LoadWarning;
+// 物品 JSON 解析器
+
+/// 解析物品数据
+/// 支持两种格式：
+/// 1. 对象格式：{"items":[...]}
+/// 2. 数组格式：[...]
+pub fn parse_items(json: &str) -> WushenResult<Vec<Item>> {
+    parse_items_with_warnings(json).map(|(items, _)| items)
+}
+
+/// 解析物品数据，并返回数据版本升级产生的警告
+pub fn parse_items_with_warnings(json: &str) -> WushenResult<(Vec<Item>, Vec<LoadWarning>)> {
+    parse_items_with_options(json, ParseOptions::default())
+}
+
+/// 按解析选项解析物品数据
+pub fn parse_items_with_options(
+    json: &str,
+    options: ParseOptions,
+) -> WushenResult<(Vec<Item>, Vec<LoadWarning>)> {
+    parse_collection(json, CollectionKind::Items, options)
+        .map_err(|e| WushenError::ParseError(format!("解析物品数据失败: {}", e)))
+}
+
+/// 解析炼制配方数据
+/// 支持两种格式：
+/// 1. 对象格式：{"recipes":[...]}
+/// 2. 数组格式：[...]
+pub fn parse_recipes(json: &str) -> WushenResult<Vec<Recipe>> {
+    parse_recipes_with_warnings(json).map(|(recipes, _)| recipes)
+}
+
+/// 解析炼制配方数据，并返回数据版本升级产生的警告
+pub fn parse_recipes_with_warnings(json: &str) -> WushenResult<(Vec<Recipe>, Vec<LoadWarning>)> {
+    parse_recipes_with_options(json, ParseOptions::default())
+}
+
+/// 按解析选项解析炼制配方数据
+pub fn parse_recipes_with_options(
+    json: &str,
+    options: ParseOptions,
+) -> WushenResult<(Vec<Recipe>, Vec<LoadWarning>)> {
+    parse_collection(json, CollectionKind::Recipes, options)
+        .map_err(|e| WushenError::ParseError(format!("解析炼制配方数据失败: {}", e)))
+}
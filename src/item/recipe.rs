@@ -0,0 +1,125 @@
+use crate::effect::condition::Condition;
+use crate::error::{WushenError, WushenResult};
+use crate::item::manager::ItemManager;
+/// 炼制配方定义
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// 炼制配方：以背包中的材料炼成丹药等物品
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recipe {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// 消耗的材料
+    pub ingredients: Vec<RecipeStack>,
+    /// 炼成的物品
+    pub output: RecipeStack,
+    /// 炼制条件（可选，如武学素养要求）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<Condition>,
+}
+
+/// 配方中的一种物品及其数量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipeStack {
+    pub id: String,
+    #[serde(default = "default_count")]
+    pub count: u32,
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+impl Recipe {
+    /// 校验配方配置
+    pub fn validate(&self) -> WushenResult<()> {
+        if self.name.trim().is_empty() {
+            return Err(WushenError::Invalid(format!(
+                "配方 {} 的名称不能为空",
+                self.id
+            )));
+        }
+        if self.ingredients.is_empty() {
+            return Err(WushenError::Invalid(format!(
+                "配方 {} 的材料不能为空",
+                self.id
+            )));
+        }
+        let mut seen = BTreeSet::new();
+        for stack in &self.ingredients {
+            if !seen.insert(stack.id.as_str()) {
+                return Err(WushenError::Invalid(format!(
+                    "配方 {} 的材料 {} 重复",
+                    self.id, stack.id
+                )));
+            }
+        }
+        if self
+            .ingredients
+            .iter()
+            .chain(std::iter::once(&self.output))
+            .any(|stack| stack.count == 0)
+        {
+            return Err(WushenError::Invalid(format!(
+                "配方 {} 的物品数量必须大于 0",
+                self.id
+            )));
+        }
+        Ok(())
+    }
+
+    /// 配方引用但未加载的物品
+    pub fn missing_items<'a>(&'a self, items: &ItemManager) -> Vec<&'a str> {
+        self.ingredients
+            .iter()
+            .chain(std::iter::once(&self.output))
+            .map(|stack| stack.id.as_str())
+            .filter(|id| items.get_item(id).is_none())
+            .collect()
+    }
+
+    /// 校验配方配置与物品引用
+    pub fn validate_with_items(&self, items: &ItemManager) -> WushenResult<()> {
+        self.validate()?;
+        match self.missing_items(items).first() {
+            Some(id) => Err(WushenError::Invalid(format!(
+                "配方 {} 引用的物品 {} 不存在",
+                self.id, id
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::parse_items;
+    use crate::item::parser::parse_recipes;
+
+    #[test]
+    fn validation_checks_counts_and_item_references() {
+        let mut items = ItemManager::new();
+        items.load_items(
+            parse_items(
+                r#"[{"id":"灵芝","name":"灵芝"},
+                    {"id":"回春丹","name":"回春丹","effects":[{"type":"heal","hp":30}]}]"#,
+            )
+            .unwrap(),
+        );
+        let recipes = parse_recipes(
+            r#"[{"id":"炼回春丹","name":"炼回春丹",
+                 "ingredients":[{"id":"灵芝","count":2}],"output":{"id":"回春丹"}},
+                {"id":"炼仙丹","name":"炼仙丹",
+                 "ingredients":[{"id":"仙草"}],"output":{"id":"回春丹","count":0}}]"#,
+        )
+        .unwrap();
+        assert_eq!(recipes[0].output.count, 1);
+        assert!(recipes[0].validate_with_items(&items).is_ok());
+        assert!(recipes[1].validate().is_err());
+        assert_eq!(recipes[1].missing_items(&items), ["仙草"]);
+    }
+}
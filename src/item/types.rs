@@ -0,0 +1,145 @@
+use crate::effect::effect::Operation;
+use crate::effect::entry::Entry;
+use crate::error::{WushenError, WushenResult};
+use crate::event::{Reward, RewardTarget};
+/// 物品类型定义
+use serde::{Deserialize, Serialize};
+
+/// 物品（丹药等消耗品，或不可使用的宝物、信物）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Item {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// 使用效果（为空时物品不可使用，只能持有）
+    #[serde(default)]
+    pub effects: Vec<ItemEffect>,
+}
+
+/// 物品使用效果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ItemEffect {
+    /// 恢复生命与内息（不超过上限）
+    Heal {
+        #[serde(default)]
+        hp: f64,
+        #[serde(default)]
+        qi: f64,
+    },
+    /// 限时增益：之后若干次行动内词条生效（同一物品的增益刷新剩余次数）
+    TimedBuff {
+        entries: Vec<Entry>,
+        /// 持续的行动次数（修行、游历各计一次）
+        actions: u32,
+    },
+    /// 永久提升属性（三维、武学素养、生命上限与基础攻防），受属性上限约束
+    Boost { target: RewardTarget, value: f64 },
+}
+
+impl Item {
+    /// 是否可以使用
+    pub fn is_usable(&self) -> bool {
+        !self.effects.is_empty()
+    }
+
+    /// 校验物品配置
+    pub fn validate(&self) -> WushenResult<()> {
+        if self.name.trim().is_empty() {
+            return Err(WushenError::Invalid(format!(
+                "物品 {} 的名称不能为空",
+                self.id
+            )));
+        }
+        for effect in &self.effects {
+            match effect {
+                ItemEffect::Heal { hp, qi } if *hp < 0.0 || *qi < 0.0 || *hp + *qi <= 0.0 => {
+                    return Err(WushenError::Invalid(format!(
+                        "物品 {} 的恢复量必须为正数",
+                        self.id
+                    )));
+                }
+                ItemEffect::TimedBuff { actions: 0, .. } => {
+                    return Err(WushenError::Invalid(format!(
+                        "物品 {} 的增益持续次数必须大于 0",
+                        self.id
+                    )));
+                }
+                ItemEffect::Boost { value, .. } if !value.is_finite() => {
+                    return Err(WushenError::Invalid(format!(
+                        "物品 {} 的属性提升必须是有限数值",
+                        self.id
+                    )));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// 以奖励形式结算的效果（属性提升与限时增益；恢复效果由调用方直接处理）
+    pub fn effect_rewards(&self) -> Vec<Reward> {
+        self.effects
+            .iter()
+            .filter_map(|effect| match effect {
+                ItemEffect::Heal { .. } => None,
+                ItemEffect::TimedBuff { entries, actions } => Some(Reward::TimedBuff {
+                    id: format!("item:{}", self.id),
+                    name: self.name.clone(),
+                    entries: entries.clone(),
+                    actions: *actions,
+                }),
+                ItemEffect::Boost { target, value } => Some(Reward::Attribute {
+                    target: *target,
+                    value: *value,
+                    operation: Operation::Add,
+                    can_exceed_limit: false,
+                }),
+            })
+            .collect()
+    }
+
+    /// 恢复效果的合计（生命, 内息）
+    pub fn heal_amount(&self) -> (f64, f64) {
+        self.effects
+            .iter()
+            .fold((0.0, 0.0), |(hp, qi), effect| match effect {
+                ItemEffect::Heal {
+                    hp: more_hp,
+                    qi: more_qi,
+                } => (hp + more_hp, qi + more_qi),
+                _ => (hp, qi),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::parse_items;
+
+    #[test]
+    fn effects_split_into_heal_and_rewards() {
+        let items = parse_items(
+            r#"[{"id":"回春丹","name":"回春丹","effects":[
+                {"type":"heal","hp":30},
+                {"type":"heal","qi":10},
+                {"type":"boost","target":"max_hp","value":5}
+            ]},{"id":"玉佩","name":"玉佩"}]"#,
+        )
+        .unwrap();
+        let pill = &items[0];
+        assert!(pill.is_usable() && !items[1].is_usable());
+        assert!(pill.validate().is_ok());
+        assert_eq!(pill.heal_amount(), (30.0, 10.0));
+        assert!(matches!(
+            pill.effect_rewards().as_slice(),
+            [Reward::Attribute { target: RewardTarget::MaxHp, value, .. }] if *value == 5.0
+        ));
+
+        let mut empty_heal = pill.clone();
+        empty_heal.effects = vec![ItemEffect::Heal { hp: 0.0, qi: 0.0 }];
+        assert!(empty_heal.validate().is_err());
+    }
+}
@@ -1,7 +1,39 @@
+//! 武神核心库
+//!
+//! 对外接口：`prelude`、`WushenCore`（JSON 接口）以及各模块根部重新导出的类型；
+//! 模块内部的子模块均为 crate 内可见，可能随重构调整。
+//! 公开接口快照见 `tests/public-api.txt`（`make api` 重新生成，`make api-check` 比对）。
+
+#[cfg(feature = "runtime")]
+pub(crate) mod async_core;
 pub mod battle;
 pub mod character;
 pub mod cultivation;
 pub mod effect;
+pub mod error;
+#[cfg(feature = "runtime")]
 pub mod event;
+#[cfg(feature = "runtime")]
 pub mod game;
+#[cfg(feature = "runtime")]
+pub mod item;
+pub mod pack;
+pub mod prelude;
+#[cfg(feature = "runtime")]
+pub mod quest;
+#[cfg(feature = "runtime")]
+pub(crate) mod scenario;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "runtime")]
+pub(crate) mod session;
+#[cfg(feature = "runtime")]
+pub(crate) mod simulation;
+#[cfg(feature = "runtime")]
 pub mod tauri_api;
+#[cfg(all(test, feature = "runtime"))]
+mod test_support;
+
+pub use error::{WushenError, WushenResult};
+#[cfg(feature = "runtime")]
+pub use tauri_api::WushenCore;
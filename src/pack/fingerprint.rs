@@ -0,0 +1,128 @@
+/// 内容指纹：按集合计算已加载内容的稳定摘要（条目按 ID 排序后逐条哈希），
+/// 记录在新存档中，恢复存档时据此判断当前内容与创建存档时是否一致
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::schema::CollectionKind;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// FNV-1a 哈希（不依赖标准库哈希的实现细节，跨版本保持稳定）
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(FNV_OFFSET)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+        // 分隔符，避免相邻字段拼接后产生歧义
+        self.0 ^= 0xff;
+        self.0 = self.0.wrapping_mul(FNV_PRIME);
+    }
+
+    fn hex(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
+
+/// 单个可序列化值的稳定摘要（如战斗配置）
+pub fn value_digest<T: Serialize>(value: &T) -> String {
+    let mut hash = Fnv1a::new();
+    hash.write(serde_json::to_string(value).unwrap_or_default().as_bytes());
+    hash.hex()
+}
+
+/// 内容指纹
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentFingerprint {
+    /// 全部内容的摘要
+    pub digest: String,
+    /// 各集合的摘要（键为集合字段名，空集合不记录）
+    pub collections: BTreeMap<String, String>,
+}
+
+impl Default for ContentFingerprint {
+    fn default() -> Self {
+        Self {
+            digest: Fnv1a::new().hex(),
+            collections: BTreeMap::new(),
+        }
+    }
+}
+
+impl ContentFingerprint {
+    /// 加入一个集合（条目为 ID 与内容），按 ID 排序后计算摘要
+    pub fn add_collection<'a, T: Serialize + 'a>(
+        &mut self,
+        kind: CollectionKind,
+        items: impl IntoIterator<Item = (&'a str, &'a T)>,
+    ) {
+        self.add_collection_text(
+            kind,
+            items
+                .into_iter()
+                .map(|(id, item)| (id, serde_json::to_string(item).unwrap_or_default())),
+        );
+    }
+
+    /// 加入一个集合（条目为 ID 与内容的规范文本）
+    pub fn add_collection_text<'a>(
+        &mut self,
+        kind: CollectionKind,
+        items: impl IntoIterator<Item = (&'a str, String)>,
+    ) {
+        let mut items: Vec<_> = items.into_iter().collect();
+        if items.is_empty() {
+            return;
+        }
+        items.sort_by(|a, b| a.0.cmp(b.0));
+        let mut hash = Fnv1a::new();
+        for (id, text) in items {
+            hash.write(id.as_bytes());
+            hash.write(text.as_bytes());
+        }
+        self.collections.insert(kind.key().to_string(), hash.hex());
+
+        let mut digest = Fnv1a::new();
+        for (key, value) in &self.collections {
+            digest.write(key.as_bytes());
+            digest.write(value.as_bytes());
+        }
+        self.digest = digest.hex();
+    }
+
+    /// 与另一指纹相比内容不同的集合（中文名称，按集合顺序）
+    pub fn changed_collections(&self, other: &Self) -> Vec<&'static str> {
+        CollectionKind::ALL
+            .iter()
+            .filter(|kind| self.collections.get(kind.key()) != other.collections.get(kind.key()))
+            .map(|kind| kind.name())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_ignores_order_and_reports_changed_collections() {
+        let a = ("a", 1);
+        let b = ("b", 2);
+        let mut first = ContentFingerprint::default();
+        first.add_collection(CollectionKind::Traits, [(a.0, &a.1), (b.0, &b.1)]);
+        let mut second = ContentFingerprint::default();
+        second.add_collection(CollectionKind::Traits, [(b.0, &b.1), (a.0, &a.1)]);
+        assert_eq!(first, second);
+
+        second.add_collection(CollectionKind::Quests, [("q", &0)]);
+        assert_ne!(first.digest, second.digest);
+        assert_eq!(first.changed_collections(&second), ["悬赏任务"]);
+    }
+}
@@ -0,0 +1,140 @@
+/// 条目 ID 规则（严格模式）
+/// ID 须为 slug：小写字母、数字、下划线与连字符，以字母或数字开头，至多 64 个字符；
+/// 各集合登记保留前缀，其他集合的条目不得占用，改名或拼写错误造成的悬空引用因此更容易发现
+use crate::error::{WushenError, WushenResult};
+use std::collections::BTreeMap;
+
+use super::schema::CollectionKind;
+
+/// ID 最大长度
+pub const MAX_ID_LEN: usize = 64;
+
+/// 各集合的保留前缀
+pub const RESERVED_PREFIXES: [(CollectionKind, &str); 10] = [
+    (CollectionKind::Traits, "trait_"),
+    (CollectionKind::Internals, "internal_"),
+    (CollectionKind::AttackSkills, "attack_"),
+    (CollectionKind::DefenseSkills, "defense_"),
+    (CollectionKind::Storylines, "story_"),
+    (CollectionKind::Adventures, "adventure_"),
+    (CollectionKind::Quests, "quest_"),
+    (CollectionKind::Enemies, "enemy_"),
+    (CollectionKind::Items, "item_"),
+    (CollectionKind::Recipes, "recipe_"),
+];
+
+/// 集合的保留前缀
+pub fn reserved_prefix(kind: CollectionKind) -> &'static str {
+    RESERVED_PREFIXES
+        .iter()
+        .find(|(owner, _)| *owner == kind)
+        .map(|(_, prefix)| *prefix)
+        .unwrap_or_default()
+}
+
+/// 校验条目 ID 的格式与保留前缀
+pub fn validate_id(kind: CollectionKind, id: &str) -> WushenResult<()> {
+    if id.is_empty() {
+        return Err(WushenError::Invalid("ID 不能为空".to_string()));
+    }
+    if id.len() > MAX_ID_LEN {
+        return Err(WushenError::Invalid(format!(
+            "ID 长度不能超过 {} 个字符",
+            MAX_ID_LEN
+        )));
+    }
+    if !id.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit()) {
+        return Err(WushenError::Invalid(
+            "ID 必须以小写字母或数字开头".to_string(),
+        ));
+    }
+    if let Some(c) = id
+        .chars()
+        .find(|c| !(c.is_ascii_lowercase() || c.is_ascii_digit() || *c == '_' || *c == '-'))
+    {
+        return Err(WushenError::Invalid(format!(
+            "ID 含有非法字符「{}」：只能使用小写字母、数字、下划线与连字符",
+            c
+        )));
+    }
+    if let Some((owner, prefix)) = RESERVED_PREFIXES
+        .iter()
+        .find(|(owner, prefix)| *owner != kind && id.starts_with(prefix))
+    {
+        return Err(WushenError::Invalid(format!(
+            "前缀 {} 保留给{}",
+            prefix,
+            owner.name()
+        )));
+    }
+    Ok(())
+}
+
+/// ID 登记表：跨集合检查重复 ID
+///
+/// 三类功法按 ID 互相查找（如奖励与任务目标引用“任意功法”），共用同一命名空间
+#[derive(Debug, Default)]
+pub struct IdRegistry {
+    ids: BTreeMap<(&'static str, String), CollectionKind>,
+}
+
+impl IdRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记条目 ID（格式不合规或与已登记的 ID 冲突时返回错误）
+    pub fn register(&mut self, kind: CollectionKind, id: &str) -> WushenResult<()> {
+        validate_id(kind, id)
+            .map_err(|e| WushenError::Invalid(format!("{} {}：{}", kind.name(), id, e)))?;
+        let key = (namespace(kind), id.to_string());
+        if let Some(existing) = self.ids.get(&key) {
+            return Err(WushenError::Invalid(format!(
+                "{} {} 与{} {} 的 ID 重复",
+                kind.name(),
+                id,
+                existing.name(),
+                id
+            )));
+        }
+        self.ids.insert(key, kind);
+        Ok(())
+    }
+}
+
+fn namespace(kind: CollectionKind) -> &'static str {
+    match kind {
+        CollectionKind::Internals
+        | CollectionKind::AttackSkills
+        | CollectionKind::DefenseSkills => "manuals",
+        _ => kind.key(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slug_and_reserved_prefix_rules() {
+        validate_id(CollectionKind::Traits, "iron-skin_2").unwrap();
+        validate_id(CollectionKind::Traits, "trait_brave").unwrap();
+        assert!(validate_id(CollectionKind::Traits, "").is_err());
+        assert!(validate_id(CollectionKind::Traits, "Brave").is_err());
+        assert!(validate_id(CollectionKind::Traits, "_brave").is_err());
+        assert!(validate_id(CollectionKind::Traits, "勇猛").is_err());
+        assert!(validate_id(CollectionKind::Traits, &"a".repeat(MAX_ID_LEN + 1)).is_err());
+        let err = validate_id(CollectionKind::Traits, "quest_brave").unwrap_err();
+        assert!(err.message().contains("悬赏任务"), "{}", err);
+
+        let mut registry = IdRegistry::new();
+        registry
+            .register(CollectionKind::Internals, "tuna")
+            .unwrap();
+        registry.register(CollectionKind::Traits, "tuna").unwrap();
+        assert!(registry
+            .register(CollectionKind::DefenseSkills, "tuna")
+            .is_err());
+        assert!(registry.register(CollectionKind::Traits, "tuna").is_err());
+    }
+}
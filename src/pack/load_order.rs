@@ -0,0 +1,159 @@
+use super::manifest::PackManifest;
+/// 模组包加载顺序求解
+/// 按依赖关系进行拓扑排序，依赖包总是先于依赖它的包加载
+use crate::error::{WushenError, WushenResult};
+use std::collections::{HashMap, HashSet};
+
+/// 求解模组包加载顺序
+///
+/// `preferred` 为用户指定的加载顺序（先加载者在前），在满足依赖的前提下尽量保持该顺序；
+/// 未出现在 `preferred` 中的包按清单顺序排在后面。
+/// 依赖缺失、版本不满足或存在循环依赖时返回错误。
+pub fn resolve_load_order(
+    manifests: &[PackManifest],
+    preferred: &[String],
+) -> WushenResult<Vec<String>> {
+    let mut by_id: HashMap<&str, &PackManifest> = HashMap::new();
+    for manifest in manifests {
+        if by_id.insert(manifest.id.as_str(), manifest).is_some() {
+            return Err(WushenError::Invalid(format!(
+                "存在重复的模组包ID: {}",
+                manifest.id
+            )));
+        }
+    }
+
+    for manifest in manifests {
+        for dep in &manifest.dependencies {
+            let target = by_id.get(dep.id.as_str()).ok_or_else(|| {
+                WushenError::Invalid(format!(
+                    "模组包 {} 依赖的模组包 {} 不存在",
+                    manifest.id, dep.id
+                ))
+            })?;
+            if !dep.is_satisfied_by(&target.version)? {
+                return Err(WushenError::Invalid(format!(
+                    "模组包 {} 依赖 {} {}，但当前版本为 {}",
+                    manifest.id,
+                    dep.id,
+                    dep.version.as_deref().unwrap_or("*"),
+                    target.version
+                )));
+            }
+        }
+    }
+
+    // 确定候选顺序：先按用户偏好，再补齐其余包
+    let mut candidates: Vec<&str> = Vec::new();
+    let mut seen: HashSet<&str> = HashSet::new();
+    for id in preferred {
+        if let Some(manifest) = by_id.get(id.as_str()) {
+            if seen.insert(manifest.id.as_str()) {
+                candidates.push(manifest.id.as_str());
+            }
+        }
+    }
+    for manifest in manifests {
+        if seen.insert(manifest.id.as_str()) {
+            candidates.push(manifest.id.as_str());
+        }
+    }
+
+    // DFS 拓扑排序：0=未访问,1=访问中,2=已完成
+    fn visit<'a>(
+        id: &'a str,
+        by_id: &HashMap<&'a str, &'a PackManifest>,
+        color: &mut HashMap<&'a str, u8>,
+        stack: &mut Vec<&'a str>,
+        order: &mut Vec<String>,
+    ) -> WushenResult<()> {
+        match color.get(id).copied().unwrap_or(0) {
+            1 => {
+                let start = stack.iter().position(|s| *s == id).unwrap_or(0);
+                let mut cycle: Vec<&str> = stack[start..].to_vec();
+                cycle.push(id);
+                return Err(WushenError::Invalid(format!(
+                    "检测到模组包循环依赖: {}",
+                    cycle.join(" -> ")
+                )));
+            }
+            2 => return Ok(()),
+            _ => {}
+        }
+        color.insert(id, 1);
+        stack.push(id);
+        for dep in &by_id[id].dependencies {
+            visit(dep.id.as_str(), by_id, color, stack, order)?;
+        }
+        stack.pop();
+        color.insert(id, 2);
+        order.push(id.to_string());
+        Ok(())
+    }
+
+    let mut color: HashMap<&str, u8> = HashMap::new();
+    let mut stack = Vec::new();
+    let mut order = Vec::with_capacity(candidates.len());
+    for id in candidates {
+        visit(id, &by_id, &mut color, &mut stack, &mut order)?;
+    }
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pack::manifest::PackDependency;
+
+    fn pack(id: &str, version: &str, deps: &[(&str, Option<&str>)]) -> PackManifest {
+        PackManifest {
+            id: id.to_string(),
+            name: id.to_string(),
+            version: version.to_string(),
+            author: None,
+            description: None,
+            files: None,
+            dependencies: deps
+                .iter()
+                .map(|(id, req)| PackDependency {
+                    id: id.to_string(),
+                    version: req.map(str::to_string),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_dependencies_load_first() {
+        let packs = vec![
+            pack("expansion", "1.0.0", &[("base", Some("^1.0"))]),
+            pack("base", "1.2.0", &[]),
+            pack("other", "0.1.0", &[]),
+        ];
+        let order =
+            resolve_load_order(&packs, &["other".to_string(), "expansion".to_string()]).unwrap();
+        assert_eq!(order, vec!["other", "base", "expansion"]);
+    }
+
+    #[test]
+    fn test_missing_and_version_mismatch() {
+        let packs = vec![pack("expansion", "1.0.0", &[("base", None)])];
+        assert!(resolve_load_order(&packs, &[]).is_err());
+
+        let packs = vec![
+            pack("expansion", "1.0.0", &[("base", Some(">=2.0"))]),
+            pack("base", "1.2.0", &[]),
+        ];
+        assert!(resolve_load_order(&packs, &[]).is_err());
+    }
+
+    #[test]
+    fn test_cyclic_dependencies() {
+        let packs = vec![
+            pack("a", "1.0.0", &[("b", None)]),
+            pack("b", "1.0.0", &[("a", None)]),
+        ];
+        let err = resolve_load_order(&packs, &[]).unwrap_err();
+        assert!(err.message().contains("循环依赖"));
+    }
+}
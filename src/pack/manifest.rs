@@ -0,0 +1,197 @@
+/// 模组包清单
+/// 对应包目录下的 metadata.toml，声明包信息与依赖关系
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// 模组包清单
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackManifest {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub files: Option<Vec<String>>,
+    /// 依赖的其他模组包
+    #[serde(default)]
+    pub dependencies: Vec<PackDependency>,
+}
+
+/// 模组包依赖声明
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackDependency {
+    /// 依赖包 ID
+    pub id: String,
+    /// 版本要求，如 `^1.2`、`>=1.0, <2.0`，为空或 `*` 时不限版本
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+impl PackDependency {
+    /// 检查给定版本是否满足依赖的版本要求
+    pub fn is_satisfied_by(&self, version: &str) -> WushenResult<bool> {
+        let actual = PackVersion::parse(version)
+            .map_err(|e| WushenError::Invalid(format!("模组包 {} 版本号无效: {}", self.id, e)))?;
+        match self.version.as_deref().map(str::trim) {
+            None | Some("") | Some("*") => Ok(true),
+            Some(req) => version_matches(req, &actual).map_err(|e| {
+                WushenError::Invalid(format!("依赖 {} 的版本要求无效: {}", self.id, e))
+            }),
+        }
+    }
+}
+
+/// 模组包版本号（major.minor.patch，缺省部分视为 0）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PackVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl PackVersion {
+    /// 解析版本号字符串
+    pub fn parse(raw: &str) -> WushenResult<Self> {
+        let (version, _) = Self::parse_partial(raw)?;
+        Ok(version)
+    }
+
+    /// 解析版本号，同时返回显式给出的段数（用于 ^ / ~ 规则）
+    fn parse_partial(raw: &str) -> WushenResult<(Self, usize)> {
+        let core = raw.trim().trim_start_matches('v');
+        let core = core.split(['-', '+']).next().unwrap_or("");
+        if core.is_empty() {
+            return Err(WushenError::ParseError(format!("版本号为空: {}", raw)));
+        }
+        let parts: Vec<&str> = core.split('.').collect();
+        if parts.len() > 3 {
+            return Err(WushenError::ParseError(format!("版本号段数过多: {}", raw)));
+        }
+        let mut nums = [0u64; 3];
+        for (i, part) in parts.iter().enumerate() {
+            nums[i] = part
+                .parse::<u64>()
+                .map_err(|_| WushenError::ParseError(format!("版本号格式错误: {}", raw)))?;
+        }
+        Ok((
+            Self {
+                major: nums[0],
+                minor: nums[1],
+                patch: nums[2],
+            },
+            parts.len(),
+        ))
+    }
+}
+
+impl fmt::Display for PackVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// 判断版本是否满足要求（逗号分隔的多个条件需同时满足）
+fn version_matches(req: &str, actual: &PackVersion) -> WushenResult<bool> {
+    for clause in req.split(',') {
+        if !clause_matches(clause.trim(), actual)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn clause_matches(clause: &str, actual: &PackVersion) -> WushenResult<bool> {
+    if clause.is_empty() || clause == "*" {
+        return Ok(true);
+    }
+    let (op, rest) = [">=", "<=", ">", "<", "=", "^", "~"]
+        .iter()
+        .find_map(|op| clause.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("^", clause));
+    let (target, parts) = PackVersion::parse_partial(rest)?;
+    let ordering = actual.cmp(&target);
+    let matched = match op {
+        ">=" => ordering != Ordering::Less,
+        "<=" => ordering != Ordering::Greater,
+        ">" => ordering == Ordering::Greater,
+        "<" => ordering == Ordering::Less,
+        "=" => ordering == Ordering::Equal,
+        "~" => {
+            let upper = if parts >= 2 {
+                PackVersion {
+                    major: target.major,
+                    minor: target.minor + 1,
+                    patch: 0,
+                }
+            } else {
+                PackVersion {
+                    major: target.major + 1,
+                    minor: 0,
+                    patch: 0,
+                }
+            };
+            ordering != Ordering::Less && *actual < upper
+        }
+        _ => {
+            // ^：不改变最左侧非零段
+            let upper = if target.major > 0 || parts == 1 {
+                PackVersion {
+                    major: target.major + 1,
+                    minor: 0,
+                    patch: 0,
+                }
+            } else if target.minor > 0 || parts == 2 {
+                PackVersion {
+                    major: 0,
+                    minor: target.minor + 1,
+                    patch: 0,
+                }
+            } else {
+                PackVersion {
+                    major: 0,
+                    minor: 0,
+                    patch: target.patch + 1,
+                }
+            };
+            ordering != Ordering::Less && *actual < upper
+        }
+    };
+    Ok(matched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dep(req: &str) -> PackDependency {
+        PackDependency {
+            id: "base".to_string(),
+            version: Some(req.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_version_requirements() {
+        assert!(dep("^1.2").is_satisfied_by("1.4.0").unwrap());
+        assert!(!dep("^1.2").is_satisfied_by("2.0.0").unwrap());
+        assert!(!dep("^0.2").is_satisfied_by("0.3.0").unwrap());
+        assert!(dep("~1.2.3").is_satisfied_by("1.2.9").unwrap());
+        assert!(!dep("~1.2.3").is_satisfied_by("1.3.0").unwrap());
+        assert!(dep(">=1.0, <2.0").is_satisfied_by("1.9.9").unwrap());
+        assert!(!dep(">=1.0, <2.0").is_satisfied_by("2.0").unwrap());
+        assert!(dep("*").is_satisfied_by("0.1.0").unwrap());
+        assert!(dep("1.x").is_satisfied_by("1.0.0").is_err());
+    }
+
+    #[test]
+    fn test_manifest_dependencies_default() {
+        let manifest: PackManifest =
+            serde_json::from_str(r#"{"id":"a","name":"A","version":"0.1.0"}"#).unwrap();
+        assert!(manifest.dependencies.is_empty());
+    }
+}
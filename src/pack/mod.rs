@@ -0,0 +1,16 @@
+pub(crate) mod fingerprint;
+pub(crate) mod ids;
+pub(crate) mod load_order;
+pub(crate) mod manifest;
+pub(crate) mod schema;
+pub(crate) mod strict;
+pub(crate) mod warning;
+
+// 重新导出常用类型
+pub use fingerprint::ContentFingerprint;
+pub use ids::{reserved_prefix, validate_id, IdRegistry};
+pub use load_order::resolve_load_order;
+pub use manifest::{PackDependency, PackManifest, PackVersion};
+pub use schema::{upgrade_collection, CollectionKind, CURRENT_SCHEMA_VERSION, SCHEMA_VERSION_KEY};
+pub use strict::ParseOptions;
+pub use warning::{LoadWarning, LoadWarningKind};
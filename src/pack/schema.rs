@@ -0,0 +1,238 @@
+/// 数据格式版本与升级管线
+/// 模组包中的每个 JSON 集合及其中的每个条目都可以声明 `schema_version`，
+/// 解析前按版本依次执行升级函数，使旧版数据在格式调整后仍可加载
+use crate::error::{WushenError, WushenResult};
+use serde_json::{Map, Value};
+
+use super::warning::LoadWarning;
+
+/// 当前数据格式版本
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/// 未声明版本时视为的版本（早期未带版本号的数据）
+pub const LEGACY_SCHEMA_VERSION: u32 = 0;
+
+/// 版本字段名
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// 数据集合类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionKind {
+    Traits,
+    Internals,
+    AttackSkills,
+    DefenseSkills,
+    Storylines,
+    Adventures,
+    Quests,
+    Enemies,
+    Items,
+    Recipes,
+}
+
+impl CollectionKind {
+    /// 全部集合类型
+    pub const ALL: [CollectionKind; 10] = [
+        CollectionKind::Traits,
+        CollectionKind::Internals,
+        CollectionKind::AttackSkills,
+        CollectionKind::DefenseSkills,
+        CollectionKind::Storylines,
+        CollectionKind::Adventures,
+        CollectionKind::Quests,
+        CollectionKind::Enemies,
+        CollectionKind::Items,
+        CollectionKind::Recipes,
+    ];
+
+    /// 对象格式中集合所在的字段名
+    pub fn key(&self) -> &'static str {
+        match self {
+            CollectionKind::Traits => "traits",
+            CollectionKind::Internals => "internals",
+            CollectionKind::AttackSkills => "attack_skills",
+            CollectionKind::DefenseSkills => "defense_skills",
+            CollectionKind::Storylines => "storylines",
+            CollectionKind::Adventures => "adventures",
+            CollectionKind::Quests => "quests",
+            CollectionKind::Enemies => "enemies",
+            CollectionKind::Items => "items",
+            CollectionKind::Recipes => "recipes",
+        }
+    }
+
+    /// 集合中条目的中文名称（用于提示信息）
+    pub fn name(&self) -> &'static str {
+        match self {
+            CollectionKind::Traits => "特性",
+            CollectionKind::Internals => "内功",
+            CollectionKind::AttackSkills => "攻击武技",
+            CollectionKind::DefenseSkills => "防御武技",
+            CollectionKind::Storylines => "剧情线",
+            CollectionKind::Adventures => "奇遇事件",
+            CollectionKind::Quests => "悬赏任务",
+            CollectionKind::Enemies => "敌人",
+            CollectionKind::Items => "物品",
+            CollectionKind::Recipes => "炼制配方",
+        }
+    }
+}
+
+/// 升级函数：将条目从版本 N 升级到 N+1，必要时写入警告
+pub type Upgrader = fn(CollectionKind, &mut Map<String, Value>, &mut Vec<LoadWarning>);
+
+/// 升级函数表，下标 i 对应版本 i -> i+1
+const UPGRADERS: [Upgrader; CURRENT_SCHEMA_VERSION as usize] = [upgrade_v0_to_v1];
+
+/// v0 -> v1：功法类型字段统一为 `type`
+fn upgrade_v0_to_v1(
+    kind: CollectionKind,
+    item: &mut Map<String, Value>,
+    warnings: &mut Vec<LoadWarning>,
+) {
+    if !matches!(
+        kind,
+        CollectionKind::Internals | CollectionKind::AttackSkills | CollectionKind::DefenseSkills
+    ) {
+        return;
+    }
+    if item.contains_key("type") {
+        return;
+    }
+    if let Some(value) = item.remove("manual_type") {
+        item.insert("type".to_string(), value);
+        let id = item_id(item);
+        warnings.push(LoadWarning::deprecated(
+            kind,
+            id,
+            format!("{} {}：字段 manual_type 已更名为 type", kind.name(), id),
+        ));
+    }
+}
+
+fn item_id(item: &Map<String, Value>) -> &str {
+    item.get("id").and_then(Value::as_str).unwrap_or("<未知>")
+}
+
+fn read_version(value: Option<&Value>) -> WushenResult<Option<u32>> {
+    match value {
+        None | Some(Value::Null) => Ok(None),
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .map(Some)
+            .ok_or_else(|| {
+                WushenError::ParseError(format!("{} 必须为非负整数", SCHEMA_VERSION_KEY))
+            }),
+    }
+}
+
+/// 读取集合并将每个条目升级到当前版本
+///
+/// 支持对象格式 `{"schema_version": 1, "<key>": [...]}` 与数组格式 `[...]`；
+/// 条目自身声明的 `schema_version` 优先于集合级版本。
+/// 返回升级后的条目（已移除版本字段）与升级过程中产生的警告。
+pub fn upgrade_collection(
+    json: &str,
+    kind: CollectionKind,
+) -> WushenResult<(Vec<Value>, Vec<LoadWarning>)> {
+    upgrade_collection_with(json, kind, &UPGRADERS)
+}
+
+fn upgrade_collection_with(
+    json: &str,
+    kind: CollectionKind,
+    upgraders: &[Upgrader],
+) -> WushenResult<(Vec<Value>, Vec<LoadWarning>)> {
+    let current = upgraders.len() as u32;
+    let value: Value = serde_json::from_str(json)?;
+    let (collection_version, items) = match value {
+        Value::Array(items) => (None, items),
+        Value::Object(mut obj) => {
+            let version = read_version(obj.get(SCHEMA_VERSION_KEY))?;
+            match obj.remove(kind.key()) {
+                Some(Value::Array(items)) => (version, items),
+                _ => return Err(WushenError::ParseError(format!("缺少 {} 数组", kind.key()))),
+            }
+        }
+        _ => return Err(WushenError::ParseError("数据必须为对象或数组".to_string())),
+    };
+
+    let mut warnings = Vec::new();
+    let mut upgraded = Vec::with_capacity(items.len());
+    for item in items {
+        let Value::Object(mut obj) = item else {
+            upgraded.push(item);
+            continue;
+        };
+        let version = read_version(obj.remove(SCHEMA_VERSION_KEY).as_ref())
+            .map_err(|e| {
+                WushenError::ParseError(format!("{} {}：{}", kind.name(), item_id(&obj), e))
+            })?
+            .or(collection_version)
+            .unwrap_or(LEGACY_SCHEMA_VERSION);
+        if version > current {
+            return Err(WushenError::ParseError(format!(
+                "{} {} 的数据版本 {} 高于当前支持的版本 {}",
+                kind.name(),
+                item_id(&obj),
+                version,
+                current
+            )));
+        }
+        for upgrader in &upgraders[version as usize..] {
+            upgrader(kind, &mut obj, &mut warnings);
+        }
+        upgraded.push(Value::Object(obj));
+    }
+    Ok((upgraded, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_manual_type_upgraded() {
+        let json = r#"{"internals":[{"id":"a","manual_type":"内功"}]}"#;
+        let (items, warnings) = upgrade_collection(json, CollectionKind::Internals).unwrap();
+        assert_eq!(items[0]["type"], "内功");
+        assert!(items[0].get("manual_type").is_none());
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_item_version_overrides_collection() {
+        let json = r#"{"schema_version":0,"internals":[
+            {"id":"a","manual_type":"x","schema_version":1},
+            {"id":"b","manual_type":"y"}
+        ]}"#;
+        let (items, warnings) = upgrade_collection(json, CollectionKind::Internals).unwrap();
+        assert_eq!(items[0]["manual_type"], "x");
+        assert!(items[0].get(SCHEMA_VERSION_KEY).is_none());
+        assert_eq!(items[1]["type"], "y");
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_upgrade_pipeline_runs_in_order() {
+        fn add_a(_: CollectionKind, item: &mut Map<String, Value>, _: &mut Vec<LoadWarning>) {
+            item.insert("trail".to_string(), Value::from("a"));
+        }
+        fn add_b(kind: CollectionKind, item: &mut Map<String, Value>, w: &mut Vec<LoadWarning>) {
+            let trail = item["trail"].as_str().unwrap_or("").to_string();
+            item.insert("trail".to_string(), Value::from(trail + "b"));
+            w.push(LoadWarning::deprecated(kind, "x", "b"));
+        }
+        let upgraders: [Upgrader; 2] = [add_a, add_b];
+        let json = r#"[{"id":"x","schema_version":0},{"id":"y","schema_version":1,"trail":""}]"#;
+        let (items, warnings) =
+            upgrade_collection_with(json, CollectionKind::Traits, &upgraders).unwrap();
+        assert_eq!(items[0]["trail"], "ab");
+        assert_eq!(items[1]["trail"], "b");
+        assert_eq!(warnings.len(), 2);
+
+        let future = r#"[{"id":"z","schema_version":3}]"#;
+        assert!(upgrade_collection_with(future, CollectionKind::Traits, &upgraders).is_err());
+    }
+}
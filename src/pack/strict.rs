@@ -0,0 +1,191 @@
+/// 集合解析选项与严格模式
+/// 严格模式下，解析前检查每个条目的 ID（见 [`super::ids`]）与未知字段，
+/// 错误信息附带 JSON Pointer 位置（按传入的原始数据计算）
+use crate::error::{WushenError, WushenResult};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::ids::validate_id;
+use super::schema::{upgrade_collection, CollectionKind};
+use super::warning::LoadWarning;
+
+/// 集合解析选项
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseOptions {
+    /// 严格模式：拒绝未知字段与不合规的 ID
+    #[serde(default)]
+    pub strict: bool,
+}
+
+impl ParseOptions {
+    pub fn strict() -> Self {
+        Self { strict: true }
+    }
+}
+
+/// 读取集合、升级到当前版本并解析条目；返回条目与升级警告
+pub fn parse_collection<T: DeserializeOwned + Serialize>(
+    json: &str,
+    kind: CollectionKind,
+    options: ParseOptions,
+) -> WushenResult<(Vec<T>, Vec<LoadWarning>)> {
+    parse_with(json, kind, options, unknown_fields::<T>)
+}
+
+/// 同 [`parse_collection`]，用于字段由条目自行展开的集合（如敌人库的覆盖字段）：严格模式只检查 ID
+#[cfg(feature = "runtime")]
+pub fn parse_open_collection<T: DeserializeOwned>(
+    json: &str,
+    kind: CollectionKind,
+    options: ParseOptions,
+) -> WushenResult<(Vec<T>, Vec<LoadWarning>)> {
+    parse_with(json, kind, options, |_| Vec::new())
+}
+
+fn parse_with<T: DeserializeOwned>(
+    json: &str,
+    kind: CollectionKind,
+    options: ParseOptions,
+    unknown_fields: fn(&Value) -> Vec<String>,
+) -> WushenResult<(Vec<T>, Vec<LoadWarning>)> {
+    let (items, warnings) = upgrade_collection(json, kind)?;
+    if options.strict {
+        let base = collection_pointer(json, kind);
+        let mut problems = Vec::new();
+        for (index, item) in items.iter().enumerate() {
+            let pointer = format!("{}/{}", base, index);
+            match item.get("id").and_then(Value::as_str) {
+                Some(id) => {
+                    if let Err(e) = validate_id(kind, id) {
+                        problems.push(format!("{}/id {}：{}", pointer, id, e));
+                    }
+                }
+                None => problems.push(format!("{}/id：缺少 ID", pointer)),
+            }
+            for field in unknown_fields(item) {
+                problems.push(format!("{}{}：未知字段", pointer, field));
+            }
+        }
+        if !problems.is_empty() {
+            return Err(WushenError::ParseError(format!(
+                "{}数据不符合严格模式：{}",
+                kind.name(),
+                problems.join("；")
+            )));
+        }
+    }
+    let parsed = serde_json::from_value(Value::Array(items))?;
+    Ok((parsed, warnings))
+}
+
+/// 条目数组在原始数据中的位置（对象格式为 `/<key>`，数组格式为根）
+fn collection_pointer(json: &str, kind: CollectionKind) -> String {
+    match serde_json::from_str::<Value>(json) {
+        Ok(Value::Object(_)) => format!("/{}", escape(kind.key())),
+        _ => String::new(),
+    }
+}
+
+/// 条目中未被类型 T 识别的字段（相对条目的 JSON Pointer）
+///
+/// 先比较条目与“解析后再序列化”的结果，找出消失的字段；消失也可能是因为字段取缺省值而未序列化，
+/// 因此再将该字段替换为任何类型都不接受的探测值：仍能解析说明字段被忽略，即为未知字段
+fn unknown_fields<T: DeserializeOwned + Serialize>(item: &Value) -> Vec<String> {
+    // 类型错误交给正常解析报告
+    let Ok(parsed) = serde_json::from_value::<T>(item.clone()) else {
+        return Vec::new();
+    };
+    let Ok(normalized) = serde_json::to_value(&parsed) else {
+        return Vec::new();
+    };
+    let mut candidates = Vec::new();
+    missing_fields(item, &normalized, String::new(), &mut candidates);
+    let probe = serde_json::json!([[[[null]]]]);
+    candidates
+        .into_iter()
+        .filter(|pointer| {
+            let mut probed = item.clone();
+            if let Some(slot) = probed.pointer_mut(pointer) {
+                *slot = probe.clone();
+            }
+            serde_json::from_value::<T>(probed).is_ok()
+        })
+        .collect()
+}
+
+fn missing_fields(input: &Value, output: &Value, path: String, found: &mut Vec<String>) {
+    match (input, output) {
+        (Value::Object(input), Value::Object(output)) => {
+            for (key, value) in input {
+                let pointer = format!("{}/{}", path, escape(key));
+                match output.get(key) {
+                    Some(normalized) => missing_fields(value, normalized, pointer, found),
+                    None => found.push(pointer),
+                }
+            }
+        }
+        (Value::Array(input), Value::Array(output)) if input.len() == output.len() => {
+            for (index, (value, normalized)) in input.iter().zip(output).enumerate() {
+                missing_fields(value, normalized, format!("{}/{}", path, index), found);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Item {
+        id: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tags: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        inner: Option<Inner>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Inner {
+        value: f64,
+    }
+
+    #[test]
+    fn strict_mode_reports_ids_and_unknown_fields() {
+        let json = r#"{"traits": [
+            {"id": "ok", "tags": [], "inner": {"value": 1, "valeu": 2}},
+            {"id": "Bad", "nmae": "x"}
+        ]}"#;
+        let (items, _) =
+            parse_collection::<Item>(json, CollectionKind::Traits, ParseOptions::default())
+                .unwrap();
+        assert_eq!(items.len(), 2);
+
+        let err = parse_collection::<Item>(json, CollectionKind::Traits, ParseOptions::strict())
+            .unwrap_err();
+        assert!(
+            err.message().contains("/traits/0/inner/valeu：未知字段"),
+            "{}",
+            err
+        );
+        assert!(err.message().contains("/traits/1/id Bad"), "{}", err);
+        assert!(
+            err.message().contains("/traits/1/nmae：未知字段"),
+            "{}",
+            err
+        );
+        // 取缺省值的已知字段不算未知字段
+        assert!(!err.message().contains("/traits/0/tags"), "{}", err);
+
+        let array = r#"[{"id": "ok", "extra": true}]"#;
+        let err = parse_collection::<Item>(array, CollectionKind::Traits, ParseOptions::strict())
+            .unwrap_err();
+        assert!(err.message().contains("/0/extra：未知字段"), "{}", err);
+    }
+}
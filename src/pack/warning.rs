@@ -0,0 +1,74 @@
+/// 加载警告
+/// 解析与加载数据时发现的问题中不影响加载的部分，按类别结构化返回，供编辑器展示
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::schema::CollectionKind;
+
+/// 警告类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadWarningKind {
+    /// 使用了已废弃的字段或格式（已自动升级）
+    Deprecated,
+    /// 数值可疑（如负的升级经验），按原样加载
+    Suspicious,
+    /// 加载时自动修正或回退了数据
+    AutoCorrected,
+}
+
+/// 加载警告
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoadWarning {
+    pub kind: LoadWarningKind,
+    /// 所属集合（对象格式中的字段名，如 internals）
+    pub collection: String,
+    /// 条目 ID（无法确定时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// 完整的警告信息
+    pub message: String,
+}
+
+impl LoadWarning {
+    pub fn new(
+        kind: LoadWarningKind,
+        collection: CollectionKind,
+        id: Option<&str>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            collection: collection.key().to_string(),
+            id: id.map(str::to_string),
+            message: message.into(),
+        }
+    }
+
+    pub fn deprecated(collection: CollectionKind, id: &str, message: impl Into<String>) -> Self {
+        Self::new(LoadWarningKind::Deprecated, collection, Some(id), message)
+    }
+
+    pub fn suspicious(collection: CollectionKind, id: &str, message: impl Into<String>) -> Self {
+        Self::new(LoadWarningKind::Suspicious, collection, Some(id), message)
+    }
+
+    pub fn auto_corrected(
+        collection: CollectionKind,
+        id: &str,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::new(
+            LoadWarningKind::AutoCorrected,
+            collection,
+            Some(id),
+            message,
+        )
+    }
+}
+
+impl fmt::Display for LoadWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
@@ -0,0 +1,30 @@
+#[cfg(feature = "runtime")]
+pub use crate::async_core::{CoreCommand, CoreReply, PendingReply, WushenCoreAsync};
+/// 常用类型预导入
+/// 嵌入方可通过 `use wushen_core::prelude::*;` 一次性引入主要 API
+pub use crate::battle::{
+    BattleConfig, BattleEngine, BattleLog, BattleLogKind, BattlePanel, BattleRecord, BattleResult,
+    PanelDelta, QiOutputProfile, Side, TiebreakerRule,
+};
+pub use crate::character::{CharacterPanel, ThreeDimensional, Trait, TraitManager};
+pub use crate::cultivation::manual_manager::ManualManager;
+pub use crate::cultivation::{AttackSkill, DefenseSkill, Internal};
+pub use crate::effect::{Condition, Effect, Entry, Trigger};
+pub use crate::error::{WushenError, WushenResult};
+#[cfg(feature = "runtime")]
+pub use crate::event::{
+    AdventureEvent, EnemyTemplate, EventManager, PostBattleRecovery, Reward, RewardTarget,
+    StoryEvent, Storyline,
+};
+#[cfg(feature = "runtime")]
+pub use crate::game::{CharacterState, GameRuntime, NewGameRequest, SaveGame};
+#[cfg(feature = "runtime")]
+pub use crate::quest::{Quest, QuestManager, QuestObjective};
+#[cfg(feature = "runtime")]
+pub use crate::scenario::{Scenario, ScenarioFailure, ScenarioReport, ScenarioStep};
+#[cfg(feature = "runtime")]
+pub use crate::session::{SessionHandle, SessionManager};
+#[cfg(feature = "runtime")]
+pub use crate::simulation::{CancellationToken, SimulationProgress};
+#[cfg(feature = "runtime")]
+pub use crate::tauri_api::{ContentStore, WushenCore};
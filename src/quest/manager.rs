@@ -0,0 +1,279 @@
+use crate::character::trait_manager::TraitManager;
+use crate::cultivation::manual_manager::ManualManager;
+use crate::error::{WushenError, WushenResult};
+use crate::event::ManualKind;
+use crate::game::{CharacterState, QuestProgress};
+use crate::quest::types::{Quest, QuestObjective};
+/// 悬赏任务管理器
+use std::collections::BTreeMap;
+
+/// 悬赏任务管理器
+#[derive(Clone)]
+pub struct QuestManager {
+    quests: BTreeMap<String, Quest>,
+}
+
+impl QuestManager {
+    pub fn new() -> Self {
+        Self {
+            quests: BTreeMap::new(),
+        }
+    }
+
+    pub fn load_quests(&mut self, quests: Vec<Quest>) {
+        for quest in quests {
+            self.quests.insert(quest.id.clone(), quest);
+        }
+    }
+
+    pub fn get_quest(&self, id: &str) -> Option<&Quest> {
+        self.quests.get(id)
+    }
+
+    pub fn all_quests(&self) -> Vec<&Quest> {
+        self.quests.values().collect()
+    }
+
+    /// 校验单个悬赏任务
+    pub fn validate_quest(quest: &Quest) -> WushenResult<()> {
+        if quest.objectives.is_empty() {
+            return Err(WushenError::Invalid(format!(
+                "悬赏任务 {} 的目标不能为空",
+                quest.id
+            )));
+        }
+        for objective in &quest.objectives {
+            match objective {
+                QuestObjective::WinBattles { count } if *count == 0 => {
+                    return Err(WushenError::Invalid(format!(
+                        "悬赏任务 {} 的战斗胜利场数必须大于0",
+                        quest.id
+                    )));
+                }
+                QuestObjective::ManualLevel { level, .. } if !(1..=5).contains(level) => {
+                    return Err(WushenError::Invalid(format!(
+                        "悬赏任务 {} 的功法等级必须在 1-5 之间",
+                        quest.id
+                    )));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// 计算任务目标进度，返回（当前值, 目标值）
+    pub fn objective_progress(
+        objective: &QuestObjective,
+        progress: &QuestProgress,
+        character: &CharacterState,
+    ) -> (u32, u32) {
+        match objective {
+            QuestObjective::WinBattles { count } => (progress.battles_won.min(*count), *count),
+            QuestObjective::ManualLevel {
+                manual_kind,
+                manual_id,
+                level,
+            } => {
+                let lists = match manual_kind {
+                    ManualKind::Internal => vec![&character.internals],
+                    ManualKind::AttackSkill => vec![&character.attack_skills],
+                    ManualKind::DefenseSkill => vec![&character.defense_skills],
+                    ManualKind::Any => vec![
+                        &character.internals,
+                        &character.attack_skills,
+                        &character.defense_skills,
+                    ],
+                };
+                let best = lists
+                    .iter()
+                    .flat_map(|list| list.owned.iter())
+                    .filter(|owned| manual_id.as_ref().is_none_or(|id| &owned.id == id))
+                    .map(|owned| owned.level)
+                    .max()
+                    .unwrap_or(0);
+                (best.min(*level), *level)
+            }
+            QuestObjective::HasTrait { trait_id } => {
+                (u32::from(character.traits.contains(trait_id)), 1)
+            }
+        }
+    }
+
+    /// 判断任务目标是否全部达成
+    pub fn is_completable(
+        quest: &Quest,
+        progress: &QuestProgress,
+        character: &CharacterState,
+    ) -> bool {
+        quest.objectives.iter().all(|objective| {
+            let (current, target) = Self::objective_progress(objective, progress, character);
+            current >= target
+        })
+    }
+
+    /// 生成任务目标描述
+    pub fn describe_objective(
+        objective: &QuestObjective,
+        manual_manager: &ManualManager,
+        trait_manager: &TraitManager,
+    ) -> String {
+        match objective {
+            QuestObjective::WinBattles { count } => format!("战斗胜利 {} 场", count),
+            QuestObjective::ManualLevel {
+                manual_kind,
+                manual_id,
+                level,
+            } => {
+                let kind_name = match manual_kind {
+                    ManualKind::Internal => "内功",
+                    ManualKind::AttackSkill => "攻击武技",
+                    ManualKind::DefenseSkill => "防御武技",
+                    ManualKind::Any => "功法",
+                };
+                let manual_name = manual_id.as_deref().map(|id| {
+                    let name = match manual_kind {
+                        ManualKind::Internal => manual_manager.get_internal(id).map(|m| &m.manual),
+                        ManualKind::AttackSkill => {
+                            manual_manager.get_attack_skill(id).map(|m| &m.manual)
+                        }
+                        ManualKind::DefenseSkill => {
+                            manual_manager.get_defense_skill(id).map(|m| &m.manual)
+                        }
+                        ManualKind::Any => manual_manager
+                            .get_internal(id)
+                            .map(|m| &m.manual)
+                            .or_else(|| manual_manager.get_attack_skill(id).map(|m| &m.manual))
+                            .or_else(|| manual_manager.get_defense_skill(id).map(|m| &m.manual)),
+                    };
+                    name.map_or(id, |m| m.name.as_str())
+                });
+                match manual_name {
+                    Some(name) => format!("将{}「{}」修炼至 {} 级", kind_name, name, level),
+                    None => format!("将任意{}修炼至 {} 级", kind_name, level),
+                }
+            }
+            QuestObjective::HasTrait { trait_id } => {
+                let name = trait_manager
+                    .get_trait(trait_id)
+                    .map_or(trait_id.as_str(), |t| t.name.as_str());
+                format!("获得特性「{}」", name)
+            }
+        }
+    }
+}
+
+impl Default for QuestManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{ManualsState, OwnedManualState, ThreeDimensionalState};
+
+    fn character() -> CharacterState {
+        let manuals = |owned: Vec<OwnedManualState>| ManualsState {
+            owned,
+            equipped: None,
+        };
+        CharacterState {
+            id: "c".to_string(),
+            name: "测试".to_string(),
+            three_d: ThreeDimensionalState {
+                comprehension: 5,
+                bone_structure: 5,
+                physique: 5,
+            },
+            traits: vec!["t1".to_string()],
+            internals: manuals(vec![OwnedManualState {
+                id: "i1".to_string(),
+                level: 3,
+                exp: 0.0,
+            }]),
+            attack_skills: manuals(Vec::new()),
+            defense_skills: manuals(Vec::new()),
+            action_points: 0,
+            cultivation_history: Vec::new(),
+            max_hp: None,
+            hp: None,
+            base_attack: None,
+            base_defense: None,
+            max_qi: None,
+            qi: None,
+            martial_arts_attainment: None,
+            timed_buffs: Vec::new(),
+            scheduled_events: Vec::new(),
+            story_flags: Vec::new(),
+            titles: Vec::new(),
+            worn_title: None,
+            inventory: Vec::new(),
+            chronicle: None,
+            appearance: None,
+        }
+    }
+
+    fn quest(objectives: Vec<QuestObjective>) -> Quest {
+        Quest {
+            id: "q".to_string(),
+            name: "悬赏".to_string(),
+            description: String::new(),
+            condition: None,
+            objectives,
+            rewards: Vec::new(),
+            repeatable: false,
+        }
+    }
+
+    #[test]
+    fn test_objective_progress() {
+        let character = character();
+        let mut progress = QuestProgress {
+            quest_id: "q".to_string(),
+            battles_won: 1,
+        };
+        let q = quest(vec![
+            QuestObjective::WinBattles { count: 2 },
+            QuestObjective::ManualLevel {
+                manual_kind: ManualKind::Any,
+                manual_id: None,
+                level: 3,
+            },
+            QuestObjective::HasTrait {
+                trait_id: "t1".to_string(),
+            },
+        ]);
+        assert!(!QuestManager::is_completable(&q, &progress, &character));
+        progress.battles_won = 5;
+        assert_eq!(
+            QuestManager::objective_progress(&q.objectives[0], &progress, &character),
+            (2, 2)
+        );
+        assert!(QuestManager::is_completable(&q, &progress, &character));
+
+        let attack = QuestObjective::ManualLevel {
+            manual_kind: ManualKind::AttackSkill,
+            manual_id: None,
+            level: 1,
+        };
+        assert_eq!(
+            QuestManager::objective_progress(&attack, &progress, &character),
+            (0, 1)
+        );
+    }
+
+    #[test]
+    fn test_validate_quest() {
+        assert!(QuestManager::validate_quest(&quest(Vec::new())).is_err());
+        assert!(
+            QuestManager::validate_quest(&quest(vec![QuestObjective::WinBattles { count: 0 }]))
+                .is_err()
+        );
+        assert!(
+            QuestManager::validate_quest(&quest(vec![QuestObjective::WinBattles { count: 1 }]))
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,7 @@
+pub(crate) mod manager;
+pub(crate) mod parser;
+pub(crate) mod types;
+
+pub use manager::QuestManager;
+pub use parser::parse_quests;
+pub use types::{Quest, QuestObjective};
@@ -0,0 +1,28 @@
+/// 悬赏任务 JSON 解析器
+use crate::error::{WushenError, WushenResult};
+use crate::pack::schema::CollectionKind;
+use crate::pack::strict::{parse_collection, ParseOptions};
+use crate::pack::warning::LoadWarning;
+use crate::quest::types::Quest;
+
+/// 解析悬赏任务数据
+/// 支持两种格式：
+/// 1. 对象格式：{"quests":[...]}
+/// 2. 数组格式：[...]
+pub fn parse_quests(json: &str) -> WushenResult<Vec<Quest>> {
+    parse_quests_with_warnings(json).map(|(quests, _)| quests)
+}
+
+/// 解析悬赏任务数据，并返回数据版本升级产生的警告
+pub fn parse_quests_with_warnings(json: &str) -> WushenResult<(Vec<Quest>, Vec<LoadWarning>)> {
+    parse_quests_with_options(json, ParseOptions::default())
+}
+
+/// 按解析选项解析悬赏任务数据
+pub fn parse_quests_with_options(
+    json: &str,
+    options: ParseOptions,
+) -> WushenResult<(Vec<Quest>, Vec<LoadWarning>)> {
+    parse_collection(json, CollectionKind::Quests, options)
+        .map_err(|e| WushenError::ParseError(format!("解析悬赏任务数据失败: {}", e)))
+}
@@ -0,0 +1,41 @@
+use crate::effect::condition::Condition;
+use crate::event::{ManualKind, Reward};
+/// 悬赏任务类型定义
+use serde::{Deserialize, Serialize};
+
+/// 悬赏任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Quest {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// 接取条件（可选）
+    #[serde(default)]
+    pub condition: Option<Condition>,
+    /// 任务目标（需全部达成）
+    pub objectives: Vec<QuestObjective>,
+    /// 完成奖励
+    #[serde(default)]
+    pub rewards: Vec<Reward>,
+    /// 是否可重复接取
+    #[serde(default)]
+    pub repeatable: bool,
+}
+
+/// 任务目标
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QuestObjective {
+    /// 接取后战斗胜利指定场数
+    WinBattles { count: u32 },
+    /// 功法修炼至指定等级（未指定 ID 时任意同类功法均可）
+    ManualLevel {
+        manual_kind: ManualKind,
+        #[serde(default)]
+        manual_id: Option<String>,
+        level: u32,
+    },
+    /// 拥有指定特性
+    HasTrait { trait_id: String },
+}
@@ -0,0 +1,333 @@
+/// 剧情场景测试
+/// 以 JSON 描述"开始剧情线 X、选择选项 A、期望获得特性 Y、期望进入剧情阶段"等步骤，
+/// 在引擎上逐步执行并报告断言失败及当时的存档状态，用于在编辑器内校验剧情包
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+use crate::event::Reward;
+use crate::game::{GameOutcome, GamePhase, GameResponse, NewGameRequest, ThreeDimensionalState};
+use crate::tauri_api::{ContentStore, WushenCore};
+
+/// 场景
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    pub steps: Vec<ScenarioStep>,
+}
+
+/// 场景步骤：操作或断言
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum ScenarioStep {
+    /// 开始剧情线
+    Start {
+        storyline_id: String,
+        #[serde(default = "default_name")]
+        name: String,
+        #[serde(default = "default_three_d")]
+        three_d: ThreeDimensionalState,
+    },
+    Cultivate {
+        manual_id: String,
+        manual_type: String,
+    },
+    EquipManual {
+        manual_id: String,
+        manual_type: String,
+    },
+    Travel,
+    StoryOption {
+        option_id: String,
+    },
+    StoryBattle,
+    StoryContinue,
+    StoryCheck,
+    AdventureOption {
+        option_id: String,
+    },
+    AdventureSkip,
+    Finish,
+    /// 期望当前阶段
+    ExpectPhase {
+        phase: GamePhase,
+    },
+    /// 期望当前剧情事件
+    ExpectEvent {
+        event_id: String,
+    },
+    /// 期望角色拥有（或不拥有）特性
+    ExpectTrait {
+        trait_id: String,
+        #[serde(default = "default_true")]
+        present: bool,
+    },
+    /// 期望角色拥有功法
+    ExpectManual {
+        manual_id: String,
+    },
+    /// 期望上一步的奖励中包含特性
+    ExpectRewardTrait {
+        trait_id: String,
+    },
+    /// 期望上一步战斗的胜负
+    ExpectBattleWin {
+        win: bool,
+    },
+}
+
+fn default_name() -> String {
+    "测试角色".to_string()
+}
+
+fn default_three_d() -> ThreeDimensionalState {
+    ThreeDimensionalState {
+        comprehension: 10,
+        bone_structure: 10,
+        physique: 10,
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 断言失败
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioFailure {
+    /// 步骤序号（从 0 开始）
+    pub step_index: usize,
+    pub message: String,
+    /// 失败时的存档状态
+    pub state: Option<Value>,
+}
+
+/// 场景执行报告
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioReport {
+    pub name: String,
+    pub passed: bool,
+    /// 已执行的步骤数
+    pub steps_run: usize,
+    pub failures: Vec<ScenarioFailure>,
+}
+
+/// 解析场景文件（单个场景或场景数组）
+pub fn parse_scenarios(json: &str) -> WushenResult<Vec<Scenario>> {
+    let value: Value = serde_json::from_str(json)
+        .map_err(|e| WushenError::ParseError(format!("解析场景文件失败: {}", e)))?;
+    let scenarios = match value {
+        Value::Array(_) => serde_json::from_value(value),
+        _ => serde_json::from_value(value).map(|scenario| vec![scenario]),
+    };
+    scenarios.map_err(|e| WushenError::ParseError(format!("解析场景失败: {}", e)))
+}
+
+/// 在独立的游戏会话中执行场景
+///
+/// 操作失败时终止执行；断言失败时记录后继续
+pub fn run_scenario(content: Arc<ContentStore>, scenario: &Scenario) -> ScenarioReport {
+    let mut runner = Runner {
+        core: WushenCore::with_content(content),
+        last: None,
+        failures: Vec::new(),
+    };
+    let mut steps_run = 0;
+    for (index, step) in scenario.steps.iter().enumerate() {
+        steps_run += 1;
+        if let Err(message) = runner.step(step) {
+            let action_failed = runner.fail(index, message);
+            if action_failed {
+                break;
+            }
+        }
+    }
+    ScenarioReport {
+        name: scenario.name.clone(),
+        passed: runner.failures.is_empty(),
+        steps_run,
+        failures: runner.failures,
+    }
+}
+
+struct Runner {
+    core: WushenCore,
+    last: Option<GameResponse>,
+    failures: Vec<ScenarioFailure>,
+}
+
+impl Runner {
+    /// 记录失败，返回是否为操作失败（需终止）
+    fn fail(&mut self, step_index: usize, failure: StepError) -> bool {
+        let (message, is_action) = match failure {
+            StepError::Action(message) => (message, true),
+            StepError::Assertion(message) => (message, false),
+        };
+        self.failures.push(ScenarioFailure {
+            step_index,
+            message,
+            state: self
+                .core
+                .current_save()
+                .and_then(|save| serde_json::to_value(save).ok()),
+        });
+        is_action
+    }
+
+    fn step(&mut self, step: &ScenarioStep) -> Result<(), StepError> {
+        let core = &mut self.core;
+        let response = match step {
+            ScenarioStep::Start {
+                storyline_id,
+                name,
+                three_d,
+            } => core.game_start_new(NewGameRequest {
+                storyline_id: storyline_id.clone(),
+                character_id: "scenario".to_string(),
+                name: name.clone(),
+                three_d: three_d.clone(),
+                post_battle_recovery: None,
+                defeat_policy: None,
+                analytics: false,
+                challenge: None,
+                appearance: None,
+            }),
+            ScenarioStep::Cultivate {
+                manual_id,
+                manual_type,
+            } => core.game_cultivate(manual_id.clone(), manual_type.clone()),
+            ScenarioStep::EquipManual {
+                manual_id,
+                manual_type,
+            } => core.game_equip_manual(manual_id.clone(), manual_type.clone()),
+            ScenarioStep::Travel => core.game_travel(None, None),
+            ScenarioStep::StoryOption { option_id } => core.game_story_option(option_id.clone()),
+            ScenarioStep::StoryBattle => core.game_story_battle(None, None),
+            ScenarioStep::StoryContinue => core.game_story_continue(),
+            ScenarioStep::StoryCheck => core.game_story_check(),
+            ScenarioStep::AdventureOption { option_id } => {
+                core.game_adventure_option(option_id.clone(), None, None)
+            }
+            ScenarioStep::AdventureSkip => core.game_adventure_skip(),
+            ScenarioStep::Finish => core.game_finish(),
+            expectation => return self.check(expectation).map_err(StepError::Assertion),
+        };
+        self.last = Some(response.map_err(|e| StepError::Action(e.into()))?);
+        Ok(())
+    }
+
+    fn check(&self, expectation: &ScenarioStep) -> Result<(), String> {
+        let last = self
+            .last
+            .as_ref()
+            .ok_or_else(|| "断言前尚未执行任何操作".to_string())?;
+        let character = &last.view.save.current_character;
+        match expectation {
+            ScenarioStep::ExpectPhase { phase } => {
+                if last.view.phase != *phase {
+                    return Err(format!(
+                        "期望阶段 {:?}，实际为 {:?}",
+                        phase, last.view.phase
+                    ));
+                }
+            }
+            ScenarioStep::ExpectEvent { event_id } => {
+                let actual = last
+                    .view
+                    .save
+                    .storyline_progress
+                    .as_ref()
+                    .map(|progress| progress.event_id.as_str());
+                if actual != Some(event_id.as_str()) {
+                    return Err(format!("期望事件 {}，实际为 {:?}", event_id, actual));
+                }
+            }
+            ScenarioStep::ExpectTrait { trait_id, present } => {
+                if character.traits.contains(trait_id) != *present {
+                    return Err(if *present {
+                        format!("期望拥有特性 {}", trait_id)
+                    } else {
+                        format!("期望未拥有特性 {}", trait_id)
+                    });
+                }
+            }
+            ScenarioStep::ExpectManual { manual_id } => {
+                let owned = [
+                    &character.internals,
+                    &character.attack_skills,
+                    &character.defense_skills,
+                ]
+                .iter()
+                .any(|manuals| manuals.owned.iter().any(|m| &m.id == manual_id));
+                if !owned {
+                    return Err(format!("期望拥有功法 {}", manual_id));
+                }
+            }
+            ScenarioStep::ExpectRewardTrait { trait_id } => {
+                let rewarded = outcome_rewards(last.outcome.as_ref())
+                    .iter()
+                    .any(|reward| matches!(reward, Reward::Trait { id } if id == trait_id));
+                if !rewarded {
+                    return Err(format!("期望奖励中包含特性 {}", trait_id));
+                }
+            }
+            ScenarioStep::ExpectBattleWin { win } => {
+                let actual = match last.outcome.as_ref() {
+                    Some(GameOutcome::Story { win, .. })
+                    | Some(GameOutcome::Adventure { win, .. }) => *win,
+                    _ => None,
+                };
+                if actual != Some(*win) {
+                    return Err(format!("期望战斗胜负为 {}，实际为 {:?}", win, actual));
+                }
+            }
+            _ => unreachable!("操作步骤不在此处理"),
+        }
+        Ok(())
+    }
+}
+
+enum StepError {
+    Action(String),
+    Assertion(String),
+}
+
+fn outcome_rewards(outcome: Option<&GameOutcome>) -> &[Reward] {
+    match outcome {
+        Some(GameOutcome::Story { rewards, .. })
+        | Some(GameOutcome::Adventure { rewards, .. })
+        | Some(GameOutcome::Quest { rewards, .. }) => rewards,
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::core_with_storyline;
+
+    #[test]
+    fn runs_steps_and_reports_failures_with_state() {
+        let core = core_with_storyline();
+        let scenarios = parse_scenarios(
+            r#"{"name":"开端到终局","steps":[
+                {"step":"start","storyline_id":"s"},
+                {"step":"expect_event","event_id":"start"},
+                {"step":"story_continue"},
+                {"step":"expect_event","event_id":"end"},
+                {"step":"expect_trait","trait_id":"missing"},
+                {"step":"story_option","option_id":"none"},
+                {"step":"expect_event","event_id":"end"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let report = run_scenario(core.content(), &scenarios[0]);
+        assert!(!report.passed);
+        assert_eq!(report.steps_run, 6);
+        assert_eq!(report.failures.len(), 2);
+        assert_eq!(report.failures[0].step_index, 4);
+        assert!(report.failures[1].state.is_some());
+    }
+}
@@ -0,0 +1,379 @@
+/// 本地 HTTP/JSON-RPC 服务（`server` 特性）
+/// 通过 `POST /rpc` 暴露与桌面端相同的核心接口，供浏览器客户端或自动化工具调用
+///
+/// 基于 axum；核心加锁后在阻塞线程池中执行，同一时刻只处理一个指令，仅用于本地开发与测试。
+/// 跨域只放行本机来源（或配置的来源），请求体有大小上限，读取请求体与处理均有超时。
+/// CORS 只阻止浏览器读取响应，因此执行指令前还会校验请求：
+/// `Origin` 不在放行范围内或 `Host` 不是本机（或配置的）主机名时返回 403（防止跨站请求与 DNS 重绑定），
+/// `Content-Type` 必须为 `application/json`（浏览器跨域发送时必须先预检）
+pub mod rpc;
+
+use std::io;
+use std::net::{TcpListener, ToSocketAddrs};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use axum::extract::{DefaultBodyLimit, State};
+use axum::http::header::{CONTENT_TYPE, HOST, ORIGIN};
+use axum::http::{HeaderMap, HeaderValue, Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tower_http::timeout::{RequestBodyTimeoutLayer, TimeoutLayer};
+
+use crate::tauri_api::WushenCore;
+
+/// 服务配置
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// 允许跨域访问的来源（如 `http://localhost:3000`）；为空时只放行本机来源的任意端口
+    pub allowed_origins: Vec<String>,
+    /// 除本机名称外允许的 `Host` 主机名（不含端口，如通过局域网地址访问时）
+    pub allowed_hosts: Vec<String>,
+    /// 请求体上限（字节）
+    pub max_body_bytes: usize,
+    /// 单个请求的超时（读取请求体与处理分别计时）
+    pub request_timeout: Duration,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_hosts: Vec::new(),
+            max_body_bytes: 8 * 1024 * 1024,
+            request_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+type SharedCore = Arc<Mutex<WushenCore>>;
+
+#[derive(Clone)]
+struct AppState {
+    core: SharedCore,
+    access: Arc<AccessPolicy>,
+}
+
+/// 执行指令前的请求校验规则
+struct AccessPolicy {
+    allowed_origins: Vec<String>,
+    allowed_hosts: Vec<String>,
+}
+
+impl AccessPolicy {
+    fn check(&self, headers: &HeaderMap) -> Result<(), StatusCode> {
+        if let Some(origin) = headers.get(ORIGIN) {
+            if !origin_allowed(origin, &self.allowed_origins) {
+                return Err(StatusCode::FORBIDDEN);
+            }
+        }
+        let host = headers.get(HOST).and_then(|host| host.to_str().ok());
+        if !host.is_some_and(|host| self.host_allowed(host)) {
+            return Err(StatusCode::FORBIDDEN);
+        }
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(str::trim);
+        if !content_type.is_some_and(|mime| mime.eq_ignore_ascii_case("application/json")) {
+            return Err(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        }
+        Ok(())
+    }
+
+    fn host_allowed(&self, host: &str) -> bool {
+        let name = host_name(host);
+        is_loopback_name(name)
+            || self
+                .allowed_hosts
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(name))
+    }
+}
+
+/// 绑定地址并以默认配置开始服务（阻塞）
+pub fn serve(addr: impl ToSocketAddrs, core: WushenCore) -> io::Result<()> {
+    serve_with_config(addr, core, ServerConfig::default())
+}
+
+/// 绑定地址并开始服务（阻塞）
+pub fn serve_with_config(
+    addr: impl ToSocketAddrs,
+    core: WushenCore,
+    config: ServerConfig,
+) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    serve_listener(listener, core, config)
+}
+
+/// 在已绑定的监听器上服务（阻塞）；连接级错误由 HTTP 层处理，只有监听失败会返回
+pub fn serve_listener(
+    listener: TcpListener,
+    core: WushenCore,
+    config: ServerConfig,
+) -> io::Result<()> {
+    listener.set_nonblocking(true)?;
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .enable_time()
+        .build()?;
+    runtime.block_on(async move {
+        let listener = tokio::net::TcpListener::from_std(listener)?;
+        axum::serve(listener, router(core, &config)).await
+    })
+}
+
+fn router(core: WushenCore, config: &ServerConfig) -> Router {
+    let state = AppState {
+        core: Arc::new(Mutex::new(core)),
+        access: Arc::new(AccessPolicy {
+            allowed_origins: config.allowed_origins.clone(),
+            allowed_hosts: config.allowed_hosts.clone(),
+        }),
+    };
+    Router::new()
+        .route("/rpc", post(handle_rpc))
+        .with_state(state)
+        .layer(DefaultBodyLimit::max(config.max_body_bytes))
+        .layer(RequestBodyTimeoutLayer::new(config.request_timeout))
+        .layer(TimeoutLayer::with_status_code(
+            StatusCode::REQUEST_TIMEOUT,
+            config.request_timeout,
+        ))
+        .layer(cors_layer(&config.allowed_origins))
+}
+
+async fn handle_rpc(State(state): State<AppState>, headers: HeaderMap, body: String) -> Response {
+    if let Err(status) = state.access.check(&headers) {
+        return status.into_response();
+    }
+    let core = state.core;
+    let response = tokio::task::spawn_blocking(move || {
+        let mut core = core.lock().unwrap_or_else(PoisonError::into_inner);
+        rpc::handle_json(&mut core, &body)
+    })
+    .await;
+    match response {
+        Ok(body) => ([(CONTENT_TYPE, "application/json; charset=utf-8")], body).into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+fn cors_layer(allowed_origins: &[String]) -> CorsLayer {
+    let allowed_origins = allowed_origins.to_vec();
+    let allow_origin =
+        AllowOrigin::predicate(move |origin, _| origin_allowed(origin, &allowed_origins));
+    CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods([Method::POST])
+        .allow_headers([CONTENT_TYPE])
+}
+
+/// 来源是否放行：未配置来源时只放行本机来源，否则只放行配置的来源
+fn origin_allowed(origin: &HeaderValue, allowed_origins: &[String]) -> bool {
+    if allowed_origins.is_empty() {
+        is_local_origin(origin)
+    } else {
+        allowed_origins
+            .iter()
+            .any(|allowed| origin.as_bytes() == allowed.as_bytes())
+    }
+}
+
+/// 是否为本机来源（localhost、127.0.0.1、[::1]，任意端口）
+fn is_local_origin(origin: &HeaderValue) -> bool {
+    origin
+        .to_str()
+        .ok()
+        .and_then(|origin| {
+            origin
+                .strip_prefix("http://")
+                .or_else(|| origin.strip_prefix("https://"))
+        })
+        .is_some_and(|rest| is_loopback_name(host_name(rest)))
+}
+
+/// 去掉端口后的主机名（`[::1]:8080` -> `::1`）
+fn host_name(host: &str) -> &str {
+    match host.strip_prefix('[') {
+        Some(rest) => rest.split_once(']').map_or("", |(host, _)| host),
+        None => host.split(':').next().unwrap_or_default(),
+    }
+}
+
+fn is_loopback_name(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost") || matches!(host, "127.0.0.1" | "::1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    const LIST_TRAITS: &str = r#"{"jsonrpc":"2.0","id":1,"method":"core_list_traits"}"#;
+    const LOAD_TRAITS: &str = r#"{"jsonrpc":"2.0","id":2,"method":"core_load_traits",
+        "params":{"json":"[{\"id\":\"铁骨\",\"name\":\"铁骨\",\"description\":\"\",\"entries\":[]}]"}}"#;
+
+    /// 发送 POST /rpc；headers 为附加请求头（每行一个）
+    fn post(addr: &str, headers: &[String], body: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let mut request = String::from("POST /rpc HTTP/1.1\r\n");
+        for header in headers {
+            request.push_str(header);
+            request.push_str("\r\n");
+        }
+        request.push_str(&format!(
+            "Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        ));
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response.to_ascii_lowercase()
+    }
+
+    fn json_headers(addr: &str, origin: Option<&str>) -> Vec<String> {
+        let mut headers = vec![
+            format!("Host: {}", addr),
+            "Content-Type: application/json".to_string(),
+        ];
+        headers.extend(origin.map(|origin| format!("Origin: {}", origin)));
+        headers
+    }
+
+    fn spawn_server(config: ServerConfig) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        std::thread::spawn(move || serve_listener(listener, WushenCore::new(), config));
+        addr
+    }
+
+    #[test]
+    fn only_local_origins_are_allowed_by_default() {
+        let local = |origin: &str| is_local_origin(&HeaderValue::from_str(origin).unwrap());
+        assert!(local("http://localhost:3000"));
+        assert!(local("http://127.0.0.1"));
+        assert!(local("https://[::1]:8080"));
+        assert!(!local("http://localhost.evil.com"));
+        assert!(!local("https://example.com"));
+        assert!(!local("null"));
+    }
+
+    #[test]
+    fn serves_rpc_with_cors_and_body_limit() {
+        let addr = spawn_server(ServerConfig {
+            max_body_bytes: 1024,
+            ..ServerConfig::default()
+        });
+
+        let response = post(
+            &addr,
+            &json_headers(&addr, Some("http://localhost:3000")),
+            LIST_TRAITS,
+        );
+        assert!(response.starts_with("http/1.1 200"), "{}", response);
+        assert!(response.contains("access-control-allow-origin: http://localhost:3000"));
+        assert!(response.contains(r#""result":[]"#));
+
+        // 非浏览器客户端不带 Origin
+        let response = post(&addr, &json_headers(&addr, None), LIST_TRAITS);
+        assert!(response.starts_with("http/1.1 200"), "{}", response);
+
+        let response = post(
+            &addr,
+            &json_headers(&addr, Some("http://localhost")),
+            &"x".repeat(2048),
+        );
+        assert!(response.starts_with("http/1.1 413"), "{}", response);
+    }
+
+    #[test]
+    fn rejects_cross_site_requests_before_running_them() {
+        let addr = spawn_server(ServerConfig::default());
+
+        // 外站来源
+        let response = post(
+            &addr,
+            &json_headers(&addr, Some("https://example.com")),
+            LOAD_TRAITS,
+        );
+        assert!(response.starts_with("http/1.1 403"), "{}", response);
+        assert!(!response.contains("access-control-allow-origin"));
+
+        // DNS 重绑定：来源与 Host 都是外站域名
+        let rebinding = vec![
+            "Host: evil.example:7878".to_string(),
+            "Content-Type: application/json".to_string(),
+        ];
+        let response = post(&addr, &rebinding, LOAD_TRAITS);
+        assert!(response.starts_with("http/1.1 403"), "{}", response);
+
+        // 无需预检的简单请求
+        let simple = vec![
+            format!("Host: {}", addr),
+            "Origin: https://example.com".to_string(),
+            "Content-Type: text/plain".to_string(),
+        ];
+        let response = post(&addr, &simple, LOAD_TRAITS);
+        assert!(response.starts_with("http/1.1 403"), "{}", response);
+        let simple_local = vec![
+            format!("Host: {}", addr),
+            "Content-Type: text/plain".to_string(),
+        ];
+        let response = post(&addr, &simple_local, LOAD_TRAITS);
+        assert!(response.starts_with("http/1.1 415"), "{}", response);
+
+        // 被拒绝的请求没有执行
+        let response = post(&addr, &json_headers(&addr, None), LIST_TRAITS);
+        assert!(response.contains(r#""result":[]"#), "{}", response);
+
+        let response = post(&addr, &json_headers(&addr, None), LOAD_TRAITS);
+        assert!(response.starts_with("http/1.1 200"), "{}", response);
+        let response = post(&addr, &json_headers(&addr, None), LIST_TRAITS);
+        assert!(response.contains("铁骨"), "{}", response);
+    }
+
+    #[test]
+    fn configured_hosts_and_origins_are_allowed() {
+        let access = AccessPolicy {
+            allowed_origins: vec!["http://dev.example:3000".to_string()],
+            allowed_hosts: vec!["wushen.lan".to_string()],
+        };
+        let headers = |pairs: &[(axum::http::HeaderName, &str)]| {
+            let mut map = HeaderMap::new();
+            for (name, value) in pairs {
+                map.insert(name.clone(), HeaderValue::from_str(value).unwrap());
+            }
+            map
+        };
+        let json = (CONTENT_TYPE, "application/json; charset=utf-8");
+
+        assert_eq!(
+            access.check(&headers(&[(HOST, "wushen.lan:7878"), json.clone()])),
+            Ok(())
+        );
+        assert_eq!(
+            access.check(&headers(&[
+                (HOST, "[::1]:7878"),
+                (ORIGIN, "http://dev.example:3000"),
+                json.clone()
+            ])),
+            Ok(())
+        );
+        // 配置来源后不再默认放行本机来源
+        assert_eq!(
+            access.check(&headers(&[
+                (HOST, "localhost"),
+                (ORIGIN, "http://localhost:3000"),
+                json.clone()
+            ])),
+            Err(StatusCode::FORBIDDEN)
+        );
+        assert_eq!(access.check(&headers(&[json])), Err(StatusCode::FORBIDDEN));
+    }
+}
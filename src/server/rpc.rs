@@ -0,0 +1,435 @@
+/// JSON-RPC 2.0 分发
+/// 方法名与参数名与桌面端 `core_*` 命令保持一致（参数为 camelCase 对象）
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::WushenError;
+use crate::game::{NewGameRequest, SaveGame};
+use crate::simulation::CancellationToken;
+use crate::tauri_api::WushenCore;
+
+/// 请求解析失败
+pub const PARSE_ERROR: i64 = -32700;
+/// 方法不存在
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// 参数错误
+pub const INVALID_PARAMS: i64 = -32602;
+/// 核心接口返回错误
+pub const APPLICATION_ERROR: i64 = -32000;
+
+/// JSON-RPC 请求
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcRequest {
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+    #[serde(default)]
+    pub id: Value,
+}
+
+/// JSON-RPC 响应
+#[derive(Debug, Clone, Serialize)]
+pub struct RpcResponse {
+    pub jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+    pub id: Value,
+}
+
+/// JSON-RPC 错误
+#[derive(Debug, Clone, Serialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    /// 核心接口错误的分类（`{"code": "not_found", "message": "..."}`）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<WushenError>,
+}
+
+impl RpcResponse {
+    fn ok(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            result: Some(result),
+            error: None,
+            id,
+        }
+    }
+
+    fn err(id: Value, code: i64, message: String) -> Self {
+        Self::error(
+            id,
+            RpcError {
+                code,
+                message,
+                data: None,
+            },
+        )
+    }
+
+    fn error(id: Value, error: RpcError) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            result: None,
+            error: Some(error),
+            id,
+        }
+    }
+}
+
+/// 处理一条 JSON 文本请求，返回 JSON 文本响应
+pub fn handle_json(core: &mut WushenCore, body: &str) -> String {
+    let response = match serde_json::from_str::<RpcRequest>(body) {
+        Ok(request) => dispatch(core, request),
+        Err(e) => RpcResponse::err(Value::Null, PARSE_ERROR, format!("解析请求失败: {}", e)),
+    };
+    serde_json::to_string(&response).unwrap_or_else(|e| {
+        format!(
+            r#"{{"jsonrpc":"2.0","error":{{"code":{},"message":"序列化响应失败: {}"}},"id":null}}"#,
+            APPLICATION_ERROR, e
+        )
+    })
+}
+
+/// 分发请求到核心接口
+pub fn dispatch(core: &mut WushenCore, request: RpcRequest) -> RpcResponse {
+    match call(core, &request.method, &request.params) {
+        Ok(result) => RpcResponse::ok(request.id, result),
+        Err(CallError::MethodNotFound) => RpcResponse::err(
+            request.id,
+            METHOD_NOT_FOUND,
+            format!("方法 {} 不存在", request.method),
+        ),
+        Err(CallError::InvalidParams(message)) => {
+            RpcResponse::err(request.id, INVALID_PARAMS, message)
+        }
+        Err(CallError::Failed(error)) => RpcResponse::error(
+            request.id,
+            RpcError {
+                code: APPLICATION_ERROR,
+                message: error.message().to_string(),
+                data: Some(error),
+            },
+        ),
+    }
+}
+
+enum CallError {
+    MethodNotFound,
+    InvalidParams(String),
+    Failed(WushenError),
+}
+
+impl From<WushenError> for CallError {
+    fn from(error: WushenError) -> Self {
+        CallError::Failed(error)
+    }
+}
+
+type CallResult = Result<Value, CallError>;
+
+fn call(core: &mut WushenCore, method: &str, params: &Value) -> CallResult {
+    let p = Params(params);
+    match method {
+        "core_reset" => {
+            core.reset();
+            Ok(Value::Null)
+        }
+        "core_load_traits" => game(core.load_traits(&p.string("json")?)),
+        "core_content_fingerprint" => json(core.content_fingerprint()),
+        "core_load_content_bundle" => game(Ok::<_, WushenError>(
+            core.load_content_bundle(p.value("bundle")?),
+        )),
+        "core_load_internals" => game(core.load_internals(&p.string("json")?)),
+        "core_load_attack_skills" => game(core.load_attack_skills(&p.string("json")?)),
+        "core_load_defense_skills" => game(core.load_defense_skills(&p.string("json")?)),
+        "core_load_storylines" => game(core.load_storylines(&p.string("json")?)),
+        "core_load_adventure_events" => game(core.load_adventure_events(&p.string("json")?)),
+        "core_load_quests" => game(core.load_quests(&p.string("json")?)),
+        "core_load_items" => game(core.load_items(&p.string("json")?)),
+        "core_load_recipes" => game(core.load_recipes(&p.string("json")?)),
+        "core_load_enemies" => game(core.load_enemies(&p.string("json")?)),
+        "core_set_battle_config" => unit(core.set_battle_config(&p.string("json")?)),
+        "core_set_disabled_entry_tags" => {
+            core.set_disabled_entry_tags(p.value("tags")?);
+            Ok(Value::Null)
+        }
+        "core_get_trait" => json(core.get_trait(&p.string("id")?)),
+        "core_list_traits" => json(core.list_traits()),
+        "core_get_internal" => json(core.get_internal(&p.string("id")?)),
+        "core_list_internals" => json(core.list_internals()),
+        "core_list_internals_filtered" => json(
+            core.list_internals_filtered(p.opt_string("tag")?.as_deref(), p.opt_u32("rarity")?),
+        ),
+        "core_get_attack_skill" => json(core.get_attack_skill(&p.string("id")?)),
+        "core_list_attack_skills" => json(core.list_attack_skills()),
+        "core_list_attack_skills_filtered" => json(
+            core.list_attack_skills_filtered(p.opt_string("tag")?.as_deref(), p.opt_u32("rarity")?),
+        ),
+        "core_get_defense_skill" => json(core.get_defense_skill(&p.string("id")?)),
+        "core_list_defense_skills" => json(core.list_defense_skills()),
+        "core_list_defense_skills_filtered" => {
+            json(core.list_defense_skills_filtered(
+                p.opt_string("tag")?.as_deref(),
+                p.opt_u32("rarity")?,
+            ))
+        }
+        "core_list_storylines" => json(core.list_storylines()),
+        "core_list_storyline_stats" => json(core.list_storyline_stats()),
+        "core_get_storyline" => json(core.get_storyline(&p.string("id")?)),
+        "core_list_adventure_events" => json(core.list_adventure_events()),
+        "core_get_adventure_event" => json(core.get_adventure_event(&p.string("id")?)),
+        "core_list_quests" => json(core.list_quests()),
+        "core_validation_report" => json(core.validation_report()),
+        "core_calculate_cultivation_exp" => {
+            let exp = core.calculate_cultivation_exp(
+                &p.string("manualId")?,
+                &p.string("manualType")?,
+                p.f64("x")?,
+                p.f64("y")?,
+                p.f64("z")?,
+                p.f64("a")?,
+            )?;
+            Ok(Value::from(exp))
+        }
+        "core_formula_variable_catalog" => json(core.formula_variable_catalog(&p.string("kind")?)),
+        "core_calculate_battle" => json(core.calculate_battle(
+            &p.string("attackerJson")?,
+            &p.string("defenderJson")?,
+            p.opt_f64("attackerQiOutputRate")?,
+            p.opt_f64("defenderQiOutputRate")?,
+        )),
+        "core_capture_battle_replay" => json(core.capture_battle_replay(
+            &p.string("attackerJson")?,
+            &p.string("defenderJson")?,
+            p.opt_f64("attackerQiOutputRate")?,
+            p.opt_f64("defenderQiOutputRate")?,
+        )),
+        "core_replay_battle" => json(core.replay_battle(&p.string("replayJson")?)),
+        "core_preview_exchange" => json(core.preview_exchange(
+            &p.string("attackerJson")?,
+            &p.string("defenderJson")?,
+            p.opt_f64("attackerQiOutputRate")?,
+            p.opt_f64("defenderQiOutputRate")?,
+        )),
+        "core_test_battle_against_enemy" => json(core.test_battle_against_enemy(
+            &p.string("characterJson")?,
+            &p.string("enemy")?,
+            p.opt_value("options")?.unwrap_or_default(),
+        )),
+        "core_preview_trait" => json(core.preview_trait(
+            &p.string("traitJson")?,
+            &p.string("characterJson")?,
+            p.opt_string("dummyJson")?.as_deref(),
+        )),
+        "core_dry_run_entry" => json(core.dry_run_entry(&p.string("requestJson")?)),
+        "core_simulate_cultivation_sweep" => {
+            json(core.simulate_cultivation_sweep(&p.string("requestJson")?))
+        }
+        "core_simulate_battles" => json(core.simulate_battles(
+            &p.string("matchupsJson")?,
+            |_| {},
+            &CancellationToken::new(),
+        )),
+        "core_run_scenarios" => json(core.run_scenarios(&p.string("scenariosJson")?)),
+        "core_execute_cultivation" => json(core.execute_cultivation(
+            &p.string("characterJson")?,
+            &p.string("manualId")?,
+            &p.string("manualType")?,
+        )),
+        "core_game_start_new" => game(core.game_start_new(p.value::<NewGameRequest>("request")?)),
+        // 服务端不读写存档文件，由客户端直接提交存档内容
+        "core_game_check_save" => game(Ok::<_, WushenError>(
+            core.game_check_save(&p.value::<SaveGame>("save")?),
+        )),
+        "core_game_resume" => {
+            let mut save = p.value::<SaveGame>("save")?;
+            if p.opt_value("repair")?.unwrap_or(false) {
+                core.game_repair_save(&mut save);
+            }
+            game(core.game_resume_segmented(save, p.opt_value("segments")?.unwrap_or_default()))
+        }
+        "core_game_segment" => json(core.game_segment(p.string("kind")?)),
+        "core_game_take_dirty_segments" => game(core.game_take_dirty_segments()),
+        "core_game_view" => game(core.game_view(None)),
+        "core_game_view_diff" => game(core.game_view_diff(p.opt_value("sinceRevision")?)),
+        "core_game_cultivate" => {
+            game(core.game_cultivate(p.string("manualId")?, p.string("manualType")?))
+        }
+        "core_game_travel" => game(core.game_travel(
+            p.opt_f64("attackerQiOutputRate")?,
+            p.opt_f64("defenderQiOutputRate")?,
+        )),
+        "core_game_story_option" => game(core.game_story_option(p.string("optionId")?)),
+        "core_game_equip_title" => game(core.game_equip_title(p.opt_string("titleId")?)),
+        "core_game_use_item" => game(core.game_use_item(p.string("itemId")?)),
+        "core_game_craft" => game(core.game_craft(p.string("recipeId")?)),
+        "core_game_titles" => json(core.game_titles()),
+        "core_game_rewards_preview" => json(core.game_rewards_preview(&p.string("rewardsJson")?)),
+        "core_game_codex" => json(core.game_codex(p.opt_string("kind")?)),
+        "core_game_adventure_collection" => {
+            json(core.game_adventure_collection(p.opt_string("global")?))
+        }
+        "core_game_equip_manual" => {
+            game(core.game_equip_manual(p.string("manualId")?, p.string("manualType")?))
+        }
+        "core_game_story_battle" => game(core.game_story_battle(
+            p.opt_f64("attackerQiOutputRate")?,
+            p.opt_f64("defenderQiOutputRate")?,
+        )),
+        "core_game_story_battle_manual" => game(core.game_story_battle_manual(
+            p.opt_f64("attackerQiOutputRate")?,
+            p.opt_f64("defenderQiOutputRate")?,
+        )),
+        "core_battle_session_step" => game(core.battle_session_step()),
+        "core_battle_session_set_qi_output" => {
+            game(core.battle_session_set_qi_output(p.f64("rate")?))
+        }
+        "core_game_story_continue" => game(core.game_story_continue()),
+        "core_game_story_check" => game(core.game_story_check()),
+        "core_game_adventure_option" => game(core.game_adventure_option(
+            p.string("optionId")?,
+            p.opt_f64("attackerQiOutputRate")?,
+            p.opt_f64("defenderQiOutputRate")?,
+        )),
+        "core_game_adventure_skip" => game(core.game_adventure_skip()),
+        "core_game_recruit_member" => game(core.game_recruit_member(
+            p.string("characterId")?,
+            p.string("name")?,
+            p.value("threeD")?,
+        )),
+        "core_game_switch_character" => game(core.game_switch_character(p.string("characterId")?)),
+        "core_game_adjust_resource" => {
+            game(core.game_adjust_resource(p.string("resourceId")?, p.value("delta")?))
+        }
+        "core_game_assign_training" => {
+            game(core.game_assign_training(p.string("characterId")?, p.opt_value("assignment")?))
+        }
+        "core_game_accept_quest" => game(core.game_accept_quest(p.string("questId")?)),
+        "core_game_abandon_quest" => game(core.game_abandon_quest(p.string("questId")?)),
+        "core_game_claim_quest" => game(core.game_claim_quest(p.string("questId")?)),
+        "core_game_set_analytics" => game(core.game_set_analytics(p.value("enabled")?)),
+        "core_game_set_battle_archive" => {
+            game(core.game_set_battle_archive(p.opt_value("policy")?))
+        }
+        "core_game_get_archived_battle" => json(core.get_archived_battle(&p.string("id")?)),
+        "core_game_analytics_report" => json(core.game_analytics_report()),
+        "core_game_finish" => game(core.game_finish()),
+        #[cfg(feature = "god-mode")]
+        "core_debug_command" => game(core.debug_execute(p.value("command")?)),
+        "core_list_completed_characters" => json(core.list_completed_characters()),
+        "core_get_completed_character" => {
+            json(core.get_completed_character(p.value::<usize>("index")?))
+        }
+        "core_export_completed_character" => {
+            json(core.export_completed_character(p.value::<usize>("index")?))
+        }
+        "core_game_export_character_build" => json(core.game_export_character_build()),
+        "core_import_character_build" => json(core.import_character_build(&p.string("json")?)),
+        "core_game_delete_completed_character" => {
+            game(core.game_delete_completed_character(p.value::<usize>("index")?))
+        }
+        _ => Err(CallError::MethodNotFound),
+    }
+}
+
+fn unit<E: Into<WushenError>>(result: Result<(), E>) -> CallResult {
+    result.map_err(Into::into)?;
+    Ok(Value::Null)
+}
+
+/// 核心接口返回 JSON 文本，解析后作为结果（避免二次转义）
+fn json<E: Into<WushenError>>(result: Result<String, E>) -> CallResult {
+    let text = result.map_err(Into::into)?;
+    serde_json::from_str(&text)
+        .map_err(|e| CallError::Failed(WushenError::ParseError(format!("解析结果失败: {}", e))))
+}
+
+fn game<T: Serialize, E: Into<WushenError>>(result: Result<T, E>) -> CallResult {
+    let response = result.map_err(Into::into)?;
+    serde_json::to_value(response)
+        .map_err(|e| CallError::Failed(WushenError::ParseError(format!("序列化结果失败: {}", e))))
+}
+
+/// 参数读取
+struct Params<'a>(&'a Value);
+
+impl Params<'_> {
+    fn get(&self, name: &str) -> Option<&Value> {
+        self.0.get(name).filter(|value| !value.is_null())
+    }
+
+    fn value<T: serde::de::DeserializeOwned>(&self, name: &str) -> Result<T, CallError> {
+        let value = self
+            .get(name)
+            .ok_or_else(|| CallError::InvalidParams(format!("缺少参数 {}", name)))?;
+        serde_json::from_value(value.clone())
+            .map_err(|e| CallError::InvalidParams(format!("参数 {} 无效: {}", name, e)))
+    }
+
+    fn opt_value<T: serde::de::DeserializeOwned>(
+        &self,
+        name: &str,
+    ) -> Result<Option<T>, CallError> {
+        match self.get(name) {
+            Some(_) => self.value(name).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn string(&self, name: &str) -> Result<String, CallError> {
+        self.value(name)
+    }
+
+    fn opt_string(&self, name: &str) -> Result<Option<String>, CallError> {
+        self.opt_value(name)
+    }
+
+    fn f64(&self, name: &str) -> Result<f64, CallError> {
+        self.value(name)
+    }
+
+    fn opt_f64(&self, name: &str) -> Result<Option<f64>, CallError> {
+        self.opt_value(name)
+    }
+
+    fn opt_u32(&self, name: &str) -> Result<Option<u32>, CallError> {
+        self.opt_value(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dispatch_maps_methods_and_errors() {
+        let mut core = WushenCore::new();
+
+        let response = handle_json(
+            &mut core,
+            r#"{"jsonrpc":"2.0","id":1,"method":"core_list_traits"}"#,
+        );
+        let value: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(value["id"], 1);
+        assert!(value["result"].is_array());
+
+        let response = handle_json(&mut core, r#"{"id":2,"method":"core_nope"}"#);
+        let value: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(value["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = handle_json(&mut core, r#"{"id":3,"method":"core_get_trait"}"#);
+        let value: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(value["error"]["code"], INVALID_PARAMS);
+
+        let response = handle_json(&mut core, r#"{"id":4,"method":"core_game_view"}"#);
+        let value: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(value["error"]["code"], APPLICATION_ERROR);
+
+        let response = handle_json(&mut core, "not json");
+        let value: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(value["error"]["code"], PARSE_ERROR);
+    }
+}
@@ -0,0 +1,137 @@
+/// 多会话管理
+/// 按存档 ID 同时托管多个游戏运行时，所有会话共享同一份内容数据
+use crate::error::{WushenError, WushenResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::game::{GameResponse, NewGameRequest, SaveGame};
+use crate::tauri_api::{ContentStore, WushenCore};
+
+/// 会话句柄（即存档 ID）
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SessionHandle(String);
+
+impl SessionHandle {
+    pub fn new(save_id: impl Into<String>) -> Self {
+        Self(save_id.into())
+    }
+
+    pub fn save_id(&self) -> &str {
+        &self.0
+    }
+}
+
+/// 会话管理器
+pub struct SessionManager {
+    content: Arc<ContentStore>,
+    sessions: HashMap<SessionHandle, WushenCore>,
+}
+
+impl SessionManager {
+    pub fn new(content: Arc<ContentStore>) -> Self {
+        Self {
+            content,
+            sessions: HashMap::new(),
+        }
+    }
+
+    /// 替换内容数据（同时作用于已有会话）
+    pub fn set_content(&mut self, content: Arc<ContentStore>) {
+        for core in self.sessions.values_mut() {
+            core.set_content(Arc::clone(&content));
+        }
+        self.content = content;
+    }
+
+    /// 开始新游戏，返回会话句柄（同一存档已有会话时报错）
+    pub fn start(
+        &mut self,
+        request: NewGameRequest,
+    ) -> WushenResult<(SessionHandle, GameResponse)> {
+        let handle = SessionHandle::new(request.character_id.clone());
+        if self.sessions.contains_key(&handle) {
+            return Err(WushenError::InvalidState(format!(
+                "会话 {} 已存在",
+                handle.save_id()
+            )));
+        }
+        let mut core = WushenCore::with_content(Arc::clone(&self.content));
+        let response = core.game_start_new(request)?;
+        self.sessions.insert(handle.clone(), core);
+        Ok((handle, response))
+    }
+
+    /// 读取存档继续游戏，返回会话句柄（同一存档已有会话时替换）
+    pub fn resume(&mut self, save: SaveGame) -> WushenResult<(SessionHandle, GameResponse)> {
+        let handle = SessionHandle::new(save.id.clone());
+        let mut core = WushenCore::with_content(Arc::clone(&self.content));
+        let response = core.game_resume(save)?;
+        self.sessions.insert(handle.clone(), core);
+        Ok((handle, response))
+    }
+
+    /// 获取会话（只读）
+    pub fn session(&self, handle: &SessionHandle) -> WushenResult<&WushenCore> {
+        self.sessions
+            .get(handle)
+            .ok_or_else(|| WushenError::NotFound(format!("会话 {} 不存在", handle.save_id())))
+    }
+
+    /// 获取会话（可调用 game_* 接口）
+    pub fn session_mut(&mut self, handle: &SessionHandle) -> WushenResult<&mut WushenCore> {
+        self.sessions
+            .get_mut(handle)
+            .ok_or_else(|| WushenError::NotFound(format!("会话 {} 不存在", handle.save_id())))
+    }
+
+    /// 关闭会话，返回其最新存档
+    pub fn close(&mut self, handle: &SessionHandle) -> Option<SaveGame> {
+        self.sessions
+            .remove(handle)
+            .and_then(|core| core.current_save().cloned())
+    }
+
+    /// 全部会话句柄（按存档 ID 排序）
+    pub fn handles(&self) -> Vec<SessionHandle> {
+        let mut handles: Vec<_> = self.sessions.keys().cloned().collect();
+        handles.sort();
+        handles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{core_with_storyline, new_game_request as request};
+
+    fn content() -> Arc<ContentStore> {
+        core_with_storyline().content()
+    }
+
+    #[test]
+    fn sessions_are_independent() {
+        let mut manager = SessionManager::new(content());
+        let (a, _) = manager.start(request("a")).unwrap();
+        let (b, _) = manager.start(request("b")).unwrap();
+        assert_eq!(
+            manager.start(request("a")).unwrap_err().code(),
+            "invalid_state"
+        );
+        assert_eq!(manager.handles(), vec![a.clone(), b.clone()]);
+
+        let save = manager.close(&a).unwrap();
+        assert_eq!(save.current_character.name, "a");
+        assert_eq!(manager.session(&a).err().unwrap().code(), "not_found");
+        assert!(manager.session_mut(&b).unwrap().current_save().is_some());
+
+        let (resumed, _) = manager.resume(save).unwrap();
+        assert_eq!(resumed, a);
+
+        // 核心接口的错误码原样透出
+        let mut missing = request("c");
+        missing.storyline_id = "missing".to_string();
+        assert_eq!(manager.start(missing).unwrap_err().code(), "not_found");
+    }
+}
@@ -0,0 +1,132 @@
+/// 批量模拟：进度回报与协作式取消
+/// 长时间的平衡性测试可通过进度回调显示进度条，并通过取消令牌中途停止
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+/// 取消令牌（可跨线程共享，取消后模拟在当前一场结束后停止）
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 请求取消
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// 是否已请求取消
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// 模拟进度
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SimulationProgress {
+    pub completed: usize,
+    pub total: usize,
+    /// 已用时间（毫秒）
+    pub elapsed_ms: u64,
+    /// 预计剩余时间（毫秒，尚无完成项时为 None）
+    pub eta_ms: Option<u64>,
+}
+
+/// 进度追踪器：按已完成数量与已用时间估算剩余时间
+#[derive(Debug)]
+pub struct ProgressTracker {
+    started_at: Instant,
+    total: usize,
+}
+
+impl ProgressTracker {
+    pub fn new(total: usize) -> Self {
+        Self {
+            started_at: Instant::now(),
+            total,
+        }
+    }
+
+    /// 生成当前进度
+    pub fn progress(&self, completed: usize) -> SimulationProgress {
+        let elapsed_ms = self.started_at.elapsed().as_millis() as u64;
+        SimulationProgress {
+            completed,
+            total: self.total,
+            elapsed_ms,
+            eta_ms: estimate_eta(elapsed_ms, completed, self.total),
+        }
+    }
+}
+
+fn estimate_eta(elapsed_ms: u64, completed: usize, total: usize) -> Option<u64> {
+    if completed == 0 {
+        return None;
+    }
+    let remaining = total.saturating_sub(completed) as u64;
+    Some(elapsed_ms * remaining / completed as u64)
+}
+
+/// 单场模拟对局
+#[derive(Debug, Clone, Deserialize)]
+pub struct BattleMatchup {
+    /// 攻击方角色面板 JSON
+    pub attacker: serde_json::Value,
+    /// 防御方角色面板 JSON
+    pub defender: serde_json::Value,
+    #[serde(default)]
+    pub attacker_qi_output_rate: Option<f64>,
+    #[serde(default)]
+    pub defender_qi_output_rate: Option<f64>,
+}
+
+/// 批量战斗模拟报告
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BattleSimulationReport {
+    pub total: usize,
+    pub completed: usize,
+    /// 是否被中途取消
+    pub cancelled: bool,
+    pub attacker_wins: usize,
+    pub defender_wins: usize,
+    pub draws: usize,
+    /// 各场结果（attacker_win / defender_win / draw），按对局顺序
+    pub results: Vec<String>,
+}
+
+impl BattleSimulationReport {
+    /// 记录一场结果
+    pub fn record(&mut self, result: &str) {
+        match result {
+            "attacker_win" => self.attacker_wins += 1,
+            "defender_win" => self.defender_wins += 1,
+            _ => self.draws += 1,
+        }
+        self.completed += 1;
+        self.results.push(result.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eta_scales_with_remaining_work() {
+        assert_eq!(estimate_eta(1000, 0, 10), None);
+        assert_eq!(estimate_eta(1000, 2, 10), Some(4000));
+        assert_eq!(estimate_eta(1000, 10, 10), Some(0));
+
+        let token = CancellationToken::new();
+        let shared = token.clone();
+        shared.cancel();
+        assert!(token.is_cancelled());
+    }
+}
@@ -1,42 +1,235 @@
+use crate::battle::battle_calculator::{BattleCalculationResult, BattleCalculator};
+use crate::battle::battle_config::{AttributeClamp, BaseQiPool, BattleConfig, FirstStrikeRule};
 use crate::battle::battle_engine::BattleEngine;
-use crate::battle::battle_record::{BattleLogKind, BattleRecord, PanelDelta};
-use crate::battle::battle_state::BattleResult;
+use crate::battle::battle_interrupt::{BattleInterrupt, InterruptTrigger};
+use crate::battle::battle_phase::{BattlePhase, PhaseAttackSkill, PhaseDefenseSkill};
+use crate::battle::battle_record::{BattleLogKind, BattleRecord, LogPolicy, PanelDelta};
+use crate::battle::battle_replay::{BattleProvenance, BattleReplay};
+use crate::battle::battle_roster::EnemyOutcome;
+use crate::battle::battle_setup::{MissingManualPolicy, UnresolvedManual};
+use crate::battle::battle_state::{BattleResult, Side};
+use crate::battle::battle_training::{TrainingMode, TrainingScore};
 use crate::character::json::{parse_character_panel, serialize_character_panel};
 use crate::character::panel::{CharacterPanel, ThreeDimensional};
 use crate::character::trait_manager::TraitManager;
-use crate::character::traits::parse_traits;
+use crate::character::traits::parse_traits_with_warnings;
 use crate::cultivation::manual_manager::ManualManager;
-use crate::cultivation::parser::{parse_attack_skills, parse_defense_skills, parse_internals};
+use crate::cultivation::parser::{
+    parse_attack_skills_with_warnings, parse_defense_skills_with_warnings,
+    parse_internals_with_warnings,
+};
 use crate::cultivation::{AttackSkill, DefenseSkill, Internal};
 use crate::effect::condition::CultivationContext;
-use crate::effect::executor::EntryExecutor;
+use crate::effect::entry::Entry;
+use crate::effect::executor::{EntryExecutor, SkippedEntry};
+use crate::effect::formula::{FormulaCalculator, FormulaContextKind};
 use crate::effect::trigger::Trigger;
-use crate::event::reward::count_available_manuals;
+use crate::error::{WushenError, WushenResult};
+use crate::event::cost::apply_costs;
+use crate::event::manager::validate_environment;
+use crate::event::parser::{
+    parse_adventure_events_with_warnings, parse_enemies_with_warnings,
+    parse_storylines_with_warnings,
+};
+use crate::event::reward::{apply_rewards_preview, count_available_manuals};
 use crate::event::{
-    parse_adventure_events, parse_storylines, AdventureEventContent, AdventureOptionResult,
-    EventManager, Reward, StoryEvent, StoryEventContent, StoryNodeType, Storyline,
+    AdventureEventContent, AdventureOptionResult, BattleHandicap, BattleInterruption, BattleSide,
+    CheckTier, ConditionLinter, DefeatPolicy, DuplicateRewardConfig, EchoEncounter, EnemyManager,
+    EnemyTemplate, EventManager, InterruptCondition, OptionCost, PostBattleRecovery,
+    ResolvedRewards, Reward, RunHistory, StoryEvent, StoryEventContent, StoryNodeType, Storyline,
 };
 use crate::game::{
-    now_timestamp, seed_from_time, AdventureDecisionView, AdventureOptionView, CharacterState,
-    GameOutcome, GamePhase, GameResponse, GameRuntime, GameView, NewGameRequest, SaveGame,
-    SimpleRng, StoryEventContentView, StoryEventSummary, StoryEventView, StoryHistoryRecord,
-    StoryHistoryScope, StoryOptionView, StorylineProgress, StorylineSummary,
+    now_timestamp, seed_from_time, ActionCostConfig, ActionKind, AdventureDecisionView,
+    AdventureDiscovery, AdventureOptionView, BattleArchivePolicy, BattleRecovery,
+    BlockedOptionView, CalendarConfig, ChallengeRuleset, ChapterView, CharacterBuild,
+    CharacterState, Chronicle, ChronicleBattle, ChronicleBeat, Codex, CodexEntryView, CodexKind,
+    GameOutcome, GamePhase, GameResponse, GameRuntime, GameView, GameViewDiff, NewGameRequest,
+    Party, QuestObjectiveView, QuestProgress, QuestStatus, QuestView, SaveAnalytics, SaveGame,
+    SaveSegments, SegmentKind, SimpleRng, StoryEventContentView, StoryEventSummary, StoryEventView,
+    StoryHistoryRecord, StoryHistoryScope, StoryOptionView, StorylineProgress, StorylineSummary,
+    ThreeDimensionalState, TimedBuff, Title, DEFAULT_CHRONICLE_TEMPLATE,
+};
+use crate::item::parser::{parse_items_with_warnings, parse_recipes_with_warnings};
+use crate::item::ItemManager;
+use crate::pack::{CollectionKind, ContentFingerprint, LoadWarning};
+use crate::quest::parser::parse_quests_with_warnings;
+use crate::quest::QuestManager;
+use crate::scenario::{parse_scenarios, run_scenario, ScenarioReport};
+use crate::simulation::{
+    BattleMatchup, BattleSimulationReport, CancellationToken, ProgressTracker, SimulationProgress,
 };
 /// Tauri API 模块
 /// 提供桌面端可调用的API接口
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::{HashMap, HashSet};
-
-/// 核心运行状态
-/// 存储特性和功法数据（从JSON加载）
-pub struct WushenCore {
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+
+mod battle_session;
+mod bundle;
+mod cultivation_sweep;
+mod entry_dry_run;
+#[cfg(feature = "god-mode")]
+mod god_mode;
+mod save_check;
+mod training;
+mod trait_preview;
+mod transaction;
+
+pub use battle_session::{BattleSessionSide, BattleSessionUpdate, BattleSessionView};
+pub use bundle::{ContentBundle, ContentBundleReport};
+pub use cultivation_sweep::{
+    CultivationSweepReport, CultivationSweepRequest, SweepCell, SweepManual, MAX_SWEEP_CELLS,
+};
+pub use entry_dry_run::EntryDryRunRequest;
+#[cfg(feature = "god-mode")]
+pub use god_mode::DebugCommand;
+pub use save_check::SaveProblem;
+pub use trait_preview::{BattlePreview, CultivationPreview, TraitPreview};
+
+/// 往昔之影在剧情历史中的事件 ID 前缀
+const ECHO_EVENT_PREFIX: &str = "echo:";
+
+/// 内容数据
+/// 特性、功法、事件、任务与战斗配置，加载后只读，可通过 `Arc` 跨线程共享
+#[derive(Clone, Default)]
+pub struct ContentStore {
     trait_manager: TraitManager,
     manual_manager: ManualManager,
     event_manager: EventManager,
+    quest_manager: QuestManager,
+    enemy_manager: EnemyManager,
+    item_manager: ItemManager,
+    battle_config: BattleConfig,
+    /// 禁用的词条标签（带有其中任一标签的词条在战斗与修行中不生效）
+    disabled_entry_tags: BTreeSet<String>,
+    /// 加载数据时产生的警告（旧版数据格式升级、可疑数值与自动修正）
+    load_warnings: Vec<LoadWarning>,
+}
+
+impl ContentStore {
+    pub fn trait_manager(&self) -> &TraitManager {
+        &self.trait_manager
+    }
+
+    pub fn manual_manager(&self) -> &ManualManager {
+        &self.manual_manager
+    }
+
+    pub fn event_manager(&self) -> &EventManager {
+        &self.event_manager
+    }
+
+    pub fn quest_manager(&self) -> &QuestManager {
+        &self.quest_manager
+    }
+
+    pub fn enemy_manager(&self) -> &EnemyManager {
+        &self.enemy_manager
+    }
+
+    pub fn item_manager(&self) -> &ItemManager {
+        &self.item_manager
+    }
+
+    pub fn battle_config(&self) -> &BattleConfig {
+        &self.battle_config
+    }
+
+    /// 剧情与奇遇的条件检查结果
+    fn lints(&self) -> Vec<String> {
+        let linter = ConditionLinter::new(&self.manual_manager, &self.trait_manager);
+        let mut lints = Vec::new();
+        for storyline in self.event_manager.all_storylines() {
+            lints.extend(linter.lint_storyline(storyline));
+        }
+        for event in self.event_manager.all_adventure_events() {
+            lints.extend(linter.lint_adventure_event(event));
+        }
+        lints.extend(linter.lint_environment(
+            &self.event_manager.all_storylines(),
+            &self.event_manager.all_adventure_events(),
+        ));
+        lints.extend(linter.lint_formulas());
+        lints.sort();
+        lints
+    }
+
+    /// 已加载内容的指纹
+    pub fn fingerprint(&self) -> ContentFingerprint {
+        let mut fingerprint = ContentFingerprint::default();
+        let traits = self.trait_manager.all_traits();
+        fingerprint.add_collection(
+            CollectionKind::Traits,
+            traits.iter().map(|t| (t.id.as_str(), *t)),
+        );
+        let manuals = &self.manual_manager;
+        fingerprint.add_collection(
+            CollectionKind::Internals,
+            manuals.all_internals().map(|m| (m.manual.id.as_str(), m)),
+        );
+        fingerprint.add_collection(
+            CollectionKind::AttackSkills,
+            manuals
+                .all_attack_skills()
+                .map(|m| (m.manual.id.as_str(), m)),
+        );
+        fingerprint.add_collection(
+            CollectionKind::DefenseSkills,
+            manuals
+                .all_defense_skills()
+                .map(|m| (m.manual.id.as_str(), m)),
+        );
+        let storylines = self.event_manager.all_storylines();
+        fingerprint.add_collection(
+            CollectionKind::Storylines,
+            storylines.iter().map(|s| (s.id.as_str(), *s)),
+        );
+        let adventures = self.event_manager.all_adventure_events();
+        fingerprint.add_collection(
+            CollectionKind::Adventures,
+            adventures.iter().map(|e| (e.id.as_str(), *e)),
+        );
+        let quests = self.quest_manager.all_quests();
+        fingerprint.add_collection(
+            CollectionKind::Quests,
+            quests.iter().map(|q| (q.id.as_str(), *q)),
+        );
+        fingerprint.add_collection(
+            CollectionKind::Enemies,
+            self.enemy_manager
+                .all_enemies()
+                .into_iter()
+                .map(|(id, enemy)| (id.as_str(), enemy)),
+        );
+        let items = self.item_manager.all_items();
+        fingerprint.add_collection(
+            CollectionKind::Items,
+            items.iter().map(|i| (i.id.as_str(), *i)),
+        );
+        let recipes = self.item_manager.all_recipes();
+        fingerprint.add_collection(
+            CollectionKind::Recipes,
+            recipes.iter().map(|r| (r.id.as_str(), *r)),
+        );
+        fingerprint
+    }
+}
+
+/// 核心运行状态
+/// 内容数据以 `Arc` 共享（写时复制），游戏运行时按实例独立持有
+pub struct WushenCore {
+    content: Arc<ContentStore>,
     game_runtime: Option<GameRuntime>,
 }
 
+// 内容数据与核心实例需可跨线程共享（桌面端命令在多个线程上执行）
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ContentStore>();
+    assert_send_sync::<WushenCore>();
+};
+
 impl Default for WushenCore {
     fn default() -> Self {
         Self::new()
@@ -47,90 +240,250 @@ impl WushenCore {
     /// 创建新的核心实例
     pub fn new() -> Self {
         Self {
-            trait_manager: TraitManager::new(),
-            manual_manager: ManualManager::new(),
-            event_manager: EventManager::new(),
+            content: Arc::new(ContentStore::default()),
+            game_runtime: None,
+        }
+    }
+
+    /// 从共享内容数据创建核心实例（不含游戏运行时）
+    pub fn with_content(content: Arc<ContentStore>) -> Self {
+        Self {
+            content,
             game_runtime: None,
         }
     }
 
+    /// 共享的内容数据
+    pub fn content(&self) -> Arc<ContentStore> {
+        Arc::clone(&self.content)
+    }
+
+    /// 只读快照：共享当前内容数据、不含游戏运行时
+    ///
+    /// 列表、查询、战斗计算等只读接口可在快照上执行，无需占用持有游戏会话的实例
+    pub fn snapshot(&self) -> Self {
+        Self::with_content(self.content())
+    }
+
+    /// 替换内容数据（保留游戏运行时）
+    pub fn set_content(&mut self, content: Arc<ContentStore>) {
+        self.content = content;
+    }
+
+    /// 当前游戏存档（游戏尚未开始时为 None）
+    pub fn current_save(&self) -> Option<&SaveGame> {
+        self.game_runtime.as_ref().map(|runtime| &runtime.save)
+    }
+
+    /// 可写的内容数据（若仍被快照共享，则先复制一份）
+    fn content_mut(&mut self) -> &mut ContentStore {
+        Arc::make_mut(&mut self.content)
+    }
+
     /// 重置核心状态
     pub fn reset(&mut self) {
-        self.trait_manager = TraitManager::new();
-        self.manual_manager = ManualManager::new();
-        self.event_manager = EventManager::new();
+        self.content = Arc::new(ContentStore::default());
         self.game_runtime = None;
     }
 
-    /// 从JSON加载特性数据
-    pub fn load_traits(&mut self, json: &str) -> Result<(), String> {
-        let traits = parse_traits(json).map_err(|e| format!("解析特性数据失败: {}", e))?;
-        self.trait_manager.load_traits(traits);
+    /// 从JSON设置战斗配置（最大轮数、平局判定规则）
+    pub fn set_battle_config(&mut self, json: &str) -> WushenResult<()> {
+        self.content_mut().battle_config = serde_json::from_str(json)
+            .map_err(|e| WushenError::ParseError(format!("解析战斗配置失败: {}", e)))?;
         Ok(())
     }
 
+    /// 设置禁用的词条标签（整个内容包生效，用于挑战玩法或排查数据包间的词条交互）
+    pub fn set_disabled_entry_tags(&mut self, tags: Vec<String>) {
+        self.content_mut().disabled_entry_tags = tags.into_iter().collect();
+    }
+
+    /// 从JSON加载特性数据
+    pub fn load_traits(&mut self, json: &str) -> WushenResult<Vec<LoadWarning>> {
+        let (traits, warnings) =
+            parse_traits_with_warnings(json).map_err(|e| e.context("解析特性数据失败"))?;
+        let content = self.content_mut();
+        content.load_warnings.extend(warnings.iter().cloned());
+        content.trait_manager.load_traits(traits);
+        Ok(warnings)
+    }
+
     /// 从JSON加载内功数据
-    pub fn load_internals(&mut self, json: &str) -> Result<(), String> {
-        let internals = parse_internals(json).map_err(|e| format!("解析内功数据失败: {}", e))?;
-        self.manual_manager.load_internals(internals);
-        Ok(())
+    pub fn load_internals(&mut self, json: &str) -> WushenResult<Vec<LoadWarning>> {
+        let (internals, warnings) =
+            parse_internals_with_warnings(json).map_err(|e| e.context("解析内功数据失败"))?;
+        let content = self.content_mut();
+        content.load_warnings.extend(warnings.iter().cloned());
+        content.manual_manager.load_internals(internals);
+        Ok(warnings)
     }
 
     /// 从JSON加载攻击武技数据
-    pub fn load_attack_skills(&mut self, json: &str) -> Result<(), String> {
-        let skills =
-            parse_attack_skills(json).map_err(|e| format!("解析攻击武技数据失败: {}", e))?;
-        self.manual_manager.load_attack_skills(skills);
-        Ok(())
+    pub fn load_attack_skills(&mut self, json: &str) -> WushenResult<Vec<LoadWarning>> {
+        let (skills, warnings) = parse_attack_skills_with_warnings(json)
+            .map_err(|e| e.context("解析攻击武技数据失败"))?;
+        let content = self.content_mut();
+        content.load_warnings.extend(warnings.iter().cloned());
+        content.manual_manager.load_attack_skills(skills);
+        Ok(warnings)
     }
 
     /// 从JSON加载防御武技数据
-    pub fn load_defense_skills(&mut self, json: &str) -> Result<(), String> {
-        let skills =
-            parse_defense_skills(json).map_err(|e| format!("解析防御武技数据失败: {}", e))?;
-        self.manual_manager.load_defense_skills(skills);
-        Ok(())
+    pub fn load_defense_skills(&mut self, json: &str) -> WushenResult<Vec<LoadWarning>> {
+        let (skills, warnings) = parse_defense_skills_with_warnings(json)
+            .map_err(|e| e.context("解析防御武技数据失败"))?;
+        let content = self.content_mut();
+        content.load_warnings.extend(warnings.iter().cloned());
+        content.manual_manager.load_defense_skills(skills);
+        Ok(warnings)
+    }
+
+    /// 从JSON加载敌人库（需在剧情线与奇遇之前加载，引用敌人库的战斗才会被替换）
+    pub fn load_enemies(&mut self, json: &str) -> WushenResult<Vec<LoadWarning>> {
+        let (enemies, warnings) =
+            parse_enemies_with_warnings(json).map_err(|e| e.context("解析敌人数据失败"))?;
+        let content = self.content_mut();
+        content
+            .enemy_manager
+            .load_enemies(enemies)
+            .map_err(|e| e.context("敌人数据校验失败"))?;
+        content.load_warnings.extend(warnings.iter().cloned());
+        Ok(warnings)
     }
 
     /// 从JSON加载剧情线数据
-    pub fn load_storylines(&mut self, json: &str) -> Result<(), String> {
-        let storylines =
-            parse_storylines(json).map_err(|e| format!("解析剧情线数据失败: {}", e))?;
+    pub fn load_storylines(&mut self, json: &str) -> WushenResult<Vec<LoadWarning>> {
+        let (mut storylines, mut warnings) =
+            parse_storylines_with_warnings(json).map_err(|e| e.context("解析剧情线数据失败"))?;
+        warnings.extend(
+            self.content
+                .enemy_manager
+                .resolve_storylines(&mut storylines),
+        );
         for storyline in &storylines {
-            EventManager::validate_storyline(storyline)
-                .map_err(|e| format!("剧情线校验失败: {}", e))?;
+            EventManager::validate_storyline(storyline).map_err(|e| e.context("剧情线校验失败"))?;
         }
-        self.event_manager.load_storylines(storylines);
-        Ok(())
+        let content = self.content_mut();
+        content.load_warnings.extend(warnings.iter().cloned());
+        content.event_manager.load_storylines(storylines);
+        Ok(warnings)
     }
 
     /// 从JSON加载奇遇事件数据
-    pub fn load_adventure_events(&mut self, json: &str) -> Result<(), String> {
-        let adventures =
-            parse_adventure_events(json).map_err(|e| format!("解析奇遇事件数据失败: {}", e))?;
+    pub fn load_adventure_events(&mut self, json: &str) -> WushenResult<Vec<LoadWarning>> {
+        let (mut adventures, mut warnings) = parse_adventure_events_with_warnings(json)
+            .map_err(|e| e.context("解析奇遇事件数据失败"))?;
+        warnings.extend(
+            self.content
+                .enemy_manager
+                .resolve_adventures(&mut adventures),
+        );
         for event in &adventures {
             EventManager::validate_adventure_event(event)
-                .map_err(|e| format!("奇遇事件校验失败: {}", e))?;
+                .map_err(|e| e.context("奇遇事件校验失败"))?;
         }
-        self.event_manager.load_adventure_events(adventures);
-        Ok(())
+        let content = self.content_mut();
+        content.load_warnings.extend(warnings.iter().cloned());
+        content.event_manager.load_adventure_events(adventures);
+        Ok(warnings)
+    }
+
+    /// 从JSON加载悬赏任务数据
+    pub fn load_quests(&mut self, json: &str) -> WushenResult<Vec<LoadWarning>> {
+        let (quests, warnings) =
+            parse_quests_with_warnings(json).map_err(|e| e.context("解析悬赏任务数据失败"))?;
+        for quest in &quests {
+            QuestManager::validate_quest(quest).map_err(|e| e.context("悬赏任务校验失败"))?;
+        }
+        let content = self.content_mut();
+        content.load_warnings.extend(warnings.iter().cloned());
+        content.quest_manager.load_quests(quests);
+        Ok(warnings)
+    }
+
+    /// 从JSON加载物品数据
+    pub fn load_items(&mut self, json: &str) -> WushenResult<Vec<LoadWarning>> {
+        let (items, warnings) =
+            parse_items_with_warnings(json).map_err(|e| e.context("解析物品数据失败"))?;
+        for item in &items {
+            item.validate().map_err(|e| e.context("物品校验失败"))?;
+        }
+        let content = self.content_mut();
+        content.load_warnings.extend(warnings.iter().cloned());
+        content.item_manager.load_items(items);
+        Ok(warnings)
+    }
+
+    /// 从JSON加载炼制配方（材料与成品须为已加载的物品）
+    pub fn load_recipes(&mut self, json: &str) -> WushenResult<Vec<LoadWarning>> {
+        let (recipes, warnings) =
+            parse_recipes_with_warnings(json).map_err(|e| e.context("解析炼制配方数据失败"))?;
+        for recipe in &recipes {
+            recipe
+                .validate_with_items(&self.content.item_manager)
+                .map_err(|e| e.context("炼制配方校验失败"))?;
+        }
+        let content = self.content_mut();
+        content.load_warnings.extend(warnings.iter().cloned());
+        content.item_manager.load_recipes(recipes);
+        Ok(warnings)
+    }
+
+    /// 获取已加载数据的校验报告（返回JSON字符串）
+    pub fn validation_report(&self) -> WushenResult<String> {
+        let mut errors: Vec<String> = Vec::new();
+        for storyline in self.content.event_manager.all_storylines() {
+            if let Err(e) = EventManager::validate_storyline(storyline) {
+                errors.push(e.into());
+            }
+        }
+        for event in self.content.event_manager.all_adventure_events() {
+            if let Err(e) = EventManager::validate_adventure_event(event) {
+                errors.push(e.into());
+            }
+        }
+        for quest in self.content.quest_manager.all_quests() {
+            if let Err(e) = QuestManager::validate_quest(quest) {
+                errors.push(e.into());
+            }
+        }
+        for item in self.content.item_manager.all_items() {
+            if let Err(e) = item.validate() {
+                errors.push(e.into());
+            }
+        }
+        for recipe in self.content.item_manager.all_recipes() {
+            if let Err(e) = recipe.validate_with_items(&self.content.item_manager) {
+                errors.push(e.into());
+            }
+        }
+        errors.sort();
+        let report = ValidationReport {
+            errors,
+            warnings: self.content.load_warnings.clone(),
+            lints: self.content.lints(),
+        };
+        serde_json::to_string(&report)
+            .map_err(|e| WushenError::ParseError(format!("序列化校验报告失败: {}", e)))
     }
 
     /// 获取特性（返回JSON字符串）
-    pub fn get_trait(&self, id: &str) -> Result<String, String> {
+    pub fn get_trait(&self, id: &str) -> WushenResult<String> {
         let trait_ = self
+            .content
             .trait_manager
             .get_trait(id)
-            .ok_or_else(|| format!("特性 {} 不存在", id))?;
+            .ok_or_else(|| WushenError::NotFound(format!("特性 {} 不存在", id)))?;
 
-        let json = serde_json::to_string(trait_).map_err(|e| format!("序列化特性失败: {}", e))?;
+        let json = serde_json::to_string(trait_)
+            .map_err(|e| WushenError::ParseError(format!("序列化特性失败: {}", e)))?;
 
         Ok(json)
     }
 
     /// 列出所有特性（返回JSON数组，包含id和name）
-    pub fn list_traits(&self) -> Result<String, String> {
-        let traits = self.trait_manager.all_traits();
+    pub fn list_traits(&self) -> WushenResult<String> {
+        let traits = self.content.trait_manager.all_traits();
         let list: Vec<_> = traits
             .iter()
             .map(|t| TraitListItem {
@@ -139,18 +492,19 @@ impl WushenCore {
             })
             .collect();
 
-        let json =
-            serde_json::to_string(&list).map_err(|e| format!("序列化特性列表失败: {}", e))?;
+        let json = serde_json::to_string(&list)
+            .map_err(|e| WushenError::ParseError(format!("序列化特性列表失败: {}", e)))?;
 
         Ok(json)
     }
 
     /// 获取内功（返回JSON字符串）
-    pub fn get_internal(&self, id: &str) -> Result<String, String> {
+    pub fn get_internal(&self, id: &str) -> WushenResult<String> {
         let internal = self
+            .content
             .manual_manager
             .get_internal(id)
-            .ok_or_else(|| format!("内功 {} 不存在", id))?;
+            .ok_or_else(|| WushenError::NotFound(format!("内功 {} 不存在", id)))?;
 
         // 序列化为JSON（需要手动构建，因为Internal没有Serialize）
         let json = internal_to_json(internal);
@@ -158,81 +512,117 @@ impl WushenCore {
     }
 
     /// 列出所有内功（返回JSON数组，包含id和name）
-    pub fn list_internals(&self) -> Result<String, String> {
+    pub fn list_internals(&self) -> WushenResult<String> {
+        self.list_internals_filtered(None, None)
+    }
+
+    /// 按标签/稀有度筛选内功列表（返回JSON数组，包含id和name）
+    pub fn list_internals_filtered(
+        &self,
+        tag: Option<&str>,
+        rarity: Option<u32>,
+    ) -> WushenResult<String> {
         let list: Vec<_> = self
+            .content
             .manual_manager
             .all_internals()
+            .filter(|i| i.manual.matches_filter(tag, rarity))
             .map(|i| ManualListItem {
                 id: i.manual.id.clone(),
                 name: i.manual.name.clone(),
             })
             .collect();
 
-        let json =
-            serde_json::to_string(&list).map_err(|e| format!("序列化内功列表失败: {}", e))?;
+        let json = serde_json::to_string(&list)
+            .map_err(|e| WushenError::ParseError(format!("序列化内功列表失败: {}", e)))?;
 
         Ok(json)
     }
 
     /// 获取攻击武技（返回JSON字符串）
-    pub fn get_attack_skill(&self, id: &str) -> Result<String, String> {
+    pub fn get_attack_skill(&self, id: &str) -> WushenResult<String> {
         let skill = self
+            .content
             .manual_manager
             .get_attack_skill(id)
-            .ok_or_else(|| format!("攻击武技 {} 不存在", id))?;
+            .ok_or_else(|| WushenError::NotFound(format!("攻击武技 {} 不存在", id)))?;
 
         let json = attack_skill_to_json(skill);
         Ok(json)
     }
 
     /// 列出所有攻击武技（返回JSON数组，包含id和name）
-    pub fn list_attack_skills(&self) -> Result<String, String> {
+    pub fn list_attack_skills(&self) -> WushenResult<String> {
+        self.list_attack_skills_filtered(None, None)
+    }
+
+    /// 按标签/稀有度筛选攻击武技列表（返回JSON数组，包含id和name）
+    pub fn list_attack_skills_filtered(
+        &self,
+        tag: Option<&str>,
+        rarity: Option<u32>,
+    ) -> WushenResult<String> {
         let list: Vec<_> = self
+            .content
             .manual_manager
             .all_attack_skills()
+            .filter(|s| s.manual.matches_filter(tag, rarity))
             .map(|s| ManualListItem {
                 id: s.manual.id.clone(),
                 name: s.manual.name.clone(),
             })
             .collect();
 
-        let json =
-            serde_json::to_string(&list).map_err(|e| format!("序列化攻击武技列表失败: {}", e))?;
+        let json = serde_json::to_string(&list)
+            .map_err(|e| WushenError::ParseError(format!("序列化攻击武技列表失败: {}", e)))?;
 
         Ok(json)
     }
 
     /// 获取防御武技（返回JSON字符串）
-    pub fn get_defense_skill(&self, id: &str) -> Result<String, String> {
+    pub fn get_defense_skill(&self, id: &str) -> WushenResult<String> {
         let skill = self
+            .content
             .manual_manager
             .get_defense_skill(id)
-            .ok_or_else(|| format!("防御武技 {} 不存在", id))?;
+            .ok_or_else(|| WushenError::NotFound(format!("防御武技 {} 不存在", id)))?;
 
         let json = defense_skill_to_json(skill);
         Ok(json)
     }
 
     /// 列出所有防御武技（返回JSON数组，包含id和name）
-    pub fn list_defense_skills(&self) -> Result<String, String> {
+    pub fn list_defense_skills(&self) -> WushenResult<String> {
+        self.list_defense_skills_filtered(None, None)
+    }
+
+    /// 按标签/稀有度筛选防御武技列表（返回JSON数组，包含id和name）
+    pub fn list_defense_skills_filtered(
+        &self,
+        tag: Option<&str>,
+        rarity: Option<u32>,
+    ) -> WushenResult<String> {
         let list: Vec<_> = self
+            .content
             .manual_manager
             .all_defense_skills()
+            .filter(|s| s.manual.matches_filter(tag, rarity))
             .map(|s| ManualListItem {
                 id: s.manual.id.clone(),
                 name: s.manual.name.clone(),
             })
             .collect();
 
-        let json =
-            serde_json::to_string(&list).map_err(|e| format!("序列化防御武技列表失败: {}", e))?;
+        let json = serde_json::to_string(&list)
+            .map_err(|e| WushenError::ParseError(format!("序列化防御武技列表失败: {}", e)))?;
 
         Ok(json)
     }
 
     /// 列出所有剧情线（返回JSON数组，包含id和name）
-    pub fn list_storylines(&self) -> Result<String, String> {
+    pub fn list_storylines(&self) -> WushenResult<String> {
         let list: Vec<_> = self
+            .content
             .event_manager
             .all_storylines()
             .iter()
@@ -241,25 +631,40 @@ impl WushenCore {
                 name: s.name.clone(),
             })
             .collect();
-        let json =
-            serde_json::to_string(&list).map_err(|e| format!("序列化剧情线列表失败: {}", e))?;
+        let json = serde_json::to_string(&list)
+            .map_err(|e| WushenError::ParseError(format!("序列化剧情线列表失败: {}", e)))?;
         Ok(json)
     }
 
+    /// 列出所有剧情线的统计（事件、战斗、结局、敌人数量与行动点范围，返回JSON数组）
+    pub fn list_storyline_stats(&self) -> WushenResult<String> {
+        let stats: Vec<_> = self
+            .content
+            .event_manager
+            .all_storylines()
+            .iter()
+            .map(|s| s.stats())
+            .collect();
+        serde_json::to_string(&stats)
+            .map_err(|e| WushenError::ParseError(format!("序列化剧情线统计失败: {}", e)))
+    }
+
     /// 获取剧情线（返回JSON字符串）
-    pub fn get_storyline(&self, id: &str) -> Result<String, String> {
+    pub fn get_storyline(&self, id: &str) -> WushenResult<String> {
         let storyline = self
+            .content
             .event_manager
             .get_storyline(id)
-            .ok_or_else(|| format!("剧情线 {} 不存在", id))?;
-        let json =
-            serde_json::to_string(storyline).map_err(|e| format!("序列化剧情线失败: {}", e))?;
+            .ok_or_else(|| WushenError::NotFound(format!("剧情线 {} 不存在", id)))?;
+        let json = serde_json::to_string(storyline)
+            .map_err(|e| WushenError::ParseError(format!("序列化剧情线失败: {}", e)))?;
         Ok(json)
     }
 
     /// 列出所有奇遇事件（返回JSON数组，包含id和name）
-    pub fn list_adventure_events(&self) -> Result<String, String> {
+    pub fn list_adventure_events(&self) -> WushenResult<String> {
         let list: Vec<_> = self
+            .content
             .event_manager
             .all_adventure_events()
             .iter()
@@ -268,19 +673,38 @@ impl WushenCore {
                 name: e.name.clone(),
             })
             .collect();
-        let json =
-            serde_json::to_string(&list).map_err(|e| format!("序列化奇遇事件列表失败: {}", e))?;
+        let json = serde_json::to_string(&list)
+            .map_err(|e| WushenError::ParseError(format!("序列化奇遇事件列表失败: {}", e)))?;
         Ok(json)
     }
 
     /// 获取奇遇事件（返回JSON字符串）
-    pub fn get_adventure_event(&self, id: &str) -> Result<String, String> {
+    pub fn get_adventure_event(&self, id: &str) -> WushenResult<String> {
         let event = self
+            .content
             .event_manager
             .get_adventure_event(id)
-            .ok_or_else(|| format!("奇遇事件 {} 不存在", id))?;
-        let json =
-            serde_json::to_string(event).map_err(|e| format!("序列化奇遇事件失败: {}", e))?;
+            .ok_or_else(|| WushenError::NotFound(format!("奇遇事件 {} 不存在", id)))?;
+        let json = serde_json::to_string(event)
+            .map_err(|e| WushenError::ParseError(format!("序列化奇遇事件失败: {}", e)))?;
+        Ok(json)
+    }
+
+    /// 列出所有悬赏任务（返回JSON数组，包含id和name）
+    pub fn list_quests(&self) -> WushenResult<String> {
+        let mut list: Vec<_> = self
+            .content
+            .quest_manager
+            .all_quests()
+            .iter()
+            .map(|q| QuestListItem {
+                id: q.id.clone(),
+                name: q.name.clone(),
+            })
+            .collect();
+        list.sort_by(|a, b| a.id.cmp(&b.id));
+        let json = serde_json::to_string(&list)
+            .map_err(|e| WushenError::ParseError(format!("序列化悬赏任务列表失败: {}", e)))?;
         Ok(json)
     }
 
@@ -294,35 +718,58 @@ impl WushenCore {
         y: f64,
         z: f64,
         a: f64,
-    ) -> Result<f64, String> {
+    ) -> WushenResult<f64> {
         let exp = match manual_type {
             "internal" => {
                 let internal = self
+                    .content
                     .manual_manager
                     .get_internal(manual_id)
-                    .ok_or_else(|| format!("内功 {} 不存在", manual_id))?;
+                    .ok_or_else(|| WushenError::NotFound(format!("内功 {} 不存在", manual_id)))?;
                 internal.manual.calculate_exp_gain(x, y, z, a)?
             }
             "attack_skill" => {
                 let skill = self
+                    .content
                     .manual_manager
                     .get_attack_skill(manual_id)
-                    .ok_or_else(|| format!("攻击武技 {} 不存在", manual_id))?;
+                    .ok_or_else(|| {
+                        WushenError::NotFound(format!("攻击武技 {} 不存在", manual_id))
+                    })?;
                 skill.manual.calculate_exp_gain(x, y, z, a)?
             }
             "defense_skill" => {
                 let skill = self
+                    .content
                     .manual_manager
                     .get_defense_skill(manual_id)
-                    .ok_or_else(|| format!("防御武技 {} 不存在", manual_id))?;
+                    .ok_or_else(|| {
+                        WushenError::NotFound(format!("防御武技 {} 不存在", manual_id))
+                    })?;
                 skill.manual.calculate_exp_gain(x, y, z, a)?
             }
-            _ => return Err(format!("未知的功法类型: {}", manual_type)),
+            _ => {
+                return Err(WushenError::Invalid(format!(
+                    "未知的功法类型: {}",
+                    manual_type
+                )))
+            }
         };
 
         Ok(exp)
     }
 
+    /// 列出公式可用的变量（供编辑器补全与校验）
+    /// 参数：公式上下文（manual_exp / cultivation / battle）
+    /// 返回：变量列表JSON（FormulaVariable 数组）
+    pub fn formula_variable_catalog(&self, kind: &str) -> WushenResult<String> {
+        let kind: FormulaContextKind = serde_json::from_value(serde_json::Value::from(kind))
+            .map_err(|_| WushenError::Invalid(format!("未知的公式上下文: {}", kind)))?;
+        let catalog = FormulaCalculator::variable_catalog(kind);
+        serde_json::to_string(&catalog)
+            .map_err(|e| WushenError::ParseError(format!("序列化公式变量失败: {}", e)))
+    }
+
     /// 计算战斗
     /// 参数：攻击者角色JSON，防御者角色JSON，攻击者内息输出（可选），防御者内息输出（可选）
     /// 返回：战斗结果JSON（包含战斗日志和结果）
@@ -335,7 +782,320 @@ impl WushenCore {
         defender_json: &str,
         attacker_qi_output_rate: Option<f64>,
         defender_qi_output_rate: Option<f64>,
-    ) -> Result<String, String> {
+    ) -> WushenResult<String> {
+        self.calculate_battle_with_config(
+            attacker_json,
+            defender_json,
+            attacker_qi_output_rate,
+            defender_qi_output_rate,
+            &self.content.battle_config,
+            BattleScript::default(),
+        )
+    }
+
+    /// 预估双方一次交手的结算结果（不推进战斗、不产生日志）
+    /// 参数：攻击者角色JSON，防御者角色JSON，攻击者内息输出（可选），防御者内息输出（可选）
+    /// 返回：双方互相攻击一次的预估结算JSON（已应用战斗开始词条）
+    pub fn preview_exchange(
+        &self,
+        attacker_json: &str,
+        defender_json: &str,
+        attacker_qi_output_rate: Option<f64>,
+        defender_qi_output_rate: Option<f64>,
+    ) -> WushenResult<String> {
+        let preview = self.exchange_preview(
+            attacker_json,
+            defender_json,
+            attacker_qi_output_rate,
+            defender_qi_output_rate,
+        )?;
+        serde_json::to_string(&preview)
+            .map_err(|e| WushenError::ParseError(format!("序列化交手预估失败: {}", e)))
+    }
+
+    fn exchange_preview(
+        &self,
+        attacker_json: &str,
+        defender_json: &str,
+        attacker_qi_output_rate: Option<f64>,
+        defender_qi_output_rate: Option<f64>,
+    ) -> WushenResult<ExchangePreviewJson> {
+        let config = &self.content.battle_config;
+        let mut battle_engine = self.build_battle_engine(
+            attacker_json,
+            defender_json,
+            attacker_qi_output_rate,
+            defender_qi_output_rate,
+            config,
+        )?;
+        battle_engine.set_log_policy(LogPolicy::None);
+        // 初始化并应用战斗开始词条
+        battle_engine.step();
+        battle_engine.step();
+
+        let side_a = battle_engine.get_side_a_panel();
+        let side_b = battle_engine.get_side_b_panel();
+        Ok(ExchangePreviewJson {
+            attacker_to_defender: BattleCalculator::preview_exchange(
+                side_a,
+                side_b,
+                config.numeric_policy,
+            ),
+            defender_to_attacker: BattleCalculator::preview_exchange(
+                side_b,
+                side_a,
+                config.numeric_policy,
+            ),
+        })
+    }
+
+    /// 以角色对战敌人模板（编辑器"试战"），敌人无需先写入事件或数据包
+    /// 参数：角色JSON，敌人（敌人库中的 ID 或敌人模板JSON），战斗选项
+    /// 返回：与剧情战斗相同的战斗结果JSON（含敌人文本与首领阶段）
+    pub fn test_battle_against_enemy(
+        &self,
+        character_json: &str,
+        enemy: &str,
+        options: EnemyBattleOptions,
+    ) -> WushenResult<String> {
+        let enemy = match serde_json::from_str::<Value>(enemy) {
+            Ok(value @ Value::Object(_)) => {
+                let template: EnemyTemplate = serde_json::from_value(value)
+                    .map_err(|e| WushenError::ParseError(format!("解析敌人模板失败: {}", e)))?;
+                template
+                    .validate()
+                    .map_err(|e| WushenError::Invalid(format!("敌人模板错误: {}", e)))?;
+                template
+            }
+            _ => self
+                .content
+                .enemy_manager
+                .get_enemy(enemy)
+                .cloned()
+                .ok_or_else(|| WushenError::NotFound(format!("敌人 {} 不存在", enemy)))?,
+        };
+        for handicap in &options.handicaps {
+            handicap.validate()?;
+        }
+        validate_environment(&options.environment)?;
+        if let Some(training) = &options.training {
+            training.validate()?;
+        }
+        // 可指定剧情线以使用其独立的战斗规则
+        let config = match &options.storyline_id {
+            Some(id) => self
+                .content
+                .event_manager
+                .get_storyline(id)
+                .ok_or_else(|| WushenError::NotFound(format!("剧情线 {} 不存在", id)))?
+                .battle_config
+                .as_ref()
+                .unwrap_or(&self.content.battle_config),
+            None => &self.content.battle_config,
+        };
+        let enemy_json = serialize_character_panel(&enemy.to_character_panel())?;
+        self.calculate_battle_with_config(
+            character_json,
+            &enemy_json,
+            options.attacker_qi_output_rate,
+            options.defender_qi_output_rate,
+            config,
+            BattleScript {
+                enemy: Some(&enemy),
+                handicaps: &options.handicaps,
+                training: options.training,
+                environment: options.environment.clone(),
+                ..BattleScript::default()
+            },
+        )
+    }
+
+    /// 执行剧情场景测试（每个场景在独立的游戏会话中运行，不影响当前游戏）
+    /// 参数：场景JSON（单个场景或场景数组）
+    /// 返回：各场景的执行报告JSON
+    pub fn run_scenarios(&self, scenarios_json: &str) -> WushenResult<String> {
+        let scenarios = parse_scenarios(scenarios_json)?;
+        let reports: Vec<ScenarioReport> = scenarios
+            .iter()
+            .map(|scenario| run_scenario(self.content(), scenario))
+            .collect();
+        serde_json::to_string(&reports)
+            .map_err(|e| WushenError::ParseError(format!("序列化场景报告失败: {}", e)))
+    }
+
+    /// 批量模拟战斗
+    /// 参数：对局列表JSON（BattleMatchup 数组），进度回调（每场结束后调用），取消令牌
+    /// 返回：模拟报告JSON（取消时为已完成部分的报告）
+    pub fn simulate_battles(
+        &self,
+        matchups_json: &str,
+        mut on_progress: impl FnMut(&SimulationProgress),
+        cancel: &CancellationToken,
+    ) -> WushenResult<String> {
+        let matchups: Vec<BattleMatchup> = serde_json::from_str(matchups_json)
+            .map_err(|e| WushenError::ParseError(format!("解析模拟对局失败: {}", e)))?;
+        let tracker = ProgressTracker::new(matchups.len());
+        let mut report = BattleSimulationReport {
+            total: matchups.len(),
+            ..BattleSimulationReport::default()
+        };
+
+        for matchup in &matchups {
+            if cancel.is_cancelled() {
+                report.cancelled = true;
+                break;
+            }
+            // 模拟只需要胜负，不保留战斗日志
+            let mut battle_engine = self.build_battle_engine(
+                &matchup.attacker.to_string(),
+                &matchup.defender.to_string(),
+                matchup.attacker_qi_output_rate,
+                matchup.defender_qi_output_rate,
+                &self.content.battle_config,
+            )?;
+            battle_engine.set_log_policy(LogPolicy::None);
+            report.record(match battle_engine.run() {
+                BattleResult::SideAWin => "attacker_win",
+                BattleResult::SideBWin => "defender_win",
+                BattleResult::Draw => "draw",
+                BattleResult::Interrupted => "interrupted",
+            });
+            on_progress(&tracker.progress(report.completed));
+        }
+
+        serde_json::to_string(&report)
+            .map_err(|e| WushenError::ParseError(format!("序列化模拟报告失败: {}", e)))
+    }
+
+    /// 记录战斗回放（参数与 [`WushenCore::calculate_battle`] 相同，不执行战斗）
+    /// 返回：战斗回放JSON（BattleReplay），可分享后用 [`WushenCore::replay_battle`] 重看
+    pub fn capture_battle_replay(
+        &self,
+        attacker_json: &str,
+        defender_json: &str,
+        attacker_qi_output_rate: Option<f64>,
+        defender_qi_output_rate: Option<f64>,
+    ) -> WushenResult<String> {
+        let battle_engine = self.prepare_battle(
+            attacker_json,
+            defender_json,
+            attacker_qi_output_rate,
+            defender_qi_output_rate,
+            &self.content.battle_config,
+            BattleScript::default(),
+        )?;
+        battle_engine.capture_replay().to_json()
+    }
+
+    /// 按回放重新执行战斗（结果只取决于回放本身，与当前加载的内容无关）
+    /// 参数：战斗回放JSON
+    /// 返回：与 [`WushenCore::calculate_battle`] 相同格式的战斗结果JSON
+    pub fn replay_battle(&self, replay_json: &str) -> WushenResult<String> {
+        let replay = BattleReplay::from_json(replay_json)?;
+        battle_result_json(BattleEngine::from_replay(&replay))
+    }
+
+    fn calculate_battle_with_config(
+        &self,
+        attacker_json: &str,
+        defender_json: &str,
+        attacker_qi_output_rate: Option<f64>,
+        defender_qi_output_rate: Option<f64>,
+        config: &BattleConfig,
+        script: BattleScript,
+    ) -> WushenResult<String> {
+        let battle_engine = self.prepare_battle(
+            attacker_json,
+            defender_json,
+            attacker_qi_output_rate,
+            defender_qi_output_rate,
+            config,
+            script,
+        )?;
+        battle_result_json(battle_engine)
+    }
+
+    /// 构建战斗引擎并装配战斗脚本（敌人文本与阶段、打断、额外词条与让子）
+    fn prepare_battle(
+        &self,
+        attacker_json: &str,
+        defender_json: &str,
+        attacker_qi_output_rate: Option<f64>,
+        defender_qi_output_rate: Option<f64>,
+        config: &BattleConfig,
+        script: BattleScript,
+    ) -> WushenResult<BattleEngine> {
+        let mut battle_engine = self.build_battle_engine(
+            attacker_json,
+            defender_json,
+            attacker_qi_output_rate,
+            defender_qi_output_rate,
+            config,
+        )?;
+        battle_engine.set_content_digest(self.content.fingerprint().digest);
+        if let Some(enemy) = script.enemy {
+            battle_engine.set_side_b_flavor(enemy.flavor());
+            battle_engine.set_side_b_phases(self.resolve_enemy_phases(enemy));
+        }
+        // 其余敌人各自装配后加入战斗，敌方让子对每名敌人生效
+        for enemy in script.enemies {
+            let mut enemy_engine = self.build_battle_engine(
+                attacker_json,
+                &serialize_character_panel(&enemy.to_character_panel())?,
+                attacker_qi_output_rate,
+                defender_qi_output_rate,
+                config,
+            )?;
+            enemy_engine.set_side_b_flavor(enemy.flavor());
+            enemy_engine.set_side_b_phases(self.resolve_enemy_phases(enemy));
+            for (index, handicap) in script.handicaps.iter().enumerate() {
+                if handicap.side != BattleSide::Enemy {
+                    continue;
+                }
+                handicap.apply_to_panel(enemy_engine.side_panel_mut(Side::B));
+                enemy_engine.add_side_b_entries(
+                    handicap.entries.clone(),
+                    format!("handicap:{}", index),
+                    handicap.display_name().to_string(),
+                );
+            }
+            battle_engine.add_enemy(enemy_engine);
+        }
+        battle_engine.set_interrupts(script.interrupts);
+        battle_engine.set_training(script.training);
+        battle_engine.set_environment(script.environment);
+        for (source_id, source_name, entries) in script.player_entries {
+            battle_engine.add_side_a_entries(entries, source_id, source_name);
+        }
+        for (index, handicap) in script.handicaps.iter().enumerate() {
+            let side = match handicap.side {
+                BattleSide::Player => Side::A,
+                BattleSide::Enemy => Side::B,
+            };
+            handicap.apply_to_panel(battle_engine.side_panel_mut(side));
+            if handicap.entries.is_empty() {
+                continue;
+            }
+            let source_id = format!("handicap:{}", index);
+            let source_name = handicap.display_name().to_string();
+            let entries = handicap.entries.clone();
+            match side {
+                Side::A => battle_engine.add_side_a_entries(entries, source_id, source_name),
+                Side::B => battle_engine.add_side_b_entries(entries, source_id, source_name),
+            }
+        }
+        Ok(battle_engine)
+    }
+
+    /// 根据角色JSON构建战斗引擎（装配功法数值、词条执行器与日志模板）
+    fn build_battle_engine(
+        &self,
+        attacker_json: &str,
+        defender_json: &str,
+        attacker_qi_output_rate: Option<f64>,
+        defender_qi_output_rate: Option<f64>,
+        config: &BattleConfig,
+    ) -> WushenResult<BattleEngine> {
         const LEVEL_ZERO_ATTACK_SPEED: f64 = 5.0;
         const LEVEL_ZERO_CHARGE_TIME: f64 = 50.0;
 
@@ -343,6 +1103,16 @@ impl WushenCore {
         let mut side_a_panel = parse_character_panel(attacker_json)?;
         let mut side_b_panel = parse_character_panel(defender_json)?;
 
+        // 装备的功法未加载时按未装备参战，按配置提示或拒绝开战
+        let manuals = &self.content.manual_manager;
+        let mut unresolved = UnresolvedManual::collect(Side::A, &side_a_panel, manuals);
+        unresolved.extend(UnresolvedManual::collect(Side::B, &side_b_panel, manuals));
+        if config.missing_manuals == MissingManualPolicy::Reject && !unresolved.is_empty() {
+            return Err(WushenError::NotFound(UnresolvedManual::describe(
+                &unresolved,
+            )));
+        }
+
         // 设置内息输出（如果提供了参数）
         if let Some(rate) = attacker_qi_output_rate {
             side_a_panel.qi_output_rate = rate.max(0.0).min(side_a_panel.max_qi_output_rate);
@@ -358,6 +1128,7 @@ impl WushenCore {
 
         // 获取特性和功法数据以构建词条执行器
         let side_a_traits: Vec<_> = self
+            .content
             .trait_manager
             .get_traits_by_ids(&side_a_panel.traits)
             .into_iter()
@@ -365,6 +1136,7 @@ impl WushenCore {
             .collect();
 
         let side_b_traits: Vec<_> = self
+            .content
             .trait_manager
             .get_traits_by_ids(&side_b_panel.traits)
             .into_iter()
@@ -375,28 +1147,28 @@ impl WushenCore {
         let side_a_internal = side_a_panel
             .current_internal_id
             .as_ref()
-            .and_then(|id| self.manual_manager.get_internal(id));
+            .and_then(|id| self.content.manual_manager.get_internal(id));
         let side_a_attack_skill = side_a_panel
             .current_attack_skill_id
             .as_ref()
-            .and_then(|id| self.manual_manager.get_attack_skill(id));
+            .and_then(|id| self.content.manual_manager.get_attack_skill(id));
         let side_a_defense_skill = side_a_panel
             .current_defense_skill_id
             .as_ref()
-            .and_then(|id| self.manual_manager.get_defense_skill(id));
+            .and_then(|id| self.content.manual_manager.get_defense_skill(id));
 
         let side_b_internal = side_b_panel
             .current_internal_id
             .as_ref()
-            .and_then(|id| self.manual_manager.get_internal(id));
+            .and_then(|id| self.content.manual_manager.get_internal(id));
         let side_b_attack_skill = side_b_panel
             .current_attack_skill_id
             .as_ref()
-            .and_then(|id| self.manual_manager.get_attack_skill(id));
+            .and_then(|id| self.content.manual_manager.get_attack_skill(id));
         let side_b_defense_skill = side_b_panel
             .current_defense_skill_id
             .as_ref()
-            .and_then(|id| self.manual_manager.get_defense_skill(id));
+            .and_then(|id| self.content.manual_manager.get_defense_skill(id));
 
         // 根据装备的内功和武技设置角色面板属性
         // Side A
@@ -414,22 +1186,12 @@ impl WushenCore {
                     side_a_panel.qi_recovery_rate = realm.qi_recovery_rate;
                 }
             }
-            // 计算内息上限
-            if let Some((level, _)) = side_a_panel
-                .current_internal_id
-                .as_ref()
-                .and_then(|id| side_a_panel.get_internal_level_exp(id))
-            {
-                let mut total_qi_gain = 0.0;
-                for lvl in 1..=level {
-                    if let Some(realm) = internal.realm_at_level(lvl) {
-                        total_qi_gain += realm.qi_gain;
-                    }
-                }
-                side_a_panel.max_qi = total_qi_gain;
-                if side_a_panel.qi == 0.0 || side_a_panel.qi > side_a_panel.max_qi {
-                    side_a_panel.qi = side_a_panel.max_qi;
-                }
+        }
+        // 计算内息上限
+        if let Some(max_qi) = derived_max_qi(&config.base_qi, &side_a_panel, side_a_internal) {
+            side_a_panel.max_qi = max_qi;
+            if side_a_panel.qi == 0.0 || side_a_panel.qi > max_qi {
+                side_a_panel.qi = max_qi;
             }
         }
         if let Some(skill) = side_a_attack_skill {
@@ -473,21 +1235,12 @@ impl WushenCore {
                     side_b_panel.qi_recovery_rate = realm.qi_recovery_rate;
                 }
             }
-            if let Some((level, _)) = side_b_panel
-                .current_internal_id
-                .as_ref()
-                .and_then(|id| side_b_panel.get_internal_level_exp(id))
-            {
-                let mut total_qi_gain = 0.0;
-                for lvl in 1..=level {
-                    if let Some(realm) = internal.realm_at_level(lvl) {
-                        total_qi_gain += realm.qi_gain;
-                    }
-                }
-                side_b_panel.max_qi = total_qi_gain;
-                if side_b_panel.qi == 0.0 || side_b_panel.qi > side_b_panel.max_qi {
-                    side_b_panel.qi = side_b_panel.max_qi;
-                }
+        }
+        // 计算内息上限
+        if let Some(max_qi) = derived_max_qi(&config.base_qi, &side_b_panel, side_b_internal) {
+            side_b_panel.max_qi = max_qi;
+            if side_b_panel.qi == 0.0 || side_b_panel.qi > max_qi {
+                side_b_panel.qi = max_qi;
             }
         }
         if let Some(skill) = side_b_attack_skill {
@@ -516,8 +1269,9 @@ impl WushenCore {
             }
         }
 
-        // 创建词条执行器
-        let side_a_executor = EntryExecutor::aggregate_entries_from_panel(
+        // 创建词条执行器（跳过带有禁用标签的词条）
+        let disabled_tags = self.disabled_entry_tags();
+        let mut side_a_executor = EntryExecutor::aggregate_entries_from_panel(
             &side_a_traits,
             &side_a_panel,
             side_a_internal,
@@ -525,134 +1279,93 @@ impl WushenCore {
             side_a_defense_skill,
         );
 
-        let side_b_executor = EntryExecutor::aggregate_entries_from_panel(
+        let mut side_b_executor = EntryExecutor::aggregate_entries_from_panel(
             &side_b_traits,
             &side_b_panel,
             side_b_internal,
             side_b_attack_skill,
             side_b_defense_skill,
         );
+        side_a_executor.disable_tags(disabled_tags.clone());
+        side_b_executor.disable_tags(disabled_tags);
 
         // 创建战斗引擎
-        let mut battle_engine = BattleEngine::new(
+        let mut battle_engine = BattleEngine::with_config(
             &side_a_panel,
             &side_b_panel,
             side_a_executor,
             side_b_executor,
+            config.clone(),
         );
 
         // 设置日志模板（使用向后兼容的方法）
         if let Some(skill) = side_a_attack_skill {
             battle_engine.set_attacker_attack_log_template(skill.log_template.clone());
+            battle_engine.set_side_a_attack_ultimate(skill.ultimate.clone());
         }
         if let Some(skill) = side_a_defense_skill {
             battle_engine.set_attacker_defense_log_template(skill.log_template.clone());
+            battle_engine.set_side_a_defense_counter(skill.counter.clone());
         }
         if let Some(skill) = side_b_attack_skill {
             battle_engine.set_defender_attack_log_template(skill.log_template.clone());
+            battle_engine.set_side_b_attack_ultimate(skill.ultimate.clone());
         }
         if let Some(skill) = side_b_defense_skill {
             battle_engine.set_defender_defense_log_template(skill.log_template.clone());
+            battle_engine.set_side_b_defense_counter(skill.counter.clone());
+        }
+        if config.missing_manuals == MissingManualPolicy::Warn {
+            battle_engine.set_unresolved_manuals(unresolved);
         }
 
-        // 执行初始化阶段
-        battle_engine.step();
+        Ok(battle_engine)
+    }
 
-        // 继续运行战斗
-        let result = battle_engine.run();
-        let log = battle_engine.get_log();
-
-        // 获取战斗结束后的面板状态
-        let side_a_battle_panel = battle_engine.get_side_a_panel().clone();
-        let side_b_battle_panel = battle_engine.get_side_b_panel().clone();
-
-        // 构建返回结果（保持外部API兼容，使用 attacker/defender 命名）
-        let attacker_name = side_a_battle_panel.name.clone();
-        let defender_name = side_b_battle_panel.name.clone();
-        let mut records = Vec::new();
-        let mut batch_records: Vec<&BattleRecord> = Vec::new();
-        let mut current_batch_id: Option<u64> = None;
-        let mut after_battle_end = false;
-
-        for record in log.get_all_records() {
-            if let Some(batch_id) = battle_record_batch_id(record) {
-                if current_batch_id.is_none() || current_batch_id == Some(batch_id) {
-                    current_batch_id = Some(batch_id);
-                    batch_records.push(record);
-                    continue;
-                }
-
-                let batch_contains_battle_end = batch_records
-                    .iter()
-                    .any(|r| matches!(r, BattleRecord::BattleEnd { .. }));
-                records.extend(build_batch_logs(
-                    &batch_records,
-                    &attacker_name,
-                    &defender_name,
-                    after_battle_end,
-                ));
-                batch_records.clear();
-                current_batch_id = Some(batch_id);
-                if batch_contains_battle_end {
-                    after_battle_end = true;
-                }
-                batch_records.push(record);
-                continue;
-            }
-
-            if !batch_records.is_empty() {
-                let batch_contains_battle_end = batch_records
-                    .iter()
-                    .any(|r| matches!(r, BattleRecord::BattleEnd { .. }));
-                records.extend(build_batch_logs(
-                    &batch_records,
-                    &attacker_name,
-                    &defender_name,
-                    after_battle_end,
-                ));
-                batch_records.clear();
-                current_batch_id = None;
-                if batch_contains_battle_end {
-                    after_battle_end = true;
-                }
-            }
-
-            let is_battle_end = matches!(record, BattleRecord::BattleEnd { .. });
-            let record_is_terminal = after_battle_end && !is_battle_end;
-            let (effect_logs, value_logs) =
-                build_record_logs(record, &attacker_name, &defender_name, record_is_terminal);
-            records.extend(effect_logs);
-            records.extend(value_logs);
-            if is_battle_end {
-                after_battle_end = true;
-            }
-        }
-
-        if !batch_records.is_empty() {
-            records.extend(build_batch_logs(
-                &batch_records,
-                &attacker_name,
-                &defender_name,
-                after_battle_end,
-            ));
-        }
-
-        let battle_result = BattleResultJson {
-            result: match result {
-                BattleResult::SideAWin => "attacker_win".to_string(),
-                BattleResult::SideBWin => "defender_win".to_string(),
-                BattleResult::Draw => "draw".to_string(),
-            },
-            records,
-            // 映射：side_a -> attacker, side_b -> defender
-            attacker_panel: battle_panel_to_json(&side_a_battle_panel),
-            defender_panel: battle_panel_to_json(&side_b_battle_panel),
-        };
-
-        let json = serde_json::to_string(&battle_result)
-            .map_err(|e| format!("序列化战斗结果失败: {}", e))?;
-
-        Ok(json)
+    /// 将敌人模板的首领阶段解析为战斗阶段（缺失的功法/特性忽略）
+    fn resolve_enemy_phases(&self, enemy: &EnemyTemplate) -> Vec<BattlePhase> {
+        let manuals = &self.content.manual_manager;
+        enemy
+            .phases
+            .iter()
+            .map(|phase| BattlePhase {
+                name: phase.name.clone(),
+                hp_ratio: phase.hp_ratio,
+                text: phase.text.clone(),
+                attack_skill: phase.attack_skill.as_ref().and_then(|owned| {
+                    let skill = manuals.get_attack_skill(&owned.id)?;
+                    let realm = skill.realm_at_level(owned.level)?;
+                    Some(PhaseAttackSkill {
+                        id: owned.id.clone(),
+                        name: skill.manual.name.clone(),
+                        power: realm.power,
+                        charge_time: realm.charge_time,
+                        log_template: skill.log_template.clone(),
+                        ultimate: skill.ultimate.clone(),
+                        entries: realm.entries.clone(),
+                    })
+                }),
+                defense_skill: phase.defense_skill.as_ref().and_then(|owned| {
+                    let skill = manuals.get_defense_skill(&owned.id)?;
+                    let realm = skill.realm_at_level(owned.level)?;
+                    Some(PhaseDefenseSkill {
+                        id: owned.id.clone(),
+                        name: skill.manual.name.clone(),
+                        defense_power: realm.defense_power,
+                        log_template: skill.log_template.clone(),
+                        counter: skill.counter.clone(),
+                        entries: realm.entries.clone(),
+                    })
+                }),
+                entries: self
+                    .content
+                    .trait_manager
+                    .get_traits_by_ids(&phase.traits)
+                    .into_iter()
+                    .flat_map(|t| t.entries.clone())
+                    .collect(),
+            })
+            .collect()
     }
 
     /// 执行修行
@@ -663,25 +1376,32 @@ impl WushenCore {
         character_json: &str,
         manual_id: &str,
         manual_type: &str,
-    ) -> Result<String, String> {
+    ) -> WushenResult<String> {
+        self.execute_cultivation_with_entries(character_json, manual_id, manual_type, Vec::new())
+    }
+
+    /// 执行修行，额外词条（如限时增益）与特性词条一同生效
+    fn execute_cultivation_with_entries(
+        &self,
+        character_json: &str,
+        manual_id: &str,
+        manual_type: &str,
+        extra_entries: Vec<Entry>,
+    ) -> WushenResult<String> {
         // 解析角色JSON
         let mut panel = parse_character_panel(character_json)?;
 
-        // 如果内息上限为0且角色有装备的内功，根据内功等级计算内息上限
+        // 如果内息上限为0，根据基础内息池与内功等级计算内息上限
         if panel.max_qi == 0.0 {
-            if let Some(internal_id) = &panel.current_internal_id {
-                if let Some(internal) = self.manual_manager.get_internal(internal_id) {
-                    if let Some((level, _)) = panel.get_internal_level_exp(internal_id) {
-                        let mut total_qi_gain = 0.0;
-                        for lvl in 1..=level {
-                            if let Some(realm) = internal.realm_at_level(lvl) {
-                                total_qi_gain += realm.qi_gain;
-                            }
-                        }
-                        panel.max_qi = total_qi_gain;
-                        panel.qi = total_qi_gain;
-                    }
-                }
+            let internal = panel
+                .current_internal_id
+                .as_ref()
+                .and_then(|id| self.content.manual_manager.get_internal(id));
+            if let Some(max_qi) =
+                derived_max_qi(&self.content.battle_config.base_qi, &panel, internal)
+            {
+                panel.max_qi = max_qi;
+                panel.qi = max_qi;
             }
         }
 
@@ -690,7 +1410,7 @@ impl WushenCore {
             let mut total_martial_arts_attainment = 0.0;
 
             for (internal_id, (level, _)) in &panel.owned_internals {
-                if let Some(internal) = self.manual_manager.get_internal(internal_id) {
+                if let Some(internal) = self.content.manual_manager.get_internal(internal_id) {
                     for lvl in 1..=*level {
                         if let Some(realm) = internal.realm_at_level(lvl) {
                             total_martial_arts_attainment += realm.martial_arts_attainment;
@@ -700,7 +1420,7 @@ impl WushenCore {
             }
 
             for (skill_id, (level, _)) in &panel.owned_attack_skills {
-                if let Some(skill) = self.manual_manager.get_attack_skill(skill_id) {
+                if let Some(skill) = self.content.manual_manager.get_attack_skill(skill_id) {
                     for lvl in 1..=*level {
                         if let Some(realm) = skill.realm_at_level(lvl) {
                             total_martial_arts_attainment += realm.martial_arts_attainment;
@@ -710,7 +1430,7 @@ impl WushenCore {
             }
 
             for (skill_id, (level, _)) in &panel.owned_defense_skills {
-                if let Some(skill) = self.manual_manager.get_defense_skill(skill_id) {
+                if let Some(skill) = self.content.manual_manager.get_defense_skill(skill_id) {
                     for lvl in 1..=*level {
                         if let Some(realm) = skill.realm_at_level(lvl) {
                             total_martial_arts_attainment += realm.martial_arts_attainment;
@@ -726,13 +1446,19 @@ impl WushenCore {
         let (old_level, old_exp) = match manual_type {
             "internal" => {
                 if !panel.has_internal(manual_id) {
-                    return Err(format!("角色未拥有内功 {}", manual_id));
+                    return Err(WushenError::ConditionNotMet(format!(
+                        "角色未拥有内功 {}",
+                        manual_id
+                    )));
                 }
                 panel.get_internal_level_exp(manual_id).unwrap_or((0, 0.0))
             }
             "attack_skill" => {
                 if !panel.has_attack_skill(manual_id) {
-                    return Err(format!("角色未拥有攻击武技 {}", manual_id));
+                    return Err(WushenError::ConditionNotMet(format!(
+                        "角色未拥有攻击武技 {}",
+                        manual_id
+                    )));
                 }
                 panel
                     .get_attack_skill_level_exp(manual_id)
@@ -740,39 +1466,50 @@ impl WushenCore {
             }
             "defense_skill" => {
                 if !panel.has_defense_skill(manual_id) {
-                    return Err(format!("角色未拥有防御武技 {}", manual_id));
+                    return Err(WushenError::ConditionNotMet(format!(
+                        "角色未拥有防御武技 {}",
+                        manual_id
+                    )));
                 }
                 panel
                     .get_defense_skill_level_exp(manual_id)
                     .unwrap_or((0, 0.0))
             }
-            _ => return Err(format!("未知的功法类型: {}", manual_type)),
+            _ => {
+                return Err(WushenError::Invalid(format!(
+                    "未知的功法类型: {}",
+                    manual_type
+                )))
+            }
         };
 
         // 创建特性执行器
-        let mut executor = self.trait_manager.create_executor(&panel.traits);
+        let mut executor = self.content.trait_manager.create_executor(&panel.traits);
+        executor.disable_tags(self.disabled_entry_tags());
+        executor.add_entries(extra_entries);
 
         // 执行修行
         let exp_gain = match manual_type {
             "internal" => {
                 let from_id = panel.current_internal_id.clone();
                 if from_id.as_deref() != Some(manual_id) {
-                    self.manual_manager.switch_internal(
+                    self.content.manual_manager.switch_internal(
                         from_id.as_deref(),
                         manual_id,
                         &mut panel,
                         Some(&mut executor),
                     )?;
                 }
-                self.manual_manager
+                self.content
+                    .manual_manager
                     .cultivate_internal(&mut panel, Some(&mut executor))?
             }
-            "attack_skill" => self.manual_manager.cultivate_attack_skill(
+            "attack_skill" => self.content.manual_manager.cultivate_attack_skill(
                 manual_id,
                 &mut panel,
                 Some(&mut executor),
             )?,
-            "defense_skill" => self.manual_manager.cultivate_defense_skill(
+            "defense_skill" => self.content.manual_manager.cultivate_defense_skill(
                 manual_id,
                 &mut panel,
                 Some(&mut executor),
@@ -807,77 +1544,140 @@ impl WushenCore {
         };
 
         let json = serde_json::to_string(&cultivation_result)
-            .map_err(|e| format!("序列化修行结果失败: {}", e))?;
+            .map_err(|e| WushenError::ParseError(format!("序列化修行结果失败: {}", e)))?;
 
         Ok(json)
     }
 
     // ==================== 游戏运行时 ====================
 
-    pub fn game_start_new(&mut self, request: NewGameRequest) -> Result<GameResponse, String> {
+    pub fn game_start_new(&mut self, request: NewGameRequest) -> WushenResult<GameResponse> {
+        self.transaction(|core| core.start_new(request))
+    }
+
+    fn start_new(&mut self, request: NewGameRequest) -> WushenResult<GameResponse> {
         let storyline = self
+            .content
             .event_manager
             .get_storyline(&request.storyline_id)
-            .ok_or_else(|| format!("剧情线 {} 不存在", request.storyline_id))?;
-        if request.three_d.comprehension == 0
-            || request.three_d.bone_structure == 0
-            || request.three_d.physique == 0
-        {
-            return Err("三维最小值为1，不能为0".to_string());
+            .ok_or_else(|| {
+                WushenError::NotFound(format!("剧情线 {} 不存在", request.storyline_id))
+            })?;
+        check_three_d(&request.three_d)?;
+
+        if let Some(challenge) = &request.challenge {
+            challenge.validate(&self.content.trait_manager, &self.content.manual_manager)?;
         }
-        let total = request.three_d.comprehension
-            + request.three_d.bone_structure
-            + request.three_d.physique;
-        if total > 100 {
-            return Err("三维总点数不能超过 100".to_string());
+        if let Some(appearance) = &request.appearance {
+            appearance.validate()?;
         }
-
-        let mut save = SaveGame {
-            id: request.character_id.clone(),
-            name: request.name.clone(),
-            created_at: now_timestamp(),
-            current_character: CharacterState {
-                id: request.character_id,
-                name: request.name,
-                three_d: request.three_d,
-                traits: vec![],
-                internals: empty_manuals(),
-                attack_skills: empty_manuals(),
-                defense_skills: empty_manuals(),
-                action_points: 0,
-                cultivation_history: vec![],
-                max_qi: Some(0.0),
-                qi: Some(0.0),
-                martial_arts_attainment: Some(0.0),
-            },
-            storyline_progress: Some(StorylineProgress {
+        storyline
+            .starting_kit
+            .validate_references(&self.content.trait_manager, &self.content.manual_manager)?;
+
+        let mut character = new_character(request.character_id, request.name, request.three_d);
+        character.appearance = request.appearance;
+        let mut save = self.new_save(
+            character,
+            StorylineProgress {
                 storyline_id: storyline.id.clone(),
                 event_id: storyline.start_event_id.clone(),
-            }),
-            active_adventure_id: None,
-            start_trait_pool: self.trait_manager.start_pool_ids(),
-            completed_characters: vec![],
-            rng_state: seed_from_time(),
-            story_history: vec![],
-        };
+            },
+        );
+        save.post_battle_recovery = request.post_battle_recovery;
+        save.defeat_policy = request.defeat_policy;
+        save.analytics = request.analytics.then(SaveAnalytics::default);
+        if let Some(challenge) = request.challenge {
+            if let Some(seed) = challenge.seed {
+                save.rng_state = seed;
+            }
+            if challenge.post_battle_recovery.is_some() {
+                save.post_battle_recovery = challenge.post_battle_recovery;
+            }
+            save.start_trait_pool.retain(|id| !challenge.bans_trait(id));
+            save.challenge = Some(challenge);
+        }
 
         Self::record_story_event(&mut save, &storyline.start_event_id);
 
         ensure_rng_state(&mut save);
-        let picked_traits = draw_start_traits(&self.trait_manager, &mut save);
+        let picked_traits = draw_start_traits(&self.content.trait_manager, &mut save);
         if !picked_traits.is_empty() {
             save.current_character.traits = picked_traits;
         }
+        storyline
+            .starting_kit
+            .apply(&mut save.current_character, save.challenge.as_ref());
         self.apply_game_start_effects(&mut save.current_character)?;
-        self.game_runtime = Some(GameRuntime { save });
+        save.sync_codex();
+        self.game_runtime = Some(GameRuntime::new(save));
         self.game_view(None)
     }
 
-    pub fn game_resume(&mut self, mut save: SaveGame) -> Result<GameResponse, String> {
+    /// 以指定角色与剧情进度创建新存档
+    fn new_save(&self, character: CharacterState, progress: StorylineProgress) -> SaveGame {
+        SaveGame {
+            id: character.id.clone(),
+            name: character.name.clone(),
+            created_at: now_timestamp(),
+            current_character: character,
+            storyline_progress: Some(progress),
+            active_adventure_id: None,
+            start_trait_pool: self.content.trait_manager.start_pool_ids(),
+            completed_characters: vec![],
+            rng_state: seed_from_time(),
+            story_history: Default::default(),
+            post_battle_recovery: None,
+            active_quests: vec![],
+            completed_quests: vec![],
+            day: 0,
+            analytics: None,
+            completed_chapters: vec![],
+            pending_victory_decision: None,
+            adventure_discovery: AdventureDiscovery::default(),
+            codex: Codex::default(),
+            battle_archive_policy: None,
+            battle_archive: Default::default(),
+            battles_archived: 0,
+            challenge: None,
+            defeat_policy: None,
+            action_point_penalty: 0,
+            content_fingerprint: Some(self.content.fingerprint()),
+            party: Party::default(),
+        }
+    }
+
+    /// 恢复存档；存档引用的内容缺失时返回错误，
+    /// 可先用 [`WushenCore::game_check_save`] 列出问题、[`WushenCore::game_repair_save`] 修复
+    pub fn game_resume(&mut self, save: SaveGame) -> WushenResult<GameResponse> {
+        self.transaction(|core| core.resume(save))
+    }
+
+    fn resume(&mut self, mut save: SaveGame) -> WushenResult<GameResponse> {
+        save.ensure_segments_loaded()?;
+        let problems = self.game_check_save(&save);
+        if !problems.is_empty() {
+            let problems: Vec<String> = problems.iter().map(SaveProblem::describe).collect();
+            return Err(WushenError::Invalid(format!(
+                "存档与当前内容不一致：{}",
+                problems.join("；")
+            )));
+        }
+        let warnings = self.content_changed_warning(&save).into_iter().collect();
         ensure_rng_state(&mut save);
+        save.sync_codex();
+        // 战后抉择只对当前事件有效
+        let current_event_id = save.storyline_progress.as_ref().map(|p| &p.event_id);
+        if save.pending_victory_decision.as_ref() != current_event_id {
+            save.pending_victory_decision = None;
+        }
         if save.story_history.is_empty() {
             if let Some(progress) = save.storyline_progress.as_ref() {
-                if let Some(storyline) = self.event_manager.get_storyline(&progress.storyline_id) {
+                if let Some(storyline) = self
+                    .content
+                    .event_manager
+                    .get_storyline(&progress.storyline_id)
+                {
                     if let Some(event) = storyline
                         .events
                         .iter()
@@ -892,51 +1692,230 @@ impl WushenCore {
                 }
             }
         }
-        self.game_runtime = Some(GameRuntime { save });
-        self.game_view(None)
+        self.game_runtime = Some(GameRuntime::new(save));
+        let mut response = self.game_view(None)?;
+        response.warnings = warnings;
+        Ok(response)
+    }
+
+    /// 当前内容与存档创建时不同时的提示（旧存档未记录指纹时不提示）
+    fn content_changed_warning(&self, save: &SaveGame) -> Option<String> {
+        let recorded = save.content_fingerprint.as_ref()?;
+        let current = self.content.fingerprint();
+        if recorded.digest == current.digest {
+            return None;
+        }
+        Some(format!(
+            "当前加载的内容与创建存档时不同（变化的内容：{}），剧情与奖励可能与预期不符",
+            recorded.changed_collections(&current).join("、")
+        ))
+    }
+
+    /// 当前内容指纹（返回JSON字符串）
+    pub fn content_fingerprint(&self) -> WushenResult<String> {
+        serde_json::to_string(&self.content.fingerprint())
+            .map_err(|e| WushenError::ParseError(format!("序列化内容指纹失败: {}", e)))
+    }
+
+    /// 载入单独存储的存档分段后恢复游戏
+    pub fn game_resume_segmented(
+        &mut self,
+        mut save: SaveGame,
+        segments: SaveSegments,
+    ) -> WushenResult<GameResponse> {
+        save.attach_segments(segments)?;
+        self.game_resume(save)
+    }
+
+    /// 按需读取当前存档的分段数据JSON
+    pub fn game_segment(&self, kind: String) -> WushenResult<String> {
+        let runtime = self
+            .game_runtime
+            .as_ref()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        let json = match SegmentKind::parse(&kind)? {
+            SegmentKind::StoryHistory => serde_json::to_string(&runtime.save.story_history[..]),
+            SegmentKind::BattleArchive => serde_json::to_string(&runtime.save.battle_archive[..]),
+        };
+        json.map_err(|e| WushenError::ParseError(format!("序列化存档分段失败: {}", e)))
+    }
+
+    /// 取出自上次调用以来发生变化的存档分段（宿主据此单独持久化）
+    pub fn game_take_dirty_segments(&mut self) -> WushenResult<SaveSegments> {
+        let runtime = self
+            .game_runtime
+            .as_mut()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        Ok(runtime.save.take_dirty_segments())
     }
 
-    pub fn game_view(&self, outcome: Option<GameOutcome>) -> Result<GameResponse, String> {
+    pub fn game_view(&self, outcome: Option<GameOutcome>) -> WushenResult<GameResponse> {
         let runtime = self
             .game_runtime
             .as_ref()
-            .ok_or_else(|| "游戏尚未初始化".to_string())?;
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
         let view = self.build_game_view(runtime)?;
-        Ok(GameResponse { view, outcome })
+        Ok(GameResponse {
+            view,
+            outcome,
+            warnings: Vec::new(),
+            training: Vec::new(),
+        })
+    }
+
+    /// 差量视图：只返回自 since_revision 版本以来发生变化的分区（为空时返回全部分区）
+    pub fn game_view_diff(&mut self, since_revision: Option<u64>) -> WushenResult<GameViewDiff> {
+        let view = {
+            let runtime = self
+                .game_runtime
+                .as_ref()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            self.build_game_view(runtime)?
+        };
+        let runtime = self
+            .game_runtime
+            .as_mut()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        Ok(runtime.view_tracker.diff(view, since_revision))
+    }
+
+    /// 开启或关闭当前存档的本地数据统计（关闭时清空已有统计）
+    pub fn game_set_analytics(&mut self, enabled: bool) -> WushenResult<GameResponse> {
+        let runtime = self
+            .game_runtime
+            .as_mut()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        if !enabled {
+            runtime.save.analytics = None;
+        } else if runtime.save.analytics.is_none() {
+            runtime.save.analytics = Some(SaveAnalytics::default());
+        }
+        self.game_view(None)
+    }
+
+    /// 设置当前存档的战斗存档策略（为空时关闭战斗存档并清空已存档的战斗）
+    pub fn game_set_battle_archive(
+        &mut self,
+        policy: Option<BattleArchivePolicy>,
+    ) -> WushenResult<GameResponse> {
+        let runtime = self
+            .game_runtime
+            .as_mut()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        let save = &mut runtime.save;
+        match policy {
+            Some(policy) => {
+                if let Some(max_battles) = policy.max_battles {
+                    let excess = save.battle_archive.len().saturating_sub(max_battles);
+                    if excess > 0 {
+                        save.battle_archive.drain_front(excess);
+                    }
+                }
+                save.battle_archive_policy = Some(policy);
+            }
+            None => {
+                save.battle_archive_policy = None;
+                let archived = save.battle_archive.len();
+                save.battle_archive.drain_front(archived);
+            }
+        }
+        self.game_view(None)
+    }
+
+    /// 读取当前存档中已存档的战斗JSON（与战斗接口返回的结构一致）
+    pub fn get_archived_battle(&self, id: &str) -> WushenResult<String> {
+        let runtime = self
+            .game_runtime
+            .as_ref()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        let battle = runtime
+            .save
+            .battle_archive
+            .iter()
+            .find(|battle| battle.id == id)
+            .ok_or_else(|| WushenError::NotFound(format!("战斗记录 {} 不存在或已被清理", id)))?;
+        serde_json::to_string(&battle.result)
+            .map_err(|e| WushenError::ParseError(format!("序列化战斗记录失败: {}", e)))
+    }
+
+    /// 导出当前存档的数据统计报告JSON
+    pub fn game_analytics_report(&self) -> WushenResult<String> {
+        let runtime = self
+            .game_runtime
+            .as_ref()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        let analytics = runtime
+            .save
+            .analytics
+            .as_ref()
+            .ok_or_else(|| WushenError::InvalidState("当前存档未开启数据统计".to_string()))?;
+        serde_json::to_string(&analytics.report())
+            .map_err(|e| WushenError::ParseError(format!("序列化统计报告失败: {}", e)))
     }
 
     pub fn game_cultivate(
         &mut self,
         manual_id: String,
         manual_type: String,
-    ) -> Result<GameResponse, String> {
-        let character_json = {
+    ) -> WushenResult<GameResponse> {
+        self.transaction(|core| core.cultivate(manual_id, manual_type))
+    }
+
+    fn cultivate(&mut self, manual_id: String, manual_type: String) -> WushenResult<GameResponse> {
+        let (character_json, extra_entries, calendar, action_costs) = {
             let runtime = self
                 .game_runtime
                 .as_ref()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
-            if runtime.save.current_character.action_points == 0 {
-                return Err("行动点不足".to_string());
-            }
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            let action_costs = self.action_costs(&runtime.save);
+            action_costs.check(
+                ActionKind::Cultivate,
+                runtime.save.current_character.action_points,
+            )?;
             let panel = character_state_to_panel(&runtime.save.current_character);
-            serialize_character_panel(&panel)?
+            let calendar = self.calendar_config(&runtime.save);
+            let mut extra_entries = runtime.save.current_character.timed_buff_entries();
+            extra_entries.extend(runtime.save.current_character.title_entries());
+            let trigger = match manual_type.as_str() {
+                "internal" => Some(Trigger::CultivatingInternal),
+                "attack_skill" => Some(Trigger::CultivatingAttack),
+                "defense_skill" => Some(Trigger::CultivatingDefense),
+                _ => None,
+            };
+            if let Some(trigger) = trigger {
+                let season = calendar.season_of(runtime.save.day);
+                extra_entries.extend(calendar.cultivation_entry(season, trigger));
+            }
+            (
+                serialize_character_panel(&panel)?,
+                extra_entries,
+                calendar,
+                action_costs,
+            )
         };
-        let result_json = self.execute_cultivation(&character_json, &manual_id, &manual_type)?;
-        let result: CultivationResultJson =
-            serde_json::from_str(&result_json).map_err(|e| format!("解析修行结果失败: {}", e))?;
+        let result_json = self.execute_cultivation_with_entries(
+            &character_json,
+            &manual_id,
+            &manual_type,
+            extra_entries,
+        )?;
+        let result: CultivationResultJson = serde_json::from_str(&result_json)
+            .map_err(|e| WushenError::ParseError(format!("解析修行结果失败: {}", e)))?;
         let updated_panel = parse_character_panel(&result.updated_character)?;
         {
             let runtime = self
                 .game_runtime
                 .as_mut()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
             update_character_from_panel(&mut runtime.save.current_character, &updated_panel);
-            runtime.save.current_character.action_points = runtime
-                .save
-                .current_character
-                .action_points
-                .saturating_sub(1);
+            action_costs.charge(
+                ActionKind::Cultivate,
+                &mut runtime.save.current_character.action_points,
+            )?;
+            runtime.save.current_character.tick_timed_buffs();
+            runtime.save.current_character.tick_scheduled_events();
             runtime.save.current_character.cultivation_history.clear();
+            runtime.save.day += calendar.days_per_action;
             Self::record_current_story_event_if_ready(&mut runtime.save);
         }
 
@@ -956,50 +1935,134 @@ impl WushenCore {
         &mut self,
         attacker_qi_output_rate: Option<f64>,
         defender_qi_output_rate: Option<f64>,
-    ) -> Result<GameResponse, String> {
-        let (mut character, rng_state, mut start_trait_pool) = {
+    ) -> WushenResult<GameResponse> {
+        self.transaction(|core| {
+            let warnings = core.drop_missing_scheduled_events()?;
+            let mut response =
+                core.travel(attacker_qi_output_rate, defender_qi_output_rate, None)?;
+            response.warnings.extend(warnings);
+            Ok(response)
+        })
+    }
+
+    /// 取消引用了已不存在奇遇的预约（内容变更后），返回提示；否则到期时游历会一直失败
+    fn drop_missing_scheduled_events(&mut self) -> WushenResult<Vec<String>> {
+        let event_manager = &self.content.event_manager;
+        let runtime = self
+            .game_runtime
+            .as_mut()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        let mut warnings = Vec::new();
+        runtime
+            .save
+            .current_character
+            .scheduled_events
+            .retain(|event| {
+                let exists = event_manager
+                    .get_adventure_event(&event.adventure_id)
+                    .is_some();
+                if !exists {
+                    warnings.push(format!(
+                        "预约的奇遇 {} 已不存在，已取消预约",
+                        event.adventure_id
+                    ));
+                }
+                exists
+            });
+        Ok(warnings)
+    }
+
+    /// 游历；指定奇遇时跳过行动点、季节与触发条件检查，直接触发该奇遇
+    fn travel(
+        &mut self,
+        attacker_qi_output_rate: Option<f64>,
+        defender_qi_output_rate: Option<f64>,
+        forced_adventure_id: Option<&str>,
+    ) -> WushenResult<GameResponse> {
+        let (cost, mut character, rng_state, mut start_trait_pool, calendar, day) = {
             let runtime = self
                 .game_runtime
                 .as_ref()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
-            if runtime.save.current_character.action_points == 0 {
-                return Err("行动点不足".to_string());
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            let cost = self.action_costs(&runtime.save).cost(ActionKind::Travel);
+            if forced_adventure_id.is_none() && runtime.save.current_character.action_points < cost
+            {
+                return Err(WushenError::ConditionNotMet("行动点不足".to_string()));
             }
             (
+                cost,
                 runtime.save.current_character.clone(),
                 runtime.save.rng_state,
                 runtime.save.start_trait_pool.clone(),
+                self.calendar_config(&runtime.save),
+                runtime.save.day,
             )
         };
+        let season = calendar.season_of(day);
 
-        character.action_points = character.action_points.saturating_sub(1);
+        character.action_points = character.action_points.saturating_sub(cost);
+        character.tick_timed_buffs();
+        character.tick_scheduled_events();
         character.cultivation_history.clear();
+        // 到期的预约奇遇优先于往昔之影与随机奇遇
+        let due_adventure_id = match forced_adventure_id {
+            Some(_) => None,
+            None => character.take_due_event(),
+        };
 
         let panel = character_state_to_panel(&character);
-        let mut available = Vec::new();
-        for event in self.event_manager.all_adventure_events() {
-            if EventManager::is_adventure_event_available(event, &panel, &self.manual_manager) {
-                available.push(event);
-            }
-        }
+        let mut rng = SimpleRng::from_state(rng_state);
+        let picked = match forced_adventure_id.or(due_adventure_id.as_deref()) {
+            Some(id) => self
+                .content
+                .event_manager
+                .get_adventure_event(id)
+                .ok_or_else(|| WushenError::NotFound(format!("奇遇事件 {} 不存在", id)))?,
+            None => {
+                let mut available = Vec::new();
+                for event in self.content.event_manager.all_adventure_events() {
+                    if event.is_in_season(season)
+                        && EventManager::is_adventure_event_available(
+                            event,
+                            &panel,
+                            &self.content.manual_manager,
+                        )
+                    {
+                        available.push(event);
+                    }
+                }
 
-        if available.is_empty() {
-            {
-                let runtime = self
-                    .game_runtime
-                    .as_mut()
-                    .ok_or_else(|| "游戏尚未初始化".to_string())?;
-                runtime.save.current_character = character;
-                Self::record_current_story_event_if_ready(&mut runtime.save);
-            }
-            let outcome = GameOutcome::Info {
-                message: "本次游历未触发奇遇".to_string(),
-            };
-            return self.game_view(Some(outcome));
-        }
+                if let Some((encounter, past)) = self.roll_echo_encounter(&mut rng, &panel) {
+                    return self.travel_echo_encounter(
+                        character,
+                        start_trait_pool,
+                        rng.state(),
+                        day + calendar.days_per_action,
+                        &encounter,
+                        &past,
+                        (attacker_qi_output_rate, defender_qi_output_rate),
+                    );
+                }
 
-        let mut rng = SimpleRng::from_state(rng_state);
-        let picked = available[rng.next_usize(available.len())];
+                if available.is_empty() {
+                    {
+                        let runtime = self.game_runtime.as_mut().ok_or_else(|| {
+                            WushenError::InvalidState("游戏尚未初始化".to_string())
+                        })?;
+                        runtime.save.set_current_character(character);
+                        runtime.save.rng_state = rng.state();
+                        runtime.save.day = day + calendar.days_per_action;
+                        Self::record_current_story_event_if_ready(&mut runtime.save);
+                    }
+                    let outcome = GameOutcome::Info {
+                        message: "本次游历未触发奇遇".to_string(),
+                    };
+                    return self.game_view(Some(outcome));
+                }
+
+                available[rng.next_usize(available.len())]
+            }
+        };
         let next_rng_state = rng.state();
 
         let mut active_adventure_id = None;
@@ -1011,8 +2074,11 @@ impl WushenCore {
                     name: picked.name.clone(),
                     text: None,
                     rewards: vec![],
+                    duplicates: vec![],
+                    enemies: Vec::new(),
                     battle_result: None,
                     win: None,
+                    recovery: None,
                 }
             }
             AdventureEventContent::Story { text, rewards } => {
@@ -1020,51 +2086,78 @@ impl WushenCore {
                 let filtered = filter_rewards_for_panel(
                     &panel,
                     rewards,
-                    &self.manual_manager,
+                    &self.content.manual_manager,
                     &start_trait_pool,
+                    &self.duplicate_rewards(),
+                    self.challenge(),
                 );
-                self.apply_rewards_to_character(&mut character, &mut start_trait_pool, &filtered)?;
+                self.apply_rewards_to_character(
+                    &mut character,
+                    &mut start_trait_pool,
+                    &filtered.rewards,
+                )?;
                 GameOutcome::Adventure {
                     name: picked.name.clone(),
                     text: Some(text.clone()),
-                    rewards: filtered,
+                    rewards: filtered.rewards,
+                    duplicates: filtered.duplicates,
+                    enemies: Vec::new(),
                     battle_result: None,
                     win: None,
+                    recovery: None,
                 }
             }
             AdventureEventContent::Battle {
                 text,
                 enemy,
+                enemies,
                 win,
                 lose,
+                draw,
+                handicaps,
+                environment,
+                ..
             } => {
                 let battle_result = self.run_battle(
                     &character,
                     enemy,
+                    enemies,
+                    handicaps,
+                    environment,
                     attacker_qi_output_rate,
                     defender_qi_output_rate,
                 )?;
                 let win_flag = battle_is_attacker_win(&battle_result);
-                let rewards = if win_flag {
-                    &win.rewards
-                } else {
-                    &lose.rewards
-                };
+                let recovery = apply_post_battle_recovery(
+                    &mut character,
+                    &battle_result,
+                    self.post_battle_recovery_rule(),
+                );
+                let rewards = &battle_branch(&battle_result, win, lose, draw.as_ref()).rewards;
                 let panel = character_state_to_panel(&character);
                 let filtered = filter_rewards_for_panel(
                     &panel,
                     rewards,
-                    &self.manual_manager,
+                    &self.content.manual_manager,
                     &start_trait_pool,
+                    &self.duplicate_rewards(),
+                    self.challenge(),
                 );
-                self.apply_rewards_to_character(&mut character, &mut start_trait_pool, &filtered)?;
-                adventure_battle_win = Some(win_flag);
+                self.apply_rewards_to_character(
+                    &mut character,
+                    &mut start_trait_pool,
+                    &filtered.rewards,
+                )?;
+                adventure_battle_win = Some((win_flag, enemy.name.clone(), battle_result.clone()));
                 GameOutcome::Adventure {
                     name: picked.name.clone(),
                     text: Some(text.clone()),
-                    rewards: filtered,
+                    rewards: filtered.rewards,
+                    duplicates: filtered.duplicates,
+                    enemies: battle_enemy_outcomes(&battle_result),
                     battle_result: Some(battle_result),
                     win: Some(win_flag),
+                    recovery: Some(recovery),
                 }
             }
         };
@@ -1073,19 +2166,30 @@ impl WushenCore {
             let runtime = self
                 .game_runtime
                 .as_mut()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
-            runtime.save.current_character = character;
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            runtime.save.set_current_character(character);
             runtime.save.start_trait_pool = start_trait_pool;
             runtime.save.rng_state = next_rng_state;
+            runtime.save.day = day + calendar.days_per_action;
+            runtime.save.adventure_discovery.record_seen(&picked.id);
+            if active_adventure_id.is_none() {
+                runtime
+                    .save
+                    .adventure_discovery
+                    .record_completed(&picked.id);
+            }
             runtime.save.active_adventure_id = active_adventure_id;
             Self::record_adventure_event(&mut runtime.save, &picked.id);
-            if let Some(win_flag) = adventure_battle_win {
+            if let Some((win_flag, enemy_name, battle_result)) = adventure_battle_win {
                 Self::record_battle_result(
                     &mut runtime.save,
                     StoryHistoryScope::Adventure,
                     &picked.id,
+                    &battle_result,
+                    &enemy_name,
                     win_flag,
                 );
+                Self::record_quest_battle_win(&mut runtime.save, win_flag);
             }
             Self::record_current_story_event_if_ready(&mut runtime.save);
         }
@@ -1093,41 +2197,185 @@ impl WushenCore {
         self.game_view(Some(outcome))
     }
 
-    pub fn game_story_option(&mut self, option_id: String) -> Result<GameResponse, String> {
+    /// 掷骰决定本次游历是否遭遇往昔之影
+    fn roll_echo_encounter(
+        &self,
+        rng: &mut SimpleRng,
+        panel: &CharacterPanel,
+    ) -> Option<(EchoEncounter, CharacterState)> {
+        let save = &self.game_runtime.as_ref()?.save;
+        if save.completed_characters.is_empty() {
+            return None;
+        }
+        let encounter = save
+            .storyline_progress
+            .as_ref()
+            .and_then(|p| self.content.event_manager.get_storyline(&p.storyline_id))
+            .and_then(|s| s.echo_encounter.as_ref())?;
+        if !EventManager::is_condition_met(
+            &encounter.condition,
+            panel,
+            &self.content.manual_manager,
+        ) {
+            return None;
+        }
+        if rng.next_f64() >= encounter.chance {
+            return None;
+        }
+        let past = &save.completed_characters[rng.next_usize(save.completed_characters.len())];
+        Some((encounter.clone(), past.clone()))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn travel_echo_encounter(
+        &mut self,
+        mut character: CharacterState,
+        mut start_trait_pool: Vec<String>,
+        rng_state: u64,
+        day: u32,
+        encounter: &EchoEncounter,
+        past: &CharacterState,
+        qi_output_rates: (Option<f64>, Option<f64>),
+    ) -> WushenResult<GameResponse> {
+        // 残影以全盛状态出战
+        let mut echo = past.clone();
+        echo.hp = None;
+        echo.qi = echo.max_qi;
+        let battle_result = self.run_battle_against(
+            &character,
+            character_state_to_panel(&echo),
+            qi_output_rates.0,
+            qi_output_rates.1,
+            BattleScript::default(),
+        )?;
+        let win_flag = battle_is_attacker_win(&battle_result);
+        let recovery = apply_post_battle_recovery(
+            &mut character,
+            &battle_result,
+            self.post_battle_recovery_rule(),
+        );
+        let branch = battle_branch(
+            &battle_result,
+            &encounter.win,
+            &encounter.lose,
+            encounter.draw.as_ref(),
+        );
+        let panel = character_state_to_panel(&character);
+        let filtered = filter_rewards_for_panel(
+            &panel,
+            &branch.rewards,
+            &self.content.manual_manager,
+            &start_trait_pool,
+            &self.duplicate_rewards(),
+            self.challenge(),
+        );
+        self.apply_rewards_to_character(&mut character, &mut start_trait_pool, &filtered.rewards)?;
+
+        let event_id = format!("{}{}", ECHO_EVENT_PREFIX, past.id);
+        {
+            let runtime = self
+                .game_runtime
+                .as_mut()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            runtime.save.set_current_character(character);
+            runtime.save.start_trait_pool = start_trait_pool;
+            runtime.save.rng_state = rng_state;
+            runtime.save.day = day;
+            Self::record_adventure_event(&mut runtime.save, &event_id);
+            Self::record_battle_result(
+                &mut runtime.save,
+                StoryHistoryScope::Adventure,
+                &event_id,
+                &battle_result,
+                &past.name,
+                win_flag,
+            );
+            Self::record_quest_battle_win(&mut runtime.save, win_flag);
+            Self::record_current_story_event_if_ready(&mut runtime.save);
+        }
+
+        let text = match &branch.text {
+            Some(extra) => format!(
+                "{}\n{}",
+                encounter.text.replace("{name}", &past.name),
+                extra
+            ),
+            None => encounter.text.replace("{name}", &past.name),
+        };
+        let outcome = GameOutcome::Adventure {
+            name: format!("往昔之影·{}", past.name),
+            text: Some(text),
+            rewards: filtered.rewards,
+            duplicates: filtered.duplicates,
+            enemies: battle_enemy_outcomes(&battle_result),
+            battle_result: Some(battle_result),
+            win: Some(win_flag),
+            recovery: Some(recovery),
+        };
+        self.game_view(Some(outcome))
+    }
+
+    pub fn game_story_option(&mut self, option_id: String) -> WushenResult<GameResponse> {
+        self.story_action(|core| core.story_option(option_id))
+    }
+
+    fn story_option(&mut self, option_id: String) -> WushenResult<GameResponse> {
         let (storyline, event) = self.current_story_event()?;
         {
             let runtime = self
                 .game_runtime
                 .as_ref()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
             ensure_event_ready(runtime, &event)?;
+            if runtime.save.pending_victory_decision.as_deref() == Some(event.id.as_str()) {
+                return self.choose_victory_option(&storyline, &event, &option_id);
+            }
         }
 
-        let selected_next_id = {
+        let (selected_next_id, paid) = {
             let runtime = self
                 .game_runtime
                 .as_ref()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
             let panel = character_state_to_panel(&runtime.save.current_character);
             let options = match &event.content {
                 StoryEventContent::Decision { options, .. } => {
-                    EventManager::available_story_options(options, &panel, &self.manual_manager)
+                    EventManager::available_story_options(
+                        options,
+                        &panel,
+                        &self.content.manual_manager,
+                        &run_history(&runtime.save),
+                    )
+                }
+                _ => {
+                    return Err(WushenError::InvalidState(
+                        "当前事件不是抉择事件".to_string(),
+                    ))
                 }
-                _ => return Err("当前事件不是抉择事件".to_string()),
             };
 
             let selected = options
                 .into_iter()
                 .find(|opt| opt.id == option_id)
-                .ok_or_else(|| "无效的选项".to_string())?;
-            selected.next_event_id.clone()
+                .ok_or_else(|| WushenError::Invalid("无效的选项".to_string()))?;
+            let paid = if selected.costs.is_empty() {
+                None
+            } else {
+                let mut character = runtime.save.current_character.clone();
+                pay_option_costs(&mut character, &selected.costs)?;
+                Some(character)
+            };
+            (selected.next_event_id.clone(), paid)
         };
 
         {
             let runtime = self
                 .game_runtime
                 .as_mut()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            if let Some(character) = paid {
+                runtime.save.set_current_character(character);
+            }
             Self::record_decision(
                 &mut runtime.save,
                 StoryHistoryScope::Story,
@@ -1136,32 +2384,461 @@ impl WushenCore {
             );
             Self::advance_to_event(runtime, &storyline, &selected_next_id)?;
         }
-        let outcome = GameOutcome::Info {
-            message: "抉择已确认".to_string(),
+        let chapter_rewards = self.complete_chapter(&storyline, &event.id, &selected_next_id)?;
+        let outcome = match chapter_rewards {
+            Some((title, rewards)) => GameOutcome::Story {
+                text: Some(format!("抉择已确认，{}", chapter_complete_text(&title))),
+                rewards: rewards.rewards,
+                duplicates: rewards.duplicates,
+                enemies: Vec::new(),
+                battle_result: None,
+                win: None,
+                recovery: None,
+                defeat: None,
+            },
+            None => GameOutcome::Info {
+                message: "抉择已确认".to_string(),
+            },
         };
         self.game_view(Some(outcome))
     }
 
-    pub fn game_equip_manual(
+    /// 战后抉择：发放选项奖励并推进到选项（或胜利分支）指定的事件
+    fn choose_victory_option(
         &mut self,
-        manual_id: String,
-        manual_type: String,
-    ) -> Result<GameResponse, String> {
-        let mut character = {
+        storyline: &Storyline,
+        event: &StoryEvent,
+        option_id: &str,
+    ) -> WushenResult<GameResponse> {
+        let (option, next_event_id) = match &event.content {
+            StoryEventContent::Battle {
+                win,
+                victory_decision: Some(decision),
+                ..
+            } => {
+                let option = decision
+                    .options
+                    .iter()
+                    .find(|option| option.id == option_id)
+                    .ok_or_else(|| WushenError::Invalid("无效的选项".to_string()))?;
+                let next = option
+                    .next_event_id
+                    .clone()
+                    .unwrap_or_else(|| win.next_event_id.clone());
+                (option, next)
+            }
+            _ => {
+                return Err(WushenError::InvalidState(
+                    "当前事件没有战后抉择".to_string(),
+                ))
+            }
+        };
+
+        let (mut character, mut start_trait_pool) = {
             let runtime = self
                 .game_runtime
                 .as_ref()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
-            runtime.save.current_character.clone()
-        };
-
-        let mut panel = character_state_to_panel(&character);
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            (
+                runtime.save.current_character.clone(),
+                runtime.save.start_trait_pool.clone(),
+            )
+        };
+        let panel = character_state_to_panel(&character);
+        let filtered = filter_rewards_for_panel(
+            &panel,
+            &option.rewards,
+            &self.content.manual_manager,
+            &start_trait_pool,
+            &self.duplicate_rewards(),
+            self.challenge(),
+        );
+        self.apply_rewards_to_character(&mut character, &mut start_trait_pool, &filtered.rewards)?;
+        {
+            let runtime = self
+                .game_runtime
+                .as_mut()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            runtime.save.set_current_character(character);
+            runtime.save.start_trait_pool = start_trait_pool;
+            runtime.save.pending_victory_decision = None;
+            Self::record_decision(
+                &mut runtime.save,
+                StoryHistoryScope::Story,
+                &event.id,
+                option.id.clone(),
+            );
+            Self::advance_to_event(runtime, storyline, &next_event_id)?;
+        }
+        let (text, rewards) = with_chapter_rewards(
+            "抉择已确认",
+            filtered,
+            self.complete_chapter(storyline, &event.id, &next_event_id)?,
+        );
+        let outcome = GameOutcome::Story {
+            text: Some(text),
+            rewards: rewards.rewards,
+            duplicates: rewards.duplicates,
+            enemies: Vec::new(),
+            battle_result: None,
+            win: None,
+            recovery: None,
+            defeat: None,
+        };
+        self.game_view(Some(outcome))
+    }
+
+    /// 佩戴称号（为 None 时卸下当前称号）
+    pub fn game_equip_title(&mut self, title_id: Option<String>) -> WushenResult<GameResponse> {
+        self.transaction(|core| core.equip_title(title_id))
+    }
+
+    fn equip_title(&mut self, title_id: Option<String>) -> WushenResult<GameResponse> {
+        self.check_action(ActionKind::EquipTitle)?;
+        let runtime = self
+            .game_runtime
+            .as_mut()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        runtime
+            .save
+            .current_character
+            .wear_title(title_id.as_deref())?;
+        let message = match runtime.save.current_character.current_title() {
+            Some(title) => format!("已佩戴称号「{}」", title.name),
+            None => "已卸下称号".to_string(),
+        };
+        self.charge_action(ActionKind::EquipTitle)?;
+        self.game_view(Some(GameOutcome::Info { message }))
+    }
+
+    /// 使用背包中的物品（消耗一件，结算恢复、限时增益与属性提升）
+    pub fn game_use_item(&mut self, item_id: String) -> WushenResult<GameResponse> {
+        self.transaction(|core| core.use_item(&item_id))
+    }
+
+    fn use_item(&mut self, item_id: &str) -> WushenResult<GameResponse> {
+        self.check_action(ActionKind::UseItem)?;
+        let item = self
+            .content
+            .item_manager
+            .get_item(item_id)
+            .cloned()
+            .ok_or_else(|| WushenError::NotFound(format!("物品 {} 不存在", item_id)))?;
+        if !item.is_usable() {
+            return Err(WushenError::InvalidState(format!(
+                "「{}」无法使用",
+                item.name
+            )));
+        }
+        let (mut character, mut start_trait_pool) = {
+            let runtime = self
+                .game_runtime
+                .as_ref()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            (
+                runtime.save.current_character.clone(),
+                runtime.save.start_trait_pool.clone(),
+            )
+        };
+
+        let mut panel = character_state_to_panel(&character);
+        if !panel.remove_item(item_id, 1) {
+            return Err(WushenError::InvalidState(format!(
+                "背包中没有「{}」",
+                item.name
+            )));
+        }
+        let (hp, qi) = item.heal_amount();
+        panel.hp = (panel.hp + hp).min(panel.max_hp);
+        panel.qi = (panel.qi + qi).min(panel.max_qi);
+        update_character_from_panel(&mut character, &panel);
+        self.apply_rewards_to_character(
+            &mut character,
+            &mut start_trait_pool,
+            &item.effect_rewards(),
+        )?;
+
+        let runtime = self
+            .game_runtime
+            .as_mut()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        runtime.save.set_current_character(character);
+        runtime.save.start_trait_pool = start_trait_pool;
+        self.charge_action(ActionKind::UseItem)?;
+        self.game_view(Some(GameOutcome::Info {
+            message: format!("使用了「{}」", item.name),
+        }))
+    }
+
+    /// 按配方炼制：消耗背包中的材料，成品放入背包
+    pub fn game_craft(&mut self, recipe_id: String) -> WushenResult<GameResponse> {
+        self.transaction(|core| core.craft(&recipe_id))
+    }
+
+    fn craft(&mut self, recipe_id: &str) -> WushenResult<GameResponse> {
+        self.check_action(ActionKind::Craft)?;
+        let recipe = self
+            .content
+            .item_manager
+            .get_recipe(recipe_id)
+            .cloned()
+            .ok_or_else(|| WushenError::NotFound(format!("炼制配方 {} 不存在", recipe_id)))?;
+        let item_name = |id: &str| {
+            self.content
+                .item_manager
+                .get_item(id)
+                .map_or_else(|| id.to_string(), |item| item.name.clone())
+        };
+        let mut character = {
+            let runtime = self
+                .game_runtime
+                .as_ref()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            runtime.save.current_character.clone()
+        };
+
+        let mut panel = character_state_to_panel(&character);
+        if !EventManager::is_condition_met(&recipe.condition, &panel, &self.content.manual_manager)
+        {
+            return Err(WushenError::ConditionNotMet(format!(
+                "不满足「{}」的炼制条件",
+                recipe.name
+            )));
+        }
+        if let Some(short) = recipe
+            .ingredients
+            .iter()
+            .find(|stack| panel.item_count(&stack.id) < stack.count)
+        {
+            return Err(WushenError::ConditionNotMet(format!(
+                "材料不足：「{}」需要 {} 件，背包中有 {} 件",
+                item_name(&short.id),
+                short.count,
+                panel.item_count(&short.id)
+            )));
+        }
+        for stack in &recipe.ingredients {
+            panel.remove_item(&stack.id, stack.count);
+        }
+        panel.add_item(&recipe.output.id, recipe.output.count);
+        update_character_from_panel(&mut character, &panel);
+        let message = format!(
+            "炼成「{}」×{}",
+            item_name(&recipe.output.id),
+            recipe.output.count
+        );
+
+        let runtime = self
+            .game_runtime
+            .as_mut()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        runtime.save.set_current_character(character);
+        self.charge_action(ActionKind::Craft)?;
+        self.game_view(Some(GameOutcome::Info { message }))
+    }
+
+    /// 当前角色的称号列表JSON
+    pub fn game_titles(&self) -> WushenResult<String> {
+        let runtime = self
+            .game_runtime
+            .as_ref()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        serde_json::to_string(&runtime.save.current_character.title_views())
+            .map_err(|e| WushenError::ParseError(format!("序列化称号列表失败: {}", e)))
+    }
+
+    /// 招收队伍成员（如门下弟子），新成员以初始状态加入队伍，不影响当前操控的角色
+    pub fn game_recruit_member(
+        &mut self,
+        character_id: String,
+        name: String,
+        three_d: ThreeDimensionalState,
+    ) -> WushenResult<GameResponse> {
+        check_three_d(&three_d)?;
+        self.transaction(|core| {
+            let runtime = core
+                .game_runtime
+                .as_mut()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            let message = format!("{} 加入了队伍", name);
+            runtime
+                .save
+                .add_party_member(new_character(character_id, name, three_d))?;
+            core.game_view(Some(GameOutcome::Info { message }))
+        })
+    }
+
+    /// 切换操控的队伍成员；只能在剧情节点之间切换（奇遇或战后抉择进行中时不可切换）
+    pub fn game_switch_character(&mut self, character_id: String) -> WushenResult<GameResponse> {
+        self.transaction(|core| {
+            let runtime = core
+                .game_runtime
+                .as_mut()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            if runtime.save.storyline_progress.is_none() {
+                return Err(WushenError::InvalidState(
+                    "剧情已结束，无法切换角色".to_string(),
+                ));
+            }
+            if runtime.save.active_adventure_id.is_some()
+                || runtime.save.pending_victory_decision.is_some()
+            {
+                return Err(WushenError::InvalidState(
+                    "当前事件尚未结束，无法切换角色".to_string(),
+                ));
+            }
+            runtime.save.switch_character(&character_id)?;
+            let message = format!("当前操控：{}", runtime.save.current_character.name);
+            core.game_view(Some(GameOutcome::Info { message }))
+        })
+    }
+
+    /// 增减队伍共享资源（delta 为负表示消耗，不足时报错）
+    pub fn game_adjust_resource(
+        &mut self,
+        resource_id: String,
+        delta: i64,
+    ) -> WushenResult<GameResponse> {
+        self.transaction(|core| {
+            let runtime = core
+                .game_runtime
+                .as_mut()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            let amount = runtime.save.party.adjust_resource(&resource_id, delta)?;
+            let message = format!("{}：{}", resource_id, amount);
+            core.game_view(Some(GameOutcome::Info { message }))
+        })
+    }
+
+    /// 预览奖励列表对当前角色的影响（按当前剧情线的重复奖励策略与挑战规则处理，不修改存档）
+    /// 参数：奖励列表JSON
+    /// 返回：奖励预览JSON
+    pub fn game_rewards_preview(&self, rewards_json: &str) -> WushenResult<String> {
+        let runtime = self
+            .game_runtime
+            .as_ref()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        let rewards: Vec<Reward> = serde_json::from_str(rewards_json)
+            .map_err(|e| WushenError::ParseError(format!("解析奖励列表失败: {}", e)))?;
+        let panel = character_state_to_panel(&runtime.save.current_character);
+        let duplicates = self.duplicate_rewards();
+        let challenge = self.challenge();
+        let filtered = filter_rewards_for_panel(
+            &panel,
+            &rewards,
+            &self.content.manual_manager,
+            &runtime.save.start_trait_pool,
+            &duplicates,
+            challenge,
+        );
+        let preview = apply_rewards_preview(
+            &panel,
+            &filtered.rewards,
+            Some(&self.content.manual_manager),
+            Some(&self.content.trait_manager),
+            &duplicates,
+            challenge
+                .map(|challenge| challenge.banned_manuals.as_slice())
+                .unwrap_or_default(),
+        )?;
+        serde_json::to_string(&preview)
+            .map_err(|e| WushenError::ParseError(format!("序列化奖励预览失败: {}", e)))
+    }
+
+    /// 奇遇图鉴；global 为其他存档合并而来的发现记录（JSON，可选）
+    pub fn game_adventure_collection(&self, global: Option<String>) -> WushenResult<String> {
+        let runtime = self
+            .game_runtime
+            .as_ref()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        let mut discovery = runtime.save.adventure_discovery.clone();
+        if let Some(global) = global {
+            discovery.merge(&AdventureDiscovery::from_json(&global)?);
+        }
+        let view = discovery.collection(self.content.event_manager.all_adventure_events());
+        serde_json::to_string(&view)
+            .map_err(|e| WushenError::ParseError(format!("序列化奇遇图鉴失败: {}", e)))
+    }
+
+    /// 武学图鉴；kind 为空时返回全部类别
+    pub fn game_codex(&self, kind: Option<String>) -> WushenResult<String> {
+        let runtime = self
+            .game_runtime
+            .as_ref()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        let kind = kind.as_deref().map(CodexKind::parse).transpose()?;
+        let manuals = &self.content.manual_manager;
+        let views: Vec<CodexEntryView> = runtime
+            .save
+            .codex
+            .entries_of(kind)
+            .map(|entry| {
+                let name = match entry.kind {
+                    CodexKind::Internal => manuals
+                        .get_internal(&entry.id)
+                        .map(|m| m.manual.name.clone()),
+                    CodexKind::AttackSkill => manuals
+                        .get_attack_skill(&entry.id)
+                        .map(|m| m.manual.name.clone()),
+                    CodexKind::DefenseSkill => manuals
+                        .get_defense_skill(&entry.id)
+                        .map(|m| m.manual.name.clone()),
+                    CodexKind::Trait => self
+                        .content
+                        .trait_manager
+                        .get_trait(&entry.id)
+                        .map(|t| t.name.clone()),
+                    CodexKind::Enemy => None,
+                };
+                CodexEntryView {
+                    kind: entry.kind,
+                    id: entry.id.clone(),
+                    name: name.unwrap_or_else(|| entry.id.clone()),
+                    first_seen_at: entry.first_seen_at,
+                    first_seen_day: entry.first_seen_day,
+                }
+            })
+            .collect();
+        serde_json::to_string(&views)
+            .map_err(|e| WushenError::ParseError(format!("序列化图鉴失败: {}", e)))
+    }
+
+    pub fn game_equip_manual(
+        &mut self,
+        manual_id: String,
+        manual_type: String,
+    ) -> WushenResult<GameResponse> {
+        self.transaction(|core| core.equip_manual(manual_id, manual_type))
+    }
+
+    fn equip_manual(
+        &mut self,
+        manual_id: String,
+        manual_type: String,
+    ) -> WushenResult<GameResponse> {
+        self.check_action(ActionKind::EquipManual)?;
+        if self.challenge().is_some_and(|c| c.bans_manual(&manual_id)) {
+            return Err(WushenError::ConditionNotMet(format!(
+                "功法 {} 已被挑战规则禁用",
+                manual_id
+            )));
+        }
+        let mut character = {
+            let runtime = self
+                .game_runtime
+                .as_ref()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            runtime.save.current_character.clone()
+        };
+
+        let mut panel = character_state_to_panel(&character);
         let (label, name, switched) = match manual_type.as_str() {
             "internal" => {
-                let mut executor = self.trait_manager.create_executor(&panel.traits);
+                let mut executor = self.content.trait_manager.create_executor(&panel.traits);
+                executor.disable_tags(self.disabled_entry_tags());
                 let from_id = panel.current_internal_id.clone();
                 if from_id.as_deref() != Some(manual_id.as_str()) {
-                    self.manual_manager.switch_internal(
+                    self.content.manual_manager.switch_internal(
                         from_id.as_deref(),
                         &manual_id,
                         &mut panel,
@@ -1169,6 +2846,7 @@ impl WushenCore {
                     )?;
                 }
                 let name = self
+                    .content
                     .manual_manager
                     .get_internal(&manual_id)
                     .map(|m| m.manual.name.clone())
@@ -1176,9 +2854,11 @@ impl WushenCore {
                 ("内功", name, true)
             }
             "attack_skill" => {
-                self.manual_manager
+                self.content
+                    .manual_manager
                     .equip_attack_skill(&manual_id, &mut panel)?;
                 let name = self
+                    .content
                     .manual_manager
                     .get_attack_skill(&manual_id)
                     .map(|m| m.manual.name.clone())
@@ -1186,16 +2866,18 @@ impl WushenCore {
                 ("攻击武技", name, false)
             }
             "defense_skill" => {
-                self.manual_manager
+                self.content
+                    .manual_manager
                     .equip_defense_skill(&manual_id, &mut panel)?;
                 let name = self
+                    .content
                     .manual_manager
                     .get_defense_skill(&manual_id)
                     .map(|m| m.manual.name.clone())
                     .unwrap_or_else(|| manual_id.clone());
                 ("防御武技", name, false)
             }
-            _ => return Err("未知的功法类型".to_string()),
+            _ => return Err(WushenError::Invalid("未知的功法类型".to_string())),
         };
 
         update_character_from_panel(&mut character, &panel);
@@ -1204,9 +2886,10 @@ impl WushenCore {
             let runtime = self
                 .game_runtime
                 .as_mut()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
-            runtime.save.current_character = character;
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            runtime.save.set_current_character(character);
         }
+        self.charge_action(ActionKind::EquipManual)?;
 
         let message = if switched && label == "内功" {
             format!("已转修{}：{}", label, name)
@@ -1217,108 +2900,417 @@ impl WushenCore {
         self.game_view(Some(outcome))
     }
 
-    pub fn game_story_battle(
-        &mut self,
-        attacker_qi_output_rate: Option<f64>,
-        defender_qi_output_rate: Option<f64>,
-    ) -> Result<GameResponse, String> {
-        let (storyline, event) = self.current_story_event()?;
+    pub fn game_accept_quest(&mut self, quest_id: String) -> WushenResult<GameResponse> {
+        self.transaction(|core| core.accept_quest(quest_id))
+    }
+
+    fn accept_quest(&mut self, quest_id: String) -> WushenResult<GameResponse> {
+        let quest = self
+            .content
+            .quest_manager
+            .get_quest(&quest_id)
+            .ok_or_else(|| WushenError::NotFound("悬赏任务不存在".to_string()))?;
         {
             let runtime = self
                 .game_runtime
                 .as_ref()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
-            ensure_event_ready(runtime, &event)?;
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            let save = &runtime.save;
+            if save.active_quests.iter().any(|p| p.quest_id == quest_id) {
+                return Err(WushenError::InvalidState("悬赏任务已接取".to_string()));
+            }
+            self.action_costs(save).check(
+                ActionKind::AcceptQuest,
+                save.current_character.action_points,
+            )?;
+            if !quest.repeatable && save.completed_quests.contains(&quest_id) {
+                return Err(WushenError::InvalidState("悬赏任务已完成".to_string()));
+            }
+            let panel = character_state_to_panel(&save.current_character);
+            if !EventManager::is_condition_met(
+                &quest.condition,
+                &panel,
+                &self.content.manual_manager,
+            ) {
+                return Err(WushenError::ConditionNotMet(
+                    "未满足悬赏任务接取条件".to_string(),
+                ));
+            }
         }
+        let message = format!("已接取悬赏：{}", quest.name);
         {
             let runtime = self
                 .game_runtime
                 .as_mut()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
-            Self::record_story_event(&mut runtime.save, &event.id);
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            runtime.save.active_quests.push(QuestProgress {
+                quest_id,
+                battles_won: 0,
+            });
         }
+        self.charge_action(ActionKind::AcceptQuest)?;
+        self.game_view(Some(GameOutcome::Info { message }))
+    }
 
-        let (text, enemy, win, lose) = match &event.content {
-            StoryEventContent::Battle {
-                text,
-                enemy,
-                win,
-                lose,
-            } => (text, enemy, win, lose),
-            _ => return Err("当前事件不是战斗事件".to_string()),
+    pub fn game_abandon_quest(&mut self, quest_id: String) -> WushenResult<GameResponse> {
+        self.transaction(|core| core.abandon_quest(quest_id))
+    }
+
+    fn abandon_quest(&mut self, quest_id: String) -> WushenResult<GameResponse> {
+        let name = self
+            .content
+            .quest_manager
+            .get_quest(&quest_id)
+            .map(|q| q.name.clone())
+            .unwrap_or_else(|| quest_id.clone());
+        self.check_action(ActionKind::AbandonQuest)?;
+        {
+            let runtime = self
+                .game_runtime
+                .as_mut()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            let before = runtime.save.active_quests.len();
+            runtime
+                .save
+                .active_quests
+                .retain(|p| p.quest_id != quest_id);
+            if runtime.save.active_quests.len() == before {
+                return Err(WushenError::InvalidState("悬赏任务未接取".to_string()));
+            }
+        }
+        self.charge_action(ActionKind::AbandonQuest)?;
+        let outcome = GameOutcome::Info {
+            message: format!("已放弃悬赏：{}", name),
         };
+        self.game_view(Some(outcome))
+    }
+
+    pub fn game_claim_quest(&mut self, quest_id: String) -> WushenResult<GameResponse> {
+        self.transaction(|core| core.claim_quest(quest_id))
+    }
 
+    fn claim_quest(&mut self, quest_id: String) -> WushenResult<GameResponse> {
+        let quest = self
+            .content
+            .quest_manager
+            .get_quest(&quest_id)
+            .cloned()
+            .ok_or_else(|| WushenError::NotFound("悬赏任务不存在".to_string()))?;
         let (mut character, mut start_trait_pool) = {
             let runtime = self
                 .game_runtime
                 .as_ref()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            let progress = runtime
+                .save
+                .active_quests
+                .iter()
+                .find(|p| p.quest_id == quest_id)
+                .ok_or_else(|| WushenError::InvalidState("悬赏任务未接取".to_string()))?;
+            if !QuestManager::is_completable(&quest, progress, &runtime.save.current_character) {
+                return Err(WushenError::ConditionNotMet(
+                    "悬赏任务目标尚未达成".to_string(),
+                ));
+            }
+            self.action_costs(&runtime.save).check(
+                ActionKind::ClaimQuest,
+                runtime.save.current_character.action_points,
+            )?;
             (
                 runtime.save.current_character.clone(),
                 runtime.save.start_trait_pool.clone(),
             )
         };
 
-        let battle_result = self.run_battle(
-            &character,
+        let panel = character_state_to_panel(&character);
+        let filtered = filter_rewards_for_panel(
+            &panel,
+            &quest.rewards,
+            &self.content.manual_manager,
+            &start_trait_pool,
+            &self.duplicate_rewards(),
+            self.challenge(),
+        );
+        self.apply_rewards_to_character(&mut character, &mut start_trait_pool, &filtered.rewards)?;
+
+        {
+            let runtime = self
+                .game_runtime
+                .as_mut()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            runtime.save.set_current_character(character);
+            runtime.save.start_trait_pool = start_trait_pool;
+            runtime
+                .save
+                .active_quests
+                .retain(|p| p.quest_id != quest_id);
+            runtime.save.completed_quests.push(quest_id);
+        }
+        self.charge_action(ActionKind::ClaimQuest)?;
+
+        let outcome = GameOutcome::Quest {
+            name: quest.name,
+            rewards: filtered.rewards,
+            duplicates: filtered.duplicates,
+        };
+        self.game_view(Some(outcome))
+    }
+
+    pub fn game_story_battle(
+        &mut self,
+        attacker_qi_output_rate: Option<f64>,
+        defender_qi_output_rate: Option<f64>,
+    ) -> WushenResult<GameResponse> {
+        self.story_action(|core| {
+            core.story_battle(attacker_qi_output_rate, defender_qi_output_rate)
+        })
+    }
+
+    fn story_battle(
+        &mut self,
+        attacker_qi_output_rate: Option<f64>,
+        defender_qi_output_rate: Option<f64>,
+    ) -> WushenResult<GameResponse> {
+        let (storyline, event) = self.current_story_battle_event()?;
+        let battle_engine =
+            self.story_battle_engine(&event, attacker_qi_output_rate, defender_qi_output_rate)?;
+        let battle_result = battle_result_value(battle_engine)?;
+        self.settle_story_battle(&storyline, &event, battle_result)
+    }
+
+    /// 当前可以开打的剧情战斗事件
+    fn current_story_battle_event(&self) -> WushenResult<(Storyline, StoryEvent)> {
+        let (storyline, event) = self.current_story_event()?;
+        let runtime = self
+            .game_runtime
+            .as_ref()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        ensure_event_ready(runtime, &event)?;
+        if runtime.save.pending_victory_decision.is_some() {
+            return Err(WushenError::InvalidState("请先完成战后抉择".to_string()));
+        }
+        if !matches!(event.content, StoryEventContent::Battle { .. }) {
+            return Err(WushenError::InvalidState(
+                "当前事件不是战斗事件".to_string(),
+            ));
+        }
+        Ok((storyline, event))
+    }
+
+    /// 按剧情战斗事件的配置构建战斗引擎（玩家为 Side A，尚未开打）
+    fn story_battle_engine(
+        &self,
+        event: &StoryEvent,
+        attacker_qi_output_rate: Option<f64>,
+        defender_qi_output_rate: Option<f64>,
+    ) -> WushenResult<BattleEngine> {
+        let StoryEventContent::Battle {
             enemy,
+            enemies,
+            handicaps,
+            environment,
+            interruptions,
+            training,
+            ..
+        } = &event.content
+        else {
+            return Err(WushenError::InvalidState(
+                "当前事件不是战斗事件".to_string(),
+            ));
+        };
+        let runtime = self
+            .game_runtime
+            .as_ref()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        self.battle_engine_against(
+            &runtime.save.current_character,
+            enemy.to_character_panel(),
             attacker_qi_output_rate,
             defender_qi_output_rate,
-        )?;
-        let win_flag = battle_is_attacker_win(&battle_result);
-        let rewards = if win_flag {
-            &win.rewards
+            BattleScript {
+                enemy: Some(enemy),
+                enemies,
+                interrupts: story_battle_interrupts(interruptions),
+                handicaps,
+                training: *training,
+                environment: environment.clone(),
+                ..BattleScript::default()
+            },
+        )
+    }
+
+    /// 按战斗结果结算剧情战斗：战后恢复、战败处理、奖励与分支推进
+    fn settle_story_battle(
+        &mut self,
+        storyline: &Storyline,
+        event: &StoryEvent,
+        battle_result: Value,
+    ) -> WushenResult<GameResponse> {
+        {
+            let runtime = self
+                .game_runtime
+                .as_mut()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            Self::record_story_event(&mut runtime.save, &event.id);
+        }
+
+        let (text, enemy, win, lose, draw, interruptions, training, victory_decision) =
+            match &event.content {
+                StoryEventContent::Battle {
+                    text,
+                    enemy,
+                    win,
+                    lose,
+                    draw,
+                    interruptions,
+                    training,
+                    victory_decision,
+                    ..
+                } => (
+                    text,
+                    enemy,
+                    win,
+                    lose,
+                    draw,
+                    interruptions,
+                    *training,
+                    victory_decision,
+                ),
+                _ => {
+                    return Err(WushenError::InvalidState(
+                        "当前事件不是战斗事件".to_string(),
+                    ))
+                }
+            };
+
+        let (mut character, mut start_trait_pool) = {
+            let runtime = self
+                .game_runtime
+                .as_ref()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            (
+                runtime.save.current_character.clone(),
+                runtime.save.start_trait_pool.clone(),
+            )
+        };
+
+        let interruption = battle_result["interruption"]
+            .as_str()
+            .and_then(|id| interruptions.iter().find(|i| i.id == id));
+        // 训练战斗没有失败，结束后总是进入胜利分支
+        let win_flag = training.is_some() || battle_is_attacker_win(&battle_result);
+        let recovery = apply_post_battle_recovery(
+            &mut character,
+            &battle_result,
+            self.post_battle_recovery_rule(),
+        );
+        let branch = if training.is_some() {
+            win
         } else {
-            &lose.rewards
+            battle_branch(&battle_result, win, lose, draw.as_ref())
+        };
+        // 对方获胜时按战败处理策略结算（重试与永久死亡不进入战败分支）
+        let defeat = (interruption.is_none()
+            && training.is_none()
+            && battle_result["result"].as_str() == Some("defender_win"))
+        .then(|| self.defeat_policy_rule());
+        if let Some(policy @ (DefeatPolicy::Retry { .. } | DefeatPolicy::Permadeath)) = &defeat {
+            return self.resolve_story_defeat(
+                storyline,
+                event,
+                character,
+                battle_result,
+                recovery,
+                &enemy.name,
+                policy.clone(),
+            );
+        }
+        let rewards = match interruption {
+            Some(interruption) => &interruption.rewards,
+            None => &branch.rewards,
         };
         let panel = character_state_to_panel(&character);
-        let filtered =
-            filter_rewards_for_panel(&panel, rewards, &self.manual_manager, &start_trait_pool);
-        self.apply_rewards_to_character(&mut character, &mut start_trait_pool, &filtered)?;
-        let next_event_id = if win_flag {
-            win.next_event_id.clone()
-        } else {
-            lose.next_event_id.clone()
+        let filtered = filter_rewards_for_panel(
+            &panel,
+            rewards,
+            &self.content.manual_manager,
+            &start_trait_pool,
+            &self.duplicate_rewards(),
+            self.challenge(),
+        );
+        self.apply_rewards_to_character(&mut character, &mut start_trait_pool, &filtered.rewards)?;
+        let next_event_id = match interruption {
+            Some(interruption) => interruption.next_event_id.clone(),
+            None => branch.next_event_id.clone(),
         };
+        // 战胜且配置了战后抉择时停留在当前事件，待抉择后再推进
+        let awaits_decision = interruption.is_none() && win_flag && victory_decision.is_some();
         {
             let runtime = self
                 .game_runtime
                 .as_mut()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
-            runtime.save.current_character = character;
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            runtime.save.set_current_character(character);
             runtime.save.start_trait_pool = start_trait_pool;
-            Self::record_battle_result(
-                &mut runtime.save,
-                StoryHistoryScope::Story,
-                &event.id,
-                win_flag,
-            );
-            Self::advance_to_event(runtime, &storyline, &next_event_id)?;
+            // 被打断的战斗与训练战斗不分胜负，不计入战斗记录
+            if interruption.is_none() && training.is_none() {
+                Self::record_battle_result(
+                    &mut runtime.save,
+                    StoryHistoryScope::Story,
+                    &event.id,
+                    &battle_result,
+                    &enemy.name,
+                    win_flag,
+                );
+                Self::record_quest_battle_win(&mut runtime.save, win_flag);
+            }
+            if awaits_decision {
+                runtime.save.pending_victory_decision = Some(event.id.clone());
+            } else {
+                Self::advance_to_event(runtime, storyline, &next_event_id)?;
+            }
         }
+        let text = interruption
+            .and_then(|interruption| interruption.text.as_ref())
+            .unwrap_or(text);
+        let chapter = if awaits_decision {
+            None
+        } else {
+            self.complete_chapter(storyline, &event.id, &next_event_id)?
+        };
+        let (text, filtered) = with_chapter_rewards(text, filtered, chapter);
 
         let outcome = GameOutcome::Story {
-            text: Some(text.clone()),
-            rewards: filtered,
+            text: Some(text),
+            rewards: filtered.rewards,
+            duplicates: filtered.duplicates,
+            enemies: battle_enemy_outcomes(&battle_result),
             battle_result: Some(battle_result),
-            win: Some(win_flag),
+            win: interruption.is_none().then_some(win_flag),
+            recovery: Some(recovery),
+            defeat,
         };
         self.game_view(Some(outcome))
     }
 
-    pub fn game_story_continue(&mut self) -> Result<GameResponse, String> {
+    pub fn game_story_continue(&mut self) -> WushenResult<GameResponse> {
+        self.story_action(|core| core.story_continue())
+    }
+
+    fn story_continue(&mut self) -> WushenResult<GameResponse> {
         let (storyline, event) = self.current_story_event()?;
         {
             let runtime = self
                 .game_runtime
                 .as_ref()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
             ensure_event_ready(runtime, &event)?;
         }
         {
             let runtime = self
                 .game_runtime
                 .as_mut()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
             Self::record_story_event(&mut runtime.save, &event.id);
         }
 
@@ -1328,40 +3320,165 @@ impl WushenCore {
                 rewards,
                 next_event_id,
             } => (text, rewards, next_event_id),
-            _ => return Err("当前事件不是剧情事件".to_string()),
+            _ => {
+                return Err(WushenError::InvalidState(
+                    "当前事件不是剧情事件".to_string(),
+                ))
+            }
         };
 
         let next_id = next_event_id
             .clone()
-            .ok_or_else(|| "剧情事件未指定后续事件".to_string())?;
+            .ok_or_else(|| WushenError::Invalid("剧情事件未指定后续事件".to_string()))?;
         let (mut character, mut start_trait_pool) = {
             let runtime = self
                 .game_runtime
                 .as_ref()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            (
+                runtime.save.current_character.clone(),
+                runtime.save.start_trait_pool.clone(),
+            )
+        };
+        let panel = character_state_to_panel(&character);
+        let filtered = filter_rewards_for_panel(
+            &panel,
+            rewards,
+            &self.content.manual_manager,
+            &start_trait_pool,
+            &self.duplicate_rewards(),
+            self.challenge(),
+        );
+        self.apply_rewards_to_character(&mut character, &mut start_trait_pool, &filtered.rewards)?;
+        {
+            let runtime = self
+                .game_runtime
+                .as_mut()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            runtime.save.set_current_character(character);
+            runtime.save.start_trait_pool = start_trait_pool;
+            Self::advance_to_event(runtime, &storyline, &next_id)?;
+        }
+        let (text, filtered) = with_chapter_rewards(
+            text,
+            filtered,
+            self.complete_chapter(&storyline, &event.id, &next_id)?,
+        );
+        let outcome = GameOutcome::Story {
+            text: Some(text),
+            rewards: filtered.rewards,
+            duplicates: filtered.duplicates,
+            enemies: Vec::new(),
+            battle_result: None,
+            win: None,
+            recovery: None,
+            defeat: None,
+        };
+        self.game_view(Some(outcome))
+    }
+
+    /// 进行属性检定：按检定结果发放分支奖励并推进到分支事件
+    pub fn game_story_check(&mut self) -> WushenResult<GameResponse> {
+        self.story_action(|core| core.story_check())
+    }
+
+    fn story_check(&mut self) -> WushenResult<GameResponse> {
+        let (storyline, event) = self.current_story_event()?;
+        {
+            let runtime = self
+                .game_runtime
+                .as_ref()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            ensure_event_ready(runtime, &event)?;
+        }
+
+        let (text, check, critical_success, success, failure) = match &event.content {
+            StoryEventContent::Check {
+                text,
+                check,
+                critical_success,
+                success,
+                failure,
+            } => (text, check, critical_success, success, failure),
+            _ => {
+                return Err(WushenError::InvalidState(
+                    "当前事件不是检定事件".to_string(),
+                ))
+            }
+        };
+
+        let (mut character, mut start_trait_pool, rng_state) = {
+            let runtime = self
+                .game_runtime
+                .as_ref()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
             (
                 runtime.save.current_character.clone(),
                 runtime.save.start_trait_pool.clone(),
+                runtime.save.rng_state,
             )
         };
         let panel = character_state_to_panel(&character);
-        let filtered =
-            filter_rewards_for_panel(&panel, rewards, &self.manual_manager, &start_trait_pool);
-        self.apply_rewards_to_character(&mut character, &mut start_trait_pool, &filtered)?;
+        let mut rng = SimpleRng::from_state(rng_state);
+        let mut value = check.stat.value(&panel);
+        if check.roll > 0.0 {
+            value += rng.next_f64() * check.roll;
+        }
+        let tier = check.resolve(value);
+        let branch = match tier {
+            CheckTier::CriticalSuccess => critical_success.as_ref().unwrap_or(success),
+            CheckTier::Success => success,
+            CheckTier::Failure => failure,
+        };
+
+        let filtered = filter_rewards_for_panel(
+            &panel,
+            &branch.rewards,
+            &self.content.manual_manager,
+            &start_trait_pool,
+            &self.duplicate_rewards(),
+            self.challenge(),
+        );
+        self.apply_rewards_to_character(&mut character, &mut start_trait_pool, &filtered.rewards)?;
+        let next_id = branch.next_event_id.clone();
         {
             let runtime = self
                 .game_runtime
                 .as_mut()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
-            runtime.save.current_character = character;
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            runtime.save.rng_state = rng.state();
+            runtime.save.set_current_character(character);
             runtime.save.start_trait_pool = start_trait_pool;
+            Self::record_story_event(&mut runtime.save, &event.id);
+            Self::record_decision(
+                &mut runtime.save,
+                StoryHistoryScope::Story,
+                &event.id,
+                tier.as_str().to_string(),
+            );
             Self::advance_to_event(runtime, &storyline, &next_id)?;
         }
+        let text = format!(
+            "{}检定（{:.1}）：{}。{}",
+            check.stat.label(),
+            value,
+            tier.label(),
+            branch.text.as_deref().unwrap_or(text)
+        );
+        let (text, filtered) = with_chapter_rewards(
+            &text,
+            filtered,
+            self.complete_chapter(&storyline, &event.id, &next_id)?,
+        );
         let outcome = GameOutcome::Story {
-            text: Some(text.clone()),
-            rewards: filtered,
+            text: Some(text),
+            rewards: filtered.rewards,
+            duplicates: filtered.duplicates,
+            enemies: Vec::new(),
             battle_result: None,
             win: None,
+            recovery: None,
+            defeat: None,
         };
         self.game_view(Some(outcome))
     }
@@ -1371,17 +3488,28 @@ impl WushenCore {
         option_id: String,
         attacker_qi_output_rate: Option<f64>,
         defender_qi_output_rate: Option<f64>,
-    ) -> Result<GameResponse, String> {
+    ) -> WushenResult<GameResponse> {
+        self.transaction(|core| {
+            core.adventure_option(option_id, attacker_qi_output_rate, defender_qi_output_rate)
+        })
+    }
+
+    fn adventure_option(
+        &mut self,
+        option_id: String,
+        attacker_qi_output_rate: Option<f64>,
+        defender_qi_output_rate: Option<f64>,
+    ) -> WushenResult<GameResponse> {
         let (adventure_id, mut character, mut start_trait_pool) = {
             let runtime = self
                 .game_runtime
                 .as_ref()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
             let adventure_id = runtime
                 .save
                 .active_adventure_id
                 .clone()
-                .ok_or_else(|| "当前没有可处理的奇遇".to_string())?;
+                .ok_or_else(|| WushenError::InvalidState("当前没有可处理的奇遇".to_string()))?;
             (
                 adventure_id,
                 runtime.save.current_character.clone(),
@@ -1389,145 +3517,561 @@ impl WushenCore {
             )
         };
         let event = self
+            .content
             .event_manager
             .get_adventure_event(&adventure_id)
-            .ok_or_else(|| "奇遇事件不存在".to_string())?;
+            .ok_or_else(|| WushenError::NotFound("奇遇事件不存在".to_string()))?;
 
         let panel = character_state_to_panel(&character);
-        let (text, rewards, battle_result, win_flag) = match &event.content {
+        let (text, rewards, battle_result, win_flag, recovery) = match &event.content {
             AdventureEventContent::Decision { options, .. } => {
                 let option = options
                     .iter()
                     .find(|opt| opt.id == option_id)
-                    .ok_or_else(|| "无效的选项".to_string())?;
-                if !EventManager::is_condition_met(&option.condition, &panel, &self.manual_manager)
+                    .ok_or_else(|| WushenError::Invalid("无效的选项".to_string()))?;
+                if !EventManager::is_condition_met(
+                    &option.condition,
+                    &panel,
+                    &self.content.manual_manager,
+                ) {
+                    return Err(WushenError::ConditionNotMet("选项条件不满足".to_string()));
+                }
+                if option.fallback
+                    && EventManager::available_adventure_options(
+                        options,
+                        &panel,
+                        &self.content.manual_manager,
+                    )
+                    .iter()
+                    .any(|o| !o.fallback)
                 {
-                    return Err("选项条件不满足".to_string());
+                    return Err(WushenError::ConditionNotMet(
+                        "还有其他选项可选，不能选择兜底选项".to_string(),
+                    ));
                 }
+                // 代价先于奖励结算，两者一并写回存档
+                pay_option_costs(&mut character, &option.costs)?;
+                let panel = character_state_to_panel(&character);
                 match &option.result {
                     AdventureOptionResult::Story { text, rewards } => {
                         let filtered = filter_rewards_for_panel(
                             &panel,
                             rewards,
-                            &self.manual_manager,
+                            &self.content.manual_manager,
                             &start_trait_pool,
+                            &self.duplicate_rewards(),
+                            self.challenge(),
                         );
                         self.apply_rewards_to_character(
                             &mut character,
                             &mut start_trait_pool,
-                            &filtered,
+                            &filtered.rewards,
                         )?;
-                        (Some(text.clone()), filtered, None, None)
+                        (Some(text.clone()), filtered, None, None, None)
                     }
                     AdventureOptionResult::Battle {
                         text,
                         enemy,
+                        enemies,
                         win,
                         lose,
+                        draw,
+                        handicaps,
+                        environment,
+                        ..
                     } => {
                         let battle_result = self.run_battle(
                             &character,
                             enemy,
+                            enemies,
+                            handicaps,
+                            environment,
                             attacker_qi_output_rate,
                             defender_qi_output_rate,
                         )?;
                         let win_flag = battle_is_attacker_win(&battle_result);
-                        let rewards = if win_flag {
-                            &win.rewards
-                        } else {
-                            &lose.rewards
-                        };
+                        let recovery = apply_post_battle_recovery(
+                            &mut character,
+                            &battle_result,
+                            self.post_battle_recovery_rule(),
+                        );
+                        let rewards =
+                            &battle_branch(&battle_result, win, lose, draw.as_ref()).rewards;
                         let filtered = filter_rewards_for_panel(
                             &panel,
                             rewards,
-                            &self.manual_manager,
+                            &self.content.manual_manager,
                             &start_trait_pool,
+                            &self.duplicate_rewards(),
+                            self.challenge(),
                         );
                         self.apply_rewards_to_character(
                             &mut character,
                             &mut start_trait_pool,
-                            &filtered,
+                            &filtered.rewards,
                         )?;
                         (
                             Some(text.clone()),
                             filtered,
                             Some(battle_result),
                             Some(win_flag),
+                            Some(recovery),
                         )
                     }
                 }
             }
-            _ => return Err("奇遇事件不是抉择类型".to_string()),
+            _ => {
+                return Err(WushenError::InvalidState(
+                    "奇遇事件不是抉择类型".to_string(),
+                ))
+            }
         };
 
         {
             let runtime = self
                 .game_runtime
                 .as_mut()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
             Self::record_decision(
                 &mut runtime.save,
                 StoryHistoryScope::Adventure,
                 &adventure_id,
                 option_id.clone(),
             );
-            if let Some(win_flag) = win_flag {
+            if let (Some(win_flag), Some(battle_result)) = (win_flag, battle_result.as_ref()) {
                 Self::record_battle_result(
                     &mut runtime.save,
                     StoryHistoryScope::Adventure,
                     &adventure_id,
+                    battle_result,
+                    battle_enemy_name(battle_result),
                     win_flag,
                 );
+                Self::record_quest_battle_win(&mut runtime.save, win_flag);
             }
-            runtime.save.current_character = character;
+            runtime.save.set_current_character(character);
             runtime.save.start_trait_pool = start_trait_pool;
             runtime.save.active_adventure_id = None;
+            runtime
+                .save
+                .adventure_discovery
+                .record_completed(&adventure_id);
             Self::record_current_story_event_if_ready(&mut runtime.save);
         }
 
         let outcome = GameOutcome::Adventure {
             name: event.name.clone(),
             text,
-            rewards,
+            rewards: rewards.rewards,
+            duplicates: rewards.duplicates,
+            enemies: battle_result
+                .as_ref()
+                .map(battle_enemy_outcomes)
+                .unwrap_or_default(),
             battle_result,
             win: win_flag,
+            recovery,
         };
         self.game_view(Some(outcome))
     }
 
-    pub fn game_finish(&mut self) -> Result<GameResponse, String> {
-        let (_storyline, event) = self.current_story_event()?;
+    /// 放弃进行中的奇遇：支付放弃代价，无法支付的部分免除；放弃的奇遇不计为已完成。
+    /// 仍有选项可选时按 `AdventureSkip` 消耗行动点，选项都不可选时免除行动点消耗
+    pub fn game_adventure_skip(&mut self) -> WushenResult<GameResponse> {
+        self.transaction(|core| core.adventure_skip())
+    }
+
+    fn adventure_skip(&mut self) -> WushenResult<GameResponse> {
+        let (adventure_id, mut character) = {
+            let runtime = self
+                .game_runtime
+                .as_ref()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            let adventure_id = runtime
+                .save
+                .active_adventure_id
+                .clone()
+                .ok_or_else(|| WushenError::InvalidState("当前没有可处理的奇遇".to_string()))?;
+            (adventure_id, runtime.save.current_character.clone())
+        };
+        // 内容变更后奇遇可能已不存在，此时直接放弃
+        let event = self
+            .content
+            .event_manager
+            .get_adventure_event(&adventure_id);
+        // 只有无路可走时才能免费放弃，否则放弃与做出选择一样占用行动
+        let stuck = match event.map(|event| &event.content) {
+            Some(AdventureEventContent::Decision { options, .. }) => {
+                let panel = character_state_to_panel(&character);
+                EventManager::available_adventure_options(
+                    options,
+                    &panel,
+                    &self.content.manual_manager,
+                )
+                .is_empty()
+            }
+            Some(_) => false,
+            None => true,
+        };
+        if !stuck {
+            self.check_action(ActionKind::AdventureSkip)?;
+        }
+        let mut paid = Vec::new();
+        if let Some(AdventureEventContent::Decision { skip_costs, .. }) =
+            event.map(|event| &event.content)
+        {
+            let mut panel = character_state_to_panel(&character);
+            for cost in skip_costs {
+                if apply_costs(&mut panel, std::slice::from_ref(cost)).is_ok() {
+                    paid.push(cost.describe());
+                }
+            }
+            update_character_from_panel(&mut character, &panel);
+        }
+        let name = event.map_or_else(|| adventure_id.clone(), |event| event.name.clone());
+
+        {
+            let runtime = self
+                .game_runtime
+                .as_mut()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            runtime.save.set_current_character(character);
+            runtime.save.active_adventure_id = None;
+            // 触发时记下的待决记录标记为放弃，不满足「已完成奇遇」前置条件
+            if let Some(record) = runtime.save.story_history.iter_mut().rev().find(|record| {
+                record.scope == StoryHistoryScope::Adventure
+                    && record.event_id == adventure_id
+                    && record.option_id.is_none()
+            }) {
+                record.skipped = true;
+            }
+            Self::record_current_story_event_if_ready(&mut runtime.save);
+        }
+        if !stuck {
+            self.charge_action(ActionKind::AdventureSkip)?;
+        }
+
+        let message = if paid.is_empty() {
+            format!("放弃了奇遇「{}」", name)
+        } else {
+            format!("放弃了奇遇「{}」，付出代价：{}", name, paid.join("，"))
+        };
+        self.game_view(Some(GameOutcome::Info { message }))
+    }
+
+    pub fn game_finish(&mut self) -> WushenResult<GameResponse> {
+        self.transaction(|core| core.finish())
+    }
+
+    fn finish(&mut self) -> WushenResult<GameResponse> {
+        let (storyline, event) = self.current_story_event()?;
         {
             let runtime = self
                 .game_runtime
                 .as_ref()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
             ensure_event_ready(runtime, &event)?;
         }
 
         if !matches!(event.node_type, StoryNodeType::End) {
-            return Err("当前事件不是结局".to_string());
+            return Err(WushenError::InvalidState("当前事件不是结局".to_string()));
         }
+        let chronicle = {
+            let runtime = self
+                .game_runtime
+                .as_ref()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            self.build_chronicle(&runtime.save, &storyline, &event)
+        };
+        let character = {
+            let runtime = self
+                .game_runtime
+                .as_mut()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            let mut character = runtime.save.current_character.clone();
+            character.chronicle = Some(chronicle);
+            runtime.save.completed_characters.push(character.clone());
+            runtime.save.storyline_progress = None;
+            runtime.save.active_adventure_id = None;
+            character
+        };
+
+        let outcome = GameOutcome::Finished {
+            message: "剧情已完成".to_string(),
+            character,
+        };
+        self.game_view(Some(outcome))
+    }
+
+    /// 根据剧情历史生成角色传记
+    fn completed_character(&self, index: usize) -> WushenResult<&CharacterState> {
+        let runtime = self
+            .game_runtime
+            .as_ref()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        runtime
+            .save
+            .completed_characters
+            .get(index)
+            .ok_or_else(|| WushenError::NotFound(format!("已完成角色 {} 不存在", index)))
+    }
+
+    /// 列出已完成角色摘要（返回JSON数组）
+    pub fn list_completed_characters(&self) -> WushenResult<String> {
+        let runtime = self
+            .game_runtime
+            .as_ref()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        let list: Vec<_> = runtime
+            .save
+            .completed_characters
+            .iter()
+            .enumerate()
+            .map(|(index, character)| character.completed_summary(index))
+            .collect();
+        serde_json::to_string(&list)
+            .map_err(|e| WushenError::ParseError(format!("序列化已完成角色列表失败: {}", e)))
+    }
+
+    /// 获取已完成角色的完整最终状态（返回JSON）
+    pub fn get_completed_character(&self, index: usize) -> WushenResult<String> {
+        let character = self.completed_character(index)?;
+        serde_json::to_string(character)
+            .map_err(|e| WushenError::ParseError(format!("序列化已完成角色失败: {}", e)))
+    }
+
+    /// 将已完成角色导出为独立的敌人模板JSON（可用作切磋对手）
+    pub fn export_completed_character(&self, index: usize) -> WushenResult<String> {
+        let template = self.completed_character(index)?.to_enemy_template();
+        serde_json::to_string_pretty(&template)
+            .map_err(|e| WushenError::ParseError(format!("序列化敌人模板失败: {}", e)))
+    }
+
+    /// 导出存档当前角色的构筑（三维、特性、功法及等级，不含剧情状态）
+    pub fn export_character_build(&self, save: &SaveGame) -> WushenResult<String> {
+        let build = CharacterBuild::from_character(&save.current_character);
+        serde_json::to_string(&build)
+            .map_err(|e| WushenError::ParseError(format!("序列化角色构筑失败: {}", e)))
+    }
+
+    /// 导出进行中存档的角色构筑
+    pub fn game_export_character_build(&self) -> WushenResult<String> {
+        let runtime = self
+            .game_runtime
+            .as_ref()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        self.export_character_build(&runtime.save)
+    }
+
+    /// 导入角色构筑：按已加载的内容包校验后实例化为敌人模板（用于切磋或比武）
+    pub fn import_character_build(&self, json: &str) -> WushenResult<String> {
+        let build: CharacterBuild = serde_json::from_str(json)
+            .map_err(|e| WushenError::ParseError(format!("解析角色构筑失败: {}", e)))?;
+        build.validate(&self.content.trait_manager, &self.content.manual_manager)?;
+        serde_json::to_string_pretty(&build.to_enemy_template())
+            .map_err(|e| WushenError::ParseError(format!("序列化敌人模板失败: {}", e)))
+    }
+
+    pub fn game_delete_completed_character(&mut self, index: usize) -> WushenResult<GameResponse> {
+        self.transaction(|core| core.delete_completed_character(index))
+    }
+
+    fn delete_completed_character(&mut self, index: usize) -> WushenResult<GameResponse> {
+        let name = {
+            let runtime = self
+                .game_runtime
+                .as_mut()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            if index >= runtime.save.completed_characters.len() {
+                return Err(WushenError::NotFound(format!(
+                    "已完成角色 {} 不存在",
+                    index
+                )));
+            }
+            runtime.save.completed_characters.remove(index).name
+        };
+        let outcome = GameOutcome::Info {
+            message: format!("已删除角色：{}", name),
+        };
+        self.game_view(Some(outcome))
+    }
+
+    /// 按重试或永久死亡策略结算剧情战斗的战败
+    #[allow(clippy::too_many_arguments)]
+    fn resolve_story_defeat(
+        &mut self,
+        storyline: &Storyline,
+        event: &StoryEvent,
+        mut character: CharacterState,
+        battle_result: Value,
+        recovery: BattleRecovery,
+        enemy_name: &str,
+        policy: DefeatPolicy,
+    ) -> WushenResult<GameResponse> {
+        let mut penalty = 0;
+        let text = match &policy {
+            DefeatPolicy::Retry {
+                injury,
+                injury_actions,
+                action_points_lost,
+            } => {
+                if !injury.is_empty() {
+                    character.add_timed_buff(TimedBuff {
+                        id: "defeat_injury".to_string(),
+                        name: "负伤".to_string(),
+                        entries: injury.clone(),
+                        remaining_actions: *injury_actions,
+                    });
+                }
+                penalty = *action_points_lost;
+                "战败负伤，可整顿后再次挑战".to_string()
+            }
+            _ => format!("败于{}之手，本局结束", enemy_name),
+        };
         {
             let runtime = self
                 .game_runtime
                 .as_mut()
-                .ok_or_else(|| "游戏尚未初始化".to_string())?;
-            runtime
-                .save
-                .completed_characters
-                .push(runtime.save.current_character.clone());
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            runtime.save.set_current_character(character);
+            runtime.save.action_point_penalty += penalty;
+            Self::record_battle_result(
+                &mut runtime.save,
+                StoryHistoryScope::Story,
+                &event.id,
+                &battle_result,
+                enemy_name,
+                false,
+            );
+            Self::record_quest_battle_win(&mut runtime.save, false);
+        }
+        if matches!(policy, DefeatPolicy::Permadeath) {
+            let chronicle = {
+                let runtime = self
+                    .game_runtime
+                    .as_ref()
+                    .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+                self.build_chronicle(&runtime.save, storyline, event)
+            };
+            let runtime = self
+                .game_runtime
+                .as_mut()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            let mut character = runtime.save.current_character.clone();
+            character.chronicle = Some(chronicle);
+            runtime.save.completed_characters.push(character);
             runtime.save.storyline_progress = None;
             runtime.save.active_adventure_id = None;
         }
 
-        let outcome = GameOutcome::Info {
-            message: "剧情已完成".to_string(),
+        let outcome = GameOutcome::Story {
+            text: Some(text),
+            rewards: Vec::new(),
+            duplicates: Vec::new(),
+            enemies: battle_enemy_outcomes(&battle_result),
+            battle_result: Some(battle_result),
+            win: Some(false),
+            recovery: Some(recovery),
+            defeat: Some(policy),
         };
         self.game_view(Some(outcome))
     }
 
-    fn build_game_view(&self, runtime: &GameRuntime) -> Result<GameView, String> {
+    /// 根据剧情历史生成角色传记
+    fn build_chronicle(
+        &self,
+        save: &SaveGame,
+        storyline: &Storyline,
+        ending: &StoryEvent,
+    ) -> Chronicle {
+        let mut chronicle = Chronicle {
+            storyline_id: storyline.id.clone(),
+            storyline_name: storyline.name.clone(),
+            beats: Vec::new(),
+            strongest_battle: None,
+            ending_event_id: ending.id.clone(),
+            ending_name: ending.name.clone(),
+            days: save.day,
+            adventures: 0,
+            battles_won: 0,
+            battles_lost: 0,
+            finished_at: now_timestamp(),
+            summary: String::new(),
+        };
+
+        for record in &save.story_history {
+            let (event_name, enemy) = match record.scope {
+                StoryHistoryScope::Story => {
+                    let Some(event) = storyline.events.iter().find(|e| e.id == record.event_id)
+                    else {
+                        continue;
+                    };
+                    let choice = match (&event.content, &record.option_id) {
+                        (StoryEventContent::Decision { options, .. }, Some(option_id)) => options
+                            .iter()
+                            .find(|o| &o.id == option_id)
+                            .map(|o| o.text.clone()),
+                        _ => None,
+                    };
+                    chronicle.beats.push(ChronicleBeat {
+                        event_id: event.id.clone(),
+                        name: event.name.clone(),
+                        choice,
+                        battle_win: record.battle_win,
+                    });
+                    let enemy = match &event.content {
+                        StoryEventContent::Battle { enemy, .. } => Some(enemy),
+                        _ => None,
+                    };
+                    (event.name.clone(), enemy)
+                }
+                StoryHistoryScope::Adventure => {
+                    chronicle.adventures += 1;
+                    let Some(event) = self
+                        .content
+                        .event_manager
+                        .get_adventure_event(&record.event_id)
+                    else {
+                        continue;
+                    };
+                    let enemy = match &event.content {
+                        AdventureEventContent::Battle { enemy, .. } => Some(enemy),
+                        AdventureEventContent::Decision { options, .. } => record
+                            .option_id
+                            .as_ref()
+                            .and_then(|id| options.iter().find(|o| &o.id == id))
+                            .and_then(|o| match &o.result {
+                                AdventureOptionResult::Battle { enemy, .. } => Some(enemy),
+                                _ => None,
+                            }),
+                        _ => None,
+                    };
+                    (event.name.clone(), enemy)
+                }
+            };
+            if let (Some(enemy), Some(win)) = (enemy, record.battle_win) {
+                chronicle.record_battle(ChronicleBattle {
+                    scope: record.scope,
+                    event_id: record.event_id.clone(),
+                    event_name,
+                    enemy_name: enemy.name.clone(),
+                    enemy_power: enemy.three_d.comprehension
+                        + enemy.three_d.bone_structure
+                        + enemy.three_d.physique,
+                    win,
+                });
+            }
+        }
+
+        let template = storyline
+            .chronicle_template
+            .as_deref()
+            .unwrap_or(DEFAULT_CHRONICLE_TEMPLATE);
+        chronicle.summary = chronicle.render_summary(&save.current_character.name, template);
+        chronicle
+    }
+
+    fn build_game_view(&self, runtime: &GameRuntime) -> WushenResult<GameView> {
         let mut phase = GamePhase::Completed;
         let mut story_event_view = None;
         let mut adventure_view = None;
@@ -1536,22 +4080,34 @@ impl WushenCore {
             .save
             .storyline_progress
             .as_ref()
-            .and_then(|p| self.event_manager.get_storyline(&p.storyline_id))
+            .and_then(|p| self.content.event_manager.get_storyline(&p.storyline_id))
             .map(|s| StorylineSummary {
                 id: s.id.clone(),
                 name: s.name.clone(),
             });
+        let chapter_view = runtime.save.storyline_progress.as_ref().and_then(|p| {
+            let storyline = self.content.event_manager.get_storyline(&p.storyline_id)?;
+            let (index, chapter) = storyline.chapter_of(&p.event_id)?;
+            Some(ChapterView {
+                id: chapter.id.clone(),
+                title: chapter.title.clone(),
+                summary: chapter.summary.clone(),
+                index,
+                total: storyline.chapters.len(),
+            })
+        });
 
         if let Some(progress) = &runtime.save.storyline_progress {
             let storyline = self
+                .content
                 .event_manager
                 .get_storyline(&progress.storyline_id)
-                .ok_or_else(|| "剧情线不存在".to_string())?;
+                .ok_or_else(|| WushenError::NotFound("剧情线不存在".to_string()))?;
             let event = storyline
                 .events
                 .iter()
                 .find(|e| e.id == progress.event_id)
-                .ok_or_else(|| "事件不存在".to_string())?;
+                .ok_or_else(|| WushenError::NotFound("事件不存在".to_string()))?;
             current_event_summary = Some(StoryEventSummary {
                 id: event.id.clone(),
                 name: event.name.clone(),
@@ -1560,29 +4116,36 @@ impl WushenCore {
 
             if let Some(adventure_id) = &runtime.save.active_adventure_id {
                 let adventure = self
+                    .content
                     .event_manager
                     .get_adventure_event(adventure_id)
-                    .ok_or_else(|| "奇遇事件不存在".to_string())?;
-                if let AdventureEventContent::Decision { text, options } = &adventure.content {
+                    .ok_or_else(|| WushenError::NotFound("奇遇事件不存在".to_string()))?;
+                if let AdventureEventContent::Decision {
+                    text,
+                    options,
+                    skip_costs,
+                } = &adventure.content
+                {
                     let panel = character_state_to_panel(&runtime.save.current_character);
-                    let mut available = Vec::new();
-                    for option in options {
-                        if EventManager::is_condition_met(
-                            &option.condition,
-                            &panel,
-                            &self.manual_manager,
-                        ) {
-                            available.push(AdventureOptionView {
-                                id: option.id.clone(),
-                                text: option.text.clone(),
-                            });
-                        }
-                    }
+                    let available = EventManager::available_adventure_options(
+                        options,
+                        &panel,
+                        &self.content.manual_manager,
+                    )
+                    .into_iter()
+                    .map(|option| AdventureOptionView {
+                        id: option.id.clone(),
+                        text: option.text.clone(),
+                        costs: option.costs.clone(),
+                    })
+                    .collect();
                     adventure_view = Some(AdventureDecisionView {
                         id: adventure.id.clone(),
                         name: adventure.name.clone(),
                         text: text.clone(),
                         options: available,
+                        skip_costs: skip_costs.clone(),
+                        presentation: adventure.presentation.clone(),
                     });
                     phase = GamePhase::AdventureDecision;
                 }
@@ -1592,46 +4155,130 @@ impl WushenCore {
                 phase = GamePhase::Action;
             } else {
                 let panel = character_state_to_panel(&runtime.save.current_character);
-                story_event_view = Some(build_story_event_view(
+                let mut view = build_story_event_view(
                     event,
                     &panel,
-                    &self.manual_manager,
+                    &self.content.manual_manager,
                     &runtime.save.start_trait_pool,
-                ));
-                phase = GamePhase::Story;
+                    &self.duplicate_rewards(),
+                    runtime.save.challenge.as_ref(),
+                    &run_history(&runtime.save),
+                );
+                if runtime.save.pending_victory_decision.as_deref() == Some(event.id.as_str()) {
+                    if let Some(content) = victory_decision_view(event) {
+                        view.content = content;
+                    }
+                }
+                if let StoryEventContentView::Battle {
+                    unresolved_manuals, ..
+                } = &mut view.content
+                {
+                    *unresolved_manuals = self.story_battle_unresolved_manuals(event, &panel);
+                }
+                phase = match &view.content {
+                    StoryEventContentView::Decision { options, .. } if options.is_empty() => {
+                        GamePhase::Blocked
+                    }
+                    _ => GamePhase::Story,
+                };
+                story_event_view = Some(view);
             }
         }
 
         Ok(GameView {
             save: runtime.save.clone(),
             storyline: storyline_summary,
+            chapter: chapter_view,
             phase,
             current_event: current_event_summary,
             story_event: story_event_view,
             adventure: adventure_view,
+            quests: self.build_quest_views(&runtime.save),
+            calendar: self.calendar_config(&runtime.save).view(runtime.save.day),
         })
     }
 
-    fn current_story_event(&self) -> Result<(Storyline, StoryEvent), String> {
+    fn build_quest_views(&self, save: &SaveGame) -> Vec<QuestView> {
+        let character = &save.current_character;
+        let panel = character_state_to_panel(character);
+        let mut quests = self.content.quest_manager.all_quests();
+        quests.sort_by(|a, b| a.id.cmp(&b.id));
+        let mut views = Vec::new();
+        for quest in quests {
+            let active = save.active_quests.iter().find(|p| p.quest_id == quest.id);
+            let idle = QuestProgress {
+                quest_id: quest.id.clone(),
+                battles_won: 0,
+            };
+            let progress = active.unwrap_or(&idle);
+            let status = if active.is_some() {
+                if QuestManager::is_completable(quest, progress, character) {
+                    QuestStatus::Completable
+                } else {
+                    QuestStatus::Active
+                }
+            } else if !quest.repeatable && save.completed_quests.contains(&quest.id) {
+                QuestStatus::Completed
+            } else if EventManager::is_condition_met(
+                &quest.condition,
+                &panel,
+                &self.content.manual_manager,
+            ) {
+                QuestStatus::Available
+            } else {
+                continue;
+            };
+            let objectives = quest
+                .objectives
+                .iter()
+                .map(|objective| {
+                    let (current, target) =
+                        QuestManager::objective_progress(objective, progress, character);
+                    QuestObjectiveView {
+                        text: QuestManager::describe_objective(
+                            objective,
+                            &self.content.manual_manager,
+                            &self.content.trait_manager,
+                        ),
+                        current,
+                        target,
+                    }
+                })
+                .collect();
+            views.push(QuestView {
+                id: quest.id.clone(),
+                name: quest.name.clone(),
+                description: quest.description.clone(),
+                status,
+                repeatable: quest.repeatable,
+                objectives,
+                rewards: quest.rewards.clone(),
+            });
+        }
+        views
+    }
+
+    fn current_story_event(&self) -> WushenResult<(Storyline, StoryEvent)> {
         let runtime = self
             .game_runtime
             .as_ref()
-            .ok_or_else(|| "游戏尚未初始化".to_string())?;
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
         let progress = runtime
             .save
             .storyline_progress
             .as_ref()
-            .ok_or_else(|| "剧情线已完成".to_string())?;
+            .ok_or_else(|| WushenError::InvalidState("剧情线已完成".to_string()))?;
         let storyline = self
+            .content
             .event_manager
             .get_storyline(&progress.storyline_id)
-            .ok_or_else(|| "剧情线不存在".to_string())?;
+            .ok_or_else(|| WushenError::NotFound("剧情线不存在".to_string()))?;
         let event = storyline
             .events
             .iter()
             .find(|e| e.id == progress.event_id)
             .cloned()
-            .ok_or_else(|| "事件不存在".to_string())?;
+            .ok_or_else(|| WushenError::NotFound("事件不存在".to_string()))?;
         Ok((storyline.clone(), event))
     }
 
@@ -1639,17 +4286,29 @@ impl WushenCore {
         runtime: &mut GameRuntime,
         storyline: &Storyline,
         next_event_id: &str,
-    ) -> Result<(), String> {
+    ) -> WushenResult<()> {
         let next_event = storyline
             .events
             .iter()
             .find(|e| e.id == next_event_id)
-            .ok_or_else(|| "后续事件不存在".to_string())?;
-        let action_points = if next_event.node_type == StoryNodeType::Middle {
+            .ok_or_else(|| WushenError::NotFound("后续事件不存在".to_string()))?;
+        let mut action_points = if next_event.node_type == StoryNodeType::Middle {
             next_event.action_points
         } else {
             0
         };
+        let leaving_start = runtime
+            .save
+            .storyline_progress
+            .as_ref()
+            .is_some_and(|progress| progress.event_id == storyline.start_event_id);
+        if leaving_start && action_points > 0 {
+            action_points += storyline.starting_kit.action_points;
+        }
+        // 战败重试的惩罚从后续节点的行动点中扣除
+        let penalty = runtime.save.action_point_penalty.min(action_points);
+        action_points -= penalty;
+        runtime.save.action_point_penalty -= penalty;
         runtime.save.current_character.action_points = action_points;
         runtime.save.current_character.cultivation_history.clear();
         if let Some(progress) = runtime.save.storyline_progress.as_mut() {
@@ -1661,6 +4320,57 @@ impl WushenCore {
         Ok(())
     }
 
+    /// 从 `from_event_id` 前进到 `to_event_id` 跨越章节边界时，发放所离开章节的完成奖励
+    ///
+    /// 每个章节只发放一次；返回章节标题与实际发放的奖励
+    fn complete_chapter(
+        &mut self,
+        storyline: &Storyline,
+        from_event_id: &str,
+        to_event_id: &str,
+    ) -> WushenResult<Option<(String, ResolvedRewards)>> {
+        let Some((from_index, chapter)) = storyline.chapter_of(from_event_id) else {
+            return Ok(None);
+        };
+        if storyline
+            .chapter_of(to_event_id)
+            .is_some_and(|(to_index, _)| to_index == from_index)
+        {
+            return Ok(None);
+        }
+        let (mut character, mut start_trait_pool) = {
+            let runtime = self
+                .game_runtime
+                .as_ref()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            if runtime.save.completed_chapters.contains(&chapter.id) {
+                return Ok(None);
+            }
+            (
+                runtime.save.current_character.clone(),
+                runtime.save.start_trait_pool.clone(),
+            )
+        };
+        let panel = character_state_to_panel(&character);
+        let filtered = filter_rewards_for_panel(
+            &panel,
+            &chapter.rewards,
+            &self.content.manual_manager,
+            &start_trait_pool,
+            &self.duplicate_rewards(),
+            self.challenge(),
+        );
+        self.apply_rewards_to_character(&mut character, &mut start_trait_pool, &filtered.rewards)?;
+        let runtime = self
+            .game_runtime
+            .as_mut()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        runtime.save.set_current_character(character);
+        runtime.save.start_trait_pool = start_trait_pool;
+        runtime.save.completed_chapters.push(chapter.id.clone());
+        Ok(Some((chapter.title.clone(), filtered)))
+    }
+
     fn record_story_event(save: &mut SaveGame, event_id: &str) {
         if save
             .story_history
@@ -1675,7 +4385,12 @@ impl WushenCore {
             event_id: event_id.to_string(),
             option_id: None,
             battle_win: None,
+            battle_id: None,
+            skipped: false,
         });
+        if let Some(analytics) = save.analytics.as_mut() {
+            analytics.record_story_node(event_id, &save.current_character);
+        }
     }
 
     fn record_adventure_event(save: &mut SaveGame, event_id: &str) {
@@ -1684,6 +4399,8 @@ impl WushenCore {
             event_id: event_id.to_string(),
             option_id: None,
             battle_win: None,
+            battle_id: None,
+            skipped: false,
         });
     }
 
@@ -1693,6 +4410,9 @@ impl WushenCore {
         event_id: &str,
         option_id: String,
     ) {
+        if let Some(analytics) = save.analytics.as_mut() {
+            analytics.record_option(event_id, &option_id);
+        }
         if let Some(record) = save
             .story_history
             .iter_mut()
@@ -1707,6 +4427,8 @@ impl WushenCore {
             event_id: event_id.to_string(),
             option_id: Some(option_id),
             battle_win: None,
+            battle_id: None,
+            skipped: false,
         });
     }
 
@@ -1714,8 +4436,26 @@ impl WushenCore {
         save: &mut SaveGame,
         scope: StoryHistoryScope,
         event_id: &str,
+        battle_result: &Value,
+        enemy_name: &str,
         win: bool,
     ) {
+        // 一对多战斗逐个记录同场的敌人：击败的敌人按胜场计入统计
+        let outcomes = battle_enemy_outcomes(battle_result);
+        if outcomes.len() > 1 {
+            for outcome in &outcomes {
+                if let Some(analytics) = save.analytics.as_mut() {
+                    analytics.record_battle(&outcome.name, outcome.defeated);
+                }
+                save.record_codex_enemy(&outcome.name);
+            }
+        } else {
+            if let Some(analytics) = save.analytics.as_mut() {
+                analytics.record_battle(enemy_name, win);
+            }
+            save.record_codex_enemy(enemy_name);
+        }
+        let battle_id = save.archive_battle(scope, event_id, enemy_name, win, battle_result);
         if let Some(record) = save
             .story_history
             .iter_mut()
@@ -1723,6 +4463,7 @@ impl WushenCore {
             .find(|record| record.scope == scope && record.event_id == event_id)
         {
             record.battle_win = Some(win);
+            record.battle_id = battle_id;
             return;
         }
         save.story_history.push(StoryHistoryRecord {
@@ -1730,9 +4471,21 @@ impl WushenCore {
             event_id: event_id.to_string(),
             option_id: None,
             battle_win: Some(win),
+            battle_id,
+            skipped: false,
         });
     }
 
+    /// 战斗胜利时推进悬赏任务进度
+    fn record_quest_battle_win(save: &mut SaveGame, win: bool) {
+        if !win {
+            return;
+        }
+        for progress in save.active_quests.iter_mut() {
+            progress.battles_won = progress.battles_won.saturating_add(1);
+        }
+    }
+
     fn record_current_story_event_if_ready(save: &mut SaveGame) {
         if save.current_character.action_points > 0 {
             return;
@@ -1751,39 +4504,89 @@ impl WushenCore {
         character: &mut CharacterState,
         start_trait_pool: &mut Vec<String>,
         rewards: &[Reward],
-    ) -> Result<(), String> {
+    ) -> WushenResult<()> {
         if rewards.is_empty() {
             return Ok(());
         }
         for reward in rewards {
-            if let Reward::StartTraitPool { id } = reward {
-                if !start_trait_pool.contains(id) {
+            match reward {
+                Reward::StartTraitPool { id }
+                    if !start_trait_pool.contains(id)
+                        && !self.challenge().is_some_and(|c| c.bans_trait(id)) =>
+                {
                     start_trait_pool.push(id.clone());
                 }
+                Reward::TimedBuff {
+                    id,
+                    name,
+                    entries,
+                    actions,
+                } => character.add_timed_buff(TimedBuff {
+                    id: id.clone(),
+                    name: name.clone(),
+                    entries: entries.clone(),
+                    remaining_actions: *actions,
+                }),
+                Reward::Title { id, name, entries } => character.add_title(Title {
+                    id: id.clone(),
+                    name: name.clone(),
+                    entries: entries.clone(),
+                }),
+                Reward::ScheduleEvent {
+                    adventure_id,
+                    actions,
+                } => character.schedule_event(adventure_id, *actions),
+                Reward::Item { id, .. } if self.content.item_manager.get_item(id).is_none() => {
+                    return Err(WushenError::NotFound(format!("物品 {} 不存在", id)));
+                }
+                Reward::StoryFlag { flag, value } => {
+                    let present = character.story_flags.contains(flag);
+                    if *value && !present {
+                        character.story_flags.push(flag.clone());
+                    } else if !*value && present {
+                        character.story_flags.retain(|f| f != flag);
+                    }
+                }
+                _ => {}
             }
         }
         let mut panel = character_state_to_panel(character);
+        let challenge = self.challenge();
         let filtered: Vec<Reward> = rewards
             .iter()
-            .filter(|reward| !matches!(reward, Reward::StartTraitPool { .. }))
+            .filter(|reward| {
+                !matches!(
+                    reward,
+                    Reward::StartTraitPool { .. }
+                        | Reward::TimedBuff { .. }
+                        | Reward::StoryFlag { .. }
+                        | Reward::Title { .. }
+                        | Reward::ScheduleEvent { .. }
+                ) && challenge.is_none_or(|challenge| challenge.allows_reward(reward))
+            })
             .cloned()
             .collect();
-        crate::event::apply_rewards(
+        crate::event::apply_rewards_with_duplicates(
             &mut panel,
             &filtered,
-            Some(&self.manual_manager),
-            Some(&self.trait_manager),
+            Some(&self.content.manual_manager),
+            Some(&self.content.trait_manager),
+            &self.duplicate_rewards(),
+            challenge
+                .map(|challenge| challenge.banned_manuals.as_slice())
+                .unwrap_or_default(),
         )?;
         update_character_from_panel(character, &panel);
         Ok(())
     }
 
-    fn apply_game_start_effects(&self, character: &mut CharacterState) -> Result<(), String> {
+    fn apply_game_start_effects(&self, character: &mut CharacterState) -> WushenResult<()> {
         if character.traits.is_empty() {
             return Ok(());
         }
         let mut panel = character_state_to_panel(character);
-        let mut executor = self.trait_manager.create_executor(&panel.traits);
+        let mut executor = self.content.trait_manager.create_executor(&panel.traits);
+        executor.disable_tags(self.disabled_entry_tags());
         let context = CultivationContext {
             internal_id: None,
             internal_type: None,
@@ -1791,39 +4594,458 @@ impl WushenCore {
             attack_skill_type: None,
             defense_skill_id: None,
             defense_skill_type: None,
+            internal_tags: Vec::new(),
+            attack_skill_tags: Vec::new(),
+            defense_skill_tags: Vec::new(),
             traits: panel.traits.clone(),
             comprehension: panel.three_d.comprehension as f64,
             bone_structure: panel.three_d.bone_structure as f64,
             physique: panel.three_d.physique as f64,
             martial_arts_attainment: panel.martial_arts_attainment,
+            max_hp: panel.max_hp,
+            base_attack: panel.base_attack,
+            base_defense: panel.base_defense,
+        };
+        let effects = executor.trigger_cultivation(Trigger::GameStart, &mut panel, &context);
+        if effects.is_empty() {
+            return Ok(());
+        }
+        executor.apply_effects_cultivation(effects, &mut panel, &context);
+        update_character_from_panel(character, &panel);
+        Ok(())
+    }
+
+    /// 当前生效的战后恢复规则（难度覆盖优先于剧情线配置）
+    fn post_battle_recovery_rule(&self) -> PostBattleRecovery {
+        let save = match self.game_runtime.as_ref() {
+            Some(runtime) => &runtime.save,
+            None => return PostBattleRecovery::default(),
+        };
+        save.post_battle_recovery
+            .or_else(|| {
+                save.storyline_progress
+                    .as_ref()
+                    .and_then(|p| self.content.event_manager.get_storyline(&p.storyline_id))
+                    .map(|s| s.post_battle_recovery)
+            })
+            .unwrap_or_default()
+    }
+
+    /// 剧情战斗的战败处理策略（难度设置优先于剧情线配置）
+    fn defeat_policy_rule(&self) -> DefeatPolicy {
+        let Some(runtime) = self.game_runtime.as_ref() else {
+            return DefeatPolicy::default();
+        };
+        runtime
+            .save
+            .defeat_policy
+            .clone()
+            .or_else(|| {
+                runtime
+                    .save
+                    .storyline_progress
+                    .as_ref()
+                    .and_then(|p| self.content.event_manager.get_storyline(&p.storyline_id))
+                    .map(|s| s.defeat_policy.clone())
+            })
+            .unwrap_or_default()
+    }
+
+    /// 本局生效的挑战规则
+    fn challenge(&self) -> Option<&ChallengeRuleset> {
+        self.game_runtime
+            .as_ref()
+            .and_then(|runtime| runtime.save.challenge.as_ref())
+    }
+
+    /// 禁用的词条标签：内容设置的标签与当前挑战规则禁用的标签
+    fn disabled_entry_tags(&self) -> BTreeSet<String> {
+        let mut tags = self.content.disabled_entry_tags.clone();
+        if let Some(challenge) = self.challenge() {
+            tags.extend(challenge.disabled_entry_tags.iter().cloned());
+        }
+        tags
+    }
+
+    /// 当前剧情线的行动点消耗配置
+    /// 当前剧情线的重复奖励处理配置
+    fn duplicate_rewards(&self) -> DuplicateRewardConfig {
+        self.game_runtime
+            .as_ref()
+            .and_then(|runtime| runtime.save.storyline_progress.as_ref())
+            .and_then(|p| self.content.event_manager.get_storyline(&p.storyline_id))
+            .map(|s| s.duplicate_rewards.clone())
+            .unwrap_or_default()
+    }
+
+    fn action_costs(&self, save: &SaveGame) -> ActionCostConfig {
+        save.storyline_progress
+            .as_ref()
+            .and_then(|p| self.content.event_manager.get_storyline(&p.storyline_id))
+            .map(|s| s.action_costs.clone())
+            .unwrap_or_default()
+    }
+
+    /// 检查当前行动点是否足够执行指令
+    fn check_action(&self, action: ActionKind) -> WushenResult<()> {
+        let runtime = self
+            .game_runtime
+            .as_ref()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        self.action_costs(&runtime.save)
+            .check(action, runtime.save.current_character.action_points)?;
+        Ok(())
+    }
+
+    /// 扣除指令消耗的行动点；行动点耗尽时记录当前剧情事件
+    fn charge_action(&mut self, action: ActionKind) -> WushenResult<()> {
+        let action_costs = match self.game_runtime.as_ref() {
+            Some(runtime) => self.action_costs(&runtime.save),
+            None => return Err(WushenError::InvalidState("游戏尚未初始化".to_string())),
         };
-        let effects = executor.trigger_cultivation(Trigger::GameStart, &mut panel, &context);
-        if effects.is_empty() {
-            return Ok(());
+        let runtime = self
+            .game_runtime
+            .as_mut()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        let cost =
+            action_costs.charge(action, &mut runtime.save.current_character.action_points)?;
+        if cost > 0 {
+            Self::record_current_story_event_if_ready(&mut runtime.save);
         }
-        executor.apply_effects_cultivation(effects, &mut panel, &context);
-        update_character_from_panel(character, &panel);
         Ok(())
     }
 
+    /// 当前剧情线的历法配置（缺省使用默认历法）
+    fn calendar_config(&self, save: &SaveGame) -> CalendarConfig {
+        save.storyline_progress
+            .as_ref()
+            .and_then(|p| self.content.event_manager.get_storyline(&p.storyline_id))
+            .and_then(|s| s.calendar.clone())
+            .unwrap_or_default()
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn run_battle(
         &self,
         character: &CharacterState,
-        enemy: &crate::event::EnemyTemplate,
+        enemy: &EnemyTemplate,
+        enemies: &[EnemyTemplate],
+        handicaps: &[BattleHandicap],
+        environment: &BTreeMap<String, f64>,
+        attacker_qi_output_rate: Option<f64>,
+        defender_qi_output_rate: Option<f64>,
+    ) -> WushenResult<Value> {
+        self.run_battle_against(
+            character,
+            enemy.to_character_panel(),
+            attacker_qi_output_rate,
+            defender_qi_output_rate,
+            BattleScript {
+                enemy: Some(enemy),
+                enemies,
+                handicaps,
+                environment: environment.clone(),
+                ..BattleScript::default()
+            },
+        )
+    }
+
+    fn run_battle_against(
+        &self,
+        character: &CharacterState,
+        enemy_panel: CharacterPanel,
+        attacker_qi_output_rate: Option<f64>,
+        defender_qi_output_rate: Option<f64>,
+        script: BattleScript,
+    ) -> WushenResult<Value> {
+        battle_result_value(self.battle_engine_against(
+            character,
+            enemy_panel,
+            attacker_qi_output_rate,
+            defender_qi_output_rate,
+            script,
+        )?)
+    }
+
+    /// 构建当前角色对战敌人的战斗引擎（附加佩戴的称号词条，使用剧情线的战斗规则）
+    fn battle_engine_against(
+        &self,
+        character: &CharacterState,
+        enemy_panel: CharacterPanel,
         attacker_qi_output_rate: Option<f64>,
         defender_qi_output_rate: Option<f64>,
-    ) -> Result<Value, String> {
+        mut script: BattleScript,
+    ) -> WushenResult<BattleEngine> {
+        if let Some(title) = character.current_title() {
+            script.player_entries.push((
+                format!("title:{}", title.id),
+                title.name.clone(),
+                title.entries.clone(),
+            ));
+        }
         let player_panel = character_state_to_panel(character);
         let player_json = serialize_character_panel(&player_panel)?;
-        let enemy_panel = enemy.to_character_panel();
         let enemy_json = serialize_character_panel(&enemy_panel)?;
-        let battle_json = self.calculate_battle(
+        self.prepare_battle(
             &player_json,
             &enemy_json,
             attacker_qi_output_rate,
             defender_qi_output_rate,
-        )?;
-        serde_json::from_str(&battle_json).map_err(|e| format!("解析战斗结果失败: {}", e))
+            self.game_battle_config(),
+            script,
+        )
+    }
+
+    /// 游戏内战斗的规则（剧情线可配置独立的战斗规则，如要求必须分出胜负）
+    fn game_battle_config(&self) -> &BattleConfig {
+        self.game_runtime
+            .as_ref()
+            .and_then(|runtime| runtime.save.storyline_progress.as_ref())
+            .and_then(|p| self.content.event_manager.get_storyline(&p.storyline_id))
+            .and_then(|s| s.battle_config.as_ref())
+            .unwrap_or(&self.content.battle_config)
+    }
+
+    /// 剧情战斗开战前未能解析的装备功法（处理方式为忽略时为空）
+    fn story_battle_unresolved_manuals(
+        &self,
+        event: &StoryEvent,
+        player: &CharacterPanel,
+    ) -> Vec<UnresolvedManual> {
+        let StoryEventContent::Battle { enemy, enemies, .. } = &event.content else {
+            return Vec::new();
+        };
+        if self.game_battle_config().missing_manuals == MissingManualPolicy::Ignore {
+            return Vec::new();
+        }
+        let manuals = &self.content.manual_manager;
+        let mut unresolved = UnresolvedManual::collect(Side::A, player, manuals);
+        for enemy in std::iter::once(enemy).chain(enemies) {
+            unresolved.extend(UnresolvedManual::collect(
+                Side::B,
+                &enemy.to_character_panel(),
+                manuals,
+            ));
+        }
+        unresolved
+    }
+}
+
+/// 执行战斗并返回战斗结果（JSON 值）
+fn battle_result_value(battle_engine: BattleEngine) -> WushenResult<Value> {
+    let battle_json = battle_result_json(battle_engine)?;
+    serde_json::from_str(&battle_json)
+        .map_err(|e| WushenError::ParseError(format!("解析战斗结果失败: {}", e)))
+}
+
+/// 执行战斗并构建战斗结果JSON
+fn battle_result_json(mut battle_engine: BattleEngine) -> WushenResult<String> {
+    // 执行初始化阶段
+    battle_engine.step();
+
+    // 继续运行战斗
+    let result = battle_engine.run();
+    let interruption = battle_engine.interruption().map(str::to_string);
+    let training = battle_engine.training_score();
+    let clamp_trace = battle_engine.clamp_trace().to_vec();
+    let provenance = battle_engine.provenance();
+    let unresolved_manuals = battle_engine.unresolved_manuals().to_vec();
+    let enemies = battle_engine.enemy_outcomes();
+    let attacker_skipped_entries = battle_engine.skipped_entries(Side::A).to_vec();
+    let defender_skipped_entries = battle_engine.skipped_entries(Side::B).to_vec();
+    let log = battle_engine.get_log();
+
+    // 获取战斗结束后的面板状态
+    let side_a_battle_panel = battle_engine.get_side_a_panel().clone();
+    let side_b_battle_panel = battle_engine.get_side_b_panel().clone();
+
+    // 构建返回结果（保持外部API兼容，使用 attacker/defender 命名）
+    let records = battle_logs(
+        log.get_all_records(),
+        &side_a_battle_panel.name,
+        &side_b_battle_panel.name,
+    );
+
+    let battle_result = BattleResultJson {
+        result: match result {
+            BattleResult::SideAWin => "attacker_win".to_string(),
+            BattleResult::SideBWin => "defender_win".to_string(),
+            BattleResult::Draw => "draw".to_string(),
+            BattleResult::Interrupted => "interrupted".to_string(),
+        },
+        interruption,
+        clamp_trace,
+        attacker_skipped_entries,
+        defender_skipped_entries,
+        training,
+        records,
+        // 映射：side_a -> attacker, side_b -> defender
+        attacker_panel: battle_panel_to_json(&side_a_battle_panel),
+        defender_panel: battle_panel_to_json(&side_b_battle_panel),
+        provenance,
+        enemies,
+        unresolved_manuals,
+    };
+
+    let json = serde_json::to_string(&battle_result)
+        .map_err(|e| WushenError::ParseError(format!("序列化战斗结果失败: {}", e)))?;
+
+    Ok(json)
+}
+
+/// 把战斗记录整理为战斗日志（同一批次触发的词条记录合并排序）
+fn battle_logs<'a>(
+    battle_records: impl IntoIterator<Item = &'a BattleRecord>,
+    attacker_name: &str,
+    defender_name: &str,
+) -> Vec<BattleRecordJson> {
+    let mut records = Vec::new();
+    let mut batch_records: Vec<&BattleRecord> = Vec::new();
+    let mut current_batch_id: Option<u64> = None;
+    let mut after_battle_end = false;
+    // 一对多战斗中 Side B 换人后，变化量归于上场的敌人
+    let mut defender_name = defender_name.to_string();
+
+    for record in battle_records {
+        if let Some(batch_id) = battle_record_batch_id(record) {
+            if current_batch_id.is_none() || current_batch_id == Some(batch_id) {
+                current_batch_id = Some(batch_id);
+                batch_records.push(record);
+                continue;
+            }
+
+            let batch_contains_battle_end = batch_records
+                .iter()
+                .any(|r| matches!(r, BattleRecord::BattleEnd { .. }));
+            records.extend(build_batch_logs(
+                &batch_records,
+                attacker_name,
+                &defender_name,
+                after_battle_end,
+            ));
+            batch_records.clear();
+            current_batch_id = Some(batch_id);
+            if batch_contains_battle_end {
+                after_battle_end = true;
+            }
+            batch_records.push(record);
+            continue;
+        }
+
+        if !batch_records.is_empty() {
+            let batch_contains_battle_end = batch_records
+                .iter()
+                .any(|r| matches!(r, BattleRecord::BattleEnd { .. }));
+            records.extend(build_batch_logs(
+                &batch_records,
+                attacker_name,
+                &defender_name,
+                after_battle_end,
+            ));
+            batch_records.clear();
+            current_batch_id = None;
+            if batch_contains_battle_end {
+                after_battle_end = true;
+            }
+        }
+
+        if let BattleRecord::EnemySwitch { enemy_name, .. } = record {
+            defender_name.clone_from(enemy_name);
+        }
+        let is_battle_end = matches!(record, BattleRecord::BattleEnd { .. });
+        let record_is_terminal = after_battle_end && !is_battle_end;
+        let (effect_logs, value_logs) =
+            build_record_logs(record, attacker_name, &defender_name, record_is_terminal);
+        records.extend(effect_logs);
+        records.extend(value_logs);
+        if is_battle_end {
+            after_battle_end = true;
+        }
+    }
+
+    if !batch_records.is_empty() {
+        records.extend(build_batch_logs(
+            &batch_records,
+            attacker_name,
+            &defender_name,
+            after_battle_end,
+        ));
+    }
+
+    records
+}
+
+/// 剧情打断转换为战斗引擎条件（玩家为 Side A，敌人为 Side B）
+fn story_battle_interrupts(interruptions: &[BattleInterruption]) -> Vec<BattleInterrupt> {
+    interruptions
+        .iter()
+        .map(|interruption| BattleInterrupt {
+            id: interruption.id.clone(),
+            trigger: match interruption.trigger {
+                InterruptCondition::Round { round } => InterruptTrigger::Round(round),
+                InterruptCondition::PlayerHpBelow { ratio } => InterruptTrigger::HpBelow {
+                    side: Side::A,
+                    ratio,
+                },
+                InterruptCondition::EnemyHpBelow { ratio } => InterruptTrigger::HpBelow {
+                    side: Side::B,
+                    ratio,
+                },
+            },
+        })
+        .collect()
+}
+
+/// 从战斗结果中读取各敌人的结果
+fn battle_enemy_outcomes(battle_result: &Value) -> Vec<EnemyOutcome> {
+    serde_json::from_value(battle_result["enemies"].clone()).unwrap_or_default()
+}
+
+/// 从战斗结果中读取敌方名称
+fn battle_enemy_name(battle_result: &Value) -> &str {
+    battle_result["defender_panel"]["name"]
+        .as_str()
+        .unwrap_or_default()
+}
+
+/// 新角色（无特性与功法）
+/// 新角色的三维须均不小于 1，且总点数不超过 100
+fn check_three_d(three_d: &ThreeDimensionalState) -> WushenResult<()> {
+    if three_d.comprehension == 0 || three_d.bone_structure == 0 || three_d.physique == 0 {
+        return Err(WushenError::Invalid("三维最小值为1，不能为0".to_string()));
+    }
+    if three_d.comprehension + three_d.bone_structure + three_d.physique > 100 {
+        return Err(WushenError::Invalid("三维总点数不能超过 100".to_string()));
+    }
+    Ok(())
+}
+
+fn new_character(id: String, name: String, three_d: ThreeDimensionalState) -> CharacterState {
+    CharacterState {
+        id,
+        name,
+        three_d,
+        traits: vec![],
+        internals: empty_manuals(),
+        attack_skills: empty_manuals(),
+        defense_skills: empty_manuals(),
+        action_points: 0,
+        cultivation_history: vec![],
+        max_hp: None,
+        hp: None,
+        base_attack: None,
+        base_defense: None,
+        max_qi: Some(0.0),
+        qi: Some(0.0),
+        martial_arts_attainment: Some(0.0),
+        timed_buffs: Vec::new(),
+        scheduled_events: Vec::new(),
+        story_flags: Vec::new(),
+        titles: Vec::new(),
+        worn_title: None,
+        inventory: Vec::new(),
+        chronicle: None,
+        appearance: None,
     }
 }
 
@@ -1882,49 +5104,172 @@ fn battle_is_attacker_win(battle: &Value) -> bool {
         .unwrap_or(false)
 }
 
-fn ensure_event_ready(runtime: &GameRuntime, event: &StoryEvent) -> Result<(), String> {
+/// 按战斗结果选择胜、负、平分支（未配置平局分支时平局按战败处理）
+fn battle_branch<'a, T>(battle: &Value, win: &'a T, lose: &'a T, draw: Option<&'a T>) -> &'a T {
+    match battle.get("result").and_then(|v| v.as_str()) {
+        Some("attacker_win") => win,
+        Some("draw") => draw.unwrap_or(lose),
+        _ => lose,
+    }
+}
+
+/// 按规则对战后角色进行恢复，并将结果写回角色状态
+fn apply_post_battle_recovery(
+    character: &mut CharacterState,
+    battle: &Value,
+    rule: PostBattleRecovery,
+) -> BattleRecovery {
+    let panel = character_state_to_panel(character);
+    let max_hp = panel.max_hp.max(0.0);
+    let max_qi = panel.max_qi.max(0.0);
+    let hp_before = battle_attacker_panel_value(battle, "hp")
+        .unwrap_or(panel.hp)
+        .clamp(0.0, max_hp);
+    let qi_before = battle_attacker_panel_value(battle, "qi")
+        .unwrap_or(panel.qi)
+        .clamp(0.0, max_qi);
+    let hp_after = rule.recover_hp(hp_before, max_hp);
+    let qi_after = rule.recover(qi_before, max_qi);
+    character.max_hp = Some(max_hp);
+    character.hp = Some(hp_after);
+    character.qi = Some(qi_after);
+    BattleRecovery {
+        rule,
+        hp_before,
+        hp_after,
+        qi_before,
+        qi_after,
+    }
+}
+
+fn battle_attacker_panel_value(battle: &Value, field: &str) -> Option<f64> {
+    battle
+        .get("attacker_panel")
+        .and_then(|panel| panel.get(field))
+        .and_then(|v| v.as_f64())
+}
+
+fn ensure_event_ready(runtime: &GameRuntime, event: &StoryEvent) -> WushenResult<()> {
     if event.node_type == StoryNodeType::Middle && runtime.save.current_character.action_points > 0
     {
-        return Err("行动点尚未耗尽，无法进入事件".to_string());
+        return Err(WushenError::ConditionNotMet(
+            "行动点尚未耗尽，无法进入事件".to_string(),
+        ));
     }
     Ok(())
 }
 
+fn chapter_complete_text(title: &str) -> String {
+    format!("「{}」完成", title)
+}
+
+/// 将章节完成奖励并入剧情结果
+fn with_chapter_rewards(
+    text: &str,
+    mut rewards: ResolvedRewards,
+    chapter: Option<(String, ResolvedRewards)>,
+) -> (String, ResolvedRewards) {
+    match chapter {
+        Some((title, chapter_rewards)) => {
+            rewards.rewards.extend(chapter_rewards.rewards);
+            rewards.duplicates.extend(chapter_rewards.duplicates);
+            let text = if text.is_empty() {
+                chapter_complete_text(&title)
+            } else {
+                format!("{}\n\n{}", text, chapter_complete_text(&title))
+            };
+            (text, rewards)
+        }
+        None => (text.to_string(), rewards),
+    }
+}
+
+/// 存档中的本局历史
+fn run_history(save: &SaveGame) -> RunHistory<'_> {
+    RunHistory {
+        story_flags: &save.current_character.story_flags,
+        records: &save.story_history[..],
+        active_adventure_id: save.active_adventure_id.as_deref(),
+    }
+}
+
 fn build_story_event_view(
     event: &StoryEvent,
     panel: &CharacterPanel,
     manual_manager: &ManualManager,
     start_trait_pool: &[String],
+    duplicates: &DuplicateRewardConfig,
+    challenge: Option<&ChallengeRuleset>,
+    history: &RunHistory,
 ) -> StoryEventView {
     let action_points = event.action_points;
     let content = match &event.content {
         StoryEventContent::Decision { text, options } => {
-            let available = EventManager::available_story_options(options, panel, manual_manager);
+            let available =
+                EventManager::available_story_options(options, panel, manual_manager, history);
+            let blocked = if available.is_empty() {
+                options
+                    .iter()
+                    .map(|opt| BlockedOptionView {
+                        id: opt.id.clone(),
+                        text: opt.text.clone(),
+                        reasons: EventManager::story_option_blockers(
+                            opt,
+                            panel,
+                            manual_manager,
+                            history,
+                        ),
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            };
             let option_views = available
                 .into_iter()
                 .map(|opt| StoryOptionView {
                     id: opt.id.clone(),
                     text: opt.text.clone(),
                     next_event_id: opt.next_event_id.clone(),
+                    costs: opt.costs.clone(),
                 })
                 .collect();
             StoryEventContentView::Decision {
                 text: text.clone(),
                 options: option_views,
+                blocked,
             }
         }
-        StoryEventContent::Battle { text, enemy, .. } => StoryEventContentView::Battle {
+        StoryEventContent::Battle {
+            text,
+            enemy,
+            training,
+            ..
+        } => StoryEventContentView::Battle {
             text: text.clone(),
             enemy_name: enemy.name.clone(),
+            training_rounds: training.map(|training| training.rounds),
+            unresolved_manuals: Vec::new(),
         },
         StoryEventContent::Story { text, rewards, .. } => {
-            let filtered =
-                filter_rewards_for_panel(panel, rewards, manual_manager, start_trait_pool);
+            let filtered = filter_rewards_for_panel(
+                panel,
+                rewards,
+                manual_manager,
+                start_trait_pool,
+                duplicates,
+                challenge,
+            );
             StoryEventContentView::Story {
                 text: text.clone(),
-                rewards: filtered,
+                rewards: filtered.rewards,
             }
         }
+        StoryEventContent::Check { text, check, .. } => StoryEventContentView::Check {
+            text: text.clone(),
+            stat: check.stat,
+            success_threshold: check.success_threshold,
+            critical_threshold: check.critical_threshold,
+        },
         StoryEventContent::End { text } => StoryEventContentView::End { text: text.clone() },
     };
 
@@ -1934,34 +5279,57 @@ fn build_story_event_view(
         node_type: event.node_type,
         action_points,
         content,
+        presentation: event.presentation.clone(),
+    }
+}
+
+/// 战后抉择以抉择事件的形式展示
+fn victory_decision_view(event: &StoryEvent) -> Option<StoryEventContentView> {
+    match &event.content {
+        StoryEventContent::Battle {
+            win,
+            victory_decision: Some(decision),
+            ..
+        } => Some(StoryEventContentView::Decision {
+            text: decision.text.clone(),
+            options: decision
+                .options
+                .iter()
+                .map(|option| StoryOptionView {
+                    id: option.id.clone(),
+                    text: option.text.clone(),
+                    next_event_id: option
+                        .next_event_id
+                        .clone()
+                        .unwrap_or_else(|| win.next_event_id.clone()),
+                    costs: Vec::new(),
+                })
+                .collect(),
+            blocked: Vec::new(),
+        }),
+        _ => None,
     }
 }
 
+/// 过滤当前无法发放（或被挑战规则禁用）的奖励，并按重复策略处理已拥有的特性与功法
 fn filter_rewards_for_panel(
     panel: &CharacterPanel,
     rewards: &[Reward],
     manual_manager: &ManualManager,
     start_trait_pool: &[String],
-) -> Vec<Reward> {
+    duplicates: &DuplicateRewardConfig,
+    challenge: Option<&ChallengeRuleset>,
+) -> ResolvedRewards {
     let mut filtered = Vec::new();
+    let excluded_manuals = challenge
+        .map(|challenge| challenge.banned_manuals.as_slice())
+        .unwrap_or_default();
 
     for reward in rewards {
+        if challenge.is_some_and(|challenge| !challenge.allows_reward(reward)) {
+            continue;
+        }
         match reward {
-            Reward::Internal { id } => {
-                if !panel.has_internal(id) {
-                    filtered.push(reward.clone());
-                }
-            }
-            Reward::AttackSkill { id } => {
-                if !panel.has_attack_skill(id) {
-                    filtered.push(reward.clone());
-                }
-            }
-            Reward::DefenseSkill { id } => {
-                if !panel.has_defense_skill(id) {
-                    filtered.push(reward.clone());
-                }
-            }
             Reward::RandomManual {
                 manual_kind,
                 rarity,
@@ -1974,6 +5342,7 @@ fn filter_rewards_for_panel(
                     *manual_kind,
                     *rarity,
                     manual_type.as_deref(),
+                    excluded_manuals,
                 );
                 if available == 0 {
                     continue;
@@ -1996,7 +5365,35 @@ fn filter_rewards_for_panel(
         }
     }
 
-    filtered
+    duplicates.resolve(panel, filtered)
+}
+
+/// 推导内息上限：基础内息池 + 已装备内功当前境界及以下的内息增益之和
+///
+/// 未装备内功时取已有上限与基础内息的较大值；两者都无从推导时返回 None（保留原值）
+fn derived_max_qi(
+    base_qi: &BaseQiPool,
+    panel: &CharacterPanel,
+    internal: Option<&Internal>,
+) -> Option<f64> {
+    let base = base_qi.amount_for(panel.three_d.physique, panel.three_d.bone_structure);
+    let internal_gain = internal.and_then(|internal| {
+        let (level, _) = panel
+            .current_internal_id
+            .as_ref()
+            .and_then(|id| panel.get_internal_level_exp(id))?;
+        Some(
+            (1..=level)
+                .filter_map(|lvl| internal.realm_at_level(lvl))
+                .map(|realm| realm.qi_gain)
+                .sum::<f64>(),
+        )
+    });
+    match internal_gain {
+        Some(gain) => Some(base + gain),
+        None if base > 0.0 => Some(panel.max_qi.max(base)),
+        None => None,
+    }
 }
 
 fn character_state_to_panel(character: &CharacterState) -> CharacterPanel {
@@ -2029,6 +5426,19 @@ fn character_state_to_panel(character: &CharacterState) -> CharacterPanel {
         panel.current_defense_skill_id = Some(id.clone());
     }
 
+    if let Some(max_hp) = character.max_hp {
+        panel.max_hp = max_hp;
+        panel.hp = max_hp;
+    }
+    if let Some(hp) = character.hp {
+        panel.hp = hp.min(panel.max_hp);
+    }
+    if let Some(base_attack) = character.base_attack {
+        panel.base_attack = base_attack;
+    }
+    if let Some(base_defense) = character.base_defense {
+        panel.base_defense = base_defense;
+    }
     if let Some(max_qi) = character.max_qi {
         panel.max_qi = max_qi;
     }
@@ -2038,10 +5448,24 @@ fn character_state_to_panel(character: &CharacterState) -> CharacterPanel {
     if let Some(attainment) = character.martial_arts_attainment {
         panel.martial_arts_attainment = attainment;
     }
+    for stack in &character.inventory {
+        panel.add_item(&stack.id, stack.count);
+    }
 
     panel
 }
 
+/// 扣除选项代价（任一代价无法支付时不修改角色）
+fn pay_option_costs(character: &mut CharacterState, costs: &[OptionCost]) -> WushenResult<()> {
+    if costs.is_empty() {
+        return Ok(());
+    }
+    let mut panel = character_state_to_panel(character);
+    apply_costs(&mut panel, costs)?;
+    update_character_from_panel(character, &panel);
+    Ok(())
+}
+
 fn update_character_from_panel(character: &mut CharacterState, panel: &CharacterPanel) {
     character.name = panel.name.clone();
     character.three_d = crate::game::ThreeDimensionalState {
@@ -2086,13 +5510,34 @@ fn update_character_from_panel(character: &mut CharacterState, panel: &Character
             .collect(),
         equipped: panel.current_defense_skill_id.clone(),
     };
+    character.max_hp = Some(panel.max_hp);
+    character.hp = Some(panel.hp);
+    character.base_attack = Some(panel.base_attack);
+    character.base_defense = Some(panel.base_defense);
     character.max_qi = Some(panel.max_qi);
     character.qi = Some(panel.qi);
     character.martial_arts_attainment = Some(panel.martial_arts_attainment);
+    character.inventory = panel
+        .inventory
+        .iter()
+        .map(|(id, count)| crate::game::ItemStack {
+            id: id.clone(),
+            count: *count,
+        })
+        .collect();
 }
 
 // ==================== 辅助结构体 ====================
 
+/// 数据校验报告
+#[derive(Serialize)]
+struct ValidationReport {
+    errors: Vec<String>,
+    warnings: Vec<LoadWarning>,
+    /// 条件检查结果（永远不满足的条件、矛盾的组合与被覆盖的选项）
+    lints: Vec<String>,
+}
+
 #[derive(Serialize)]
 struct TraitListItem {
     id: String,
@@ -2117,6 +5562,12 @@ struct AdventureListItem {
     name: String,
 }
 
+#[derive(Serialize)]
+struct QuestListItem {
+    id: String,
+    name: String,
+}
+
 #[derive(Serialize)]
 struct BattlePanelJson {
     name: String,
@@ -2137,6 +5588,7 @@ struct BattlePanelJson {
     attack_speed: f64,
     qi_recovery_rate: f64,
     charge_time: f64,
+    momentum: f64,
 }
 
 #[derive(Serialize)]
@@ -2158,6 +5610,7 @@ struct PanelDeltaJson {
     attack_speed_delta: Option<f64>,
     qi_recovery_rate_delta: Option<f64>,
     charge_time_delta: Option<f64>,
+    momentum_delta: Option<f64>,
 }
 
 #[derive(Serialize)]
@@ -2167,14 +5620,83 @@ struct BattleRecordJson {
     attacker_panel_delta: Option<PanelDeltaJson>,
     defender_panel_delta: Option<PanelDeltaJson>,
     is_terminal: bool,
+    /// 触发词条的来源（特性名、功法名与境界、称号等）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+}
+
+/// 双方互相攻击一次的预估结算
+#[derive(Serialize)]
+struct ExchangePreviewJson {
+    attacker_to_defender: BattleCalculationResult,
+    defender_to_attacker: BattleCalculationResult,
+}
+
+/// 试战选项
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EnemyBattleOptions {
+    #[serde(default)]
+    pub attacker_qi_output_rate: Option<f64>,
+    #[serde(default)]
+    pub defender_qi_output_rate: Option<f64>,
+    /// 双方的让子与限制
+    #[serde(default)]
+    pub handicaps: Vec<BattleHandicap>,
+    /// 训练模式
+    #[serde(default)]
+    pub training: Option<TrainingMode>,
+    /// 战斗环境变量
+    #[serde(default)]
+    pub environment: BTreeMap<String, f64>,
+    /// 使用该剧情线的战斗配置（为空时使用核心配置）
+    #[serde(default)]
+    pub storyline_id: Option<String>,
+}
+
+/// 战斗的附加脚本（敌人模板的文本与阶段、剧情打断、玩家额外词条）
+#[derive(Default)]
+struct BattleScript<'a> {
+    enemy: Option<&'a EnemyTemplate>,
+    /// 一对多战斗中同场的其余敌人
+    enemies: &'a [EnemyTemplate],
+    interrupts: Vec<BattleInterrupt>,
+    /// 玩家方额外词条（来源ID、来源名称、词条），如佩戴的称号
+    player_entries: Vec<(String, String, Vec<Entry>)>,
+    /// 剧情战斗声明的让子与限制
+    handicaps: &'a [BattleHandicap],
+    /// 训练模式
+    training: Option<TrainingMode>,
+    /// 战斗声明的环境变量
+    environment: BTreeMap<String, f64>,
 }
 
 #[derive(Serialize)]
 struct BattleResultJson {
     result: String,
+    /// 触发的剧情打断标识
+    #[serde(skip_serializing_if = "Option::is_none")]
+    interruption: Option<String>,
+    /// 属性上限约束记录
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    clamp_trace: Vec<AttributeClamp>,
+    /// 因标签被禁用而跳过的词条
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    attacker_skipped_entries: Vec<SkippedEntry>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    defender_skipped_entries: Vec<SkippedEntry>,
+    /// 训练表现评分（训练战斗才有）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    training: Option<TrainingScore>,
     records: Vec<BattleRecordJson>,
     attacker_panel: BattlePanelJson,
     defender_panel: BattlePanelJson,
+    /// 溯源信息（引擎版本、内容指纹、随机种子与配置摘要）
+    provenance: BattleProvenance,
+    /// 各敌人的结果（按出场序号，一对多战斗时有多个）
+    enemies: Vec<EnemyOutcome>,
+    /// 开战时未能解析的装备功法（按未装备参战）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    unresolved_manuals: Vec<UnresolvedManual>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -2190,6 +5712,10 @@ struct CultivationResultJson {
 
 // ==================== 辅助函数 ====================
 
+fn tags_to_json(tags: &[String]) -> String {
+    serde_json::to_string(tags).unwrap_or_else(|_| "[]".to_string())
+}
+
 fn internal_to_json(internal: &Internal) -> String {
     format!(
         r#"{{
@@ -2198,6 +5724,7 @@ fn internal_to_json(internal: &Internal) -> String {
             "description": "{}",
             "rarity": {},
             "manual_type": "{}",
+            "tags": {},
             "cultivation_formula": "{}",
             "level": {},
             "current_exp": {}
@@ -2207,6 +5734,7 @@ fn internal_to_json(internal: &Internal) -> String {
         internal.manual.description,
         internal.manual.rarity.level(),
         internal.manual.manual_type,
+        tags_to_json(&internal.manual.tags),
         "formula",
         internal.manual.level,
         internal.manual.current_exp,
@@ -2221,6 +5749,7 @@ fn attack_skill_to_json(skill: &AttackSkill) -> String {
             "description": "{}",
             "rarity": {},
             "manual_type": "{}",
+            "tags": {},
             "level": {},
             "current_exp": {}
         }}"#,
@@ -2229,6 +5758,7 @@ fn attack_skill_to_json(skill: &AttackSkill) -> String {
         skill.manual.description,
         skill.manual.rarity.level(),
         skill.manual.manual_type,
+        tags_to_json(&skill.manual.tags),
         skill.manual.level,
         skill.manual.current_exp,
     )
@@ -2242,6 +5772,7 @@ fn defense_skill_to_json(skill: &DefenseSkill) -> String {
             "description": "{}",
             "rarity": {},
             "manual_type": "{}",
+            "tags": {},
             "level": {},
             "current_exp": {}
         }}"#,
@@ -2250,6 +5781,7 @@ fn defense_skill_to_json(skill: &DefenseSkill) -> String {
         skill.manual.description,
         skill.manual.rarity.level(),
         skill.manual.manual_type,
+        tags_to_json(&skill.manual.tags),
         skill.manual.level,
         skill.manual.current_exp,
     )
@@ -2275,6 +5807,7 @@ fn battle_panel_to_json(panel: &crate::battle::battle_panel::BattlePanel) -> Bat
         attack_speed: panel.attack_speed,
         qi_recovery_rate: panel.qi_recovery_rate,
         charge_time: panel.charge_time,
+        momentum: panel.momentum,
     }
 }
 
@@ -2297,6 +5830,7 @@ fn panel_delta_to_json(delta: crate::battle::battle_record::PanelDelta) -> Panel
         attack_speed_delta: delta.attack_speed_delta,
         qi_recovery_rate_delta: delta.qi_recovery_rate_delta,
         charge_time_delta: delta.charge_time_delta,
+        momentum_delta: delta.momentum_delta,
     }
 }
 
@@ -2341,6 +5875,7 @@ fn accumulate_panel_delta(acc: &mut PanelDelta, delta: &PanelDelta) {
         delta.qi_recovery_rate_delta,
     );
     add_delta_field(&mut acc.charge_time_delta, delta.charge_time_delta);
+    add_delta_field(&mut acc.momentum_delta, delta.momentum_delta);
 }
 
 fn extract_panel_deltas(
@@ -2400,6 +5935,16 @@ fn extract_panel_deltas(
             side_b_panel_delta,
             ..
         }
+        | BattleRecord::PhaseChange {
+            side_a_panel_delta,
+            side_b_panel_delta,
+            ..
+        }
+        | BattleRecord::EnemySwitch {
+            side_a_panel_delta,
+            side_b_panel_delta,
+            ..
+        }
         | BattleRecord::BattleEnd {
             side_a_panel_delta,
             side_b_panel_delta,
@@ -2413,11 +5958,23 @@ fn format_battle_record(record: &BattleRecord) -> String {
         BattleRecord::BattleStart {
             side_a_name,
             side_b_name,
+            first_strike,
             ..
         } => {
-            format!("{} 与 {} 的战斗开始了！", side_a_name, side_b_name)
+            if *first_strike == FirstStrikeRule::default() {
+                format!("{} 与 {} 的战斗开始了！", side_a_name, side_b_name)
+            } else {
+                format!(
+                    "{} 与 {} 的战斗开始了！（同时蓄力完成时{}）",
+                    side_a_name,
+                    side_b_name,
+                    first_strike.name()
+                )
+            }
         }
-        BattleRecord::EntryTriggered { description, .. } => description.clone(),
+        BattleRecord::EntryTriggered { description, .. }
+        | BattleRecord::PhaseChange { description, .. }
+        | BattleRecord::EnemySwitch { description, .. } => description.clone(),
         BattleRecord::AttackAction {
             attacker_name,
             skill_name,
@@ -2552,6 +6109,13 @@ fn battle_record_entry_id(record: &BattleRecord) -> Option<&str> {
     }
 }
 
+fn battle_record_source(record: &BattleRecord) -> Option<&str> {
+    match record {
+        BattleRecord::EntryTriggered { source, .. } => source.as_deref(),
+        _ => None,
+    }
+}
+
 fn battle_record_entry_order(record: &BattleRecord) -> Option<u64> {
     match record {
         BattleRecord::EntryTriggered { entry_order, .. } => Some(*entry_order),
@@ -2596,6 +6160,11 @@ fn build_batch_logs(
     let mut output = Vec::new();
 
     for group in ordered_groups {
+        let source = group
+            .records
+            .first()
+            .and_then(|record| battle_record_source(record))
+            .map(str::to_string);
         let mut combined_side_a = PanelDelta::default();
         let mut combined_side_b = PanelDelta::default();
         let mut narrative_parts = Vec::new();
@@ -2657,6 +6226,7 @@ fn build_batch_logs(
                 attacker_panel_delta: side_a_opt.map(panel_delta_to_json),
                 defender_panel_delta: side_b_opt.map(panel_delta_to_json),
                 is_terminal: after_battle_end,
+                source,
             });
         }
     }
@@ -2695,6 +6265,7 @@ fn build_record_logs(
         format_battle_record(record)
     };
 
+    let source = battle_record_source(record).map(str::to_string);
     let mut effect_logs = Vec::new();
     let mut value_logs = Vec::new();
 
@@ -2707,6 +6278,7 @@ fn build_record_logs(
                     attacker_panel_delta: None,
                     defender_panel_delta: None,
                     is_terminal,
+                    source: source.clone(),
                 });
             }
             if let Some(value_text) = value_text {
@@ -2716,6 +6288,7 @@ fn build_record_logs(
                     attacker_panel_delta: side_a_delta.map(panel_delta_to_json),
                     defender_panel_delta: side_b_delta.map(panel_delta_to_json),
                     is_terminal,
+                    source: source.clone(),
                 });
             }
         }
@@ -2728,6 +6301,7 @@ fn build_record_logs(
                         attacker_panel_delta: side_a_delta.map(panel_delta_to_json),
                         defender_panel_delta: side_b_delta.map(panel_delta_to_json),
                         is_terminal,
+                        source: source.clone(),
                     });
                 } else if !text.is_empty() {
                     value_logs.push(BattleRecordJson {
@@ -2736,6 +6310,7 @@ fn build_record_logs(
                         attacker_panel_delta: side_a_delta.map(panel_delta_to_json),
                         defender_panel_delta: side_b_delta.map(panel_delta_to_json),
                         is_terminal,
+                        source: source.clone(),
                     });
                 }
             } else if !text.is_empty() {
@@ -2747,6 +6322,7 @@ fn build_record_logs(
                             attacker_panel_delta: side_a_delta.map(panel_delta_to_json),
                             defender_panel_delta: side_b_delta.map(panel_delta_to_json),
                             is_terminal,
+                            source: source.clone(),
                         });
                     } else {
                         value_logs.push(BattleRecordJson {
@@ -2755,6 +6331,7 @@ fn build_record_logs(
                             attacker_panel_delta: None,
                             defender_panel_delta: None,
                             is_terminal,
+                            source: source.clone(),
                         });
                         value_logs.push(BattleRecordJson {
                             text: value_text,
@@ -2762,6 +6339,7 @@ fn build_record_logs(
                             attacker_panel_delta: side_a_delta.map(panel_delta_to_json),
                             defender_panel_delta: side_b_delta.map(panel_delta_to_json),
                             is_terminal,
+                            source: source.clone(),
                         });
                     }
                 } else {
@@ -2771,6 +6349,7 @@ fn build_record_logs(
                         attacker_panel_delta: side_a_delta.map(panel_delta_to_json),
                         defender_panel_delta: side_b_delta.map(panel_delta_to_json),
                         is_terminal,
+                        source: source.clone(),
                     });
                 }
             } else if let Some(value_text) = value_text {
@@ -2780,6 +6359,7 @@ fn build_record_logs(
                     attacker_panel_delta: side_a_delta.map(panel_delta_to_json),
                     defender_panel_delta: side_b_delta.map(panel_delta_to_json),
                     is_terminal,
+                    source: source.clone(),
                 });
             }
         }
@@ -2876,6 +6456,7 @@ fn format_panel_delta_log(
             delta.charge_time_delta,
             false,
         );
+        append_delta_text(&mut parts, side_a_name, "气势", delta.momentum_delta, false);
         append_delta_text(
             &mut parts,
             side_a_name,
@@ -2979,6 +6560,7 @@ fn format_panel_delta_log(
             delta.charge_time_delta,
             false,
         );
+        append_delta_text(&mut parts, side_b_name, "气势", delta.momentum_delta, false);
         append_delta_text(
             &mut parts,
             side_b_name,
@@ -3029,3 +6611,287 @@ fn format_panel_delta_log(
         Some(parts.join("，"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::ItemStack;
+    use crate::test_support::{
+        core_with_storyline, core_with_storylines, new_game_request, start_game,
+        storyline_with_mid, MINIMAL_STORYLINE,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn content_writes_do_not_leak_into_shared_snapshots() {
+        let mut core = core_with_storyline();
+        let snapshot = core.snapshot();
+        assert!(Arc::ptr_eq(&core.content, &snapshot.content));
+
+        // 共享时写入先复制，快照保持原样
+        core.set_battle_config(r#"{"max_rounds": 7}"#).unwrap();
+        core.set_disabled_entry_tags(vec!["诅咒".to_string()]);
+        assert!(!Arc::ptr_eq(&core.content, &snapshot.content));
+        assert_eq!(core.content.battle_config().max_rounds, 7);
+        assert_ne!(snapshot.content.battle_config().max_rounds, 7);
+        assert!(snapshot.content.disabled_entry_tags.is_empty());
+
+        // 反向同样互不影响
+        let mut other = snapshot.snapshot();
+        assert!(Arc::ptr_eq(&other.content, &snapshot.content));
+        other
+            .load_storylines(&MINIMAL_STORYLINE.replace(r#""id":"s""#, r#""id":"t""#))
+            .unwrap();
+        assert!(other.content.event_manager().get_storyline("t").is_some());
+        assert!(core.content.event_manager().get_storyline("t").is_none());
+        assert!(snapshot
+            .content
+            .event_manager()
+            .get_storyline("t")
+            .is_none());
+        assert!(snapshot
+            .content
+            .event_manager()
+            .get_storyline("s")
+            .is_some());
+
+        // 独占时原地写入，不再复制
+        let before = Arc::as_ptr(&core.content);
+        core.set_disabled_entry_tags(vec![]);
+        assert_eq!(Arc::as_ptr(&core.content), before);
+        assert!(core.content.disabled_entry_tags.is_empty());
+    }
+
+    // ========== 战败处理 ==========
+
+    /// 开局即与强敌交手、战后进入中间节点的剧情线，战败后的去向由 `defeat_policy` 决定
+    fn core_with_defeat_policy(defeat_policy: Value) -> WushenCore {
+        let mut storylines = storyline_with_mid(3);
+        storylines[0]["defeat_policy"] = defeat_policy;
+        storylines[0]["events"][0]["content"] = json!({
+            "type": "battle", "text": "",
+            "enemy": {"name": "魔头", "three_d":
+                {"comprehension": 300, "bone_structure": 300, "physique": 300}},
+            "win": {"next_event_id": "mid"}, "lose": {"next_event_id": "mid"}});
+        let mut core = core_with_storylines(&storylines);
+        start_game(&mut core, "c");
+        core
+    }
+
+    #[test]
+    fn narrative_defeat_continues_on_the_lose_branch() {
+        let mut core = core_with_defeat_policy(json!({"type": "narrative"}));
+
+        let response = core.game_story_battle(None, None).unwrap();
+        let outcome = serde_json::to_value(&response.outcome).unwrap();
+        assert_eq!(outcome["win"], false);
+        assert_eq!(response.view.current_event.unwrap().id, "mid");
+        assert_eq!(response.view.save.current_character.action_points, 3);
+    }
+
+    #[test]
+    fn retry_defeat_deducts_action_points_from_the_next_node() {
+        let mut core = core_with_defeat_policy(json!({
+            "type": "retry", "injury_actions": 2, "action_points_lost": 2,
+            "injury": [{"trigger": "battle_start", "effects": [{"type": "modify_attribute",
+                "target": "base_attack", "value": -10.0, "operation": "add",
+                "can_exceed_limit": false}]}]
+        }));
+
+        let response = core.game_story_battle(None, None).unwrap();
+        let outcome = serde_json::to_value(&response.outcome).unwrap();
+        assert_eq!(outcome["defeat"]["type"], "retry");
+        assert_eq!(response.view.current_event.unwrap().id, "start");
+        let mut save = core.current_save().unwrap().clone();
+        assert_eq!(save.action_point_penalty, 2);
+        assert_eq!(save.current_character.timed_buffs[0].id, "defeat_injury");
+
+        // 再战改按剧情分支处理，进入中间节点时扣除上次战败的行动点
+        save.defeat_policy = Some(DefeatPolicy::Narrative);
+        core.game_resume(save).unwrap();
+        let response = core.game_story_battle(None, None).unwrap();
+        assert_eq!(response.view.current_event.unwrap().id, "mid");
+        assert_eq!(response.view.save.current_character.action_points, 1);
+        assert_eq!(response.view.save.action_point_penalty, 0);
+    }
+
+    #[test]
+    fn permadeath_defeat_ends_the_run() {
+        let mut core = core_with_defeat_policy(json!({"type": "permadeath"}));
+
+        let response = core.game_story_battle(None, None).unwrap();
+        let outcome = serde_json::to_value(&response.outcome).unwrap();
+        assert_eq!(outcome["defeat"]["type"], "permadeath");
+        assert!(response.view.save.storyline_progress.is_none());
+        assert_eq!(response.view.save.completed_characters.len(), 1);
+        assert!(core.game_story_battle(None, None).is_err());
+    }
+
+    // ========== 炼制 ==========
+
+    #[test]
+    fn crafting_consumes_materials_and_checks_recipe_references() {
+        let mut storylines = storyline_with_mid(3);
+        storylines[0]["events"][0]["content"]["rewards"] =
+            json!([{"type": "item", "id": "灵芝", "count": 2}]);
+        let mut core = core_with_storylines(&storylines);
+        core.load_items(
+            r#"[{"id":"灵芝","name":"灵芝"},
+                {"id":"回春丹","name":"回春丹","effects":[{"type":"heal","hp":30}]}]"#,
+        )
+        .unwrap();
+        let err = core
+            .load_recipes(
+                r#"[{"id":"炼仙丹","name":"炼仙丹","ingredients":[{"id":"仙草"}],"output":{"id":"回春丹"}}]"#,
+            )
+            .unwrap_err();
+        assert_eq!(err.code(), "invalid");
+        core.load_recipes(
+            r#"[{"id":"炼回春丹","name":"炼回春丹",
+                 "ingredients":[{"id":"灵芝","count":2}],"output":{"id":"回春丹"}}]"#,
+        )
+        .unwrap();
+        start_game(&mut core, "c");
+        core.game_story_continue().unwrap();
+
+        let response = core.game_craft("炼回春丹".to_string()).unwrap();
+        let character = &response.view.save.current_character;
+        assert_eq!(
+            character.inventory,
+            [ItemStack {
+                id: "回春丹".to_string(),
+                count: 1
+            }]
+        );
+        assert_eq!(character.action_points, 2);
+        let err = core.game_craft("炼回春丹".to_string()).unwrap_err();
+        assert_eq!(err.code(), "condition_not_met");
+    }
+
+    // ========== 预约奇遇 ==========
+
+    #[test]
+    fn scheduled_adventures_fire_before_random_rolls() {
+        let mut storylines = storyline_with_mid(5);
+        storylines[0]["events"][0]["content"]["rewards"] = json!([
+            {"type": "schedule_event", "adventure_id": "约定", "actions": 2},
+            {"type": "schedule_event", "adventure_id": "失约", "actions": 1}
+        ]);
+        let mut core = core_with_storylines(&storylines);
+        // 约定只在冬季随机出现，开局正值春季
+        core.load_adventure_events(
+            r#"[{"id":"约定","name":"约定","seasons":["winter"],
+                 "content":{"type":"story","text":"","rewards":[]}},
+                {"id":"路人","name":"路人","content":{"type":"story","text":"","rewards":[]}}]"#,
+        )
+        .unwrap();
+        start_game(&mut core, "c");
+        core.game_story_continue().unwrap();
+        let adventure_name = |response: &GameResponse| match &response.outcome {
+            Some(GameOutcome::Adventure { name, .. }) => name.clone(),
+            other => panic!("没有触发奇遇: {:?}", other),
+        };
+
+        // 失约引用的奇遇不存在：取消预约并提示，本次照常随机游历
+        let response = core.game_travel(None, None).unwrap();
+        assert!(response.warnings[0].contains("失约"));
+        assert_eq!(adventure_name(&response), "路人");
+        let scheduled = &response.view.save.current_character.scheduled_events;
+        assert_eq!(scheduled.len(), 1);
+        assert_eq!(scheduled[0].remaining_actions, 1);
+
+        // 约定到期，优先于随机奇遇触发
+        let response = core.game_travel(None, None).unwrap();
+        assert_eq!(adventure_name(&response), "约定");
+        assert!(response.warnings.is_empty());
+        assert!(response
+            .view
+            .save
+            .current_character
+            .scheduled_events
+            .is_empty());
+        assert_eq!(
+            adventure_name(&core.game_travel(None, None).unwrap()),
+            "路人"
+        );
+    }
+
+    // ========== 群战 ==========
+
+    #[test]
+    fn group_battles_report_and_record_every_enemy() {
+        let enemy = |name: &str| {
+            json!({"name": name,
+                "three_d": {"comprehension": 1, "bone_structure": 1, "physique": 1}})
+        };
+        let mut storylines: Value = serde_json::from_str(MINIMAL_STORYLINE).unwrap();
+        storylines[0]["events"][0]["content"] = json!({
+            "type": "battle", "text": "", "enemy": enemy("甲"), "enemies": [enemy("乙")],
+            "win": {"next_event_id": "end"}, "lose": {"next_event_id": "end"}});
+        let mut core = core_with_storylines(&storylines);
+        let mut request = new_game_request("c");
+        request.analytics = true;
+        core.game_start_new(request).unwrap();
+
+        let response = core.game_story_battle(None, None).unwrap();
+        let Some(GameOutcome::Story { enemies, win, .. }) = response.outcome else {
+            panic!("不是剧情结果");
+        };
+        assert_eq!(win, Some(true));
+        let names: Vec<_> = enemies.iter().map(|enemy| enemy.name.as_str()).collect();
+        assert_eq!(names, ["甲", "乙"]);
+        assert!(enemies.iter().all(|enemy| enemy.defeated));
+
+        let save = core.current_save().unwrap();
+        let codex: Vec<_> = save
+            .codex
+            .entries
+            .iter()
+            .map(|entry| entry.id.as_str())
+            .collect();
+        assert!(codex.contains(&"甲") && codex.contains(&"乙"));
+        let battles = &save.analytics.as_ref().unwrap().battles;
+        assert_eq!(battles["乙"].wins, 1);
+    }
+
+    // ========== 奇遇 ==========
+
+    #[test]
+    fn adventure_with_no_available_option_can_fall_back_or_be_skipped() {
+        let mut core = core_with_storylines(&storyline_with_mid(3));
+        core.load_adventure_events(
+            r#"[{"id":"山洞","name":"山洞","content":{"type":"decision","text":"",
+                "options":[
+                    {"id":"深入","text":"","costs":[{"type":"qi","amount":100000}],
+                     "result":{"type":"story","text":""}},
+                    {"id":"离开","text":"","fallback":true,"result":{"type":"story","text":""}}
+                ],
+                "skip_costs":[{"type":"qi","amount":100000},{"type":"hp","amount":1}]}}]"#,
+        )
+        .unwrap();
+        start_game(&mut core, "c");
+        core.game_story_continue().unwrap();
+
+        let view = core.game_travel(None, None).unwrap().view;
+        assert_eq!(view.phase, GamePhase::AdventureDecision);
+        let options: Vec<_> = view
+            .adventure
+            .unwrap()
+            .options
+            .into_iter()
+            .map(|option| option.id)
+            .collect();
+        assert_eq!(options, ["离开"]);
+
+        let response = core.game_adventure_skip().unwrap();
+        assert_eq!(response.view.phase, GamePhase::Action);
+        // 兜底选项仍可选，放弃要占用一次行动
+        assert_eq!(response.view.save.current_character.action_points, 1);
+        // 内息不足的代价免除，生命代价照付
+        let outcome = serde_json::to_value(&response.outcome).unwrap();
+        assert_eq!(outcome["message"], "放弃了奇遇「山洞」，付出代价：生命 1");
+        let history = &core.current_save().unwrap().story_history;
+        assert!(history.last().unwrap().skipped);
+        assert!(core.game_adventure_skip().is_err());
+    }
+}
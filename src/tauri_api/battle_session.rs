@@ -0,0 +1,244 @@
+/// 手动剧情战斗：剧情战斗以手动模式开打后逐回合推进，玩家可在回合之间调整内息输出；
+/// 战斗结束时按与自动战斗相同的规则结算剧情
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{battle_logs, battle_result_value, WushenCore};
+use crate::battle::battle_panel::BattlePanel;
+use crate::battle::battle_state::{BattleState, Side};
+use crate::error::{WushenError, WushenResult};
+use crate::game::{BattleSession, GameResponse};
+
+/// 手动战斗的当前状态
+#[derive(Debug, Clone, Serialize)]
+pub struct BattleSessionView {
+    pub event_id: String,
+    /// 已开始的回合数
+    pub round: u32,
+    pub finished: bool,
+    pub player: BattleSessionSide,
+    pub enemy: BattleSessionSide,
+    /// 上次返回之后新增的战斗日志（与战斗结果中的 records 结构相同）
+    pub logs: Vec<Value>,
+}
+
+/// 手动战斗中一方的状态
+#[derive(Debug, Clone, Serialize)]
+pub struct BattleSessionSide {
+    pub name: String,
+    pub hp: f64,
+    pub max_hp: f64,
+    pub qi: f64,
+    pub max_qi: f64,
+    pub qi_output_rate: f64,
+    pub max_qi_output_rate: f64,
+    pub momentum: f64,
+}
+
+/// 推进手动战斗的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BattleSessionUpdate {
+    pub session: BattleSessionView,
+    /// 战斗结束后剧情结算的结果（战斗未结束时为空）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<GameResponse>,
+}
+
+impl BattleSessionSide {
+    fn from_panel(panel: &BattlePanel) -> Self {
+        Self {
+            name: panel.name.clone(),
+            hp: panel.hp,
+            max_hp: panel.max_hp,
+            qi: panel.qi,
+            max_qi: panel.max_qi,
+            qi_output_rate: panel.qi_output_rate,
+            max_qi_output_rate: panel.max_qi_output_rate,
+            momentum: panel.momentum,
+        }
+    }
+}
+
+impl BattleSession {
+    /// 当前状态，并取出尚未返回的战斗日志
+    fn view(&mut self) -> BattleSessionView {
+        let player = self.engine.get_side_a_panel();
+        let enemy = self.engine.get_side_b_panel();
+        let records = self.engine.get_log().get_all_records();
+        let logs = battle_logs(
+            records.iter().skip(self.reported_records),
+            &player.name,
+            &enemy.name,
+        )
+        .into_iter()
+        .filter_map(|log| serde_json::to_value(log).ok())
+        .collect();
+        self.reported_records = records.len();
+        BattleSessionView {
+            event_id: self.event_id.clone(),
+            round: self.engine.get_round(),
+            finished: self.engine.get_state().is_finished(),
+            player: BattleSessionSide::from_panel(player),
+            enemy: BattleSessionSide::from_panel(enemy),
+            logs,
+        }
+    }
+}
+
+impl WushenCore {
+    /// 以手动模式开始当前剧情战斗：完成开场词条后停在第一回合之前
+    pub fn game_story_battle_manual(
+        &mut self,
+        attacker_qi_output_rate: Option<f64>,
+        defender_qi_output_rate: Option<f64>,
+    ) -> WushenResult<BattleSessionView> {
+        if self.battle_session_mut().is_ok() {
+            return Err(WushenError::InvalidState(
+                "已有进行中的战斗，请先完成当前战斗".to_string(),
+            ));
+        }
+        let (_, event) = self.current_story_battle_event()?;
+        let mut engine =
+            self.story_battle_engine(&event, attacker_qi_output_rate, defender_qi_output_rate)?;
+        while !engine.get_state().is_finished()
+            && *engine.get_state() != BattleState::ActionBarAdvancing
+        {
+            engine.step();
+        }
+
+        let runtime = self
+            .game_runtime
+            .as_mut()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        let session = runtime
+            .battle_session
+            .insert(BattleSession::new(event.id, engine));
+        Ok(session.view())
+    }
+
+    /// 推进手动战斗一个回合；战斗结束时结算剧情并结束会话
+    pub fn battle_session_step(&mut self) -> WushenResult<BattleSessionUpdate> {
+        let session = self.battle_session_mut()?;
+        session.engine.step_round();
+        let view = session.view();
+        if !view.finished {
+            return Ok(BattleSessionUpdate {
+                session: view,
+                response: None,
+            });
+        }
+
+        let session = self
+            .game_runtime
+            .as_mut()
+            .and_then(|runtime| runtime.battle_session.take())
+            .ok_or_else(|| WushenError::InvalidState("没有进行中的战斗".to_string()))?;
+        let settled = self.story_action(|core| {
+            let (storyline, event) = core.current_story_battle_event()?;
+            if event.id != session.event_id {
+                return Err(WushenError::InvalidState(
+                    "当前剧情事件与进行中的战斗不符".to_string(),
+                ));
+            }
+            let battle_result = battle_result_value(session.engine.clone())?;
+            core.settle_story_battle(&storyline, &event, battle_result)
+        });
+        match settled {
+            Ok(response) => Ok(BattleSessionUpdate {
+                session: view,
+                response: Some(response),
+            }),
+            Err(e) => {
+                // 结算失败时保留会话，便于排除问题后重试
+                if let Some(runtime) = self.game_runtime.as_mut() {
+                    runtime.battle_session = Some(session);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// 调整玩家在手动战斗中的内息输出（下一回合生效）
+    pub fn battle_session_set_qi_output(&mut self, rate: f64) -> WushenResult<BattleSessionView> {
+        if !rate.is_finite() {
+            return Err(WushenError::Invalid("内息输出必须是有限数值".to_string()));
+        }
+        let session = self.battle_session_mut()?;
+        session.engine.set_qi_output_rate(Side::A, rate);
+        Ok(session.view())
+    }
+
+    /// 是否有进行中的手动战斗
+    pub(super) fn has_battle_session(&self) -> bool {
+        self.game_runtime
+            .as_ref()
+            .is_some_and(|runtime| runtime.battle_session.is_some())
+    }
+
+    fn battle_session_mut(&mut self) -> WushenResult<&mut BattleSession> {
+        self.game_runtime
+            .as_mut()
+            .and_then(|runtime| runtime.battle_session.as_mut())
+            .ok_or_else(|| WushenError::InvalidState("没有进行中的战斗".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::new_game_request;
+    use serde_json::json;
+
+    fn core() -> WushenCore {
+        let mut core = WushenCore::new();
+        core.load_storylines(
+            &json!([{"id": "s", "name": "测试", "start_event_id": "duel", "events": [
+                {"id": "duel", "name": "比武", "node_type": "start", "content": {
+                    "type": "battle", "text": "",
+                    "enemy": {"name": "对手", "three_d":
+                        {"comprehension": 5, "bone_structure": 5, "physique": 5}},
+                    "win": {"next_event_id": "end"}, "lose": {"next_event_id": "end"}}},
+                {"id": "end", "name": "终局", "node_type": "end",
+                 "content": {"type": "end", "text": ""}}
+            ]}])
+            .to_string(),
+        )
+        .unwrap();
+        core.game_start_new(new_game_request("少侠")).unwrap();
+        core
+    }
+
+    #[test]
+    fn manual_battle_advances_round_by_round_and_settles_the_story() {
+        let mut core = core();
+        assert!(core.battle_session_step().is_err());
+
+        let session = core.game_story_battle_manual(None, None).unwrap();
+        assert_eq!((session.event_id.as_str(), session.round), ("duel", 0));
+        assert!(core.game_story_battle_manual(None, None).is_err());
+        assert!(core.game_story_continue().is_err());
+
+        let view = core.battle_session_set_qi_output(1000.0).unwrap();
+        assert_eq!(view.player.qi_output_rate, view.player.max_qi_output_rate);
+        assert!(view.logs.is_empty());
+
+        let first = core.battle_session_step().unwrap();
+        assert_eq!(first.session.round, 1);
+        assert!(!first.session.logs.is_empty());
+        let mut response = first.response;
+        for _ in 0..200 {
+            if response.is_some() {
+                break;
+            }
+            let update = core.battle_session_step().unwrap();
+            assert_eq!(
+                update.session.player.qi_output_rate,
+                view.player.max_qi_output_rate
+            );
+            response = update.response;
+        }
+        let response = response.expect("手动战斗应当结束");
+        assert_eq!(response.view.current_event.unwrap().id, "end");
+        assert!(!core.has_battle_session());
+    }
+}
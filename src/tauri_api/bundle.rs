@@ -0,0 +1,646 @@
+/// 内容合集：一次性加载全部数据集合，在单次校验中检查交叉引用，
+/// 全部通过后才替换当前内容，避免宿主逐个加载时出现只加载了一部分的状态
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+use super::{ContentStore, WushenCore};
+use crate::battle::battle_config::BattleConfig;
+use crate::character::traits::parse_traits_with_options;
+use crate::cultivation::parser::{
+    parse_attack_skills_with_options, parse_defense_skills_with_options,
+    parse_internals_with_options,
+};
+use crate::event::parser::{
+    parse_adventure_events_with_options, parse_enemies_with_options, parse_storylines_with_options,
+};
+use crate::event::{
+    AdventureEventContent, AdventureOptionResult, EnemyTemplate, EventManager, OptionCost,
+    OwnedManualTemplate, Reward, StoryEventContent,
+};
+use crate::item::parser::{parse_items_with_options, parse_recipes_with_options};
+use crate::pack::{CollectionKind, ContentFingerprint, IdRegistry, LoadWarning, ParseOptions};
+use crate::quest::parser::parse_quests_with_options;
+use crate::quest::{QuestManager, QuestObjective};
+
+/// 内容合集（各集合的格式与对应的 load_* 接口相同，缺省的集合视为空）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContentBundle {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub traits: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub internals: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attack_skills: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defense_skills: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enemies: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storylines: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adventures: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quests: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub items: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recipes: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub battle_config: Option<BattleConfig>,
+    /// 严格模式：拒绝未知字段与不合规的 ID，并检查重复 ID
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict: bool,
+}
+
+/// 内容合集加载报告
+#[derive(Debug, Clone, Serialize)]
+pub struct ContentBundleReport {
+    /// 是否已替换当前内容（存在错误时保持原内容不变）
+    pub loaded: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<LoadWarning>,
+    /// 条件检查结果
+    pub lints: Vec<String>,
+    /// 合集内容的指纹
+    pub fingerprint: ContentFingerprint,
+}
+
+impl WushenCore {
+    /// 一次性加载内容合集，替换当前全部内容数据（保留游戏运行时）
+    ///
+    /// 所有集合解析后统一校验；存在任何错误时不修改当前内容
+    pub fn load_content_bundle(&mut self, bundle: ContentBundle) -> ContentBundleReport {
+        let (content, errors) = build_content(bundle);
+        let lints = content.lints();
+        let loaded = errors.is_empty();
+        let warnings = content.load_warnings.clone();
+        let fingerprint = content.fingerprint();
+        if loaded {
+            self.content = Arc::new(content);
+        }
+        ContentBundleReport {
+            loaded,
+            errors,
+            warnings,
+            lints,
+            fingerprint,
+        }
+    }
+}
+
+/// 解析合集中的全部集合（敌人库先于剧情线与奇遇），收集所有错误而不提前返回
+fn build_content(bundle: ContentBundle) -> (ContentStore, Vec<String>) {
+    let mut content = ContentStore {
+        battle_config: bundle.battle_config.unwrap_or_default(),
+        ..ContentStore::default()
+    };
+    let mut errors = Vec::new();
+    let options = ParseOptions {
+        strict: bundle.strict,
+    };
+    let mut ids = bundle.strict.then(IdRegistry::new);
+
+    if let Some(traits) = parse(
+        bundle.traits,
+        parse_traits_with_options,
+        options,
+        &mut content,
+        &mut errors,
+    ) {
+        let trait_ids = traits.iter().map(|t| t.id.as_str());
+        register(&mut ids, CollectionKind::Traits, trait_ids, &mut errors);
+        content.trait_manager.load_traits(traits);
+    }
+    if let Some(internals) = parse(
+        bundle.internals,
+        parse_internals_with_options,
+        options,
+        &mut content,
+        &mut errors,
+    ) {
+        let manual_ids = internals.iter().map(|m| m.manual.id.as_str());
+        register(&mut ids, CollectionKind::Internals, manual_ids, &mut errors);
+        content.manual_manager.load_internals(internals);
+    }
+    if let Some(skills) = parse(
+        bundle.attack_skills,
+        parse_attack_skills_with_options,
+        options,
+        &mut content,
+        &mut errors,
+    ) {
+        let manual_ids = skills.iter().map(|m| m.manual.id.as_str());
+        register(
+            &mut ids,
+            CollectionKind::AttackSkills,
+            manual_ids,
+            &mut errors,
+        );
+        content.manual_manager.load_attack_skills(skills);
+    }
+    if let Some(skills) = parse(
+        bundle.defense_skills,
+        parse_defense_skills_with_options,
+        options,
+        &mut content,
+        &mut errors,
+    ) {
+        let manual_ids = skills.iter().map(|m| m.manual.id.as_str());
+        register(
+            &mut ids,
+            CollectionKind::DefenseSkills,
+            manual_ids,
+            &mut errors,
+        );
+        content.manual_manager.load_defense_skills(skills);
+    }
+    if let Some(enemies) = parse(
+        bundle.enemies,
+        parse_enemies_with_options,
+        options,
+        &mut content,
+        &mut errors,
+    ) {
+        let enemy_ids = enemies.iter().map(|e| e.id.as_str());
+        register(&mut ids, CollectionKind::Enemies, enemy_ids, &mut errors);
+        if let Err(e) = content.enemy_manager.load_enemies(enemies) {
+            errors.push(format!("敌人数据校验失败: {}", e));
+        }
+    }
+    if let Some(mut storylines) = parse(
+        bundle.storylines,
+        parse_storylines_with_options,
+        options,
+        &mut content,
+        &mut errors,
+    ) {
+        let storyline_ids = storylines.iter().map(|s| s.id.as_str());
+        register(
+            &mut ids,
+            CollectionKind::Storylines,
+            storyline_ids,
+            &mut errors,
+        );
+        let warnings = content.enemy_manager.resolve_storylines(&mut storylines);
+        content.load_warnings.extend(warnings);
+        for storyline in &storylines {
+            if let Err(e) = EventManager::validate_storyline(storyline) {
+                errors.push(format!("剧情线校验失败: {}", e));
+            }
+        }
+        content.event_manager.load_storylines(storylines);
+    }
+    if let Some(mut adventures) = parse(
+        bundle.adventures,
+        parse_adventure_events_with_options,
+        options,
+        &mut content,
+        &mut errors,
+    ) {
+        let adventure_ids = adventures.iter().map(|e| e.id.as_str());
+        register(
+            &mut ids,
+            CollectionKind::Adventures,
+            adventure_ids,
+            &mut errors,
+        );
+        let warnings = content.enemy_manager.resolve_adventures(&mut adventures);
+        content.load_warnings.extend(warnings);
+        for event in &adventures {
+            if let Err(e) = EventManager::validate_adventure_event(event) {
+                errors.push(format!("奇遇事件校验失败: {}", e));
+            }
+        }
+        content.event_manager.load_adventure_events(adventures);
+    }
+    if let Some(quests) = parse(
+        bundle.quests,
+        parse_quests_with_options,
+        options,
+        &mut content,
+        &mut errors,
+    ) {
+        let quest_ids = quests.iter().map(|q| q.id.as_str());
+        register(&mut ids, CollectionKind::Quests, quest_ids, &mut errors);
+        for quest in &quests {
+            if let Err(e) = QuestManager::validate_quest(quest) {
+                errors.push(format!("悬赏任务校验失败: {}", e));
+            }
+        }
+        content.quest_manager.load_quests(quests);
+    }
+    if let Some(items) = parse(
+        bundle.items,
+        parse_items_with_options,
+        options,
+        &mut content,
+        &mut errors,
+    ) {
+        let item_ids = items.iter().map(|i| i.id.as_str());
+        register(&mut ids, CollectionKind::Items, item_ids, &mut errors);
+        for item in &items {
+            if let Err(e) = item.validate() {
+                errors.push(format!("物品校验失败: {}", e));
+            }
+        }
+        content.item_manager.load_items(items);
+    }
+    if let Some(recipes) = parse(
+        bundle.recipes,
+        parse_recipes_with_options,
+        options,
+        &mut content,
+        &mut errors,
+    ) {
+        let recipe_ids = recipes.iter().map(|r| r.id.as_str());
+        register(&mut ids, CollectionKind::Recipes, recipe_ids, &mut errors);
+        for recipe in &recipes {
+            if let Err(e) = recipe.validate() {
+                errors.push(format!("炼制配方校验失败: {}", e));
+            }
+        }
+        content.item_manager.load_recipes(recipes);
+    }
+
+    errors.extend(ReferenceChecker::new(&content).check());
+    (content, errors)
+}
+
+/// 集合解析函数（返回数据与加载警告）
+type CollectionParser<T, E> = fn(&str, ParseOptions) -> Result<(Vec<T>, Vec<LoadWarning>), E>;
+
+/// 解析一个集合，解析失败时记录错误并跳过
+fn parse<T, E: Into<String>>(
+    collection: Option<Value>,
+    parser: CollectionParser<T, E>,
+    options: ParseOptions,
+    content: &mut ContentStore,
+    errors: &mut Vec<String>,
+) -> Option<Vec<T>> {
+    match parser(&collection?.to_string(), options) {
+        Ok((items, warnings)) => {
+            content.load_warnings.extend(warnings);
+            Some(items)
+        }
+        Err(e) => {
+            errors.push(e.into());
+            None
+        }
+    }
+}
+
+/// 严格模式下登记集合中的 ID，记录重复
+fn register<'a>(
+    ids: &mut Option<IdRegistry>,
+    kind: CollectionKind,
+    items: impl Iterator<Item = &'a str>,
+    errors: &mut Vec<String>,
+) {
+    let Some(registry) = ids else {
+        return;
+    };
+    for id in items {
+        if let Err(e) = registry.register(kind, id) {
+            errors.push(e.into());
+        }
+    }
+}
+
+/// 交叉引用检查：奖励、选项代价、任务目标、敌人模板与炼制配方引用的特性、功法和物品必须存在于合集中
+struct ReferenceChecker<'a> {
+    content: &'a ContentStore,
+    errors: Vec<String>,
+}
+
+impl<'a> ReferenceChecker<'a> {
+    fn new(content: &'a ContentStore) -> Self {
+        Self {
+            content,
+            errors: Vec::new(),
+        }
+    }
+
+    fn check(mut self) -> Vec<String> {
+        let content = self.content;
+        for (id, enemy) in content.enemy_manager.all_enemies() {
+            self.enemy(&format!("敌人 {}", id), enemy);
+        }
+        for storyline in content.event_manager.all_storylines() {
+            let kit = &storyline.starting_kit;
+            let location = format!("剧情线 {} 开局行装", storyline.id);
+            for id in &kit.traits {
+                self.trait_(&location, id);
+            }
+            let manuals = &content.manual_manager;
+            for manual in &kit.internals {
+                if manuals.get_internal(&manual.id).is_none() {
+                    self.missing(&location, "内功", &manual.id);
+                }
+            }
+            for manual in &kit.attack_skills {
+                if manuals.get_attack_skill(&manual.id).is_none() {
+                    self.missing(&location, "攻击武技", &manual.id);
+                }
+            }
+            for manual in &kit.defense_skills {
+                if manuals.get_defense_skill(&manual.id).is_none() {
+                    self.missing(&location, "防御武技", &manual.id);
+                }
+            }
+            for chapter in &storyline.chapters {
+                let location = format!("剧情线 {} 章节 {}", storyline.id, chapter.id);
+                self.rewards(&location, &chapter.rewards);
+            }
+            for event in &storyline.events {
+                let location = format!("剧情线 {} 事件 {}", storyline.id, event.id);
+                self.story_event(&location, &event.content);
+            }
+        }
+        for event in content.event_manager.all_adventure_events() {
+            let location = format!("奇遇 {}", event.id);
+            self.adventure_event(&location, &event.content);
+        }
+        for quest in content.quest_manager.all_quests() {
+            let location = format!("悬赏任务 {}", quest.id);
+            self.rewards(&location, &quest.rewards);
+            for objective in &quest.objectives {
+                match objective {
+                    QuestObjective::HasTrait { trait_id } => self.trait_(&location, trait_id),
+                    QuestObjective::ManualLevel {
+                        manual_id: Some(id),
+                        ..
+                    } => self.manual(&location, id),
+                    _ => {}
+                }
+            }
+        }
+        for recipe in content.item_manager.all_recipes() {
+            let location = format!("炼制配方 {}", recipe.id);
+            for id in recipe.missing_items(&content.item_manager) {
+                self.missing(&location, "物品", id);
+            }
+        }
+        self.errors
+    }
+
+    fn story_event(&mut self, location: &str, content: &StoryEventContent) {
+        match content {
+            StoryEventContent::Battle {
+                enemy,
+                win,
+                lose,
+                draw,
+                interruptions,
+                victory_decision,
+                ..
+            } => {
+                self.enemy(location, enemy);
+                for branch in [Some(win), Some(lose), draw.as_ref()].into_iter().flatten() {
+                    self.rewards(location, &branch.rewards);
+                }
+                for interruption in interruptions {
+                    self.rewards(location, &interruption.rewards);
+                }
+                for option in victory_decision.iter().flat_map(|d| &d.options) {
+                    self.rewards(location, &option.rewards);
+                }
+            }
+            StoryEventContent::Story { rewards, .. } => self.rewards(location, rewards),
+            StoryEventContent::Check {
+                critical_success,
+                success,
+                failure,
+                ..
+            } => {
+                let branches = [critical_success.as_ref(), Some(success), Some(failure)];
+                for branch in branches.into_iter().flatten() {
+                    self.rewards(location, &branch.rewards);
+                }
+            }
+            StoryEventContent::Decision { options, .. } => {
+                for option in options {
+                    self.costs(location, &option.costs);
+                }
+            }
+            StoryEventContent::End { .. } => {}
+        }
+    }
+
+    fn adventure_event(&mut self, location: &str, content: &AdventureEventContent) {
+        match content {
+            AdventureEventContent::Decision { options, .. } => {
+                for option in options {
+                    self.costs(location, &option.costs);
+                    match &option.result {
+                        AdventureOptionResult::Story { rewards, .. } => {
+                            self.rewards(location, rewards)
+                        }
+                        AdventureOptionResult::Battle {
+                            enemy,
+                            win,
+                            lose,
+                            draw,
+                            ..
+                        } => {
+                            self.enemy(location, enemy);
+                            for outcome in
+                                [Some(win), Some(lose), draw.as_ref()].into_iter().flatten()
+                            {
+                                self.rewards(location, &outcome.rewards);
+                            }
+                        }
+                    }
+                }
+            }
+            AdventureEventContent::Battle {
+                enemy,
+                win,
+                lose,
+                draw,
+                ..
+            } => {
+                self.enemy(location, enemy);
+                for outcome in [Some(win), Some(lose), draw.as_ref()].into_iter().flatten() {
+                    self.rewards(location, &outcome.rewards);
+                }
+            }
+            AdventureEventContent::Story { rewards, .. } => self.rewards(location, rewards),
+        }
+    }
+
+    fn enemy(&mut self, location: &str, enemy: &EnemyTemplate) {
+        for id in &enemy.traits {
+            self.trait_(location, id);
+        }
+        let manuals = [&enemy.internal, &enemy.attack_skill, &enemy.defense_skill];
+        for manual in manuals.into_iter().flatten() {
+            self.owned_manual(location, manual);
+        }
+        for phase in &enemy.phases {
+            for id in &phase.traits {
+                self.trait_(location, id);
+            }
+            for manual in [&phase.attack_skill, &phase.defense_skill]
+                .into_iter()
+                .flatten()
+            {
+                self.owned_manual(location, manual);
+            }
+        }
+    }
+
+    fn rewards(&mut self, location: &str, rewards: &[Reward]) {
+        let manuals = &self.content.manual_manager;
+        for reward in rewards {
+            match reward {
+                Reward::Trait { id } | Reward::StartTraitPool { id } => self.trait_(location, id),
+                Reward::Internal { id } if manuals.get_internal(id).is_none() => {
+                    self.missing(location, "内功", id)
+                }
+                Reward::AttackSkill { id } if manuals.get_attack_skill(id).is_none() => {
+                    self.missing(location, "攻击武技", id)
+                }
+                Reward::DefenseSkill { id } if manuals.get_defense_skill(id).is_none() => {
+                    self.missing(location, "防御武技", id)
+                }
+                Reward::ScheduleEvent { adventure_id, .. }
+                    if self
+                        .content
+                        .event_manager
+                        .get_adventure_event(adventure_id)
+                        .is_none() =>
+                {
+                    self.missing(location, "奇遇事件", adventure_id)
+                }
+                Reward::Item { id, .. } if self.content.item_manager.get_item(id).is_none() => {
+                    self.missing(location, "物品", id)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn costs(&mut self, location: &str, costs: &[OptionCost]) {
+        let manuals = &self.content.manual_manager;
+        for cost in costs {
+            match cost {
+                OptionCost::Trait { id } => self.trait_(location, id),
+                OptionCost::Internal { id } if manuals.get_internal(id).is_none() => {
+                    self.missing(location, "内功", id)
+                }
+                OptionCost::AttackSkill { id } if manuals.get_attack_skill(id).is_none() => {
+                    self.missing(location, "攻击武技", id)
+                }
+                OptionCost::DefenseSkill { id } if manuals.get_defense_skill(id).is_none() => {
+                    self.missing(location, "防御武技", id)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn trait_(&mut self, location: &str, id: &str) {
+        if self.content.trait_manager.get_trait(id).is_none() {
+            self.missing(location, "特性", id);
+        }
+    }
+
+    fn owned_manual(&mut self, location: &str, manual: &OwnedManualTemplate) {
+        self.manual(location, &manual.id);
+    }
+
+    /// 任意类别的功法
+    fn manual(&mut self, location: &str, id: &str) {
+        let manuals = &self.content.manual_manager;
+        let exists = manuals.get_internal(id).is_some()
+            || manuals.get_attack_skill(id).is_some()
+            || manuals.get_defense_skill(id).is_some();
+        if !exists {
+            self.missing(location, "功法", id);
+        }
+    }
+
+    fn missing(&mut self, location: &str, kind: &str, id: &str) {
+        self.errors
+            .push(format!("{} 引用的{} {} 不存在", location, kind, id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn storylines(reward_trait: &str) -> Value {
+        json!([{"id":"s","name":"测试","start_event_id":"start","events":[
+            {"id":"start","name":"开端","node_type":"start",
+             "content":{"type":"story","text":"","next_event_id":"end",
+                        "rewards":[{"type":"trait","id":reward_trait}]}},
+            {"id":"end","name":"终局","node_type":"end","content":{"type":"end","text":""}}
+        ]}])
+    }
+
+    #[test]
+    fn bundle_with_errors_keeps_previous_content() {
+        let mut core = WushenCore::new();
+        let traits = json!([{"id":"勇猛","name":"勇猛","description":"","entries":[]}]);
+
+        let report = core.load_content_bundle(ContentBundle {
+            traits: Some(traits.clone()),
+            storylines: Some(storylines("勇猛")),
+            ..ContentBundle::default()
+        });
+        assert!(report.loaded, "{:?}", report.errors);
+        assert!(core.content().event_manager().get_storyline("s").is_some());
+
+        // 引用缺失的特性且任务数据无法解析：报告全部错误，且不改动已加载的内容
+        let report = core.load_content_bundle(ContentBundle {
+            traits: Some(traits),
+            storylines: Some(storylines("不存在")),
+            quests: Some(json!("not a collection")),
+            ..ContentBundle::default()
+        });
+        assert!(!report.loaded);
+        assert_eq!(report.errors.len(), 2);
+        assert!(report
+            .errors
+            .iter()
+            .any(|e| e.contains("剧情线 s 事件 start 引用的特性 不存在 不存在")));
+        assert!(core.content().trait_manager().get_trait("勇猛").is_some());
+    }
+
+    #[test]
+    fn strict_bundle_rejects_unknown_fields_and_duplicate_ids() {
+        let mut core = WushenCore::new();
+        let brave = json!({"id":"brave","name":"勇猛","description":"","entries":[]});
+        let storylines = json!([{"id":"s","name":"测试","start_event_id":"start","events":[
+            {"id":"start","name":"开端","node_type":"start","actoin_points":2,
+             "content":{"type":"story","text":"","next_event_id":"end"}},
+            {"id":"end","name":"终局","node_type":"end","content":{"type":"end","text":""}}
+        ]}]);
+        let bundle = ContentBundle {
+            traits: Some(json!([brave.clone(), brave])),
+            storylines: Some(storylines),
+            ..ContentBundle::default()
+        };
+
+        let report = core.load_content_bundle(bundle.clone());
+        assert!(report.loaded, "{:?}", report.errors);
+
+        let report = core.load_content_bundle(ContentBundle {
+            strict: true,
+            ..bundle
+        });
+        assert!(!report.loaded);
+        assert_eq!(report.errors.len(), 2, "{:?}", report.errors);
+        assert!(report
+            .errors
+            .iter()
+            .any(|e| e.contains("特性 brave 与特性 brave 的 ID 重复")));
+        assert!(report
+            .errors
+            .iter()
+            .any(|e| e.contains("/0/events/0/actoin_points：未知字段")));
+    }
+}
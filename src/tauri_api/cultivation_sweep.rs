@@ -0,0 +1,250 @@
+/// 修行曲线推演：在固定的三维点数预算下遍历各种分配，
+/// 模拟给定行动点内依次修行一组功法能达到的等级，结果按悟性 × 根骨排成网格，便于绘制热力图
+use serde::{Deserialize, Serialize};
+
+use super::{CultivationResultJson, WushenCore};
+use crate::character::json::serialize_character_panel;
+use crate::character::panel::{CharacterPanel, ThreeDimensional};
+use crate::error::{WushenError, WushenResult};
+
+/// 功法最高等级
+const MAX_MANUAL_LEVEL: u32 = 5;
+
+/// 网格格子数上限（每格都要完整模拟一遍修行）
+pub const MAX_SWEEP_CELLS: usize = 2000;
+
+/// 推演请求
+#[derive(Debug, Clone, Deserialize)]
+pub struct CultivationSweepRequest {
+    /// 三维点数总和（体魄取预算减去悟性与根骨后的余量）
+    pub budget: u32,
+    /// 每一维的下限
+    #[serde(default)]
+    pub min_stat: u32,
+    /// 悟性与根骨的取值步长
+    #[serde(default = "default_step")]
+    pub step: u32,
+    /// 可用行动点（每次修行消耗一点）
+    pub action_points: u32,
+    /// 依次修行的功法：前一门圆满后转修下一门
+    pub manuals: Vec<SweepManual>,
+    /// 角色特性
+    #[serde(default)]
+    pub traits: Vec<String>,
+}
+
+fn default_step() -> u32 {
+    1
+}
+
+/// 参与推演的功法
+#[derive(Debug, Clone, Deserialize)]
+pub struct SweepManual {
+    pub manual_id: String,
+    /// internal / attack_skill / defense_skill
+    pub manual_type: String,
+}
+
+/// 推演结果
+#[derive(Debug, Clone, Serialize)]
+pub struct CultivationSweepReport {
+    /// 行对应的悟性取值
+    pub comprehension: Vec<u32>,
+    /// 列对应的根骨取值
+    pub bone_structure: Vec<u32>,
+    /// `cells[行][列]`；体魄余量低于下限的分配为空
+    pub cells: Vec<Vec<Option<SweepCell>>>,
+    /// 总等级最高的分配（相同时取用行动点最少者）
+    pub best: Option<SweepCell>,
+}
+
+/// 单个三维分配的模拟结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SweepCell {
+    pub comprehension: u32,
+    pub bone_structure: u32,
+    pub physique: u32,
+    /// 各功法达到的等级（与请求中的功法顺序一致）
+    pub levels: Vec<u32>,
+    pub total_level: u32,
+    /// 实际消耗的行动点（全部功法圆满后不再消耗）
+    pub action_points_used: u32,
+}
+
+impl CultivationSweepRequest {
+    fn validate(&self) -> WushenResult<()> {
+        if self.step == 0 {
+            return Err(WushenError::Invalid("步长必须大于 0".to_string()));
+        }
+        if self.manuals.is_empty() {
+            return Err(WushenError::ConditionNotMet("至少需要一门功法".to_string()));
+        }
+        if self.budget < self.min_stat.saturating_mul(3) {
+            return Err(WushenError::ConditionNotMet(format!(
+                "三维预算 {} 不足以让每一维达到下限 {}",
+                self.budget, self.min_stat
+            )));
+        }
+        let cells = self.axis().len().pow(2);
+        if cells > MAX_SWEEP_CELLS {
+            return Err(WushenError::Invalid(format!(
+                "网格过大（{} 格，上限 {}），请增大步长",
+                cells, MAX_SWEEP_CELLS
+            )));
+        }
+        Ok(())
+    }
+
+    /// 悟性与根骨的取值（为另外两维留出下限）
+    fn axis(&self) -> Vec<u32> {
+        let max = self.budget - self.min_stat * 2;
+        (self.min_stat..=max).step_by(self.step as usize).collect()
+    }
+}
+
+impl WushenCore {
+    /// 推演不同三维分配下的修行曲线
+    /// 参数：推演请求JSON（CultivationSweepRequest）
+    /// 返回：推演结果JSON（CultivationSweepReport）
+    pub fn simulate_cultivation_sweep(&self, request_json: &str) -> WushenResult<String> {
+        let request: CultivationSweepRequest = serde_json::from_str(request_json)
+            .map_err(|e| WushenError::ParseError(format!("解析推演请求失败: {}", e)))?;
+        request.validate()?;
+
+        let axis = request.axis();
+        let mut best: Option<SweepCell> = None;
+        let mut cells = Vec::with_capacity(axis.len());
+        for &comprehension in &axis {
+            let mut row = Vec::with_capacity(axis.len());
+            for &bone_structure in &axis {
+                let physique = request
+                    .budget
+                    .checked_sub(comprehension + bone_structure)
+                    .filter(|physique| *physique >= request.min_stat);
+                let Some(physique) = physique else {
+                    row.push(None);
+                    continue;
+                };
+                let three_d = ThreeDimensional::new(comprehension, bone_structure, physique);
+                let cell = self.simulate_sweep_cell(&request, three_d)?;
+                let better = best.as_ref().is_none_or(|best| {
+                    (cell.total_level, std::cmp::Reverse(cell.action_points_used))
+                        > (best.total_level, std::cmp::Reverse(best.action_points_used))
+                });
+                if better {
+                    best = Some(cell.clone());
+                }
+                row.push(Some(cell));
+            }
+            cells.push(row);
+        }
+
+        let report = CultivationSweepReport {
+            comprehension: axis.clone(),
+            bone_structure: axis,
+            cells,
+            best,
+        };
+        serde_json::to_string(&report)
+            .map_err(|e| WushenError::ParseError(format!("序列化推演结果失败: {}", e)))
+    }
+
+    /// 从零开始依次修行各功法，直到行动点耗尽或全部圆满
+    fn simulate_sweep_cell(
+        &self,
+        request: &CultivationSweepRequest,
+        three_d: ThreeDimensional,
+    ) -> WushenResult<SweepCell> {
+        let mut panel = CharacterPanel::new("推演角色".to_string(), three_d);
+        panel.traits = request.traits.clone();
+        for manual in &request.manuals {
+            let id = manual.manual_id.clone();
+            match manual.manual_type.as_str() {
+                "internal" => panel.set_internal_level_exp(id, 0, 0.0),
+                "attack_skill" => panel.set_attack_skill_level_exp(id, 0, 0.0),
+                "defense_skill" => panel.set_defense_skill_level_exp(id, 0, 0.0),
+                other => return Err(WushenError::Invalid(format!("未知的功法类型: {}", other))),
+            }
+        }
+
+        let mut character_json = serialize_character_panel(&panel)?;
+        let mut levels = vec![0; request.manuals.len()];
+        let mut current = 0;
+        let mut action_points_used = 0;
+        while action_points_used < request.action_points && current < levels.len() {
+            if levels[current] >= MAX_MANUAL_LEVEL {
+                current += 1;
+                continue;
+            }
+            let manual = &request.manuals[current];
+            let result =
+                self.execute_cultivation(&character_json, &manual.manual_id, &manual.manual_type)?;
+            let result: CultivationResultJson = serde_json::from_str(&result)
+                .map_err(|e| WushenError::ParseError(format!("解析修行结果失败: {}", e)))?;
+            levels[current] = result.new_level;
+            character_json = result.updated_character;
+            action_points_used += 1;
+        }
+
+        Ok(SweepCell {
+            comprehension: three_d.comprehension,
+            bone_structure: three_d.bone_structure,
+            physique: three_d.physique,
+            total_level: levels.iter().sum(),
+            levels,
+            action_points_used,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn sweep_favors_comprehension_for_comprehension_formulas() {
+        let realms: Vec<_> = (1..=5)
+            .map(|level| {
+                json!({"level": level, "exp_required": 100 * level, "qi_gain": 10,
+                       "martial_arts_attainment": 0, "qi_quality": 1.0, "attack_speed": 1.0,
+                       "qi_recovery_rate": 0.05, "entries": []})
+            })
+            .collect();
+        let mut core = WushenCore::new();
+        core.load_internals(
+            &json!([{"id": "tuna", "name": "吐纳", "description": "", "rarity": 1,
+                     "type": "neutral", "cultivation_formula": "x * 10", "realms": realms}])
+            .to_string(),
+        )
+        .unwrap();
+
+        let request = json!({
+            "budget": 30, "min_stat": 5, "step": 5, "action_points": 10,
+            "manuals": [{"manual_id": "tuna", "manual_type": "internal"}]
+        });
+        let report: Value = serde_json::from_str(
+            &core
+                .simulate_cultivation_sweep(&request.to_string())
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(report["comprehension"], json!([5, 10, 15, 20]));
+        // 悟性 20、根骨 10 时体魄只剩 0，低于下限
+        assert!(report["cells"][3][1].is_null());
+        let best = &report["best"];
+        assert_eq!(best["comprehension"], 20);
+        assert!(
+            best["total_level"].as_u64().unwrap()
+                > report["cells"][0][0]["total_level"].as_u64().unwrap()
+        );
+
+        let too_fine = json!({
+            "budget": 1000, "action_points": 1,
+            "manuals": [{"manual_id": "tuna", "manual_type": "internal"}]
+        });
+        assert!(core
+            .simulate_cultivation_sweep(&too_fine.to_string())
+            .is_err());
+    }
+}
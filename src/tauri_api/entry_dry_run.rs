@@ -0,0 +1,147 @@
+/// 词条试算：在编辑器给定的角色、对手与攻击结果下试算单个词条，
+/// 返回条件是否满足与各效果的数值，不必跑完整场战斗（词条可以尚未保存到数据包）
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use super::WushenCore;
+use crate::battle::battle_state::Side;
+use crate::character::json::parse_character_panel;
+use crate::effect::condition::AttackResult;
+use crate::effect::dry_run::EntryDryRun;
+use crate::effect::entry::Entry;
+use crate::effect::formula::{CultivationFormulaContext, FormulaCalculator, FormulaContextKind};
+use crate::error::{WushenError, WushenResult};
+
+/// 试算请求
+#[derive(Debug, Clone, Deserialize)]
+pub struct EntryDryRunRequest {
+    pub entry: Entry,
+    /// 词条持有者（角色JSON，战斗词条按开战时的战斗面板试算）
+    pub character: Value,
+    /// 对手（角色JSON，为空时以持有者自身为对手）
+    #[serde(default)]
+    pub opponent: Option<Value>,
+    /// 攻击结果（攻击后/防御后的词条使用）
+    #[serde(default)]
+    pub attack_result: Option<AttackResult>,
+    /// 战斗环境变量
+    #[serde(default)]
+    pub environment: BTreeMap<String, f64>,
+}
+
+impl WushenCore {
+    /// 试算单个词条
+    /// 参数：试算请求JSON（EntryDryRunRequest）
+    /// 返回：试算结果JSON（EntryDryRun）
+    pub fn dry_run_entry(&self, request_json: &str) -> WushenResult<String> {
+        let request: EntryDryRunRequest = serde_json::from_str(request_json)
+            .map_err(|e| WushenError::ParseError(format!("解析词条试算请求失败: {}", e)))?;
+        let character_json = request.character.to_string();
+
+        let dry_run = match FormulaContextKind::for_trigger(request.entry.trigger) {
+            FormulaContextKind::Battle => {
+                let opponent_json = request
+                    .opponent
+                    .as_ref()
+                    .map_or_else(|| character_json.clone(), Value::to_string);
+                let mut engine = self.build_battle_engine(
+                    &character_json,
+                    &opponent_json,
+                    None,
+                    None,
+                    &self.content.battle_config,
+                )?;
+                engine.set_environment(request.environment);
+                engine.dry_run_entry(Side::A, &request.entry, request.attack_result)
+            }
+            _ => {
+                let panel = parse_character_panel(&character_json)?;
+                let context = panel.create_cultivation_context(&self.content.manual_manager);
+                let condition_met = request
+                    .entry
+                    .condition
+                    .as_ref()
+                    .is_none_or(|condition| condition.check_cultivation(&context));
+                let formula_context = CultivationFormulaContext {
+                    self_panel: panel.clone(),
+                };
+                EntryDryRun::evaluate(&request.entry, condition_met, &panel, None, |formula| {
+                    FormulaCalculator::evaluate_cultivation(formula, &formula_context)
+                })
+            }
+        };
+        serde_json::to_string(&dry_run)
+            .map_err(|e| WushenError::ParseError(format!("序列化词条试算结果失败: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn character(physique: u32) -> Value {
+        json!({"name": "试招", "three_d": {"comprehension": 10, "bone_structure": 10, "physique": physique},
+               "traits": [], "internals": {"owned": [], "equipped": null},
+               "attack_skills": {"owned": [], "equipped": null},
+               "defense_skills": {"owned": [], "equipped": null}})
+    }
+
+    fn dry_run(core: &WushenCore, request: Value) -> Value {
+        serde_json::from_str(&core.dry_run_entry(&request.to_string()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn dry_run_reports_condition_and_effect_values() {
+        let core = WushenCore::new();
+        let entry = json!({
+            "trigger": "after_attack",
+            "condition": "attack_broke_qi_defense",
+            "effects": [
+                {"type": "modify_attribute", "target": "hp",
+                 "value": "attack_hp_damage * 0.5", "operation": "add"},
+                {"type": "modify_percentage", "target": "base_attack",
+                 "value": 0.1, "operation": "add", "target_panel": "opponent"},
+                {"type": "extra_attack", "output": "self_base_atack"}
+            ]
+        });
+        let report = dry_run(
+            &core,
+            json!({"entry": entry, "character": character(10), "opponent": character(20),
+                   "attack_result": {"hp_damage": 80.0, "broke_qi_defense": true}}),
+        );
+        assert_eq!(report["condition_met"], true);
+        let effects = &report["effects"];
+        assert_eq!(effects[0]["value"], 40.0);
+        // 对手基础攻击力 3 × 20 = 60，百分比效果折算为 6
+        assert_eq!(effects[1]["applied_value"], 6.0);
+        assert!(effects[2]["error"].is_string());
+
+        let missed = dry_run(
+            &core,
+            json!({"entry": entry, "character": character(10),
+                   "attack_result": {"hp_damage": 80.0, "broke_qi_defense": false}}),
+        );
+        assert_eq!(missed["condition_met"], false);
+        assert_eq!(missed["effects"][0]["value"], 40.0);
+    }
+
+    #[test]
+    fn dry_run_uses_the_cultivation_context_outside_battle() {
+        let core = WushenCore::new();
+        let report = dry_run(
+            &core,
+            json!({"character": character(10), "entry": {
+                "trigger": "cultivating_internal",
+                "condition": {"attribute_comparison":
+                    {"attribute": "comprehension", "op": "greater_than_or_equal", "value": 10}},
+                "effects": [{"type": "modify_attribute", "target": "cultivation_exp_gain",
+                             "value": "self_x * 2", "operation": "add"}]
+            }}),
+        );
+        assert_eq!(report["condition_met"], true);
+        assert_eq!(report["effects"][0]["value"], 20.0);
+        assert!(report.get("invalid").is_none());
+    }
+}
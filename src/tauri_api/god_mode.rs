@@ -0,0 +1,336 @@
+/// 上帝模式（`god-mode` 特性）：供编辑器试玩后期内容的调试接口
+/// 所有操作直接修改当前存档，跳过行动点与条件检查
+use serde::{Deserialize, Serialize};
+
+use super::{character_state_to_panel, new_character, update_character_from_panel, WushenCore};
+use crate::error::{WushenError, WushenResult};
+use crate::event::Reward;
+use crate::game::{
+    CharacterState, GameOutcome, GameResponse, GameRuntime, StorylineProgress,
+    ThreeDimensionalState,
+};
+
+/// 功法等级上限
+const MAX_MANUAL_LEVEL: u32 = 5;
+
+/// 未开始游戏时跳转所用的默认角色
+const DEBUG_CHARACTER_ID: &str = "debug";
+const DEBUG_CHARACTER_NAME: &str = "试玩角色";
+
+/// 调试指令
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DebugCommand {
+    /// 立即获得功法
+    GrantManual {
+        manual_id: String,
+        manual_type: String,
+    },
+    /// 立即获得特性
+    GrantTrait { trait_id: String },
+    /// 设置已拥有功法的等级（经验清零）
+    SetManualLevel {
+        manual_id: String,
+        manual_type: String,
+        level: u32,
+    },
+    /// 跳转到当前剧情线的任意事件
+    JumpToEvent { event_id: String },
+    /// 跳转到任意剧情线的任意事件，可替换当前角色
+    GotoEvent {
+        storyline_id: String,
+        event_id: String,
+        #[serde(default)]
+        character: Option<Box<CharacterState>>,
+    },
+    /// 设置三维
+    SetThreeD { three_d: ThreeDimensionalState },
+    /// 设置武学素养
+    SetAttainment { value: f64 },
+    /// 强制触发指定奇遇
+    TriggerAdventure {
+        adventure_id: String,
+        #[serde(default)]
+        attacker_qi_output_rate: Option<f64>,
+        #[serde(default)]
+        defender_qi_output_rate: Option<f64>,
+    },
+}
+
+impl WushenCore {
+    /// 执行调试指令
+    pub fn debug_execute(&mut self, command: DebugCommand) -> WushenResult<GameResponse> {
+        self.transaction(|core| match command {
+            DebugCommand::GrantManual {
+                manual_id,
+                manual_type,
+            } => core.debug_grant_manual(&manual_id, &manual_type),
+            DebugCommand::GrantTrait { trait_id } => core.debug_grant_trait(&trait_id),
+            DebugCommand::SetManualLevel {
+                manual_id,
+                manual_type,
+                level,
+            } => core.debug_set_manual_level(&manual_id, &manual_type, level),
+            DebugCommand::JumpToEvent { event_id } => core.debug_jump_to_event(&event_id),
+            DebugCommand::GotoEvent {
+                storyline_id,
+                event_id,
+                character,
+            } => core.debug_goto_event(&storyline_id, &event_id, character.map(|c| *c)),
+            DebugCommand::SetThreeD { three_d } => core.debug_set_three_d(three_d),
+            DebugCommand::SetAttainment { value } => core.debug_set_attainment(value),
+            DebugCommand::TriggerAdventure {
+                adventure_id,
+                attacker_qi_output_rate,
+                defender_qi_output_rate,
+            } => core.travel(
+                attacker_qi_output_rate,
+                defender_qi_output_rate,
+                Some(&adventure_id),
+            ),
+        })
+    }
+
+    pub fn debug_grant_manual(
+        &mut self,
+        manual_id: &str,
+        manual_type: &str,
+    ) -> WushenResult<GameResponse> {
+        let manuals = &self.content.manual_manager;
+        let (reward, name) = match manual_type {
+            "internal" => (
+                Reward::Internal {
+                    id: manual_id.to_string(),
+                },
+                manuals
+                    .get_internal(manual_id)
+                    .map(|m| m.manual.name.clone()),
+            ),
+            "attack_skill" => (
+                Reward::AttackSkill {
+                    id: manual_id.to_string(),
+                },
+                manuals
+                    .get_attack_skill(manual_id)
+                    .map(|m| m.manual.name.clone()),
+            ),
+            "defense_skill" => (
+                Reward::DefenseSkill {
+                    id: manual_id.to_string(),
+                },
+                manuals
+                    .get_defense_skill(manual_id)
+                    .map(|m| m.manual.name.clone()),
+            ),
+            _ => return Err(WushenError::Invalid("未知的功法类型".to_string())),
+        };
+        let name =
+            name.ok_or_else(|| WushenError::NotFound(format!("功法 {} 不存在", manual_id)))?;
+        self.debug_apply_reward(reward, format!("已获得功法：{}", name))
+    }
+
+    pub fn debug_grant_trait(&mut self, trait_id: &str) -> WushenResult<GameResponse> {
+        let name = self
+            .content
+            .trait_manager
+            .get_trait(trait_id)
+            .map(|t| t.name.clone())
+            .ok_or_else(|| WushenError::NotFound(format!("特性 {} 不存在", trait_id)))?;
+        let reward = Reward::Trait {
+            id: trait_id.to_string(),
+        };
+        self.debug_apply_reward(reward, format!("已获得特性：{}", name))
+    }
+
+    pub fn debug_set_manual_level(
+        &mut self,
+        manual_id: &str,
+        manual_type: &str,
+        level: u32,
+    ) -> WushenResult<GameResponse> {
+        if level > MAX_MANUAL_LEVEL {
+            return Err(WushenError::Invalid(format!(
+                "功法等级范围为 0-{}",
+                MAX_MANUAL_LEVEL
+            )));
+        }
+        self.debug_update_character(|_, character| {
+            let manuals = match manual_type {
+                "internal" => &mut character.internals,
+                "attack_skill" => &mut character.attack_skills,
+                "defense_skill" => &mut character.defense_skills,
+                _ => return Err(WushenError::Invalid("未知的功法类型".to_string())),
+            };
+            let owned = manuals
+                .owned
+                .iter_mut()
+                .find(|owned| owned.id == manual_id)
+                .ok_or_else(|| {
+                    WushenError::ConditionNotMet(format!("尚未拥有功法 {}", manual_id))
+                })?;
+            owned.level = level;
+            owned.exp = 0.0;
+            // 重新装配面板，使等级相关数值生效
+            let panel = character_state_to_panel(character);
+            update_character_from_panel(character, &panel);
+            Ok(format!("功法 {} 等级已设为 {}", manual_id, level))
+        })
+    }
+
+    /// 跳转到当前剧情线的任意事件
+    pub fn debug_jump_to_event(&mut self, event_id: &str) -> WushenResult<GameResponse> {
+        let storyline_id = self
+            .game_runtime
+            .as_ref()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?
+            .save
+            .storyline_progress
+            .as_ref()
+            .map(|progress| progress.storyline_id.clone())
+            .ok_or_else(|| WushenError::InvalidState("剧情线已完成".to_string()))?;
+        self.debug_goto_event(&storyline_id, event_id, None)
+    }
+
+    /// 跳转到任意剧情线的任意事件，可替换当前角色
+    ///
+    /// 按目标节点重建最小一致状态：剧情进度、行动点（中间节点取节点配置，其余为 0）、
+    /// 清除进行中的奇遇；尚未开始游戏时以默认角色创建新存档
+    pub fn debug_goto_event(
+        &mut self,
+        storyline_id: &str,
+        event_id: &str,
+        character_override: Option<CharacterState>,
+    ) -> WushenResult<GameResponse> {
+        let storyline = self
+            .content
+            .event_manager
+            .get_storyline(storyline_id)
+            .cloned()
+            .ok_or_else(|| WushenError::NotFound(format!("剧情线 {} 不存在", storyline_id)))?;
+        if !storyline.events.iter().any(|event| event.id == event_id) {
+            return Err(WushenError::NotFound(format!(
+                "剧情线 {} 中不存在事件 {}",
+                storyline_id, event_id
+            )));
+        }
+        let progress = StorylineProgress {
+            storyline_id: storyline_id.to_string(),
+            event_id: event_id.to_string(),
+        };
+        let mut save = match self.game_runtime.take() {
+            Some(runtime) => runtime.save,
+            None => {
+                let character = new_character(
+                    DEBUG_CHARACTER_ID.to_string(),
+                    DEBUG_CHARACTER_NAME.to_string(),
+                    ThreeDimensionalState {
+                        comprehension: 10,
+                        bone_structure: 10,
+                        physique: 10,
+                    },
+                );
+                self.new_save(character, progress.clone())
+            }
+        };
+        if let Some(character) = character_override {
+            save.current_character = character;
+        }
+        save.storyline_progress = Some(progress);
+        save.active_adventure_id = None;
+        let runtime = self.game_runtime.insert(GameRuntime::new(save));
+        Self::advance_to_event(runtime, &storyline, event_id)?;
+
+        let outcome = GameOutcome::Info {
+            message: format!("已跳转到事件 {}", event_id),
+        };
+        self.game_view(Some(outcome))
+    }
+
+    pub fn debug_set_three_d(
+        &mut self,
+        three_d: ThreeDimensionalState,
+    ) -> WushenResult<GameResponse> {
+        self.debug_update_character(|_, character| {
+            character.three_d = three_d;
+            Ok("三维已修改".to_string())
+        })
+    }
+
+    pub fn debug_set_attainment(&mut self, value: f64) -> WushenResult<GameResponse> {
+        if !value.is_finite() || value < 0.0 {
+            return Err(WushenError::Invalid("武学素养必须为非负数".to_string()));
+        }
+        self.debug_update_character(|_, character| {
+            character.martial_arts_attainment = Some(value);
+            Ok(format!("武学素养已设为 {}", value))
+        })
+    }
+
+    fn debug_apply_reward(
+        &mut self,
+        reward: Reward,
+        message: String,
+    ) -> WushenResult<GameResponse> {
+        self.debug_update_character(|core, character| {
+            let mut start_trait_pool = Vec::new();
+            core.apply_rewards_to_character(character, &mut start_trait_pool, &[reward])?;
+            Ok(message)
+        })
+    }
+
+    /// 修改当前角色并返回附带提示的游戏视图
+    fn debug_update_character(
+        &mut self,
+        update: impl FnOnce(&Self, &mut CharacterState) -> WushenResult<String>,
+    ) -> WushenResult<GameResponse> {
+        let mut character = self
+            .game_runtime
+            .as_ref()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?
+            .save
+            .current_character
+            .clone();
+        let message = update(self, &mut character)?;
+        if let Some(runtime) = self.game_runtime.as_mut() {
+            runtime.save.set_current_character(character);
+        }
+        self.game_view(Some(GameOutcome::Info { message }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::started_core;
+
+    #[test]
+    fn debug_commands_edit_current_save() {
+        let mut core = started_core();
+
+        let command: DebugCommand =
+            serde_json::from_str(r#"{"type":"jump_to_event","event_id":"end"}"#).unwrap();
+        let response = core.debug_execute(command).unwrap();
+        let progress = response.view.save.storyline_progress.unwrap();
+        assert_eq!(progress.event_id, "end");
+
+        core.debug_set_attainment(42.0).unwrap();
+        let save = core.current_save().unwrap().clone();
+        assert_eq!(save.current_character.martial_arts_attainment, Some(42.0));
+
+        assert!(core.debug_jump_to_event("missing").is_err());
+
+        let mut fresh = WushenCore::with_content(core.content());
+        let mut character = save.current_character.clone();
+        character.name = "替身".to_string();
+        let response = fresh
+            .debug_goto_event("s", "start", Some(character))
+            .unwrap();
+        assert_eq!(response.view.save.current_character.name, "替身");
+        assert!(fresh.debug_goto_event("s", "missing", None).is_err());
+        assert!(fresh.debug_goto_event("missing", "start", None).is_err());
+        assert!(core.debug_grant_trait("missing").is_err());
+        assert!(core
+            .debug_set_manual_level("missing", "internal", 9)
+            .is_err());
+    }
+}
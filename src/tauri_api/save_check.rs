@@ -0,0 +1,156 @@
+/// 恢复存档前的一致性检查：存档引用的剧情线、事件、奇遇与已装备功法
+/// 须在当前加载的内容中存在，否则进入游戏后会在构建视图时才报错
+use serde::Serialize;
+
+use super::WushenCore;
+use crate::game::SaveGame;
+
+/// 存档与当前内容不一致之处
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SaveProblem {
+    /// 剧情线不存在；修复时结束当前剧情
+    MissingStoryline { storyline_id: String },
+    /// 剧情线存在但当前事件不存在；修复时回到剧情线起点
+    MissingStoryEvent {
+        storyline_id: String,
+        event_id: String,
+    },
+    /// 进行中的奇遇不存在；修复时放弃该奇遇
+    MissingAdventure { adventure_id: String },
+    /// 已装备的功法不存在；修复时卸下该功法
+    MissingEquippedManual {
+        manual_type: String,
+        manual_id: String,
+    },
+}
+
+impl SaveProblem {
+    /// 面向玩家的问题说明
+    pub fn describe(&self) -> String {
+        match self {
+            SaveProblem::MissingStoryline { storyline_id } => {
+                format!("剧情线 {} 不存在", storyline_id)
+            }
+            SaveProblem::MissingStoryEvent {
+                storyline_id,
+                event_id,
+            } => format!("剧情线 {} 中的事件 {} 不存在", storyline_id, event_id),
+            SaveProblem::MissingAdventure { adventure_id } => {
+                format!("奇遇事件 {} 不存在", adventure_id)
+            }
+            SaveProblem::MissingEquippedManual {
+                manual_type,
+                manual_id,
+            } => format!("已装备的功法 {}（{}）不存在", manual_id, manual_type),
+        }
+    }
+}
+
+impl WushenCore {
+    /// 检查存档与当前内容是否一致，返回发现的问题（无问题时为空）
+    pub fn game_check_save(&self, save: &SaveGame) -> Vec<SaveProblem> {
+        let mut repaired = save.clone();
+        self.game_repair_save(&mut repaired)
+    }
+
+    /// 修复存档中与当前内容不一致之处，返回已修复的问题；
+    /// 修复结果再次检查不会出现问题，重复修复也不会再改动存档
+    pub fn game_repair_save(&self, save: &mut SaveGame) -> Vec<SaveProblem> {
+        let events = &self.content.event_manager;
+        let manuals = &self.content.manual_manager;
+        let mut problems = Vec::new();
+
+        if let Some(progress) = save.storyline_progress.as_mut() {
+            match events.get_storyline(&progress.storyline_id) {
+                None => {
+                    problems.push(SaveProblem::MissingStoryline {
+                        storyline_id: progress.storyline_id.clone(),
+                    });
+                    save.storyline_progress = None;
+                    save.pending_victory_decision = None;
+                }
+                Some(storyline) => {
+                    if !storyline.events.iter().any(|e| e.id == progress.event_id) {
+                        problems.push(SaveProblem::MissingStoryEvent {
+                            storyline_id: progress.storyline_id.clone(),
+                            event_id: progress.event_id.clone(),
+                        });
+                        progress.event_id = storyline.start_event_id.clone();
+                        save.pending_victory_decision = None;
+                    }
+                }
+            }
+        }
+
+        if let Some(adventure_id) = save.active_adventure_id.as_ref() {
+            if events.get_adventure_event(adventure_id).is_none() {
+                problems.push(SaveProblem::MissingAdventure {
+                    adventure_id: adventure_id.clone(),
+                });
+                save.active_adventure_id = None;
+            }
+        }
+
+        let character = &mut save.current_character;
+        let slots = [
+            ("internal", &mut character.internals),
+            ("attack_skill", &mut character.attack_skills),
+            ("defense_skill", &mut character.defense_skills),
+        ];
+        for (manual_type, state) in slots {
+            let Some(manual_id) = state.equipped.as_deref() else {
+                continue;
+            };
+            let exists = match manual_type {
+                "internal" => manuals.get_internal(manual_id).is_some(),
+                "attack_skill" => manuals.get_attack_skill(manual_id).is_some(),
+                _ => manuals.get_defense_skill(manual_id).is_some(),
+            };
+            if !exists {
+                problems.push(SaveProblem::MissingEquippedManual {
+                    manual_type: manual_type.to_string(),
+                    manual_id: manual_id.to_string(),
+                });
+                state.equipped = None;
+            }
+        }
+
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{core_with_storyline, start_game};
+
+    #[test]
+    fn resume_rejects_broken_save_until_repaired() {
+        let mut core = core_with_storyline();
+        let mut save = start_game(&mut core, "c").view.save;
+        assert!(core.game_check_save(&save).is_empty());
+
+        save.storyline_progress.as_mut().unwrap().event_id = "已删除".to_string();
+        save.active_adventure_id = Some("旧奇遇".to_string());
+        save.current_character.internals.equipped = Some("旧内功".to_string());
+        let problems = core.game_check_save(&save);
+        assert_eq!(problems.len(), 3);
+        assert_eq!(
+            problems[2],
+            SaveProblem::MissingEquippedManual {
+                manual_type: "internal".to_string(),
+                manual_id: "旧内功".to_string(),
+            }
+        );
+        let error = core.game_resume(save.clone()).unwrap_err();
+        assert!(error.message().contains("事件 已删除 不存在"));
+        assert_eq!(error.code(), "invalid");
+
+        assert_eq!(core.game_repair_save(&mut save), problems);
+        assert!(core.game_repair_save(&mut save).is_empty());
+        assert_eq!(save.storyline_progress.as_ref().unwrap().event_id, "start");
+        assert!(save.active_adventure_id.is_none());
+        assert!(core.game_resume(save).is_ok());
+    }
+}
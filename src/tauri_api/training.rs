@@ -0,0 +1,200 @@
+/// 弟子修行：未被操控的队伍成员每到新的剧情节点，按修行安排自行修行指定功法
+///
+/// 成员修行沿用功法修行公式，但不计季节、限时增益与称号等当前角色的额外词条
+use super::{
+    character_state_to_panel, update_character_from_panel, CultivationResultJson, WushenCore,
+};
+use crate::character::json::{parse_character_panel, serialize_character_panel};
+use crate::error::{WushenError, WushenResult};
+use crate::game::{CharacterState, GameResponse, TrainingAssignment, TrainingReport};
+
+impl WushenCore {
+    /// 安排队伍成员修行（为 None 时取消安排）
+    pub fn game_assign_training(
+        &mut self,
+        character_id: String,
+        assignment: Option<TrainingAssignment>,
+    ) -> WushenResult<GameResponse> {
+        self.transaction(|core| {
+            let runtime = core
+                .game_runtime
+                .as_mut()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            runtime.save.assign_training(&character_id, assignment)?;
+            core.game_view(None)
+        })
+    }
+
+    /// 以事务方式执行剧情操作；操作使剧情进入新节点时，队伍成员按安排修行一次，
+    /// 修行结果附在返回中（手动战斗进行中时拒绝执行）
+    pub(super) fn story_action(
+        &mut self,
+        action: impl FnOnce(&mut Self) -> WushenResult<GameResponse>,
+    ) -> WushenResult<GameResponse> {
+        if self.has_battle_session() {
+            return Err(WushenError::InvalidState(
+                "战斗进行中，请先完成当前战斗".to_string(),
+            ));
+        }
+        self.transaction(|core| {
+            let node = core.current_node();
+            let mut response = action(core)?;
+            if core.current_node() == node {
+                return Ok(response);
+            }
+            let training = core.train_party()?;
+            if !training.is_empty() {
+                response.view = core.game_view(None)?.view;
+                response.training = training;
+            }
+            Ok(response)
+        })
+    }
+
+    fn current_node(&self) -> Option<(String, String)> {
+        let progress = self
+            .game_runtime
+            .as_ref()?
+            .save
+            .storyline_progress
+            .as_ref()?;
+        Some((progress.storyline_id.clone(), progress.event_id.clone()))
+    }
+
+    /// 未被操控的成员按安排修行，返回各成员的修行结果
+    fn train_party(&mut self) -> WushenResult<Vec<TrainingReport>> {
+        let runtime = self
+            .game_runtime
+            .as_ref()
+            .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+        let mut members = runtime.save.party.members.clone();
+        let mut reports = Vec::new();
+        for member in &mut members {
+            let Some(assignment) = runtime.save.party.training.get(&member.id) else {
+                continue;
+            };
+            if let Some(report) = self.train_member(member, assignment)? {
+                reports.push(report);
+            }
+        }
+        if !reports.is_empty() {
+            let runtime = self
+                .game_runtime
+                .as_mut()
+                .ok_or_else(|| WushenError::InvalidState("游戏尚未初始化".to_string()))?;
+            runtime.save.party.members = members;
+        }
+        Ok(reports)
+    }
+
+    /// 按安排修行，一次都未能修行（如功法已满级）时返回 None
+    fn train_member(
+        &self,
+        member: &mut CharacterState,
+        assignment: &TrainingAssignment,
+    ) -> WushenResult<Option<TrainingReport>> {
+        let mut panel = character_state_to_panel(member);
+        let mut report = TrainingReport {
+            character_id: member.id.clone(),
+            name: member.name.clone(),
+            manual_id: assignment.manual_id.clone(),
+            manual_type: assignment.manual_type.clone(),
+            sessions: 0,
+            exp_gain: 0.0,
+            old_level: 0,
+            new_level: 0,
+        };
+        for _ in 0..assignment.action_points {
+            let Ok(result_json) = self.execute_cultivation_with_entries(
+                &serialize_character_panel(&panel)?,
+                &assignment.manual_id,
+                &assignment.manual_type,
+                Vec::new(),
+            ) else {
+                break;
+            };
+            let result: CultivationResultJson = serde_json::from_str(&result_json)
+                .map_err(|e| WushenError::ParseError(format!("解析修行结果失败: {}", e)))?;
+            if report.sessions == 0 {
+                report.old_level = result.old_level;
+            }
+            report.sessions += 1;
+            report.exp_gain += result.exp_gain;
+            report.new_level = result.new_level;
+            panel = parse_character_panel(&result.updated_character)?;
+        }
+        if report.sessions == 0 {
+            return Ok(None);
+        }
+        update_character_from_panel(member, &panel);
+        member.cultivation_history.clear();
+        Ok(Some(report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{OwnedManualState, ThreeDimensionalState};
+    use crate::test_support::{start_game, MINIMAL_STORYLINE};
+    use serde_json::json;
+
+    #[test]
+    fn disciples_train_when_story_advances() {
+        let realms: Vec<_> = (1..=5)
+            .map(|level| {
+                json!({"level": level, "exp_required": 100 * level, "qi_gain": 10,
+                       "martial_arts_attainment": 1, "qi_quality": 1.0, "attack_speed": 1.0,
+                       "qi_recovery_rate": 0.05, "entries": []})
+            })
+            .collect();
+        let mut core = WushenCore::new();
+        core.load_internals(
+            &json!({"internals": [{"id": "吐纳", "name": "吐纳", "description": "", "rarity": 1,
+                                   "type": "neutral", "cultivation_formula": "x * 10",
+                                   "realms": realms}]})
+            .to_string(),
+        )
+        .unwrap();
+        core.load_storylines(MINIMAL_STORYLINE).unwrap();
+        start_game(&mut core, "师父");
+        let three_d = ThreeDimensionalState {
+            comprehension: 10,
+            bone_structure: 10,
+            physique: 10,
+        };
+        core.game_recruit_member("弟子".to_string(), "弟子".to_string(), three_d)
+            .unwrap();
+        let assignment = TrainingAssignment {
+            manual_id: "吐纳".to_string(),
+            manual_type: "internal".to_string(),
+            action_points: 2,
+        };
+        assert!(core
+            .game_assign_training("弟子".to_string(), Some(assignment.clone()))
+            .is_err());
+        let save = &mut core.game_runtime.as_mut().unwrap().save;
+        save.party.members[0]
+            .internals
+            .owned
+            .push(OwnedManualState {
+                id: "吐纳".to_string(),
+                level: 1,
+                exp: 0.0,
+            });
+        core.game_assign_training("弟子".to_string(), Some(assignment))
+            .unwrap();
+
+        let response = core.game_story_continue().unwrap();
+        let report = &response.training[0];
+        assert_eq!((report.character_id.as_str(), report.sessions), ("弟子", 2));
+        assert!(report.exp_gain > 0.0);
+        let save = &response.view.save;
+        assert_eq!(save.current_character.id, "师父");
+        assert_eq!(
+            save.party.members[0].internals.owned[0].level,
+            report.new_level
+        );
+        assert!(report.new_level > report.old_level);
+    }
+}
@@ -0,0 +1,144 @@
+/// 特性预览：在参考角色上对比有无某特性时的修行经验与交手伤害，
+/// 供特性编辑器即时检查数值（特性可以尚未保存到数据包）
+use serde::Serialize;
+
+use super::{CultivationResultJson, WushenCore};
+use crate::character::json::{parse_character_panel, serialize_character_panel};
+use crate::character::traits::parse_traits_with_warnings;
+use crate::error::{WushenError, WushenResult};
+
+/// 特性预览结果
+#[derive(Debug, Clone, Serialize)]
+pub struct TraitPreview {
+    pub trait_id: String,
+    /// 各已拥有功法修行一次获得的经验（无法修行的功法，如已满级，不列出）
+    pub cultivation: Vec<CultivationPreview>,
+    /// 与参考对手交手一次的生命伤害
+    pub battle: BattlePreview,
+}
+
+/// 单个功法的修行经验对比
+#[derive(Debug, Clone, Serialize)]
+pub struct CultivationPreview {
+    pub manual_id: String,
+    pub manual_type: String,
+    /// 无该特性时的经验
+    pub base_exp: f64,
+    /// 有该特性时的经验
+    pub trait_exp: f64,
+}
+
+/// 交手伤害对比（已应用战斗开始词条）
+#[derive(Debug, Clone, Serialize)]
+pub struct BattlePreview {
+    pub base_damage_dealt: f64,
+    pub trait_damage_dealt: f64,
+    pub base_damage_taken: f64,
+    pub trait_damage_taken: f64,
+}
+
+impl WushenCore {
+    /// 预览特性对修行与战斗的影响
+    /// 参数：特性JSON（单个特性），参考角色JSON，参考对手JSON（为空时与无该特性的参考角色对战）
+    /// 返回：特性预览JSON
+    pub fn preview_trait(
+        &self,
+        trait_json: &str,
+        character_json: &str,
+        dummy_json: Option<&str>,
+    ) -> WushenResult<String> {
+        let (mut traits, _) = parse_traits_with_warnings(&format!("[{}]", trait_json))?;
+        let trait_ = traits
+            .pop()
+            .ok_or_else(|| WushenError::Invalid("特性数据为空".to_string()))?;
+        let trait_id = trait_.id.clone();
+
+        let mut base_panel = parse_character_panel(character_json)?;
+        base_panel.traits.retain(|id| id != &trait_id);
+        let mut trait_panel = base_panel.clone();
+        trait_panel.traits.push(trait_id.clone());
+        let base_json = serialize_character_panel(&base_panel)?;
+        let trait_panel_json = serialize_character_panel(&trait_panel)?;
+
+        // 在内容副本中加入（可能尚未保存的）特性，不影响当前内容
+        let mut preview = self.snapshot();
+        preview
+            .content_mut()
+            .trait_manager
+            .load_traits(vec![trait_]);
+
+        let manuals = [
+            ("internal", base_panel.owned_internals.keys()),
+            ("attack_skill", base_panel.owned_attack_skills.keys()),
+            ("defense_skill", base_panel.owned_defense_skills.keys()),
+        ];
+        let mut cultivation = Vec::new();
+        for (manual_type, ids) in manuals {
+            for manual_id in ids {
+                let exp = |json: &str| {
+                    let result = preview.execute_cultivation(json, manual_id, manual_type)?;
+                    serde_json::from_str::<CultivationResultJson>(&result)
+                        .map(|r| r.exp_gain)
+                        .map_err(|e| e.to_string())
+                };
+                if let (Ok(base_exp), Ok(trait_exp)) = (exp(&base_json), exp(&trait_panel_json)) {
+                    cultivation.push(CultivationPreview {
+                        manual_id: manual_id.clone(),
+                        manual_type: manual_type.to_string(),
+                        base_exp,
+                        trait_exp,
+                    });
+                }
+            }
+        }
+
+        let dummy_json = dummy_json.unwrap_or(&base_json);
+        let base = preview.exchange_preview(&base_json, dummy_json, None, None)?;
+        let with_trait = preview.exchange_preview(&trait_panel_json, dummy_json, None, None)?;
+        let report = TraitPreview {
+            trait_id,
+            cultivation,
+            battle: BattlePreview {
+                base_damage_dealt: base.attacker_to_defender.hp_damage,
+                trait_damage_dealt: with_trait.attacker_to_defender.hp_damage,
+                base_damage_taken: base.defender_to_attacker.hp_damage,
+                trait_damage_taken: with_trait.defender_to_attacker.hp_damage,
+            },
+        };
+        serde_json::to_string(&report)
+            .map_err(|e| WushenError::ParseError(format!("序列化特性预览失败: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::panel::{CharacterPanel, ThreeDimensional};
+
+    #[test]
+    fn preview_compares_damage_with_unsaved_trait() {
+        let core = WushenCore::new();
+        let panel = CharacterPanel::new("参考角色".to_string(), ThreeDimensional::new(10, 10, 10));
+        let character_json = serialize_character_panel(&panel).unwrap();
+        let trait_json = r#"{"id":"神力","name":"神力","description":"","entries":[{
+            "trigger":"battle_start",
+            "effects":[{"type":"modify_attribute","target":"base_attack",
+                        "value":1000.0,"operation":"add","can_exceed_limit":false}]
+        }]}"#;
+
+        let report: serde_json::Value = serde_json::from_str(
+            &core
+                .preview_trait(trait_json, &character_json, None)
+                .unwrap(),
+        )
+        .unwrap();
+        let battle = &report["battle"];
+        assert_eq!(report["trait_id"], "神力");
+        assert!(
+            battle["trait_damage_dealt"].as_f64().unwrap()
+                > battle["base_damage_dealt"].as_f64().unwrap()
+        );
+        // 预览不会把特性写入当前内容
+        assert!(core.content().trait_manager().get_trait("神力").is_none());
+    }
+}
@@ -0,0 +1,57 @@
+/// 游戏操作的事务语义：各游戏操作先复制存档状态计算、再分段写回，
+/// 中途出错时统一回滚，保证一次操作要么完整生效，要么不留痕迹
+use super::WushenCore;
+use crate::error::WushenResult;
+
+impl WushenCore {
+    /// 以事务方式执行游戏操作：操作中途出错时，丢弃已写回的全部改动（角色、随机数状态、
+    /// 开局特性池、剧情进度等），恢复为操作前的运行时
+    pub(super) fn transaction<T>(
+        &mut self,
+        action: impl FnOnce(&mut Self) -> WushenResult<T>,
+    ) -> WushenResult<T> {
+        let staged = self.game_runtime.clone();
+        let result = action(self);
+        if result.is_err() {
+            self.game_runtime = staged;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::WushenError;
+    use crate::test_support::started_core;
+
+    fn save_json(core: &WushenCore) -> serde_json::Value {
+        serde_json::to_value(core.current_save().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn failed_action_rolls_back_all_staged_changes() {
+        let mut core = started_core();
+        let before = save_json(&core);
+
+        let result = core.transaction(|core| {
+            core.story_continue()?;
+            let save = &mut core.game_runtime.as_mut().unwrap().save;
+            save.rng_state = save.rng_state.wrapping_add(1);
+            save.start_trait_pool.clear();
+            save.current_character.name = "改名".to_string();
+            Err::<(), _>(WushenError::Other("注入的错误".to_string()))
+        });
+        assert_eq!(result.unwrap_err().message(), "注入的错误");
+        assert_eq!(save_json(&core), before);
+
+        // 公开操作失败同样不改变存档；成功的操作正常生效
+        assert!(core.game_accept_quest("missing".to_string()).is_err());
+        assert_eq!(save_json(&core), before);
+        let response = core.game_story_continue().unwrap();
+        assert_eq!(
+            response.view.save.storyline_progress.unwrap().event_id,
+            "end"
+        );
+    }
+}
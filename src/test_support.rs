@@ -0,0 +1,63 @@
+//! 单元测试共用的夹具：最小剧情线与默认开局请求
+
+use serde_json::{json, Value};
+
+use crate::game::{GameResponse, NewGameRequest};
+use crate::WushenCore;
+
+/// 剧情线 `s`：开端（剧情）→ 终局
+pub(crate) const MINIMAL_STORYLINE: &str = r#"[{"id":"s","name":"测试","start_event_id":"start","events":[
+    {"id":"start","name":"开端","node_type":"start",
+     "content":{"type":"story","text":"","next_event_id":"end"}},
+    {"id":"end","name":"终局","node_type":"end","content":{"type":"end","text":""}}
+]}]"#;
+
+/// 剧情线 `s`（JSON 值）：开端 → 给予行动点的中间节点 `mid` → 终局，测试可在其上改写节点
+pub(crate) fn storyline_with_mid(action_points: u32) -> Value {
+    let mut storylines: Value = serde_json::from_str(MINIMAL_STORYLINE).unwrap();
+    let events = storylines[0]["events"].as_array_mut().unwrap();
+    events[0]["content"]["next_event_id"] = json!("mid");
+    events.insert(
+        1,
+        json!({"id": "mid", "name": "游历", "node_type": "middle", "action_points": action_points,
+               "content": {"type": "story", "text": "", "next_event_id": "end"}}),
+    );
+    storylines
+}
+
+/// 剧情线 `s` 的开局请求，角色 id 与名字相同，三维均为 10
+pub(crate) fn new_game_request(name: &str) -> NewGameRequest {
+    serde_json::from_value(serde_json::json!({
+        "storyline_id": "s",
+        "character_id": name,
+        "name": name,
+        "three_d": { "comprehension": 10, "bone_structure": 10, "physique": 10 }
+    }))
+    .unwrap()
+}
+
+/// 已载入最小剧情线的核心
+pub(crate) fn core_with_storyline() -> WushenCore {
+    let mut core = WushenCore::new();
+    core.load_storylines(MINIMAL_STORYLINE).unwrap();
+    core
+}
+
+/// 已载入给定剧情线的核心
+pub(crate) fn core_with_storylines(storylines: &Value) -> WushenCore {
+    let mut core = WushenCore::new();
+    core.load_storylines(&storylines.to_string()).unwrap();
+    core
+}
+
+/// 以给定角色名在剧情线 `s` 上开局
+pub(crate) fn start_game(core: &mut WushenCore, name: &str) -> GameResponse {
+    core.game_start_new(new_game_request(name)).unwrap()
+}
+
+/// 已载入最小剧情线并以角色 `c` 开局的核心
+pub(crate) fn started_core() -> WushenCore {
+    let mut core = core_with_storyline();
+    start_game(&mut core, "c");
+    core
+}
@@ -0,0 +1,104 @@
+//! 数据格式兼容性检查：用旧格式样例（`tests/fixtures/`）验证更名后的字段仍可读取，
+//! 且读取后按当前格式写出、再次读取结果不变
+//!
+//! 对外字段更名对照表（新增更名时请同步补充样例与测试）：
+//!
+//! | 格式 | 当前字段 | 兼容的旧字段 | 处理方式 |
+//! |------|----------|--------------|----------|
+//! | 战斗日志 `BattleRecord` | `side_a_*` / `side_b_*`（名称、行动条、面板变化） | `attacker_*` / `defender_*` | serde 别名 |
+//! | 功法数据（内功、攻击武技、防御武技） | `type` | `manual_type` | 数据版本升级 v0 -> v1 |
+//! | 存档 `SaveGame` | 后续新增字段 | 缺省 | `#[serde(default)]` |
+//!
+//! 战斗结果 JSON（`attacker_panel`、`defender_panel`、`attacker_panel_delta` 等）
+//! 对外始终以进攻方/防守方命名，由 `tests/public_api.rs` 检查
+use wushen_core::prelude::{BattleLog, BattleRecord};
+
+const LEGACY_BATTLE_LOG: &str = include_str!("fixtures/legacy_battle_log.json");
+
+#[test]
+fn legacy_battle_log_reads_attacker_defender_names() {
+    let log = BattleLog::from_json(LEGACY_BATTLE_LOG).unwrap();
+    let records: Vec<_> = log.get_all_records().iter().collect();
+    assert_eq!(records.len(), 3);
+    match records[0] {
+        BattleRecord::BattleStart {
+            side_a_name,
+            side_b_name,
+            ..
+        } => assert_eq!((side_a_name.as_str(), side_b_name.as_str()), ("甲", "乙")),
+        other => panic!("应为战斗开始记录: {:?}", other),
+    }
+    match records[2] {
+        BattleRecord::BattleEnd {
+            side_b_panel_delta, ..
+        } => assert_eq!(side_b_panel_delta.as_ref().unwrap().hp_delta, Some(-1000.0)),
+        other => panic!("应为战斗结束记录: {:?}", other),
+    }
+
+    let current = log.to_json().unwrap();
+    assert!(current.contains("side_a_name") && !current.contains("attacker_name"));
+    assert_eq!(BattleLog::from_json(&current).unwrap(), log);
+}
+
+#[cfg(feature = "runtime")]
+mod runtime {
+    use serde_json::Value;
+    use wushen_core::game::{decode_save, encode_save, CharacterAppearance, SaveFormat};
+    use wushen_core::WushenCore;
+
+    const LEGACY_INTERNALS: &str = include_str!("fixtures/legacy_internals_v0.json");
+    const LEGACY_SAVE: &str = include_str!("fixtures/legacy_save.json");
+
+    #[test]
+    fn legacy_manual_pack_loads_with_upgrade_warning() {
+        let mut core = WushenCore::new();
+        core.load_internals(LEGACY_INTERNALS).unwrap();
+        let internal: Value =
+            serde_json::from_str(&core.get_internal("旧版内功").unwrap()).unwrap();
+        assert_eq!(internal["id"], "旧版内功");
+        let report: Value = serde_json::from_str(&core.validation_report().unwrap()).unwrap();
+        assert!(report.to_string().contains("manual_type 已更名为 type"));
+    }
+
+    #[test]
+    fn legacy_save_round_trips() {
+        let save = decode_save(LEGACY_SAVE.as_bytes()).unwrap();
+        assert_eq!(save.current_character.name, "甲");
+        assert_eq!(save.day, 0);
+        assert!(save.content_fingerprint.is_none());
+
+        for format in [SaveFormat::Json, SaveFormat::Compact] {
+            let encoded = encode_save(&save, format).unwrap();
+            let decoded = decode_save(&encoded).unwrap();
+            assert_eq!(
+                serde_json::to_value(&decoded).unwrap(),
+                serde_json::to_value(&save).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn appearance_survives_save_round_trip() {
+        let mut save = decode_save(LEGACY_SAVE.as_bytes()).unwrap();
+        assert!(save.current_character.appearance.is_none());
+
+        let invalid: CharacterAppearance =
+            serde_json::from_value(serde_json::json!({"portrait_key": " "})).unwrap();
+        assert!(invalid.validate().is_err());
+
+        let appearance: CharacterAppearance = serde_json::from_value(serde_json::json!({
+            "portrait_key": "hero_03", "options": {"hair": "black"}
+        }))
+        .unwrap();
+        appearance.validate().unwrap();
+        save.current_character.appearance = Some(appearance.clone());
+
+        for format in [SaveFormat::Json, SaveFormat::Compact] {
+            let decoded = decode_save(&encode_save(&save, format).unwrap()).unwrap();
+            assert_eq!(
+                decoded.current_character.appearance.as_ref(),
+                Some(&appearance)
+            );
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! 公开接口稳定性检查
+//!
+//! 这里只通过外部可见的路径使用本库，锁定数据包工具、Tauri 层与 JSON 服务依赖的接口：
+//! 签名变化会导致编译失败，JSON 字段变化会导致断言失败。
+//! 内部重构（例如战斗双方由 attacker/defender 改为 side_a/side_b）不应触及这里；
+//! 确需修改公开接口时，请同步更新本文件并在发布说明中注明。
+#![cfg(feature = "runtime")]
+
+use serde_json::Value;
+use wushen_core::event::EnemyTemplate;
+use wushen_core::game::{BattleArchivePolicy, GameResponse, NewGameRequest, SaveGame};
+use wushen_core::pack::{
+    resolve_load_order, upgrade_collection, CollectionKind, ContentFingerprint, LoadWarning,
+    PackDependency, PackManifest, CURRENT_SCHEMA_VERSION, SCHEMA_VERSION_KEY,
+};
+use wushen_core::prelude::*;
+use wushen_core::tauri_api::{
+    BattleSessionUpdate, BattleSessionView, ContentBundle, ContentBundleReport, EnemyBattleOptions,
+    WushenCore as CoreByPath,
+};
+
+type Loader = fn(&mut WushenCore, &str) -> WushenResult<Vec<LoadWarning>>;
+type Query = fn(&WushenCore, &str) -> WushenResult<String>;
+type LoadOrder = fn(&[PackManifest], &[String]) -> WushenResult<Vec<String>>;
+type Upgrade = fn(&str, CollectionKind) -> WushenResult<(Vec<Value>, Vec<LoadWarning>)>;
+type Battle = fn(&WushenCore, &str, &str, Option<f64>, Option<f64>) -> WushenResult<String>;
+
+/// 各层调用的核心入口签名
+#[test]
+fn facade_signatures_are_stable() {
+    let _: fn() -> WushenCore = CoreByPath::new;
+    let _: fn(&WushenCore) -> WushenCore = WushenCore::snapshot;
+    let _: [Loader; 5] = [
+        WushenCore::load_traits,
+        WushenCore::load_internals,
+        WushenCore::load_attack_skills,
+        WushenCore::load_defense_skills,
+        WushenCore::load_enemies,
+    ];
+    let _: Loader = WushenCore::load_storylines;
+    let _: Loader = WushenCore::load_items;
+    let _: Loader = WushenCore::load_recipes;
+    let _: Query = WushenCore::get_trait;
+    let _: fn(&WushenCore) -> WushenResult<String> = WushenCore::list_traits;
+    let _: fn(&WushenCore) -> WushenResult<String> = WushenCore::content_fingerprint;
+    let _: Battle = WushenCore::calculate_battle;
+    let _: Battle = WushenCore::capture_battle_replay;
+    let _: Query = WushenCore::replay_battle;
+    let _: Query = WushenCore::formula_variable_catalog;
+    let _: Query = WushenCore::dry_run_entry;
+    let _: fn(&WushenCore, &str, &str, EnemyBattleOptions) -> WushenResult<String> =
+        WushenCore::test_battle_against_enemy;
+    let _: fn(&WushenCore, &str, &str, &str) -> WushenResult<String> =
+        WushenCore::execute_cultivation;
+    let _: fn(&mut WushenCore, ContentBundle) -> ContentBundleReport =
+        WushenCore::load_content_bundle;
+    let _: fn(&mut WushenCore, NewGameRequest) -> WushenResult<GameResponse> =
+        WushenCore::game_start_new;
+    let _: fn(&mut WushenCore, SaveGame) -> WushenResult<GameResponse> = WushenCore::game_resume;
+    let _: fn(&mut WushenCore, String, String) -> WushenResult<GameResponse> =
+        WushenCore::game_cultivate;
+    let _: fn(&mut WushenCore, String) -> WushenResult<GameResponse> = WushenCore::game_use_item;
+    let _: fn(&mut WushenCore, String) -> WushenResult<GameResponse> = WushenCore::game_craft;
+    let _: fn(&mut WushenCore) -> WushenResult<GameResponse> = WushenCore::game_adventure_skip;
+    let _: fn(&mut WushenCore, Option<f64>, Option<f64>) -> WushenResult<BattleSessionView> =
+        WushenCore::game_story_battle_manual;
+    let _: fn(&mut WushenCore) -> WushenResult<BattleSessionUpdate> =
+        WushenCore::battle_session_step;
+
+    let _: fn() -> CancellationToken = CancellationToken::new;
+    let _ = BattleArchivePolicy::default();
+    let _: &str = SCHEMA_VERSION_KEY;
+    let _: u32 = CURRENT_SCHEMA_VERSION;
+    let _: LoadOrder = resolve_load_order;
+    let _: Upgrade = upgrade_collection;
+    let _: fn() -> ContentFingerprint = ContentFingerprint::default;
+    let _: &[CollectionKind] = &CollectionKind::ALL;
+    let _ = std::any::type_name::<(PackDependency, EnemyTemplate, ContentStore)>();
+}
+
+fn character(name: &str) -> String {
+    serde_json::json!({
+        "name": name,
+        "three_d": {"comprehension": 10, "bone_structure": 10, "physique": 10},
+        "traits": [],
+        "internals": {"owned": [], "equipped": null},
+        "attack_skills": {"owned": [], "equipped": null},
+        "defense_skills": {"owned": [], "equipped": null}
+    })
+    .to_string()
+}
+
+/// 战斗结果对外仍以进攻方/防守方命名
+#[test]
+fn battle_json_keeps_attacker_defender_fields() {
+    let core = WushenCore::new();
+    let result = core
+        .calculate_battle(&character("甲"), &character("乙"), None, None)
+        .unwrap();
+    let result: Value = serde_json::from_str(&result).unwrap();
+
+    for key in ["result", "records", "attacker_panel", "defender_panel"] {
+        assert!(result.get(key).is_some(), "战斗结果缺少字段 {}", key);
+    }
+    assert_eq!(result["attacker_panel"]["name"], "甲");
+    assert_eq!(result["defender_panel"]["name"], "乙");
+
+    // 溯源信息：据此把分享出来的战斗结果对应到引擎版本、内容与配置
+    let provenance = &result["provenance"];
+    assert_eq!(provenance["engine_version"], env!("CARGO_PKG_VERSION"));
+    let fingerprint: Value = serde_json::from_str(&core.content_fingerprint().unwrap()).unwrap();
+    assert_eq!(provenance["content_digest"], fingerprint["digest"]);
+    assert!(provenance["config_hash"].is_string());
+    assert_eq!(provenance["seed"], 0);
+    for key in [
+        "hp",
+        "max_hp",
+        "qi",
+        "max_qi",
+        "base_attack",
+        "base_defense",
+    ] {
+        assert!(
+            result["attacker_panel"].get(key).is_some(),
+            "面板缺少字段 {}",
+            key
+        );
+    }
+}
+
+/// 战斗回放可分享：按回放重新执行得到相同的战斗结果
+#[test]
+fn battle_replay_reproduces_battle_json() {
+    let core = WushenCore::new();
+    let replay = core
+        .capture_battle_replay(&character("甲"), &character("乙"), None, None)
+        .unwrap();
+    let battle = core
+        .calculate_battle(&character("甲"), &character("乙"), None, None)
+        .unwrap();
+    assert_eq!(WushenCore::new().replay_battle(&replay).unwrap(), battle);
+    assert_eq!(core.replay_battle("{}").unwrap_err().code(), "parse_error");
+}
+
+/// 装备的功法未加载时，战斗结果列出这些功法；配置为拒绝时不开战
+#[test]
+fn battle_reports_unresolved_equipped_manuals() {
+    let mut armed: Value = serde_json::from_str(&character("甲")).unwrap();
+    armed["attack_skills"] = serde_json::json!({
+        "owned": [{"id": "失传剑法", "level": 1, "exp": 0.0}],
+        "equipped": "失传剑法"
+    });
+    let mut core = WushenCore::new();
+    let result = core
+        .calculate_battle(&armed.to_string(), &character("乙"), None, None)
+        .unwrap();
+    let result: Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(
+        result["unresolved_manuals"],
+        serde_json::json!([{"side": "a", "character": "甲",
+                            "manual_type": "attack_skill", "manual_id": "失传剑法"}])
+    );
+
+    core.set_battle_config(r#"{"missing_manuals": "reject"}"#)
+        .unwrap();
+    let err = core
+        .calculate_battle(&armed.to_string(), &character("乙"), None, None)
+        .unwrap_err();
+    assert_eq!(err.code(), "not_found");
+    assert!(err.to_string().contains("失传剑法"), "{}", err);
+}